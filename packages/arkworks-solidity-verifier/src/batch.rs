@@ -0,0 +1,505 @@
+//! Batch verification of every Groth16 proof of one game.
+//!
+//! This is not proof aggregation: nothing here produces a constant-size proof, and the
+//! calldata grows linearly with the number of proofs. The proofs of a game use different
+//! verifying keys (one per circuit), so SnarkPack-style aggregation, which needs a single
+//! key, does not apply. Instead the whole transcript is checked with one random linear
+//! combination of the Groth16 equations:
+//!
+//! ```text
+//! prod_i e(r_i A_i, B_i) == prod_k e((sum r_i) alpha_k, beta_k)
+//!                                 * e(sum r_i vk_x_i, gamma_k)
+//!                                 * e(sum r_i C_i, delta_k)
+//! ```
+//!
+//! where `k` runs over the verifying keys and the sums over the proofs made with key `k`.
+//! `r_i` are derived from a Keccak transcript of all proofs and inputs, so the same check
+//! runs on-chain in a single call with `N + 3K` pairings instead of `4N`, which saves gas
+//! but not calldata.
+//!
+//! Besides the pairing check, consecutive proofs must be linked by the state chain: the
+//! previous digest of proof `i` (second to last instance element) equals the next digest of
+//! proof `i - 1` (last instance element).
+//!
+//! A verifying key with a single public input was made in `InstanceMode::Hashed` (a full
+//! instance always has the four state chain inputs). Its transcript entries still carry the
+//! full instance, and the proof is checked against `instance_hash` of it, as the hashed
+//! instance verifier of `schemes::groth16` does.
+
+use ark_bn254::{Bn254, Fq, Fr, G1Projective};
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{BigInteger, One, PrimeField, Zero};
+use ark_groth16::{Proof, VerifyingKey};
+use mpc_algebra_wasm::{instance_hash, PoseidonParameters};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use thiserror::Error;
+
+use crate::{
+    constants::TEMPLATE_PREFIX_TEXT,
+    schemes::groth16::fill_poseidon_permutation,
+    utils::{format_bigint, format_modulus},
+    PairingLibrary,
+};
+
+/// `alive_mask`, `day_count`, `prev_state_digest` and `next_state_digest` are the last four
+/// public inputs of every circuit.
+const STATE_CHAIN_PUBLIC_INPUT_LEN: usize = 4;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BatchVerificationError {
+    #[error("transcript has no proofs")]
+    EmptyTranscript,
+    #[error("proof {index} refers to unknown verifying key {vk_index}")]
+    UnknownVerifyingKey { index: usize, vk_index: usize },
+    #[error("proof {index} has {actual} public inputs, verifying key expects {expected}")]
+    PublicInputLength {
+        index: usize,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("proof {index} has {actual} instance elements, the state chain needs {STATE_CHAIN_PUBLIC_INPUT_LEN}")]
+    InstanceTooShort { index: usize, actual: usize },
+    #[error("proof {index} does not continue the state digest of the previous proof")]
+    StateChainBroken { index: usize },
+    #[error("batched pairing check failed")]
+    InvalidProof,
+}
+
+/// One proof of the transcript together with the verifying key it was made with.
+#[derive(Debug, Clone)]
+pub struct TranscriptEntry {
+    pub vk_index: usize,
+    /// The full instance, also for a hashed instance key.
+    pub public_inputs: Vec<Fr>,
+    pub proof: Proof<Bn254>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProof {
+    pub ax: String,
+    pub ay: String,
+    pub bx: Vec<String>,
+    pub by: Vec<String>,
+    pub cx: String,
+    pub cy: String,
+}
+
+/// Calldata of `TranscriptVerifier.verifyTranscript`, produced by [`batch_verify`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchTranscript {
+    pub proofs: Vec<BatchProof>,
+    pub inputs: Vec<Vec<String>>,
+    pub vk_index: Vec<usize>,
+    pub initial_state_digest: String,
+    pub final_state_digest: String,
+}
+
+/// Checks the whole transcript off-chain and returns the arguments for the single on-chain call.
+pub fn batch_verify(
+    vks: &[VerifyingKey<Bn254>],
+    entries: &[TranscriptEntry],
+) -> Result<BatchTranscript, BatchVerificationError> {
+    verify_transcript(vks, entries)?;
+
+    let first = entries.first().ok_or(BatchVerificationError::EmptyTranscript)?;
+    let last = entries.last().ok_or(BatchVerificationError::EmptyTranscript)?;
+
+    Ok(BatchTranscript {
+        proofs: entries
+            .iter()
+            .map(|entry| BatchProof {
+                ax: format_bigint(entry.proof.a.x.into_repr()),
+                ay: format_bigint(entry.proof.a.y.into_repr()),
+                bx: vec![
+                    format_bigint(entry.proof.b.x.c0.into_repr()),
+                    format_bigint(entry.proof.b.x.c1.into_repr()),
+                ],
+                by: vec![
+                    format_bigint(entry.proof.b.y.c0.into_repr()),
+                    format_bigint(entry.proof.b.y.c1.into_repr()),
+                ],
+                cx: format_bigint(entry.proof.c.x.into_repr()),
+                cy: format_bigint(entry.proof.c.y.into_repr()),
+            })
+            .collect(),
+        inputs: entries
+            .iter()
+            .map(|entry| {
+                entry
+                    .public_inputs
+                    .iter()
+                    .map(|input| format_bigint(input.into_repr()))
+                    .collect()
+            })
+            .collect(),
+        vk_index: entries.iter().map(|entry| entry.vk_index).collect(),
+        initial_state_digest: format_bigint(prev_state_digest(first).into_repr()),
+        final_state_digest: format_bigint(next_state_digest(last).into_repr()),
+    })
+}
+
+/// Same check as the exported `verifyTranscript`, including the transcript-derived challenges.
+pub fn verify_transcript(
+    vks: &[VerifyingKey<Bn254>],
+    entries: &[TranscriptEntry],
+) -> Result<(), BatchVerificationError> {
+    if entries.is_empty() {
+        return Err(BatchVerificationError::EmptyTranscript);
+    }
+
+    for (index, entry) in entries.iter().enumerate() {
+        let vk = vks
+            .get(entry.vk_index)
+            .ok_or(BatchVerificationError::UnknownVerifyingKey {
+                index,
+                vk_index: entry.vk_index,
+            })?;
+        let expected = vk.gamma_abc_g1.len() - 1;
+        if !is_hashed_instance(vk) && entry.public_inputs.len() != expected {
+            return Err(BatchVerificationError::PublicInputLength {
+                index,
+                expected,
+                actual: entry.public_inputs.len(),
+            });
+        }
+        if entry.public_inputs.len() < STATE_CHAIN_PUBLIC_INPUT_LEN {
+            return Err(BatchVerificationError::InstanceTooShort {
+                index,
+                actual: entry.public_inputs.len(),
+            });
+        }
+    }
+
+    for (index, pair) in entries.windows(2).enumerate() {
+        if prev_state_digest(&pair[1]) != next_state_digest(&pair[0]) {
+            return Err(BatchVerificationError::StateChainBroken { index: index + 1 });
+        }
+    }
+
+    let seed = transcript_seed(entries);
+    let params = PoseidonParameters::<Fr>::new();
+
+    let mut r_sum = vec![Fr::zero(); vks.len()];
+    let mut acc_x = vec![G1Projective::zero(); vks.len()];
+    let mut acc_c = vec![G1Projective::zero(); vks.len()];
+    let mut pairs: Vec<(
+        <Bn254 as PairingEngine>::G1Prepared,
+        <Bn254 as PairingEngine>::G2Prepared,
+    )> = Vec::with_capacity(entries.len() + 3 * vks.len());
+
+    for (index, entry) in entries.iter().enumerate() {
+        let vk = &vks[entry.vk_index];
+        let r = challenge(&seed, index);
+
+        let public_inputs = if is_hashed_instance(vk) {
+            vec![instance_hash(&params, &entry.public_inputs)]
+        } else {
+            entry.public_inputs.clone()
+        };
+
+        let mut vk_x = vk.gamma_abc_g1[0].into_projective();
+        for (input, base) in public_inputs.iter().zip(vk.gamma_abc_g1.iter().skip(1)) {
+            vk_x += base.mul(input.into_repr());
+        }
+
+        pairs.push((
+            entry.proof.a.mul(r.into_repr()).into_affine().into(),
+            entry.proof.b.into(),
+        ));
+        r_sum[entry.vk_index] += r;
+        acc_x[entry.vk_index] += vk_x.mul(r.into_repr());
+        acc_c[entry.vk_index] += entry.proof.c.mul(r.into_repr());
+    }
+
+    for (k, vk) in vks.iter().enumerate() {
+        let alpha = vk.alpha_g1.mul(r_sum[k].into_repr());
+        pairs.push(((-alpha).into_affine().into(), vk.beta_g2.into()));
+        pairs.push(((-acc_x[k]).into_affine().into(), vk.gamma_g2.into()));
+        pairs.push(((-acc_c[k]).into_affine().into(), vk.delta_g2.into()));
+    }
+
+    if Bn254::product_of_pairings(pairs.iter()).is_one() {
+        Ok(())
+    } else {
+        Err(BatchVerificationError::InvalidProof)
+    }
+}
+
+fn is_hashed_instance(vk: &VerifyingKey<Bn254>) -> bool {
+    vk.gamma_abc_g1.len() == 2
+}
+
+fn prev_state_digest(entry: &TranscriptEntry) -> Fr {
+    entry.public_inputs[entry.public_inputs.len() - 2]
+}
+
+fn next_state_digest(entry: &TranscriptEntry) -> Fr {
+    entry.public_inputs[entry.public_inputs.len() - 1]
+}
+
+// Solidity 側の abi.encodePacked(seed, vkIndex, a, b, c, input) と同じバイト列を作る
+fn transcript_seed(entries: &[TranscriptEntry]) -> [u8; 32] {
+    entries.iter().fold([0u8; 32], |seed, entry| {
+        let mut hasher = Keccak256::new();
+        hasher.update(seed);
+        hasher.update(word_from_usize(entry.vk_index));
+        let proof = &entry.proof;
+        for coordinate in [
+            proof.a.x,
+            proof.a.y,
+            proof.b.x.c0,
+            proof.b.x.c1,
+            proof.b.y.c0,
+            proof.b.y.c1,
+            proof.c.x,
+            proof.c.y,
+        ] {
+            hasher.update(word::<Fq>(coordinate));
+        }
+        for input in entry.public_inputs.iter() {
+            hasher.update(word::<Fr>(*input));
+        }
+        hasher.finalize().into()
+    })
+}
+
+fn challenge(seed: &[u8; 32], index: usize) -> Fr {
+    let mut hasher = Keccak256::new();
+    hasher.update(seed);
+    hasher.update(word_from_usize(index));
+    Fr::from_be_bytes_mod_order(&hasher.finalize())
+}
+
+fn word<F: PrimeField>(value: F) -> [u8; 32] {
+    let bytes = value.into_repr().to_bytes_be();
+    let mut out = [0u8; 32];
+    out[32 - bytes.len()..].copy_from_slice(&bytes);
+    out
+}
+
+fn word_from_usize(value: usize) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    out
+}
+
+/// Exports `TranscriptVerifier`, which checks a whole game transcript made with `vks` in one call.
+/// The order of `vks` defines the `vkIndex` expected in the calldata. The Poseidon instance hash
+/// is only included when one of the keys is a hashed instance key.
+pub fn export_transcript_verifier(vks: &[VerifyingKey<Bn254>]) -> String {
+    assert!(!vks.is_empty(), "at least one verifying key is required");
+
+    let mut vk_branches = String::new();
+    for (k, vk) in vks.iter().enumerate() {
+        vk_branches.push_str(&format!("if (index == {k}) {{\n"));
+        vk_branches.push_str(&format!(
+            "            vk.alpha = Pairing.G1Point({});\n",
+            Bn254::g1_to_string(&vk.alpha_g1)
+        ));
+        vk_branches.push_str(&format!(
+            "            vk.beta = Pairing.G2Point({});\n",
+            Bn254::g2_to_string(&vk.beta_g2)
+        ));
+        vk_branches.push_str(&format!(
+            "            vk.gamma = Pairing.G2Point({});\n",
+            Bn254::g2_to_string(&vk.gamma_g2)
+        ));
+        vk_branches.push_str(&format!(
+            "            vk.delta = Pairing.G2Point({});\n",
+            Bn254::g2_to_string(&vk.delta_g2)
+        ));
+        vk_branches.push_str(&format!(
+            "            vk.gamma_abc = new Pairing.G1Point[]({});\n",
+            vk.gamma_abc_g1.len()
+        ));
+        for (i, g1) in vk.gamma_abc_g1.iter().enumerate() {
+            vk_branches.push_str(&format!(
+                "            vk.gamma_abc[{i}] = Pairing.G1Point({});\n",
+                Bn254::g1_to_string(g1)
+            ));
+        }
+        vk_branches.push_str("            return vk;\n        }\n        ");
+    }
+
+    let has_hashed_instance = vks.iter().any(is_hashed_instance);
+    let mut template_text = CONTRACT_TEMPLATE.replace(
+        "<%hashed_input%>",
+        if has_hashed_instance {
+            HASHED_INSTANCE_INPUT
+        } else {
+            ""
+        },
+    );
+    template_text = Regex::new(r"<%vk_count%>")
+        .unwrap()
+        .replace_all(&template_text, format!("{}", vks.len()))
+        .into_owned();
+    template_text = Regex::new(r"<%vk_branches%>")
+        .unwrap()
+        .replace(&template_text, vk_branches.as_str())
+        .into_owned();
+    template_text = Regex::new(r"<%scalar_field%>")
+        .unwrap()
+        .replace(&template_text, format_modulus::<Fr>())
+        .into_owned();
+
+    let re = Regex::new(r"(?P<v>0[xX][0-9a-fA-F]{64})").unwrap();
+    template_text = re.replace_all(&template_text, "uint256($v)").to_string();
+
+    // Poseidon は assembly の中で modulus を使うので uint256(...) で包んだ後に入れる
+    template_text = template_text.replace(
+        "<%poseidon%>",
+        if has_hashed_instance {
+            HASHED_INSTANCE_TEMPLATE
+        } else {
+            ""
+        },
+    );
+    template_text = fill_poseidon_permutation(template_text)
+        .replace("<%scalar_field%>", &format_modulus::<Fr>());
+
+    format!(
+        "{}\n{}\n{}",
+        TEMPLATE_PREFIX_TEXT,
+        Bn254::template(false),
+        template_text
+    )
+}
+
+const CONTRACT_TEMPLATE: &str = r#"
+contract TranscriptVerifier {
+    using Pairing for *;
+    uint256 constant SNARK_SCALAR_FIELD = <%scalar_field%>;
+    uint256 constant VK_COUNT = <%vk_count%>;
+    uint256 constant STATE_CHAIN_INPUT_LENGTH = 4;
+    struct VerifyingKey {
+        Pairing.G1Point alpha;
+        Pairing.G2Point beta;
+        Pairing.G2Point gamma;
+        Pairing.G2Point delta;
+        Pairing.G1Point[] gamma_abc;
+    }
+    struct Proof {
+        Pairing.G1Point a;
+        Pairing.G2Point b;
+        Pairing.G1Point c;
+    }
+    struct Accumulator {
+        Pairing.G1Point[] p1;
+        Pairing.G2Point[] p2;
+        uint256[] rSum;
+        Pairing.G1Point[] accX;
+        Pairing.G1Point[] accC;
+    }
+    function verifyingKey(uint index) pure internal returns (VerifyingKey memory vk) {
+        <%vk_branches%>revert("Unknown verifying key");
+    }<%poseidon%>
+    function transcriptSeed(
+            Proof[] memory proofs,
+            uint[][] memory inputs,
+            uint[] memory vkIndex
+        ) internal pure returns (bytes32 seed) {
+        for (uint i = 0; i < proofs.length; i++) {
+            Proof memory p = proofs[i];
+            seed = keccak256(abi.encodePacked(
+                seed, vkIndex[i],
+                p.a.X, p.a.Y, p.b.X[0], p.b.X[1], p.b.Y[0], p.b.Y[1], p.c.X, p.c.Y,
+                inputs[i]));
+        }
+    }
+    function accumulate(
+            Accumulator memory acc,
+            VerifyingKey memory vk,
+            uint k,
+            Proof memory proof,
+            uint[] memory input,
+            uint256 r,
+            uint slot
+        ) internal view {
+        Pairing.G1Point memory vk_x = vk.gamma_abc[0];
+        for (uint j = 0; j < input.length; j++) {
+            require(input[j] < SNARK_SCALAR_FIELD);
+            vk_x = Pairing.addition(vk_x, Pairing.scalar_mul(vk.gamma_abc[j + 1], input[j]));
+        }
+        acc.p1[slot] = Pairing.scalar_mul(proof.a, r);
+        acc.p2[slot] = proof.b;
+        acc.rSum[k] = addmod(acc.rSum[k], r, SNARK_SCALAR_FIELD);
+        acc.accX[k] = Pairing.addition(acc.accX[k], Pairing.scalar_mul(vk_x, r));
+        acc.accC[k] = Pairing.addition(acc.accC[k], Pairing.scalar_mul(proof.c, r));
+    }
+    /// Verifies every proof of a game transcript with a single pairing check.
+    /// Consecutive proofs must be linked by their state digests (prev / next digest, the last two instance elements).
+    /// `inputs` holds full instances; a key with one public input is checked against the instance hash.
+    function verifyTranscript(
+            Proof[] memory proofs,
+            uint[][] memory inputs,
+            uint[] memory vkIndex
+        ) public view returns (bool r) {
+        uint n = proofs.length;
+        if (n == 0 || inputs.length != n || vkIndex.length != n) return false;
+
+        VerifyingKey[] memory vks = new VerifyingKey[](VK_COUNT);
+        for (uint k = 0; k < VK_COUNT; k++) {
+            vks[k] = verifyingKey(k);
+        }
+
+        for (uint i = 0; i < n; i++) {
+            if (vkIndex[i] >= VK_COUNT) return false;
+            uint abcLength = vks[vkIndex[i]].gamma_abc.length;
+            if (abcLength != 2 && inputs[i].length + 1 != abcLength) return false;
+            if (inputs[i].length < STATE_CHAIN_INPUT_LENGTH) return false;
+            if (i > 0 && inputs[i][inputs[i].length - 2] != inputs[i - 1][inputs[i - 1].length - 1]) {
+                return false;
+            }
+        }
+
+        bytes32 seed = transcriptSeed(proofs, inputs, vkIndex);
+        Accumulator memory acc;
+        acc.p1 = new Pairing.G1Point[](n + 3 * VK_COUNT);
+        acc.p2 = new Pairing.G2Point[](n + 3 * VK_COUNT);
+        acc.rSum = new uint256[](VK_COUNT);
+        acc.accX = new Pairing.G1Point[](VK_COUNT);
+        acc.accC = new Pairing.G1Point[](VK_COUNT);
+
+        for (uint i = 0; i < n; i++) {
+            uint256 challenge = uint256(keccak256(abi.encodePacked(seed, i))) % SNARK_SCALAR_FIELD;
+            uint[] memory publicInput = inputs[i];<%hashed_input%>
+            accumulate(acc, vks[vkIndex[i]], vkIndex[i], proofs[i], publicInput, challenge, i);
+        }
+
+        for (uint k = 0; k < VK_COUNT; k++) {
+            acc.p1[n + 3 * k] = Pairing.negate(Pairing.scalar_mul(vks[k].alpha, acc.rSum[k]));
+            acc.p2[n + 3 * k] = vks[k].beta;
+            acc.p1[n + 3 * k + 1] = Pairing.negate(acc.accX[k]);
+            acc.p2[n + 3 * k + 1] = vks[k].gamma;
+            acc.p1[n + 3 * k + 2] = Pairing.negate(acc.accC[k]);
+            acc.p2[n + 3 * k + 2] = vks[k].delta;
+        }
+
+        return Pairing.pairing(acc.p1, acc.p2);
+    }
+}
+"#;
+
+const HASHED_INSTANCE_INPUT: &str = r#"
+            if (vks[vkIndex[i]].gamma_abc.length == 2) {
+                publicInput = new uint[](1);
+                publicInput[0] = hashInstance(inputs[i]);
+            }"#;
+
+const HASHED_INSTANCE_TEMPLATE: &str = r#"
+
+    // instance_hash: h = N, h = poseidon(h, input[i])
+    function hashInstance(uint[] memory input) internal pure returns (uint256 h) {
+        bytes memory c = POSEIDON_C;
+        bytes memory m = POSEIDON_M;
+        h = input.length;
+        for (uint i = 0; i < input.length; i++) {
+            require(input[i] < SNARK_SCALAR_FIELD);
+            h = poseidon(c, m, h, input[i]);
+        }
+    }<%poseidon_permutation%>"#;
@@ -2,13 +2,16 @@ use std::{path::PathBuf, process::Command};
 
 use ark_bn254::{Bn254, Fr};
 use ark_ff::{BigInteger, PrimeField};
-use ark_groth16::{Groth16, ProvingKey};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
 use ark_serialize::CanonicalDeserialize;
 use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
 use ark_std::test_rng;
+use arkworks_solidity_verifier::evm::{check_groth16_layouts, solc};
 use mpc_algebra::CommitmentScheme;
+use mpc_algebra_wasm::{StateChainParameter, StateChainTag};
 use mpc_circuits::{
-    AnonymousVotingCircuit, AnonymousVotingPrivateInput, AnonymousVotingPublicInput,
+    state_chain_public_inputs, AnonymousVotingCircuit, AnonymousVotingPrivateInput,
+    AnonymousVotingPublicInput,
 };
 use serde::Serialize;
 use zk_mpc::circuits::LocalOrMPC;
@@ -29,6 +32,9 @@ struct FixtureOutput {
 }
 
 fn main() -> anyhow::Result<()> {
+    if std::env::args().any(|arg| arg == "--check-evm") {
+        return check_evm();
+    }
     if std::env::args().any(|arg| arg == "--emit-json") {
         let fixture = generate_fixture()?;
         println!("{}", serde_json::to_string(&fixture)?);
@@ -55,10 +61,51 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn generate_fixture() -> anyhow::Result<FixtureOutput> {
+/// Proves the fixture circuit, then deploys its standard and compact verifiers in revm:
+/// the proof must pass, a tampered proof or public input must fail.
+fn check_evm() -> anyhow::Result<()> {
+    let solc = solc().ok_or_else(|| anyhow::anyhow!("solc not found; set SOLC to its path"))?;
+    let fixture = prove_fixture()?;
+    anyhow::ensure!(
+        fixture.offchain_verified,
+        "fixture proof does not verify off-chain"
+    );
+    let reports =
+        check_groth16_layouts(&solc, &fixture.vk, &fixture.proof, &fixture.public_inputs)?;
+    for (layout, gas_used) in reports {
+        println!("anonymous_voting n={FIXED_PLAYERS} {layout:?}: verifyTx {gas_used} gas");
+    }
+    Ok(())
+}
+
+struct ProvedFixture {
+    vk: VerifyingKey<Bn254>,
+    proof: Proof<Bn254>,
+    public_inputs: Vec<Fr>,
+    offchain_verified: bool,
+}
+
+fn prove_fixture() -> anyhow::Result<ProvedFixture> {
     let mut rng = test_rng();
     let circuit = build_fixed_anonymous_voting_circuit(&mut rng)?;
-    let public_inputs = vec![circuit.calculate_output()];
+    let most_voted_id = circuit.calculate_output();
+    let commitments = circuit
+        .public_input
+        .player_commitment
+        .iter()
+        .map(|c| (c.x, c.y))
+        .collect::<Vec<_>>();
+    let mut public_inputs = vec![
+        Fr::from(circuit.public_input.state_chain.alive_mask),
+        most_voted_id,
+    ];
+    public_inputs.extend(commitments.iter().flat_map(|(x, y)| [*x, *y]));
+    public_inputs.extend(state_chain_public_inputs(
+        &circuit.public_input.state_chain,
+        StateChainTag::AnonymousVoting,
+        &commitments,
+        &[most_voted_id],
+    ));
 
     let pk = load_or_generate_proving_key(circuit.clone(), &mut rng)?;
     let vk = pk.vk.clone();
@@ -67,6 +114,22 @@ fn generate_fixture() -> anyhow::Result<FixtureOutput> {
     let ok = Groth16::<Bn254>::verify(&vk, &public_inputs, &proof)
         .map_err(|e| anyhow::anyhow!("Groth16 verify failed: {e:?}"))?;
 
+    Ok(ProvedFixture {
+        vk,
+        proof,
+        public_inputs,
+        offchain_verified: ok,
+    })
+}
+
+fn generate_fixture() -> anyhow::Result<FixtureOutput> {
+    let ProvedFixture {
+        proof,
+        public_inputs,
+        offchain_verified,
+        ..
+    } = prove_fixture()?;
+
     Ok(FixtureOutput {
         ax: field_to_hex(proof.a.x),
         ay: field_to_hex(proof.a.y),
@@ -75,7 +138,7 @@ fn generate_fixture() -> anyhow::Result<FixtureOutput> {
         cx: field_to_hex(proof.c.x),
         cy: field_to_hex(proof.c.y),
         public_inputs: public_inputs.iter().copied().map(field_to_hex).collect(),
-        offchain_verified: ok,
+        offchain_verified,
     })
 }
 
@@ -145,6 +208,10 @@ fn build_fixed_anonymous_voting_circuit(
             pedersen_param,
             player_commitment,
             player_num: FIXED_PLAYERS,
+            state_chain: StateChainParameter {
+                alive_mask: (1 << FIXED_PLAYERS) - 1,
+                ..Default::default()
+            },
         },
     })
 }
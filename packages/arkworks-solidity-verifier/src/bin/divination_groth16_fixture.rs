@@ -4,12 +4,16 @@ use ark_bn254::{Bn254, Fr};
 use ark_crypto_primitives::encryption::AsymmetricEncryptionScheme;
 use ark_ec::AffineCurve;
 use ark_ff::{BigInteger, PrimeField, UniformRand};
-use ark_groth16::{Groth16, ProvingKey};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
 use ark_serialize::CanonicalDeserialize;
 use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
 use ark_std::test_rng;
+use arkworks_solidity_verifier::evm::{check_groth16_layouts, solc};
 use mpc_algebra::CommitmentScheme;
-use mpc_circuits::{DivinationCircuit, DivinationPrivateInput, DivinationPublicInput};
+use mpc_algebra_wasm::{StateChainParameter, StateChainTag};
+use mpc_circuits::{
+    state_chain_public_inputs, DivinationCircuit, DivinationPrivateInput, DivinationPublicInput,
+};
 use serde::Serialize;
 use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
 
@@ -29,6 +33,9 @@ struct FixtureOutput {
 }
 
 fn main() -> anyhow::Result<()> {
+    if std::env::args().any(|arg| arg == "--check-evm") {
+        return check_evm();
+    }
     if std::env::args().any(|arg| arg == "--emit-json") {
         let fixture = generate_fixture()?;
         println!("{}", serde_json::to_string(&fixture)?);
@@ -55,7 +62,31 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn generate_fixture() -> anyhow::Result<FixtureOutput> {
+/// Proves the fixture circuit, then deploys its standard and compact verifiers in revm:
+/// the proof must pass, a tampered proof or public input must fail.
+fn check_evm() -> anyhow::Result<()> {
+    let solc = solc().ok_or_else(|| anyhow::anyhow!("solc not found; set SOLC to its path"))?;
+    let fixture = prove_fixture()?;
+    anyhow::ensure!(
+        fixture.offchain_verified,
+        "fixture proof does not verify off-chain"
+    );
+    let reports =
+        check_groth16_layouts(&solc, &fixture.vk, &fixture.proof, &fixture.public_inputs)?;
+    for (layout, gas_used) in reports {
+        println!("divination n={FIXED_PLAYERS} {layout:?}: verifyTx {gas_used} gas");
+    }
+    Ok(())
+}
+
+struct ProvedFixture {
+    vk: VerifyingKey<Bn254>,
+    proof: Proof<Bn254>,
+    public_inputs: Vec<Fr>,
+    offchain_verified: bool,
+}
+
+fn prove_fixture() -> anyhow::Result<ProvedFixture> {
     let mut rng = test_rng();
     let circuit = build_fixed_divination_circuit(&mut rng)?;
     let public_inputs = build_divination_public_inputs(&circuit)?;
@@ -67,6 +98,22 @@ fn generate_fixture() -> anyhow::Result<FixtureOutput> {
     let ok = Groth16::<Bn254>::verify(&vk, &public_inputs, &proof)
         .map_err(|e| anyhow::anyhow!("Groth16 verify failed: {e:?}"))?;
 
+    Ok(ProvedFixture {
+        vk,
+        proof,
+        public_inputs,
+        offchain_verified: ok,
+    })
+}
+
+fn generate_fixture() -> anyhow::Result<FixtureOutput> {
+    let ProvedFixture {
+        proof,
+        public_inputs,
+        offchain_verified,
+        ..
+    } = prove_fixture()?;
+
     Ok(FixtureOutput {
         ax: field_to_hex(proof.a.x),
         ay: field_to_hex(proof.a.y),
@@ -75,7 +122,7 @@ fn generate_fixture() -> anyhow::Result<FixtureOutput> {
         cx: field_to_hex(proof.c.x),
         cy: field_to_hex(proof.c.y),
         public_inputs: public_inputs.iter().copied().map(field_to_hex).collect(),
-        offchain_verified: ok,
+        offchain_verified,
     })
 }
 
@@ -162,6 +209,10 @@ fn build_fixed_divination_circuit(
             elgamal_param,
             pub_key,
             player_num: FIXED_PLAYERS,
+            state_chain: StateChainParameter {
+                alive_mask: (1 << FIXED_PLAYERS) - 1,
+                ..Default::default()
+            },
         },
     })
 }
@@ -200,16 +251,27 @@ fn build_divination_public_inputs(circuit: &DivinationCircuit<Fr>) -> anyhow::Re
         )
         .map_err(|e| anyhow::anyhow!("elgamal encrypt failed: {e:?}"))?;
 
-    Ok(vec![
-        circuit.public_input.elgamal_param.generator.x,
-        circuit.public_input.elgamal_param.generator.y,
-        circuit.public_input.pub_key.x,
-        circuit.public_input.pub_key.y,
+    let ciphertext = [
         ciphertext.0.x,
         ciphertext.0.y,
         ciphertext.1.x,
         ciphertext.1.y,
-    ])
+    ];
+    let mut inputs = vec![
+        Fr::from(circuit.public_input.state_chain.alive_mask),
+        circuit.public_input.elgamal_param.generator.x,
+        circuit.public_input.elgamal_param.generator.y,
+        circuit.public_input.pub_key.x,
+        circuit.public_input.pub_key.y,
+    ];
+    inputs.extend(ciphertext);
+    inputs.extend(state_chain_public_inputs(
+        &circuit.public_input.state_chain,
+        StateChainTag::Divination,
+        &[],
+        &ciphertext,
+    ));
+    Ok(inputs)
 }
 
 fn field_to_hex<F: PrimeField>(value: F) -> String {
@@ -3,12 +3,17 @@ use std::{path::PathBuf, process::Command};
 use ark_bn254::{Bn254, Fr};
 use ark_crypto_primitives::encryption::AsymmetricEncryptionScheme;
 use ark_ff::{BigInteger, PrimeField};
-use ark_groth16::{Groth16, ProvingKey};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
 use ark_serialize::CanonicalDeserialize;
 use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
-use ark_std::test_rng;
+use ark_std::{test_rng, UniformRand};
+use arkworks_solidity_verifier::evm::{check_groth16_layouts, solc};
 use mpc_algebra::CommitmentScheme;
-use mpc_circuits::{KeyPublicizeCircuit, KeyPublicizePrivateInput, KeyPublicizePublicInput};
+use mpc_algebra_wasm::{StateChainParameter, StateChainTag};
+use mpc_circuits::{
+    state_chain_public_inputs, KeyPublicizeCircuit, KeyPublicizePrivateInput,
+    KeyPublicizePublicInput,
+};
 use serde::Serialize;
 use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
 
@@ -28,6 +33,9 @@ struct FixtureOutput {
 }
 
 fn main() -> anyhow::Result<()> {
+    if std::env::args().any(|arg| arg == "--check-evm") {
+        return check_evm();
+    }
     if std::env::args().any(|arg| arg == "--emit-json") {
         let fixture = generate_fixture()?;
         println!("{}", serde_json::to_string(&fixture)?);
@@ -54,10 +62,34 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn generate_fixture() -> anyhow::Result<FixtureOutput> {
+/// Proves the fixture circuit, then deploys its standard and compact verifiers in revm:
+/// the proof must pass, a tampered proof or public input must fail.
+fn check_evm() -> anyhow::Result<()> {
+    let solc = solc().ok_or_else(|| anyhow::anyhow!("solc not found; set SOLC to its path"))?;
+    let fixture = prove_fixture()?;
+    anyhow::ensure!(
+        fixture.offchain_verified,
+        "fixture proof does not verify off-chain"
+    );
+    let reports =
+        check_groth16_layouts(&solc, &fixture.vk, &fixture.proof, &fixture.public_inputs)?;
+    for (layout, gas_used) in reports {
+        println!("key_publicize n={FIXED_PLAYERS} {layout:?}: verifyTx {gas_used} gas");
+    }
+    Ok(())
+}
+
+struct ProvedFixture {
+    vk: VerifyingKey<Bn254>,
+    proof: Proof<Bn254>,
+    public_inputs: Vec<Fr>,
+    offchain_verified: bool,
+}
+
+fn prove_fixture() -> anyhow::Result<ProvedFixture> {
     let mut rng = test_rng();
     let circuit = build_fixed_key_publicize_circuit(&mut rng)?;
-    let public_inputs: Vec<Fr> = Vec::new();
+    let public_inputs = key_publicize_public_inputs(&circuit);
 
     let pk = load_or_generate_proving_key(circuit.clone(), &mut rng)?;
     let vk = pk.vk.clone();
@@ -66,6 +98,22 @@ fn generate_fixture() -> anyhow::Result<FixtureOutput> {
     let ok = Groth16::<Bn254>::verify(&vk, &public_inputs, &proof)
         .map_err(|e| anyhow::anyhow!("Groth16 verify failed: {e:?}"))?;
 
+    Ok(ProvedFixture {
+        vk,
+        proof,
+        public_inputs,
+        offchain_verified: ok,
+    })
+}
+
+fn generate_fixture() -> anyhow::Result<FixtureOutput> {
+    let ProvedFixture {
+        proof,
+        public_inputs,
+        offchain_verified,
+        ..
+    } = prove_fixture()?;
+
     Ok(FixtureOutput {
         ax: field_to_hex(proof.a.x),
         ay: field_to_hex(proof.a.y),
@@ -74,7 +122,7 @@ fn generate_fixture() -> anyhow::Result<FixtureOutput> {
         cx: field_to_hex(proof.c.x),
         cy: field_to_hex(proof.c.y),
         public_inputs: public_inputs.iter().copied().map(field_to_hex).collect(),
-        offchain_verified: ok,
+        offchain_verified,
     })
 }
 
@@ -132,25 +180,62 @@ fn build_fixed_key_publicize_circuit(
         )
         .map_err(|e| anyhow::anyhow!("elgamal keygen failed: {e:?}"))?;
 
-    let private_input = (0..FIXED_PLAYERS)
-        .map(|id| KeyPublicizePrivateInput::<Fr> {
+    let mut private_input = Vec::with_capacity(FIXED_PLAYERS);
+    let mut role_commitment = Vec::with_capacity(FIXED_PLAYERS);
+    for id in 0..FIXED_PLAYERS {
+        // プレイヤー0が占い師 (役職ID 1)、それ以外は村人 (役職ID 0)
+        let role_id = if id == 0 {
+            Fr::from(1u32)
+        } else {
+            Fr::from(0u32)
+        };
+        let randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng);
+        let commitment = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::commit(
+            &pedersen_param,
+            &role_id.into_repr().to_bytes_le(),
+            &randomness,
+        )
+        .map_err(|e| anyhow::anyhow!("pedersen commit failed: {e:?}"))?;
+
+        private_input.push(KeyPublicizePrivateInput::<Fr> {
             id,
             pub_key_or_dummy_x: if id == 0 { pub_key.x } else { Fr::from(0u32) },
             pub_key_or_dummy_y: if id == 0 { pub_key.y } else { Fr::from(0u32) },
-            is_fortune_teller: if id == 0 {
-                Fr::from(1u32)
-            } else {
-                Fr::from(0u32)
-            },
-        })
-        .collect::<Vec<_>>();
+            role_id,
+            randomness,
+        });
+        role_commitment.push(commitment);
+    }
 
     Ok(KeyPublicizeCircuit {
         private_input,
-        public_input: KeyPublicizePublicInput::<Fr> { pedersen_param },
+        public_input: KeyPublicizePublicInput::<Fr> {
+            pedersen_param,
+            role_commitment,
+            state_chain: StateChainParameter::default(),
+        },
     })
 }
 
+// circuit の new_input と同じ順序: pub_key (x, y), role_commitment[i] (x, y), alive mask, day count, prev / next state digest
+fn key_publicize_public_inputs(circuit: &KeyPublicizeCircuit<Fr>) -> Vec<Fr> {
+    let (pub_key_x, pub_key_y) = circuit.calculate_output();
+    let mut inputs = vec![pub_key_x, pub_key_y];
+    let mut commitments = Vec::new();
+    for commitment in circuit.public_input.role_commitment.iter() {
+        inputs.push(commitment.x);
+        inputs.push(commitment.y);
+        commitments.push((commitment.x, commitment.y));
+    }
+    inputs.extend(state_chain_public_inputs(
+        &circuit.public_input.state_chain,
+        StateChainTag::KeyPublicize,
+        &commitments,
+        &[pub_key_x, pub_key_y],
+    ));
+    inputs
+}
+
 fn field_to_hex<F: PrimeField>(value: F) -> String {
     let mut le = value.into_repr().to_bytes_le();
     le.resize(32, 0);
@@ -1,48 +1,33 @@
-use std::{collections::BTreeMap, fs, path::PathBuf};
+use std::{fs, io::BufReader, path::PathBuf};
 
 use anyhow::{bail, Context, Result};
-use ark_bn254::{Bn254, Fr};
-use ark_crypto_primitives::encryption::AsymmetricEncryptionScheme;
+use ark_bn254::Bn254;
 use ark_groth16::{Groth16, ProvingKey};
 use ark_serialize::CanonicalSerialize;
 use ark_snark::CircuitSpecificSetupSNARK;
-use ark_std::{test_rng, UniformRand};
-use mpc_algebra::CommitmentScheme;
-use mpc_algebra_wasm::{GroupingParameter, Role as GroupingRole};
-use mpc_circuits::{
-    AnonymousVotingCircuit, AnonymousVotingPrivateInput, AnonymousVotingPublicInput,
-    DivinationCircuit, DivinationPrivateInput, DivinationPublicInput, KeyPublicizeCircuit,
-    KeyPublicizePrivateInput, KeyPublicizePublicInput, RoleAssignmentCircuit,
-    RoleAssignmentPrivateInput, RoleAssignmentPublicInput, WinningJudgementCircuit,
-    WinningJudgementPrivateInput, WinningJudgementPublicInput,
+use ark_std::{
+    rand::{RngCore, SeedableRng},
+    test_rng,
 };
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+use mpc_algebra_wasm::{
+    sha256_hex, Groth16Manifest, Groth16ManifestEntry, InstanceMode, GROTH16_MANIFEST_FILE_NAME,
+    GROTH16_MANIFEST_VERSION,
+};
+use rand::rngs::OsRng;
+use rand_chacha::ChaCha20Rng;
 use serde::Serialize;
-use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
-
-use arkworks_solidity_verifier::SolidityVerifier;
-
-const DIVINATION_PLAYER_COUNTS: [usize; 7] = [3, 4, 5, 6, 7, 8, 9];
-const ANONYMOUS_VOTING_PLAYER_COUNTS: [usize; 7] = [3, 4, 5, 6, 7, 8, 9];
-const WINNING_JUDGEMENT_PLAYER_COUNTS: [usize; 8] = [2, 3, 4, 5, 6, 7, 8, 9];
-const KEY_PUBLICIZE_PLAYER_COUNTS: [usize; 6] = [4, 5, 6, 7, 8, 9];
-const ROLE_ASSIGNMENT_PROFILES: [(usize, usize); 7] = [
-    (4, 1),
-    (5, 1),
-    (5, 2),
-    (6, 1),
-    (6, 2),
-    // Temporarily disabled: generated verifier exceeds EVM max contract size (24,576 bytes).
-    // (7, 1),
-    (7, 2),
-    (7, 3),
-    // Temporarily disabled: generated verifier exceeds EVM max contract size (24,576 bytes).
-    // (8, 1),
-    // (8, 2),
-    // (8, 3),
-    // (9, 1),
-    // (9, 2),
-    // (9, 3),
-];
+use sha3::{Digest, Keccak256};
+
+use arkworks_solidity_verifier::{
+    ceremony::{CeremonyCircuit, Phase2Parameters, PowersOfTau},
+    profiles::ProfileSpec,
+    schemes::groth16::{
+        export_hashed_instance, export_with_layout, vk_hash, ExportedVerifier, VerifierLayout,
+    },
+};
+
+const SHARED_VERIFIER_CONTRACT_NAME: &str = "Groth16SharedVerifier";
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -52,331 +37,278 @@ struct SetupMetadata {
     public_input_len: usize,
     pk_path: String,
     verifier_path: String,
+    /// Phase-2 ceremony transcript hash after each contribution (hex). Empty for dev setups.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    contribution_hashes: Vec<String>,
 }
 
 fn main() -> Result<()> {
-    for (num_players, werewolf_count) in ROLE_ASSIGNMENT_PROFILES {
-        generate_role_assignment_profile(num_players, werewolf_count)?;
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("ceremony") {
+        return run_ceremony(&args[1..]);
     }
 
-    for num_players in DIVINATION_PLAYER_COUNTS {
-        generate_divination_profile(num_players)?;
-    }
-    for num_players in ANONYMOUS_VOTING_PLAYER_COUNTS {
-        generate_anonymous_voting_profile(num_players)?;
-    }
-    for num_players in WINNING_JUDGEMENT_PLAYER_COUNTS {
-        generate_winning_judgement_profile(num_players)?;
-    }
-    for num_players in KEY_PUBLICIZE_PLAYER_COUNTS {
-        generate_key_publicize_profile(num_players)?;
+    // 開発用: test_rng() で鍵を作るので toxic waste は誰でも再現できる。本番は ceremony を使う
+    let mut entries = Vec::new();
+    for spec in ProfileSpec::all() {
+        let mut rng = test_rng();
+        let profile = spec.build(instance_mode(spec)?, &mut rng)?;
+        let (pk, _vk) = Groth16::<Bn254>::setup(profile.circuit, &mut rng).map_err(|e| {
+            anyhow::anyhow!("Groth16 setup failed for {}: {e:?}", spec.circuit_id())
+        })?;
+        entries.push(write_outputs(profile.spec, &pk, Vec::new())?);
     }
+    write_manifest(entries)?;
 
     println!("Generated Groth16 setups for all configured profiles.");
     Ok(())
 }
 
-fn generate_role_assignment_profile(num_players: usize, werewolf_count: usize) -> Result<()> {
-    let circuit_id = format!("role_assignment_n{num_players}_w{werewolf_count}_v1");
-    let contract_name = format!("RoleAssignmentN{num_players}W{werewolf_count}Groth16Verifier");
-    let mut rng = test_rng();
-    let circuit = build_role_assignment_circuit(num_players, werewolf_count, &mut rng)?;
-    let public_input_len = role_assignment_public_input_len(num_players, werewolf_count);
+const CEREMONY_USAGE: &str = "usage:
+  multi_profile_groth16_setup ceremony init <circuit_id> <ptau> <out.params>
+  multi_profile_groth16_setup ceremony contribute <in.params> <out.params> [entropy]
+  multi_profile_groth16_setup ceremony verify <circuit_id> <ptau> <in.params>
+  multi_profile_groth16_setup ceremony finalize <circuit_id> <ptau> <in.params>";
+
+fn run_ceremony(args: &[String]) -> Result<()> {
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    match args.as_slice() {
+        ["init", circuit_id, ptau, out] => {
+            let params = initial_parameters(ProfileSpec::find(circuit_id)?, ptau)?;
+            fs::write(out, params.to_bytes()?).with_context(|| format!("failed to write {out}"))?;
+            println!("initialized {circuit_id}");
+            println!("cs hash: {}", hex::encode(params.cs_hash));
+        }
+        ["contribute", input, out, entropy @ ..] => {
+            let mut params = read_parameters(input)?;
+            // OS の乱数とユーザー入力を混ぜて seed にする
+            let mut seed = [0u8; 32];
+            OsRng.fill_bytes(&mut seed);
+            let mut hasher = Keccak256::new();
+            hasher.update(seed);
+            hasher.update(entropy.join(" ").as_bytes());
+            let mut rng = ChaCha20Rng::from_seed(hasher.finalize().into());
+            let hash = params.contribute(&mut rng)?;
+            fs::write(out, params.to_bytes()?).with_context(|| format!("failed to write {out}"))?;
+            println!(
+                "contribution #{} hash: {}",
+                params.contributions.len(),
+                hex::encode(hash)
+            );
+        }
+        ["verify", circuit_id, ptau, input] => {
+            let hashes = verify_parameters(ProfileSpec::find(circuit_id)?, ptau, input)?;
+            for (i, hash) in hashes.iter().enumerate() {
+                println!("contribution #{} hash: {}", i + 1, hex::encode(hash));
+            }
+            println!("{} contributions verified", hashes.len());
+        }
+        ["finalize", circuit_id, ptau, input] => {
+            let spec = ProfileSpec::find(circuit_id)?;
+            let hashes = verify_parameters(spec, ptau, input)?;
+            if hashes.is_empty() {
+                bail!("ceremony for {circuit_id} has no contributions");
+            }
+            let params = read_parameters(input)?;
+            let entry = write_outputs(spec, &params.pk, hashes.iter().map(hex::encode).collect())?;
+            // 他の profile の entry は残して、この回路だけ差し替えて署名し直す
+            let mut entries = read_manifest()?
+                .map(|manifest| manifest.entries)
+                .unwrap_or_default();
+            entries.retain(|existing| existing.circuit_id != entry.circuit_id);
+            entries.push(entry);
+            write_manifest(entries)?;
+        }
+        _ => bail!("{CEREMONY_USAGE}"),
+    }
+    Ok(())
+}
 
-    generate_and_write(
-        circuit_id,
-        contract_name,
-        num_players,
-        public_input_len,
-        circuit,
-        &mut rng,
-    )
+fn initial_parameters(spec: ProfileSpec, ptau: &str) -> Result<Phase2Parameters> {
+    let circuit = ceremony_circuit(spec)?;
+    let file = fs::File::open(ptau).with_context(|| format!("failed to open {ptau}"))?;
+    let powers = PowersOfTau::read_ptau(BufReader::new(file), circuit.domain_size())?;
+    powers.check_consistency(&mut OsRng)?;
+    Ok(Phase2Parameters::new(&circuit, &powers)?)
 }
 
-fn generate_divination_profile(num_players: usize) -> Result<()> {
-    let circuit_id = format!("divination_n{num_players}_v1");
-    let contract_name = format!("DivinationN{num_players}Groth16Verifier");
-    let mut rng = test_rng();
-    let circuit = build_divination_circuit(num_players, &mut rng)?;
-    generate_and_write(circuit_id, contract_name, num_players, 8, circuit, &mut rng)
+/// Circuit used by the ceremony. Its constants (Pedersen/ElGamal parameters) come from
+/// `test_rng()` like the dev setup; only the Groth16 trapdoor has to stay secret.
+fn ceremony_circuit(spec: ProfileSpec) -> Result<CeremonyCircuit> {
+    let profile = spec.build(instance_mode(spec)?, &mut test_rng())?;
+    CeremonyCircuit::synthesize(profile.circuit)
+        .with_context(|| format!("failed to synthesize {}", spec.circuit_id()))
 }
 
-fn generate_anonymous_voting_profile(num_players: usize) -> Result<()> {
-    let circuit_id = format!("anonymous_voting_n{num_players}_v1");
-    let contract_name = format!("AnonymousVotingN{num_players}Groth16Verifier");
-    let mut rng = test_rng();
-    let circuit = build_anonymous_voting_circuit(num_players, &mut rng)?;
-    generate_and_write(circuit_id, contract_name, num_players, 1, circuit, &mut rng)
+fn read_parameters(path: &str) -> Result<Phase2Parameters> {
+    let bytes = fs::read(path).with_context(|| format!("failed to read {path}"))?;
+    Ok(Phase2Parameters::from_bytes(&bytes)?)
 }
 
-fn generate_winning_judgement_profile(num_players: usize) -> Result<()> {
-    let circuit_id = format!("winning_judgement_n{num_players}_v1");
-    let contract_name = format!("WinningJudgementN{num_players}Groth16Verifier");
-    let mut rng = test_rng();
-    let circuit = build_winning_judgement_circuit(num_players, &mut rng)?;
-    generate_and_write(circuit_id, contract_name, num_players, 2, circuit, &mut rng)
+fn verify_parameters(spec: ProfileSpec, ptau: &str, input: &str) -> Result<Vec<[u8; 32]>> {
+    let initial = initial_parameters(spec, ptau)?;
+    let params = read_parameters(input)?;
+    Ok(params.verify(&initial, &mut OsRng)?)
 }
 
-fn generate_key_publicize_profile(num_players: usize) -> Result<()> {
-    let circuit_id = format!("key_publicize_n{num_players}_v1");
-    let contract_name = format!("KeyPublicizeN{num_players}Groth16Verifier");
-    let mut rng = test_rng();
-    let circuit = build_key_publicize_circuit(num_players, &mut rng)?;
-    generate_and_write(circuit_id, contract_name, num_players, 0, circuit, &mut rng)
+fn groth16_data_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../zk-mpc-node/data/groth16")
 }
 
-fn generate_and_write<C>(
-    circuit_id: String,
-    contract_name: String,
-    max_players: usize,
-    public_input_len: usize,
-    circuit: C,
-    rng: &mut (impl ark_std::rand::RngCore + ark_std::rand::CryptoRng),
-) -> Result<()>
-where
-    C: ark_relations::r1cs::ConstraintSynthesizer<Fr>,
-{
-    let pk_out = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join(format!("../zk-mpc-node/data/groth16/{circuit_id}.pk"));
+fn write_outputs(
+    spec: ProfileSpec,
+    pk: &ProvingKey<Bn254>,
+    contribution_hashes: Vec<String>,
+) -> Result<Groth16ManifestEntry> {
+    let circuit_id = spec.circuit_id();
+    let contract_name = spec.contract_name("Groth16");
+    let pk_out = groth16_data_dir().join(format!("{circuit_id}.pk"));
     let verifier_out = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!(
         "../foundry/contracts/verifiers/generated/{contract_name}.sol"
     ));
-    let metadata_out = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join(format!("../zk-mpc-node/data/groth16/{circuit_id}.json"));
+    let metadata_out = groth16_data_dir().join(format!("{circuit_id}.json"));
 
-    let (pk, vk) = Groth16::<Bn254>::setup(circuit, rng)
-        .map_err(|e| anyhow::anyhow!("Groth16 setup failed for {circuit_id}: {e:?}"))?;
+    let pk_bytes = write_proving_key(&pk_out, pk)?;
 
-    write_proving_key(&pk_out, &pk)?;
-
-    let contract_src = Groth16::<Bn254>::export(&vk);
-    let renamed_contract = rename_generated_contract(&contract_src, &contract_name);
+    let layout = verifier_layout()?;
+    let exported = match instance_mode(spec)? {
+        InstanceMode::Full => export_with_layout(&pk.vk, layout),
+        InstanceMode::Hashed => {
+            if layout != VerifierLayout::Compact {
+                bail!("hashed instance verifiers are only generated with the compact layout");
+            }
+            ExportedVerifier {
+                contract: export_hashed_instance(&pk.vk, spec.instance_len()),
+                shared_verifier: None,
+            }
+        }
+    };
+    let renamed_contract = rename_generated_contract(&exported.contract, &contract_name);
     write_file(&verifier_out, &renamed_contract)?;
+    if let Some(shared_verifier) = exported.shared_verifier {
+        let shared_out = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!(
+            "../foundry/contracts/verifiers/generated/{SHARED_VERIFIER_CONTRACT_NAME}.sol"
+        ));
+        write_file(&shared_out, &shared_verifier)?;
+    }
 
-    let metadata = SetupMetadata {
+    let entry = Groth16ManifestEntry {
         circuit_id: circuit_id.clone(),
-        max_players,
-        public_input_len,
+        profile: spec.circuit_profile(),
+        vk_hash: hex::encode(vk_hash(&pk.vk)),
+        pk_hash: sha256_hex(&pk_bytes),
+        public_input_len: public_input_len(spec)?,
+    };
+
+    let metadata = SetupMetadata {
+        circuit_id,
+        max_players: spec.max_players(),
+        public_input_len: public_input_len(spec)?,
         pk_path: pk_out.display().to_string(),
         verifier_path: verifier_out.display().to_string(),
+        contribution_hashes,
     };
     write_file(&metadata_out, &serde_json::to_string_pretty(&metadata)?)?;
 
     println!("wrote {}", pk_out.display());
     println!("wrote {}", verifier_out.display());
     println!("wrote {}", metadata_out.display());
-    Ok(())
+    Ok(entry)
 }
 
-fn build_role_assignment_circuit(
-    num_players: usize,
-    werewolf_count: usize,
-    rng: &mut impl ark_std::rand::RngCore,
-) -> Result<RoleAssignmentCircuit<Fr>> {
-    if num_players < 4 || num_players > 9 {
-        bail!("RoleAssignment supports 4..9 players only");
+/// `GROTH16_VERIFIER_LAYOUT=standard|compact|split`。既定は compact
+/// (RoleAssignment n=7..9 の verifier も 24,576 byte 以内に収まる)
+fn verifier_layout() -> Result<VerifierLayout> {
+    match std::env::var("GROTH16_VERIFIER_LAYOUT").as_deref() {
+        Err(_) | Ok("compact") => Ok(VerifierLayout::Compact),
+        Ok("standard") => Ok(VerifierLayout::Standard),
+        Ok("split") => Ok(VerifierLayout::Split),
+        Ok(other) => bail!("unknown GROTH16_VERIFIER_LAYOUT {other}"),
     }
-    if werewolf_count == 0 || werewolf_count >= num_players {
-        bail!("Invalid werewolf count {werewolf_count} for {num_players} players");
-    }
-    let villager_count = num_players
-        .checked_sub(1 + werewolf_count)
-        .context("invalid (num_players, werewolf_count) profile")?;
-
-    let mut map = BTreeMap::new();
-    map.insert(GroupingRole::FortuneTeller, (1, false));
-    map.insert(GroupingRole::Werewolf, (werewolf_count, werewolf_count > 1));
-    map.insert(GroupingRole::Villager, (villager_count, false));
-    let grouping_parameter = GroupingParameter::new(map);
-    let tau_matrix = grouping_parameter.generate_tau_matrix::<Fr>();
-    let matrix_size = tau_matrix.nrows();
-    let identity = nalgebra::DMatrix::<Fr>::identity(matrix_size, matrix_size);
-
-    let private_input = (0..num_players)
-        .map(|id| RoleAssignmentPrivateInput::<Fr> {
-            id,
-            shuffle_matrices: identity.clone(),
-            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::default(),
-            player_randomness: Fr::from((id + 1) as u64),
-        })
-        .collect::<Vec<_>>();
-
-    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
-        .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
-
-    let role_commitment = vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); num_players];
-    let player_commitment =
-        vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); num_players];
-
-    Ok(RoleAssignmentCircuit {
-        private_input,
-        public_input: RoleAssignmentPublicInput::<Fr> {
-            num_players,
-            max_group_size: grouping_parameter.get_max_group_size(),
-            pedersen_param,
-            grouping_parameter,
-            tau_matrix,
-            role_commitment,
-            player_commitment,
-        },
-    })
 }
 
-fn build_divination_circuit(
-    num_players: usize,
-    rng: &mut (impl ark_std::rand::RngCore + ark_std::rand::CryptoRng),
-) -> Result<DivinationCircuit<Fr>> {
-    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
-        .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
-
-    let elgamal_param =
-        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::setup(rng)
-            .map_err(|e| anyhow::anyhow!("elgamal setup failed: {e:?}"))?;
-    let (pub_key, _secret_key) =
-        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::keygen(
-            &elgamal_param,
-            rng,
-        )
-        .map_err(|e| anyhow::anyhow!("elgamal keygen failed: {e:?}"))?;
-
-    let randomness = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalRandomness::rand(rng);
-    let private_input = (0..num_players)
-        .map(|id| {
-            let mut is_target = vec![Fr::from(0u32); num_players];
-            if id == 0 {
-                is_target[0] = Fr::from(1u32);
-            }
-
-            DivinationPrivateInput::<Fr> {
-                id,
-                is_werewolf: if id == 0 {
-                    Fr::from(1u32)
-                } else {
-                    Fr::from(0u32)
-                },
-                is_target,
-                randomness: randomness.clone(),
-            }
-        })
-        .collect::<Vec<_>>();
-
-    Ok(DivinationCircuit {
-        private_input,
-        public_input: DivinationPublicInput::<Fr> {
-            pedersen_param,
-            elgamal_param,
-            pub_key,
-            player_num: num_players,
-        },
-    })
+/// `GROTH16_INSTANCE_MODE=full|hashed`。既定は full。hashed は RoleAssignment の鍵と verifier を
+/// instance hash 1 つの公開入力で作る (compact レイアウトのみ)
+fn role_assignment_instance_mode() -> Result<InstanceMode> {
+    match std::env::var("GROTH16_INSTANCE_MODE").as_deref() {
+        Err(_) | Ok("full") => Ok(InstanceMode::Full),
+        Ok("hashed") => Ok(InstanceMode::Hashed),
+        Ok(other) => bail!("unknown GROTH16_INSTANCE_MODE {other}"),
+    }
 }
 
-fn build_anonymous_voting_circuit(
-    num_players: usize,
-    rng: &mut impl ark_std::rand::RngCore,
-) -> Result<AnonymousVotingCircuit<Fr>> {
-    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
-        .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
-
-    let private_input = (0..num_players)
-        .map(|id| {
-            let mut is_target_id = vec![Fr::from(0u32); num_players];
-            is_target_id[1] = Fr::from(1u32);
-
-            AnonymousVotingPrivateInput::<Fr> {
-                id,
-                is_target_id,
-                player_randomness: Fr::from((id + 1) as u64),
-            }
-        })
-        .collect::<Vec<_>>();
-
-    let player_commitment =
-        vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); num_players];
-
-    Ok(AnonymousVotingCircuit {
-        private_input,
-        public_input: AnonymousVotingPublicInput::<Fr> {
-            pedersen_param,
-            player_commitment,
-            player_num: num_players,
-        },
-    })
+/// Hashed instance にできるのは人数に比例して公開入力が増える RoleAssignment だけ
+fn instance_mode(spec: ProfileSpec) -> Result<InstanceMode> {
+    match spec {
+        ProfileSpec::RoleAssignment { .. } => role_assignment_instance_mode(),
+        _ => Ok(InstanceMode::Full),
+    }
 }
 
-fn build_winning_judgement_circuit(
-    num_players: usize,
-    rng: &mut impl ark_std::rand::RngCore,
-) -> Result<WinningJudgementCircuit<Fr>> {
-    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
-        .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
-
-    let private_input = (0..num_players)
-        .map(|id| WinningJudgementPrivateInput::<Fr> {
-            id,
-            am_werewolf: if id == 0 {
-                Fr::from(1u32)
-            } else {
-                Fr::from(0u32)
-            },
-            player_randomness: Fr::from((id + 7) as u64),
-        })
-        .collect::<Vec<_>>();
-
-    let player_commitment =
-        vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); num_players];
-
-    Ok(WinningJudgementCircuit {
-        private_input,
-        public_input: WinningJudgementPublicInput::<Fr> {
-            pedersen_param,
-            player_commitment,
-        },
+/// Public inputs of the verifying key: 1 for a hashed instance.
+fn public_input_len(spec: ProfileSpec) -> Result<usize> {
+    Ok(match instance_mode(spec)? {
+        InstanceMode::Full => spec.instance_len(),
+        InstanceMode::Hashed => 1,
     })
 }
 
-fn build_key_publicize_circuit(
-    num_players: usize,
-    rng: &mut (impl ark_std::rand::RngCore + ark_std::rand::CryptoRng),
-) -> Result<KeyPublicizeCircuit<Fr>> {
-    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
-        .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
-
-    let elgamal_param =
-        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::setup(rng)
-            .map_err(|e| anyhow::anyhow!("elgamal setup failed: {e:?}"))?;
-    let (pub_key, _secret_key) =
-        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::keygen(
-            &elgamal_param,
-            rng,
-        )
-        .map_err(|e| anyhow::anyhow!("elgamal keygen failed: {e:?}"))?;
-
-    let private_input = (0..num_players)
-        .map(|id| KeyPublicizePrivateInput::<Fr> {
-            id,
-            pub_key_or_dummy_x: if id == 0 { pub_key.x } else { Fr::from(0u32) },
-            pub_key_or_dummy_y: if id == 0 { pub_key.y } else { Fr::from(0u32) },
-            is_fortune_teller: if id == 0 {
-                Fr::from(1u32)
-            } else {
-                Fr::from(0u32)
-            },
-        })
-        .collect::<Vec<_>>();
-
-    Ok(KeyPublicizeCircuit {
-        private_input,
-        public_input: KeyPublicizePublicInput::<Fr> { pedersen_param },
-    })
+fn read_manifest() -> Result<Option<Groth16Manifest>> {
+    let path = groth16_data_dir().join(GROTH16_MANIFEST_FILE_NAME);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let body =
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(Some(serde_json::from_str(&body).with_context(|| {
+        format!("failed to parse {}", path.display())
+    })?))
 }
 
-fn role_assignment_public_input_len(num_players: usize, werewolf_count: usize) -> usize {
-    let matrix_size = 2 * num_players - werewolf_count + 1;
-    matrix_size * matrix_size
+/// Signs the manifest with `GROTH16_MANIFEST_SIGNING_KEY` (Base64 Ed25519 secret key).
+/// Without it an ephemeral key is used, which is only accepted by nodes started with
+/// `ALLOW_UNPINNED_GROTH16_MANIFEST=true`.
+fn write_manifest(entries: Vec<Groth16ManifestEntry>) -> Result<()> {
+    let secret = match std::env::var("GROTH16_MANIFEST_SIGNING_KEY")
+        .ok()
+        .filter(|key| !key.trim().is_empty())
+    {
+        Some(secret_base64) => {
+            let bytes = base64::decode(secret_base64.trim())
+                .context("GROTH16_MANIFEST_SIGNING_KEY is not valid base64")?;
+            SecretKey::from_bytes(&bytes)
+                .map_err(|e| anyhow::anyhow!("invalid GROTH16_MANIFEST_SIGNING_KEY: {e}"))?
+        }
+        None => {
+            eprintln!("GROTH16_MANIFEST_SIGNING_KEY is not set; signing the manifest with an ephemeral key");
+            let mut bytes = [0u8; 32];
+            OsRng.fill_bytes(&mut bytes);
+            SecretKey::from_bytes(&bytes).expect("32 bytes is a valid Ed25519 secret key")
+        }
+    };
+    let public = PublicKey::from(&secret);
+    let keypair = Keypair { secret, public };
+
+    let signature = keypair.sign(&Groth16Manifest::signing_message(
+        GROTH16_MANIFEST_VERSION,
+        &entries,
+    ));
+    let manifest = Groth16Manifest {
+        version: GROTH16_MANIFEST_VERSION,
+        entries,
+        signer_public_key: base64::encode(public.as_bytes()),
+        signature: base64::encode(signature.to_bytes()),
+    };
+
+    let path = groth16_data_dir().join(GROTH16_MANIFEST_FILE_NAME);
+    write_file(&path, &serde_json::to_string_pretty(&manifest)?)?;
+    println!("wrote {}", path.display());
+    println!("manifest hash: {}", manifest.manifest_hash());
+    println!("manifest signer: {}", manifest.signer_public_key);
+    Ok(())
 }
 
-fn write_proving_key(path: &PathBuf, pk: &ProvingKey<Bn254>) -> Result<()> {
+fn write_proving_key(path: &PathBuf, pk: &ProvingKey<Bn254>) -> Result<Vec<u8>> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .with_context(|| format!("failed to create {}", parent.display()))?;
@@ -385,8 +317,8 @@ fn write_proving_key(path: &PathBuf, pk: &ProvingKey<Bn254>) -> Result<()> {
     let mut bytes = Vec::new();
     pk.serialize_uncompressed(&mut bytes)
         .map_err(|e| anyhow::anyhow!("failed to serialize proving key: {e:?}"))?;
-    fs::write(path, bytes).with_context(|| format!("failed to write {}", path.display()))?;
-    Ok(())
+    fs::write(path, &bytes).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(bytes)
 }
 
 fn write_file(path: &PathBuf, body: &str) -> Result<()> {
@@ -0,0 +1,154 @@
+use std::{fs, io::BufReader, path::PathBuf};
+
+use anyhow::{Context, Result};
+use ark_bn254::{Bn254, Fr};
+use ark_marlin::{ahp::AHPForR1CS, IndexProverKey, Marlin};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly_commit::marlin_pc::MarlinKZG10;
+use ark_serialize::CanonicalSerialize;
+use ark_std::test_rng;
+use mpc_algebra_wasm::InstanceMode;
+use rand::rngs::OsRng;
+use serde::Serialize;
+use sha3::Keccak256;
+
+use arkworks_solidity_verifier::{
+    ceremony::PowersOfTau, profiles::ProfileSpec, schemes::marlin::marlin_pc, SolidityVerifier,
+};
+
+type MarlinInst = Marlin<Fr, MarlinKZG10<Bn254, DensePolynomial<Fr>>, Keccak256>;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SetupMetadata {
+    circuit_id: String,
+    max_players: usize,
+    /// Length of the `verifyTx` input array (instance zero-padded to the x-domain).
+    public_input_len: usize,
+    instance_len: usize,
+    ipk_path: String,
+    verifier_path: String,
+}
+
+/// `multi_profile_marlin_setup [<ptau>]`. With a snarkjs `.ptau` file the universal SRS comes
+/// from its powers of tau; without one it comes from `test_rng()` for development.
+fn main() -> Result<()> {
+    // Marlin は全 profile で 1 つの SRS を共有するので、最大の index に合わせて作る
+    let (mut num_constraints, mut num_variables, mut num_non_zero) = (0, 0, 0);
+    for spec in ProfileSpec::all() {
+        let profile = spec.build(InstanceMode::Full, &mut test_rng())?;
+        let index = AHPForR1CS::index(profile.circuit).map_err(|e| {
+            anyhow::anyhow!("Marlin indexing failed for {}: {e:?}", spec.circuit_id())
+        })?;
+        let info = index.index_info;
+        num_constraints = num_constraints.max(info.num_constraints);
+        num_variables = num_variables.max(info.num_variables);
+        num_non_zero = num_non_zero.max(info.num_non_zero);
+    }
+    let srs = match std::env::args().nth(1) {
+        Some(ptau) => {
+            let max_degree =
+                AHPForR1CS::<Fr>::max_degree(num_constraints, num_variables, num_non_zero)
+                    .map_err(|e| anyhow::anyhow!("Marlin degree bound failed: {e:?}"))?;
+            let file = fs::File::open(&ptau).with_context(|| format!("failed to open {ptau}"))?;
+            let powers = PowersOfTau::read_ptau(
+                BufReader::new(file),
+                PowersOfTau::marlin_degree(max_degree),
+            )?;
+            powers.check_consistency(&mut OsRng)?;
+            powers.marlin_universal_params(max_degree)?
+        }
+        None => {
+            // 開発用: test_rng() で universal SRS を作るので trapdoor は誰でも再現できる
+            eprintln!("no ptau given; the universal SRS comes from test_rng(), so these keys are for development only");
+            MarlinInst::universal_setup(
+                num_constraints,
+                num_variables,
+                num_non_zero,
+                &mut test_rng(),
+            )
+            .map_err(|e| anyhow::anyhow!("Marlin universal setup failed: {e:?}"))?
+        }
+    };
+
+    for spec in ProfileSpec::all() {
+        let profile = spec.build(InstanceMode::Full, &mut test_rng())?;
+        let (ipk, _ivk) = MarlinInst::index(&srs, profile.circuit)
+            .map_err(|e| anyhow::anyhow!("Marlin index failed for {}: {e:?}", spec.circuit_id()))?;
+        write_outputs(spec, &ipk)?;
+    }
+
+    println!("Generated Marlin setups for all configured profiles.");
+    Ok(())
+}
+
+fn groth16_data_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../zk-mpc-node/data/groth16")
+}
+
+fn write_outputs(
+    spec: ProfileSpec,
+    ipk: &IndexProverKey<Fr, MarlinKZG10<Bn254, DensePolynomial<Fr>>>,
+) -> Result<()> {
+    let circuit_id = spec.circuit_id();
+    let contract_name = spec.contract_name("Marlin");
+    let ipk_out = groth16_data_dir().join(format!("{circuit_id}.marlin.ipk"));
+    let verifier_out = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!(
+        "../foundry/contracts/verifiers/generated/{contract_name}.sol"
+    ));
+    let metadata_out = groth16_data_dir().join(format!("{circuit_id}.marlin.json"));
+
+    let mut ipk_bytes = Vec::new();
+    ipk.serialize_uncompressed(&mut ipk_bytes)
+        .map_err(|e| anyhow::anyhow!("failed to serialize Marlin index: {e:?}"))?;
+    write_bytes(&ipk_out, &ipk_bytes)?;
+
+    let contract = MarlinInst::export(&ipk.index_vk);
+    write_bytes(
+        &verifier_out,
+        rename_generated_contract(&contract, &contract_name).as_bytes(),
+    )?;
+
+    let metadata = SetupMetadata {
+        circuit_id,
+        max_players: spec.max_players(),
+        public_input_len: marlin_pc::public_input_len(&ipk.index_vk),
+        instance_len: spec.instance_len(),
+        ipk_path: ipk_out.display().to_string(),
+        verifier_path: verifier_out.display().to_string(),
+    };
+    write_bytes(
+        &metadata_out,
+        serde_json::to_string_pretty(&metadata)?.as_bytes(),
+    )?;
+
+    println!("wrote {}", ipk_out.display());
+    println!("wrote {}", verifier_out.display());
+    println!("wrote {}", metadata_out.display());
+    Ok(())
+}
+
+fn write_bytes(path: &PathBuf, body: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    fs::write(path, body).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(())
+}
+
+fn rename_generated_contract(contract_src: &str, contract_name: &str) -> String {
+    let pairing_name = format!("{contract_name}Pairing");
+    let renamed = contract_src.replacen(
+        "contract Verifier {",
+        &format!("contract {contract_name} {{"),
+        1,
+    );
+    let renamed = renamed.replace("Pairing", &pairing_name);
+
+    if renamed.starts_with("// SPDX-License-Identifier:") {
+        renamed
+    } else {
+        format!("// SPDX-License-Identifier: MIT\n{renamed}")
+    }
+}
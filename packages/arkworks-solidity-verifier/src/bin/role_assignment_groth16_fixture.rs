@@ -2,13 +2,17 @@ use std::{path::PathBuf, process::Command};
 
 use ark_bn254::{Bn254, Fr};
 use ark_ff::{BigInteger, PrimeField};
-use ark_groth16::{Groth16, ProvingKey};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
 use ark_serialize::CanonicalDeserialize;
 use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
-use ark_std::test_rng;
+use ark_std::{test_rng, UniformRand};
+use arkworks_solidity_verifier::evm::{check_groth16_layouts, solc};
 use mpc_algebra::CommitmentScheme;
-use mpc_algebra_wasm::GroupingParameter;
-use mpc_circuits::{RoleAssignmentCircuit, RoleAssignmentPrivateInput, RoleAssignmentPublicInput};
+use mpc_algebra_wasm::{GroupingParameter, InstanceMode, StateChainParameter, StateChainTag};
+use mpc_circuits::{
+    state_chain_public_inputs, RoleAssignmentCircuit, RoleAssignmentPrivateInput,
+    RoleAssignmentPublicInput,
+};
 use serde::Serialize;
 use zk_mpc::circuits::LocalOrMPC;
 
@@ -28,6 +32,9 @@ struct FixtureOutput {
 }
 
 fn main() -> anyhow::Result<()> {
+    if std::env::args().any(|arg| arg == "--check-evm") {
+        return check_evm();
+    }
     if std::env::args().any(|arg| arg == "--emit-json") {
         let fixture = generate_fixture()?;
         println!("{}", serde_json::to_string(&fixture)?);
@@ -55,16 +62,35 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn generate_fixture() -> anyhow::Result<FixtureOutput> {
+/// Proves the fixture circuit, then deploys its standard and compact verifiers in revm:
+/// the proof must pass, a tampered proof or public input must fail.
+fn check_evm() -> anyhow::Result<()> {
+    let solc = solc().ok_or_else(|| anyhow::anyhow!("solc not found; set SOLC to its path"))?;
+    let fixture = prove_fixture()?;
+    anyhow::ensure!(
+        fixture.offchain_verified,
+        "fixture proof does not verify off-chain"
+    );
+    let reports =
+        check_groth16_layouts(&solc, &fixture.vk, &fixture.proof, &fixture.public_inputs)?;
+    for (layout, gas_used) in reports {
+        println!("role_assignment n={FIXED_PLAYERS} {layout:?}: verifyTx {gas_used} gas");
+    }
+    Ok(())
+}
+
+struct ProvedFixture {
+    vk: VerifyingKey<Bn254>,
+    proof: Proof<Bn254>,
+    public_inputs: Vec<Fr>,
+    offchain_verified: bool,
+}
+
+fn prove_fixture() -> anyhow::Result<ProvedFixture> {
     let mut rng = test_rng();
     let circuit = build_fixed_role_assignment_circuit(&mut rng)?;
 
-    let public_inputs = circuit
-        .public_input
-        .tau_matrix
-        .iter()
-        .cloned()
-        .collect::<Vec<_>>();
+    let public_inputs = role_assignment_public_inputs(&circuit);
 
     let pk = load_or_generate_proving_key(circuit.clone(), &mut rng)?;
     let vk = pk.vk.clone();
@@ -73,6 +99,22 @@ fn generate_fixture() -> anyhow::Result<FixtureOutput> {
     let ok = Groth16::<Bn254>::verify(&vk, &public_inputs, &proof)
         .map_err(|e| anyhow::anyhow!("Groth16 verify failed: {e:?}"))?;
 
+    Ok(ProvedFixture {
+        vk,
+        proof,
+        public_inputs,
+        offchain_verified: ok,
+    })
+}
+
+fn generate_fixture() -> anyhow::Result<FixtureOutput> {
+    let ProvedFixture {
+        proof,
+        public_inputs,
+        offchain_verified,
+        ..
+    } = prove_fixture()?;
+
     Ok(FixtureOutput {
         ax: field_to_hex(proof.a.x),
         ay: field_to_hex(proof.a.y),
@@ -81,7 +123,7 @@ fn generate_fixture() -> anyhow::Result<FixtureOutput> {
         cx: field_to_hex(proof.c.x),
         cy: field_to_hex(proof.c.y),
         public_inputs: public_inputs.iter().copied().map(field_to_hex).collect(),
-        offchain_verified: ok,
+        offchain_verified,
     })
 }
 
@@ -131,23 +173,32 @@ fn build_fixed_role_assignment_circuit(
     let matrix_size = tau_matrix.nrows();
     let identity = nalgebra::DMatrix::<Fr>::identity(matrix_size, matrix_size);
 
-    let private_input = (0..FIXED_PLAYERS)
-        .map(|id| RoleAssignmentPrivateInput::<Fr> {
-            id,
-            shuffle_matrices: identity.clone(),
-            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::default(),
-            player_randomness: Fr::from((id + 1) as u64),
-        })
-        .collect::<Vec<_>>();
-
     let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
         .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
-    let role_commitment =
-        vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); FIXED_PLAYERS];
-    let player_commitment =
-        vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); FIXED_PLAYERS];
 
-    Ok(RoleAssignmentCircuit {
+    let mut private_input = Vec::with_capacity(FIXED_PLAYERS);
+    let mut player_commitment = Vec::with_capacity(FIXED_PLAYERS);
+    for id in 0..FIXED_PLAYERS {
+        let player_randomness = Fr::from((id + 1) as u64);
+        let player_commitment_randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng);
+        player_commitment.push(
+            <Fr as LocalOrMPC<Fr>>::PedersenComScheme::commit(
+                &pedersen_param,
+                &player_randomness.into_repr().to_bytes_le(),
+                &player_commitment_randomness,
+            )
+            .map_err(|e| anyhow::anyhow!("pedersen commit failed: {e:?}"))?,
+        );
+        private_input.push(RoleAssignmentPrivateInput::<Fr> {
+            id,
+            shuffle_matrices: identity.clone(),
+            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng),
+            player_randomness,
+            player_commitment_randomness,
+        });
+    }
+
+    let mut circuit = RoleAssignmentCircuit {
         private_input,
         public_input: RoleAssignmentPublicInput::<Fr> {
             num_players: FIXED_PLAYERS,
@@ -155,10 +206,52 @@ fn build_fixed_role_assignment_circuit(
             pedersen_param,
             grouping_parameter,
             tau_matrix,
-            role_commitment,
+            role_commitment: Vec::new(),
             player_commitment,
+            state_chain: StateChainParameter::default(),
+            instance_mode: InstanceMode::Full,
         },
-    })
+    };
+
+    let role_commitment = circuit
+        .calculate_output()
+        .iter()
+        .zip(circuit.private_input.iter())
+        .map(|(role_id, input)| {
+            <Fr as LocalOrMPC<Fr>>::PedersenComScheme::commit(
+                &circuit.public_input.pedersen_param,
+                &role_id.into_repr().to_bytes_le(),
+                &input.randomness,
+            )
+            .map_err(|e| anyhow::anyhow!("pedersen commit failed: {e:?}"))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    circuit.public_input.role_commitment = role_commitment;
+
+    Ok(circuit)
+}
+
+// player_commitment[i] (x, y) の後に role_commitment[i] (x, y)、alive mask、day count、prev / next state digest が続く
+fn role_assignment_public_inputs(circuit: &RoleAssignmentCircuit<Fr>) -> Vec<Fr> {
+    let mut inputs = Vec::new();
+    let mut commitments = Vec::new();
+    for commitment in circuit
+        .public_input
+        .player_commitment
+        .iter()
+        .chain(circuit.public_input.role_commitment.iter())
+    {
+        inputs.push(commitment.x);
+        inputs.push(commitment.y);
+        commitments.push((commitment.x, commitment.y));
+    }
+    inputs.extend(state_chain_public_inputs(
+        &circuit.public_input.state_chain,
+        StateChainTag::RoleAssignment,
+        &commitments,
+        &[],
+    ));
+    inputs
 }
 
 fn field_to_hex<F: PrimeField>(value: F) -> String {
@@ -2,13 +2,16 @@ use std::{path::PathBuf, process::Command};
 
 use ark_bn254::{Bn254, Fr};
 use ark_ff::{BigInteger, PrimeField};
-use ark_groth16::{Groth16, ProvingKey};
+use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
 use ark_serialize::CanonicalDeserialize;
 use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
-use ark_std::test_rng;
+use ark_std::{test_rng, UniformRand};
+use arkworks_solidity_verifier::evm::{check_groth16_layouts, solc};
 use mpc_algebra::CommitmentScheme;
+use mpc_algebra_wasm::{StateChainParameter, StateChainTag, WinConditionParameter};
 use mpc_circuits::{
-    WinningJudgementCircuit, WinningJudgementPrivateInput, WinningJudgementPublicInput,
+    state_chain_public_inputs, WinningJudgementCircuit, WinningJudgementPrivateInput,
+    WinningJudgementPublicInput,
 };
 use serde::Serialize;
 use zk_mpc::circuits::LocalOrMPC;
@@ -29,6 +32,9 @@ struct FixtureOutput {
 }
 
 fn main() -> anyhow::Result<()> {
+    if std::env::args().any(|arg| arg == "--check-evm") {
+        return check_evm();
+    }
     if std::env::args().any(|arg| arg == "--emit-json") {
         let fixture = generate_fixture()?;
         println!("{}", serde_json::to_string(&fixture)?);
@@ -55,14 +61,58 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn generate_fixture() -> anyhow::Result<FixtureOutput> {
+/// Proves the fixture circuit, then deploys its standard and compact verifiers in revm:
+/// the proof must pass, a tampered proof or public input must fail.
+fn check_evm() -> anyhow::Result<()> {
+    let solc = solc().ok_or_else(|| anyhow::anyhow!("solc not found; set SOLC to its path"))?;
+    let fixture = prove_fixture()?;
+    anyhow::ensure!(
+        fixture.offchain_verified,
+        "fixture proof does not verify off-chain"
+    );
+    let reports =
+        check_groth16_layouts(&solc, &fixture.vk, &fixture.proof, &fixture.public_inputs)?;
+    for (layout, gas_used) in reports {
+        println!("winning_judgement n={FIXED_PLAYERS} {layout:?}: verifyTx {gas_used} gas");
+    }
+    Ok(())
+}
+
+struct ProvedFixture {
+    vk: VerifyingKey<Bn254>,
+    proof: Proof<Bn254>,
+    public_inputs: Vec<Fr>,
+    offchain_verified: bool,
+}
+
+fn prove_fixture() -> anyhow::Result<ProvedFixture> {
     let mut rng = test_rng();
     let circuit = build_fixed_winning_judgement_circuit(&mut rng)?;
 
-    let public_inputs = vec![
-        Fr::from(circuit.private_input.len() as u64),
-        circuit.calculate_output(),
+    let win_condition = circuit.public_input.win_condition;
+    let state_chain = &circuit.public_input.state_chain;
+    let results = [circuit.calculate_output(), circuit.calculate_winner_mask()];
+    let commitments = circuit
+        .public_input
+        .role_commitment
+        .iter()
+        .map(|c| (c.x, c.y))
+        .collect::<Vec<_>>();
+    let mut public_inputs = vec![
+        Fr::from(state_chain.alive_mask.count_ones()),
+        Fr::from(state_chain.alive_mask),
+        Fr::from(win_condition.werewolf_win_on_majority as u32),
+        Fr::from(win_condition.day_limit),
+        Fr::from(state_chain.day_count),
     ];
+    public_inputs.extend(results);
+    public_inputs.extend(commitments.iter().flat_map(|(x, y)| [*x, *y]));
+    public_inputs.extend(state_chain_public_inputs(
+        state_chain,
+        StateChainTag::WinningJudgement,
+        &commitments,
+        &results,
+    ));
 
     let pk = load_or_generate_proving_key(circuit.clone(), &mut rng)?;
     let vk = pk.vk.clone();
@@ -71,6 +121,22 @@ fn generate_fixture() -> anyhow::Result<FixtureOutput> {
     let ok = Groth16::<Bn254>::verify(&vk, &public_inputs, &proof)
         .map_err(|e| anyhow::anyhow!("Groth16 verify failed: {e:?}"))?;
 
+    Ok(ProvedFixture {
+        vk,
+        proof,
+        public_inputs,
+        offchain_verified: ok,
+    })
+}
+
+fn generate_fixture() -> anyhow::Result<FixtureOutput> {
+    let ProvedFixture {
+        proof,
+        public_inputs,
+        offchain_verified,
+        ..
+    } = prove_fixture()?;
+
     Ok(FixtureOutput {
         ax: field_to_hex(proof.a.x),
         ay: field_to_hex(proof.a.y),
@@ -79,7 +145,7 @@ fn generate_fixture() -> anyhow::Result<FixtureOutput> {
         cx: field_to_hex(proof.c.x),
         cy: field_to_hex(proof.c.y),
         public_inputs: public_inputs.iter().copied().map(field_to_hex).collect(),
-        offchain_verified: ok,
+        offchain_verified,
     })
 }
 
@@ -127,22 +193,46 @@ fn build_fixed_winning_judgement_circuit(
     let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
         .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
 
-    let private_input = (0..FIXED_PLAYERS)
-        .map(|id| WinningJudgementPrivateInput::<Fr> {
+    let mut private_input = Vec::with_capacity(FIXED_PLAYERS);
+    let mut role_commitment = Vec::with_capacity(FIXED_PLAYERS);
+    for id in 0..FIXED_PLAYERS {
+        // プレイヤー0が人狼 (役職ID 2)、それ以外は村人 (役職ID 0)
+        let role_id = if id == 0 {
+            Fr::from(2u32)
+        } else {
+            Fr::from(0u32)
+        };
+        let randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng);
+        let commitment = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::commit(
+            &pedersen_param,
+            &role_id.into_repr().to_bytes_le(),
+            &randomness,
+        )
+        .map_err(|e| anyhow::anyhow!("pedersen commit failed: {e:?}"))?;
+
+        private_input.push(WinningJudgementPrivateInput::<Fr> {
             id,
             am_werewolf: if id == 0 { Fr::from(1u32) } else { Fr::from(0u32) },
-            player_randomness: Fr::from((id + 7) as u64),
-        })
-        .collect::<Vec<_>>();
-
-    let player_commitment =
-        vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); FIXED_PLAYERS];
+            am_werewolf_team: if id == 0 { Fr::from(1u32) } else { Fr::from(0u32) },
+            am_fox: Fr::from(0u32),
+            role_id,
+            randomness,
+        });
+        role_commitment.push(commitment);
+    }
 
     Ok(WinningJudgementCircuit {
         private_input,
         public_input: WinningJudgementPublicInput::<Fr> {
             pedersen_param,
-            player_commitment,
+            player_num: FIXED_PLAYERS,
+            win_condition: WinConditionParameter::default(),
+            role_commitment,
+            state_chain: StateChainParameter {
+                alive_mask: (1 << FIXED_PLAYERS) - 1,
+                day_count: 1,
+                ..Default::default()
+            },
         },
     })
 }
@@ -0,0 +1,741 @@
+//! Circuit-specific (Phase-2) Groth16 trusted-setup ceremony.
+//!
+//! Keys are derived from a Phase-1 powers-of-tau file (snarkjs `.ptau`) instead of a local
+//! random `tau`, so nobody learns `tau`, `alpha` or `beta`. Each participant then rescales
+//! `delta` by a fresh secret and publishes a proof of knowledge of it, following
+//! Bowe–Gabizon–Miers (ePrint 2017/1050). As long as one participant destroys their secret,
+//! nobody can forge proofs.
+//!
+//! `gamma` is fixed to the G2 generator as in the paper; the Groth16 verifier does not rely on
+//! `gamma` being secret.
+//!
+//! The same Phase-1 file also yields the universal SRS of the Marlin keys
+//! ([`PowersOfTau::marlin_universal_params`]); Marlin needs no Phase-2.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, SeekFrom};
+
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{BigInteger, BigInteger256, Field, FpParameters, One, PrimeField, UniformRand, Zero};
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_poly_commit::kzg10::UniversalParams;
+use ark_relations::r1cs::{
+    ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, OptimizationGoal, SynthesisError,
+    SynthesisMode,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::rand::{CryptoRng, Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha3::{Digest, Keccak256};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CeremonyError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("serialization error: {0}")]
+    Serialization(#[from] SerializationError),
+    #[error("synthesis error: {0}")]
+    Synthesis(#[from] SynthesisError),
+    #[error("invalid ptau file: {0}")]
+    InvalidPtau(String),
+    #[error("ptau supports up to 2^{power} constraints, circuit needs {required}")]
+    PtauTooSmall { power: u32, required: usize },
+    #[error("powers of tau are inconsistent: {0}")]
+    InconsistentPowers(&'static str),
+    #[error("parameters were not initialized from this circuit and ptau: {0}")]
+    CircuitMismatch(&'static str),
+    #[error("contribution {index} is invalid: {reason}")]
+    InvalidContribution { index: usize, reason: &'static str },
+}
+
+/// Phase-1 output restricted to the degree one circuit needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PowersOfTau {
+    /// `tau^i * G1` for `i < 2 * degree - 1`
+    pub tau_g1: Vec<G1Affine>,
+    /// `tau^i * G2` for `i < degree`
+    pub tau_g2: Vec<G2Affine>,
+    pub alpha_tau_g1: Vec<G1Affine>,
+    pub beta_tau_g1: Vec<G1Affine>,
+    pub beta_g2: G2Affine,
+}
+
+impl PowersOfTau {
+    /// Reads a snarkjs powers-of-tau file. Points are stored little-endian in Montgomery form,
+    /// which is the same internal representation as `ark_bn254::Fq`.
+    pub fn read_ptau<R: Read + Seek>(mut reader: R, degree: usize) -> Result<Self, CeremonyError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != b"ptau" {
+            return Err(CeremonyError::InvalidPtau("missing ptau magic".into()));
+        }
+        let _version = read_u32(&mut reader)?;
+        let num_sections = read_u32(&mut reader)?;
+
+        let mut sections = Vec::with_capacity(num_sections as usize);
+        for _ in 0..num_sections {
+            let section_type = read_u32(&mut reader)?;
+            let size = read_u64(&mut reader)?;
+            let offset = reader.stream_position()?;
+            sections.push((section_type, offset, size));
+            reader.seek(SeekFrom::Current(size as i64))?;
+        }
+        let seek_section = |reader: &mut R, section_type: u32| -> Result<u64, CeremonyError> {
+            let (_, offset, size) = sections
+                .iter()
+                .find(|(t, _, _)| *t == section_type)
+                .ok_or_else(|| {
+                    CeremonyError::InvalidPtau(format!("missing section {section_type}"))
+                })?;
+            reader.seek(SeekFrom::Start(*offset))?;
+            Ok(*size)
+        };
+
+        seek_section(&mut reader, 1)?;
+        let n8 = read_u32(&mut reader)?;
+        if n8 != 32 {
+            return Err(CeremonyError::InvalidPtau(format!("unsupported n8 {n8}")));
+        }
+        let mut q = [0u8; 32];
+        reader.read_exact(&mut q)?;
+        if q.as_slice()
+            != <<Fq as PrimeField>::Params as FpParameters>::MODULUS
+                .to_bytes_le()
+                .as_slice()
+        {
+            return Err(CeremonyError::InvalidPtau("ptau is not over BN254".into()));
+        }
+        let power = read_u32(&mut reader)?;
+        if degree > 1usize << power {
+            return Err(CeremonyError::PtauTooSmall {
+                power,
+                required: degree,
+            });
+        }
+
+        let read_g1s = |reader: &mut R, section_type: u32, count: usize| {
+            let size = seek_section(reader, section_type)?;
+            if size < (count * 64) as u64 {
+                return Err(CeremonyError::InvalidPtau(format!(
+                    "section {section_type} is too short"
+                )));
+            }
+            (0..count)
+                .map(|_| read_g1(reader))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let tau_g1 = read_g1s(&mut reader, 2, 2 * degree - 1)?;
+        let alpha_tau_g1 = read_g1s(&mut reader, 4, degree)?;
+        let beta_tau_g1 = read_g1s(&mut reader, 5, degree)?;
+
+        let read_g2s = |reader: &mut R, section_type: u32, count: usize| {
+            let size = seek_section(reader, section_type)?;
+            if size < (count * 128) as u64 {
+                return Err(CeremonyError::InvalidPtau(format!(
+                    "section {section_type} is too short"
+                )));
+            }
+            (0..count)
+                .map(|_| read_g2(reader))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let tau_g2 = read_g2s(&mut reader, 3, degree)?;
+        let beta_g2 = read_g2s(&mut reader, 6, 1)?[0];
+
+        Ok(Self {
+            tau_g1,
+            tau_g2,
+            alpha_tau_g1,
+            beta_tau_g1,
+            beta_g2,
+        })
+    }
+
+    /// Powers from known secrets. Only for tests: whoever knows `tau` can forge proofs.
+    #[cfg(test)]
+    pub(crate) fn from_secrets(degree: usize, tau: Fr, alpha: Fr, beta: Fr) -> Self {
+        let g1 = G1Affine::prime_subgroup_generator();
+        let g2 = G2Affine::prime_subgroup_generator();
+        let powers = (0..2 * degree - 1)
+            .scan(Fr::one(), |acc, _| {
+                let power = *acc;
+                *acc *= tau;
+                Some(power)
+            })
+            .collect::<Vec<_>>();
+        let powers_g1 = |factor: Fr, count: usize| {
+            powers[..count]
+                .iter()
+                .map(|p| g1.mul((factor * p).into_repr()).into_affine())
+                .collect::<Vec<_>>()
+        };
+
+        Self {
+            tau_g1: powers_g1(Fr::one(), 2 * degree - 1),
+            tau_g2: powers[..degree]
+                .iter()
+                .map(|p| g2.mul(p.into_repr()).into_affine())
+                .collect(),
+            alpha_tau_g1: powers_g1(alpha, degree),
+            beta_tau_g1: powers_g1(beta, degree),
+            beta_g2: g2.mul(beta.into_repr()).into_affine(),
+        }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.tau_g2.len()
+    }
+
+    /// Degree to read the ptau with for a Marlin SRS of `max_degree`: the hiding powers go one
+    /// past the powers of `tau`.
+    pub fn marlin_degree(max_degree: usize) -> usize {
+        max_degree + 2
+    }
+
+    /// Universal SRS of `MarlinKZG10` for polynomials up to `max_degree`, laid out like
+    /// `MarlinKZG10::setup` but without a local trapdoor. `alpha * G1` is the hiding generator
+    /// `gamma_g` and the G2 generator is `h`. Negative powers of `h` are not needed by Marlin.
+    pub fn marlin_universal_params(
+        &self,
+        max_degree: usize,
+    ) -> Result<UniversalParams<Bn254>, CeremonyError> {
+        let required = Self::marlin_degree(max_degree);
+        if self.degree() < required
+            || self.alpha_tau_g1.len() < required
+            || self.tau_g1.len() <= max_degree
+        {
+            return Err(CeremonyError::InvalidPtau(format!(
+                "Marlin needs {required} powers of tau, got {}",
+                self.degree()
+            )));
+        }
+        let h = self.tau_g2[0];
+        let beta_h = self.tau_g2[1];
+
+        Ok(UniversalParams {
+            powers_of_g: self.tau_g1[..=max_degree].to_vec(),
+            powers_of_gamma_g: self.alpha_tau_g1[..required]
+                .iter()
+                .copied()
+                .enumerate()
+                .collect(),
+            h,
+            beta_h,
+            neg_powers_of_h: BTreeMap::new(),
+            prepared_h: h.into(),
+            prepared_beta_h: beta_h.into(),
+        })
+    }
+
+    /// Checks that every vector is a run of powers of the same `tau`. This does not replace
+    /// verifying the Phase-1 ceremony itself.
+    pub fn check_consistency<R: Rng>(&self, rng: &mut R) -> Result<(), CeremonyError> {
+        let g1 = G1Affine::prime_subgroup_generator();
+        let g2 = G2Affine::prime_subgroup_generator();
+        if self.tau_g1.len() != 2 * self.degree() - 1
+            || self.alpha_tau_g1.len() != self.degree()
+            || self.beta_tau_g1.len() != self.degree()
+        {
+            return Err(CeremonyError::InconsistentPowers(
+                "unexpected vector lengths",
+            ));
+        }
+        if self.tau_g1[0] != g1 || self.tau_g2[0] != g2 {
+            return Err(CeremonyError::InconsistentPowers("unexpected generators"));
+        }
+        if self.degree() < 2 {
+            return Ok(());
+        }
+        let tau_g1 = self.tau_g1[1];
+        let tau_g2 = self.tau_g2[1];
+
+        let (lower, upper) = shifted_combination(&self.tau_g1, rng);
+        if !same_ratio((lower, upper), (g2, tau_g2)) {
+            return Err(CeremonyError::InconsistentPowers("tau_g1"));
+        }
+        let (lower, upper) = shifted_combination(&self.tau_g2, rng);
+        if !same_ratio((g1, tau_g1), (lower, upper)) {
+            return Err(CeremonyError::InconsistentPowers("tau_g2"));
+        }
+        let (lower, upper) = shifted_combination(&self.alpha_tau_g1, rng);
+        if !same_ratio((lower, upper), (g2, tau_g2)) {
+            return Err(CeremonyError::InconsistentPowers("alpha_tau_g1"));
+        }
+        let (lower, upper) = shifted_combination(&self.beta_tau_g1, rng);
+        if !same_ratio((lower, upper), (g2, tau_g2)) {
+            return Err(CeremonyError::InconsistentPowers("beta_tau_g1"));
+        }
+        if !same_ratio((g1, self.beta_tau_g1[0]), (g2, self.beta_g2)) {
+            return Err(CeremonyError::InconsistentPowers("beta_g2"));
+        }
+        Ok(())
+    }
+}
+
+/// R1CS of a circuit in the same form `ark_groth16` builds its QAP from.
+pub struct CeremonyCircuit {
+    pub matrices: ConstraintMatrices<Fr>,
+    pub domain: Radix2EvaluationDomain<Fr>,
+}
+
+impl CeremonyCircuit {
+    pub fn synthesize<C: ConstraintSynthesizer<Fr>>(circuit: C) -> Result<Self, CeremonyError> {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Setup);
+        circuit.generate_constraints(cs.clone())?;
+        cs.finalize();
+
+        let domain =
+            Radix2EvaluationDomain::<Fr>::new(cs.num_constraints() + cs.num_instance_variables())
+                .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        let matrices = cs.to_matrices().ok_or(SynthesisError::AssignmentMissing)?;
+        Ok(Self { matrices, domain })
+    }
+
+    /// Number of powers of tau the ptau file must provide.
+    pub fn domain_size(&self) -> usize {
+        self.domain.size()
+    }
+}
+
+/// Proof that a participant multiplied `delta` by a secret they know.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contribution {
+    pub delta_after: G1Affine,
+    pub s: G1Affine,
+    pub s_delta: G1Affine,
+    /// `r * delta'` where `r` is hashed to G2 from the transcript, `s` and `s_delta`.
+    pub r_delta: G2Affine,
+}
+
+impl Contribution {
+    fn to_bytes(self) -> Result<Vec<u8>, SerializationError> {
+        let mut bytes = Vec::new();
+        self.delta_after.serialize_uncompressed(&mut bytes)?;
+        self.s.serialize_uncompressed(&mut bytes)?;
+        self.s_delta.serialize_uncompressed(&mut bytes)?;
+        self.r_delta.serialize_uncompressed(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Phase2Parameters {
+    pub pk: ProvingKey<Bn254>,
+    /// Hash of the initial proving key, identifies the circuit and the Phase-1 input.
+    pub cs_hash: [u8; 32],
+    pub contributions: Vec<Contribution>,
+}
+
+impl Phase2Parameters {
+    /// Builds the initial keys (`delta = 1`) exactly like `ark_groth16::generate_parameters`,
+    /// but evaluates the QAP at the hidden `tau` through the Lagrange basis.
+    pub fn new(circuit: &CeremonyCircuit, powers: &PowersOfTau) -> Result<Self, CeremonyError> {
+        let domain = &circuit.domain;
+        let n = domain.size();
+        if powers.degree() < n {
+            return Err(CeremonyError::CircuitMismatch("ptau degree is too small"));
+        }
+        let matrices = &circuit.matrices;
+        let num_instance = matrices.num_instance_variables;
+        let num_variables = num_instance + matrices.num_witness_variables;
+        let num_constraints = matrices.num_constraints;
+
+        let lagrange_g1 = group_ifft(domain, &powers.tau_g1[..n]);
+        let lagrange_g2 = group_ifft(domain, &powers.tau_g2[..n]);
+        let alpha_lagrange_g1 = group_ifft(domain, &powers.alpha_tau_g1[..n]);
+        let beta_lagrange_g1 = group_ifft(domain, &powers.beta_tau_g1[..n]);
+
+        let mut a_g1 = vec![G1Projective::zero(); num_variables];
+        let mut b_g1 = vec![G1Projective::zero(); num_variables];
+        let mut b_g2 = vec![G2Projective::zero(); num_variables];
+        // beta * A_i(tau) + alpha * B_i(tau) + C_i(tau)
+        let mut abc_g1 = vec![G1Projective::zero(); num_variables];
+
+        // ark_groth16 は公開入力ごとに A 側へ制約を 1 つ追加している
+        for (i, (a, abc)) in a_g1
+            .iter_mut()
+            .zip(abc_g1.iter_mut())
+            .take(num_instance)
+            .enumerate()
+        {
+            *a += lagrange_g1[num_constraints + i];
+            *abc += beta_lagrange_g1[num_constraints + i];
+        }
+        for (j, row) in matrices.a.iter().enumerate() {
+            for (coeff, index) in row {
+                a_g1[*index] += lagrange_g1[j].mul(coeff.into_repr());
+                abc_g1[*index] += beta_lagrange_g1[j].mul(coeff.into_repr());
+            }
+        }
+        for (j, row) in matrices.b.iter().enumerate() {
+            for (coeff, index) in row {
+                b_g1[*index] += lagrange_g1[j].mul(coeff.into_repr());
+                b_g2[*index] += lagrange_g2[j].mul(coeff.into_repr());
+                abc_g1[*index] += alpha_lagrange_g1[j].mul(coeff.into_repr());
+            }
+        }
+        for (j, row) in matrices.c.iter().enumerate() {
+            for (coeff, index) in row {
+                abc_g1[*index] += lagrange_g1[j].mul(coeff.into_repr());
+            }
+        }
+
+        // tau^i * Z(tau) = tau^(i + n) - tau^i
+        let h_query = (0..n - 1)
+            .map(|i| powers.tau_g1[i + n].into_projective() - powers.tau_g1[i].into_projective())
+            .collect::<Vec<_>>();
+
+        let g1 = G1Affine::prime_subgroup_generator();
+        let g2 = G2Affine::prime_subgroup_generator();
+        let abc_g1 = G1Projective::batch_normalization_into_affine(&abc_g1);
+        let vk = VerifyingKey {
+            alpha_g1: powers.alpha_tau_g1[0],
+            beta_g2: powers.beta_g2,
+            gamma_g2: g2,
+            delta_g2: g2,
+            gamma_abc_g1: abc_g1[..num_instance].to_vec(),
+        };
+        let pk = ProvingKey {
+            vk,
+            beta_g1: powers.beta_tau_g1[0],
+            delta_g1: g1,
+            a_query: G1Projective::batch_normalization_into_affine(&a_g1),
+            b_g1_query: G1Projective::batch_normalization_into_affine(&b_g1),
+            b_g2_query: G2Projective::batch_normalization_into_affine(&b_g2),
+            h_query: G1Projective::batch_normalization_into_affine(&h_query),
+            l_query: abc_g1[num_instance..].to_vec(),
+        };
+
+        let mut pk_bytes = Vec::new();
+        pk.serialize_uncompressed(&mut pk_bytes)?;
+        Ok(Self {
+            pk,
+            cs_hash: Keccak256::digest(&pk_bytes).into(),
+            contributions: Vec::new(),
+        })
+    }
+
+    /// Hash of the cs_hash and every contribution so far. Participants publish it so that
+    /// anyone can check their contribution is part of the final keys.
+    pub fn transcript_hash(&self) -> Result<[u8; 32], CeremonyError> {
+        self.contributions
+            .iter()
+            .try_fold(self.cs_hash, |acc, contribution| {
+                next_transcript_hash(&acc, contribution)
+            })
+    }
+
+    /// Multiplies `delta` by a fresh secret drawn from `rng` and returns the new transcript hash.
+    /// The secret is dropped when this returns.
+    pub fn contribute<R: Rng + CryptoRng>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<[u8; 32], CeremonyError> {
+        let delta = loop {
+            let candidate = Fr::rand(rng);
+            if !candidate.is_zero() {
+                break candidate;
+            }
+        };
+        let delta_inverse = delta.inverse().expect("delta is non-zero");
+
+        let s = G1Projective::rand(rng).into_affine();
+        let s_delta = s.mul(delta.into_repr()).into_affine();
+        let r = hash_to_g2(&self.transcript_hash()?, &s, &s_delta)?;
+        let r_delta = r.mul(delta.into_repr()).into_affine();
+
+        self.pk.delta_g1 = self.pk.delta_g1.mul(delta.into_repr()).into_affine();
+        self.pk.vk.delta_g2 = self.pk.vk.delta_g2.mul(delta.into_repr()).into_affine();
+        self.pk.l_query = scale_g1(&self.pk.l_query, delta_inverse);
+        self.pk.h_query = scale_g1(&self.pk.h_query, delta_inverse);
+
+        self.contributions.push(Contribution {
+            delta_after: self.pk.delta_g1,
+            s,
+            s_delta,
+            r_delta,
+        });
+        self.transcript_hash()
+    }
+
+    /// Verifies every contribution against the initial parameters rebuilt from the circuit and
+    /// the ptau file. Returns the transcript hash after each contribution.
+    pub fn verify<R: Rng>(
+        &self,
+        initial: &Phase2Parameters,
+        rng: &mut R,
+    ) -> Result<Vec<[u8; 32]>, CeremonyError> {
+        if !initial.contributions.is_empty() {
+            return Err(CeremonyError::CircuitMismatch(
+                "initial parameters already have contributions",
+            ));
+        }
+        if self.cs_hash != initial.cs_hash {
+            return Err(CeremonyError::CircuitMismatch("cs_hash"));
+        }
+        let (pk, init) = (&self.pk, &initial.pk);
+        if pk.vk.alpha_g1 != init.vk.alpha_g1
+            || pk.vk.beta_g2 != init.vk.beta_g2
+            || pk.vk.gamma_g2 != init.vk.gamma_g2
+            || pk.vk.gamma_abc_g1 != init.vk.gamma_abc_g1
+            || pk.beta_g1 != init.beta_g1
+            || pk.a_query != init.a_query
+            || pk.b_g1_query != init.b_g1_query
+            || pk.b_g2_query != init.b_g2_query
+        {
+            return Err(CeremonyError::CircuitMismatch(
+                "delta-independent keys changed",
+            ));
+        }
+        if pk.l_query.len() != init.l_query.len() || pk.h_query.len() != init.h_query.len() {
+            return Err(CeremonyError::CircuitMismatch("query lengths changed"));
+        }
+
+        let mut hashes = Vec::with_capacity(self.contributions.len());
+        let mut transcript = self.cs_hash;
+        let mut delta_before = init.delta_g1;
+        for (index, contribution) in self.contributions.iter().enumerate() {
+            let invalid = |reason| CeremonyError::InvalidContribution { index, reason };
+            if contribution.s.is_zero() || contribution.delta_after.is_zero() {
+                return Err(invalid("zero point"));
+            }
+            let r = hash_to_g2(&transcript, &contribution.s, &contribution.s_delta)?;
+            if !same_ratio(
+                (contribution.s, contribution.s_delta),
+                (r, contribution.r_delta),
+            ) {
+                return Err(invalid("proof of knowledge does not verify"));
+            }
+            if !same_ratio(
+                (delta_before, contribution.delta_after),
+                (r, contribution.r_delta),
+            ) {
+                return Err(invalid("delta was not multiplied by the proven secret"));
+            }
+            delta_before = contribution.delta_after;
+            transcript = next_transcript_hash(&transcript, contribution)?;
+            hashes.push(transcript);
+        }
+
+        let g1 = G1Affine::prime_subgroup_generator();
+        let g2 = G2Affine::prime_subgroup_generator();
+        if pk.delta_g1 != delta_before || !same_ratio((g1, pk.delta_g1), (g2, pk.vk.delta_g2)) {
+            return Err(CeremonyError::CircuitMismatch(
+                "delta does not match the transcript",
+            ));
+        }
+        // l, h が delta^-1 でスケールされていることをランダム線形結合でまとめて確認する
+        for (current, original, name) in [
+            (&pk.l_query, &init.l_query, "l_query"),
+            (&pk.h_query, &init.h_query, "h_query"),
+        ] {
+            let scalars = (0..current.len())
+                .map(|_| Fr::rand(rng).into_repr())
+                .collect::<Vec<_>>();
+            let current =
+                VariableBaseMSM::multi_scalar_mul(current.as_slice(), &scalars).into_affine();
+            let original =
+                VariableBaseMSM::multi_scalar_mul(original.as_slice(), &scalars).into_affine();
+            if !same_ratio((current, original), (g2, pk.vk.delta_g2)) {
+                return Err(CeremonyError::CircuitMismatch(name));
+            }
+        }
+
+        Ok(hashes)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CeremonyError> {
+        let mut bytes = Vec::new();
+        self.pk.serialize_uncompressed(&mut bytes)?;
+        bytes.extend_from_slice(&self.cs_hash);
+        (self.contributions.len() as u64).serialize(&mut bytes)?;
+        for contribution in self.contributions.iter() {
+            bytes.extend(contribution.to_bytes()?);
+        }
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CeremonyError> {
+        let mut reader = bytes;
+        let pk = ProvingKey::<Bn254>::deserialize_uncompressed(&mut reader)?;
+        if reader.len() < 32 {
+            return Err(SerializationError::InvalidData.into());
+        }
+        let mut cs_hash = [0u8; 32];
+        cs_hash.copy_from_slice(&reader[..32]);
+        reader = &reader[32..];
+        let count = u64::deserialize(&mut reader)?;
+        let contributions = (0..count)
+            .map(|_| {
+                Ok(Contribution {
+                    delta_after: G1Affine::deserialize_uncompressed(&mut reader)?,
+                    s: G1Affine::deserialize_uncompressed(&mut reader)?,
+                    s_delta: G1Affine::deserialize_uncompressed(&mut reader)?,
+                    r_delta: G2Affine::deserialize_uncompressed(&mut reader)?,
+                })
+            })
+            .collect::<Result<Vec<_>, SerializationError>>()?;
+        Ok(Self {
+            pk,
+            cs_hash,
+            contributions,
+        })
+    }
+}
+
+fn next_transcript_hash(
+    transcript: &[u8; 32],
+    contribution: &Contribution,
+) -> Result<[u8; 32], CeremonyError> {
+    let mut hasher = Keccak256::new();
+    hasher.update(transcript);
+    hasher.update(contribution.to_bytes()?);
+    Ok(hasher.finalize().into())
+}
+
+// 離散対数の分からない G2 点を transcript から決定的に得る (try-and-increment + cofactor clearing)
+fn hash_to_g2(
+    transcript: &[u8; 32],
+    s: &G1Affine,
+    s_delta: &G1Affine,
+) -> Result<G2Affine, CeremonyError> {
+    let mut hasher = Keccak256::new();
+    hasher.update(transcript);
+    let mut bytes = Vec::new();
+    s.serialize_uncompressed(&mut bytes)?;
+    s_delta.serialize_uncompressed(&mut bytes)?;
+    hasher.update(&bytes);
+    let mut rng = ChaCha20Rng::from_seed(hasher.finalize().into());
+    Ok(G2Projective::rand(&mut rng).into_affine())
+}
+
+/// `e(a.0, b.1) == e(a.1, b.0)`, i.e. both pairs differ by the same scalar.
+fn same_ratio(a: (G1Affine, G1Affine), b: (G2Affine, G2Affine)) -> bool {
+    Bn254::pairing(a.0, b.1) == Bn254::pairing(a.1, b.0)
+}
+
+/// Random combinations `(sum r_i x_i, sum r_i x_(i+1))` of a run of powers.
+fn shifted_combination<G: AffineCurve<ScalarField = Fr>, R: Rng>(
+    points: &[G],
+    rng: &mut R,
+) -> (G, G) {
+    let scalars = (0..points.len() - 1)
+        .map(|_| Fr::rand(rng).into_repr())
+        .collect::<Vec<_>>();
+    let lower = VariableBaseMSM::multi_scalar_mul(&points[..points.len() - 1], &scalars);
+    let upper = VariableBaseMSM::multi_scalar_mul(&points[1..], &scalars);
+    (lower.into_affine(), upper.into_affine())
+}
+
+fn scale_g1(points: &[G1Affine], factor: Fr) -> Vec<G1Affine> {
+    let scaled = points
+        .iter()
+        .map(|p| p.mul(factor.into_repr()))
+        .collect::<Vec<_>>();
+    G1Projective::batch_normalization_into_affine(&scaled)
+}
+
+/// Inverse FFT in the exponent: turns `tau^i * G` into the Lagrange basis `L_i(tau) * G`.
+fn group_ifft<G: AffineCurve<ScalarField = Fr>>(
+    domain: &Radix2EvaluationDomain<Fr>,
+    points: &[G],
+) -> Vec<G::Projective> {
+    let n = points.len();
+    let log_n = domain.log_size_of_group;
+    let mut values = points
+        .iter()
+        .map(|p| p.into_projective())
+        .collect::<Vec<_>>();
+
+    for k in 0..n {
+        let rk = (k as u64)
+            .reverse_bits()
+            .checked_shr(64 - log_n)
+            .unwrap_or(0) as usize;
+        if k < rk {
+            values.swap(k, rk);
+        }
+    }
+
+    let mut m = 1;
+    while m < n {
+        let w_m = domain.group_gen_inv.pow([(n / (2 * m)) as u64]);
+        for k in (0..n).step_by(2 * m) {
+            let mut w = Fr::one();
+            for j in 0..m {
+                let t = values[k + j + m].mul(w.into_repr());
+                let u = values[k + j];
+                values[k + j] = u + t;
+                values[k + j + m] = u - t;
+                w *= w_m;
+            }
+        }
+        m *= 2;
+    }
+
+    values
+        .into_iter()
+        .map(|v| v.mul(domain.size_inv.into_repr()))
+        .collect()
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, CeremonyError> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, CeremonyError> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_fq<R: Read>(reader: &mut R) -> Result<Fq, CeremonyError> {
+    let mut limbs = [0u64; 4];
+    for limb in limbs.iter_mut() {
+        *limb = read_u64(reader)?;
+    }
+    let montgomery = BigInteger256(limbs);
+    if montgomery >= <<Fq as PrimeField>::Params as FpParameters>::MODULUS {
+        return Err(CeremonyError::InvalidPtau(
+            "coordinate is not reduced".into(),
+        ));
+    }
+    Ok(Fq::new(montgomery))
+}
+
+fn read_g1<R: Read>(reader: &mut R) -> Result<G1Affine, CeremonyError> {
+    let x = read_fq(reader)?;
+    let y = read_fq(reader)?;
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::zero());
+    }
+    let point = G1Affine::new(x, y, false);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(CeremonyError::InvalidPtau(
+            "G1 point is not in the subgroup".into(),
+        ));
+    }
+    Ok(point)
+}
+
+fn read_g2<R: Read>(reader: &mut R) -> Result<G2Affine, CeremonyError> {
+    let x = ark_bn254::Fq2::new(read_fq(reader)?, read_fq(reader)?);
+    let y = ark_bn254::Fq2::new(read_fq(reader)?, read_fq(reader)?);
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::zero());
+    }
+    let point = G2Affine::new(x, y, false);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(CeremonyError::InvalidPtau(
+            "G2 point is not in the subgroup".into(),
+        ));
+    }
+    Ok(point)
+}
@@ -0,0 +1,224 @@
+//! Compiles generated verifiers with `solc` and runs them in an in-process EVM (revm), so
+//! that they can be checked without deploying them to a chain. The EVM runs the Prague spec,
+//! so the BLS12-381 verifiers run on the EIP-2537 precompiles as well.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use ark_bn254::{Bn254, Fr};
+use ark_ff::One;
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use revm::{
+    db::{CacheDB, EmptyDB},
+    primitives::{Address, ExecutionResult, Output, SpecId, TxKind},
+    Evm as Revm,
+};
+
+use crate::{
+    schemes::groth16::{export_with_layout, verify_tx_calldata, VerifierLayout},
+    PairingLibrary, SolidityVerifier,
+};
+
+/// EIP-170 limit on the runtime bytecode of a deployed contract.
+pub const CONTRACT_CODE_SIZE_LIMIT: usize = 24_576;
+
+/// `SOLC` か PATH 上の `solc`。見つからなければ None
+pub fn solc() -> Option<PathBuf> {
+    let solc = std::env::var_os("SOLC")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("solc"));
+    Command::new(&solc)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|_| solc)
+}
+
+/// Compiles `source` and returns the creation bytecode of `contract_name`.
+pub fn compile(solc: &Path, source: &str, contract_name: &str) -> Result<Vec<u8>> {
+    solc_output(solc, source, contract_name, "bin")
+}
+
+/// Compiles `source` and returns the runtime bytecode of `contract_name`, the code that
+/// [`CONTRACT_CODE_SIZE_LIMIT`] applies to.
+pub fn compile_runtime(solc: &Path, source: &str, contract_name: &str) -> Result<Vec<u8>> {
+    solc_output(solc, source, contract_name, "bin-runtime")
+}
+
+fn solc_output(solc: &Path, source: &str, contract_name: &str, output: &str) -> Result<Vec<u8>> {
+    // 並列に走るテストと衝突しないよう呼び出しごとに作る。drop で消える
+    let dir = tempfile::Builder::new()
+        .prefix("arkworks-solidity-verifier-")
+        .tempdir()
+        .context("failed to create a temporary directory")?;
+    let path = dir.path().join(format!("{contract_name}.sol"));
+    std::fs::write(&path, source).with_context(|| format!("failed to write {}", path.display()))?;
+
+    let output = Command::new(solc)
+        .args(["--optimize", "--combined-json", output])
+        .arg(&path)
+        .output()
+        .with_context(|| format!("failed to run {}", solc.display()))?;
+    if !output.status.success() {
+        bail!("solc failed: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    let combined: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let key = format!("{}:{contract_name}", path.display());
+    let bin = combined["contracts"][&key][output]
+        .as_str()
+        .ok_or_else(|| anyhow!("{key} is missing in the solc output"))?;
+    Ok(hex::decode(bin)?)
+}
+
+/// Result of a call: `None` when the call reverted.
+pub struct CallOutcome {
+    pub output: Option<Vec<u8>>,
+    pub gas_used: u64,
+}
+
+/// EVM with an empty state at the Prague spec. [`Evm::new`] enforces
+/// [`CONTRACT_CODE_SIZE_LIMIT`] like the chains the verifiers are deployed to.
+pub struct Evm {
+    evm: Revm<'static, (), CacheDB<EmptyDB>>,
+}
+
+impl Default for Evm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Evm {
+    pub fn new() -> Self {
+        let evm = Revm::builder()
+            .with_db(CacheDB::new(EmptyDB::default()))
+            .with_spec_id(SpecId::PRAGUE)
+            .build();
+        Self { evm }
+    }
+
+    /// Lifts the contract size limit, for verifiers that are checked here before they are
+    /// split (Marlin).
+    pub fn without_code_size_limit() -> Self {
+        let mut evm = Self::new();
+        evm.evm.cfg_mut().limit_contract_code_size = Some(usize::MAX);
+        evm
+    }
+
+    pub fn deploy(&mut self, bytecode: Vec<u8>) -> Result<Address> {
+        let tx = self.evm.tx_mut();
+        tx.transact_to = TxKind::Create;
+        tx.data = bytecode.into();
+        tx.gas_limit = u64::MAX;
+        match self.transact()? {
+            ExecutionResult::Success {
+                output: Output::Create(_, Some(address)),
+                ..
+            } => Ok(address),
+            other => bail!("deployment failed: {other:?}"),
+        }
+    }
+
+    pub fn call(&mut self, address: Address, calldata: Vec<u8>) -> Result<CallOutcome> {
+        let tx = self.evm.tx_mut();
+        tx.transact_to = TxKind::Call(address);
+        tx.data = calldata.into();
+        tx.gas_limit = u64::MAX;
+        match self.transact()? {
+            ExecutionResult::Success {
+                output: Output::Call(bytes),
+                gas_used,
+                ..
+            } => Ok(CallOutcome {
+                output: Some(bytes.to_vec()),
+                gas_used,
+            }),
+            ExecutionResult::Revert { gas_used, .. } | ExecutionResult::Halt { gas_used, .. } => {
+                Ok(CallOutcome {
+                    output: None,
+                    gas_used,
+                })
+            }
+            other => bail!("unexpected call result: {other:?}"),
+        }
+    }
+
+    /// Calls a `returns (bool)` function. A revert counts as `false`.
+    pub fn call_bool(&mut self, address: Address, calldata: Vec<u8>) -> Result<(bool, u64)> {
+        let outcome = self.call(address, calldata)?;
+        let accepted = outcome
+            .output
+            .map(|output| output.len() == 32 && output[31] == 1)
+            .unwrap_or(false);
+        Ok((accepted, outcome.gas_used))
+    }
+
+    fn transact(&mut self) -> Result<ExecutionResult> {
+        self.evm
+            .transact_commit()
+            .map_err(|e| anyhow!("EVM transaction failed: {e:?}"))
+    }
+}
+
+/// Deploys a generated Groth16 verifier and checks that `verifyTx` accepts `proof`, and
+/// rejects it once `proof.a` or the first public input is changed. Returns the gas used
+/// by the accepted call. `inputs` is the calldata instance (the full instance for a hashed
+/// instance verifier). Works for every curve with a [`PairingLibrary`].
+pub fn check_groth16_verifier<E: PairingLibrary>(
+    solc: &Path,
+    source: &str,
+    contract_name: &str,
+    proof: &Proof<E>,
+    inputs: &[E::Fr],
+) -> Result<u64> {
+    let mut evm = Evm::new();
+    let verifier = evm.deploy(compile(solc, source, contract_name)?)?;
+
+    let (accepted, gas_used) = evm.call_bool(verifier, verify_tx_calldata(proof, inputs))?;
+    ensure!(accepted, "{contract_name} rejected a valid proof");
+
+    let mut tampered = proof.clone();
+    tampered.a = -tampered.a;
+    let (accepted, _) = evm.call_bool(verifier, verify_tx_calldata(&tampered, inputs))?;
+    ensure!(!accepted, "{contract_name} accepted a tampered proof");
+
+    if let Some(first) = inputs.first() {
+        let mut tampered = inputs.to_vec();
+        tampered[0] = *first + E::Fr::one();
+        let (accepted, _) = evm.call_bool(verifier, verify_tx_calldata(proof, &tampered))?;
+        ensure!(
+            !accepted,
+            "{contract_name} accepted a tampered public input"
+        );
+    }
+
+    Ok(gas_used)
+}
+
+/// Runs [`check_groth16_verifier`] on the standard and compact verifiers of `vk` and
+/// returns the `verifyTx` gas of each layout.
+pub fn check_groth16_layouts(
+    solc: &Path,
+    vk: &VerifyingKey<Bn254>,
+    proof: &Proof<Bn254>,
+    inputs: &[Fr],
+) -> Result<Vec<(VerifierLayout, u64)>> {
+    [
+        (VerifierLayout::Standard, Groth16::<Bn254>::export(vk)),
+        (
+            VerifierLayout::Compact,
+            export_with_layout(vk, VerifierLayout::Compact).contract,
+        ),
+    ]
+    .into_iter()
+    .map(|(layout, source)| {
+        let gas_used = check_groth16_verifier(solc, &source, "Verifier", proof, inputs)
+            .with_context(|| format!("{layout:?} layout"))?;
+        Ok((layout, gas_used))
+    })
+    .collect()
+}
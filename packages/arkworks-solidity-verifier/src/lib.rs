@@ -4,6 +4,11 @@ pub(crate) mod constants;
 pub(crate) mod pairings;
 pub(crate) mod utils;
 
+pub mod batch;
+pub mod ceremony;
+#[cfg(feature = "evm")]
+pub mod evm;
+pub mod profiles;
 pub mod schemes;
 
 #[cfg(test)]
@@ -11,7 +16,8 @@ mod tests;
 
 /// Helper trait for generating library for elliptic curve group and pairing operation in Solidity.
 ///
-/// This can be extended to other curve other than BN254 once precompiles are available.
+/// Implemented for BN254 (EIP-196/197) and BLS12-381 (EIP-2537). Other curves can be added
+/// once precompiles are available.
 ///
 /// Example:
 /// ```rust
@@ -21,11 +27,36 @@ mod tests;
 /// }
 /// ```
 pub trait PairingLibrary: PairingEngine {
+    /// Precompiles the library calls. Exporters take the library from the curve, so this is
+    /// where the precompile set of a curve is chosen.
+    const PRECOMPILES: Precompiles;
+
+    /// ABI types of `Pairing.G1Point` and `Pairing.G2Point`, as used in function signatures.
+    const G1_ABI_TYPE: &'static str;
+    const G2_ABI_TYPE: &'static str;
+
     fn template(g2_addition: bool) -> String;
 
     fn g1_to_string(g1: &Self::G1Affine) -> String;
 
     fn g2_to_string(g2: &Self::G2Affine) -> String;
+
+    /// ABI words of a point in the layout of `Pairing.G1Point`.
+    fn g1_to_words(g1: &Self::G1Affine) -> Vec<[u8; 32]>;
+
+    /// ABI words of a point in the layout of `Pairing.G2Point`.
+    fn g2_to_words(g2: &Self::G2Affine) -> Vec<[u8; 32]>;
+}
+
+/// Precompile addresses used by a [`PairingLibrary`] template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Precompiles {
+    pub g1_add: u8,
+    /// `ecMul` on BN254. BLS12-381 has no single multiplication, so this is `G1MSM` with one pair.
+    pub g1_mul: u8,
+    /// G2 addition precompile, if the curve has one.
+    pub g2_add: Option<u8>,
+    pub pairing: u8,
 }
 
 /// Primary trait for proving schemes for generating Solidity verifier.
@@ -0,0 +1,260 @@
+use ark_bls12_381::{Bls12_381, Fq};
+use ark_ff::{BigInteger, FpParameters, PrimeField, Zero};
+
+use crate::{PairingLibrary, Precompiles};
+
+/// BLS12-381 on the EIP-2537 precompiles (Prague addresses). A base field element does not
+/// fit in a word, so the library keeps its 64-byte EIP-2537 encoding as two words.
+impl PairingLibrary for Bls12_381 {
+    const PRECOMPILES: Precompiles = Precompiles {
+        g1_add: 0x0b,
+        g1_mul: 0x0c,
+        g2_add: Some(0x0d),
+        pairing: 0x0f,
+    };
+
+    const G1_ABI_TYPE: &'static str = "(uint256[2],uint256[2])";
+    const G2_ABI_TYPE: &'static str = "(uint256[4],uint256[4])";
+
+    fn template(g2_addition: bool) -> String {
+        let (q_hi, q_lo) = fp_words(&<<Fq as PrimeField>::Params as FpParameters>::MODULUS);
+        let pairing_lib = PAIRING_LIB
+            .replace("<%q_hi%>", &format!("0x{}", hex::encode(q_hi)))
+            .replace("<%q_lo%>", &format!("0x{}", hex::encode(q_lo)))
+            .replace("<%g1_add%>", &Self::PRECOMPILES.g1_add.to_string())
+            .replace("<%g1_mul%>", &Self::PRECOMPILES.g1_mul.to_string())
+            .replace("<%pairing%>", &Self::PRECOMPILES.pairing.to_string());
+        match (g2_addition, Self::PRECOMPILES.g2_add) {
+            (true, Some(g2_add)) => format!(
+                "{pairing_lib}\n{}\n}}",
+                PAIRING_LIB_G2_ADDITION.replace("<%g2_add%>", &g2_add.to_string())
+            ),
+            _ => format!("{pairing_lib}\n}}"),
+        }
+    }
+
+    fn g1_to_string(g1: &Self::G1Affine) -> String {
+        let words = Self::g1_to_words(g1);
+        format!("{}, {}", word_array(&words[0..2]), word_array(&words[2..4]))
+    }
+
+    fn g2_to_string(g2: &Self::G2Affine) -> String {
+        let words = Self::g2_to_words(g2);
+        format!("{}, {}", word_array(&words[0..4]), word_array(&words[4..8]))
+    }
+
+    fn g1_to_words(g1: &Self::G1Affine) -> Vec<[u8; 32]> {
+        // EIP-2537 は無限遠点を全 0 で表す
+        if g1.is_zero() {
+            return vec![[0u8; 32]; 4];
+        }
+        [g1.x, g1.y]
+            .iter()
+            .flat_map(|fq| {
+                let (hi, lo) = fp_words(&fq.into_repr());
+                [hi, lo]
+            })
+            .collect()
+    }
+
+    fn g2_to_words(g2: &Self::G2Affine) -> Vec<[u8; 32]> {
+        if g2.is_zero() {
+            return vec![[0u8; 32]; 8];
+        }
+        [g2.x.c0, g2.x.c1, g2.y.c0, g2.y.c1]
+            .iter()
+            .flat_map(|fq| {
+                let (hi, lo) = fp_words(&fq.into_repr());
+                [hi, lo]
+            })
+            .collect()
+    }
+}
+
+/// Splits a 48-byte base field element into the two words of its 64-byte EIP-2537 encoding.
+fn fp_words<B: BigInteger>(value: &B) -> ([u8; 32], [u8; 32]) {
+    let bytes = value.to_bytes_be();
+    let mut encoded = [0u8; 64];
+    encoded[64 - bytes.len()..].copy_from_slice(&bytes);
+    let mut hi = [0u8; 32];
+    let mut lo = [0u8; 32];
+    hi.copy_from_slice(&encoded[..32]);
+    lo.copy_from_slice(&encoded[32..]);
+    (hi, lo)
+}
+
+fn word_array(words: &[[u8; 32]]) -> String {
+    let words = words
+        .iter()
+        .map(|word| format!("0x{}", hex::encode(word)))
+        .collect::<Vec<_>>();
+    format!("[{}]", words.join(", "))
+}
+
+const PAIRING_LIB: &str = r#"
+pragma solidity ^0.8.0;
+/// BLS12-381 operations on the EIP-2537 precompiles. A base field element is its 64-byte
+/// EIP-2537 encoding split into two words: [top 16 bytes (zero padded), low 32 bytes].
+library Pairing {
+    struct G1Point {
+        uint[2] X;
+        uint[2] Y;
+    }
+    // Encoding of field elements is: [c0 (2 words), c1 (2 words)]
+    struct G2Point {
+        uint[4] X;
+        uint[4] Y;
+    }
+    /// @return r the negation of p, i.e. p.addition(p.negate()) should be zero.
+    function negate(G1Point memory p) internal pure returns (G1Point memory r) {
+        // The prime q in the base field F_q for G1, as q_hi * 2^256 + q_lo
+        uint q_hi = <%q_hi%>;
+        uint q_lo = <%q_lo%>;
+        r.X = p.X;
+        if (p.Y[0] == 0 && p.Y[1] == 0)
+            return r;
+        uint borrow = p.Y[1] > q_lo ? 1 : 0;
+        unchecked {
+            r.Y[1] = q_lo - p.Y[1];
+        }
+        r.Y[0] = q_hi - p.Y[0] - borrow;
+    }
+    /// @return r the sum of two points of G1
+    function addition(G1Point memory p1, G1Point memory p2) internal view returns (G1Point memory r) {
+        uint[8] memory input;
+        input[0] = p1.X[0];
+        input[1] = p1.X[1];
+        input[2] = p1.Y[0];
+        input[3] = p1.Y[1];
+        input[4] = p2.X[0];
+        input[5] = p2.X[1];
+        input[6] = p2.Y[0];
+        input[7] = p2.Y[1];
+        uint[4] memory out;
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), <%g1_add%>, input, 0x100, out, 0x80)
+            // Use "invalid" to make gas estimation work
+            switch success case 0 { invalid() }
+        }
+        require(success);
+        r.X = [out[0], out[1]];
+        r.Y = [out[2], out[3]];
+    }
+    /// @return r the product of a point on G1 and a scalar, i.e.
+    /// p == p.scalar_mul(1) and p.addition(p) == p.scalar_mul(2) for all points p.
+    function scalar_mul(G1Point memory p, uint s) internal view returns (G1Point memory r) {
+        // G1MSM with a single (point, scalar) pair
+        uint[5] memory input;
+        input[0] = p.X[0];
+        input[1] = p.X[1];
+        input[2] = p.Y[0];
+        input[3] = p.Y[1];
+        input[4] = s;
+        uint[4] memory out;
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), <%g1_mul%>, input, 0xa0, out, 0x80)
+            // Use "invalid" to make gas estimation work
+            switch success case 0 { invalid() }
+        }
+        require(success);
+        r.X = [out[0], out[1]];
+        r.Y = [out[2], out[3]];
+    }
+    /// @return the result of computing the pairing check
+    /// e(p1[0], p2[0]) *  .... * e(p1[n], p2[n]) == 1
+    function pairing(G1Point[] memory p1, G2Point[] memory p2) internal view returns (bool) {
+        require(p1.length == p2.length);
+        uint elements = p1.length;
+        uint inputSize = elements * 12;
+        uint[] memory input = new uint[](inputSize);
+        for (uint i = 0; i < elements; i++)
+        {
+            input[i * 12 + 0] = p1[i].X[0];
+            input[i * 12 + 1] = p1[i].X[1];
+            input[i * 12 + 2] = p1[i].Y[0];
+            input[i * 12 + 3] = p1[i].Y[1];
+            for (uint j = 0; j < 4; j++) {
+                input[i * 12 + 4 + j] = p2[i].X[j];
+                input[i * 12 + 8 + j] = p2[i].Y[j];
+            }
+        }
+        uint[1] memory out;
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), <%pairing%>, add(input, 0x20), mul(inputSize, 0x20), out, 0x20)
+            // Use "invalid" to make gas estimation work
+            switch success case 0 { invalid() }
+        }
+        require(success);
+        return out[0] != 0;
+    }
+    /// Convenience method for a pairing check for two pairs.
+    function pairingProd2(G1Point memory a1, G2Point memory a2, G1Point memory b1, G2Point memory b2) internal view returns (bool) {
+        G1Point[] memory p1 = new G1Point[](2);
+        G2Point[] memory p2 = new G2Point[](2);
+        p1[0] = a1;
+        p1[1] = b1;
+        p2[0] = a2;
+        p2[1] = b2;
+        return pairing(p1, p2);
+    }
+    /// Convenience method for a pairing check for three pairs.
+    function pairingProd3(
+            G1Point memory a1, G2Point memory a2,
+            G1Point memory b1, G2Point memory b2,
+            G1Point memory c1, G2Point memory c2
+    ) internal view returns (bool) {
+        G1Point[] memory p1 = new G1Point[](3);
+        G2Point[] memory p2 = new G2Point[](3);
+        p1[0] = a1;
+        p1[1] = b1;
+        p1[2] = c1;
+        p2[0] = a2;
+        p2[1] = b2;
+        p2[2] = c2;
+        return pairing(p1, p2);
+    }
+    /// Convenience method for a pairing check for four pairs.
+    function pairingProd4(
+            G1Point memory a1, G2Point memory a2,
+            G1Point memory b1, G2Point memory b2,
+            G1Point memory c1, G2Point memory c2,
+            G1Point memory d1, G2Point memory d2
+    ) internal view returns (bool) {
+        G1Point[] memory p1 = new G1Point[](4);
+        G2Point[] memory p2 = new G2Point[](4);
+        p1[0] = a1;
+        p1[1] = b1;
+        p1[2] = c1;
+        p1[3] = d1;
+        p2[0] = a2;
+        p2[1] = b2;
+        p2[2] = c2;
+        p2[3] = d2;
+        return pairing(p1, p2);
+    }
+"#;
+
+const PAIRING_LIB_G2_ADDITION: &str = r#"
+    /// @return r the sum of two points of G2
+    function addition(G2Point memory p1, G2Point memory p2) internal view returns (G2Point memory r) {
+        uint[16] memory input;
+        for (uint j = 0; j < 4; j++) {
+            input[j] = p1.X[j];
+            input[4 + j] = p1.Y[j];
+            input[8 + j] = p2.X[j];
+            input[12 + j] = p2.Y[j];
+        }
+        uint[8] memory out;
+        bool success;
+        assembly {
+            success := staticcall(sub(gas(), 2000), <%g2_add%>, input, 0x200, out, 0x100)
+            // Use "invalid" to make gas estimation work
+            switch success case 0 { invalid() }
+        }
+        require(success);
+        r.X = [out[0], out[1], out[2], out[3]];
+        r.Y = [out[4], out[5], out[6], out[7]];
+    }"#;
@@ -1,15 +1,29 @@
-use ark_bn254::Bn254;
-use ark_ff::PrimeField;
+use ark_bn254::{Bn254, Fq};
+use ark_ff::{BigInteger, PrimeField};
 
-use crate::PairingLibrary;
+use crate::{PairingLibrary, Precompiles};
 
 impl PairingLibrary for Bn254 {
+    const PRECOMPILES: Precompiles = Precompiles {
+        g1_add: 0x06,
+        g1_mul: 0x07,
+        g2_add: None,
+        pairing: 0x08,
+    };
+
+    const G1_ABI_TYPE: &'static str = "(uint256,uint256)";
+    const G2_ABI_TYPE: &'static str = "(uint256[2],uint256[2])";
+
     fn template(g2_addition: bool) -> String {
+        let pairing_lib = PAIRING_LIB_START
+            .replace("<%g1_add%>", &Self::PRECOMPILES.g1_add.to_string())
+            .replace("<%g1_mul%>", &Self::PRECOMPILES.g1_mul.to_string())
+            .replace("<%pairing%>", &Self::PRECOMPILES.pairing.to_string());
         match g2_addition {
             true => {
-                format!("{G2_ADDITION_LIB}\n{PAIRING_LIB_START}\n{PAIRING_LIB_G2_ADDITION}\n}}")
+                format!("{G2_ADDITION_LIB}\n{pairing_lib}\n{PAIRING_LIB_G2_ADDITION}\n}}")
             }
-            false => format!("{PAIRING_LIB_START}\n}}"),
+            false => format!("{pairing_lib}\n}}"),
         }
     }
 
@@ -26,6 +40,26 @@ impl PairingLibrary for Bn254 {
             g2.y.c1.into_repr()
         )
     }
+
+    fn g1_to_words(g1: &Self::G1Affine) -> Vec<[u8; 32]> {
+        vec![fq_word(g1.x), fq_word(g1.y)]
+    }
+
+    fn g2_to_words(g2: &Self::G2Affine) -> Vec<[u8; 32]> {
+        vec![
+            fq_word(g2.x.c0),
+            fq_word(g2.x.c1),
+            fq_word(g2.y.c0),
+            fq_word(g2.y.c1),
+        ]
+    }
+}
+
+fn fq_word(value: Fq) -> [u8; 32] {
+    let bytes = value.into_repr().to_bytes_be();
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    word
 }
 
 const PAIRING_LIB_START: &str = r#"
@@ -76,7 +110,7 @@ library Pairing {
         input[3] = p2.Y;
         bool success;
         assembly {
-            success := staticcall(sub(gas(), 2000), 6, input, 0xc0, r, 0x60)
+            success := staticcall(sub(gas(), 2000), <%g1_add%>, input, 0xc0, r, 0x60)
             // Use "invalid" to make gas estimation work
             switch success case 0 { invalid() }
         }
@@ -91,7 +125,7 @@ library Pairing {
         input[2] = s;
         bool success;
         assembly {
-            success := staticcall(sub(gas(), 2000), 7, input, 0x80, r, 0x60)
+            success := staticcall(sub(gas(), 2000), <%g1_mul%>, input, 0x80, r, 0x60)
             // Use "invalid" to make gas estimation work
             switch success case 0 { invalid() }
         }
@@ -118,7 +152,7 @@ library Pairing {
         uint[1] memory out;
         bool success;
         assembly {
-            success := staticcall(sub(gas(), 2000), 8, add(input, 0x20), mul(inputSize, 0x20), out, 0x20)
+            success := staticcall(sub(gas(), 2000), <%pairing%>, add(input, 0x20), mul(inputSize, 0x20), out, 0x20)
             // Use "invalid" to make gas estimation work
             switch success case 0 { invalid() }
         }
@@ -0,0 +1,670 @@
+//! Circuit profiles shared by the setup binaries: one entry per (circuit, player count)
+//! with dummy inputs, enough to synthesize the constraint system for key generation.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context, Result};
+use ark_bn254::Fr;
+use ark_crypto_primitives::encryption::AsymmetricEncryptionScheme;
+use ark_ff::{BigInteger, PrimeField};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef};
+use ark_std::UniformRand;
+use mpc_algebra::CommitmentScheme;
+use mpc_algebra_wasm::{
+    CircuitProfile, GroupingParameter, InstanceMode, Role as GroupingRole, StateChainParameter,
+    WinConditionParameter,
+};
+use mpc_circuits::{
+    AnonymousVotingCircuit, AnonymousVotingPrivateInput, AnonymousVotingPublicInput,
+    DivinationCircuit, DivinationPrivateInput, DivinationPublicInput, KeyPublicizeCircuit,
+    KeyPublicizePrivateInput, KeyPublicizePublicInput, MediumCircuit, MediumPrivateInput,
+    MediumPublicInput, NightResolutionCircuit, NightResolutionPrivateInput,
+    NightResolutionPublicInput, RoleAssignmentCircuit, RoleAssignmentPrivateInput,
+    RoleAssignmentPublicInput, WinningJudgementCircuit, WinningJudgementPrivateInput,
+    WinningJudgementPublicInput,
+};
+use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
+
+pub const DIVINATION_PLAYER_COUNTS: [usize; 7] = [3, 4, 5, 6, 7, 8, 9];
+pub const ANONYMOUS_VOTING_PLAYER_COUNTS: [usize; 7] = [3, 4, 5, 6, 7, 8, 9];
+pub const WINNING_JUDGEMENT_PLAYER_COUNTS: [usize; 8] = [2, 3, 4, 5, 6, 7, 8, 9];
+pub const KEY_PUBLICIZE_PLAYER_COUNTS: [usize; 6] = [4, 5, 6, 7, 8, 9];
+pub const NIGHT_RESOLUTION_PLAYER_COUNTS: [usize; 6] = [4, 5, 6, 7, 8, 9];
+pub const MEDIUM_PLAYER_COUNTS: [usize; 7] = [3, 4, 5, 6, 7, 8, 9];
+pub const STATE_CHAIN_PUBLIC_INPUT_LEN: usize = 4;
+pub const ROLE_ASSIGNMENT_PROFILES: [(usize, usize); 14] = [
+    (4, 1),
+    (5, 1),
+    (5, 2),
+    (6, 1),
+    (6, 2),
+    (7, 1),
+    (7, 2),
+    (7, 3),
+    (8, 1),
+    (8, 2),
+    (8, 3),
+    (9, 1),
+    (9, 2),
+    (9, 3),
+];
+
+#[derive(Debug, Clone, Copy)]
+pub enum ProfileSpec {
+    RoleAssignment {
+        num_players: usize,
+        werewolf_count: usize,
+    },
+    Divination(usize),
+    AnonymousVoting(usize),
+    WinningJudgement(usize),
+    KeyPublicize(usize),
+    NightResolution(usize),
+    Medium(usize),
+}
+
+pub struct Profile {
+    pub spec: ProfileSpec,
+    pub circuit: ProfileCircuit,
+}
+
+pub enum ProfileCircuit {
+    RoleAssignment(RoleAssignmentCircuit<Fr>),
+    Divination(DivinationCircuit<Fr>),
+    AnonymousVoting(AnonymousVotingCircuit<Fr>),
+    WinningJudgement(WinningJudgementCircuit<Fr>),
+    KeyPublicize(KeyPublicizeCircuit<Fr>),
+    NightResolution(NightResolutionCircuit<Fr>),
+    Medium(MediumCircuit<Fr>),
+}
+
+impl ConstraintSynthesizer<Fr> for ProfileCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> ark_relations::r1cs::Result<()> {
+        match self {
+            ProfileCircuit::RoleAssignment(circuit) => circuit.generate_constraints(cs),
+            ProfileCircuit::Divination(circuit) => circuit.generate_constraints(cs),
+            ProfileCircuit::AnonymousVoting(circuit) => circuit.generate_constraints(cs),
+            ProfileCircuit::WinningJudgement(circuit) => circuit.generate_constraints(cs),
+            ProfileCircuit::KeyPublicize(circuit) => circuit.generate_constraints(cs),
+            ProfileCircuit::NightResolution(circuit) => circuit.generate_constraints(cs),
+            ProfileCircuit::Medium(circuit) => circuit.generate_constraints(cs),
+        }
+    }
+}
+
+impl ProfileSpec {
+    pub fn all() -> Vec<ProfileSpec> {
+        let mut specs = ROLE_ASSIGNMENT_PROFILES
+            .iter()
+            .map(
+                |&(num_players, werewolf_count)| ProfileSpec::RoleAssignment {
+                    num_players,
+                    werewolf_count,
+                },
+            )
+            .collect::<Vec<_>>();
+        specs.extend(DIVINATION_PLAYER_COUNTS.map(ProfileSpec::Divination));
+        specs.extend(ANONYMOUS_VOTING_PLAYER_COUNTS.map(ProfileSpec::AnonymousVoting));
+        specs.extend(WINNING_JUDGEMENT_PLAYER_COUNTS.map(ProfileSpec::WinningJudgement));
+        specs.extend(KEY_PUBLICIZE_PLAYER_COUNTS.map(ProfileSpec::KeyPublicize));
+        specs.extend(NIGHT_RESOLUTION_PLAYER_COUNTS.map(ProfileSpec::NightResolution));
+        specs.extend(MEDIUM_PLAYER_COUNTS.map(ProfileSpec::Medium));
+        specs
+    }
+
+    pub fn find(circuit_id: &str) -> Result<ProfileSpec> {
+        Self::all()
+            .into_iter()
+            .find(|spec| spec.circuit_id() == circuit_id)
+            .with_context(|| format!("unknown circuit id {circuit_id}"))
+    }
+
+    pub fn circuit_id(self) -> String {
+        match self {
+            ProfileSpec::RoleAssignment {
+                num_players,
+                werewolf_count,
+            } => format!("role_assignment_n{num_players}_w{werewolf_count}_v1"),
+            ProfileSpec::Divination(n) => format!("divination_n{n}_v1"),
+            ProfileSpec::AnonymousVoting(n) => format!("anonymous_voting_n{n}_v1"),
+            ProfileSpec::WinningJudgement(n) => format!("winning_judgement_n{n}_v1"),
+            ProfileSpec::KeyPublicize(n) => format!("key_publicize_n{n}_v1"),
+            ProfileSpec::NightResolution(n) => format!("night_resolution_n{n}_v1"),
+            ProfileSpec::Medium(n) => format!("medium_n{n}_v1"),
+        }
+    }
+
+    /// Solidity contract name of the verifier, e.g. `DivinationN5Groth16Verifier` for
+    /// `scheme = "Groth16"`.
+    pub fn contract_name(self, scheme: &str) -> String {
+        match self {
+            ProfileSpec::RoleAssignment {
+                num_players,
+                werewolf_count,
+            } => format!("RoleAssignmentN{num_players}W{werewolf_count}{scheme}Verifier"),
+            ProfileSpec::Divination(n) => format!("DivinationN{n}{scheme}Verifier"),
+            ProfileSpec::AnonymousVoting(n) => format!("AnonymousVotingN{n}{scheme}Verifier"),
+            ProfileSpec::WinningJudgement(n) => format!("WinningJudgementN{n}{scheme}Verifier"),
+            ProfileSpec::KeyPublicize(n) => format!("KeyPublicizeN{n}{scheme}Verifier"),
+            ProfileSpec::NightResolution(n) => format!("NightResolutionN{n}{scheme}Verifier"),
+            ProfileSpec::Medium(n) => format!("MediumN{n}{scheme}Verifier"),
+        }
+    }
+
+    pub fn max_players(self) -> usize {
+        match self {
+            ProfileSpec::RoleAssignment { num_players, .. } => num_players,
+            ProfileSpec::Divination(n)
+            | ProfileSpec::AnonymousVoting(n)
+            | ProfileSpec::WinningJudgement(n)
+            | ProfileSpec::KeyPublicize(n)
+            | ProfileSpec::NightResolution(n)
+            | ProfileSpec::Medium(n) => n,
+        }
+    }
+
+    pub fn circuit_profile(self) -> CircuitProfile {
+        match self {
+            ProfileSpec::RoleAssignment {
+                num_players,
+                werewolf_count,
+            } => CircuitProfile::RoleAssignment {
+                player_count: num_players,
+                werewolf_count,
+            },
+            ProfileSpec::Divination(player_count) => CircuitProfile::Divination { player_count },
+            ProfileSpec::AnonymousVoting(player_count) => {
+                CircuitProfile::AnonymousVoting { player_count }
+            }
+            ProfileSpec::WinningJudgement(player_count) => {
+                CircuitProfile::WinningJudge { player_count }
+            }
+            ProfileSpec::KeyPublicize(player_count) => {
+                CircuitProfile::KeyPublicize { player_count }
+            }
+            ProfileSpec::NightResolution(player_count) => {
+                CircuitProfile::NightResolution { player_count }
+            }
+            ProfileSpec::Medium(player_count) => CircuitProfile::Medium { player_count },
+        }
+    }
+
+    /// Words of the instance, i.e. the `uint256[N]` calldata of `verifyTx` in either mode.
+    pub fn instance_len(self) -> usize {
+        // 全回路の公開入力の末尾に alive_mask, day_count, prev / next state digest が付く
+        STATE_CHAIN_PUBLIC_INPUT_LEN
+            + match self {
+                ProfileSpec::RoleAssignment { num_players, .. } => {
+                    role_assignment_public_input_len(num_players)
+                }
+                // alive_mask + ElGamal param, pub_key, ciphertext
+                ProfileSpec::Divination(_) => 9,
+                // alive_mask + most_voted_id + player_commitment[i] (x, y)
+                ProfileSpec::AnonymousVoting(n) => 2 + 2 * n,
+                // num_alive + alive_mask + majority, day_limit, day_count + game_state + winner_mask
+                // + role_commitment[i] (x, y)
+                ProfileSpec::WinningJudgement(n) => 7 + 2 * n,
+                ProfileSpec::KeyPublicize(n) => key_publicize_public_input_len(n),
+                ProfileSpec::NightResolution(n) => night_resolution_public_input_len(n),
+                // executed id + Divination layout + role_commitment[i] (x, y)
+                ProfileSpec::Medium(n) => 9 + 2 * n,
+            }
+    }
+
+    /// Builds the circuit of this profile with dummy private inputs. The Pedersen and
+    /// ElGamal parameters are sampled from `rng`, so the same seed gives the same constants.
+    pub fn build(
+        self,
+        instance_mode: InstanceMode,
+        rng: &mut (impl ark_std::rand::RngCore + ark_std::rand::CryptoRng),
+    ) -> Result<Profile> {
+        let circuit = match self {
+            ProfileSpec::RoleAssignment {
+                num_players,
+                werewolf_count,
+            } => ProfileCircuit::RoleAssignment(build_role_assignment_circuit(
+                num_players,
+                werewolf_count,
+                instance_mode,
+                rng,
+            )?),
+            ProfileSpec::Divination(n) => {
+                ProfileCircuit::Divination(build_divination_circuit(n, rng)?)
+            }
+            ProfileSpec::AnonymousVoting(n) => {
+                ProfileCircuit::AnonymousVoting(build_anonymous_voting_circuit(n, rng)?)
+            }
+            ProfileSpec::WinningJudgement(n) => {
+                ProfileCircuit::WinningJudgement(build_winning_judgement_circuit(n, rng)?)
+            }
+            ProfileSpec::KeyPublicize(n) => {
+                ProfileCircuit::KeyPublicize(build_key_publicize_circuit(n, rng)?)
+            }
+            ProfileSpec::NightResolution(n) => {
+                ProfileCircuit::NightResolution(build_night_resolution_circuit(n, rng)?)
+            }
+            ProfileSpec::Medium(n) => ProfileCircuit::Medium(build_medium_circuit(n, rng)?),
+        };
+        Ok(Profile {
+            spec: self,
+            circuit,
+        })
+    }
+}
+
+fn build_role_assignment_circuit(
+    num_players: usize,
+    werewolf_count: usize,
+    instance_mode: InstanceMode,
+    rng: &mut impl ark_std::rand::RngCore,
+) -> Result<RoleAssignmentCircuit<Fr>> {
+    if num_players < 4 || num_players > 9 {
+        bail!("RoleAssignment supports 4..9 players only");
+    }
+    if werewolf_count == 0 || werewolf_count >= num_players {
+        bail!("Invalid werewolf count {werewolf_count} for {num_players} players");
+    }
+    let villager_count = num_players
+        .checked_sub(1 + werewolf_count)
+        .context("invalid (num_players, werewolf_count) profile")?;
+
+    let mut map = BTreeMap::new();
+    map.insert(GroupingRole::FortuneTeller, (1, false));
+    map.insert(GroupingRole::Werewolf, (werewolf_count, werewolf_count > 1));
+    map.insert(GroupingRole::Villager, (villager_count, false));
+    let grouping_parameter = GroupingParameter::new(map);
+    let tau_matrix = grouping_parameter.generate_tau_matrix::<Fr>();
+    let matrix_size = tau_matrix.nrows();
+    let identity = nalgebra::DMatrix::<Fr>::identity(matrix_size, matrix_size);
+
+    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
+        .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
+
+    let mut private_input = Vec::with_capacity(num_players);
+    let mut player_commitment = Vec::with_capacity(num_players);
+    for id in 0..num_players {
+        let player_randomness = Fr::from((id + 1) as u64);
+        let player_commitment_randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng);
+        player_commitment.push(
+            <Fr as LocalOrMPC<Fr>>::PedersenComScheme::commit(
+                &pedersen_param,
+                &player_randomness.into_repr().to_bytes_le(),
+                &player_commitment_randomness,
+            )
+            .map_err(|e| anyhow::anyhow!("pedersen commit failed: {e:?}"))?,
+        );
+        private_input.push(RoleAssignmentPrivateInput::<Fr> {
+            id,
+            shuffle_matrices: identity.clone(),
+            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng),
+            player_randomness,
+            player_commitment_randomness,
+        });
+    }
+
+    let mut circuit = RoleAssignmentCircuit {
+        private_input,
+        public_input: RoleAssignmentPublicInput::<Fr> {
+            num_players,
+            max_group_size: grouping_parameter.get_max_group_size(),
+            pedersen_param,
+            grouping_parameter,
+            tau_matrix,
+            role_commitment: Vec::new(),
+            player_commitment,
+            state_chain: StateChainParameter::default(),
+            instance_mode,
+        },
+    };
+
+    // role_commitment は計算された役職IDへのコミットメント
+    let role_commitment = circuit
+        .calculate_output()
+        .iter()
+        .zip(circuit.private_input.iter())
+        .map(|(role_id, input)| {
+            <Fr as LocalOrMPC<Fr>>::PedersenComScheme::commit(
+                &circuit.public_input.pedersen_param,
+                &role_id.into_repr().to_bytes_le(),
+                &input.randomness,
+            )
+            .map_err(|e| anyhow::anyhow!("pedersen commit failed: {e:?}"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    circuit.public_input.role_commitment = role_commitment;
+
+    Ok(circuit)
+}
+
+fn build_divination_circuit(
+    num_players: usize,
+    rng: &mut (impl ark_std::rand::RngCore + ark_std::rand::CryptoRng),
+) -> Result<DivinationCircuit<Fr>> {
+    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
+        .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
+
+    let elgamal_param =
+        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::setup(rng)
+            .map_err(|e| anyhow::anyhow!("elgamal setup failed: {e:?}"))?;
+    let (pub_key, _secret_key) =
+        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::keygen(
+            &elgamal_param,
+            rng,
+        )
+        .map_err(|e| anyhow::anyhow!("elgamal keygen failed: {e:?}"))?;
+
+    let randomness = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalRandomness::rand(rng);
+    let private_input = (0..num_players)
+        .map(|id| {
+            let mut is_target = vec![Fr::from(0u32); num_players];
+            if id == 0 {
+                is_target[0] = Fr::from(1u32);
+            }
+
+            DivinationPrivateInput::<Fr> {
+                id,
+                is_werewolf: if id == 0 {
+                    Fr::from(1u32)
+                } else {
+                    Fr::from(0u32)
+                },
+                is_target,
+                randomness: randomness.clone(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    Ok(DivinationCircuit {
+        private_input,
+        public_input: DivinationPublicInput::<Fr> {
+            pedersen_param,
+            elgamal_param,
+            pub_key,
+            player_num: num_players,
+            state_chain: StateChainParameter {
+                alive_mask: (1 << num_players) - 1,
+                ..Default::default()
+            },
+        },
+    })
+}
+
+fn build_anonymous_voting_circuit(
+    num_players: usize,
+    rng: &mut impl ark_std::rand::RngCore,
+) -> Result<AnonymousVotingCircuit<Fr>> {
+    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
+        .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
+
+    let private_input = (0..num_players)
+        .map(|id| {
+            let mut is_target_id = vec![Fr::from(0u32); num_players];
+            is_target_id[1] = Fr::from(1u32);
+
+            AnonymousVotingPrivateInput::<Fr> {
+                id,
+                is_target_id,
+                player_randomness: Fr::from((id + 1) as u64),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let player_commitment =
+        vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); num_players];
+
+    Ok(AnonymousVotingCircuit {
+        private_input,
+        public_input: AnonymousVotingPublicInput::<Fr> {
+            pedersen_param,
+            player_commitment,
+            player_num: num_players,
+            state_chain: StateChainParameter {
+                alive_mask: (1 << num_players) - 1,
+                ..Default::default()
+            },
+        },
+    })
+}
+
+fn build_winning_judgement_circuit(
+    num_players: usize,
+    rng: &mut impl ark_std::rand::RngCore,
+) -> Result<WinningJudgementCircuit<Fr>> {
+    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
+        .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
+
+    let mut private_input = Vec::with_capacity(num_players);
+    let mut role_commitment = Vec::with_capacity(num_players);
+    for id in 0..num_players {
+        // プレイヤー0が人狼 (役職ID 2)、それ以外は村人 (役職ID 0)
+        let role_id = if id == 0 {
+            Fr::from(2u32)
+        } else {
+            Fr::from(0u32)
+        };
+        let randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng);
+        let commitment = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::commit(
+            &pedersen_param,
+            &role_id.into_repr().to_bytes_le(),
+            &randomness,
+        )
+        .map_err(|e| anyhow::anyhow!("pedersen commit failed: {e:?}"))?;
+
+        private_input.push(WinningJudgementPrivateInput::<Fr> {
+            id,
+            am_werewolf: if id == 0 {
+                Fr::from(1u32)
+            } else {
+                Fr::from(0u32)
+            },
+            am_werewolf_team: if id == 0 {
+                Fr::from(1u32)
+            } else {
+                Fr::from(0u32)
+            },
+            am_fox: Fr::from(0u32),
+            role_id,
+            randomness,
+        });
+        role_commitment.push(commitment);
+    }
+
+    Ok(WinningJudgementCircuit {
+        private_input,
+        public_input: WinningJudgementPublicInput::<Fr> {
+            pedersen_param,
+            player_num: num_players,
+            win_condition: WinConditionParameter::default(),
+            role_commitment,
+            state_chain: StateChainParameter {
+                alive_mask: (1 << num_players) - 1,
+                day_count: 1,
+                ..Default::default()
+            },
+        },
+    })
+}
+
+fn build_key_publicize_circuit(
+    num_players: usize,
+    rng: &mut (impl ark_std::rand::RngCore + ark_std::rand::CryptoRng),
+) -> Result<KeyPublicizeCircuit<Fr>> {
+    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
+        .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
+
+    let elgamal_param =
+        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::setup(rng)
+            .map_err(|e| anyhow::anyhow!("elgamal setup failed: {e:?}"))?;
+    let (pub_key, _secret_key) =
+        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::keygen(
+            &elgamal_param,
+            rng,
+        )
+        .map_err(|e| anyhow::anyhow!("elgamal keygen failed: {e:?}"))?;
+
+    let mut private_input = Vec::with_capacity(num_players);
+    let mut role_commitment = Vec::with_capacity(num_players);
+    for id in 0..num_players {
+        // プレイヤー0が占い師 (役職ID 1)、それ以外は村人 (役職ID 0)
+        let role_id = if id == 0 {
+            Fr::from(1u32)
+        } else {
+            Fr::from(0u32)
+        };
+        let randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng);
+        let commitment = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::commit(
+            &pedersen_param,
+            &role_id.into_repr().to_bytes_le(),
+            &randomness,
+        )
+        .map_err(|e| anyhow::anyhow!("pedersen commit failed: {e:?}"))?;
+
+        private_input.push(KeyPublicizePrivateInput::<Fr> {
+            id,
+            pub_key_or_dummy_x: if id == 0 { pub_key.x } else { Fr::from(0u32) },
+            pub_key_or_dummy_y: if id == 0 { pub_key.y } else { Fr::from(0u32) },
+            role_id,
+            randomness,
+        });
+        role_commitment.push(commitment);
+    }
+
+    Ok(KeyPublicizeCircuit {
+        private_input,
+        public_input: KeyPublicizePublicInput::<Fr> {
+            pedersen_param,
+            role_commitment,
+            state_chain: StateChainParameter::default(),
+        },
+    })
+}
+
+fn build_night_resolution_circuit(
+    num_players: usize,
+    rng: &mut impl ark_std::rand::RngCore,
+) -> Result<NightResolutionCircuit<Fr>> {
+    if num_players < 4 {
+        bail!("NightResolution needs at least 4 players");
+    }
+    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
+        .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
+
+    // プレイヤー0が人狼でプレイヤー1を襲撃し、プレイヤー2が騎士でプレイヤー3を護衛する
+    let mut private_input = Vec::with_capacity(num_players);
+    let mut role_commitment = Vec::with_capacity(num_players);
+    for id in 0..num_players {
+        let role_id = match id {
+            0 => Fr::from(2u32),
+            2 => Fr::from(3u32),
+            _ => Fr::from(0u32),
+        };
+        let mut is_attack_target_id = vec![Fr::from(0u32); num_players];
+        let mut is_guard_target_id = vec![Fr::from(0u32); num_players];
+        if id == 0 {
+            is_attack_target_id[1] = Fr::from(1u32);
+        }
+        if id == 2 {
+            is_guard_target_id[3] = Fr::from(1u32);
+        }
+
+        let randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng);
+        let commitment = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::commit(
+            &pedersen_param,
+            &role_id.into_repr().to_bytes_le(),
+            &randomness,
+        )
+        .map_err(|e| anyhow::anyhow!("pedersen commit failed: {e:?}"))?;
+
+        private_input.push(NightResolutionPrivateInput::<Fr> {
+            id,
+            is_attack_target_id,
+            is_guard_target_id,
+            role_id,
+            randomness,
+        });
+        role_commitment.push(commitment);
+    }
+
+    Ok(NightResolutionCircuit {
+        private_input,
+        public_input: NightResolutionPublicInput::<Fr> {
+            pedersen_param,
+            player_num: num_players,
+            role_commitment,
+            state_chain: StateChainParameter::default(),
+        },
+    })
+}
+
+fn build_medium_circuit(
+    num_players: usize,
+    rng: &mut (impl ark_std::rand::RngCore + ark_std::rand::CryptoRng),
+) -> Result<MediumCircuit<Fr>> {
+    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng)
+        .map_err(|e| anyhow::anyhow!("pedersen setup failed: {e:?}"))?;
+
+    let elgamal_param =
+        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::setup(rng)
+            .map_err(|e| anyhow::anyhow!("elgamal setup failed: {e:?}"))?;
+    let (pub_key, _secret_key) =
+        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::keygen(
+            &elgamal_param,
+            rng,
+        )
+        .map_err(|e| anyhow::anyhow!("elgamal keygen failed: {e:?}"))?;
+
+    // プレイヤー0が人狼 (役職ID 2) で処刑された
+    let randomness = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalRandomness::rand(rng);
+    let mut private_input = Vec::with_capacity(num_players);
+    let mut role_commitment = Vec::with_capacity(num_players);
+    for id in 0..num_players {
+        let role_id = if id == 0 {
+            Fr::from(2u32)
+        } else {
+            Fr::from(0u32)
+        };
+        let role_randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng);
+        let commitment = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::commit(
+            &pedersen_param,
+            &role_id.into_repr().to_bytes_le(),
+            &role_randomness,
+        )
+        .map_err(|e| anyhow::anyhow!("pedersen commit failed: {e:?}"))?;
+
+        private_input.push(MediumPrivateInput::<Fr> {
+            id,
+            role_id,
+            role_randomness,
+            randomness: randomness.clone(),
+        });
+        role_commitment.push(commitment);
+    }
+
+    Ok(MediumCircuit {
+        private_input,
+        public_input: MediumPublicInput::<Fr> {
+            pedersen_param,
+            elgamal_param,
+            pub_key,
+            player_num: num_players,
+            executed_id: 0,
+            role_commitment,
+            state_chain: StateChainParameter::default(),
+        },
+    })
+}
+
+pub fn night_resolution_public_input_len(num_players: usize) -> usize {
+    // dead id + role_commitment[i] (x, y)
+    1 + 2 * num_players
+}
+
+pub fn key_publicize_public_input_len(num_players: usize) -> usize {
+    // pub_key (x, y) + role_commitment[i] (x, y)
+    2 + 2 * num_players
+}
+
+pub fn role_assignment_public_input_len(num_players: usize) -> usize {
+    // player_commitment[i] (x, y) + role_commitment[i] (x, y)
+    4 * num_players
+}
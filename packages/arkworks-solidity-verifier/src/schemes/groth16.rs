@@ -1,11 +1,63 @@
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G2Affine};
 use ark_ec::PairingEngine;
+use ark_ff::{BigInteger, PrimeField};
 use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_serialize::CanonicalSerialize;
+use mpc_algebra_wasm::{
+    digest_to_bytes, PoseidonParameters, POSEIDON_FULL_ROUNDS, POSEIDON_PARTIAL_ROUNDS,
+};
 use regex::Regex;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
 
 use crate::{
     constants::TEMPLATE_PREFIX_TEXT, utils::format_modulus, PairingLibrary, SolidityVerifier,
 };
 
+/// Shape of the generated Groth16 verifier. Every layout keeps the
+/// `verifyTx(Proof, uint256[N])` ABI and the `VK_HASH` constant of the standard verifier.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VerifierLayout {
+    /// Pairing library plus a `verifyingKey()` built in memory.
+    #[default]
+    Standard,
+    /// `gamma_abc` packed into one `bytes` constant. The linear combination and the pairing
+    /// check run in a single assembly block on the `ecAdd`/`ecMul`/`ecPairing` precompiles.
+    Compact,
+    /// Key-storage contract holding only the packed key. It forwards to a
+    /// `Groth16SharedVerifier` that is deployed once and passed to its constructor.
+    Split,
+}
+
+/// Output of [`export_with_layout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportedVerifier {
+    /// The per-key contract, named `Verifier`.
+    pub contract: String,
+    /// `Groth16SharedVerifier` source for [`VerifierLayout::Split`].
+    pub shared_verifier: Option<String>,
+}
+
+/// Exports a BN254 Groth16 verifier in the given layout.
+pub fn export_with_layout(vk: &VerifyingKey<Bn254>, layout: VerifierLayout) -> ExportedVerifier {
+    match layout {
+        VerifierLayout::Standard => ExportedVerifier {
+            contract: Groth16::<Bn254>::export(vk),
+            shared_verifier: None,
+        },
+        VerifierLayout::Compact => ExportedVerifier {
+            contract: export_compact(vk),
+            shared_verifier: None,
+        },
+        VerifierLayout::Split => ExportedVerifier {
+            contract: export_split_key(vk),
+            shared_verifier: Some(format!(
+                "// SPDX-License-Identifier: MIT\n{TEMPLATE_PREFIX_TEXT}\n{SHARED_VERIFIER_TEMPLATE}"
+            )),
+        },
+    }
+}
+
 impl<E: PairingEngine + PairingLibrary> SolidityVerifier<E> for Groth16<E> {
     type Proof = Proof<E>;
 
@@ -20,6 +72,14 @@ impl<E: PairingEngine + PairingLibrary> SolidityVerifier<E> for Groth16<E> {
         let vk_input_len_regex = Regex::new(r#"(<%vk_input_length%>)"#).unwrap();
         let input_loop = Regex::new(r#"(<%input_loop%>)"#).unwrap();
         let input_argument = Regex::new(r#"(<%input_argument%>)"#).unwrap();
+        let vk_hash_regex = Regex::new(r#"(<%vk_hash%>)"#).unwrap();
+
+        template_text = vk_hash_regex
+            .replace(
+                template_text.as_str(),
+                format!("0x{}", hex::encode(vk_hash(vk))).as_str(),
+            )
+            .into_owned();
 
         template_text = vk_regex
             .replace(template_text.as_str(), E::g1_to_string(&vk.alpha_g1))
@@ -111,9 +171,214 @@ impl<E: PairingEngine + PairingLibrary> SolidityVerifier<E> for Groth16<E> {
     }
 }
 
+/// SHA-256 of the uncompressed verifying key, exported as `VK_HASH` so that a deployed
+/// verifier can be matched against the Groth16 key manifest.
+pub fn vk_hash<E: PairingEngine>(vk: &VerifyingKey<E>) -> [u8; 32] {
+    let mut bytes = Vec::new();
+    vk.serialize_uncompressed(&mut bytes)
+        .expect("serializing into a Vec cannot fail");
+    Sha256::digest(&bytes).into()
+}
+
+/// `verifyTx(Proof, uint256[N])` calldata with `N = inputs.len()`, accepted by every layout.
+/// The proof words follow the curve's `Pairing` structs; on BN254 this is the order the
+/// nodes send them (`b` as `x.c0, x.c1, y.c0, y.c1`).
+pub fn verify_tx_calldata<E: PairingLibrary>(proof: &Proof<E>, inputs: &[E::Fr]) -> Vec<u8> {
+    let proof_type = format!("({g1},{},{g1})", E::G2_ABI_TYPE, g1 = E::G1_ABI_TYPE);
+    let signature = if inputs.is_empty() {
+        format!("verifyTx({proof_type})")
+    } else {
+        format!("verifyTx({proof_type},uint256[{}])", inputs.len())
+    };
+    let mut calldata = Keccak256::digest(signature.as_bytes())[..4].to_vec();
+    let words = [
+        E::g1_to_words(&proof.a),
+        E::g2_to_words(&proof.b),
+        E::g1_to_words(&proof.c),
+    ];
+    calldata.extend(words.into_iter().flatten().flatten());
+    calldata.extend(inputs.iter().flat_map(|input| digest_to_bytes(*input)));
+    calldata
+}
+
+/// Exports a compact verifier for a key made in `InstanceMode::Hashed`, whose only public
+/// input is `mpc_algebra_wasm::instance_hash` of the instance. `verifyTx` still takes the
+/// full instance of `instance_len` words and recomputes the Poseidon hash from calldata.
+pub fn export_hashed_instance(vk: &VerifyingKey<Bn254>, instance_len: usize) -> String {
+    assert_eq!(
+        vk.gamma_abc_g1.len(),
+        2,
+        "a hashed instance verifying key has exactly one public input"
+    );
+    // poseidon の中にも placeholder があるので先に展開する
+    let template_text = COMPACT_CONTRACT_TEMPLATE
+        .replace("<%input_loop%>", HASHED_INSTANCE_INPUT)
+        .replace(
+            "<%instance_hash%>",
+            "\n        uint256 instanceHash = hashInstance(input);",
+        )
+        .replace("<%poseidon%>", POSEIDON_TEMPLATE)
+        .replace("<%instance_length%>", &instance_len.to_string());
+    let template_text = fill_poseidon_permutation(template_text);
+    fill_compact_template(template_text, vk, compact_input_argument(instance_len))
+}
+
+/// Expands `<%poseidon_permutation%>` into the Poseidon constants and permutation used by
+/// `hashInstance`. `<%scalar_field%>` is left for the caller.
+pub(crate) fn fill_poseidon_permutation(template_text: String) -> String {
+    let params = PoseidonParameters::<Fr>::new();
+    let round_constants = params
+        .round_constants
+        .iter()
+        .flat_map(|c| digest_to_bytes(*c))
+        .collect::<Vec<_>>();
+    let mds = params
+        .mds
+        .iter()
+        .flatten()
+        .flat_map(|m| digest_to_bytes(*m))
+        .collect::<Vec<_>>();
+
+    template_text
+        .replace("<%poseidon_permutation%>", POSEIDON_PERMUTATION_TEMPLATE)
+        .replace("<%poseidon_c%>", &hex::encode(round_constants))
+        .replace("<%poseidon_m%>", &hex::encode(mds))
+        .replace(
+            "<%poseidon_rounds%>",
+            &(POSEIDON_FULL_ROUNDS + POSEIDON_PARTIAL_ROUNDS).to_string(),
+        )
+        .replace(
+            "<%poseidon_half_full_rounds%>",
+            &(POSEIDON_FULL_ROUNDS / 2).to_string(),
+        )
+        .replace(
+            "<%poseidon_last_partial_round%>",
+            &(POSEIDON_FULL_ROUNDS / 2 + POSEIDON_PARTIAL_ROUNDS - 1).to_string(),
+        )
+}
+
+fn export_compact(vk: &VerifyingKey<Bn254>) -> String {
+    let input_len = vk.gamma_abc_g1.len() - 1;
+
+    // input_loop の中にも placeholder があるので先に展開する
+    let template_text = COMPACT_CONTRACT_TEMPLATE
+        .replace(
+            "<%input_loop%>",
+            if input_len > 0 {
+                COMPACT_INPUT_LOOP
+            } else {
+                ""
+            },
+        )
+        .replace("<%instance_hash%>", "")
+        .replace("<%poseidon%>", "");
+    fill_compact_template(template_text, vk, compact_input_argument(input_len))
+}
+
+fn fill_compact_template(
+    mut template_text: String,
+    vk: &VerifyingKey<Bn254>,
+    input_argument: String,
+) -> String {
+    let input_len = vk.gamma_abc_g1.len() - 1;
+    let neg_alpha = -vk.alpha_g1;
+
+    let mut replace = |key: String, value: String| {
+        template_text = template_text.replace(&format!("<%{key}%>"), &value);
+    };
+    replace("vk_hash".into(), format!("0x{}", hex::encode(vk_hash(vk))));
+    replace("vk_input_length".into(), input_len.to_string());
+    replace(
+        "scalar_field".into(),
+        format_modulus::<<Bn254 as PairingEngine>::Fr>(),
+    );
+    replace("base_field".into(), format_modulus::<Fq>());
+    replace(
+        "gamma_abc_packed".into(),
+        hex::encode(pack_g1_points(&vk.gamma_abc_g1)),
+    );
+    replace("input_argument".into(), input_argument);
+    for (name, point_words) in [
+        ("neg_alpha", g1_words(&neg_alpha)),
+        ("beta", g2_words(&vk.beta_g2)),
+        ("gamma", g2_words(&vk.gamma_g2)),
+        ("delta", g2_words(&vk.delta_g2)),
+    ] {
+        for (i, word) in point_words.iter().enumerate() {
+            replace(format!("{name}_{i}"), format!("0x{}", hex::encode(word)));
+        }
+    }
+
+    format!("// SPDX-License-Identifier: MIT\n{TEMPLATE_PREFIX_TEXT}\n{template_text}")
+}
+
+fn export_split_key(vk: &VerifyingKey<Bn254>) -> String {
+    let input_len = vk.gamma_abc_g1.len() - 1;
+
+    // -alpha, beta, gamma, delta, gamma_abc の順。Groth16SharedVerifier が読む並び
+    let mut packed = g1_words(&-vk.alpha_g1).concat();
+    for g2 in [&vk.beta_g2, &vk.gamma_g2, &vk.delta_g2] {
+        packed.extend(g2_words(g2).concat());
+    }
+    packed.extend(pack_g1_points(&vk.gamma_abc_g1));
+
+    let input_copy = if input_len > 0 {
+        r#"
+        for (uint256 i = 0; i < input.length; i++) {
+            inputValues[i] = input[i];
+        }"#
+    } else {
+        ""
+    };
+
+    format!(
+        "// SPDX-License-Identifier: MIT\n{TEMPLATE_PREFIX_TEXT}\n{}",
+        SPLIT_KEY_TEMPLATE
+            .replace("<%vk_hash%>", &format!("0x{}", hex::encode(vk_hash(vk))))
+            .replace("<%vk_input_length%>", &input_len.to_string())
+            .replace("<%vk_packed%>", &hex::encode(packed))
+            .replace("<%input_argument%>", &compact_input_argument(input_len))
+            .replace("<%input_loop%>", input_copy)
+    )
+}
+
+fn compact_input_argument(input_len: usize) -> String {
+    if input_len > 0 {
+        format!(", uint256[{input_len}] calldata input")
+    } else {
+        String::new()
+    }
+}
+
+fn fq_word(value: Fq) -> [u8; 32] {
+    let bytes = value.into_repr().to_bytes_be();
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    word
+}
+
+fn g1_words(g1: &G1Affine) -> Vec<[u8; 32]> {
+    vec![fq_word(g1.x), fq_word(g1.y)]
+}
+
+/// EIP-197 order: `x.c1, x.c0, y.c1, y.c0`.
+fn g2_words(g2: &G2Affine) -> Vec<[u8; 32]> {
+    vec![
+        fq_word(g2.x.c1),
+        fq_word(g2.x.c0),
+        fq_word(g2.y.c1),
+        fq_word(g2.y.c0),
+    ]
+}
+
+fn pack_g1_points(points: &[G1Affine]) -> Vec<u8> {
+    points.iter().flat_map(g1_words).flatten().collect()
+}
+
 const CONTRACT_TEMPLATE: &str = r#"
 contract Verifier {
     using Pairing for *;
+    bytes32 public constant VK_HASH = bytes32(<%vk_hash%>);
     struct VerifyingKey {
         Pairing.G1Point alpha;
         Pairing.G2Point beta;
@@ -139,12 +404,11 @@ contract Verifier {
         VerifyingKey memory vk = verifyingKey();
         require(input.length + 1 == vk.gamma_abc.length);
         // Compute the linear combination vk_x
-        Pairing.G1Point memory vk_x = Pairing.G1Point(0, 0);
+        Pairing.G1Point memory vk_x = vk.gamma_abc[0];
         for (uint i = 0; i < input.length; i++) {
             require(input[i] < snark_scalar_field);
             vk_x = Pairing.addition(vk_x, Pairing.scalar_mul(vk.gamma_abc[i + 1], input[i]));
         }
-        vk_x = Pairing.addition(vk_x, vk.gamma_abc[0]);
         if(!Pairing.pairingProd4(
              proof.a, proof.b,
              Pairing.negate(vk_x), vk.gamma,
@@ -165,3 +429,243 @@ contract Verifier {
     }
 }
 "#;
+
+const COMPACT_CONTRACT_TEMPLATE: &str = r#"pragma solidity ^0.8.0;
+
+contract Verifier {
+    bytes32 public constant VK_HASH = bytes32(uint256(<%vk_hash%>));
+    // gamma_abc[i] の (x, y) を 64 byte ずつ連結したもの
+    bytes internal constant GAMMA_ABC = hex"<%gamma_abc_packed%>";
+
+    struct G1Point {
+        uint X;
+        uint Y;
+    }
+    struct G2Point {
+        uint[2] X;
+        uint[2] Y;
+    }
+    struct Proof {
+        G1Point a;
+        G2Point b;
+        G1Point c;
+    }
+
+    function verifyTx(Proof calldata proof<%input_argument%>) external view returns (bool r) {
+        bytes memory gammaAbc = GAMMA_ABC;<%instance_hash%>
+        assembly {
+            let q := <%base_field%>
+            let ok := 1
+            let f := mload(0x40)
+            let abc := add(gammaAbc, 0x20)
+
+            // vk_x = gamma_abc[0] + sum(input[i] * gamma_abc[i + 1])
+            mstore(f, mload(abc))
+            mstore(add(f, 0x20), mload(add(abc, 0x20)))<%input_loop%>
+            let vkX := mload(f)
+            let vkY := mload(add(f, 0x20))
+
+            // e(A, B) * e(-vk_x, gamma) * e(-C, delta) * e(-alpha, beta) == 1
+            mstore(f, calldataload(proof))
+            mstore(add(f, 0x20), calldataload(add(proof, 0x20)))
+            mstore(add(f, 0x40), calldataload(add(proof, 0x60)))
+            mstore(add(f, 0x60), calldataload(add(proof, 0x40)))
+            mstore(add(f, 0x80), calldataload(add(proof, 0xa0)))
+            mstore(add(f, 0xa0), calldataload(add(proof, 0x80)))
+
+            mstore(add(f, 0xc0), vkX)
+            mstore(add(f, 0xe0), mod(sub(q, mod(vkY, q)), q))
+            mstore(add(f, 0x100), <%gamma_0%>)
+            mstore(add(f, 0x120), <%gamma_1%>)
+            mstore(add(f, 0x140), <%gamma_2%>)
+            mstore(add(f, 0x160), <%gamma_3%>)
+
+            mstore(add(f, 0x180), calldataload(add(proof, 0xc0)))
+            mstore(add(f, 0x1a0), mod(sub(q, mod(calldataload(add(proof, 0xe0)), q)), q))
+            mstore(add(f, 0x1c0), <%delta_0%>)
+            mstore(add(f, 0x1e0), <%delta_1%>)
+            mstore(add(f, 0x200), <%delta_2%>)
+            mstore(add(f, 0x220), <%delta_3%>)
+
+            mstore(add(f, 0x240), <%neg_alpha_0%>)
+            mstore(add(f, 0x260), <%neg_alpha_1%>)
+            mstore(add(f, 0x280), <%beta_0%>)
+            mstore(add(f, 0x2a0), <%beta_1%>)
+            mstore(add(f, 0x2c0), <%beta_2%>)
+            mstore(add(f, 0x2e0), <%beta_3%>)
+
+            ok := and(ok, staticcall(gas(), 8, f, 0x300, f, 0x20))
+            r := and(ok, mload(f))
+        }
+    }<%poseidon%>
+}
+"#;
+
+const COMPACT_INPUT_LOOP: &str = r#"
+            for { let i := 0 } lt(i, <%vk_input_length%>) { i := add(i, 1) } {
+                let s := calldataload(add(input, mul(i, 0x20)))
+                ok := and(ok, lt(s, <%scalar_field%>))
+                let pt := add(abc, mul(add(i, 1), 0x40))
+                mstore(add(f, 0x40), mload(pt))
+                mstore(add(f, 0x60), mload(add(pt, 0x20)))
+                mstore(add(f, 0x80), s)
+                ok := and(ok, staticcall(gas(), 7, add(f, 0x40), 0x60, add(f, 0x40), 0x40))
+                ok := and(ok, staticcall(gas(), 6, f, 0x80, f, 0x40))
+            }"#;
+
+// instanceHash は addmod で作るので scalar field に収まっている
+const HASHED_INSTANCE_INPUT: &str = r#"
+            mstore(add(f, 0x40), mload(add(abc, 0x40)))
+            mstore(add(f, 0x60), mload(add(abc, 0x60)))
+            mstore(add(f, 0x80), instanceHash)
+            ok := and(ok, staticcall(gas(), 7, add(f, 0x40), 0x60, add(f, 0x40), 0x40))
+            ok := and(ok, staticcall(gas(), 6, f, 0x80, f, 0x40))"#;
+
+const POSEIDON_TEMPLATE: &str = r#"
+
+    // instance_hash: h = N, h = poseidon(h, input[i])
+    function hashInstance(uint256[<%instance_length%>] calldata input) internal pure returns (uint256 h) {
+        bytes memory c = POSEIDON_C;
+        bytes memory m = POSEIDON_M;
+        h = <%instance_length%>;
+        for (uint256 i = 0; i < <%instance_length%>; i++) {
+            require(input[i] < <%scalar_field%>, "Input is not in the scalar field");
+            h = poseidon(c, m, h, input[i]);
+        }
+    }<%poseidon_permutation%>"#;
+
+const POSEIDON_PERMUTATION_TEMPLATE: &str = r#"
+
+    // circomlib 互換 Poseidon (t = 3) の round constants と MDS 行列 (row-major)
+    bytes internal constant POSEIDON_C = hex"<%poseidon_c%>";
+    bytes internal constant POSEIDON_M = hex"<%poseidon_m%>";
+
+    // state = [0, s1, s2] の permutation の先頭要素
+    function poseidon(bytes memory c, bytes memory m, uint256 s1, uint256 s2) internal pure returns (uint256 s0) {
+        assembly {
+            let p := <%scalar_field%>
+            let cp := add(c, 0x20)
+            let mp := add(m, 0x20)
+            for { let r := 0 } lt(r, <%poseidon_rounds%>) { r := add(r, 1) } {
+                s0 := addmod(s0, mload(cp), p)
+                s1 := addmod(s1, mload(add(cp, 0x20)), p)
+                s2 := addmod(s2, mload(add(cp, 0x40)), p)
+                cp := add(cp, 0x60)
+
+                // x^5。full round は全要素、partial round は先頭だけ
+                let t := mulmod(s0, s0, p)
+                s0 := mulmod(mulmod(t, t, p), s0, p)
+                if or(lt(r, <%poseidon_half_full_rounds%>), gt(r, <%poseidon_last_partial_round%>)) {
+                    t := mulmod(s1, s1, p)
+                    s1 := mulmod(mulmod(t, t, p), s1, p)
+                    t := mulmod(s2, s2, p)
+                    s2 := mulmod(mulmod(t, t, p), s2, p)
+                }
+
+                let n0 := addmod(addmod(mulmod(mload(mp), s0, p), mulmod(mload(add(mp, 0x20)), s1, p), p), mulmod(mload(add(mp, 0x40)), s2, p), p)
+                let n1 := addmod(addmod(mulmod(mload(add(mp, 0x60)), s0, p), mulmod(mload(add(mp, 0x80)), s1, p), p), mulmod(mload(add(mp, 0xa0)), s2, p), p)
+                s2 := addmod(addmod(mulmod(mload(add(mp, 0xc0)), s0, p), mulmod(mload(add(mp, 0xe0)), s1, p), p), mulmod(mload(add(mp, 0x100)), s2, p), p)
+                s0 := n0
+                s1 := n1
+            }
+        }
+    }"#;
+
+const SPLIT_KEY_TEMPLATE: &str = r#"pragma solidity ^0.8.0;
+
+interface IGroth16SharedVerifier {
+    function verify(bytes calldata vk, uint256[8] calldata proof, uint256[] calldata input) external view returns (bool);
+}
+
+contract Verifier {
+    bytes32 public constant VK_HASH = bytes32(uint256(<%vk_hash%>));
+    // -alpha, beta, gamma, delta (EIP-197 の並び) と gamma_abc を連結したもの
+    bytes internal constant VK = hex"<%vk_packed%>";
+
+    address public immutable sharedVerifier;
+
+    struct G1Point {
+        uint X;
+        uint Y;
+    }
+    struct G2Point {
+        uint[2] X;
+        uint[2] Y;
+    }
+    struct Proof {
+        G1Point a;
+        G2Point b;
+        G1Point c;
+    }
+
+    constructor(address sharedVerifierAddress) {
+        require(sharedVerifierAddress != address(0), "Invalid shared verifier");
+        sharedVerifier = sharedVerifierAddress;
+    }
+
+    function verifyTx(Proof calldata proof<%input_argument%>) external view returns (bool) {
+        uint256[8] memory proofWords = [
+            proof.a.X, proof.a.Y, proof.b.X[0], proof.b.X[1], proof.b.Y[0], proof.b.Y[1], proof.c.X, proof.c.Y
+        ];
+        uint256[] memory inputValues = new uint256[](<%vk_input_length%>);<%input_loop%>
+        return IGroth16SharedVerifier(sharedVerifier).verify(VK, proofWords, inputValues);
+    }
+}
+"#;
+
+const SHARED_VERIFIER_TEMPLATE: &str = r#"pragma solidity ^0.8.0;
+
+/// Groth16 verifier shared by every key contract of the split layout.
+/// `vk` is -alpha (2 words), beta, gamma, delta (4 words each) and gamma_abc (2 words each).
+contract Groth16SharedVerifier {
+    uint256 internal constant SNARK_SCALAR_FIELD = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+    uint256 internal constant PRIME_Q = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+
+    function verify(bytes calldata vk, uint256[8] calldata proof, uint256[] calldata input) external view returns (bool r) {
+        if (vk.length != 14 * 32 + (input.length + 1) * 64) {
+            return false;
+        }
+        uint256 q = PRIME_Q;
+        uint256 field = SNARK_SCALAR_FIELD;
+        assembly {
+            let ok := 1
+            let f := mload(0x40)
+            let abc := add(vk.offset, 0x1c0)
+
+            // vk_x = gamma_abc[0] + sum(input[i] * gamma_abc[i + 1])
+            calldatacopy(f, abc, 0x40)
+            for { let i := 0 } lt(i, input.length) { i := add(i, 1) } {
+                let s := calldataload(add(input.offset, mul(i, 0x20)))
+                ok := and(ok, lt(s, field))
+                calldatacopy(add(f, 0x40), add(abc, mul(add(i, 1), 0x40)), 0x40)
+                mstore(add(f, 0x80), s)
+                ok := and(ok, staticcall(gas(), 7, add(f, 0x40), 0x60, add(f, 0x40), 0x40))
+                ok := and(ok, staticcall(gas(), 6, f, 0x80, f, 0x40))
+            }
+            let vkX := mload(f)
+            let vkY := mload(add(f, 0x20))
+
+            // e(A, B) * e(-vk_x, gamma) * e(-C, delta) * e(-alpha, beta) == 1
+            mstore(f, calldataload(proof))
+            mstore(add(f, 0x20), calldataload(add(proof, 0x20)))
+            mstore(add(f, 0x40), calldataload(add(proof, 0x60)))
+            mstore(add(f, 0x60), calldataload(add(proof, 0x40)))
+            mstore(add(f, 0x80), calldataload(add(proof, 0xa0)))
+            mstore(add(f, 0xa0), calldataload(add(proof, 0x80)))
+
+            mstore(add(f, 0xc0), vkX)
+            mstore(add(f, 0xe0), mod(sub(q, mod(vkY, q)), q))
+            calldatacopy(add(f, 0x100), add(vk.offset, 0xc0), 0x80)
+
+            mstore(add(f, 0x180), calldataload(add(proof, 0xc0)))
+            mstore(add(f, 0x1a0), mod(sub(q, mod(calldataload(add(proof, 0xe0)), q)), q))
+            calldatacopy(add(f, 0x1c0), add(vk.offset, 0x140), 0x80)
+
+            calldatacopy(add(f, 0x240), vk.offset, 0xc0)
+
+            ok := and(ok, staticcall(gas(), 8, f, 0x300, f, 0x20))
+            r := and(ok, mload(f))
+        }
+    }
+}
+"#;
@@ -1,11 +1,12 @@
 use std::fmt::Write;
 
+use ark_bn254::{Bn254, Fr, G1Affine};
 use ark_ec::PairingEngine;
-use ark_ff::{to_bytes, FftField, PrimeField};
+use ark_ff::{to_bytes, BigInteger, FftField, PrimeField};
 use ark_marlin::{IndexVerifierKey, Marlin};
 use ark_poly::univariate::DensePolynomial;
 use ark_poly_commit::marlin_pc::MarlinKZG10;
-use digest::Digest;
+use sha3::{Digest, Keccak256};
 
 use crate::{
     constants::TEMPLATE_PREFIX_TEXT,
@@ -51,8 +52,11 @@ pub struct Proof<E: PairingEngine> {
     pub batch_lc_proof_2: E::G1Affine,
 }
 
-impl<E: PairingEngine + PairingLibrary, D: Digest> SolidityVerifier<E>
-    for Marlin<E::Fr, MarlinKZG10<E, DensePolynomial<E::Fr>>, D>
+/// The generated verifier replays the Fiat-Shamir transcript of `ark_marlin`: Keccak256 as the
+/// digest and a ChaCha20 stream keyed by the current seed for the challenges, so only
+/// `Marlin<_, _, Keccak256>` proofs verify on-chain.
+impl<E: PairingEngine + PairingLibrary> SolidityVerifier<E>
+    for Marlin<E::Fr, MarlinKZG10<E, DensePolynomial<E::Fr>>, Keccak256>
 {
     type Proof = Proof<E>;
 
@@ -60,9 +64,9 @@ impl<E: PairingEngine + PairingLibrary, D: Digest> SolidityVerifier<E>
 
     fn export(vk: &Self::VerifyingKey) -> String {
         let template = String::from(CONTRACT_TEMPLATE);
-        let fs_seed = to_bytes![b"marlin", &vk].unwrap();
-        let x_root_of_unity =
-            <E::Fr as FftField>::get_root_of_unity(vk.index_info.num_instance_variables).unwrap();
+        let fs_seed = to_bytes![&Self::PROTOCOL_NAME, vk].unwrap();
+        let x_domain_size = x_domain_size(vk);
+        let x_root_of_unity = <E::Fr as FftField>::get_root_of_unity(x_domain_size).unwrap();
 
         // Replace public parameters in template
         let src = template
@@ -140,11 +144,6 @@ impl<E: PairingEngine + PairingLibrary, D: Digest> SolidityVerifier<E>
                 )
             })
             .replace("<%fs_init_seed_len%>", &(fs_seed.len() / 32).to_string())
-            .replace("<%fs_init_seed_overflow_len%>", &{
-                let seed_len_in_32_byte_words = fs_seed.len() / 32;
-                let seed_len_overflow_in_bytes = fs_seed.len() - (seed_len_in_32_byte_words * 32);
-                seed_len_overflow_in_bytes.to_string()
-            })
             .replace("<%fs_populate_init_seed%>", &{
                 let mut populate_init_seed = String::new();
                 for i in 0..fs_seed.len() / 32 {
@@ -163,10 +162,7 @@ impl<E: PairingEngine + PairingLibrary, D: Digest> SolidityVerifier<E>
             })
             .replace("<%fs_init_seed_overflow%>", &{
                 let seed_len_in_32_byte_words = fs_seed.len() / 32;
-                format!(
-                    "0x{}",
-                    hex::encode(&fs_seed[seed_len_in_32_byte_words * 32..])
-                )
+                hex::encode(&fs_seed[seed_len_in_32_byte_words * 32..])
             })
             .replace("<%h_domain_size%>", &{
                 let size = if vk.index_info.num_constraints.is_power_of_two() {
@@ -184,41 +180,117 @@ impl<E: PairingEngine + PairingLibrary, D: Digest> SolidityVerifier<E>
                 };
                 size.to_string()
             })
-            .replace("<%x_domain_size%>", &{
-                let x = vk.index_info.num_instance_variables;
-                let size = if x.is_power_of_two() {
-                    x as u64
-                } else {
-                    x.next_power_of_two() as u64
-                };
-                size.to_string()
-            })
-            .replace("<%pub_padded_size%>", &{
-                let x = vk.index_info.num_instance_variables;
-                let size = if x.is_power_of_two() {
-                    x as u64
-                } else {
-                    x.next_power_of_two() as u64
-                };
-                (size - 1).to_string()
-            })
-            .replace(
-                "<%num_instance_variables%>",
-                &vk.index_info.num_instance_variables.to_string(),
-            )
-            .replace(
-                "<%num_public_inputs%>",
-                &vk.index_info.num_instance_variables.to_string(),
-            )
+            .replace("<%x_domain_size%>", &x_domain_size.to_string())
+            // verifyTx は x-domain に合わせて 0 で埋めた公開入力を受け取る
+            .replace("<%pub_padded_size%>", &(x_domain_size - 1).to_string())
+            .replace("<%num_public_inputs%>", &(x_domain_size - 1).to_string())
             .replace("<%x_root%>", &format_bigint(x_root_of_unity.into_repr()))
-            .replace("<%f_mod%>", &format_modulus::<E::Fq>())
-            .replace("<%f_r%>", &format_modulus::<E::Fr>())
+            .replace("<%f_mod%>", &format_modulus::<E::Fr>())
             .replace("<%f_inv%>", &format_inv::<E::Fr>());
 
         format!("{}\n{}\n{}", TEMPLATE_PREFIX_TEXT, E::template(false), src)
     }
 }
 
+/// Number of public inputs `verifyTx` takes: the x-domain size minus the constant one.
+/// Shorter instances are padded with zeros, as `ark_marlin` does before hashing them.
+pub fn public_input_len<E: PairingEngine>(
+    vk: &IndexVerifierKey<E::Fr, MarlinKZG10<E, DensePolynomial<E::Fr>>>,
+) -> usize {
+    x_domain_size(vk) - 1
+}
+
+fn x_domain_size<E: PairingEngine>(
+    vk: &IndexVerifierKey<E::Fr, MarlinKZG10<E, DensePolynomial<E::Fr>>>,
+) -> usize {
+    vk.index_info.num_instance_variables.next_power_of_two()
+}
+
+/// ABI encoding of the `Proof` tuple of `verifyTx`, starting at the tuple's own offset.
+pub fn abi_encode_proof(proof: &Proof<Bn254>) -> Vec<u8> {
+    // head は 13 word。動的配列は tuple 先頭からの offset を置く
+    const HEAD_WORDS: usize = 13;
+    let arrays = [
+        g1_array_words(&proof.comms_1),
+        g1_array_words(&proof.comms_2),
+        g1_array_words(&proof.comms_3),
+        fr_array_words(&proof.evals),
+    ];
+    let mut offsets = Vec::with_capacity(arrays.len());
+    let mut offset = HEAD_WORDS * 32;
+    for array in &arrays {
+        offsets.push(usize_word(offset));
+        offset += array.len() * 32;
+    }
+
+    let mut words = vec![offsets[0], offsets[1]];
+    words.extend(g1_words(&proof.degree_bound_comms_2_g1));
+    words.push(offsets[2]);
+    words.extend(g1_words(&proof.degree_bound_comms_3_g2));
+    words.push(offsets[3]);
+    words.extend(g1_words(&proof.batch_lc_proof_1));
+    words.push(field_word(proof.batch_lc_proof_1_r));
+    words.extend(g1_words(&proof.batch_lc_proof_2));
+    words.extend(arrays.into_iter().flatten());
+    words.into_iter().flatten().collect()
+}
+
+/// Calldata of `verifyTx(proof, input)` for a verifier taking `input_len` public inputs
+/// (see [`public_input_len`]). `public_inputs` is padded with zeros to that length.
+pub fn verify_tx_calldata(proof: &Proof<Bn254>, public_inputs: &[Fr], input_len: usize) -> Vec<u8> {
+    assert!(
+        public_inputs.len() <= input_len,
+        "{} public inputs do not fit the verifier's {input_len}",
+        public_inputs.len()
+    );
+    let signature = format!(
+        "verifyTx(((uint256,uint256)[],(uint256,uint256)[],(uint256,uint256),(uint256,uint256)[],(uint256,uint256),uint256[],(uint256,uint256),uint256,(uint256,uint256)),uint256[{input_len}])"
+    );
+    let mut calldata = Keccak256::digest(signature.as_bytes())[..4].to_vec();
+    // Proof は動的な tuple なので head には offset、固定長の input はそのまま続く
+    calldata.extend(usize_word(32 * (1 + input_len)));
+    for i in 0..input_len {
+        let input = public_inputs.get(i).copied().unwrap_or_default();
+        calldata.extend(field_word(input));
+    }
+    calldata.extend(abi_encode_proof(proof));
+    calldata
+}
+
+fn usize_word(value: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
+
+fn field_word<F: PrimeField>(value: F) -> [u8; 32] {
+    let bytes = value.into_repr().to_bytes_be();
+    let mut word = [0u8; 32];
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    word
+}
+
+/// The point at infinity is encoded as `(0, 0)` like the precompiles expect.
+fn g1_words(g1: &G1Affine) -> Vec<[u8; 32]> {
+    if g1.infinity {
+        vec![[0u8; 32]; 2]
+    } else {
+        vec![field_word(g1.x), field_word(g1.y)]
+    }
+}
+
+fn g1_array_words(points: &[G1Affine]) -> Vec<[u8; 32]> {
+    let mut words = vec![usize_word(points.len())];
+    words.extend(points.iter().flat_map(g1_words));
+    words
+}
+
+fn fr_array_words(values: &[Fr]) -> Vec<[u8; 32]> {
+    let mut words = vec![usize_word(values.len())];
+    words.extend(values.iter().copied().map(field_word));
+    words
+}
+
 const CONTRACT_TEMPLATE: &str = r#"
 contract Verifier {
     using Pairing for *;
@@ -274,7 +346,7 @@ contract Verifier {
         {
             bytes32[<%fs_init_seed_len%>] memory init_seed;
             <%fs_populate_init_seed%>
-            bytes<%fs_init_seed_overflow_len%> init_seed_overflow = <%fs_init_seed_overflow%>;
+            bytes memory init_seed_overflow = hex"<%fs_init_seed_overflow%>";
             uint256[<%pub_padded_size%>] memory input_reverse;
             for (uint i = 0; i < input.length; i++) {
                 input_reverse[i] = be_to_le(input[i]);
@@ -543,15 +615,15 @@ contract Verifier {
         // swap 16-byte long pairs
         v = (v >> 128) | (v << 128);
     }
+    // ark_marlin の FiatShamirRng: ChaCha20 (nonce 0) を fs_seed で鍵付けし、u64 を順に取り出す。
+    // ctr は reseed 後に取り出した u64 の数
     function sample_field(bytes32 fs_seed, uint32 ctr) internal pure returns (uint256, uint32) {
-        // https://github.com/arkworks-rs/algebra/blob/master/ff/src/fields/models/fp/mod.rs#L561
+        // https://github.com/arkworks-rs/algebra/blob/v0.3.0/ff/src/fields/macros.rs
+        // Fp256::rand samples the Montgomery form; callers apply montgomery_reduction.
         while (true) {
-            uint256 v;
-            for (uint i = 0; i < 4; i++) {
-                v |= (uint256(keccak256(abi.encodePacked(fs_seed, ctr))) & uint256(0xFFFFFFFFFFFFFFFF)) << ((3-i) * 64);
-                ctr += 1;
-            }
-            v = be_to_le(v);
+            uint256[4] memory limbs = chacha_u64s(fs_seed, ctr, 4);
+            ctr += 4;
+            uint256 v = limbs[0] | (limbs[1] << 64) | (limbs[2] << 128) | (limbs[3] << 192);
             v &= (1 << 254) - 1;
             if (v < <%f_mod%>) {
                 return (v, ctr);
@@ -559,14 +631,63 @@ contract Verifier {
         }
     }
     function sample_field_128(bytes32 fs_seed, uint32 ctr) internal pure returns (uint256, uint32) {
-        // https://github.com/arkworks-rs/algebra/blob/master/ff/src/fields/models/fp/mod.rs#L561
-        uint256 v;
-        for (uint i = 0; i < 2; i++) {
-            v |= (uint256(keccak256(abi.encodePacked(fs_seed, ctr))) & uint256(0xFFFFFFFFFFFFFFFF)) << ((3-i) * 64);
-            ctr += 1;
+        // u128::rand takes the low word first
+        uint256[4] memory limbs = chacha_u64s(fs_seed, ctr, 2);
+        return (limbs[0] | (limbs[1] << 64), ctr + 2);
+    }
+    function chacha_u64s(bytes32 fs_seed, uint32 ctr, uint256 n) internal pure returns (uint256[4] memory out) {
+        // 1 block = 16 words = 8 u64
+        uint256[16] memory block = chacha_block(fs_seed, ctr / 8);
+        for (uint i = 0; i < n; i++) {
+            uint256 k = ctr + i;
+            if (i > 0 && k % 8 == 0) {
+                block = chacha_block(fs_seed, k / 8);
+            }
+            out[i] = block[(k % 8) * 2] | (block[(k % 8) * 2 + 1] << 32);
+        }
+    }
+    function chacha_block(bytes32 key, uint256 counter) internal pure returns (uint256[16] memory x) {
+        uint256[16] memory s;
+        s[0] = 0x61707865;
+        s[1] = 0x3320646e;
+        s[2] = 0x79622d32;
+        s[3] = 0x6b206574;
+        for (uint i = 0; i < 8; i++) {
+            // the key is read as little-endian u32 words
+            uint256 w = (uint256(key) >> (224 - i * 32)) & 0xFFFFFFFF;
+            s[4 + i] = ((w & 0xFF) << 24) | ((w & 0xFF00) << 8) | ((w >> 8) & 0xFF00) | (w >> 24);
         }
-        v = be_to_le(v);
-        return (v, ctr);
+        s[12] = counter & 0xFFFFFFFF;
+        s[13] = counter >> 32;
+        for (uint i = 0; i < 16; i++) {
+            x[i] = s[i];
+        }
+        for (uint i = 0; i < 10; i++) {
+            quarter_round(x, 0, 4, 8, 12);
+            quarter_round(x, 1, 5, 9, 13);
+            quarter_round(x, 2, 6, 10, 14);
+            quarter_round(x, 3, 7, 11, 15);
+            quarter_round(x, 0, 5, 10, 15);
+            quarter_round(x, 1, 6, 11, 12);
+            quarter_round(x, 2, 7, 8, 13);
+            quarter_round(x, 3, 4, 9, 14);
+        }
+        for (uint i = 0; i < 16; i++) {
+            x[i] = (x[i] + s[i]) & 0xFFFFFFFF;
+        }
+    }
+    function quarter_round(uint256[16] memory x, uint a, uint b, uint c, uint d) internal pure {
+        x[a] = (x[a] + x[b]) & 0xFFFFFFFF;
+        x[d] = rotl32(x[d] ^ x[a], 16);
+        x[c] = (x[c] + x[d]) & 0xFFFFFFFF;
+        x[b] = rotl32(x[b] ^ x[c], 12);
+        x[a] = (x[a] + x[b]) & 0xFFFFFFFF;
+        x[d] = rotl32(x[d] ^ x[a], 8);
+        x[c] = (x[c] + x[d]) & 0xFFFFFFFF;
+        x[b] = rotl32(x[b] ^ x[c], 7);
+    }
+    function rotl32(uint256 v, uint256 n) internal pure returns (uint256) {
+        return ((v << n) | (v >> (32 - n))) & 0xFFFFFFFF;
     }
     function montgomery_reduction(uint256 r) internal pure returns (uint256 v) {
         uint256[4] memory limbs;
@@ -580,7 +701,7 @@ contract Verifier {
             uint256 k = mulmod(limbs[i], <%f_inv%>, 1 << 64);
             uint256 carry = 0;
             carry = (limbs[i] + (k * mod_limbs[0]) + carry) >> 64;
-            for (uint j = 0; j < 4; j++) {
+            for (uint j = 1; j < 4; j++) {
                 uint256 tmp = limbs[(i + j) % 4] + (k * mod_limbs[j]) + carry;
                 limbs[(i + j) % 4] = tmp & uint256(0xFFFFFFFFFFFFFFFF);
                 carry = tmp >> 64;
@@ -1,9 +1,13 @@
 use std::marker::PhantomData;
+#[cfg(feature = "evm")]
+use std::path::PathBuf;
 
-use ark_bn254::{Bn254, Fr};
-use ark_ec::PairingEngine;
+use ark_bls12_381::{Bls12_381, Fr as BlsFr};
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G2Affine};
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_ff::{BigInteger, FpParameters, PrimeField, UniformRand};
 use ark_groth16::Groth16;
-use ark_marlin::Marlin;
+use ark_marlin::{ahp::AHPForR1CS, Marlin};
 use ark_poly::univariate::DensePolynomial;
 use ark_poly_commit::marlin_pc::MarlinKZG10;
 use ark_r1cs_std::{
@@ -13,9 +17,41 @@ use ark_r1cs_std::{
 use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
 use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
 use ark_std::{error::Error, test_rng};
-use blake2::Blake2s;
+use mpc_algebra_wasm::{digest_to_bytes, instance_hash, InstanceMode, PoseidonParameters};
+use sha3::Keccak256;
 
-use crate::SolidityVerifier;
+#[cfg(feature = "evm")]
+use crate::evm::{
+    check_groth16_verifier, compile, compile_runtime, solc, Evm, CONTRACT_CODE_SIZE_LIMIT,
+};
+use crate::{
+    batch::{batch_verify, export_transcript_verifier, BatchVerificationError, TranscriptEntry},
+    ceremony::{CeremonyCircuit, Phase2Parameters, PowersOfTau},
+    profiles::ProfileSpec,
+    schemes::{
+        groth16::{self, export_hashed_instance, export_with_layout, vk_hash, VerifierLayout},
+        marlin::marlin_pc::{self, verify_tx_calldata},
+    },
+    SolidityVerifier,
+};
+
+type MarlinInst = Marlin<Fr, MarlinKZG10<Bn254, DensePolynomial<Fr>>, Keccak256>;
+
+/// `solc` for an EVM check, or None to skip `check`. With `REQUIRE_SOLC` set (as in CI) a
+/// missing `solc` fails the test instead of skipping it.
+#[cfg(feature = "evm")]
+fn solc_or_skip(check: &str) -> Option<PathBuf> {
+    let solc = solc();
+    if solc.is_none() {
+        assert!(
+            std::env::var_os("REQUIRE_SOLC").is_none(),
+            "solc not found and REQUIRE_SOLC is set; cannot run {}",
+            check
+        );
+        eprintln!("solc not found; skipping {}", check);
+    }
+    solc
+}
 
 /// Simple circuit that enforces self.0 * self.1 == self.2 when self.0 and self.1 is private witness
 struct ExpCircuits<E: PairingEngine>(Option<u64>, Option<u64>, Option<u64>, PhantomData<E>);
@@ -47,6 +83,88 @@ impl<E: PairingEngine> ConstraintSynthesizer<E::Fr> for ExpCircuits<E> {
     }
 }
 
+/// Exposes `self.0` public inputs. A Groth16 verifier's size only depends on this count.
+struct PublicInputsCircuit(usize);
+
+impl ConstraintSynthesizer<Fr> for PublicInputsCircuit {
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<Fr>,
+    ) -> ark_relations::r1cs::Result<()> {
+        for _ in 0..self.0 {
+            let input = FpVar::new_input(cs.clone(), || Ok(Fr::from(0u64)))?;
+            input.enforce_equal(&input)?;
+        }
+        Ok(())
+    }
+}
+
+/// Enforces prev + step == next, exposing (prev, next) as the last two public inputs
+/// like the state chain of the werewolf circuits. `scale` only changes the verifying key.
+struct LinkCircuit<E: PairingEngine>(Option<u64>, Option<u64>, Option<u64>, u64, PhantomData<E>);
+
+impl<E: PairingEngine> ConstraintSynthesizer<E::Fr> for LinkCircuit<E> {
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<E::Fr>,
+    ) -> ark_relations::r1cs::Result<()> {
+        let step = FpVar::new_witness(cs.clone(), || {
+            self.1
+                .map(E::Fr::from)
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let prev = FpVar::new_input(cs.clone(), || {
+            self.0
+                .map(E::Fr::from)
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let next = FpVar::new_input(cs.clone(), || {
+            self.2
+                .map(E::Fr::from)
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        (&prev + &step * E::Fr::from(self.3)).enforce_equal(&next)?;
+
+        Ok(())
+    }
+}
+
+/// [`LinkCircuit`] behind `alive_mask` and `day_count` inputs, so that its public inputs end
+/// with the four state chain inputs of the werewolf circuits.
+struct StateChainCircuit(LinkCircuit<Bn254>);
+
+impl ConstraintSynthesizer<Fr> for StateChainCircuit {
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<Fr>,
+    ) -> ark_relations::r1cs::Result<()> {
+        for _ in 0..2 {
+            let input = FpVar::new_input(cs.clone(), || Ok(Fr::from(0u64)))?;
+            input.enforce_equal(&input)?;
+        }
+        self.0.generate_constraints(cs)
+    }
+}
+
+/// Exposes a single public input like a circuit in `InstanceMode::Hashed`. Binding it to the
+/// instance is the real circuit's job; here any value can be proven.
+struct HashedInstanceCircuit(Option<Fr>);
+
+impl ConstraintSynthesizer<Fr> for HashedInstanceCircuit {
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<Fr>,
+    ) -> ark_relations::r1cs::Result<()> {
+        let hash = FpVar::new_input(cs.clone(), || {
+            self.0.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let witness = FpVar::new_witness(cs, || self.0.ok_or(SynthesisError::AssignmentMissing))?;
+        hash.enforce_equal(&witness)?;
+        Ok(())
+    }
+}
+
 #[test]
 fn circuit_works() -> Result<(), Box<dyn Error>> {
     let mut rng = test_rng();
@@ -71,8 +189,6 @@ fn circuit_works() -> Result<(), Box<dyn Error>> {
 
 #[test]
 fn export_works() -> Result<(), Box<dyn Error>> {
-    type MarlinInst = Marlin<Fr, MarlinKZG10<Bn254, DensePolynomial<Fr>>, Blake2s>;
-
     let rng = &mut test_rng();
 
     let (_, vk) =
@@ -86,3 +202,436 @@ fn export_works() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+#[test]
+fn bls12_381_groth16_export_works() -> Result<(), Box<dyn Error>> {
+    let rng = &mut test_rng();
+
+    let (pk, vk) =
+        Groth16::<Bls12_381>::setup(ExpCircuits::<Bls12_381>(None, None, None, PhantomData), rng)?;
+    let proof = Groth16::<Bls12_381>::prove(
+        &pk,
+        ExpCircuits::<Bls12_381>(Some(5), Some(10), Some(50), PhantomData),
+        rng,
+    )?;
+    let inputs = [BlsFr::from(50u64)];
+    assert!(Groth16::<Bls12_381>::verify(&vk, &inputs, &proof)?);
+
+    let source = Groth16::export(&vk);
+    assert!(!source.contains("<%"));
+    // EIP-2537 の G1ADD / G1MSM / PAIRING_CHECK を呼び、BN254 の precompile は使わない
+    for address in [0x0b, 0x0c, 0x0f] {
+        assert!(source.contains(&format!("staticcall(sub(gas(), 2000), {address},")));
+    }
+    assert!(!source.contains("staticcall(sub(gas(), 2000), 8,"));
+
+    // a, c は 4 word、b は 8 word。Fp の上位 16 byte は 0 で埋まる
+    let calldata = groth16::verify_tx_calldata(&proof, &inputs);
+    assert_eq!(calldata.len(), 4 + 16 * 32 + 32);
+    assert!(calldata[4..4 + 16].iter().all(|b| *b == 0));
+    assert_eq!(calldata[4 + 16 * 32..], digest_to_bytes(BlsFr::from(50u64)));
+
+    // EIP-2537 の precompile は Prague の revm で動かす
+    #[cfg(feature = "evm")]
+    if let Some(solc) = solc_or_skip("the EVM check of the BLS12-381 verifier") {
+        check_groth16_verifier(&solc, &source, "Verifier", &proof, &inputs)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "evm")]
+#[test]
+fn marlin_verifier_runs_in_evm() -> Result<(), Box<dyn Error>> {
+    let Some(solc) = solc_or_skip("the EVM check of the Marlin verifier") else {
+        return Ok(());
+    };
+    let rng = &mut test_rng();
+
+    let srs = MarlinInst::universal_setup(5, 3, 3, rng).unwrap();
+    let (pk, vk) =
+        MarlinInst::index(&srs, ExpCircuits::<Bn254>(None, None, None, PhantomData)).unwrap();
+    let proof = MarlinInst::prove(
+        &pk,
+        ExpCircuits::<Bn254>(Some(5), Some(10), Some(50), PhantomData),
+        rng,
+    )
+    .unwrap();
+    assert!(MarlinInst::verify(&vk, &[Fr::from(50u64)], &proof, rng).unwrap());
+
+    let source = MarlinInst::export(&vk);
+    assert!(source.contains("uint256[1] memory input"));
+    let mut evm = Evm::without_code_size_limit();
+    let verifier = evm.deploy(compile(&solc, &source, "Verifier")?)?;
+
+    let input_len = marlin_pc::public_input_len(&vk);
+    let proof = marlin_pc::Proof::from(proof);
+    let (accepted, gas_used) = evm.call_bool(
+        verifier,
+        verify_tx_calldata(&proof, &[Fr::from(50u64)], input_len),
+    )?;
+    assert!(accepted, "valid Marlin proof rejected ({gas_used} gas)");
+
+    let (accepted, _) = evm.call_bool(
+        verifier,
+        verify_tx_calldata(&proof, &[Fr::from(51u64)], input_len),
+    )?;
+    assert!(!accepted);
+
+    let mut tampered = proof;
+    tampered.evals[0] += Fr::from(1u64);
+    let (accepted, _) = evm.call_bool(
+        verifier,
+        verify_tx_calldata(&tampered, &[Fr::from(50u64)], input_len),
+    )?;
+    assert!(!accepted);
+
+    Ok(())
+}
+
+#[test]
+fn marlin_srs_from_powers_of_tau_works() -> Result<(), Box<dyn Error>> {
+    let rng = &mut test_rng();
+
+    let max_degree = AHPForR1CS::<Fr>::max_degree(5, 3, 3).unwrap();
+    let powers = PowersOfTau::from_secrets(
+        PowersOfTau::marlin_degree(max_degree),
+        Fr::rand(rng),
+        Fr::rand(rng),
+        Fr::rand(rng),
+    );
+    powers.check_consistency(rng)?;
+    let srs = powers.marlin_universal_params(max_degree)?;
+    assert!(powers.marlin_universal_params(max_degree + 1).is_err());
+
+    let (pk, vk) =
+        MarlinInst::index(&srs, ExpCircuits::<Bn254>(None, None, None, PhantomData)).unwrap();
+    let proof = MarlinInst::prove(
+        &pk,
+        ExpCircuits::<Bn254>(Some(5), Some(10), Some(50), PhantomData),
+        rng,
+    )
+    .unwrap();
+    assert!(MarlinInst::verify(&vk, &[Fr::from(50u64)], &proof, rng).unwrap());
+    assert!(!MarlinInst::verify(&vk, &[Fr::from(51u64)], &proof, rng).unwrap());
+
+    Ok(())
+}
+
+#[cfg(feature = "evm")]
+#[test]
+#[ignore = "indexes a full werewolf circuit"]
+fn marlin_export_for_profile_deploys() -> Result<(), Box<dyn Error>> {
+    let Some(solc) = solc_or_skip("the EVM check of the Marlin verifier") else {
+        return Ok(());
+    };
+    let rng = &mut test_rng();
+    let spec = ProfileSpec::Divination(4);
+
+    let index =
+        ark_marlin::ahp::AHPForR1CS::index(spec.build(InstanceMode::Full, rng)?.circuit).unwrap();
+    let info = index.index_info;
+    let srs = MarlinInst::universal_setup(
+        info.num_constraints,
+        info.num_variables,
+        info.num_non_zero,
+        rng,
+    )
+    .unwrap();
+    let (_, vk) = MarlinInst::index(&srs, spec.build(InstanceMode::Full, rng)?.circuit).unwrap();
+
+    // 定数 1 を含めて 2 冪に揃えた x-domain から 1 を引いた長さ
+    let input_len = marlin_pc::public_input_len(&vk);
+    assert_eq!(input_len, (spec.instance_len() + 1).next_power_of_two() - 1);
+
+    let source = MarlinInst::export(&vk);
+    let mut evm = Evm::without_code_size_limit();
+    evm.deploy(compile(&solc, &source, "Verifier")?)?;
+
+    Ok(())
+}
+
+#[cfg(feature = "evm")]
+#[test]
+fn groth16_layouts_run_in_evm() -> Result<(), Box<dyn Error>> {
+    let Some(solc) = solc_or_skip("the EVM check of the Groth16 verifiers") else {
+        return Ok(());
+    };
+    let rng = &mut test_rng();
+
+    let (pk, vk) =
+        Groth16::<Bn254>::setup(ExpCircuits::<Bn254>(None, None, None, PhantomData), rng)?;
+    let proof = Groth16::<Bn254>::prove(
+        &pk,
+        ExpCircuits::<Bn254>(Some(5), Some(10), Some(50), PhantomData),
+        rng,
+    )?;
+
+    for source in [
+        Groth16::export(&vk),
+        export_with_layout(&vk, VerifierLayout::Compact).contract,
+    ] {
+        check_groth16_verifier(&solc, &source, "Verifier", &proof, &[Fr::from(50u64)])?;
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "evm")]
+#[test]
+fn compact_verifier_of_largest_role_assignment_fits_size_limit() -> Result<(), Box<dyn Error>> {
+    let Some(solc) = solc_or_skip("the size check of the compact verifier") else {
+        return Ok(());
+    };
+    let largest = ProfileSpec::all()
+        .into_iter()
+        .filter(|spec| matches!(spec, ProfileSpec::RoleAssignment { .. }))
+        .max_by_key(|spec| spec.instance_len())
+        .unwrap();
+
+    let (_, vk) =
+        Groth16::<Bn254>::setup(PublicInputsCircuit(largest.instance_len()), &mut test_rng())?;
+    let source = export_with_layout(&vk, VerifierLayout::Compact).contract;
+    let runtime = compile_runtime(&solc, &source, "Verifier")?;
+    assert!(
+        runtime.len() <= CONTRACT_CODE_SIZE_LIMIT,
+        "{} compact verifier is {} bytes",
+        largest.circuit_id(),
+        runtime.len()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn compact_export_works() -> Result<(), Box<dyn Error>> {
+    let rng = &mut test_rng();
+
+    let (_, vk) =
+        Groth16::<Bn254>::setup(ExpCircuits::<Bn254>(None, None, None, PhantomData), rng)?;
+    let standard = Groth16::export(&vk);
+    let compact = export_with_layout(&vk, VerifierLayout::Compact);
+    let split = export_with_layout(&vk, VerifierLayout::Split);
+
+    for source in [&compact.contract, &split.contract] {
+        assert!(!source.contains("<%"));
+        assert!(
+            source.contains("function verifyTx(Proof calldata proof, uint256[1] calldata input)")
+        );
+        assert!(source.contains(&hex::encode(vk_hash(&vk))));
+    }
+    assert!(compact.shared_verifier.is_none());
+    assert!(split
+        .shared_verifier
+        .unwrap()
+        .contains("contract Groth16SharedVerifier"));
+    // gamma_abc は 1 つの bytes 定数にまとめられる
+    assert!(compact.contract.len() < standard.len());
+
+    Ok(())
+}
+
+#[test]
+fn hashed_instance_export_works() -> Result<(), Box<dyn Error>> {
+    let rng = &mut test_rng();
+
+    let (_, vk) =
+        Groth16::<Bn254>::setup(ExpCircuits::<Bn254>(None, None, None, PhantomData), rng)?;
+    let hashed = export_hashed_instance(&vk, 38);
+    let compact = export_with_layout(&vk, VerifierLayout::Compact).contract;
+
+    assert!(!hashed.contains("<%"));
+    assert!(hashed.contains("function verifyTx(Proof calldata proof, uint256[38] calldata input)"));
+    assert!(hashed.contains("uint256 instanceHash = hashInstance(input);"));
+    // Poseidon の定数は PoseidonParameters と同じ
+    let params = PoseidonParameters::<Fr>::new();
+    let first_constant = hex::encode(digest_to_bytes(params.round_constants[0]));
+    assert!(hashed.contains(&first_constant));
+    assert!(!compact.contains("hashInstance"));
+
+    Ok(())
+}
+
+#[test]
+fn transcript_batch_verification_works() -> Result<(), Box<dyn Error>> {
+    let rng = &mut test_rng();
+
+    let link = |prev, step, next, scale| {
+        StateChainCircuit(LinkCircuit::<Bn254>(prev, step, next, scale, PhantomData))
+    };
+    let (pk_a, vk_a) = Groth16::<Bn254>::setup(link(None, None, None, 1), rng)?;
+    let (pk_b, vk_b) = Groth16::<Bn254>::setup(link(None, None, None, 2), rng)?;
+    let (pk_h, vk_h) = Groth16::<Bn254>::setup(HashedInstanceCircuit(None), rng)?;
+    let vks = vec![vk_a, vk_b, vk_h];
+
+    let mut entries = Vec::new();
+    for (vk_index, prev, step, next) in [(0, 0, 3, 3), (1, 3, 2, 7), (0, 7, 1, 8)] {
+        let (pk, scale) = if vk_index == 0 {
+            (&pk_a, 1)
+        } else {
+            (&pk_b, 2)
+        };
+        let proof = Groth16::prove(pk, link(Some(prev), Some(step), Some(next), scale), rng)?;
+        entries.push(TranscriptEntry {
+            vk_index,
+            public_inputs: vec![
+                Fr::from(0u64),
+                Fr::from(0u64),
+                Fr::from(prev),
+                Fr::from(next),
+            ],
+            proof,
+        });
+    }
+
+    // hashed instance の鍵でも state chain は instance の末尾から読む
+    let instance = vec![
+        Fr::from(5u64),
+        Fr::from(1u64),
+        Fr::from(8u64),
+        Fr::from(11u64),
+    ];
+    let hash = instance_hash(&PoseidonParameters::new(), &instance);
+    entries.push(TranscriptEntry {
+        vk_index: 2,
+        public_inputs: instance,
+        proof: Groth16::prove(&pk_h, HashedInstanceCircuit(Some(hash)), rng)?,
+    });
+
+    let batched = batch_verify(&vks, &entries)?;
+    assert_eq!(batched.vk_index, vec![0, 1, 0, 2]);
+    assert_eq!(batched.inputs.len(), 4);
+    assert_eq!(batched.inputs[3].len(), 4);
+
+    // 別の鍵で作った証明を入れ替えると検証に失敗する
+    let mut swapped = entries.clone();
+    swapped[1].vk_index = 0;
+    assert_eq!(
+        batch_verify(&vks, &swapped).unwrap_err(),
+        BatchVerificationError::InvalidProof
+    );
+
+    let mut tampered = entries.clone();
+    tampered[3].public_inputs[0] = Fr::from(4u64);
+    assert_eq!(
+        batch_verify(&vks, &tampered).unwrap_err(),
+        BatchVerificationError::InvalidProof
+    );
+
+    let mut broken = entries.clone();
+    broken.remove(1);
+    assert_eq!(
+        batch_verify(&vks, &broken).unwrap_err(),
+        BatchVerificationError::StateChainBroken { index: 1 }
+    );
+
+    let sol_verifier = export_transcript_verifier(&vks);
+    assert!(sol_verifier.contains("function verifyTranscript"));
+    assert!(sol_verifier.contains("function hashInstance"));
+    assert!(!sol_verifier.contains("<%"));
+    assert!(!export_transcript_verifier(&vks[..2]).contains("hashInstance"));
+
+    #[cfg(feature = "evm")]
+    if let Some(solc) = solc_or_skip("the transcript verifier compilation") {
+        compile(&solc, &sol_verifier, "TranscriptVerifier")?;
+    }
+
+    Ok(())
+}
+
+/// snarkjs の ptau 形式 (little-endian Montgomery) で書き出す
+fn write_ptau(powers: &PowersOfTau) -> Vec<u8> {
+    fn fq(out: &mut Vec<u8>, value: Fq) {
+        for limb in (value.0).0 {
+            out.extend_from_slice(&limb.to_le_bytes());
+        }
+    }
+    fn g1(out: &mut Vec<u8>, point: &G1Affine) {
+        fq(out, point.x);
+        fq(out, point.y);
+    }
+    fn g2(out: &mut Vec<u8>, point: &G2Affine) {
+        for value in [point.x.c0, point.x.c1, point.y.c0, point.y.c1] {
+            fq(out, value);
+        }
+    }
+
+    let power = powers.degree().trailing_zeros();
+    let mut header = 32u32.to_le_bytes().to_vec();
+    header.extend(<<Fq as PrimeField>::Params as FpParameters>::MODULUS.to_bytes_le());
+    header.extend(power.to_le_bytes());
+    header.extend(power.to_le_bytes());
+
+    let mut sections = vec![(1u32, header)];
+    let mut section = Vec::new();
+    powers.tau_g1.iter().for_each(|p| g1(&mut section, p));
+    sections.push((2, std::mem::take(&mut section)));
+    powers.tau_g2.iter().for_each(|p| g2(&mut section, p));
+    sections.push((3, std::mem::take(&mut section)));
+    powers.alpha_tau_g1.iter().for_each(|p| g1(&mut section, p));
+    sections.push((4, std::mem::take(&mut section)));
+    powers.beta_tau_g1.iter().for_each(|p| g1(&mut section, p));
+    sections.push((5, std::mem::take(&mut section)));
+    g2(&mut section, &powers.beta_g2);
+    sections.push((6, section));
+
+    let mut out = b"ptau".to_vec();
+    out.extend(1u32.to_le_bytes());
+    out.extend((sections.len() as u32).to_le_bytes());
+    for (section_type, body) in sections {
+        out.extend(section_type.to_le_bytes());
+        out.extend((body.len() as u64).to_le_bytes());
+        out.extend(body);
+    }
+    out
+}
+
+#[test]
+fn ceremony_works() -> Result<(), Box<dyn Error>> {
+    let rng = &mut test_rng();
+
+    let circuit =
+        CeremonyCircuit::synthesize(LinkCircuit::<Bn254>(None, None, None, 1, PhantomData))?;
+    let powers = PowersOfTau::from_secrets(
+        circuit.domain_size(),
+        Fr::rand(rng),
+        Fr::rand(rng),
+        Fr::rand(rng),
+    );
+    powers.check_consistency(rng)?;
+    let ptau = write_ptau(&powers);
+    assert_eq!(
+        PowersOfTau::read_ptau(std::io::Cursor::new(ptau), circuit.domain_size())?,
+        powers
+    );
+
+    let initial = Phase2Parameters::new(&circuit, &powers)?;
+    let mut params = initial.clone();
+    params.contribute(rng)?;
+    let last_hash = params.contribute(rng)?;
+    let params = Phase2Parameters::from_bytes(&params.to_bytes()?)?;
+    assert_eq!(params.verify(&initial, rng)?.last(), Some(&last_hash));
+
+    let proof = Groth16::prove(
+        &params.pk,
+        LinkCircuit::<Bn254>(Some(2), Some(3), Some(5), 1, PhantomData),
+        rng,
+    )?;
+    assert!(Groth16::verify(
+        &params.pk.vk,
+        &[Fr::from(2u64), Fr::from(5u64)],
+        &proof
+    )?);
+    assert!(!Groth16::verify(
+        &params.pk.vk,
+        &[Fr::from(2u64), Fr::from(6u64)],
+        &proof
+    )?);
+
+    // delta と無関係に l_query を書き換えると検証に失敗する
+    let mut tampered = params.clone();
+    tampered.pk.l_query[0] = G1Affine::prime_subgroup_generator();
+    assert!(tampered.verify(&initial, rng).is_err());
+
+    Ok(())
+}
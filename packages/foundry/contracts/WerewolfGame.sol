@@ -37,7 +37,8 @@ contract WerewolfGame is Ownable {
     enum GameResult {
         InProgress,
         VillagerWin,
-        WerewolfWin
+        WerewolfWin,
+        FoxWin
     }
 
     struct GameState {
@@ -5,6 +5,7 @@ import "openzeppelin-contracts/contracts/access/Ownable.sol";
 
 interface IVerifierAdapter {
     function verify(bytes calldata proof, bytes calldata publicInputs) external view returns (bool);
+    function vkHash() external view returns (bytes32);
 }
 
 contract WerewolfProofVerifier is Ownable {
@@ -27,12 +28,20 @@ contract WerewolfProofVerifier is Ownable {
     mapping(bytes32 => ProofRecord) public proofs;
     mapping(bytes32 => mapping(ProofType => bool)) private verifiedByType;
     mapping(bytes32 => address) public verifierAdapterByCircuit;
+    // 各ゲームで最後に検証された proof の next state digest (public inputs の最終ワード)
+    mapping(bytes32 => bytes32) public latestStateDigest;
+    // Groth16 manifest に記載された verifying key の SHA-256。設定済みの回路には一致する adapter しか登録できない
+    mapping(bytes32 => bytes32) public expectedVkHashByCircuit;
+    // 鍵一式を署名した manifest のハッシュ (オフチェーンで ProofOutput の manifest_hash と突き合わせる)
+    bytes32 public manifestHash;
 
     address public gameContract;
 
     event ProofVerified(bytes32 indexed proofId, bytes32 indexed gameId, ProofType proofType, uint256 timestamp);
     event ProofFailed(bytes32 indexed proofId, bytes32 indexed gameId, ProofType proofType, string reason);
     event VerifierAdapterSet(bytes32 indexed circuitKey, ProofType proofType, uint8 playerCount, uint8 werewolfCount, address adapter);
+    event ExpectedVkHashSet(bytes32 indexed circuitKey, bytes32 vkHash);
+    event ManifestHashSet(bytes32 manifestHash);
 
     modifier onlyGameOrOwner() {
         require(msg.sender == gameContract || msg.sender == owner(), "Not authorized");
@@ -55,11 +64,34 @@ contract WerewolfProofVerifier is Ownable {
         require(isSupportedProfile(proofType, playerCount, werewolfCount), "Unsupported proof profile");
 
         bytes32 key = buildCircuitKey(proofType, playerCount, werewolfCount);
+        bytes32 expectedVkHash = expectedVkHashByCircuit[key];
+        if (expectedVkHash != bytes32(0)) {
+            require(IVerifierAdapter(adapter).vkHash() == expectedVkHash, "Verifying key hash mismatch");
+        }
         verifierAdapterByCircuit[key] = adapter;
 
         emit VerifierAdapterSet(key, proofType, playerCount, werewolfCount, adapter);
     }
 
+    /// @notice Pins the verifying key hash of a circuit. The current adapter, if any, must already match.
+    function setExpectedVkHash(ProofType proofType, uint8 playerCount, uint8 werewolfCount, bytes32 vkHash) external onlyOwner {
+        require(isSupportedProfile(proofType, playerCount, werewolfCount), "Unsupported proof profile");
+
+        bytes32 key = buildCircuitKey(proofType, playerCount, werewolfCount);
+        address adapter = verifierAdapterByCircuit[key];
+        if (adapter != address(0) && vkHash != bytes32(0)) {
+            require(IVerifierAdapter(adapter).vkHash() == vkHash, "Verifying key hash mismatch");
+        }
+        expectedVkHashByCircuit[key] = vkHash;
+
+        emit ExpectedVkHashSet(key, vkHash);
+    }
+
+    function setManifestHash(bytes32 _manifestHash) external onlyOwner {
+        manifestHash = _manifestHash;
+        emit ManifestHashSet(_manifestHash);
+    }
+
     function getVerifierAdapter(ProofType proofType, uint8 playerCount, uint8 werewolfCount) external view returns (address) {
         bytes32 key = buildCircuitKey(proofType, playerCount, werewolfCount);
         return verifierAdapterByCircuit[key];
@@ -84,6 +116,17 @@ contract WerewolfProofVerifier is Ownable {
             return false;
         }
 
+        // public inputs の末尾 4 ワードは alive mask, day count, prev / next state digest
+        if (publicInputs.length < 64) {
+            emit ProofFailed(proofId, gameId, proofType, "Missing state digest");
+            return false;
+        }
+        bytes32 prevStateDigest = bytes32(publicInputs[publicInputs.length - 64:publicInputs.length - 32]);
+        if (prevStateDigest != latestStateDigest[gameId]) {
+            emit ProofFailed(proofId, gameId, proofType, "State digest mismatch");
+            return false;
+        }
+
         address adapter = verifierAdapterByCircuit[buildCircuitKey(proofType, playerCount, werewolfCount)];
 
         if (adapter == address(0)) {
@@ -115,6 +158,7 @@ contract WerewolfProofVerifier is Ownable {
             timestamp: block.timestamp
         });
         verifiedByType[gameId][proofType] = true;
+        latestStateDigest[gameId] = bytes32(publicInputs[publicInputs.length - 32:]);
 
         emit ProofVerified(proofId, gameId, proofType, block.timestamp);
         return true;
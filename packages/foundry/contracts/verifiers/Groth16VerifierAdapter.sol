@@ -32,4 +32,15 @@ contract Groth16VerifierAdapter {
 
         return abi.decode(result, (bool));
     }
+
+    /// @notice SHA-256 of the verifying key, read from the generated verifier's `VK_HASH`.
+    /// @dev Returns zero for verifiers generated before `VK_HASH` was exported.
+    function vkHash() external view returns (bytes32) {
+        (bool ok, bytes memory result) = verifier.staticcall(abi.encodeWithSignature("VK_HASH()"));
+        if (!ok || result.length < 32) {
+            return bytes32(0);
+        }
+
+        return abi.decode(result, (bytes32));
+    }
 }
@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.19;
+
+/// @notice Adapter for Marlin verifier contracts generated by arkworks-solidity-verifier.
+/// @dev `verifyTx(Proof, uint256[N])` takes the instance zero-padded to the x-domain, and the
+/// proof is a dynamic tuple. The nodes send the ABI-encoded tuple body as `proofBytes`.
+contract MarlinVerifierAdapter {
+    address public immutable verifier;
+    bytes4 public immutable verifySelector;
+    // verifyTx の uint256[N] の N (x-domain サイズ - 1)
+    uint256 public immutable publicInputWordLength;
+
+    constructor(address verifierAddress, bytes4 selector, uint256 inputWordLength) {
+        require(verifierAddress != address(0), "Invalid verifier");
+        verifier = verifierAddress;
+        verifySelector = selector;
+        publicInputWordLength = inputWordLength;
+    }
+
+    function verify(bytes calldata proofBytes, bytes calldata publicInputBytes) external view returns (bool) {
+        if (proofBytes.length == 0 || proofBytes.length % 32 != 0) {
+            return false;
+        }
+        if (publicInputBytes.length % 32 != 0 || publicInputBytes.length > publicInputWordLength * 32) {
+            return false;
+        }
+
+        // head: proof の offset と固定長の公開入力。足りない分は 0 で埋める
+        bytes memory padding = new bytes(publicInputWordLength * 32 - publicInputBytes.length);
+        bytes memory callData = bytes.concat(
+            verifySelector,
+            bytes32(32 * (1 + publicInputWordLength)),
+            publicInputBytes,
+            padding,
+            proofBytes
+        );
+        (bool ok, bytes memory result) = verifier.staticcall(callData);
+        if (!ok || result.length < 32) {
+            return false;
+        }
+
+        return abi.decode(result, (bool));
+    }
+
+    /// @notice Marlin verifiers do not export a Groth16 key hash.
+    function vkHash() external pure returns (bytes32) {
+        return bytes32(0);
+    }
+}
@@ -11,6 +11,9 @@ import "openzeppelin-contracts/contracts/utils/Strings.sol";
 contract DeployWerewolf is ScaffoldETHDeploy {
     using Strings for uint256;
 
+    // GROTH16_VERIFIER_LAYOUT=split で生成した verifier は共有 verifier のアドレスを constructor で受け取る
+    address internal sharedGroth16Verifier;
+
     function run() external ScaffoldEthDeployerRunner {
         WerewolfGame game = new WerewolfGame();
         WerewolfProofVerifier verifier = new WerewolfProofVerifier();
@@ -35,17 +38,12 @@ contract DeployWerewolf is ScaffoldETHDeploy {
         _deployAndRegisterRoleAssignment(verifier, 5, 2);
         _deployAndRegisterRoleAssignment(verifier, 6, 1);
         _deployAndRegisterRoleAssignment(verifier, 6, 2);
-        // Temporarily disabled: exceeds EVM max contract size (24,576 bytes) on Sepolia.
-        // _deployAndRegisterRoleAssignment(verifier, 7, 1);
-        _deployAndRegisterRoleAssignment(verifier, 7, 2);
-        _deployAndRegisterRoleAssignment(verifier, 7, 3);
-        // Temporarily disabled: exceeds EVM max contract size (24,576 bytes) on Sepolia.
-        // _deployAndRegisterRoleAssignment(verifier, 8, 1);
-        // _deployAndRegisterRoleAssignment(verifier, 8, 2);
-        // _deployAndRegisterRoleAssignment(verifier, 8, 3);
-        // _deployAndRegisterRoleAssignment(verifier, 9, 1);
-        // _deployAndRegisterRoleAssignment(verifier, 9, 2);
-        // _deployAndRegisterRoleAssignment(verifier, 9, 3);
+        // 7-9 人の verifier は compact / split レイアウトで生成したときだけ 24,576 byte に収まる
+        for (uint8 n = 7; n <= 9; n++) {
+            for (uint8 w = 1; w <= 3; w++) {
+                _deployAndRegisterRoleAssignment(verifier, n, w);
+            }
+        }
     }
 
     function _registerPlayerCountProfiles(WerewolfProofVerifier verifier) internal {
@@ -56,7 +54,7 @@ contract DeployWerewolf is ScaffoldETHDeploy {
                 n,
                 0,
                 _divinationContractName(n),
-                8
+                11
             );
             _deployAndRegister(
                 verifier,
@@ -64,7 +62,7 @@ contract DeployWerewolf is ScaffoldETHDeploy {
                 n,
                 0,
                 _anonymousVotingContractName(n),
-                1
+                4
             );
         }
 
@@ -75,7 +73,7 @@ contract DeployWerewolf is ScaffoldETHDeploy {
                 n,
                 0,
                 _winningJudgementContractName(n),
-                2
+                9
             );
         }
 
@@ -86,7 +84,7 @@ contract DeployWerewolf is ScaffoldETHDeploy {
                 n,
                 0,
                 _keyPublicizeContractName(n),
-                0
+                _keyPublicizePublicInputWordLength(n)
             );
         }
     }
@@ -100,7 +98,7 @@ contract DeployWerewolf is ScaffoldETHDeploy {
             playerCount,
             werewolfCount,
             _roleAssignmentContractName(playerCount, werewolfCount),
-            _roleAssignmentPublicInputWordLength(playerCount, werewolfCount)
+            _roleAssignmentPublicInputWordLength(playerCount)
         );
     }
 
@@ -131,6 +129,9 @@ contract DeployWerewolf is ScaffoldETHDeploy {
             string.concat("contracts/verifiers/generated/", contractName, ".sol:", contractName);
         bytes memory creationCode = vm.getCode(artifactFqn);
         require(creationCode.length != 0, string.concat("Missing generated verifier artifact: ", artifactFqn));
+        if (vm.envOr("GROTH16_SPLIT_VERIFIER", false)) {
+            creationCode = bytes.concat(creationCode, abi.encode(_sharedGroth16Verifier()));
+        }
 
         assembly {
             deployed := create(0, add(creationCode, 0x20), mload(creationCode))
@@ -140,6 +141,21 @@ contract DeployWerewolf is ScaffoldETHDeploy {
         deployments.push(Deployment({ name: contractName, addr: deployed }));
     }
 
+    function _sharedGroth16Verifier() internal returns (address) {
+        if (sharedGroth16Verifier == address(0)) {
+            bytes memory creationCode =
+                vm.getCode("contracts/verifiers/generated/Groth16SharedVerifier.sol:Groth16SharedVerifier");
+            address deployed;
+            assembly {
+                deployed := create(0, add(creationCode, 0x20), mload(creationCode))
+            }
+            require(deployed != address(0), "Failed to deploy Groth16SharedVerifier");
+            sharedGroth16Verifier = deployed;
+            deployments.push(Deployment({ name: "Groth16SharedVerifier", addr: sharedGroth16Verifier }));
+        }
+        return sharedGroth16Verifier;
+    }
+
     function _verifyTxSelector(uint256 publicInputWordLength) internal pure returns (bytes4) {
         string memory proofTupleType = "((uint256,uint256),(uint256[2],uint256[2]),(uint256,uint256))";
         if (publicInputWordLength == 0) {
@@ -160,13 +176,16 @@ contract DeployWerewolf is ScaffoldETHDeploy {
         );
     }
 
-    function _roleAssignmentPublicInputWordLength(uint8 playerCount, uint8 werewolfCount) internal pure returns (uint256) {
-        uint256 n = uint256(playerCount);
-        uint256 w = uint256(werewolfCount);
-        // grouping_parameter(Seer=1, Werewolf=w, Villager=n-w-1) のとき:
-        // num_groups = n - w + 1, matrix_size = n + num_groups = 2n - w + 1
-        uint256 matrixSize = 2 * n - w + 1;
-        return matrixSize * matrixSize;
+    function _roleAssignmentPublicInputWordLength(uint8 playerCount) internal pure returns (uint256) {
+        // 各プレイヤーの player_commitment (x, y) + role_commitment (x, y)
+        // tau 行列は回路に定数として埋め込まれるため公開入力に含まれない
+        // 末尾に prev / next state digest が続く
+        return 4 * uint256(playerCount) + 2;
+    }
+
+    function _keyPublicizePublicInputWordLength(uint8 playerCount) internal pure returns (uint256) {
+        // 占い師の公開鍵 (x, y) + 各プレイヤーの役職コミットメント (x, y) + prev / next state digest
+        return 2 + 2 * uint256(playerCount) + 2;
     }
 
     function _roleAssignmentContractName(uint8 playerCount, uint8 werewolfCount)
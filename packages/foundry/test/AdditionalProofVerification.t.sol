@@ -11,9 +11,10 @@ import "../contracts/verifiers/generated/KeyPublicizeN5Groth16Verifier.sol";
 import "../contracts/verifiers/generated/WinningJudgementN5Groth16Verifier.sol";
 
 contract AdditionalProofVerificationTest is Test {
-    uint256 internal constant ANONYMOUS_VOTING_PUBLIC_INPUTS = 1;
-    uint256 internal constant DIVINATION_PUBLIC_INPUTS = 8;
-    uint256 internal constant WINNING_JUDGEMENT_PUBLIC_INPUTS = 2;
+    uint256 internal constant ANONYMOUS_VOTING_PUBLIC_INPUTS = 4;
+    uint256 internal constant DIVINATION_PUBLIC_INPUTS = 11;
+    uint256 internal constant WINNING_JUDGEMENT_PUBLIC_INPUTS = 9;
+    uint256 internal constant KEY_PUBLICIZE_PUBLIC_INPUTS = 14;
 
     WerewolfGame internal game;
     WerewolfProofVerifier internal verifier;
@@ -34,29 +35,29 @@ contract AdditionalProofVerificationTest is Test {
         anonymousVotingVerifier = new AnonymousVotingN5Groth16Verifier();
         anonymousVotingAdapter = new Groth16VerifierAdapter(
             address(anonymousVotingVerifier),
-            bytes4(keccak256("verifyTx(((uint256,uint256),(uint256[2],uint256[2]),(uint256,uint256)),uint256[1])")),
+            bytes4(keccak256("verifyTx(((uint256,uint256),(uint256[2],uint256[2]),(uint256,uint256)),uint256[4])")),
             ANONYMOUS_VOTING_PUBLIC_INPUTS
         );
 
         divinationVerifier = new DivinationN5Groth16Verifier();
         divinationAdapter = new Groth16VerifierAdapter(
             address(divinationVerifier),
-            bytes4(keccak256("verifyTx(((uint256,uint256),(uint256[2],uint256[2]),(uint256,uint256)),uint256[8])")),
+            bytes4(keccak256("verifyTx(((uint256,uint256),(uint256[2],uint256[2]),(uint256,uint256)),uint256[11])")),
             DIVINATION_PUBLIC_INPUTS
         );
 
         winningJudgementVerifier = new WinningJudgementN5Groth16Verifier();
         winningJudgementAdapter = new Groth16VerifierAdapter(
             address(winningJudgementVerifier),
-            bytes4(keccak256("verifyTx(((uint256,uint256),(uint256[2],uint256[2]),(uint256,uint256)),uint256[2])")),
+            bytes4(keccak256("verifyTx(((uint256,uint256),(uint256[2],uint256[2]),(uint256,uint256)),uint256[9])")),
             WINNING_JUDGEMENT_PUBLIC_INPUTS
         );
 
         keyPublicizeVerifier = new KeyPublicizeN5Groth16Verifier();
         keyPublicizeAdapter = new Groth16VerifierAdapter(
             address(keyPublicizeVerifier),
-            bytes4(keccak256("verifyTx(((uint256,uint256),(uint256[2],uint256[2]),(uint256,uint256))")),
-            0
+            bytes4(keccak256("verifyTx(((uint256,uint256),(uint256[2],uint256[2]),(uint256,uint256)),uint256[14])")),
+            KEY_PUBLICIZE_PUBLIC_INPUTS
         );
 
         game.setVerifier(address(verifier));
@@ -319,9 +320,13 @@ contract AdditionalProofVerificationTest is Test {
 
         proof = abi.encode(solidityProof);
 
+        uint256[KEY_PUBLICIZE_PUBLIC_INPUTS] memory parsedInputs;
         bytes32[] memory parsedRawInputs = abi.decode(vm.parseJson(json, ".publicInputs"), (bytes32[]));
-        require(parsedRawInputs.length == 0, "invalid public input length");
-        publicInputs = bytes("");
+        require(parsedRawInputs.length == KEY_PUBLICIZE_PUBLIC_INPUTS, "invalid public input length");
+        for (uint256 i = 0; i < KEY_PUBLICIZE_PUBLIC_INPUTS; i++) {
+            parsedInputs[i] = uint256(parsedRawInputs[i]);
+        }
+        publicInputs = abi.encode(parsedInputs);
 
         offchainVerified = abi.decode(vm.parseJson(json, ".offchainVerified"), (bool));
     }
@@ -5,7 +5,7 @@ import "forge-std/Test.sol";
 import "../contracts/verifiers/generated/RoleAssignmentN5W1Groth16Verifier.sol";
 
 contract RoleAssignmentGroth16VerificationTest is Test {
-    uint256 internal constant FIXED_PUBLIC_INPUTS = 100; // (num_players + num_groups)^2 for 5 players: (5 + 5)^2
+    uint256 internal constant FIXED_PUBLIC_INPUTS = 24; // player_commitment (x, y) + role_commitment (x, y) for 5 players + alive mask, day count, prev / next state digest
 
     RoleAssignmentN5W1Groth16Verifier internal verifier;
 
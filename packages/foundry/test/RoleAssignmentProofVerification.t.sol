@@ -8,7 +8,7 @@ import "../contracts/verifiers/Groth16VerifierAdapter.sol";
 import "../contracts/verifiers/generated/RoleAssignmentN5W1Groth16Verifier.sol";
 
 contract RoleAssignmentProofVerificationTest is Test {
-    uint256 internal constant FIXED_PUBLIC_INPUTS = 100; // (5 players + 5 groups)^2
+    uint256 internal constant FIXED_PUBLIC_INPUTS = 24; // 5 players * (player_commitment + role_commitment) * (x, y) + alive mask, day count, prev / next state digest
 
     WerewolfGame internal game;
     WerewolfProofVerifier internal verifier;
@@ -23,7 +23,7 @@ contract RoleAssignmentProofVerificationTest is Test {
             address(roleAssignmentGroth16Verifier),
             bytes4(
                 keccak256(
-                    "verifyTx(((uint256,uint256),(uint256[2],uint256[2]),(uint256,uint256)),uint256[100])"
+                    "verifyTx(((uint256,uint256),(uint256[2],uint256[2]),(uint256,uint256)),uint256[24])"
                 )
             ),
             FIXED_PUBLIC_INPUTS
@@ -81,6 +81,10 @@ contract RoleAssignmentProofVerificationTest is Test {
         assertEq(recordedProofHash, keccak256(abi.encodePacked(proof, publicInputs)));
         assertTrue(recordedVerified);
         assertGt(recordedTimestamp, 0);
+
+        // 次の proof は今回の next state digest から始まる
+        uint256[FIXED_PUBLIC_INPUTS] memory words = abi.decode(publicInputs, (uint256[FIXED_PUBLIC_INPUTS]));
+        assertEq(verifier.latestStateDigest(gameId), bytes32(words[FIXED_PUBLIC_INPUTS - 1]));
     }
 
     function _generateRoleAssignmentFixture()
@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.19;
+
+import "forge-std/Test.sol";
+import "../contracts/WerewolfProofVerifier.sol";
+import "../contracts/verifiers/Groth16VerifierAdapter.sol";
+
+contract HashedVerifierMock {
+    bytes32 public constant VK_HASH = keccak256("vk");
+}
+
+contract UnhashedVerifierMock { }
+
+contract VerifyingKeyHashTest is Test {
+    WerewolfProofVerifier internal verifier;
+    Groth16VerifierAdapter internal hashedAdapter;
+    Groth16VerifierAdapter internal unhashedAdapter;
+
+    function setUp() public {
+        verifier = new WerewolfProofVerifier();
+        hashedAdapter = new Groth16VerifierAdapter(address(new HashedVerifierMock()), bytes4(0), 4);
+        unhashedAdapter = new Groth16VerifierAdapter(address(new UnhashedVerifierMock()), bytes4(0), 4);
+    }
+
+    function testAdapterReadsVkHash() public view {
+        assertEq(hashedAdapter.vkHash(), keccak256("vk"));
+        assertEq(unhashedAdapter.vkHash(), bytes32(0));
+    }
+
+    function testAdapterMustMatchExpectedVkHash() public {
+        verifier.setExpectedVkHash(WerewolfProofVerifier.ProofType.AnonymousVoting, 5, 0, keccak256("vk"));
+
+        vm.expectRevert("Verifying key hash mismatch");
+        verifier.setVerifierAdapter(WerewolfProofVerifier.ProofType.AnonymousVoting, 5, 0, address(unhashedAdapter));
+
+        verifier.setVerifierAdapter(WerewolfProofVerifier.ProofType.AnonymousVoting, 5, 0, address(hashedAdapter));
+        assertEq(
+            verifier.getVerifierAdapter(WerewolfProofVerifier.ProofType.AnonymousVoting, 5, 0), address(hashedAdapter)
+        );
+    }
+
+    function testExpectedVkHashChecksCurrentAdapter() public {
+        verifier.setVerifierAdapter(WerewolfProofVerifier.ProofType.Divination, 5, 0, address(hashedAdapter));
+
+        vm.expectRevert("Verifying key hash mismatch");
+        verifier.setExpectedVkHash(WerewolfProofVerifier.ProofType.Divination, 5, 0, keccak256("other vk"));
+
+        verifier.setExpectedVkHash(WerewolfProofVerifier.ProofType.Divination, 5, 0, keccak256("vk"));
+    }
+
+    function testOnlyOwnerSetsManifestHash() public {
+        verifier.setManifestHash(keccak256("manifest"));
+        assertEq(verifier.manifestHash(), keccak256("manifest"));
+
+        vm.prank(address(0xBEEF));
+        vm.expectRevert();
+        verifier.setManifestHash(bytes32(0));
+    }
+}
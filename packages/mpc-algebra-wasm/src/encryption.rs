@@ -112,8 +112,11 @@ pub trait SplitAndEncrypt {
 
 pub struct AnonymousVotingEncryption;
 pub struct KeyPublicizeEncryption;
+pub struct AttackEncryption;
+pub struct NightResolutionEncryption;
 pub struct RoleAssignmentEncryption;
 pub struct DivinationEncryption;
+pub struct MediumEncryption;
 pub struct WinningJudgementEncryption;
 
 impl SplitAndEncrypt for AnonymousVotingEncryption {
@@ -188,14 +191,16 @@ impl SplitAndEncrypt for KeyPublicizeEncryption {
 
         let pub_key_or_dummy_x_share = split_fr(private_input.pub_key_or_dummy_x, scheme);
         let pub_key_or_dummy_y_share = split_fr(private_input.pub_key_or_dummy_y, scheme);
-        let is_fortune_teller_share = split_fr(private_input.is_fortune_teller, scheme);
+        let role_id_share = split_fr(private_input.role_id, scheme);
+        let randomness_share = split_pedersen_randomness(private_input.randomness.clone(), scheme);
 
         (0..scheme.total_shares)
             .map(|i| KeyPublicizePrivateInput {
                 id: private_input.id,
                 pub_key_or_dummy_x: pub_key_or_dummy_x_share[i],
                 pub_key_or_dummy_y: pub_key_or_dummy_y_share[i],
-                is_fortune_teller: is_fortune_teller_share[i],
+                role_id: role_id_share[i],
+                randomness: randomness_share[i].clone(),
             })
             .collect::<Vec<_>>()
     }
@@ -218,6 +223,93 @@ impl SplitAndEncrypt for KeyPublicizeEncryption {
     }
 }
 
+impl SplitAndEncrypt for AttackEncryption {
+    type Input = AttackInput;
+    type Output = AttackOutput;
+
+    type ShareForNode = AttackPrivateInput;
+
+    fn split(input: &Self::Input) -> Vec<Self::ShareForNode> {
+        let scheme = &input.scheme;
+        let private_input = &input.private_input;
+
+        let is_target_share = split_vec_fr(private_input.is_target_id.clone(), scheme);
+        let role_id_share = split_fr(private_input.role_id, scheme);
+        let randomness_share = split_pedersen_randomness(private_input.randomness.clone(), scheme);
+
+        (0..scheme.total_shares)
+            .map(|i| AttackPrivateInput {
+                id: private_input.id,
+                is_target_id: is_target_share.iter().map(|row| row[i]).collect(),
+                role_id: role_id_share[i],
+                randomness: randomness_share[i].clone(),
+            })
+            .collect::<Vec<_>>()
+    }
+
+    fn create_encrypted_shares(input: &Self::Input) -> Result<Self::Output, JsValue> {
+        let mut shares = Vec::new();
+
+        let plain_shares = Self::split(input);
+
+        for (i, node_key) in input.node_keys.iter().enumerate() {
+            let encrypted_share = Self::encrypt(plain_shares[i].clone(), node_key)?;
+
+            shares.push(encrypted_share);
+        }
+
+        Ok(AttackOutput {
+            shares,
+            public_input: input.public_input.clone(),
+        })
+    }
+}
+
+impl SplitAndEncrypt for NightResolutionEncryption {
+    type Input = NightResolutionInput;
+    type Output = NightResolutionOutput;
+
+    type ShareForNode = NightResolutionPrivateInput;
+
+    fn split(input: &Self::Input) -> Vec<Self::ShareForNode> {
+        let scheme = &input.scheme;
+        let private_input = &input.private_input;
+
+        let is_attack_target_share =
+            split_vec_fr(private_input.is_attack_target_id.clone(), scheme);
+        let is_guard_target_share = split_vec_fr(private_input.is_guard_target_id.clone(), scheme);
+        let role_id_share = split_fr(private_input.role_id, scheme);
+        let randomness_share = split_pedersen_randomness(private_input.randomness.clone(), scheme);
+
+        (0..scheme.total_shares)
+            .map(|i| NightResolutionPrivateInput {
+                id: private_input.id,
+                is_attack_target_id: is_attack_target_share.iter().map(|row| row[i]).collect(),
+                is_guard_target_id: is_guard_target_share.iter().map(|row| row[i]).collect(),
+                role_id: role_id_share[i],
+                randomness: randomness_share[i].clone(),
+            })
+            .collect::<Vec<_>>()
+    }
+
+    fn create_encrypted_shares(input: &Self::Input) -> Result<Self::Output, JsValue> {
+        let mut shares = Vec::new();
+
+        let plain_shares = Self::split(input);
+
+        for (i, node_key) in input.node_keys.iter().enumerate() {
+            let encrypted_share = Self::encrypt(plain_shares[i].clone(), node_key)?;
+
+            shares.push(encrypted_share);
+        }
+
+        Ok(NightResolutionOutput {
+            shares,
+            public_input: input.public_input.clone(),
+        })
+    }
+}
+
 impl SplitAndEncrypt for RoleAssignmentEncryption {
     type Input = RoleAssignmentInput;
     type Output = RoleAssignmentOutput;
@@ -234,6 +326,8 @@ impl SplitAndEncrypt for RoleAssignmentEncryption {
         // let randomness_share = split_vec_fr(private_input.randomness.clone(), scheme);
         let player_randomness_share = split_fr(private_input.player_randomness, scheme);
         let randomness_share = split_pedersen_randomness(private_input.randomness.clone(), scheme);
+        let player_commitment_randomness_share =
+            split_pedersen_randomness(private_input.player_commitment_randomness.clone(), scheme);
 
         (0..scheme.total_shares)
             .map(|i| RoleAssignmentPrivateInput {
@@ -241,6 +335,7 @@ impl SplitAndEncrypt for RoleAssignmentEncryption {
                 shuffle_matrices: shuffle_matrix_share[i].clone(),
                 player_randomness: player_randomness_share[i],
                 randomness: randomness_share[i].clone(),
+                player_commitment_randomness: player_commitment_randomness_share[i].clone(),
             })
             .collect::<Vec<_>>()
     }
@@ -307,6 +402,49 @@ impl SplitAndEncrypt for DivinationEncryption {
     }
 }
 
+impl SplitAndEncrypt for MediumEncryption {
+    type Input = MediumInput;
+    type Output = MediumOutput;
+
+    type ShareForNode = MediumPrivateInput;
+
+    fn split(input: &Self::Input) -> Vec<Self::ShareForNode> {
+        let scheme = &input.scheme;
+        let private_input = &input.private_input;
+
+        let role_id_share = split_fr(private_input.role_id, scheme);
+        let role_randomness_share =
+            split_pedersen_randomness(private_input.role_randomness.clone(), scheme);
+        let randomness_share = split_elgamal_randomness(private_input.randomness.clone(), scheme);
+
+        (0..scheme.total_shares)
+            .map(|i| MediumPrivateInput {
+                id: private_input.id,
+                role_id: role_id_share[i],
+                role_randomness: role_randomness_share[i].clone(),
+                randomness: randomness_share[i].clone(),
+            })
+            .collect::<Vec<_>>()
+    }
+
+    fn create_encrypted_shares(input: &Self::Input) -> Result<Self::Output, JsValue> {
+        let mut shares = Vec::new();
+
+        let plain_shares = Self::split(input);
+
+        for (i, node_key) in input.node_keys.iter().enumerate() {
+            let encrypted_share = Self::encrypt(plain_shares[i].clone(), node_key)?;
+
+            shares.push(encrypted_share);
+        }
+
+        Ok(MediumOutput {
+            shares,
+            public_input: input.public_input.clone(),
+        })
+    }
+}
+
 impl SplitAndEncrypt for WinningJudgementEncryption {
     type Input = WinningJudgementInput;
     type Output = WinningJudgementOutput;
@@ -318,13 +456,19 @@ impl SplitAndEncrypt for WinningJudgementEncryption {
         let private_input = &input.private_input;
 
         let am_werewolf_share = split_fr(private_input.am_werewolf, scheme);
-        let player_randomness_share = split_fr(private_input.player_randomness, scheme);
+        let am_werewolf_team_share = split_fr(private_input.am_werewolf_team, scheme);
+        let am_fox_share = split_fr(private_input.am_fox, scheme);
+        let role_id_share = split_fr(private_input.role_id, scheme);
+        let randomness_share = split_pedersen_randomness(private_input.randomness.clone(), scheme);
 
         (0..scheme.total_shares)
             .map(|i| WinningJudgementPrivateInput {
                 id: private_input.id,
                 am_werewolf: am_werewolf_share[i],
-                player_randomness: player_randomness_share[i],
+                am_werewolf_team: am_werewolf_team_share[i],
+                am_fox: am_fox_share[i],
+                role_id: role_id_share[i],
+                randomness: randomness_share[i].clone(),
             })
             .collect::<Vec<_>>()
     }
@@ -460,7 +604,10 @@ mod tests {
 
     // use mpc_algebra::crh::pedersen;
 
-    use crate::{PedersenComScheme, PedersenCommitment};
+    use crate::{
+        InstanceMode, PedersenComScheme, PedersenCommitment, StateChainParameter,
+        WinConditionParameter,
+    };
     use ark_crypto_primitives::CommitmentScheme;
     use rand::CryptoRng;
 
@@ -572,6 +719,10 @@ mod tests {
             pedersen_param: pedersen_param.clone(),
             player_commitment: vec![PedersenCommitment::default(); 3],
             player_num: 3, // Assuming 3 players for this test
+            state_chain: StateChainParameter {
+                alive_mask: 0b111,
+                ..Default::default()
+            },
         };
 
         let input = AnonymousVotingInput {
@@ -633,6 +784,10 @@ mod tests {
             pedersen_param,
             player_commitment: vec![PedersenCommitment::default(); 3],
             player_num: 3,
+            state_chain: StateChainParameter {
+                alive_mask: 0b111,
+                ..Default::default()
+            },
         };
 
         let input = AnonymousVotingInput {
@@ -671,11 +826,16 @@ mod tests {
             id: 1,
             pub_key_or_dummy_x: Fr::pub_rand(rng),
             pub_key_or_dummy_y: Fr::pub_rand(rng),
-            is_fortune_teller: Fr::pub_rand(rng),
+            role_id: Fr::pub_rand(rng),
+            randomness: PedersenRandomness::rand(rng),
         };
 
         let pedersen_param = PedersenComScheme::setup(rng).unwrap();
-        let public_input = KeyPublicizePublicInput { pedersen_param };
+        let public_input = KeyPublicizePublicInput {
+            pedersen_param,
+            role_commitment: vec![PedersenCommitment::default(); 3],
+            state_chain: StateChainParameter::default(),
+        };
 
         let input = KeyPublicizeInput {
             private_input: private_input.clone(),
@@ -698,6 +858,133 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_encrypted_shares_attack() {
+        let rng = &mut rand::thread_rng();
+        let scheme = SecretSharingScheme {
+            total_shares: 3,
+            modulus: 97,
+        };
+
+        let (node_keys, secret_keys) = generate_test_node_keys(rng, 3);
+
+        let private_input = AttackPrivateInput {
+            id: 1,
+            is_target_id: vec![Fr::zero(), Fr::zero(), Fr::from(1u32)],
+            role_id: Fr::from(2u32),
+            randomness: PedersenRandomness::rand(rng),
+        };
+
+        let pedersen_param = PedersenComScheme::setup(rng).unwrap();
+        let public_input = AttackPublicInput {
+            pedersen_param,
+            player_num: 3,
+            role_commitment: vec![PedersenCommitment::default(); 3],
+            state_chain: StateChainParameter::default(),
+        };
+
+        let input = AttackInput {
+            private_input: private_input.clone(),
+            public_input,
+            node_keys,
+            scheme,
+        };
+
+        let result = AttackEncryption::create_encrypted_shares(&input);
+        assert!(result.is_ok(), "create_encrypted_shares should succeed");
+
+        let output = result.unwrap();
+        assert_eq!(output.shares.len(), 3, "Should have 3 encrypted shares");
+
+        // 復号したシェアを足し合わせると元の入力に戻る
+        let decrypted = output
+            .shares
+            .iter()
+            .enumerate()
+            .map(|(i, share)| AttackEncryption::decrypt(share, &secret_keys[i]).unwrap())
+            .collect::<Vec<_>>();
+        assert!(decrypted.iter().all(|share| share.id == private_input.id));
+        assert_eq!(
+            decrypted.iter().map(|share| share.role_id).sum::<Fr>(),
+            private_input.role_id
+        );
+        for j in 0..3 {
+            assert_eq!(
+                decrypted
+                    .iter()
+                    .map(|share| share.is_target_id[j])
+                    .sum::<Fr>(),
+                private_input.is_target_id[j]
+            );
+        }
+        assert_eq!(
+            decrypted
+                .iter()
+                .map(|share| share.randomness.0)
+                .sum::<ark_ed_on_bn254::Fr>(),
+            private_input.randomness.0
+        );
+    }
+
+    #[test]
+    fn test_create_encrypted_shares_night_resolution() {
+        let rng = &mut rand::thread_rng();
+        let scheme = SecretSharingScheme {
+            total_shares: 3,
+            modulus: 97,
+        };
+
+        let (node_keys, secret_keys) = generate_test_node_keys(rng, 3);
+
+        // 騎士がプレイヤー0を護衛する
+        let private_input = NightResolutionPrivateInput {
+            id: 2,
+            is_attack_target_id: vec![Fr::zero(); 3],
+            is_guard_target_id: vec![Fr::from(1u32), Fr::zero(), Fr::zero()],
+            role_id: Fr::from(3u32),
+            randomness: PedersenRandomness::rand(rng),
+        };
+
+        let pedersen_param = PedersenComScheme::setup(rng).unwrap();
+        let public_input = NightResolutionPublicInput {
+            pedersen_param,
+            player_num: 3,
+            role_commitment: vec![PedersenCommitment::default(); 3],
+            state_chain: StateChainParameter::default(),
+        };
+
+        let input = NightResolutionInput {
+            private_input: private_input.clone(),
+            public_input,
+            node_keys,
+            scheme,
+        };
+
+        let output = NightResolutionEncryption::create_encrypted_shares(&input).unwrap();
+        assert_eq!(output.shares.len(), 3, "Should have 3 encrypted shares");
+
+        let decrypted = output
+            .shares
+            .iter()
+            .enumerate()
+            .map(|(i, share)| NightResolutionEncryption::decrypt(share, &secret_keys[i]).unwrap())
+            .collect::<Vec<_>>();
+        assert!(decrypted.iter().all(|share| share.id == private_input.id));
+        assert_eq!(
+            decrypted.iter().map(|share| share.role_id).sum::<Fr>(),
+            private_input.role_id
+        );
+        for j in 0..3 {
+            assert_eq!(
+                decrypted
+                    .iter()
+                    .map(|share| share.is_guard_target_id[j])
+                    .sum::<Fr>(),
+                private_input.is_guard_target_id[j]
+            );
+        }
+    }
+
     #[test]
     fn test_create_encrypted_shares_winning_judgement() {
         let rng = &mut rand::thread_rng();
@@ -711,13 +998,23 @@ mod tests {
         let private_input = WinningJudgementPrivateInput {
             id: 1,
             am_werewolf: Fr::pub_rand(rng),
-            player_randomness: Fr::pub_rand(rng),
+            am_werewolf_team: Fr::pub_rand(rng),
+            am_fox: Fr::pub_rand(rng),
+            role_id: Fr::from(2u32),
+            randomness: PedersenRandomness::rand(rng),
         };
 
         let pedersen_param = PedersenComScheme::setup(rng).unwrap();
         let public_input = WinningJudgementPublicInput {
             pedersen_param,
-            player_commitment: vec![PedersenCommitment::default(); 3],
+            player_num: 3,
+            win_condition: WinConditionParameter::default(),
+            role_commitment: vec![PedersenCommitment::default(); 3],
+            state_chain: StateChainParameter {
+                alive_mask: 0b111,
+                day_count: 1,
+                ..Default::default()
+            },
         };
 
         let input = WinningJudgementInput {
@@ -772,6 +1069,10 @@ mod tests {
             elgamal_param,
             pub_key: elgamal_pub_key,
             player_num: 3,
+            state_chain: StateChainParameter {
+                alive_mask: 0b111,
+                ..Default::default()
+            },
         };
 
         let input = DivinationInput {
@@ -795,6 +1096,68 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_encrypted_shares_medium() {
+        use crate::ElGamalScheme;
+        use ark_crypto_primitives::encryption::AsymmetricEncryptionScheme;
+
+        let rng = &mut rand::thread_rng();
+        let scheme = SecretSharingScheme {
+            total_shares: 3,
+            modulus: 97,
+        };
+
+        let (node_keys, secret_keys) = generate_test_node_keys(rng, 3);
+
+        let elgamal_param = ElGamalScheme::setup(rng).unwrap();
+        let (elgamal_pub_key, _elgamal_secret_key) =
+            ElGamalScheme::keygen(&elgamal_param, rng).unwrap();
+        let elgamal_randomness =
+            <ElGamalScheme as AsymmetricEncryptionScheme>::Randomness::rand(rng);
+
+        // 処刑されたプレイヤー1が人狼
+        let private_input = MediumPrivateInput {
+            id: 1,
+            role_id: Fr::from(2u32),
+            role_randomness: PedersenRandomness::rand(rng),
+            randomness: elgamal_randomness,
+        };
+
+        let pedersen_param = PedersenComScheme::setup(rng).unwrap();
+        let public_input = MediumPublicInput {
+            pedersen_param,
+            elgamal_param,
+            pub_key: elgamal_pub_key,
+            player_num: 3,
+            executed_id: 1,
+            role_commitment: vec![PedersenCommitment::default(); 3],
+            state_chain: StateChainParameter::default(),
+        };
+
+        let input = MediumInput {
+            private_input: private_input.clone(),
+            public_input,
+            node_keys,
+            scheme,
+        };
+
+        let output = MediumEncryption::create_encrypted_shares(&input).unwrap();
+        assert_eq!(output.shares.len(), 3, "Should have 3 encrypted shares");
+        assert_eq!(output.public_input.executed_id, 1);
+
+        let decrypted = output
+            .shares
+            .iter()
+            .enumerate()
+            .map(|(i, share)| MediumEncryption::decrypt(share, &secret_keys[i]).unwrap())
+            .collect::<Vec<_>>();
+        assert!(decrypted.iter().all(|share| share.id == private_input.id));
+        assert_eq!(
+            decrypted.iter().map(|share| share.role_id).sum::<Fr>(),
+            private_input.role_id
+        );
+    }
+
     #[test]
     fn test_create_encrypted_shares_role_assignment() {
         use nalgebra::DMatrix;
@@ -826,6 +1189,7 @@ mod tests {
             shuffle_matrices: shuffle_matrix,
             randomness: pedersen_randomness,
             player_randomness: Fr::pub_rand(rng),
+            player_commitment_randomness: PedersenRandomness::rand(rng),
         };
 
         // Setup grouping parameter
@@ -845,6 +1209,8 @@ mod tests {
             tau_matrix,
             role_commitment: vec![PedersenCommitment::default(); num_players],
             player_commitment: vec![PedersenCommitment::default(); num_players],
+            state_chain: StateChainParameter::default(),
+            instance_mode: InstanceMode::default(),
         };
 
         let input = RoleAssignmentInput {
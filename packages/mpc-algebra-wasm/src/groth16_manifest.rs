@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::CircuitProfile;
+
+pub const GROTH16_MANIFEST_FILE_NAME: &str = "manifest.json";
+pub const GROTH16_MANIFEST_VERSION: u32 = 1;
+
+const MANIFEST_SIGNING_DOMAIN: &str = "zk-werewolf/groth16-manifest/v1";
+
+/// Keys of one circuit profile. Hashes are hex encoded SHA-256.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Groth16ManifestEntry {
+    pub circuit_id: String,
+    pub profile: CircuitProfile,
+    /// SHA-256 of the uncompressed verifying key. Also exported as `VK_HASH` in the Solidity verifier.
+    pub vk_hash: String,
+    /// SHA-256 of the `.pk` file as written to disk.
+    pub pk_hash: String,
+    pub public_input_len: usize,
+}
+
+/// Signed list of the Groth16 keys produced by `multi_profile_groth16_setup`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Groth16Manifest {
+    pub version: u32,
+    pub entries: Vec<Groth16ManifestEntry>,
+    /// Base64 Ed25519 public key of the signer.
+    pub signer_public_key: String,
+    /// Base64 Ed25519 signature over `signing_message()`.
+    pub signature: String,
+}
+
+impl Groth16Manifest {
+    /// Entries を circuit_id 順に並べた JSON に domain を付けたもの。ファイル上の並び順には依存しない
+    pub fn signing_message(version: u32, entries: &[Groth16ManifestEntry]) -> Vec<u8> {
+        let mut sorted = entries.iter().collect::<Vec<_>>();
+        sorted.sort_by(|a, b| a.circuit_id.cmp(&b.circuit_id));
+        let body = serde_json::to_string(&sorted).expect("manifest entries are serializable");
+        format!("{}\n{}\n{}", MANIFEST_SIGNING_DOMAIN, version, body).into_bytes()
+    }
+
+    /// Hex SHA-256 of the signed message. Nodes report it in `ProofOutput::manifest_hash`.
+    pub fn manifest_hash(&self) -> String {
+        sha256_hex(&Self::signing_message(self.version, &self.entries))
+    }
+
+    pub fn entry(&self, circuit_id: &str) -> Option<&Groth16ManifestEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.circuit_id == circuit_id)
+    }
+
+    /// Replaces the entry with the same `circuit_id`, or appends it.
+    pub fn upsert(&mut self, entry: Groth16ManifestEntry) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|existing| existing.circuit_id == entry.circuit_id)
+        {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(circuit_id: &str, player_count: usize) -> Groth16ManifestEntry {
+        Groth16ManifestEntry {
+            circuit_id: circuit_id.to_string(),
+            profile: CircuitProfile::Divination { player_count },
+            vk_hash: sha256_hex(b"vk"),
+            pk_hash: sha256_hex(b"pk"),
+            public_input_len: 11,
+        }
+    }
+
+    #[test]
+    fn manifest_hash_ignores_entry_order() {
+        let mut manifest = Groth16Manifest {
+            version: GROTH16_MANIFEST_VERSION,
+            entries: vec![entry("divination_n3_v1", 3), entry("divination_n4_v1", 4)],
+            signer_public_key: String::new(),
+            signature: String::new(),
+        };
+        let hash = manifest.manifest_hash();
+
+        manifest.entries.reverse();
+        assert_eq!(manifest.manifest_hash(), hash);
+
+        manifest.upsert(Groth16ManifestEntry {
+            pk_hash: sha256_hex(b"other pk"),
+            ..entry("divination_n3_v1", 3)
+        });
+        assert_eq!(manifest.entries.len(), 2);
+        assert_ne!(manifest.manifest_hash(), hash);
+    }
+}
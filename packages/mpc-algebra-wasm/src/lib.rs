@@ -6,10 +6,12 @@ use serde::{Deserialize, Serialize};
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
 pub mod encryption;
+pub mod groth16_manifest;
 pub mod mpc_circuits_wasm;
 pub mod types;
 pub mod werewolf;
 pub use encryption::*;
+pub use groth16_manifest::*;
 pub use mpc_circuits_wasm::*;
 pub use types::*;
 pub use werewolf::*;
@@ -83,6 +85,33 @@ pub fn key_publicize(input: JsValue) -> Result<JsValue, JsValue> {
     Ok(JsValue::from_str(&json_str))
 }
 
+#[wasm_bindgen]
+pub fn attack_split_and_encrypt(input: JsValue) -> Result<JsValue, JsValue> {
+    let input: AttackInput = serde_wasm_bindgen::from_value(input)?;
+    let result = AttackEncryption::create_encrypted_shares(&input)?;
+    let json_str = serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))?;
+    Ok(JsValue::from_str(&json_str))
+}
+
+#[wasm_bindgen]
+pub fn night_resolution_split_and_encrypt(input: JsValue) -> Result<JsValue, JsValue> {
+    let input: NightResolutionInput = serde_wasm_bindgen::from_value(input)?;
+    let result = NightResolutionEncryption::create_encrypted_shares(&input)?;
+    let json_str = serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))?;
+    Ok(JsValue::from_str(&json_str))
+}
+
+#[wasm_bindgen]
+pub fn medium_split_and_encrypt(input: JsValue) -> Result<JsValue, JsValue> {
+    let input: MediumInput = serde_wasm_bindgen::from_value(input)?;
+    let result = MediumEncryption::create_encrypted_shares(&input)?;
+    let json_str = serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))?;
+    Ok(JsValue::from_str(&json_str))
+}
+
 #[wasm_bindgen]
 pub fn role_assignment(input: JsValue) -> Result<JsValue, JsValue> {
     let input: RoleAssignmentInput = serde_wasm_bindgen::from_value(input)?;
@@ -235,6 +264,10 @@ mod tests {
                     PedersenCommitment::default(),
                 ],
                 player_num: 3, // Assuming 3 players for this test
+                state_chain: StateChainParameter {
+                    alive_mask: 0b111,
+                    ..Default::default()
+                },
             },
             node_keys: vec![
                 NodeKey {
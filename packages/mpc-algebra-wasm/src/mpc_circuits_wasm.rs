@@ -3,9 +3,11 @@ pub mod inputs;
 pub mod traits;
 
 pub use circuits::{
-    anonymous_voting::*, divination::*, key_publicize::*, role_assignment::*, winning_judgement::*,
+    anonymous_voting::*, attack::*, divination::*, key_publicize::*, medium::*,
+    night_resolution::*, role_assignment::*, winning_judgement::*,
 };
 pub use inputs::{
-    anonymous_voting::*, divination::*, key_publicize::*, role_assignment::*, winning_judgement::*,
+    anonymous_voting::*, attack::*, divination::*, key_publicize::*, medium::*,
+    night_resolution::*, role_assignment::*, winning_judgement::*,
 };
 pub use traits::*;
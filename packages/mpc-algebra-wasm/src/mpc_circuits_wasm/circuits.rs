@@ -1,11 +1,17 @@
 pub mod anonymous_voting;
+pub mod attack;
 pub mod divination;
 pub mod key_publicize;
+pub mod medium;
+pub mod night_resolution;
 pub mod role_assignment;
 pub mod winning_judgement;
 
 pub use anonymous_voting::*;
+pub use attack::*;
 pub use divination::*;
 pub use key_publicize::*;
+pub use medium::*;
+pub use night_resolution::*;
 pub use role_assignment::*;
 pub use winning_judgement::*;
@@ -0,0 +1,8 @@
+use crate::{AttackPrivateInput, AttackPublicInput};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct AttackCircuit {
+    pub private_input: Vec<AttackPrivateInput>,
+    pub public_input: AttackPublicInput,
+}
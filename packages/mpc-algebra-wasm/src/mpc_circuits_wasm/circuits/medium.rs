@@ -0,0 +1,8 @@
+use crate::{MediumPrivateInput, MediumPublicInput};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct MediumCircuit {
+    pub private_input: Vec<MediumPrivateInput>,
+    pub public_input: MediumPublicInput,
+}
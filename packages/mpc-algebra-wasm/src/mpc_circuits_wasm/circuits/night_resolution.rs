@@ -0,0 +1,8 @@
+use crate::{NightResolutionPrivateInput, NightResolutionPublicInput};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct NightResolutionCircuit {
+    pub private_input: Vec<NightResolutionPrivateInput>,
+    pub public_input: NightResolutionPublicInput,
+}
@@ -1,4 +1,4 @@
-use crate::{PedersenCommitment, PedersenParam};
+use crate::{PedersenCommitment, PedersenParam, StateChainParameter};
 use ark_bn254::Fr;
 use serde::{Deserialize, Serialize};
 
@@ -16,4 +16,7 @@ pub struct AnonymousVotingPublicInput {
     pub pedersen_param: PedersenParam,
     pub player_commitment: Vec<PedersenCommitment>,
     pub player_num: usize,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
 }
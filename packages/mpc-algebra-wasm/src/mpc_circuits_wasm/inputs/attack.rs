@@ -0,0 +1,28 @@
+use crate::{PedersenCommitment, PedersenParam, PedersenRandomness, StateChainParameter};
+use ark_bn254::Fr;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AttackPrivateInput {
+    pub id: usize,
+    // 人狼は襲撃先の one-hot、それ以外は全て 0
+    pub is_target_id: Vec<Fr>,
+    // role_commitment[id] の開示値 (0: 村人, 1: 占い師, 2: 人狼)
+    pub role_id: Fr,
+    pub randomness: PedersenRandomness,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AttackPublicInput {
+    pub pedersen_param: PedersenParam,
+    pub player_num: usize,
+
+    // instance
+    // 死亡者を含む全プレイヤーの役職 commitment (id 順)
+    pub role_commitment: Vec<PedersenCommitment>,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
+}
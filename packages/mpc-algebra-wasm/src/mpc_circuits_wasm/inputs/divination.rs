@@ -1,4 +1,4 @@
-use crate::{ElGamalParam, ElGamalPubKey, ElGamalRandomness, PedersenParam};
+use crate::{ElGamalParam, ElGamalPubKey, ElGamalRandomness, PedersenParam, StateChainParameter};
 use ark_bn254::Fr;
 use serde::{Deserialize, Serialize};
 
@@ -19,4 +19,7 @@ pub struct DivinationPublicInput {
     pub elgamal_param: ElGamalParam,
     pub pub_key: ElGamalPubKey,
     pub player_num: usize,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
 }
@@ -1,4 +1,4 @@
-use crate::PedersenParam;
+use crate::{PedersenCommitment, PedersenParam, PedersenRandomness, StateChainParameter};
 use ark_bn254::Fr;
 use serde::{Deserialize, Serialize};
 
@@ -8,11 +8,19 @@ pub struct KeyPublicizePrivateInput {
     pub id: usize,
     pub pub_key_or_dummy_x: Fr,
     pub pub_key_or_dummy_y: Fr,
-    pub is_fortune_teller: Fr,
+    // role_commitment[id] の開示値 (占い師かどうかは回路内で役職IDから求める)
+    pub role_id: Fr,
+    pub randomness: PedersenRandomness,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct KeyPublicizePublicInput {
     pub pedersen_param: PedersenParam,
+
+    // instance
+    pub role_commitment: Vec<PedersenCommitment>,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
 }
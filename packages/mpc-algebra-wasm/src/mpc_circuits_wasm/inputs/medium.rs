@@ -0,0 +1,36 @@
+use crate::{
+    ElGamalParam, ElGamalPubKey, ElGamalRandomness, PedersenCommitment, PedersenParam,
+    PedersenRandomness, StateChainParameter,
+};
+use ark_bn254::Fr;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediumPrivateInput {
+    pub id: usize,
+    // role_commitment[id] の開示値。人狼かどうかはここから求める
+    pub role_id: Fr,
+    pub role_randomness: PedersenRandomness,
+    // 暗号化の乱数 (先頭の行のものを使う)
+    pub randomness: ElGamalRandomness,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediumPublicInput {
+    pub pedersen_param: PedersenParam,
+    pub elgamal_param: ElGamalParam,
+    // KeyPublicize で公開された霊媒師の公開鍵
+    pub pub_key: ElGamalPubKey,
+    pub player_num: usize,
+    // AnonymousVoting の出力 (処刑時点の生存者 index)
+    pub executed_id: usize,
+
+    // instance
+    // 処刑時点の生存者 index 順に並べた役職 commitment
+    pub role_commitment: Vec<PedersenCommitment>,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
+}
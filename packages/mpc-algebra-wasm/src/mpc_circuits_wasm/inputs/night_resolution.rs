@@ -0,0 +1,29 @@
+use crate::{PedersenCommitment, PedersenParam, PedersenRandomness, StateChainParameter};
+use ark_bn254::Fr;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NightResolutionPrivateInput {
+    pub id: usize,
+    // 人狼は襲撃先の one-hot、それ以外は全て 0
+    pub is_attack_target_id: Vec<Fr>,
+    // 騎士は護衛先の one-hot、それ以外は全て 0
+    pub is_guard_target_id: Vec<Fr>,
+    // role_commitment[id] の開示値 (0: 村人, 1: 占い師, 2: 人狼, 3: 騎士)
+    pub role_id: Fr,
+    pub randomness: PedersenRandomness,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NightResolutionPublicInput {
+    pub pedersen_param: PedersenParam,
+    pub player_num: usize,
+
+    // instance
+    pub role_commitment: Vec<PedersenCommitment>,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
+}
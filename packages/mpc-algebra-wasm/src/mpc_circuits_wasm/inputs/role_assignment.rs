@@ -1,7 +1,10 @@
 use nalgebra as na;
 use serde::{Deserialize, Serialize};
 
-use crate::{GroupingParameter, PedersenCommitment, PedersenParam, PedersenRandomness};
+use crate::{
+    GroupingParameter, InstanceMode, PedersenCommitment, PedersenParam, PedersenRandomness,
+    StateChainParameter,
+};
 use ark_bn254::Fr;
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -9,8 +12,11 @@ use ark_bn254::Fr;
 pub struct RoleAssignmentPrivateInput {
     pub id: usize,
     pub shuffle_matrices: na::DMatrix<Fr>,
+    // role_commitment[id] のランダムネス。後の回路で役職を開示するためプレイヤーが保持する
     pub randomness: PedersenRandomness,
     pub player_randomness: Fr,
+    // player_commitment[id] の開示値
+    pub player_commitment_randomness: PedersenRandomness,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -24,6 +30,14 @@ pub struct RoleAssignmentPublicInput {
 
     // instance
     pub tau_matrix: na::DMatrix<Fr>,
+    // ノードが MPC で計算して公開入力にするので、リクエストでは空でよい
+    #[serde(default)]
     pub role_commitment: Vec<PedersenCommitment>,
     pub player_commitment: Vec<PedersenCommitment>,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
+    // Hashed のときは instance 全体の Poseidon hash だけが公開入力になる
+    #[serde(default)]
+    pub instance_mode: InstanceMode,
 }
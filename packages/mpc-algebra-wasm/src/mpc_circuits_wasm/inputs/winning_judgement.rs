@@ -1,4 +1,7 @@
-use crate::{PedersenCommitment, PedersenParam};
+use crate::{
+    PedersenCommitment, PedersenParam, PedersenRandomness, StateChainParameter,
+    WinConditionParameter,
+};
 use ark_bn254::Fr;
 use serde::{Deserialize, Serialize};
 
@@ -7,12 +10,23 @@ use serde::{Deserialize, Serialize};
 pub struct WinningJudgementPrivateInput {
     pub id: usize,
     pub am_werewolf: Fr,
-    pub player_randomness: Fr,
+    pub am_werewolf_team: Fr,
+    pub am_fox: Fr,
+    // role_commitment[id] の開示値
+    pub role_id: Fr,
+    pub randomness: PedersenRandomness,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct WinningJudgementPublicInput {
     pub pedersen_param: PedersenParam,
-    pub player_commitment: Vec<PedersenCommitment>,
+    pub player_num: usize,
+    pub win_condition: WinConditionParameter,
+
+    // instance
+    pub role_commitment: Vec<PedersenCommitment>,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
 }
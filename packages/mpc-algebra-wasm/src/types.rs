@@ -1,7 +1,7 @@
 use crate::mpc_circuits_wasm::*;
 use serde::{Deserialize, Serialize};
 
-use crate::{NodeKey, SecretSharingScheme};
+use crate::{InstanceMode, NodeKey, SecretSharingScheme};
 
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -44,6 +44,54 @@ pub struct KeyPublicizeOutput {
     pub public_input: KeyPublicizePublicInput,
 }
 
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttackInput {
+    pub private_input: AttackPrivateInput,
+    pub public_input: AttackPublicInput,
+    pub node_keys: Vec<NodeKey>,
+    pub scheme: SecretSharingScheme,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttackOutput {
+    pub shares: Vec<NodeEncryptedShare>,
+    pub public_input: AttackPublicInput,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NightResolutionInput {
+    pub private_input: NightResolutionPrivateInput,
+    pub public_input: NightResolutionPublicInput,
+    pub node_keys: Vec<NodeKey>,
+    pub scheme: SecretSharingScheme,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NightResolutionOutput {
+    pub shares: Vec<NodeEncryptedShare>,
+    pub public_input: NightResolutionPublicInput,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediumInput {
+    pub private_input: MediumPrivateInput,
+    pub public_input: MediumPublicInput,
+    pub node_keys: Vec<NodeKey>,
+    pub scheme: SecretSharingScheme,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediumOutput {
+    pub shares: Vec<NodeEncryptedShare>,
+    pub public_input: MediumPublicInput,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RoleAssignmentInput {
@@ -99,6 +147,9 @@ pub enum CircuitEncryptedInputIdentifier {
     WinningJudge(Vec<WinningJudgementOutput>),
     RoleAssignment(Vec<RoleAssignmentOutput>),
     KeyPublicize(Vec<KeyPublicizeOutput>),
+    Attack(Vec<AttackOutput>),
+    NightResolution(Vec<NightResolutionOutput>),
+    Medium(Vec<MediumOutput>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -119,6 +170,15 @@ pub enum CircuitProfile {
     KeyPublicize {
         player_count: usize,
     },
+    Attack {
+        player_count: usize,
+    },
+    NightResolution {
+        player_count: usize,
+    },
+    Medium {
+        player_count: usize,
+    },
 }
 
 impl CircuitProfile {
@@ -128,7 +188,10 @@ impl CircuitProfile {
             | Self::Divination { player_count }
             | Self::AnonymousVoting { player_count }
             | Self::WinningJudge { player_count }
-            | Self::KeyPublicize { player_count } => *player_count,
+            | Self::KeyPublicize { player_count }
+            | Self::Attack { player_count }
+            | Self::NightResolution { player_count }
+            | Self::Medium { player_count } => *player_count,
         }
     }
 
@@ -169,6 +232,7 @@ impl CircuitProfile {
             Self::AnonymousVoting { player_count } => (3..=9).contains(player_count),
             Self::WinningJudge { player_count } => (2..=9).contains(player_count),
             Self::KeyPublicize { player_count } => (4..=9).contains(player_count),
+            // Attack / NightResolution / Medium の Solidity verifier は未生成のためオフチェーンのみ
             _ => false,
         }
     }
@@ -190,19 +254,48 @@ impl CircuitEncryptedInputIdentifier {
                     player_count: first.public_input.player_num,
                 })
             }
+            // 死亡者の行も含めて全プレイヤー分の回路になる
             CircuitEncryptedInputIdentifier::AnonymousVoting(items) => {
+                let first = items.first()?;
                 Some(CircuitProfile::AnonymousVoting {
-                    player_count: items.len(),
+                    player_count: first.public_input.player_num,
                 })
             }
             CircuitEncryptedInputIdentifier::WinningJudge(items) => {
-                let player_count = items.len();
-                Some(CircuitProfile::WinningJudge { player_count })
+                let first = items.first()?;
+                Some(CircuitProfile::WinningJudge {
+                    player_count: first.public_input.player_num,
+                })
             }
             CircuitEncryptedInputIdentifier::KeyPublicize(items) => {
                 let player_count = items.len();
                 Some(CircuitProfile::KeyPublicize { player_count })
             }
+            // AnonymousVoting と同じく死亡者の行も含めて全プレイヤー分の回路になる
+            CircuitEncryptedInputIdentifier::Attack(items) => {
+                let first = items.first()?;
+                Some(CircuitProfile::Attack {
+                    player_count: first.public_input.player_num,
+                })
+            }
+            CircuitEncryptedInputIdentifier::NightResolution(items) => {
+                let player_count = items.len();
+                Some(CircuitProfile::NightResolution { player_count })
+            }
+            CircuitEncryptedInputIdentifier::Medium(items) => {
+                let player_count = items.len();
+                Some(CircuitProfile::Medium { player_count })
+            }
+        }
+    }
+    /// Only RoleAssignment, whose instance grows with the player count, can be hashed.
+    pub fn instance_mode(&self) -> InstanceMode {
+        match self {
+            CircuitEncryptedInputIdentifier::RoleAssignment(items) => items
+                .first()
+                .map(|first| first.public_input.instance_mode)
+                .unwrap_or_default(),
+            _ => InstanceMode::Full,
         }
     }
 }
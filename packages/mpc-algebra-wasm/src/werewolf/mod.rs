@@ -1,5 +1,7 @@
+pub mod state_chain;
 pub mod types;
 pub mod utils;
 
+pub use state_chain::*;
 pub use types::*;
 pub use utils::*;
@@ -0,0 +1,221 @@
+use ark_ff::{BigInteger, PrimeField};
+
+// circomlib と同じ Poseidon (x^5, t = 3, R_F = 8, R_P = 57)。
+// 定数は Poseidon 論文の Grain LFSR から生成するので、同じパラメータなら Solidity/JS 側の実装とも一致する。
+pub const POSEIDON_WIDTH: usize = 3;
+pub const POSEIDON_FULL_ROUNDS: usize = 8;
+pub const POSEIDON_PARTIAL_ROUNDS: usize = 57;
+
+/// Domain tag hashed into the state digest so that proofs of different circuits
+/// never produce the same chain link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateChainTag {
+    RoleAssignment = 1,
+    KeyPublicize = 2,
+    Divination = 3,
+    AnonymousVoting = 4,
+    WinningJudgement = 5,
+    Attack = 6,
+    NightResolution = 7,
+    Medium = 8,
+}
+
+#[derive(Debug, Clone)]
+pub struct PoseidonParameters<F: PrimeField> {
+    /// `(R_F + R_P) * t` constants, row-major by round.
+    pub round_constants: Vec<F>,
+    pub mds: [[F; POSEIDON_WIDTH]; POSEIDON_WIDTH],
+}
+
+impl<F: PrimeField> PoseidonParameters<F> {
+    pub fn new() -> Self {
+        let n = F::size_in_bits();
+        let mut grain = GrainLfsr::new(n);
+
+        let num_constants = (POSEIDON_FULL_ROUNDS + POSEIDON_PARTIAL_ROUNDS) * POSEIDON_WIDTH;
+        let mut round_constants = Vec::with_capacity(num_constants);
+        while round_constants.len() < num_constants {
+            // p 以上の値は棄却する
+            let bits = grain.next_field_bits(n);
+            if let Some(c) = F::from_repr(F::BigInt::from_bits_be(&bits)) {
+                round_constants.push(c);
+            }
+        }
+
+        // Cauchy 行列 M[i][j] = 1 / (x_i + y_j)。こちらは棄却せず mod p で還元する
+        let mut sample = || {
+            let bits = grain.next_field_bits(n);
+            F::from_be_bytes_mod_order(&F::BigInt::from_bits_be(&bits).to_bytes_be())
+        };
+        let xs = [sample(), sample(), sample()];
+        let ys = [sample(), sample(), sample()];
+        let mut mds = [[F::zero(); POSEIDON_WIDTH]; POSEIDON_WIDTH];
+        for (row, x) in mds.iter_mut().zip(xs.iter()) {
+            for (entry, y) in row.iter_mut().zip(ys.iter()) {
+                *entry = (*x + y)
+                    .inverse()
+                    .expect("Poseidon MDS entries must be invertible");
+            }
+        }
+
+        Self {
+            round_constants,
+            mds,
+        }
+    }
+
+    pub fn is_full_round(round: usize) -> bool {
+        round < POSEIDON_FULL_ROUNDS / 2
+            || round >= POSEIDON_FULL_ROUNDS / 2 + POSEIDON_PARTIAL_ROUNDS
+    }
+
+    pub fn permute(&self, mut state: [F; POSEIDON_WIDTH]) -> [F; POSEIDON_WIDTH] {
+        let sbox = |x: F| x.square().square() * x;
+        for round in 0..POSEIDON_FULL_ROUNDS + POSEIDON_PARTIAL_ROUNDS {
+            for (i, s) in state.iter_mut().enumerate() {
+                *s += self.round_constants[round * POSEIDON_WIDTH + i];
+            }
+            if Self::is_full_round(round) {
+                state.iter_mut().for_each(|s| *s = sbox(*s));
+            } else {
+                state[0] = sbox(state[0]);
+            }
+            let mut mixed = [F::zero(); POSEIDON_WIDTH];
+            for (i, m) in mixed.iter_mut().enumerate() {
+                for (j, s) in state.iter().enumerate() {
+                    *m += self.mds[i][j] * s;
+                }
+            }
+            state = mixed;
+        }
+        state
+    }
+
+    /// 2-to-1 hash, compatible with circomlib `Poseidon(2)`.
+    pub fn hash(&self, a: F, b: F) -> F {
+        self.permute([F::zero(), a, b])[0]
+    }
+}
+
+impl<F: PrimeField> Default for PoseidonParameters<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Folds `elements` into `prev` one by one: `acc = H(acc, e)`.
+pub fn state_digest<F: PrimeField>(params: &PoseidonParameters<F>, prev: F, elements: &[F]) -> F {
+    elements.iter().fold(prev, |acc, e| params.hash(acc, *e))
+}
+
+/// Single public input of a circuit in `InstanceMode::Hashed`. The fold starts from the
+/// instance length so that instances differing only in trailing zeros hash differently.
+pub fn instance_hash<F: PrimeField>(params: &PoseidonParameters<F>, instance: &[F]) -> F {
+    state_digest(params, F::from(instance.len() as u64), instance)
+}
+
+/// Digest of a list of commitments given as affine `(x, y)` pairs. An empty list hashes to zero.
+pub fn commitments_digest<F: PrimeField>(params: &PoseidonParameters<F>, points: &[(F, F)]) -> F {
+    points.iter().fold(F::zero(), |acc, (x, y)| {
+        params.hash(params.hash(acc, *x), *y)
+    })
+}
+
+/// 32-byte big-endian form, identical to the uint256 word of the proof's public inputs.
+pub fn digest_to_bytes<F: PrimeField>(digest: F) -> [u8; 32] {
+    let bytes = digest.into_repr().to_bytes_be();
+    let mut out = [0u8; 32];
+    out[32 - bytes.len()..].copy_from_slice(&bytes);
+    out
+}
+
+pub fn digest_from_bytes<F: PrimeField>(bytes: &[u8; 32]) -> F {
+    F::from_be_bytes_mod_order(bytes)
+}
+
+struct GrainLfsr {
+    state: Vec<bool>,
+}
+
+impl GrainLfsr {
+    fn new(field_bits: usize) -> Self {
+        let mut state = Vec::with_capacity(80);
+        let mut push = |value: usize, len: usize| {
+            for i in (0..len).rev() {
+                state.push((value >> i) & 1 == 1);
+            }
+        };
+        push(1, 2); // prime field
+        push(0, 4); // x^alpha S-box
+        push(field_bits, 12);
+        push(POSEIDON_WIDTH, 12);
+        push(POSEIDON_FULL_ROUNDS, 10);
+        push(POSEIDON_PARTIAL_ROUNDS, 10);
+        push((1 << 30) - 1, 30);
+
+        let mut lfsr = Self { state };
+        for _ in 0..160 {
+            lfsr.step();
+        }
+        lfsr
+    }
+
+    fn step(&mut self) -> bool {
+        let s = &self.state;
+        let bit = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0];
+        self.state.remove(0);
+        self.state.push(bit);
+        bit
+    }
+
+    // ビットを 2 つずつ読み、先頭が 1 のときだけ 2 つ目を出力する
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let keep = self.step();
+            let bit = self.step();
+            if keep {
+                return bit;
+            }
+        }
+    }
+
+    fn next_field_bits(&mut self, n: usize) -> Vec<bool> {
+        (0..n).map(|_| self.next_bit()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use std::str::FromStr;
+
+    #[test]
+    fn poseidon_matches_circomlib() {
+        let params = PoseidonParameters::<Fr>::new();
+        // circomlibjs: poseidon([1, 2])
+        let expected = Fr::from_str(
+            "7853200120776062878684798364095072458815029376092732009249414926327459813530",
+        )
+        .unwrap();
+        assert_eq!(params.hash(Fr::from(1u32), Fr::from(2u32)), expected);
+    }
+
+    #[test]
+    fn digest_bytes_roundtrip() {
+        let params = PoseidonParameters::<Fr>::new();
+        let digest = state_digest(&params, Fr::from(0u32), &[Fr::from(4u32), Fr::from(7u32)]);
+        assert_eq!(digest_from_bytes::<Fr>(&digest_to_bytes(digest)), digest);
+    }
+
+    #[test]
+    fn instance_hash_binds_length() {
+        let params = PoseidonParameters::<Fr>::new();
+        let instance = [Fr::from(3u32), Fr::from(5u32)];
+        let padded = [Fr::from(3u32), Fr::from(5u32), Fr::from(0u32)];
+        assert_ne!(
+            instance_hash(&params, &instance),
+            instance_hash(&params, &padded)
+        );
+    }
+}
@@ -8,6 +8,9 @@ pub enum Role {
     FortuneTeller,
     Werewolf,
     Villager,
+    Medium,
+    Possessed,
+    Guard,
 }
 
 impl Role {
@@ -16,12 +19,20 @@ impl Role {
             Role::Villager => "Villager: They have no special abilities, but they participate in discussions and voting.",
             Role::Werewolf => "Werewolf: They attack villagers at night. They pretend to be villagers during the day.",
             Role::FortuneTeller => "Fortune Teller: They can know whether a player is a werewolf or not at night.",
+            Role::Medium => "Medium: They can know whether the executed player was a werewolf or not.",
+            Role::Possessed => "Possessed: They are human, but they win together with the werewolves.",
+            Role::Guard => "Guard: They protect one player from the werewolves' attack each night.",
         }
     }
 
     pub fn is_werewolf(&self) -> bool {
         matches!(self, Role::Werewolf)
     }
+
+    // 占いや勝利判定の人数では人間として扱われるが、勝敗は人狼陣営として決まる
+    pub fn is_werewolf_team(&self) -> bool {
+        matches!(self, Role::Werewolf | Role::Possessed)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +47,7 @@ impl GroupingParameter {
     /// Rules:
     /// - FortuneTeller: 1
     /// - Werewolf: 4-6 players → 1, 7-9 players → 2, 10-12 players → 3
+    /// - Medium: 7 players or more → 1
     /// - Villager: remaining players
     pub fn from_player_count(num_players: usize) -> Self {
         assert!(num_players >= 4, "At least 4 players are required");
@@ -50,7 +62,8 @@ impl GroupingParameter {
         };
 
         let num_fortune_tellers = 1;
-        let num_villagers = num_players - num_werewolves - num_fortune_tellers;
+        let num_mediums = if num_players >= 7 { 1 } else { 0 };
+        let num_villagers = num_players - num_werewolves - num_fortune_tellers - num_mediums;
 
         let mut map = BTreeMap::new();
 
@@ -63,6 +76,11 @@ impl GroupingParameter {
         // Villager: multiple people, not a group
         map.insert(Role::Villager, (num_villagers, false));
 
+        // Medium: 1 person, not a group
+        if num_mediums > 0 {
+            map.insert(Role::Medium, (num_mediums, false));
+        }
+
         Self(map)
     }
 
@@ -141,6 +159,18 @@ impl GroupingParameter {
         self.get_role_count(Role::Werewolf)
     }
 
+    pub fn get_medium_count(&self) -> usize {
+        self.get_role_count(Role::Medium)
+    }
+
+    pub fn get_possessed_count(&self) -> usize {
+        self.get_role_count(Role::Possessed)
+    }
+
+    pub fn get_guard_count(&self) -> usize {
+        self.get_role_count(Role::Guard)
+    }
+
     pub fn get_corresponding_role(&self, role_id: usize) -> Role {
         let mut count = self.get_num_players();
         for (role, (role_count, is_not_alone)) in self.0.iter() {
@@ -153,3 +183,92 @@ impl GroupingParameter {
         panic!("Error: Invalid role id is given");
     }
 }
+
+/// Win condition rules given to WinningJudgementCircuit as public inputs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WinConditionParameter {
+    /// false: werewolves win when they are at least as many as humans (parity).
+    /// true: werewolves win only when they outnumber humans (majority).
+    #[serde(default)]
+    pub werewolf_win_on_majority: bool,
+    /// Villagers win when the game reaches this day without a decision. 0 means no limit.
+    #[serde(default)]
+    pub day_limit: u32,
+}
+
+impl WinConditionParameter {
+    pub fn is_day_limit_reached(&self, day_count: u32) -> bool {
+        self.day_limit != 0 && day_count >= self.day_limit
+    }
+}
+
+/// Game state every circuit links its proof to. The circuit takes `prev_state_digest`
+/// as a public input and outputs the next digest over this state and its result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateChainParameter {
+    /// Digest output by the previous proof, 32-byte big-endian. All zero before the first proof.
+    #[serde(default)]
+    pub prev_state_digest: [u8; 32],
+    #[serde(default)]
+    pub day_count: u32,
+    /// 生存者の bitmask (bit i = プレイヤー i)
+    #[serde(default)]
+    pub alive_mask: u32,
+}
+
+/// How a circuit exposes its instance to the Groth16 verifier.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceMode {
+    /// Every instance element is a public input.
+    #[default]
+    Full,
+    /// The only public input is [`instance_hash`](crate::instance_hash) of the full instance.
+    /// The calldata still carries the full instance and the Solidity verifier re-hashes it.
+    Hashed,
+}
+
+/// Proof system the nodes prove with. The on-chain verifier is picked by the same tag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofScheme {
+    #[default]
+    Groth16,
+    /// Marlin with the KZG-based `marlin_pc` commitment and a Keccak256 transcript.
+    Marlin,
+}
+
+/// game_state output of WinningJudgementCircuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WinningJudgeOutcome {
+    WerewolfWin = 1,
+    VillagerWin = 2,
+    Continue = 3,
+    /// A surviving fox takes the win from whichever side would have won.
+    FoxWin = 4,
+    VillagerWinByDayLimit = 5,
+}
+
+impl WinningJudgeOutcome {
+    pub const ALL: [WinningJudgeOutcome; 5] = [
+        WinningJudgeOutcome::WerewolfWin,
+        WinningJudgeOutcome::VillagerWin,
+        WinningJudgeOutcome::Continue,
+        WinningJudgeOutcome::FoxWin,
+        WinningJudgeOutcome::VillagerWinByDayLimit,
+    ];
+
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+
+    pub fn from_field<F: PrimeField>(value: F) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|outcome| F::from(outcome.code()) == value)
+    }
+
+    pub fn is_game_over(self) -> bool {
+        self != WinningJudgeOutcome::Continue
+    }
+}
@@ -9,14 +9,20 @@ use zk_mpc::{
 };
 
 pub mod anonymous_voting;
+pub mod attack;
 pub mod divination;
 pub mod key_publicize;
+pub mod medium;
+pub mod night_resolution;
 pub mod role_assignment;
 pub mod winning_judgement;
 
 pub use anonymous_voting::*;
+pub use attack::*;
 pub use divination::*;
 pub use key_publicize::*;
+pub use medium::*;
+pub use night_resolution::*;
 pub use role_assignment::*;
 pub use winning_judgement::*;
 
@@ -28,6 +34,9 @@ pub enum BuiltinCircuit<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
     WinningJudge(WinningJudgementCircuit<F>),
     RoleAssignment(RoleAssignmentCircuit<F>),
     KeyPublicize(KeyPublicizeCircuit<F>),
+    Attack(AttackCircuit<F>),
+    NightResolution(NightResolutionCircuit<F>),
+    Medium(MediumCircuit<F>),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -46,6 +55,9 @@ impl<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> std::fmt::Debug for B
             BuiltinCircuit::WinningJudge(_) => write!(f, "WinningJudge"),
             BuiltinCircuit::RoleAssignment(_) => write!(f, "RoleAssignment"),
             BuiltinCircuit::KeyPublicize(_) => write!(f, "KeyPublicize"),
+            BuiltinCircuit::Attack(_) => write!(f, "Attack"),
+            BuiltinCircuit::NightResolution(_) => write!(f, "NightResolution"),
+            BuiltinCircuit::Medium(_) => write!(f, "Medium"),
         }
     }
 }
@@ -59,6 +71,9 @@ impl ConstraintSynthesizer<MFr> for BuiltinCircuit<MFr> {
             Self::WinningJudge(c) => c.generate_constraints(cs),
             Self::RoleAssignment(c) => c.generate_constraints(cs),
             Self::KeyPublicize(c) => c.generate_constraints(cs),
+            Self::Attack(c) => c.generate_constraints(cs),
+            Self::NightResolution(c) => c.generate_constraints(cs),
+            Self::Medium(c) => c.generate_constraints(cs),
         }
     }
 }
@@ -72,6 +87,9 @@ impl ConstraintSynthesizer<Fr> for BuiltinCircuit<Fr> {
             Self::WinningJudge(c) => c.generate_constraints(cs),
             Self::RoleAssignment(c) => c.generate_constraints(cs),
             Self::KeyPublicize(c) => c.generate_constraints(cs),
+            Self::Attack(c) => c.generate_constraints(cs),
+            Self::NightResolution(c) => c.generate_constraints(cs),
+            Self::Medium(c) => c.generate_constraints(cs),
         }
     }
 }
@@ -0,0 +1,11 @@
+use ark_ff::PrimeField;
+use serde::{Deserialize, Serialize};
+use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
+
+use crate::{AttackPrivateInput, AttackPublicInput};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AttackCircuit<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
+    pub private_input: Vec<AttackPrivateInput<F>>,
+    pub public_input: AttackPublicInput<F>,
+}
@@ -0,0 +1,11 @@
+use ark_ff::PrimeField;
+use serde::{Deserialize, Serialize};
+use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
+
+use crate::{MediumPrivateInput, MediumPublicInput};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MediumCircuit<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
+    pub private_input: Vec<MediumPrivateInput<F>>,
+    pub public_input: MediumPublicInput<F>,
+}
@@ -0,0 +1,11 @@
+use ark_ff::PrimeField;
+use serde::{Deserialize, Serialize};
+use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
+
+use crate::{NightResolutionPrivateInput, NightResolutionPublicInput};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NightResolutionCircuit<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
+    pub private_input: Vec<NightResolutionPrivateInput<F>>,
+    pub public_input: NightResolutionPublicInput<F>,
+}
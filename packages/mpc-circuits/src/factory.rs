@@ -1,5 +1,5 @@
 use ark_bn254::Fr;
-use ark_ff::PrimeField;
+use ark_ff::{PrimeField, Zero};
 use ark_serialize::CanonicalSerialize;
 use ark_std::{test_rng, UniformRand};
 use mpc_algebra::reveal::Reveal;
@@ -9,10 +9,12 @@ use zk_mpc::circuits::ElGamalLocalOrMPC;
 use zk_mpc::{circuits::LocalOrMPC, marlin::MFr};
 
 use mpc_algebra_wasm::{
-    AnonymousVotingEncryption, CircuitEncryptedInputIdentifier, DivinationEncryption,
-    KeyPublicizeEncryption, RoleAssignmentEncryption, SplitAndEncrypt, WinningJudgementEncryption,
+    AnonymousVotingEncryption, AttackEncryption, CircuitEncryptedInputIdentifier,
+    DivinationEncryption, KeyPublicizeEncryption, MediumEncryption, NightResolutionEncryption,
+    RoleAssignmentEncryption, SplitAndEncrypt, StateChainTag, WinningJudgementEncryption,
 };
 
+use crate::traits::circuits::CommitmentPoints;
 use crate::*;
 
 pub struct CircuitFactory;
@@ -24,6 +26,8 @@ struct RoleAssignmentOutputShare {
     role_share_encoding: &'static str,
     werewolf_mates_mask_share: String,
     werewolf_mates_mask_share_encoding: &'static str,
+    role_flags_share: String,
+    role_flags_share_encoding: &'static str,
 }
 
 impl CircuitFactory {
@@ -33,16 +37,16 @@ impl CircuitFactory {
         match circuit_type {
             CircuitEncryptedInputIdentifier::Divination(c) => {
                 let player_num = c[0].public_input.player_num;
-                let alive_player_num = c.len();
                 let rng = &mut test_rng();
 
                 let elgamal_randomness =
                     <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalRandomness::rand(rng);
 
+                // 死亡者も含めて全プレイヤー分の行を持つ
                 BuiltinCircuit::Divination(DivinationCircuit {
-                    private_input: (0..alive_player_num)
-                        .map(|_| DivinationPrivateInput::<Fr> {
-                            id: 0,
+                    private_input: (0..player_num)
+                        .map(|id| DivinationPrivateInput::<Fr> {
+                            id,
                             is_werewolf: Fr::default(),
                             is_target: vec![Fr::default(); player_num],
                             randomness: elgamal_randomness.clone(),
@@ -53,17 +57,17 @@ impl CircuitFactory {
                         elgamal_param: c[0].public_input.elgamal_param.clone(),
                         pub_key: c[0].public_input.pub_key,
                         player_num,
+                        state_chain: c[0].public_input.state_chain,
                     },
                 })
             }
             CircuitEncryptedInputIdentifier::AnonymousVoting(c) => {
                 let player_num = c[0].public_input.player_num;
-                let alive_player_num = c.len();
 
                 BuiltinCircuit::AnonymousVoting(AnonymousVotingCircuit {
-                    private_input: (0..alive_player_num)
-                        .map(|_| AnonymousVotingPrivateInput::<Fr> {
-                            id: 0,
+                    private_input: (0..player_num)
+                        .map(|id| AnonymousVotingPrivateInput::<Fr> {
+                            id,
                             is_target_id: vec![Fr::default(); player_num],
                             player_randomness: Fr::default(),
                         })
@@ -72,23 +76,32 @@ impl CircuitFactory {
                         pedersen_param: c[0].public_input.pedersen_param.clone(),
                         player_commitment: c[0].public_input.player_commitment.clone(),
                         player_num,
+                        state_chain: c[0].public_input.state_chain,
                     },
                 })
             }
             CircuitEncryptedInputIdentifier::WinningJudge(ref c) => {
-                let alive_player_num = c.len();
+                let player_num = c[0].public_input.player_num;
+
+                let rng = &mut test_rng();
 
                 BuiltinCircuit::WinningJudge(WinningJudgementCircuit {
-                    private_input: (0..alive_player_num)
-                        .map(|_| WinningJudgementPrivateInput::<Fr> {
-                            id: 0,
+                    private_input: (0..player_num)
+                        .map(|id| WinningJudgementPrivateInput::<Fr> {
+                            id,
                             am_werewolf: Fr::default(),
-                            player_randomness: Fr::default(),
+                            am_werewolf_team: Fr::default(),
+                            am_fox: Fr::default(),
+                            role_id: Fr::default(),
+                            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng),
                         })
                         .collect::<Vec<_>>(),
                     public_input: WinningJudgementPublicInput::<Fr> {
                         pedersen_param: c[0].public_input.pedersen_param.clone(),
-                        player_commitment: c[0].public_input.player_commitment.clone(),
+                        player_num,
+                        win_condition: c[0].public_input.win_condition,
+                        role_commitment: c[0].public_input.role_commitment.clone(),
+                        state_chain: c[0].public_input.state_chain,
                     },
                 })
             }
@@ -105,34 +118,127 @@ impl CircuitFactory {
                     private_input: (0..player_num)
                         .map(|_| RoleAssignmentPrivateInput::<Fr> {
                             id: 0,
-                            shuffle_matrices: nalgebra::DMatrix::<Fr>::zeros(n + m, n + m),
+                            shuffle_matrices: nalgebra::DMatrix::<Fr>::identity(n + m, n + m),
                             player_randomness: Fr::default(),
                             randomness:
                                 ark_crypto_primitives::commitment::pedersen::Randomness::rand(
                                     &mut rng,
                                 ),
+                            player_commitment_randomness:
+                                ark_crypto_primitives::commitment::pedersen::Randomness::rand(
+                                    &mut rng,
+                                ),
                         })
                         .collect::<Vec<_>>(),
                     public_input: RoleAssignmentPublicInput::<Fr> {
                         num_players: public_input.num_players,
                         max_group_size: public_input.num_players,
                         tau_matrix: public_input.tau_matrix,
-                        role_commitment: public_input.role_commitment,
+                        // 役職コミットメントはノードが計算するので、鍵生成には形だけ合わせる
+                        role_commitment: vec![Default::default(); public_input.num_players],
                         player_commitment: public_input.player_commitment,
                         pedersen_param: public_input.pedersen_param,
                         grouping_parameter: public_input.grouping_parameter.clone(),
+                        state_chain: public_input.state_chain,
+                        instance_mode: public_input.instance_mode,
                     },
                 })
             }
             CircuitEncryptedInputIdentifier::KeyPublicize(ref c) => {
                 let alive_player_num = c.len();
 
+                let rng = &mut test_rng();
+
                 BuiltinCircuit::KeyPublicize(KeyPublicizeCircuit {
                     private_input: (0..alive_player_num)
-                        .map(|_| KeyPublicizePrivateInput::<Fr>::default())
+                        .map(|id| KeyPublicizePrivateInput::<Fr> {
+                            id,
+                            pub_key_or_dummy_x: Fr::default(),
+                            pub_key_or_dummy_y: Fr::default(),
+                            role_id: Fr::default(),
+                            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng),
+                        })
                         .collect::<Vec<_>>(),
                     public_input: KeyPublicizePublicInput::<Fr> {
                         pedersen_param: c[0].public_input.pedersen_param.clone(),
+                        role_commitment: c[0].public_input.role_commitment.clone(),
+                        state_chain: c[0].public_input.state_chain,
+                    },
+                })
+            }
+            CircuitEncryptedInputIdentifier::Attack(c) => {
+                let player_num = c[0].public_input.player_num;
+
+                let rng = &mut test_rng();
+
+                BuiltinCircuit::Attack(AttackCircuit {
+                    private_input: (0..player_num)
+                        .map(|id| AttackPrivateInput::<Fr> {
+                            id,
+                            is_target_id: vec![Fr::default(); player_num],
+                            role_id: Fr::default(),
+                            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng),
+                        })
+                        .collect::<Vec<_>>(),
+                    public_input: AttackPublicInput::<Fr> {
+                        pedersen_param: c[0].public_input.pedersen_param.clone(),
+                        player_num,
+                        role_commitment: c[0].public_input.role_commitment.clone(),
+                        state_chain: c[0].public_input.state_chain,
+                    },
+                })
+            }
+            CircuitEncryptedInputIdentifier::NightResolution(c) => {
+                let player_num = c[0].public_input.player_num;
+                let alive_player_num = c.len();
+
+                let rng = &mut test_rng();
+
+                BuiltinCircuit::NightResolution(NightResolutionCircuit {
+                    private_input: (0..alive_player_num)
+                        .map(|id| NightResolutionPrivateInput::<Fr> {
+                            id,
+                            is_attack_target_id: vec![Fr::default(); player_num],
+                            is_guard_target_id: vec![Fr::default(); player_num],
+                            role_id: Fr::default(),
+                            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(rng),
+                        })
+                        .collect::<Vec<_>>(),
+                    public_input: NightResolutionPublicInput::<Fr> {
+                        pedersen_param: c[0].public_input.pedersen_param.clone(),
+                        player_num,
+                        role_commitment: c[0].public_input.role_commitment.clone(),
+                        state_chain: c[0].public_input.state_chain,
+                    },
+                })
+            }
+            CircuitEncryptedInputIdentifier::Medium(c) => {
+                let player_num = c[0].public_input.player_num;
+                let alive_player_num = c.len();
+                let rng = &mut test_rng();
+
+                let elgamal_randomness =
+                    <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalRandomness::rand(rng);
+
+                BuiltinCircuit::Medium(MediumCircuit {
+                    private_input: (0..alive_player_num)
+                        .map(|id| MediumPrivateInput::<Fr> {
+                            id,
+                            role_id: Fr::default(),
+                            role_randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(
+                                rng,
+                            ),
+                            randomness: elgamal_randomness.clone(),
+                        })
+                        .collect::<Vec<_>>(),
+                    public_input: MediumPublicInput::<Fr> {
+                        pedersen_param: c[0].public_input.pedersen_param.clone(),
+                        elgamal_param: c[0].public_input.elgamal_param.clone(),
+                        pub_key: c[0].public_input.pub_key,
+                        player_num,
+                        executed_id: c[0].public_input.executed_id,
+                        role_commitment: c[0].public_input.role_commitment.clone(),
+                        state_chain: c[0].public_input.state_chain,
                     },
                 })
             }
@@ -175,6 +281,23 @@ impl CircuitFactory {
                     });
                 }
 
+                // 暗号化には先頭行の randomness を使うので、死亡者の行にも提出済みのものを入れる
+                let randomness = private_input[0].randomness.clone();
+                let private_input = pad_dead_rows(
+                    private_input,
+                    circuit[0].public_input.player_num,
+                    |input| input.id,
+                    |id| DivinationPrivateInput::<MFr> {
+                        id,
+                        is_target: vec![
+                            MFr::from_add_shared(Fr::zero());
+                            circuit[0].public_input.player_num
+                        ],
+                        is_werewolf: MFr::from_add_shared(Fr::zero()),
+                        randomness: randomness.clone(),
+                    },
+                );
+
                 BuiltinCircuit::Divination(DivinationCircuit {
                     private_input,
                     public_input: DivinationPublicInput::<MFr> {
@@ -188,6 +311,7 @@ impl CircuitFactory {
                             circuit[0].public_input.pub_key,
                         ),
                         player_num: circuit[0].public_input.player_num,
+                        state_chain: circuit[0].public_input.state_chain,
                     },
                 })
             }
@@ -221,6 +345,20 @@ impl CircuitFactory {
                     });
                 }
 
+                let private_input = pad_dead_rows(
+                    private_input,
+                    circuit[0].public_input.player_num,
+                    |input| input.id,
+                    |id| AnonymousVotingPrivateInput::<MFr> {
+                        id,
+                        is_target_id: vec![
+                            MFr::from_add_shared(Fr::zero());
+                            circuit[0].public_input.player_num
+                        ],
+                        player_randomness: MFr::from_add_shared(Fr::zero()),
+                    },
+                );
+
                 BuiltinCircuit::AnonymousVoting(AnonymousVotingCircuit {
                     private_input,
                     public_input: AnonymousVotingPublicInput::<MFr> {
@@ -234,6 +372,7 @@ impl CircuitFactory {
                             .map(|c| <MFr as LocalOrMPC<MFr>>::PedersenCommitment::from_local(&c))
                             .collect::<Vec<_>>(),
                         player_num: circuit[0].public_input.player_num,
+                        state_chain: circuit[0].public_input.state_chain,
                     },
                 })
             }
@@ -259,22 +398,46 @@ impl CircuitFactory {
                     private_input.push(WinningJudgementPrivateInput::<MFr> {
                         id: decrypted_input.id,
                         am_werewolf: MFr::from_add_shared(decrypted_input.am_werewolf),
-                        player_randomness: MFr::from_add_shared(decrypted_input.player_randomness),
+                        am_werewolf_team: MFr::from_add_shared(decrypted_input.am_werewolf_team),
+                        am_fox: MFr::from_add_shared(decrypted_input.am_fox),
+                        role_id: MFr::from_add_shared(decrypted_input.role_id),
+                        randomness: <MFr as LocalOrMPC<MFr>>::PedersenRandomness::from_add_shared(
+                            decrypted_input.randomness,
+                        ),
                     });
                 }
 
+                // 死亡者の行は role_commitment を開示しないので、randomness は提出済みのものでよい
+                let randomness = private_input[0].randomness.clone();
+                let private_input = pad_dead_rows(
+                    private_input,
+                    circuit[0].public_input.player_num,
+                    |input| input.id,
+                    |id| WinningJudgementPrivateInput::<MFr> {
+                        id,
+                        am_werewolf: MFr::from_add_shared(Fr::zero()),
+                        am_werewolf_team: MFr::from_add_shared(Fr::zero()),
+                        am_fox: MFr::from_add_shared(Fr::zero()),
+                        role_id: MFr::from_add_shared(Fr::zero()),
+                        randomness: randomness.clone(),
+                    },
+                );
+
                 BuiltinCircuit::WinningJudge(WinningJudgementCircuit {
                     private_input,
                     public_input: WinningJudgementPublicInput::<MFr> {
                         pedersen_param: <MFr as LocalOrMPC<MFr>>::PedersenParam::from_local(
                             &circuit[0].public_input.pedersen_param,
                         ),
-                        player_commitment: circuit[0]
+                        player_num: circuit[0].public_input.player_num,
+                        win_condition: circuit[0].public_input.win_condition,
+                        role_commitment: circuit[0]
                             .public_input
-                            .player_commitment
+                            .role_commitment
                             .iter()
                             .map(|c| <MFr as LocalOrMPC<MFr>>::PedersenCommitment::from_local(&c))
                             .collect::<Vec<_>>(),
+                        state_chain: circuit[0].public_input.state_chain,
                     },
                 })
             }
@@ -302,24 +465,22 @@ impl CircuitFactory {
                         randomness: <MFr as LocalOrMPC<MFr>>::PedersenRandomness::from_add_shared(
                             decrypted_input.randomness,
                         ),
+                        player_commitment_randomness:
+                            <MFr as LocalOrMPC<MFr>>::PedersenRandomness::from_add_shared(
+                                decrypted_input.player_commitment_randomness,
+                            ),
                     });
                 }
 
                 let grouping_parameter = circuit[0].public_input.grouping_parameter.clone();
 
-                BuiltinCircuit::RoleAssignment(RoleAssignmentCircuit {
+                let mut role_assignment = RoleAssignmentCircuit {
                     private_input,
                     public_input: RoleAssignmentPublicInput::<MFr> {
                         num_players: circuit[0].public_input.num_players,
                         max_group_size: circuit[0].public_input.max_group_size,
                         tau_matrix: grouping_parameter.generate_tau_matrix(),
-                        role_commitment: circuit[0]
-                            .public_input
-                            .role_commitment
-                            .iter()
-                            .map(|c| <MFr as LocalOrMPC<MFr>>::PedersenCommitment::from_local(&c))
-                            .collect::<Vec<_>>(),
-
+                        role_commitment: Vec::new(),
                         player_commitment: circuit[0]
                             .public_input
                             .player_commitment
@@ -330,8 +491,16 @@ impl CircuitFactory {
                             &circuit[0].public_input.pedersen_param,
                         ),
                         grouping_parameter,
+                        state_chain: circuit[0].public_input.state_chain,
+                        instance_mode: circuit[0].public_input.instance_mode,
                     },
-                })
+                };
+                // 役職IDは誰も知らないので、コミットメントは各プレイヤーのランダムネスから MPC で作る。
+                // リクエストの role_commitment は使わない
+                role_assignment.public_input.role_commitment =
+                    role_assignment.calculate_role_commitments();
+
+                BuiltinCircuit::RoleAssignment(role_assignment)
             }
             CircuitEncryptedInputIdentifier::KeyPublicize(circuit) => {
                 // private_input部分は復号化してそのまま入れるイメージ。
@@ -360,7 +529,10 @@ impl CircuitFactory {
                         pub_key_or_dummy_y: MFr::from_add_shared(
                             decrypted_input.pub_key_or_dummy_y,
                         ),
-                        is_fortune_teller: MFr::from_add_shared(decrypted_input.is_fortune_teller),
+                        role_id: MFr::from_add_shared(decrypted_input.role_id),
+                        randomness: <MFr as LocalOrMPC<MFr>>::PedersenRandomness::from_add_shared(
+                            decrypted_input.randomness,
+                        ),
                     });
                 }
 
@@ -370,6 +542,180 @@ impl CircuitFactory {
                         pedersen_param: <MFr as LocalOrMPC<MFr>>::PedersenParam::from_local(
                             &circuit[0].public_input.pedersen_param,
                         ),
+                        role_commitment: circuit[0]
+                            .public_input
+                            .role_commitment
+                            .iter()
+                            .map(|c| <MFr as LocalOrMPC<MFr>>::PedersenCommitment::from_local(&c))
+                            .collect::<Vec<_>>(),
+                        state_chain: circuit[0].public_input.state_chain,
+                    },
+                })
+            }
+            CircuitEncryptedInputIdentifier::Attack(circuit) => {
+                let mut private_input = Vec::new();
+
+                for i in 0..circuit.len() {
+                    let private_encrypted_input = circuit[i]
+                        .shares
+                        .iter()
+                        .find(|share| share.node_id == my_node_id)
+                        .expect("No share found for this node");
+
+                    // mpc-algebra-wasmにおけるcreate_encrypted_sharesの反転が必要。
+                    let decrypted_input =
+                        AttackEncryption::decrypt(private_encrypted_input, secret_key)
+                            .expect("Failed to decrypt input");
+
+                    private_input.push(AttackPrivateInput::<MFr> {
+                        id: decrypted_input.id,
+                        is_target_id: decrypted_input
+                            .is_target_id
+                            .iter()
+                            .map(|&x| MFr::from_add_shared(x))
+                            .collect(),
+                        role_id: MFr::from_add_shared(decrypted_input.role_id),
+                        randomness: <MFr as LocalOrMPC<MFr>>::PedersenRandomness::from_add_shared(
+                            decrypted_input.randomness,
+                        ),
+                    });
+                }
+
+                // 死亡者の行は role_commitment を開示しないので、randomness は提出済みのものでよい
+                let randomness = private_input[0].randomness.clone();
+                let private_input = pad_dead_rows(
+                    private_input,
+                    circuit[0].public_input.player_num,
+                    |input| input.id,
+                    |id| AttackPrivateInput::<MFr> {
+                        id,
+                        is_target_id: vec![
+                            MFr::from_add_shared(Fr::zero());
+                            circuit[0].public_input.player_num
+                        ],
+                        role_id: MFr::from_add_shared(Fr::zero()),
+                        randomness: randomness.clone(),
+                    },
+                );
+
+                BuiltinCircuit::Attack(AttackCircuit {
+                    private_input,
+                    public_input: AttackPublicInput::<MFr> {
+                        pedersen_param: <MFr as LocalOrMPC<MFr>>::PedersenParam::from_local(
+                            &circuit[0].public_input.pedersen_param,
+                        ),
+                        player_num: circuit[0].public_input.player_num,
+                        role_commitment: circuit[0]
+                            .public_input
+                            .role_commitment
+                            .iter()
+                            .map(|c| <MFr as LocalOrMPC<MFr>>::PedersenCommitment::from_local(&c))
+                            .collect::<Vec<_>>(),
+                        state_chain: circuit[0].public_input.state_chain,
+                    },
+                })
+            }
+            CircuitEncryptedInputIdentifier::NightResolution(circuit) => {
+                let mut private_input = Vec::new();
+
+                for i in 0..circuit.len() {
+                    let private_encrypted_input = circuit[i]
+                        .shares
+                        .iter()
+                        .find(|share| share.node_id == my_node_id)
+                        .expect("No share found for this node");
+
+                    // mpc-algebra-wasmにおけるcreate_encrypted_sharesの反転が必要。
+                    let decrypted_input =
+                        NightResolutionEncryption::decrypt(private_encrypted_input, secret_key)
+                            .expect("Failed to decrypt input");
+
+                    private_input.push(NightResolutionPrivateInput::<MFr> {
+                        id: decrypted_input.id,
+                        is_attack_target_id: decrypted_input
+                            .is_attack_target_id
+                            .iter()
+                            .map(|&x| MFr::from_add_shared(x))
+                            .collect(),
+                        is_guard_target_id: decrypted_input
+                            .is_guard_target_id
+                            .iter()
+                            .map(|&x| MFr::from_add_shared(x))
+                            .collect(),
+                        role_id: MFr::from_add_shared(decrypted_input.role_id),
+                        randomness: <MFr as LocalOrMPC<MFr>>::PedersenRandomness::from_add_shared(
+                            decrypted_input.randomness,
+                        ),
+                    });
+                }
+
+                BuiltinCircuit::NightResolution(NightResolutionCircuit {
+                    private_input,
+                    public_input: NightResolutionPublicInput::<MFr> {
+                        pedersen_param: <MFr as LocalOrMPC<MFr>>::PedersenParam::from_local(
+                            &circuit[0].public_input.pedersen_param,
+                        ),
+                        player_num: circuit[0].public_input.player_num,
+                        role_commitment: circuit[0]
+                            .public_input
+                            .role_commitment
+                            .iter()
+                            .map(|c| <MFr as LocalOrMPC<MFr>>::PedersenCommitment::from_local(&c))
+                            .collect::<Vec<_>>(),
+                        state_chain: circuit[0].public_input.state_chain,
+                    },
+                })
+            }
+            CircuitEncryptedInputIdentifier::Medium(circuit) => {
+                let mut private_input = Vec::new();
+
+                for i in 0..circuit.len() {
+                    let private_encrypted_input = circuit[i]
+                        .shares
+                        .iter()
+                        .find(|share| share.node_id == my_node_id)
+                        .expect("No share found for this node");
+
+                    // mpc-algebra-wasmにおけるcreate_encrypted_sharesの反転が必要。
+                    let decrypted_input =
+                        MediumEncryption::decrypt(private_encrypted_input, secret_key)
+                            .expect("Failed to decrypt input");
+
+                    private_input.push(MediumPrivateInput::<MFr> {
+                        id: decrypted_input.id,
+                        role_id: MFr::from_add_shared(decrypted_input.role_id),
+                        role_randomness:
+                            <MFr as LocalOrMPC<MFr>>::PedersenRandomness::from_add_shared(
+                                decrypted_input.role_randomness,
+                            ),
+                        randomness:
+                            <MFr as ElGamalLocalOrMPC<MFr>>::ElGamalRandomness::from_add_shared(
+                                decrypted_input.randomness,
+                            ),
+                    });
+                }
+
+                BuiltinCircuit::Medium(MediumCircuit {
+                    private_input,
+                    public_input: MediumPublicInput::<MFr> {
+                        pedersen_param: <MFr as LocalOrMPC<MFr>>::PedersenParam::from_local(
+                            &circuit[0].public_input.pedersen_param,
+                        ),
+                        elgamal_param: <MFr as ElGamalLocalOrMPC<MFr>>::ElGamalParam::from_public(
+                            circuit[0].public_input.elgamal_param.clone(),
+                        ),
+                        pub_key: <MFr as ElGamalLocalOrMPC<MFr>>::ElGamalPubKey::from_public(
+                            circuit[0].public_input.pub_key,
+                        ),
+                        player_num: circuit[0].public_input.player_num,
+                        executed_id: circuit[0].public_input.executed_id,
+                        role_commitment: circuit[0]
+                            .public_input
+                            .role_commitment
+                            .iter()
+                            .map(|c| <MFr as LocalOrMPC<MFr>>::PedersenCommitment::from_local(&c))
+                            .collect::<Vec<_>>(),
+                        state_chain: circuit[0].public_input.state_chain,
                     },
                 })
             }
@@ -386,6 +732,7 @@ impl CircuitFactory {
 
                 let revealed_is_target_werewolf = is_target_werewolf.sync_reveal();
 
+                inputs.push(Fr::from(circuit.public_input.state_chain.alive_mask));
                 inputs.push(circuit.public_input.elgamal_param.generator.sync_reveal().x);
                 inputs.push(circuit.public_input.elgamal_param.generator.sync_reveal().y);
 
@@ -397,52 +744,191 @@ impl CircuitFactory {
                 inputs.push(revealed_is_target_werewolf.0.y);
                 inputs.push(revealed_is_target_werewolf.1.x);
                 inputs.push(revealed_is_target_werewolf.1.y);
+
+                inputs.extend(state_chain_public_inputs(
+                    &circuit.public_input.state_chain,
+                    StateChainTag::Divination,
+                    &[],
+                    &inputs[inputs.len() - 4..],
+                ));
                 inputs
             }
             BuiltinCircuit::AnonymousVoting(circuit) => {
+                // IMPORTANT: keep the same ordering as input allocation in the circuit
+                // (alive_mask, most voted id, then player_commitment[i].x, .y).
                 let mut inputs = Vec::new();
-                // let mut inputs = circuit
-                //     .player_commitment
-                //     .iter()
-                //     .flat_map(|c| {
-                //         let d = c.to_local();
-                //         vec![d.x, d.y]
-                //     })
-                //     .collect::<Vec<_>>();
-
-                let most_voted_id = circuit.calculate_output();
-
-                inputs.push(most_voted_id.sync_reveal());
+
+                let most_voted_id = circuit.calculate_output().sync_reveal();
+                let commitments = MFr::commitment_points(&circuit.public_input.player_commitment);
+
+                inputs.push(Fr::from(circuit.public_input.state_chain.alive_mask));
+                inputs.push(most_voted_id);
+                inputs.extend(commitments.iter().flat_map(|(x, y)| [*x, *y]));
+
+                inputs.extend(state_chain_public_inputs(
+                    &circuit.public_input.state_chain,
+                    StateChainTag::AnonymousVoting,
+                    &commitments,
+                    &[most_voted_id],
+                ));
                 inputs
             }
             BuiltinCircuit::WinningJudge(circuit) => {
                 let mut inputs = Vec::new();
 
-                let num_alive = Fr::from(circuit.private_input.len() as u32);
+                let state_chain = circuit.public_input.state_chain;
+                let num_alive = Fr::from(state_chain.alive_mask.count_ones());
+                let win_condition = circuit.public_input.win_condition;
 
                 let game_state = circuit.calculate_output();
+                let winner_mask = circuit.calculate_winner_mask();
 
                 inputs.push(num_alive);
+                inputs.push(Fr::from(state_chain.alive_mask));
+                inputs.push(Fr::from(win_condition.werewolf_win_on_majority as u32));
+                inputs.push(Fr::from(win_condition.day_limit));
+                inputs.push(Fr::from(state_chain.day_count));
                 inputs.push(game_state.sync_reveal());
+                inputs.push(winner_mask.sync_reveal());
+
+                // game_state と winner_mask の後に role_commitment[i].x, .y が続く
+                let results = inputs[5..].to_vec();
+                let commitments = MFr::commitment_points(&circuit.public_input.role_commitment);
+                inputs.extend(commitments.iter().flat_map(|(x, y)| [*x, *y]));
+
+                inputs.extend(state_chain_public_inputs(
+                    &state_chain,
+                    StateChainTag::WinningJudgement,
+                    &commitments,
+                    &results,
+                ));
                 inputs
             }
             BuiltinCircuit::RoleAssignment(circuit) => {
-                // IMPORTANT: keep the same ordering as matrix allocation in the circuit
-                // (nalgebra iterator order = column-major).
-                circuit
+                // IMPORTANT: keep the same ordering as input allocation in the circuit
+                // (player_commitment[i].x, .y for all players, then role_commitment[i].x, .y).
+                // tau_matrix is embedded as a constant and is not a public input.
+                let mut inputs = Vec::new();
+                for commitment in circuit
                     .public_input
-                    .tau_matrix
+                    .player_commitment
                     .iter()
-                    .map(|v| v.sync_reveal())
-                    .collect::<Vec<_>>()
+                    .chain(circuit.public_input.role_commitment.iter())
+                {
+                    let commitment = commitment.sync_reveal();
+                    inputs.push(commitment.x);
+                    inputs.push(commitment.y);
+                }
+
+                // コミットメントは player_commitment, role_commitment の順に並んでいる
+                let commitments = inputs
+                    .chunks(2)
+                    .map(|point| (point[0], point[1]))
+                    .collect::<Vec<_>>();
+                inputs.extend(state_chain_public_inputs(
+                    &circuit.public_input.state_chain,
+                    StateChainTag::RoleAssignment,
+                    &commitments,
+                    &[],
+                ));
+                inputs
             }
             BuiltinCircuit::KeyPublicize(circuit) => {
-                let (_pub_key_x, _pub_key_y) = circuit.calculate_output();
+                // IMPORTANT: keep the same ordering as input allocation in the circuit
+                // (pub_key.x, pub_key.y, then role_commitment[i].x, role_commitment[i].y).
+                let mut inputs = Vec::new();
+                let (pub_key_x, pub_key_y) = circuit.calculate_output();
 
                 // 公開鍵のX座標とY座標を入力として返す
-                // inputs.push(revealed_pub_key_x);
-                // inputs.push(revealed_pub_key_y);
-                Vec::new()
+                inputs.push(pub_key_x.sync_reveal());
+                inputs.push(pub_key_y.sync_reveal());
+
+                for commitment in circuit.public_input.role_commitment.iter() {
+                    let commitment = commitment.sync_reveal();
+                    inputs.push(commitment.x);
+                    inputs.push(commitment.y);
+                }
+
+                let commitments = inputs[2..]
+                    .chunks(2)
+                    .map(|point| (point[0], point[1]))
+                    .collect::<Vec<_>>();
+                let pub_key = [inputs[0], inputs[1]];
+                inputs.extend(state_chain_public_inputs(
+                    &circuit.public_input.state_chain,
+                    StateChainTag::KeyPublicize,
+                    &commitments,
+                    &pub_key,
+                ));
+                inputs
+            }
+            BuiltinCircuit::Attack(circuit) => {
+                // IMPORTANT: keep the same ordering as input allocation in the circuit
+                // (alive_mask, victim id, then role_commitment[i].x, .y in id order).
+                let mut inputs = vec![
+                    Fr::from(circuit.public_input.state_chain.alive_mask),
+                    circuit.calculate_output().sync_reveal(),
+                ];
+                let commitments = MFr::commitment_points(&circuit.public_input.role_commitment);
+                inputs.extend(commitments.iter().flat_map(|(x, y)| [*x, *y]));
+
+                let result = [inputs[1]];
+                inputs.extend(state_chain_public_inputs(
+                    &circuit.public_input.state_chain,
+                    StateChainTag::Attack,
+                    &commitments,
+                    &result,
+                ));
+                inputs
+            }
+            BuiltinCircuit::NightResolution(circuit) => {
+                // IMPORTANT: keep the same ordering as input allocation in the circuit
+                // (dead id, then role_commitment[i].x, .y in id order).
+                let mut inputs = vec![circuit.calculate_output().sync_reveal()];
+                let commitments = MFr::commitment_points(&circuit.public_input.role_commitment);
+                inputs.extend(commitments.iter().flat_map(|(x, y)| [*x, *y]));
+
+                let result = [inputs[0]];
+                inputs.extend(state_chain_public_inputs(
+                    &circuit.public_input.state_chain,
+                    StateChainTag::NightResolution,
+                    &commitments,
+                    &result,
+                ));
+                inputs
+            }
+            BuiltinCircuit::Medium(circuit) => {
+                // IMPORTANT: keep the same ordering as input allocation in the circuit
+                // (executed id, the same layout as Divination, then role_commitment[i].x, .y
+                // in id order).
+                let mut inputs = vec![Fr::from(circuit.public_input.executed_id as u32)];
+                let is_executed_werewolf = circuit.calculate_output().sync_reveal();
+
+                inputs.push(circuit.public_input.elgamal_param.generator.sync_reveal().x);
+                inputs.push(circuit.public_input.elgamal_param.generator.sync_reveal().y);
+
+                inputs.push(circuit.public_input.pub_key.sync_reveal().x);
+                inputs.push(circuit.public_input.pub_key.sync_reveal().y);
+
+                // elgamal ciphertext
+                inputs.push(is_executed_werewolf.0.x);
+                inputs.push(is_executed_werewolf.0.y);
+                inputs.push(is_executed_werewolf.1.x);
+                inputs.push(is_executed_werewolf.1.y);
+
+                // 処刑者 id と暗号文
+                let results = [&inputs[..1], &inputs[inputs.len() - 4..]].concat();
+
+                let commitments = MFr::commitment_points(&circuit.public_input.role_commitment);
+                inputs.extend(commitments.iter().flat_map(|(x, y)| [*x, *y]));
+
+                inputs.extend(state_chain_public_inputs(
+                    &circuit.public_input.state_chain,
+                    StateChainTag::Medium,
+                    &commitments,
+                    &results,
+                ));
+                inputs
             }
         }
     }
@@ -457,6 +943,13 @@ impl CircuitFactory {
                 CanonicalSerialize::serialize(&is_target_werewolf, &mut buffer).unwrap();
                 buffer
             }
+            BuiltinCircuit::Medium(circuit) => {
+                let is_executed_werewolf = circuit.calculate_output().sync_reveal();
+
+                let mut buffer = Vec::new();
+                CanonicalSerialize::serialize(&is_executed_werewolf, &mut buffer).unwrap();
+                buffer
+            }
             BuiltinCircuit::AnonymousVoting(circuit) => {
                 let most_voted_id = circuit.calculate_output().sync_reveal();
 
@@ -471,11 +964,29 @@ impl CircuitFactory {
                 CanonicalSerialize::serialize(&public_key, &mut buffer).unwrap();
                 buffer
             }
+            BuiltinCircuit::Attack(circuit) => {
+                let victim_id = circuit.calculate_output().sync_reveal();
+
+                let mut buffer = Vec::new();
+                CanonicalSerialize::serialize(&victim_id, &mut buffer).unwrap();
+                buffer
+            }
+            BuiltinCircuit::NightResolution(circuit) => {
+                // 誰も死亡しなかった場合は player_num
+                let dead_id = circuit.calculate_output().sync_reveal();
+
+                let mut buffer = Vec::new();
+                CanonicalSerialize::serialize(&dead_id, &mut buffer).unwrap();
+                buffer
+            }
             BuiltinCircuit::WinningJudge(circuit) => {
                 let game_state = circuit.calculate_output().sync_reveal();
+                // 勝利陣営に属するプレイヤーの bitmask。game_state の後ろに続けて書き込む
+                let winner_mask = circuit.calculate_winner_mask().sync_reveal();
 
                 let mut buffer = Vec::new();
                 CanonicalSerialize::serialize(&game_state, &mut buffer).unwrap();
+                CanonicalSerialize::serialize(&winner_mask, &mut buffer).unwrap();
                 buffer
             }
             BuiltinCircuit::RoleAssignment(circuit) => {
@@ -484,7 +995,7 @@ impl CircuitFactory {
                 let serialized_outputs: Vec<RoleAssignmentOutputShare> = role_outputs
                     .iter()
                     .map(|output| RoleAssignmentOutputShare {
-                        schema_version: "role_assignment_share_v2",
+                        schema_version: "role_assignment_share_v3",
                         role_share: output.role_share.unwrap_as_public().into_repr().to_string(),
                         role_share_encoding: "bn254_fr_decimal_string",
                         werewolf_mates_mask_share: output
@@ -493,6 +1004,12 @@ impl CircuitFactory {
                             .into_repr()
                             .to_string(),
                         werewolf_mates_mask_share_encoding: "player_index_bitmask_lsb0",
+                        role_flags_share: output
+                            .role_flags_share
+                            .unwrap_as_public()
+                            .into_repr()
+                            .to_string(),
+                        role_flags_share_encoding: "werewolf_species_bit0_team_bit1",
                     })
                     .collect();
 
@@ -502,3 +1019,25 @@ impl CircuitFactory {
         }
     }
 }
+
+// 生存者だけが提出した入力を id 順に並べ、死亡者の行を補って全プレイヤー分にする
+fn pad_dead_rows<T>(
+    rows: Vec<T>,
+    player_num: usize,
+    id_of: impl Fn(&T) -> usize,
+    dead_row: impl Fn(usize) -> T,
+) -> Vec<T> {
+    let mut slots: Vec<Option<T>> = (0..player_num).map(|_| None).collect();
+    for row in rows {
+        let id = id_of(&row);
+        assert!(id < player_num, "Player id out of range: {}", id);
+        assert!(slots[id].is_none(), "Duplicate input for player id: {}", id);
+        slots[id] = Some(row);
+    }
+
+    slots
+        .into_iter()
+        .enumerate()
+        .map(|(id, row)| row.unwrap_or_else(|| dead_row(id)))
+        .collect()
+}
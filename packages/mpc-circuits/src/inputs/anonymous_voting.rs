@@ -1,4 +1,5 @@
 use ark_ff::PrimeField;
+use mpc_algebra_wasm::StateChainParameter;
 use serde::{Deserialize, Serialize};
 use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
 
@@ -14,4 +15,7 @@ pub struct AnonymousVotingPublicInput<F: PrimeField + LocalOrMPC<F> + ElGamalLoc
     pub pedersen_param: <F as LocalOrMPC<F>>::PedersenParam,
     pub player_commitment: Vec<<F as LocalOrMPC<F>>::PedersenCommitment>,
     pub player_num: usize,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
 }
@@ -0,0 +1,27 @@
+use ark_ff::PrimeField;
+use mpc_algebra_wasm::StateChainParameter;
+use serde::{Deserialize, Serialize};
+use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AttackPrivateInput<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
+    pub id: usize,
+    // 人狼は襲撃先の one-hot、それ以外は全て 0
+    pub is_target_id: Vec<F>,
+    // role_commitment[id] の開示値 (0: 村人, 1: 占い師, 2: 人狼)
+    pub role_id: F,
+    pub randomness: F::PedersenRandomness,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AttackPublicInput<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
+    pub pedersen_param: <F as LocalOrMPC<F>>::PedersenParam,
+    pub player_num: usize,
+
+    // instance
+    // 死亡者を含む全プレイヤーの役職 commitment (id 順)
+    pub role_commitment: Vec<F::PedersenCommitment>,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
+}
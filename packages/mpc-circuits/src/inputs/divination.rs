@@ -1,4 +1,5 @@
 use ark_ff::PrimeField;
+use mpc_algebra_wasm::StateChainParameter;
 use serde::{Deserialize, Serialize};
 use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
 
@@ -16,4 +17,7 @@ pub struct DivinationPublicInput<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrM
     pub elgamal_param: F::ElGamalParam,
     pub pub_key: F::ElGamalPubKey,
     pub player_num: usize,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
 }
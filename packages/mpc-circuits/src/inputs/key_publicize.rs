@@ -1,16 +1,25 @@
 use ark_ff::PrimeField;
+use mpc_algebra_wasm::StateChainParameter;
 use serde::{Deserialize, Serialize};
 use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
 
-#[derive(Serialize, Deserialize, Clone, Default)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct KeyPublicizePrivateInput<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
     pub id: usize,
     pub pub_key_or_dummy_x: F,
     pub pub_key_or_dummy_y: F,
-    pub is_fortune_teller: F,
+    // role_commitment[id] の開示値 (占い師かどうかは回路内で役職IDから求める)
+    pub role_id: F,
+    pub randomness: F::PedersenRandomness,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct KeyPublicizePublicInput<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
     pub pedersen_param: <F as LocalOrMPC<F>>::PedersenParam,
+
+    // instance
+    pub role_commitment: Vec<F::PedersenCommitment>,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
 }
@@ -0,0 +1,32 @@
+use ark_ff::PrimeField;
+use mpc_algebra_wasm::StateChainParameter;
+use serde::{Deserialize, Serialize};
+use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MediumPrivateInput<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
+    pub id: usize,
+    // role_commitment[id] の開示値。人狼かどうかはここから求める
+    pub role_id: F,
+    pub role_randomness: F::PedersenRandomness,
+    // 暗号化の乱数 (先頭の行のものを使う)
+    pub randomness: F::ElGamalRandomness,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MediumPublicInput<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
+    pub pedersen_param: F::PedersenParam,
+    pub elgamal_param: F::ElGamalParam,
+    // KeyPublicize で公開された霊媒師の公開鍵
+    pub pub_key: F::ElGamalPubKey,
+    pub player_num: usize,
+    // AnonymousVoting の出力 (処刑時点の生存者 index)
+    pub executed_id: usize,
+
+    // instance
+    // 処刑時点の生存者 index 順に並べた役職 commitment
+    pub role_commitment: Vec<F::PedersenCommitment>,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
+}
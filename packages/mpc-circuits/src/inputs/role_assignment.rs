@@ -1,5 +1,5 @@
 use ark_ff::PrimeField;
-use mpc_algebra_wasm::GroupingParameter;
+use mpc_algebra_wasm::{GroupingParameter, InstanceMode, StateChainParameter};
 use nalgebra as na;
 use serde::{Deserialize, Serialize};
 use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
@@ -8,8 +8,11 @@ use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
 pub struct RoleAssignmentPrivateInput<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
     pub id: usize,
     pub shuffle_matrices: na::DMatrix<F>,
+    // role_commitment[id] のランダムネス
     pub randomness: F::PedersenRandomness,
     pub player_randomness: F,
+    // player_commitment[id] の開示値
+    pub player_commitment_randomness: F::PedersenRandomness,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -24,4 +27,10 @@ pub struct RoleAssignmentPublicInput<F: PrimeField + LocalOrMPC<F> + ElGamalLoca
     pub tau_matrix: na::DMatrix<F>,
     pub role_commitment: Vec<F::PedersenCommitment>,
     pub player_commitment: Vec<F::PedersenCommitment>,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
+    // Hashed のときは instance 全体の Poseidon hash だけが公開入力になる
+    #[serde(default)]
+    pub instance_mode: InstanceMode,
 }
@@ -1,4 +1,5 @@
 use ark_ff::PrimeField;
+use mpc_algebra_wasm::{StateChainParameter, WinConditionParameter};
 use serde::{Deserialize, Serialize};
 use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
 
@@ -6,11 +7,24 @@ use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
 pub struct WinningJudgementPrivateInput<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
     pub id: usize,
     pub am_werewolf: F,
-    pub player_randomness: F,
+    // 人狼陣営かどうか。狂人は人狼ではないが人狼陣営として勝敗が決まる
+    pub am_werewolf_team: F,
+    // 妖狐 (第三陣営)。人間として数え、決着時に生存していれば勝利を奪う
+    pub am_fox: F,
+    // role_commitment[id] の開示値。陣営フラグは役職IDと一致していなければならない
+    pub role_id: F,
+    pub randomness: F::PedersenRandomness,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct WinningJudgementPublicInput<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> {
     pub pedersen_param: <F as LocalOrMPC<F>>::PedersenParam,
-    pub player_commitment: Vec<<F as LocalOrMPC<F>>::PedersenCommitment>,
+    pub player_num: usize,
+    pub win_condition: WinConditionParameter,
+
+    // instance
+    pub role_commitment: Vec<F::PedersenCommitment>,
+    // 直前の proof の state digest と、このリクエスト時点の生存者・日数
+    #[serde(default)]
+    pub state_chain: StateChainParameter,
 }
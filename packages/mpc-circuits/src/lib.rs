@@ -4,10 +4,13 @@ pub mod inputs;
 pub mod traits;
 
 pub use circuits::{
-    anonymous_voting::*, divination::*, key_publicize::*, role_assignment::*, winning_judgement::*,
-    BuiltinCircuit, CircuitIdentifier,
+    anonymous_voting::*, attack::*, divination::*, key_publicize::*, medium::*,
+    night_resolution::*, role_assignment::*, winning_judgement::*, BuiltinCircuit,
+    CircuitIdentifier,
 };
 pub use factory::*;
 pub use inputs::{
-    anonymous_voting::*, divination::*, key_publicize::*, role_assignment::*, winning_judgement::*,
+    anonymous_voting::*, attack::*, divination::*, key_publicize::*, medium::*,
+    night_resolution::*, role_assignment::*, winning_judgement::*,
 };
+pub use traits::circuits::{state_chain_public_inputs, verifier_public_inputs};
@@ -1,17 +1,22 @@
 use crate::*;
 
 use ark_bn254::Fr;
+use ark_crypto_primitives::commitment::CommitmentGadget;
 use ark_crypto_primitives::encryption::AsymmetricEncryptionScheme;
+use ark_crypto_primitives::CommitmentScheme;
+use ark_ec::models::TEModelParameters;
 use ark_ec::AffineCurve;
 use ark_ff::{BigInteger, PrimeField, SquareRootField};
-use ark_r1cs_std::alloc::AllocVar;
+use ark_r1cs_std::alloc::{AllocVar, AllocationMode};
 use ark_r1cs_std::eq::EqGadget;
 use ark_r1cs_std::fields::fp::FpVar;
 use ark_r1cs_std::fields::FieldVar;
 use ark_r1cs_std::groups::CurveVar;
 use ark_r1cs_std::prelude::Boolean;
 use ark_r1cs_std::select::CondSelectGadget;
+use ark_r1cs_std::uint8::UInt8;
 use ark_r1cs_std::R1CSVar;
+use ark_r1cs_std::ToBytesGadget;
 use ark_relations::r1cs::ConstraintSynthesizer;
 use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
 use ark_std::{One, Zero};
@@ -25,7 +30,11 @@ use mpc_algebra::MpcFpVar;
 use mpc_algebra::Reveal;
 use mpc_algebra::{BitDecomposition, BooleanWire};
 use mpc_algebra::{EqualityZero, ModulusConversion};
-use mpc_algebra_wasm::Role;
+use mpc_algebra_wasm::{
+    commitments_digest, digest_from_bytes, instance_hash, state_digest, GroupingParameter,
+    InstanceMode, PoseidonParameters, Role, StateChainParameter, StateChainTag,
+    WinningJudgeOutcome, POSEIDON_FULL_ROUNDS, POSEIDON_PARTIAL_ROUNDS, POSEIDON_WIDTH,
+};
 use nalgebra as na;
 use std::collections::HashSet;
 use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
@@ -35,6 +44,8 @@ use zk_mpc::field::*;
 pub struct RoleAssignmentPlayerShares<F> {
     pub role_share: F,
     pub werewolf_mates_mask_share: F,
+    // bit0: 人狼 (種族), bit1: 人狼陣営。狂人は 0b10、人狼は 0b11
+    pub role_flags_share: F,
 }
 
 impl AnonymousVotingCircuit<Fr> {
@@ -109,8 +120,24 @@ impl ConstraintSynthesizer<Fr> for AnonymousVotingCircuit<Fr> {
     ) -> Result<(), ark_relations::r1cs::SynthesisError> {
         // Implement constraint generation logic here
         // initialize
-        let player_num = self.private_input[0].is_target_id.len();
-        let alive_player_num = self.private_input.len();
+        let player_num = self.public_input.player_num;
+        let row_ids = self
+            .private_input
+            .iter()
+            .map(|input| input.id)
+            .collect::<Vec<_>>();
+        check_alive_mask_rows(
+            &row_ids,
+            player_num,
+            self.public_input.state_chain.alive_mask,
+        )?;
+        if self
+            .private_input
+            .iter()
+            .any(|input| input.is_target_id.len() != player_num)
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
 
         // TODO: check player commitment
         // for i in 0..player_num {
@@ -135,16 +162,35 @@ impl ConstraintSynthesizer<Fr> for AnonymousVotingCircuit<Fr> {
             })
             .collect::<Result<Vec<_>, _>>()?;
 
+        let (alive_mask_var, alive_var) = alloc_alive_mask(
+            cs.clone(),
+            self.public_input.state_chain.alive_mask,
+            player_num,
+        )?;
+
         let is_most_voted_id_var = FpVar::new_input(cs.clone(), || Ok(self.calculate_output()))?;
 
+        // 生存者はちょうど1票を投じ、死亡者の行は空。投票先は生存者に限られる
+        let one = <FpVar<Fr> as One>::one();
+        let zero = <FpVar<Fr> as Zero>::zero();
+        for (row, voter_alive) in is_target_id_var.iter().zip(alive_var.iter()) {
+            let mut row_sum = zero.clone();
+            for (vote, target_alive) in row.iter().zip(alive_var.iter()) {
+                (vote * (&one - vote)).enforce_equal(&zero)?;
+                (vote * (&one - target_alive)).enforce_equal(&zero)?;
+                row_sum += vote;
+            }
+            row_sum.enforce_equal(voter_alive)?;
+        }
+
         // calculate
         let mut num_voted_var = Vec::new();
 
         for i in 0..player_num {
             let mut each_num_voted = <FpVar<Fr> as Zero>::zero();
 
-            for j in 0..alive_player_num {
-                each_num_voted += is_target_id_var[j][i].clone();
+            for row in is_target_id_var.iter() {
+                each_num_voted += row[i].clone();
             }
 
             num_voted_var.push(each_num_voted);
@@ -175,6 +221,22 @@ impl ConstraintSynthesizer<Fr> for AnonymousVotingCircuit<Fr> {
         // enforce equal
         is_most_voted_id_var.enforce_equal(&calced_is_most_voted_id)?;
 
+        // player_commitment は公開入力として確保し、その変数から state digest を計算する
+        let player_commitment_vars =
+            alloc_commitment_inputs::<Fr>(cs.clone(), &self.public_input.player_commitment)?
+                .iter()
+                .map(commitment_var_coordinates)
+                .collect::<Vec<_>>();
+        enforce_state_chain(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::AnonymousVoting,
+            &player_commitment_vars,
+            Some(alive_mask_var),
+            None,
+            &[is_most_voted_id_var],
+        )?;
+
         println!(
             "[AnonymousVotingCircuit(Local)] instance vars: {}",
             cs.num_instance_variables()
@@ -197,8 +259,24 @@ impl ConstraintSynthesizer<MpcField<Fr>> for AnonymousVotingCircuit<MpcField<Fr>
         cs: ConstraintSystemRef<MpcField<Fr>>,
     ) -> ark_relations::r1cs::Result<()> {
         // initialize
-        let player_num = self.private_input[0].is_target_id.len();
-        let alive_player_num = self.private_input.len();
+        let player_num = self.public_input.player_num;
+        let row_ids = self
+            .private_input
+            .iter()
+            .map(|input| input.id)
+            .collect::<Vec<_>>();
+        check_alive_mask_rows(
+            &row_ids,
+            player_num,
+            self.public_input.state_chain.alive_mask,
+        )?;
+        if self
+            .private_input
+            .iter()
+            .any(|input| input.is_target_id.len() != player_num)
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
 
         // TODO: check player commitment
         // for i in 0..player_num {
@@ -225,8 +303,27 @@ impl ConstraintSynthesizer<MpcField<Fr>> for AnonymousVotingCircuit<MpcField<Fr>
             })
             .collect::<Result<Vec<_>, _>>()?;
 
+        let (alive_mask_var, alive_var) = alloc_alive_mask_mpc(
+            cs.clone(),
+            self.public_input.state_chain.alive_mask,
+            player_num,
+        )?;
+
         let is_most_voted_id_var = MpcFpVar::new_input(cs.clone(), || Ok(self.calculate_output()))?;
 
+        // 生存者はちょうど1票を投じ、死亡者の行は空。投票先は生存者に限られる
+        let one = <MpcFpVar<MpcField<Fr>> as One>::one();
+        let zero = <MpcFpVar<MpcField<Fr>> as Zero>::zero();
+        for (row, voter_alive) in is_target_id_var.iter().zip(alive_var.iter()) {
+            let mut row_sum = zero.clone();
+            for (vote, target_alive) in row.iter().zip(alive_var.iter()) {
+                (vote * (&one - vote)).enforce_equal(&zero)?;
+                (vote * (&one - target_alive)).enforce_equal(&zero)?;
+                row_sum += vote;
+            }
+            row_sum.enforce_equal(voter_alive)?;
+        }
+
         // calculate
         let mut num_voted_var = Vec::new();
 
@@ -234,8 +331,8 @@ impl ConstraintSynthesizer<MpcField<Fr>> for AnonymousVotingCircuit<MpcField<Fr>
             let mut each_num_voted =
                 <MpcFpVar<MpcField<Fr>> as MpcFieldVar<MpcField<Fr>, MpcField<Fr>>>::zero();
 
-            for j in 0..alive_player_num {
-                each_num_voted += is_target_id_var[j][i].clone();
+            for row in is_target_id_var.iter() {
+                each_num_voted += row[i].clone();
             }
 
             num_voted_var.push(each_num_voted);
@@ -265,6 +362,23 @@ impl ConstraintSynthesizer<MpcField<Fr>> for AnonymousVotingCircuit<MpcField<Fr>
         // enforce equal
         is_most_voted_id_var.enforce_equal(&calced_is_most_voted_id)?;
 
+        let player_commitment_vars = alloc_commitment_inputs::<MpcField<Fr>>(
+            cs.clone(),
+            &self.public_input.player_commitment,
+        )?
+        .iter()
+        .map(commitment_var_coordinates_mpc)
+        .collect::<Vec<_>>();
+        enforce_state_chain_mpc(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::AnonymousVoting,
+            &player_commitment_vars,
+            Some(alive_mask_var),
+            None,
+            &[is_most_voted_id_var],
+        )?;
+
         println!(
             "[AnonymousVotingCircuit(MPC)] instance vars: {}",
             cs.num_instance_variables()
@@ -282,250 +396,1422 @@ impl ConstraintSynthesizer<MpcField<Fr>> for AnonymousVotingCircuit<MpcField<Fr>
     }
 }
 
-impl<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>> ConstraintSynthesizer<F>
-    for KeyPublicizeCircuit<F>
-{
+impl AttackCircuit<Fr> {
+    pub fn calculate_output(&self) -> Fr {
+        let player_num = self.public_input.player_num;
+
+        let mut num_voted = vec![Fr::zero(); player_num];
+
+        // 人狼以外の入力は全て 0 なので、そのまま人狼の票数になる
+        for input in self.private_input.iter() {
+            for (i, b) in input.is_target_id.iter().take(player_num).enumerate() {
+                num_voted[i] += b;
+            }
+        }
+
+        let mut victim_id = Fr::zero();
+        let mut max_votes = Fr::zero();
+
+        for (i, votes) in num_voted.iter().enumerate() {
+            if *votes > max_votes {
+                victim_id = Fr::from(i as u32);
+                max_votes = *votes;
+            }
+        }
+        victim_id
+    }
+}
+
+impl AttackCircuit<MpcField<Fr>> {
+    pub fn calculate_output(&self) -> MpcField<Fr> {
+        let player_num = self.public_input.player_num;
+
+        let mut num_voted = vec![MpcField::<Fr>::zero(); player_num];
+
+        for input in self.private_input.iter() {
+            for (i, b) in input.is_target_id.iter().take(player_num).enumerate() {
+                num_voted[i] += b;
+            }
+        }
+
+        let mut victim_id = MpcField::<Fr>::zero();
+        let mut max_votes = MpcField::<Fr>::zero();
+
+        for (i, votes) in num_voted.iter().enumerate() {
+            // AnonymousVotingCircuit と同じく、同票のときは小さい id を残す
+            let le_like = max_votes.sync_is_smaller_than(votes).field();
+            let is_equal = (max_votes - votes).sync_is_zero_shared().field();
+            let is_new_max = le_like * (MpcField::<Fr>::one() - is_equal);
+            max_votes += (*votes - max_votes) * is_new_max;
+            victim_id += (MpcField::<Fr>::from(i as u32) - victim_id) * is_new_max;
+        }
+        victim_id
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for AttackCircuit<Fr> {
     fn generate_constraints(
         self,
-        cs: ark_relations::r1cs::ConstraintSystemRef<F>,
+        cs: ark_relations::r1cs::ConstraintSystemRef<Fr>,
     ) -> Result<(), ark_relations::r1cs::SynthesisError> {
-        let pk_x = self
-            .private_input
-            .iter()
-            .map(|input| input.pub_key_or_dummy_x)
-            .collect::<Vec<_>>();
+        let player_num = self.public_input.player_num;
+        check_attack_inputs(&self.private_input, &self.public_input)?;
 
-        let pk_y = self
-            .private_input
-            .iter()
-            .map(|input| input.pub_key_or_dummy_y)
-            .collect::<Vec<_>>();
+        // 公開入力は AnonymousVotingCircuit と同じく生存者マスクが先頭で、襲撃先 id、
+        // role_commitment (id 順) が続く
+        let (alive_mask_var, alive_var) = alloc_alive_mask(
+            cs.clone(),
+            self.public_input.state_chain.alive_mask,
+            player_num,
+        )?;
+        let victim_id_var = FpVar::new_input(cs.clone(), || Ok(self.calculate_output()))?;
+        let role_commitment_vars =
+            alloc_commitment_inputs::<Fr>(cs.clone(), &self.public_input.role_commitment)?;
 
-        let is_fortune_teller = self
-            .private_input
-            .iter()
-            .map(|input| input.is_fortune_teller)
-            .collect::<Vec<_>>();
+        let pedersen_param_var = <Fr as LocalOrMPC<Fr>>::PedersenParamVar::new_constant(
+            ark_relations::ns!(cs, "pedersen_param"),
+            &self.public_input.pedersen_param,
+        )?;
+        let werewolf_role_id = FpVar::Constant(Fr::from(WEREWOLF_ROLE_ID));
+        let one = <FpVar<Fr> as One>::one();
+        let zero = <FpVar<Fr> as Zero>::zero();
+
+        let mut num_voted_var = vec![zero.clone(); player_num];
+
+        for (input, row_alive) in self.private_input.iter().zip(alive_var.iter()) {
+            // 生存者の役職を role_commitment に結びつける。死亡者は入力を送らないので開示しない
+            let role_id_var =
+                FpVar::new_witness(ark_relations::ns!(cs, "role_id"), || Ok(input.role_id))?;
+            enforce_alive_pedersen_opening(
+                cs.clone(),
+                &pedersen_param_var,
+                &role_id_var.to_bytes()?,
+                &input.randomness,
+                &role_commitment_vars[input.id],
+                row_alive,
+            )?;
+            let is_werewolf_var = FpVar::from(role_id_var.is_eq(&werewolf_role_id)?);
 
-        let x_var = pk_x
-            .iter()
-            .map(|x| FpVar::<F>::new_witness(ark_relations::ns!(cs, "gadget_randomness"), || Ok(x)))
-            .collect::<Result<Vec<_>, _>>()?;
+            let is_target_id_var = input
+                .is_target_id
+                .iter()
+                .map(|b| FpVar::new_witness(cs.clone(), || Ok(*b)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            // 各要素は 0 or 1 で、生存する人狼は生存者のちょうど1人に投票し、
+            // それ以外 (死亡した人狼を含む) は投票しない
+            let mut num_targets_var = zero.clone();
+            for (b, target_alive) in is_target_id_var.iter().zip(alive_var.iter()) {
+                let is_zero = ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_zero(b)?;
+                let is_one = ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_one(b)?;
+                is_zero.or(&is_one)?.enforce_equal(&Boolean::TRUE)?;
+                (b * (&one - target_alive)).enforce_equal(&zero)?;
+                num_targets_var += b;
+            }
+            num_targets_var.enforce_equal(&(is_werewolf_var * row_alive))?;
 
-        let y_var = pk_y
-            .iter()
-            .map(|y| FpVar::<F>::new_witness(ark_relations::ns!(cs, "gadget_randomness"), || Ok(y)))
-            .collect::<Result<Vec<_>, _>>()?;
+            for (i, b) in is_target_id_var.iter().enumerate() {
+                num_voted_var[i] += b;
+            }
+        }
 
-        let is_ft_var = is_fortune_teller
-            .iter()
-            .map(|b| FpVar::<F>::new_witness(ark_relations::ns!(cs, "gadget_randomness"), || Ok(b)))
-            .collect::<Result<Vec<_>, _>>()?;
+        let mut calced_victim_id = FpVar::Constant(Fr::zero());
+        let mut current_max_votes = FpVar::Constant(Fr::zero());
+
+        for (i, num_voted) in num_voted_var.iter().enumerate() {
+            // keep current victim when current_max_votes >= num_voted[i]
+            let keep_current = FpVar::is_cmp(
+                &current_max_votes,
+                num_voted,
+                std::cmp::Ordering::Greater,
+                true,
+            )?;
 
-        // is_fortune_teller = 0 or 1
-        for b in is_ft_var.iter() {
-            let is_zero = ark_r1cs_std::prelude::FieldVar::<F, F>::is_zero(b)?;
-            let is_one = ark_r1cs_std::prelude::FieldVar::<F, F>::is_one(b)?;
-            let is_bool = is_zero.or(&is_one)?;
-            is_bool.enforce_equal(&Boolean::constant(true))?;
+            let candidate_id = FpVar::Constant(Fr::from(i as u32));
+            current_max_votes =
+                FpVar::conditionally_select(&keep_current, &current_max_votes, num_voted)?;
+            calced_victim_id =
+                FpVar::conditionally_select(&keep_current, &calced_victim_id, &candidate_id)?;
         }
 
-        let _sum_x_var =
-            x_var
-                .iter()
-                .enumerate()
-                .fold(<FpVar<F> as Zero>::zero(), |mut acc, (i, x)| {
-                    acc = acc + x * &is_ft_var[i];
-                    acc
-                });
+        // 票が無いと襲撃先がプレイヤー0 に決まってしまうので、少なくとも1票を要求する
+        ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_zero(&current_max_votes)?
+            .enforce_equal(&Boolean::FALSE)?;
+        victim_id_var.enforce_equal(&calced_victim_id)?;
 
-        let _sum_y_var =
-            y_var
+        enforce_state_chain(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::Attack,
+            &role_commitment_vars
                 .iter()
-                .enumerate()
-                .fold(<FpVar<F> as Zero>::zero(), |mut acc, (i, y)| {
-                    acc = acc + y * &is_ft_var[i];
-                    acc
-                });
-
-        // TODO: Add verify commitments
-        // self.verify_commitments(cs.clone())?;
+                .map(commitment_var_coordinates)
+                .collect::<Vec<_>>(),
+            Some(alive_mask_var),
+            None,
+            &[victim_id_var],
+        )?;
 
         println!(
-            "[KeyPublicizeCircuit] instance vars: {}",
+            "[AttackCircuit(Local)] instance vars: {}",
             cs.num_instance_variables()
         );
         println!(
-            "[KeyPublicizeCircuit] witness vars: {}",
+            "[AttackCircuit(Local)] witness vars: {}",
             cs.num_witness_variables()
         );
         println!(
-            "[KeyPublicizeCircuit] total number of constraints: {}",
+            "[AttackCircuit(Local)] total number of constraints: {}",
             cs.num_constraints()
         );
-
         Ok(())
     }
 }
 
-impl ConstraintSynthesizer<Fr> for DivinationCircuit<Fr> {
+impl ConstraintSynthesizer<MpcField<Fr>> for AttackCircuit<MpcField<Fr>> {
     fn generate_constraints(
         self,
-        cs: ark_relations::r1cs::ConstraintSystemRef<Fr>,
-    ) -> Result<(), ark_relations::r1cs::SynthesisError> {
-        let is_werewolf_bit = self
-            .private_input
-            .iter()
-            .map(|input| Boolean::new_witness(cs.clone(), || Ok(input.is_werewolf.is_one())))
-            .collect::<Result<Vec<_>, _>>()?;
+        cs: ConstraintSystemRef<MpcField<Fr>>,
+    ) -> ark_relations::r1cs::Result<()> {
+        let player_num = self.public_input.player_num;
+        check_attack_inputs(&self.private_input, &self.public_input)?;
 
-        let is_target_bit = self
-            .private_input
-            .iter()
-            .map(|input| {
-                input
-                    .is_target
-                    .iter()
-                    .map(|b| Boolean::new_witness(cs.clone(), || Ok(b.is_one())))
-                    .collect::<Result<Vec<_>, _>>()
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        // 公開入力は AnonymousVotingCircuit と同じく生存者マスクが先頭で、襲撃先 id、
+        // role_commitment (id 順) が続く
+        let (alive_mask_var, alive_var) = alloc_alive_mask_mpc(
+            cs.clone(),
+            self.public_input.state_chain.alive_mask,
+            player_num,
+        )?;
+        let victim_id_var = MpcFpVar::new_input(cs.clone(), || Ok(self.calculate_output()))?;
+        let role_commitment_vars = alloc_commitment_inputs::<MpcField<Fr>>(
+            cs.clone(),
+            &self.public_input.role_commitment,
+        )?;
 
-        let player_num = is_target_bit[0].len();
-        let row_ids = self
-            .private_input
-            .iter()
-            .map(|input| input.id)
-            .collect::<Vec<_>>();
+        let pedersen_param_var =
+            <MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenParamVar::new_constant(
+                ark_relations::ns!(cs, "pedersen_param"),
+                &self.public_input.pedersen_param,
+            )?;
+        let werewolf_role_id =
+            MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(WEREWOLF_ROLE_ID))?;
+        let one = <MpcFpVar<MpcField<Fr>> as One>::one();
+        let zero = <MpcFpVar<MpcField<Fr>> as Zero>::zero();
+
+        let mut num_voted_var = vec![zero.clone(); player_num];
+
+        for (input, row_alive) in self.private_input.iter().zip(alive_var.iter()) {
+            // 生存者の役職を role_commitment に結びつける。死亡者は入力を送らないので開示しない
+            let role_id_var =
+                MpcFpVar::new_witness(ark_relations::ns!(cs, "role_id"), || Ok(input.role_id))?;
+            enforce_alive_pedersen_opening_mpc(
+                cs.clone(),
+                &pedersen_param_var,
+                &role_id_var.to_bytes()?,
+                &input.randomness,
+                &role_commitment_vars[input.id],
+                row_alive,
+            )?;
+            let is_werewolf_var = MpcFpVar::conditionally_select(
+                &(&role_id_var - &werewolf_role_id).is_zero()?,
+                &one,
+                &zero,
+            )?;
 
-        let mut seen_row_ids = HashSet::new();
-        for id in &row_ids {
-            if *id >= player_num || !seen_row_ids.insert(*id) {
-                return Err(SynthesisError::Unsatisfiable);
+            let is_target_id_var = input
+                .is_target_id
+                .iter()
+                .map(|b| MpcFpVar::new_witness(cs.clone(), || Ok(*b)))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            // 各要素は 0 or 1 で、生存する人狼は生存者のちょうど1人に投票し、
+            // それ以外 (死亡した人狼を含む) は投票しない
+            let mut num_targets_var = zero.clone();
+            for (b, target_alive) in is_target_id_var.iter().zip(alive_var.iter()) {
+                b.is_zero()?
+                    .or(&(b - &one).is_zero()?)?
+                    .enforce_equal(&MpcBoolean::TRUE)?;
+                (b * (&one - target_alive)).enforce_equal(&zero)?;
+                num_targets_var += b;
+            }
+            num_targets_var.enforce_equal(&(&is_werewolf_var * row_alive))?;
+
+            for (i, b) in is_target_id_var.iter().enumerate() {
+                num_voted_var[i] += b;
             }
         }
 
-        let is_target_sum_bit = (0..player_num)
-            .map(|j| {
-                Boolean::kary_or(
-                    &is_target_bit
-                        .iter() // i を動かす
-                        .map(|row| row[j].clone())
-                        .collect::<Vec<_>>(),
-                )
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        let mut current_max_votes = MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::zero())?;
+        let mut calced_victim_id = MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::zero())?;
 
-        let is_wt = is_werewolf_bit
-            .iter()
-            .enumerate()
-            .map(|(row_index, x)| x.and(&is_target_sum_bit[row_ids[row_index]]))
-            .collect::<Result<Vec<_>, _>>()?;
+        for (i, num_voted) in num_voted_var.iter().enumerate() {
+            // keep current victim when current_max_votes >= num_voted[i]
+            let keep_current = MpcFpVar::is_cmp(
+                &current_max_votes,
+                num_voted,
+                std::cmp::Ordering::Greater,
+                true,
+            )?;
 
-        let is_target_werewolf_bit = Boolean::kary_or(is_wt.as_slice())?;
+            let candidate_id = MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(i as u32))?;
+            current_max_votes =
+                MpcFpVar::conditionally_select(&keep_current, &current_max_votes, num_voted)?;
+            calced_victim_id =
+                MpcFpVar::conditionally_select(&keep_current, &calced_victim_id, &candidate_id)?;
+        }
 
-        let one_point = <Fr as ElGamalLocalOrMPC<Fr>>::EdwardsVar::new_witness(
-            ark_relations::ns!(cs, "gadget_randomness"),
-            || Ok(<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::prime_subgroup_generator()),
-        )?;
+        // 票が無いと襲撃先がプレイヤー0 に決まってしまうので、少なくとも1票を要求する
+        current_max_votes
+            .is_zero()?
+            .enforce_equal(&MpcBoolean::FALSE)?;
+        victim_id_var.enforce_equal(&calced_victim_id)?;
 
-        let zero_point = <Fr as ElGamalLocalOrMPC<Fr>>::EdwardsVar::new_witness(
-            ark_relations::ns!(cs, "gadget_randomness"),
-            || Ok(<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::default()),
+        enforce_state_chain_mpc(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::Attack,
+            &role_commitment_vars
+                .iter()
+                .map(commitment_var_coordinates_mpc)
+                .collect::<Vec<_>>(),
+            Some(alive_mask_var),
+            None,
+            &[victim_id_var],
         )?;
 
-        let is_target_werewolf = is_target_werewolf_bit.select(&one_point, &zero_point)?;
+        println!(
+            "[AttackCircuit(MPC)] instance vars: {}",
+            cs.num_instance_variables()
+        );
+        println!(
+            "[AttackCircuit(MPC)] witness vars: {}",
+            cs.num_witness_variables()
+        );
+        println!(
+            "[AttackCircuit(MPC)] total number of constraints: {}",
+            cs.num_constraints()
+        );
 
-        // elgamal encryption
+        Ok(())
+    }
+}
 
-        let param_var = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalParamVar::new_input(
-            ark_relations::ns!(cs, "gadget_parameters"),
-            || Ok(self.public_input.elgamal_param.clone()),
-        )?;
+impl NightResolutionCircuit<Fr> {
+    /// 襲撃で死亡したプレイヤーの id を返す。誰も死亡しなかった場合は `player_num`。
+    pub fn calculate_output(&self) -> Fr {
+        let player_num = self.public_input.player_num;
 
-        // let randomness_sum_var = randomness_var.iter().fold(
-        //     <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalRandomnessVar>::default(),
-        //     |mut acc, x| {
-        //         acc += x;
-        //         acc
-        //     },
-        // );
+        let mut num_attacked = vec![Fr::zero(); player_num];
+        let mut num_guarded = vec![Fr::zero(); player_num];
 
-        let randomness_bits_var = self.private_input[0]
-            .randomness
-            .0
-            .into_repr()
-            .to_bits_le()
-            .iter()
-            .map(|b| Boolean::new_witness(cs.clone(), || Ok(*b)))
-            .collect::<Result<Vec<_>, _>>()?;
+        for input in self.private_input.iter() {
+            for (i, b) in input
+                .is_attack_target_id
+                .iter()
+                .take(player_num)
+                .enumerate()
+            {
+                num_attacked[i] += b;
+            }
+            for (i, b) in input.is_guard_target_id.iter().take(player_num).enumerate() {
+                num_guarded[i] += b;
+            }
+        }
 
-        // allocate public key
-        let pub_key_var = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPublicKeyVar::new_input(
-            ark_relations::ns!(cs, "gadget_public_key"),
-            || Ok(self.public_input.pub_key),
-        )?;
+        let mut victim_id = 0;
+        let mut max_votes = Fr::zero();
 
-        // allocate the output
-        let enc_result_var = {
-            // flatten randomness to little-endian bit vector
-            let randomness = randomness_bits_var;
+        for (i, votes) in num_attacked.iter().enumerate() {
+            if *votes > max_votes {
+                victim_id = i;
+                max_votes = *votes;
+            }
+        }
 
-            // compute s = randomness*pk
-            let s = Fr::get_public_key(&pub_key_var)
-                .clone()
-                .scalar_mul_le(randomness.iter())?;
+        if max_votes.is_zero() || !num_guarded[victim_id].is_zero() {
+            Fr::from(player_num as u32)
+        } else {
+            Fr::from(victim_id as u32)
+        }
+    }
+}
 
-            // compute c1 = randomness*generator
-            let c1 = Fr::get_generator(&param_var)
-                .clone()
-                .scalar_mul_le(randomness.iter())?;
+impl NightResolutionCircuit<MpcField<Fr>> {
+    pub fn calculate_output(&self) -> MpcField<Fr> {
+        let player_num = self.public_input.player_num;
 
-            // compute c2 = m + s
+        let mut num_attacked = vec![MpcField::<Fr>::zero(); player_num];
+        let mut num_guarded = vec![MpcField::<Fr>::zero(); player_num];
+
+        for input in self.private_input.iter() {
+            for (i, b) in input
+                .is_attack_target_id
+                .iter()
+                .take(player_num)
+                .enumerate()
+            {
+                num_attacked[i] += b;
+            }
+            for (i, b) in input.is_guard_target_id.iter().take(player_num).enumerate() {
+                num_guarded[i] += b;
+            }
+        }
+
+        let mut victim_id = MpcField::<Fr>::zero();
+        let mut victim_guarded = MpcField::<Fr>::zero();
+        let mut max_votes = MpcField::<Fr>::zero();
+
+        for (i, votes) in num_attacked.iter().enumerate() {
+            // AttackCircuit と同じく、同票のときは小さい id を残す
+            let le_like = max_votes.sync_is_smaller_than(votes).field();
+            let is_equal = (max_votes - votes).sync_is_zero_shared().field();
+            let is_new_max = le_like * (MpcField::<Fr>::one() - is_equal);
+            max_votes += (*votes - max_votes) * is_new_max;
+            victim_id += (MpcField::<Fr>::from(i as u32) - victim_id) * is_new_max;
+            victim_guarded += (num_guarded[i] - victim_guarded) * is_new_max;
+        }
+
+        // 襲撃があり、かつ護衛されていなければ死亡
+        let is_attacked = MpcField::<Fr>::one() - max_votes.sync_is_zero_shared().field();
+        let is_unguarded = victim_guarded.sync_is_zero_shared().field();
+        let dies = is_attacked * is_unguarded;
+
+        let nobody = MpcField::<Fr>::from(player_num as u32);
+        nobody + (victim_id - nobody) * dies
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for NightResolutionCircuit<Fr> {
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<Fr>,
+    ) -> Result<(), ark_relations::r1cs::SynthesisError> {
+        let player_num = self.public_input.player_num;
+        check_night_resolution_inputs(&self.private_input, &self.public_input)?;
+
+        // 公開入力は死亡者 id (誰も死亡しなければ player_num) が先頭、その後に role_commitment が id 順に続く
+        let dead_id_var = FpVar::new_input(cs.clone(), || Ok(self.calculate_output()))?;
+        let role_commitment_vars =
+            alloc_commitment_inputs::<Fr>(cs.clone(), &self.public_input.role_commitment)?;
+
+        let pedersen_param_var = <Fr as LocalOrMPC<Fr>>::PedersenParamVar::new_constant(
+            ark_relations::ns!(cs, "pedersen_param"),
+            &self.public_input.pedersen_param,
+        )?;
+        let werewolf_role_id = FpVar::Constant(Fr::from(WEREWOLF_ROLE_ID));
+        let guard_role_id = FpVar::Constant(Fr::from(GUARD_ROLE_ID));
+
+        let mut num_attacked_var = vec![<FpVar<Fr> as Zero>::zero(); player_num];
+        let mut num_guarded_var = vec![<FpVar<Fr> as Zero>::zero(); player_num];
+
+        for input in self.private_input.iter() {
+            // 提出者の役職を role_commitment に結びつける
+            let role_id_var =
+                FpVar::new_witness(ark_relations::ns!(cs, "role_id"), || Ok(input.role_id))?;
+            enforce_pedersen_opening::<Fr>(
+                cs.clone(),
+                &pedersen_param_var,
+                &role_id_var.to_bytes()?,
+                &input.randomness,
+                &role_commitment_vars[input.id],
+            )?;
+            let is_werewolf_var = FpVar::from(role_id_var.is_eq(&werewolf_role_id)?);
+            let is_guard_var = FpVar::from(role_id_var.is_eq(&guard_role_id)?);
+
+            // 人狼はちょうど1人を襲撃し、騎士はちょうど1人を護衛する。それ以外は全て 0
+            for (targets, expected_count, tally) in [
+                (
+                    &input.is_attack_target_id,
+                    &is_werewolf_var,
+                    &mut num_attacked_var,
+                ),
+                (
+                    &input.is_guard_target_id,
+                    &is_guard_var,
+                    &mut num_guarded_var,
+                ),
+            ] {
+                let target_vars = targets
+                    .iter()
+                    .map(|b| FpVar::new_witness(cs.clone(), || Ok(*b)))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let mut num_targets_var = <FpVar<Fr> as Zero>::zero();
+                for (i, b) in target_vars.iter().enumerate() {
+                    let is_zero = ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_zero(b)?;
+                    let is_one = ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_one(b)?;
+                    is_zero.or(&is_one)?.enforce_equal(&Boolean::TRUE)?;
+                    num_targets_var += b;
+                    tally[i] += b;
+                }
+                num_targets_var.enforce_equal(expected_count)?;
+            }
+        }
+
+        let mut calced_victim_id = FpVar::Constant(Fr::zero());
+        let mut victim_guarded = FpVar::Constant(Fr::zero());
+        let mut current_max_votes = FpVar::Constant(Fr::zero());
+
+        for (i, num_attacked) in num_attacked_var.iter().enumerate() {
+            // keep current victim when current_max_votes >= num_attacked[i]
+            let keep_current = FpVar::is_cmp(
+                &current_max_votes,
+                num_attacked,
+                std::cmp::Ordering::Greater,
+                true,
+            )?;
+
+            let candidate_id = FpVar::Constant(Fr::from(i as u32));
+            current_max_votes =
+                FpVar::conditionally_select(&keep_current, &current_max_votes, num_attacked)?;
+            calced_victim_id =
+                FpVar::conditionally_select(&keep_current, &calced_victim_id, &candidate_id)?;
+            victim_guarded =
+                FpVar::conditionally_select(&keep_current, &victim_guarded, &num_guarded_var[i])?;
+        }
+
+        // 襲撃があり、かつ護衛されていなければ死亡
+        let one = <FpVar<Fr> as One>::one();
+        let is_attacked = &one
+            - FpVar::from(ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_zero(
+                &current_max_votes,
+            )?);
+        let is_unguarded = FpVar::from(ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_zero(
+            &victim_guarded,
+        )?);
+        let dies = is_attacked * is_unguarded;
+
+        let nobody = FpVar::Constant(Fr::from(player_num as u32));
+        let calced_dead_id = &nobody + (calced_victim_id - &nobody) * dies;
+
+        dead_id_var.enforce_equal(&calced_dead_id)?;
+
+        enforce_state_chain(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::NightResolution,
+            &role_commitment_vars
+                .iter()
+                .map(commitment_var_coordinates)
+                .collect::<Vec<_>>(),
+            None,
+            None,
+            &[dead_id_var],
+        )?;
+
+        println!(
+            "[NightResolutionCircuit(Local)] instance vars: {}",
+            cs.num_instance_variables()
+        );
+        println!(
+            "[NightResolutionCircuit(Local)] witness vars: {}",
+            cs.num_witness_variables()
+        );
+        println!(
+            "[NightResolutionCircuit(Local)] total number of constraints: {}",
+            cs.num_constraints()
+        );
+        Ok(())
+    }
+}
+
+impl ConstraintSynthesizer<MpcField<Fr>> for NightResolutionCircuit<MpcField<Fr>> {
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<MpcField<Fr>>,
+    ) -> ark_relations::r1cs::Result<()> {
+        let player_num = self.public_input.player_num;
+        check_night_resolution_inputs(&self.private_input, &self.public_input)?;
+
+        // 公開入力は死亡者 id (誰も死亡しなければ player_num) が先頭、その後に role_commitment が id 順に続く
+        let dead_id_var = MpcFpVar::new_input(cs.clone(), || Ok(self.calculate_output()))?;
+        let role_commitment_vars = alloc_commitment_inputs::<MpcField<Fr>>(
+            cs.clone(),
+            &self.public_input.role_commitment,
+        )?;
+
+        let pedersen_param_var =
+            <MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenParamVar::new_constant(
+                ark_relations::ns!(cs, "pedersen_param"),
+                &self.public_input.pedersen_param,
+            )?;
+        let werewolf_role_id =
+            MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(WEREWOLF_ROLE_ID))?;
+        let guard_role_id =
+            MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(GUARD_ROLE_ID))?;
+        let one = <MpcFpVar<MpcField<Fr>> as One>::one();
+        let zero = <MpcFpVar<MpcField<Fr>> as Zero>::zero();
+
+        let mut num_attacked_var = vec![zero.clone(); player_num];
+        let mut num_guarded_var = vec![zero.clone(); player_num];
+
+        for input in self.private_input.iter() {
+            // 提出者の役職を role_commitment に結びつける
+            let role_id_var =
+                MpcFpVar::new_witness(ark_relations::ns!(cs, "role_id"), || Ok(input.role_id))?;
+            enforce_pedersen_opening::<MpcField<Fr>>(
+                cs.clone(),
+                &pedersen_param_var,
+                &role_id_var.to_bytes()?,
+                &input.randomness,
+                &role_commitment_vars[input.id],
+            )?;
+            let is_werewolf_var = MpcFpVar::conditionally_select(
+                &(&role_id_var - &werewolf_role_id).is_zero()?,
+                &one,
+                &zero,
+            )?;
+            let is_guard_var = MpcFpVar::conditionally_select(
+                &(&role_id_var - &guard_role_id).is_zero()?,
+                &one,
+                &zero,
+            )?;
+
+            // 人狼はちょうど1人を襲撃し、騎士はちょうど1人を護衛する。それ以外は全て 0
+            for (targets, expected_count, tally) in [
+                (
+                    &input.is_attack_target_id,
+                    &is_werewolf_var,
+                    &mut num_attacked_var,
+                ),
+                (
+                    &input.is_guard_target_id,
+                    &is_guard_var,
+                    &mut num_guarded_var,
+                ),
+            ] {
+                let target_vars = targets
+                    .iter()
+                    .map(|b| MpcFpVar::new_witness(cs.clone(), || Ok(*b)))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let mut num_targets_var = zero.clone();
+                for (i, b) in target_vars.iter().enumerate() {
+                    b.is_zero()?
+                        .or(&(b - &one).is_zero()?)?
+                        .enforce_equal(&MpcBoolean::TRUE)?;
+                    num_targets_var += b;
+                    tally[i] += b;
+                }
+                num_targets_var.enforce_equal(expected_count)?;
+            }
+        }
+
+        let mut current_max_votes = MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::zero())?;
+        let mut calced_victim_id = MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::zero())?;
+        let mut victim_guarded = MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::zero())?;
+
+        for (i, num_attacked) in num_attacked_var.iter().enumerate() {
+            // keep current victim when current_max_votes >= num_attacked[i]
+            let keep_current = MpcFpVar::is_cmp(
+                &current_max_votes,
+                num_attacked,
+                std::cmp::Ordering::Greater,
+                true,
+            )?;
+
+            let candidate_id = MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(i as u32))?;
+            current_max_votes =
+                MpcFpVar::conditionally_select(&keep_current, &current_max_votes, num_attacked)?;
+            calced_victim_id =
+                MpcFpVar::conditionally_select(&keep_current, &calced_victim_id, &candidate_id)?;
+            victim_guarded = MpcFpVar::conditionally_select(
+                &keep_current,
+                &victim_guarded,
+                &num_guarded_var[i],
+            )?;
+        }
+
+        // 襲撃があり、かつ護衛されていなければ死亡
+        let is_attacked =
+            MpcFpVar::conditionally_select(&current_max_votes.is_zero()?, &zero, &one)?;
+        let is_unguarded = MpcFpVar::conditionally_select(&victim_guarded.is_zero()?, &one, &zero)?;
+        let dies = is_attacked * is_unguarded;
+
+        let nobody = MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(player_num as u32))?;
+        let calced_dead_id = &nobody + (calced_victim_id - &nobody) * dies;
+
+        dead_id_var.enforce_equal(&calced_dead_id)?;
+
+        enforce_state_chain_mpc(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::NightResolution,
+            &role_commitment_vars
+                .iter()
+                .map(commitment_var_coordinates_mpc)
+                .collect::<Vec<_>>(),
+            None,
+            None,
+            &[dead_id_var],
+        )?;
+
+        println!(
+            "[NightResolutionCircuit(MPC)] instance vars: {}",
+            cs.num_instance_variables()
+        );
+        println!(
+            "[NightResolutionCircuit(MPC)] witness vars: {}",
+            cs.num_witness_variables()
+        );
+        println!(
+            "[NightResolutionCircuit(MPC)] total number of constraints: {}",
+            cs.num_constraints()
+        );
+
+        Ok(())
+    }
+}
+
+impl MediumCircuit<Fr> {
+    pub fn calculate_output(&self) -> <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalCiphertext {
+        let is_werewolf = self
+            .private_input
+            .iter()
+            .find(|input| input.id == self.public_input.executed_id)
+            .is_some_and(|input| input.role_id == Fr::from(WEREWOLF_ROLE_ID));
+
+        let message = match is_werewolf {
+            true => <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::prime_subgroup_generator(),
+            false => <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::default(),
+        };
+        <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme::encrypt(
+            &self.public_input.elgamal_param,
+            &self.public_input.pub_key,
+            &message,
+            &self.private_input[0].randomness,
+        )
+        .unwrap()
+    }
+}
+
+impl MediumCircuit<MpcField<Fr>> {
+    pub fn calculate_output(
+        &self,
+    ) -> <MpcField<Fr> as ElGamalLocalOrMPC<MpcField<Fr>>>::ElGamalCiphertext {
+        // 処刑者の id は公開値なので、その行の役職IDだけを見る
+        let role_id = self
+            .private_input
+            .iter()
+            .find(|input| input.id == self.public_input.executed_id)
+            .map(|input| input.role_id)
+            .unwrap_or_else(MpcField::<Fr>::zero);
+
+        // 役職IDとの一致判定なので is_werewolf は必ず 0 or 1
+        let mut is_werewolf = (role_id - MpcField::<Fr>::from(WEREWOLF_ROLE_ID))
+            .sync_is_zero_shared()
+            .field();
+        let is_werewolf_scalar = is_werewolf.sync_modulus_conversion();
+        let base = <MpcField<Fr> as ElGamalLocalOrMPC<MpcField<Fr>>>::ElGamalPlaintext::prime_subgroup_generator();
+        let message = base.scalar_mul(is_werewolf_scalar).into();
+
+        <MpcField<Fr> as ElGamalLocalOrMPC<MpcField<Fr>>>::ElGamalScheme::encrypt(
+            &self.public_input.elgamal_param,
+            &self.public_input.pub_key,
+            &message,
+            &self.private_input[0].randomness,
+        )
+        .unwrap()
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for MediumCircuit<Fr> {
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<Fr>,
+    ) -> Result<(), ark_relations::r1cs::SynthesisError> {
+        check_medium_inputs(&self.private_input, &self.public_input)?;
+
+        // 公開入力は処刑者 id が先頭、その後は DivinationCircuit と同じ並び
+        let executed_id_var = FpVar::new_input(cs.clone(), || {
+            Ok(Fr::from(self.public_input.executed_id as u32))
+        })?;
+
+        // 各行の役職IDは後で role_commitment に開示し、人狼かどうかは役職IDから求める
+        let werewolf_role_id = FpVar::Constant(Fr::from(WEREWOLF_ROLE_ID));
+        let role_id_vars = self
+            .private_input
+            .iter()
+            .map(|input| {
+                FpVar::new_witness(ark_relations::ns!(cs, "role_id"), || Ok(input.role_id))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let is_werewolf_bit = role_id_vars
+            .iter()
+            .map(|role_id_var| role_id_var.is_eq(&werewolf_role_id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // 処刑者の行の is_werewolf だけを残す
+        let is_executed_werewolf = self
+            .private_input
+            .iter()
+            .zip(is_werewolf_bit.iter())
+            .map(|(input, is_werewolf)| {
+                let is_executed =
+                    executed_id_var.is_eq(&FpVar::Constant(Fr::from(input.id as u32)))?;
+                is_werewolf.and(&is_executed)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let is_executed_werewolf_bit = Boolean::kary_or(is_executed_werewolf.as_slice())?;
+
+        // 平文の点は定数なので、証明者が差し替えることはできない
+        let one_point = <Fr as ElGamalLocalOrMPC<Fr>>::EdwardsVar::new_constant(
+            ark_relations::ns!(cs, "werewolf_point"),
+            <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::prime_subgroup_generator(),
+        )?;
+
+        let zero_point = <Fr as ElGamalLocalOrMPC<Fr>>::EdwardsVar::new_constant(
+            ark_relations::ns!(cs, "villager_point"),
+            <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::default(),
+        )?;
+
+        let is_executed_werewolf = is_executed_werewolf_bit.select(&one_point, &zero_point)?;
+
+        // elgamal encryption
+
+        let param_var = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalParamVar::new_input(
+            ark_relations::ns!(cs, "gadget_parameters"),
+            || Ok(self.public_input.elgamal_param.clone()),
+        )?;
+
+        let randomness_bits_var = self.private_input[0]
+            .randomness
+            .0
+            .into_repr()
+            .to_bits_le()
+            .iter()
+            .map(|b| Boolean::new_witness(cs.clone(), || Ok(*b)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // allocate public key
+        let pub_key_var = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPublicKeyVar::new_input(
+            ark_relations::ns!(cs, "gadget_public_key"),
+            || Ok(self.public_input.pub_key),
+        )?;
+
+        // allocate the output
+        let enc_result_var = {
+            // compute s = randomness*pk
+            let s = Fr::get_public_key(&pub_key_var)
+                .clone()
+                .scalar_mul_le(randomness_bits_var.iter())?;
+
+            // compute c1 = randomness*generator
+            let c1 = Fr::get_generator(&param_var)
+                .clone()
+                .scalar_mul_le(randomness_bits_var.iter())?;
+
+            // compute c2 = m + s
+            let c2 = is_executed_werewolf.clone() + s;
+
+            <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalCiphertextVar::new(c1, c2)
+        };
+
+        // compare
+        let ciphertext = self.calculate_output();
+        let enc_result_var2 = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalCiphertextVar::new_input(
+            ark_relations::ns!(cs, "gadget_commitment"),
+            || Ok(ciphertext),
+        )?;
+
+        enc_result_var.enforce_equal(&enc_result_var2)?;
+
+        // 暗号文の後に role_commitment が id 順に続く。各行の役職IDをそこに結びつける
+        let role_commitment_vars =
+            alloc_commitment_inputs::<Fr>(cs.clone(), &self.public_input.role_commitment)?;
+        let pedersen_param_var = <Fr as LocalOrMPC<Fr>>::PedersenParamVar::new_constant(
+            ark_relations::ns!(cs, "pedersen_param"),
+            &self.public_input.pedersen_param,
+        )?;
+        for (input, role_id_var) in self.private_input.iter().zip(role_id_vars.iter()) {
+            enforce_pedersen_opening::<Fr>(
+                cs.clone(),
+                &pedersen_param_var,
+                &role_id_var.to_bytes()?,
+                &input.role_randomness,
+                &role_commitment_vars[input.id],
+            )?;
+        }
+
+        let ciphertext_vars = ciphertext_var_coordinates(&enc_result_var2);
+        // 処刑者 id も結果として含める
+        let result_vars = std::iter::once(executed_id_var)
+            .chain(ciphertext_vars)
+            .collect::<Vec<_>>();
+        enforce_state_chain(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::Medium,
+            &role_commitment_vars
+                .iter()
+                .map(commitment_var_coordinates)
+                .collect::<Vec<_>>(),
+            None,
+            None,
+            &result_vars,
+        )?;
+
+        println!(
+            "[MediumCircuit(Local)] instance vars: {}",
+            cs.num_instance_variables()
+        );
+        println!(
+            "[MediumCircuit(Local)] witness vars: {}",
+            cs.num_witness_variables()
+        );
+        println!(
+            "[MediumCircuit(Local)] total number of constraints: {}",
+            cs.num_constraints()
+        );
+
+        Ok(())
+    }
+}
+
+impl ConstraintSynthesizer<MpcField<Fr>> for MediumCircuit<MpcField<Fr>> {
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<MpcField<Fr>>,
+    ) -> Result<(), ark_relations::r1cs::SynthesisError> {
+        check_medium_inputs(&self.private_input, &self.public_input)?;
+
+        // 公開入力は処刑者 id が先頭、その後は DivinationCircuit と同じ並び
+        let executed_id_var = MpcFpVar::new_input(cs.clone(), || {
+            Ok(MpcField::<Fr>::from(self.public_input.executed_id as u32))
+        })?;
+
+        // 各行の役職IDは後で role_commitment に開示し、人狼かどうかは役職IDから求める
+        let werewolf_role_id =
+            MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(WEREWOLF_ROLE_ID))?;
+        let role_id_vars = self
+            .private_input
+            .iter()
+            .map(|input| {
+                MpcFpVar::new_witness(ark_relations::ns!(cs, "role_id"), || Ok(input.role_id))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let is_werewolf_bit = role_id_vars
+            .iter()
+            .map(|role_id_var| (role_id_var - &werewolf_role_id).is_zero())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // 処刑者の行の is_werewolf だけを残す
+        let is_executed_werewolf = self
+            .private_input
+            .iter()
+            .zip(is_werewolf_bit.iter())
+            .map(|(input, is_werewolf)| {
+                let id_var =
+                    MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(input.id as u32))?;
+                let is_executed = (&executed_id_var - &id_var).is_zero()?;
+                is_werewolf.and(&is_executed)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let is_executed_werewolf_bit = MpcBoolean::kary_or(is_executed_werewolf.as_slice())?;
+
+        // 平文の点は定数なので、証明者が差し替えることはできない
+        let one_point = <MpcField<Fr> as ElGamalLocalOrMPC<MpcField<Fr>>>::EdwardsVar::new_constant(
+            ark_relations::ns!(cs, "werewolf_point"),
+            <MpcField<Fr> as ElGamalLocalOrMPC<MpcField<Fr>>>::ElGamalPlaintext::prime_subgroup_generator(),
+        )?;
+
+        let zero_point =
+            <MpcField<Fr> as ElGamalLocalOrMPC<MpcField<Fr>>>::EdwardsVar::new_constant(
+                ark_relations::ns!(cs, "villager_point"),
+                <MpcField<Fr> as ElGamalLocalOrMPC<MpcField<Fr>>>::ElGamalPlaintext::default(),
+            )?;
+
+        let is_executed_werewolf =
+            MpcField::<Fr>::select(&is_executed_werewolf_bit, &one_point, &zero_point)?;
+
+        // elgamal encryption
+
+        let param_var =
+            <MpcField<Fr> as ElGamalLocalOrMPC<MpcField<Fr>>>::ElGamalParamVar::new_input(
+                ark_relations::ns!(cs, "gadget_parameters"),
+                || Ok(self.public_input.elgamal_param.clone()),
+            )?;
+
+        let mut randomness_bits_mpc = self.private_input[0]
+            .randomness
+            .0
+            .sync_bit_decomposition()
+            .iter()
+            .map(|b| b.field().sync_modulus_conversion())
+            .collect::<Vec<_>>();
+
+        // Pad with zeros to match Fr bit size (to_bits_le() returns fixed length)
+        randomness_bits_mpc.resize(256, MpcField::<Fr>::zero());
+
+        let randomness_bits_var = MpcBoolean::new_witness_vec(cs.clone(), &randomness_bits_mpc)?;
+
+        // allocate public key
+        let pub_key_var =
+            <MpcField<Fr> as ElGamalLocalOrMPC<MpcField<Fr>>>::ElGamalPublicKeyVar::new_input(
+                ark_relations::ns!(cs, "gadget_public_key"),
+                || Ok(self.public_input.pub_key),
+            )?;
+
+        // allocate the output
+        let enc_result_var = {
+            // compute s = randomness*pk
+            let s = MpcField::<Fr>::get_public_key(&pub_key_var)
+                .clone()
+                .scalar_mul_le(randomness_bits_var.iter())?;
+
+            // compute c1 = randomness*generator
+            let c1 = MpcField::<Fr>::get_generator(&param_var)
+                .clone()
+                .scalar_mul_le(randomness_bits_var.iter())?;
+
+            // compute c2 = m + s
+            let c2 = is_executed_werewolf.clone() + s;
+
+            <MpcField<Fr> as ElGamalLocalOrMPC<MpcField<Fr>>>::ElGamalCiphertextVar::new(c1, c2)
+        };
+
+        // compare
+        let ciphertext = self.calculate_output();
+        let enc_result_var2 =
+            <MpcField<Fr> as ElGamalLocalOrMPC<MpcField<Fr>>>::ElGamalCiphertextVar::new_input(
+                ark_relations::ns!(cs, "gadget_commitment"),
+                || Ok(ciphertext.clone()),
+            )?;
+
+        enc_result_var.enforce_equal(&enc_result_var2)?;
+
+        // 暗号文の後に role_commitment が id 順に続く。各行の役職IDをそこに結びつける
+        let role_commitment_vars = alloc_commitment_inputs::<MpcField<Fr>>(
+            cs.clone(),
+            &self.public_input.role_commitment,
+        )?;
+        let pedersen_param_var =
+            <MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenParamVar::new_constant(
+                ark_relations::ns!(cs, "pedersen_param"),
+                &self.public_input.pedersen_param,
+            )?;
+        for (input, role_id_var) in self.private_input.iter().zip(role_id_vars.iter()) {
+            enforce_pedersen_opening::<MpcField<Fr>>(
+                cs.clone(),
+                &pedersen_param_var,
+                &role_id_var.to_bytes()?,
+                &input.role_randomness,
+                &role_commitment_vars[input.id],
+            )?;
+        }
+
+        let ciphertext_vars = ciphertext_var_coordinates_mpc(&enc_result_var2);
+        // 処刑者 id も結果として含める
+        let result_vars = std::iter::once(executed_id_var)
+            .chain(ciphertext_vars)
+            .collect::<Vec<_>>();
+        enforce_state_chain_mpc(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::Medium,
+            &role_commitment_vars
+                .iter()
+                .map(commitment_var_coordinates_mpc)
+                .collect::<Vec<_>>(),
+            None,
+            None,
+            &result_vars,
+        )?;
+
+        println!(
+            "[MediumCircuit(MPC)] instance vars: {}",
+            cs.num_instance_variables()
+        );
+        println!(
+            "[MediumCircuit(MPC)] witness vars: {}",
+            cs.num_witness_variables()
+        );
+        println!(
+            "[MediumCircuit(MPC)] total number of constraints: {}",
+            cs.num_constraints()
+        );
+
+        Ok(())
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for KeyPublicizeCircuit<Fr> {
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<Fr>,
+    ) -> Result<(), ark_relations::r1cs::SynthesisError> {
+        if self.public_input.role_commitment.len() != self.private_input.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let pedersen_param_var = <Fr as LocalOrMPC<Fr>>::PedersenParamVar::new_constant(
+            ark_relations::ns!(cs, "pedersen_param"),
+            &self.public_input.pedersen_param,
+        )?;
+        let fortune_teller_role_id = FpVar::Constant(Fr::from(FORTUNE_TELLER_ROLE_ID));
+
+        // 公開入力は占い師の公開鍵 (x, y) が先頭、その後に role_commitment が id 順に続く
+        let (pub_key_x, pub_key_y) = self.calculate_output();
+        let pub_key_x_var =
+            FpVar::new_input(ark_relations::ns!(cs, "pub_key_x"), || Ok(pub_key_x))?;
+        let pub_key_y_var =
+            FpVar::new_input(ark_relations::ns!(cs, "pub_key_y"), || Ok(pub_key_y))?;
+        let role_commitment_vars =
+            alloc_commitment_inputs::<Fr>(cs.clone(), &self.public_input.role_commitment)?;
+
+        // 各プレイヤーの役職IDを役職コミットメントに結びつけ、占い師かどうかは回路内で求める
+        let mut is_ft_var = Vec::with_capacity(self.private_input.len());
+        for (input, commitment_var) in self.private_input.iter().zip(role_commitment_vars.iter()) {
+            let role_id_var =
+                FpVar::new_witness(ark_relations::ns!(cs, "role_id"), || Ok(input.role_id))?;
+            enforce_pedersen_opening::<Fr>(
+                cs.clone(),
+                &pedersen_param_var,
+                &role_id_var.to_bytes()?,
+                &input.randomness,
+                commitment_var,
+            )?;
+            is_ft_var.push(FpVar::from(role_id_var.is_eq(&fortune_teller_role_id)?));
+        }
+
+        // 占い師はちょうど1人
+        let num_fortune_teller_var = is_ft_var
+            .iter()
+            .fold(<FpVar<Fr> as Zero>::zero(), |acc, b| acc + b);
+        num_fortune_teller_var.enforce_equal(&FpVar::one())?;
+
+        let mut sum_x_var = <FpVar<Fr> as Zero>::zero();
+        let mut sum_y_var = <FpVar<Fr> as Zero>::zero();
+        for (input, is_ft) in self.private_input.iter().zip(is_ft_var.iter()) {
+            let x_var = FpVar::new_witness(ark_relations::ns!(cs, "pub_key_or_dummy_x"), || {
+                Ok(input.pub_key_or_dummy_x)
+            })?;
+            let y_var = FpVar::new_witness(ark_relations::ns!(cs, "pub_key_or_dummy_y"), || {
+                Ok(input.pub_key_or_dummy_y)
+            })?;
+            sum_x_var += x_var * is_ft;
+            sum_y_var += y_var * is_ft;
+        }
+
+        // 公開される占い師の公開鍵
+        pub_key_x_var.enforce_equal(&sum_x_var)?;
+        pub_key_y_var.enforce_equal(&sum_y_var)?;
+
+        // 公開鍵は Baby Jubjub 上の点: a * x^2 + y^2 = 1 + d * x^2 * y^2
+        let (coeff_a, coeff_d) = baby_jubjub_coefficients::<Fr>();
+        let x2 = pub_key_x_var.square()?;
+        let y2 = pub_key_y_var.square()?;
+        let lhs = &x2 * coeff_a + &y2;
+        let rhs = (x2 * y2) * coeff_d + Fr::one();
+        lhs.enforce_equal(&rhs)?;
+
+        enforce_state_chain(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::KeyPublicize,
+            &role_commitment_vars
+                .iter()
+                .map(commitment_var_coordinates)
+                .collect::<Vec<_>>(),
+            None,
+            None,
+            &[pub_key_x_var, pub_key_y_var],
+        )?;
+
+        println!(
+            "[KeyPublicizeCircuit(Local)] instance vars: {}",
+            cs.num_instance_variables()
+        );
+        println!(
+            "[KeyPublicizeCircuit(Local)] witness vars: {}",
+            cs.num_witness_variables()
+        );
+        println!(
+            "[KeyPublicizeCircuit(Local)] total number of constraints: {}",
+            cs.num_constraints()
+        );
+
+        Ok(())
+    }
+}
+
+impl ConstraintSynthesizer<MpcField<Fr>> for KeyPublicizeCircuit<MpcField<Fr>> {
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<MpcField<Fr>>,
+    ) -> Result<(), ark_relations::r1cs::SynthesisError> {
+        if self.public_input.role_commitment.len() != self.private_input.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let pedersen_param_var =
+            <MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenParamVar::new_constant(
+                ark_relations::ns!(cs, "pedersen_param"),
+                &self.public_input.pedersen_param,
+            )?;
+        let fortune_teller_role_id =
+            MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(FORTUNE_TELLER_ROLE_ID))?;
+        let one = <MpcFpVar<MpcField<Fr>> as One>::one();
+        let zero = <MpcFpVar<MpcField<Fr>> as Zero>::zero();
+
+        // 公開入力は占い師の公開鍵 (x, y) が先頭、その後に role_commitment が id 順に続く
+        let (pub_key_x, pub_key_y) = self.calculate_output();
+        let pub_key_x_var =
+            MpcFpVar::new_input(ark_relations::ns!(cs, "pub_key_x"), || Ok(pub_key_x))?;
+        let pub_key_y_var =
+            MpcFpVar::new_input(ark_relations::ns!(cs, "pub_key_y"), || Ok(pub_key_y))?;
+        let role_commitment_vars = alloc_commitment_inputs::<MpcField<Fr>>(
+            cs.clone(),
+            &self.public_input.role_commitment,
+        )?;
+
+        // 各プレイヤーの役職IDを役職コミットメントに結びつけ、占い師かどうかは回路内で求める
+        let mut is_ft_var = Vec::with_capacity(self.private_input.len());
+        for (input, commitment_var) in self.private_input.iter().zip(role_commitment_vars.iter()) {
+            let role_id_var =
+                MpcFpVar::new_witness(ark_relations::ns!(cs, "role_id"), || Ok(input.role_id))?;
+            enforce_pedersen_opening::<MpcField<Fr>>(
+                cs.clone(),
+                &pedersen_param_var,
+                &role_id_var.to_bytes()?,
+                &input.randomness,
+                commitment_var,
+            )?;
+            is_ft_var.push(MpcFpVar::conditionally_select(
+                &(&role_id_var - &fortune_teller_role_id).is_zero()?,
+                &one,
+                &zero,
+            )?);
+        }
+
+        // 占い師はちょうど1人
+        let num_fortune_teller_var = is_ft_var.iter().fold(zero.clone(), |acc, b| acc + b);
+        num_fortune_teller_var.enforce_equal(&one)?;
+
+        let mut sum_x_var = zero.clone();
+        let mut sum_y_var = zero.clone();
+        for (input, is_ft) in self.private_input.iter().zip(is_ft_var.iter()) {
+            let x_var =
+                MpcFpVar::new_witness(ark_relations::ns!(cs, "pub_key_or_dummy_x"), || {
+                    Ok(input.pub_key_or_dummy_x)
+                })?;
+            let y_var =
+                MpcFpVar::new_witness(ark_relations::ns!(cs, "pub_key_or_dummy_y"), || {
+                    Ok(input.pub_key_or_dummy_y)
+                })?;
+            sum_x_var += x_var * is_ft;
+            sum_y_var += y_var * is_ft;
+        }
+
+        // 公開される占い師の公開鍵
+        pub_key_x_var.enforce_equal(&sum_x_var)?;
+        pub_key_y_var.enforce_equal(&sum_y_var)?;
+
+        // 公開鍵は Baby Jubjub 上の点: a * x^2 + y^2 = 1 + d * x^2 * y^2
+        let (coeff_a, coeff_d) = baby_jubjub_coefficients::<MpcField<Fr>>();
+        let x2 = pub_key_x_var.square()?;
+        let y2 = pub_key_y_var.square()?;
+        let lhs = &x2 * coeff_a + &y2;
+        let rhs = (x2 * y2) * coeff_d + MpcField::<Fr>::one();
+        lhs.enforce_equal(&rhs)?;
+
+        enforce_state_chain_mpc(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::KeyPublicize,
+            &role_commitment_vars
+                .iter()
+                .map(commitment_var_coordinates_mpc)
+                .collect::<Vec<_>>(),
+            None,
+            None,
+            &[pub_key_x_var, pub_key_y_var],
+        )?;
+
+        println!(
+            "[KeyPublicizeCircuit(MPC)] instance vars: {}",
+            cs.num_instance_variables()
+        );
+        println!(
+            "[KeyPublicizeCircuit(MPC)] witness vars: {}",
+            cs.num_witness_variables()
+        );
+        println!(
+            "[KeyPublicizeCircuit(MPC)] total number of constraints: {}",
+            cs.num_constraints()
+        );
+
+        Ok(())
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for DivinationCircuit<Fr> {
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<Fr>,
+    ) -> Result<(), ark_relations::r1cs::SynthesisError> {
+        let is_werewolf_bit = self
+            .private_input
+            .iter()
+            .map(|input| Boolean::new_witness(cs.clone(), || Ok(input.is_werewolf.is_one())))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let is_target_bit = self
+            .private_input
+            .iter()
+            .map(|input| {
+                input
+                    .is_target
+                    .iter()
+                    .map(|b| Boolean::new_witness(cs.clone(), || Ok(b.is_one())))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let player_num = self.public_input.player_num;
+        let row_ids = self
+            .private_input
+            .iter()
+            .map(|input| input.id)
+            .collect::<Vec<_>>();
+
+        check_alive_mask_rows(
+            &row_ids,
+            player_num,
+            self.public_input.state_chain.alive_mask,
+        )?;
+        if is_target_bit.iter().any(|row| row.len() != player_num) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let is_target_sum_bit = (0..player_num)
+            .map(|j| {
+                Boolean::kary_or(
+                    &is_target_bit
+                        .iter() // i を動かす
+                        .map(|row| row[j].clone())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let is_wt = is_werewolf_bit
+            .iter()
+            .enumerate()
+            .map(|(row_index, x)| x.and(&is_target_sum_bit[row_ids[row_index]]))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let is_target_werewolf_bit = Boolean::kary_or(is_wt.as_slice())?;
+
+        let one_point = <Fr as ElGamalLocalOrMPC<Fr>>::EdwardsVar::new_witness(
+            ark_relations::ns!(cs, "gadget_randomness"),
+            || Ok(<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::prime_subgroup_generator()),
+        )?;
+
+        let zero_point = <Fr as ElGamalLocalOrMPC<Fr>>::EdwardsVar::new_witness(
+            ark_relations::ns!(cs, "gadget_randomness"),
+            || Ok(<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::default()),
+        )?;
+
+        let is_target_werewolf = is_target_werewolf_bit.select(&one_point, &zero_point)?;
+
+        // 死亡者の行は空で、占い先は生存者に限られる
+        let (alive_mask_var, alive_var) = alloc_alive_mask(
+            cs.clone(),
+            self.public_input.state_chain.alive_mask,
+            player_num,
+        )?;
+        let one = <FpVar<Fr> as One>::one();
+        let zero = <FpVar<Fr> as Zero>::zero();
+        for ((row, is_werewolf), row_alive) in is_target_bit
+            .iter()
+            .zip(is_werewolf_bit.iter())
+            .zip(alive_var.iter())
+        {
+            let row_dead = &one - row_alive;
+            (FpVar::from(is_werewolf.clone()) * &row_dead).enforce_equal(&zero)?;
+            for (target, target_alive) in row.iter().zip(alive_var.iter()) {
+                let target = FpVar::from(target.clone());
+                (&target * &row_dead).enforce_equal(&zero)?;
+                (&target * (&one - target_alive)).enforce_equal(&zero)?;
+            }
+        }
+
+        // elgamal encryption
+
+        let param_var = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalParamVar::new_input(
+            ark_relations::ns!(cs, "gadget_parameters"),
+            || Ok(self.public_input.elgamal_param.clone()),
+        )?;
+
+        // let randomness_sum_var = randomness_var.iter().fold(
+        //     <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalRandomnessVar>::default(),
+        //     |mut acc, x| {
+        //         acc += x;
+        //         acc
+        //     },
+        // );
+
+        let randomness_bits_var = self.private_input[0]
+            .randomness
+            .0
+            .into_repr()
+            .to_bits_le()
+            .iter()
+            .map(|b| Boolean::new_witness(cs.clone(), || Ok(*b)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // allocate public key
+        let pub_key_var = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPublicKeyVar::new_input(
+            ark_relations::ns!(cs, "gadget_public_key"),
+            || Ok(self.public_input.pub_key),
+        )?;
+
+        // allocate the output
+        let enc_result_var = {
+            // flatten randomness to little-endian bit vector
+            let randomness = randomness_bits_var;
+
+            // compute s = randomness*pk
+            let s = Fr::get_public_key(&pub_key_var)
+                .clone()
+                .scalar_mul_le(randomness.iter())?;
+
+            // compute c1 = randomness*generator
+            let c1 = Fr::get_generator(&param_var)
+                .clone()
+                .scalar_mul_le(randomness.iter())?;
+
+            // compute c2 = m + s
             let c2 = is_target_werewolf.clone() + s;
 
             <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalCiphertextVar::new(c1, c2)
         };
 
         // compare
+        let ciphertext = self.calculate_output();
         let enc_result_var2 = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalCiphertextVar::new_input(
             ark_relations::ns!(cs, "gadget_commitment"),
-            || {
-                let sum_target = (0..player_num)
-                    .map(|i| {
-                        let mut tmp = Fr::from(0);
-                        for j in 0..self.private_input.len() {
-                            tmp += self.private_input[j].is_target[i];
-                        }
-                        tmp
-                    })
-                    .collect::<Vec<_>>();
-                let is_werewolf: Fr = self
-                    .private_input
-                    .iter()
-                    .zip(row_ids.iter())
-                    .map(|(input, id)| input.is_werewolf * sum_target[*id])
-                    .sum();
-
-                let message = match is_werewolf.is_one() {
-                    true => {
-                        <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::prime_subgroup_generator()
-                    }
-                    false => <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::default(),
-                };
-                let enc_result = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme::encrypt(
-                    &self.public_input.elgamal_param,
-                    &self.public_input.pub_key,
-                    &message,
-                    &self.private_input[0].randomness,
-                )
-                .unwrap();
-                Ok(enc_result)
-            },
+            || Ok(ciphertext),
         )?;
 
         enc_result_var.enforce_equal(&enc_result_var2)?;
 
+        // 公開入力の暗号文の座標を state digest に含める
+        let ciphertext_vars = ciphertext_var_coordinates(&enc_result_var2);
+        enforce_state_chain(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::Divination,
+            &[],
+            Some(alive_mask_var),
+            None,
+            &ciphertext_vars,
+        )?;
+
         // TODO: Add verify commitments
         // self.verify_commitments(cs.clone())?;
 
@@ -568,18 +1854,20 @@ impl ConstraintSynthesizer<MpcField<Fr>> for DivinationCircuit<MpcField<Fr>> {
             .map(|input| MpcBoolean::new_witness_vec(cs.clone(), &input.is_target))
             .collect::<Result<Vec<_>, _>>()?;
 
-        let player_num = is_target_bit[0].len();
+        let player_num = self.public_input.player_num;
         let row_ids = self
             .private_input
             .iter()
             .map(|input| input.id)
             .collect::<Vec<_>>();
 
-        let mut seen_row_ids = HashSet::new();
-        for id in &row_ids {
-            if *id >= player_num || !seen_row_ids.insert(*id) {
-                return Err(SynthesisError::Unsatisfiable);
-            }
+        check_alive_mask_rows(
+            &row_ids,
+            player_num,
+            self.public_input.state_chain.alive_mask,
+        )?;
+        if is_target_bit.iter().any(|row| row.len() != player_num) {
+            return Err(SynthesisError::Unsatisfiable);
         }
 
         let is_target_sum_bit = (0..player_num)
@@ -619,6 +1907,29 @@ impl ConstraintSynthesizer<MpcField<Fr>> for DivinationCircuit<MpcField<Fr>> {
         let is_target_werewolf =
             MpcField::<Fr>::select(&is_target_werewolf_bit, &one_point, &zero_point)?;
 
+        // 死亡者の行は空で、占い先は生存者に限られる
+        let (alive_mask_var, alive_var) = alloc_alive_mask_mpc(
+            cs.clone(),
+            self.public_input.state_chain.alive_mask,
+            player_num,
+        )?;
+        let one = <MpcFpVar<MpcField<Fr>> as One>::one();
+        let zero = <MpcFpVar<MpcField<Fr>> as Zero>::zero();
+        for ((row, is_werewolf), row_alive) in is_target_bit
+            .iter()
+            .zip(is_werewolf_bit.iter())
+            .zip(alive_var.iter())
+        {
+            let row_dead = &one - row_alive;
+            (MpcFpVar::conditionally_select(is_werewolf, &one, &zero)? * &row_dead)
+                .enforce_equal(&zero)?;
+            for (target, target_alive) in row.iter().zip(alive_var.iter()) {
+                let target = MpcFpVar::conditionally_select(target, &one, &zero)?;
+                (&target * &row_dead).enforce_equal(&zero)?;
+                (&target * (&one - target_alive)).enforce_equal(&zero)?;
+            }
+        }
+
         // elgamal encryption
 
         let param_var =
@@ -708,6 +2019,18 @@ impl ConstraintSynthesizer<MpcField<Fr>> for DivinationCircuit<MpcField<Fr>> {
 
         enc_result_var.enforce_equal(&enc_result_var2)?;
 
+        // 公開入力の暗号文の座標を state digest に含める
+        let ciphertext_vars = ciphertext_var_coordinates_mpc(&enc_result_var2);
+        enforce_state_chain_mpc(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::Divination,
+            &[],
+            Some(alive_mask_var),
+            None,
+            &ciphertext_vars,
+        )?;
+
         // TODO: Add verify commitments
         // self.verify_commitments(cs.clone())?;
 
@@ -733,37 +2056,47 @@ impl ConstraintSynthesizer<Fr> for RoleAssignmentCircuit<Fr> {
         self,
         cs: ark_relations::r1cs::ConstraintSystemRef<Fr>,
     ) -> Result<(), ark_relations::r1cs::SynthesisError> {
-        // TODO: Implement constraint generation logic here
-        // check player commitment
-        // assert_eq!(
-        //     self.public_input.num_players,
-        //     self.private_input.player_randomness.len()
-        // );
-        // assert_eq!(
-        //     self.public_input.num_players,
-        //     self.public_input.player_commitment.len()
-        // );
+        let num_players = self.public_input.num_players;
+        if self.private_input.len() != num_players
+            || self.public_input.role_commitment.len() != num_players
+            || self.public_input.player_commitment.len() != num_players
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
 
-        // TODO: check player commitment
-        // for i in 0..self.public_input.num_players {
-        //     let pedersen_circuit = PedersenComCircuit {
-        //         param: Some(self.pedersen_param.clone()),
-        //         input: self.player_randomness[i],
-        //         open: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::default(),
-        //         commit: self.player_commitment[i],
-        //     };
-        //     pedersen_circuit.generate_constraints(cs.clone())?;
-        // }
+        let pedersen_param_var = <Fr as LocalOrMPC<Fr>>::PedersenParamVar::new_constant(
+            ark_relations::ns!(cs, "pedersen_param"),
+            &self.public_input.pedersen_param,
+        )?;
+        let instance_mode = self.public_input.instance_mode;
+        let commitment_mode = instance_alloc_mode(instance_mode);
+        // Hashed のときに hash する instance。公開入力と同じ並び
+        let mut instance_var = Vec::with_capacity(4 * num_players + 4);
+
+        // check player commitment
+        for (input, commitment) in self
+            .private_input
+            .iter()
+            .zip(self.public_input.player_commitment.iter())
+        {
+            let player_randomness_var =
+                FpVar::new_witness(ark_relations::ns!(cs, "player_randomness"), || {
+                    Ok(input.player_randomness)
+                })?;
+            let commitment_var = alloc_pedersen_opening::<Fr>(
+                cs.clone(),
+                &pedersen_param_var,
+                &player_randomness_var.to_bytes()?,
+                &input.player_commitment_randomness,
+                commitment,
+                commitment_mode,
+            )?;
+            instance_var.extend(commitment_var_coordinates(&commitment_var));
+        }
 
         // initialize
-        let tau_matrix_var = na::DMatrix::from_iterator(
-            self.public_input.tau_matrix.nrows(),
-            self.public_input.tau_matrix.ncols(),
-            self.public_input.tau_matrix.iter().map(|b| {
-                FpVar::new_input(cs.clone(), || Ok(b))
-                    .expect("tau matrix var is not allocated correctly")
-            }),
-        );
+        // tau はプロファイルごとに固定なので定数として埋め込む
+        let tau_matrix_var = self.public_input.tau_matrix.map(FpVar::Constant);
 
         let shuffle_matrix_var = self
             .private_input
@@ -780,28 +2113,18 @@ impl ConstraintSynthesizer<Fr> for RoleAssignmentCircuit<Fr> {
             })
             .collect::<Vec<_>>();
 
-        let inverse_shuffle_matrix_var = self
-            .private_input
+        // each shuffle matrix is a permutation matrix and sub matrix is a identity matrix
+        for matrix in shuffle_matrix_var.iter() {
+            enforce_permutation_matrix(matrix, num_players)?;
+        }
+
+        // 置換行列の逆行列は転置なので、同じ変数から作る
+        let inverse_shuffle_matrix_var = shuffle_matrix_var
             .iter()
             .rev()
-            .map(|input| {
-                na::DMatrix::from_iterator(
-                    input.shuffle_matrices.nrows(),
-                    input.shuffle_matrices.ncols(),
-                    input.shuffle_matrices.transpose().iter().map(|b| {
-                        FpVar::new_witness(cs.clone(), || Ok(b))
-                            .expect("shuffle matrix var is not allocated correctly")
-                    }),
-                )
-            })
+            .map(|matrix| matrix.transpose())
             .collect::<Vec<_>>();
 
-        // each shuffle matrix is a permutation matrix and sub matrix is a identity matrix
-        // Warning: This should be enforced, but we skip it to reduce the constraint count.
-        // shuffle_matrix_var.iter().for_each(|matrix| {
-        //     enforce_permutation_matrix(matrix, self.public_input.num_players).unwrap()
-        // });
-
         // calculate
         // M = Product of shuffle_matrix
         let matrix_m_var = shuffle_matrix_var
@@ -819,9 +2142,9 @@ impl ConstraintSynthesizer<Fr> for RoleAssignmentCircuit<Fr> {
         // rho = M^-1 * tau * M
         let rho_var = inverse_matrix_m_var * &tau_matrix_var * &matrix_m_var;
 
-        let mut rho_sequence_var = Vec::with_capacity(self.public_input.num_players);
+        let mut rho_sequence_var = Vec::with_capacity(num_players);
         let mut current_rho = rho_var.clone();
-        for _ in 0..self.public_input.num_players {
+        for _ in 0..num_players {
             rho_sequence_var.push(current_rho.clone());
             current_rho *= rho_var.clone(); // rho^(i+1) = rho^i * rho
         }
@@ -830,7 +2153,7 @@ impl ConstraintSynthesizer<Fr> for RoleAssignmentCircuit<Fr> {
         let length = self.public_input.tau_matrix.nrows();
 
         // 1. gen one-hot vector
-        let unit_vecs = (0..self.public_input.num_players)
+        let unit_vecs = (0..num_players)
             .map(|i| test_one_hot_vector(length, i, cs.clone()))
             .collect::<Vec<_>>();
 
@@ -842,29 +2165,68 @@ impl ConstraintSynthesizer<Fr> for RoleAssignmentCircuit<Fr> {
                     .iter()
                     .map(|rho| {
                         let res_index = rho * unit_vec_j.clone();
-                        // Warning: This should be enforced, but we skip it to reduce the constraint count.
-                        test_index_to_value(res_index, false).unwrap()
+                        test_index_to_value(res_index, true)
                     })
-                    .collect::<Vec<_>>()
+                    .collect::<Result<Vec<_>, _>>()
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
 
-        // Warning: This should be enforced, but we skip it to reduce the constraint count.
-        let _calced_role = calced_vec
+        // 3. role_val = max(rho^1(i), ..., rho^n(i))
+        let calced_role = calced_vec
             .iter()
-            .map(|val| test_max(val, self.public_input.num_players, false).unwrap())
-            .collect::<Vec<_>>();
+            .map(|val| test_max(val, num_players, true))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        // // commitment
-        // for i in 0..self.num_players {
-        //     let pedersen_circuit = PedersenComCircuit {
-        //         param: Some(self.public_input.pedersen_param.clone()),
-        //         input: calced_role[i].value().unwrap_or_default(),
-        //         open: self.private_input[i].randomness.clone(),
-        //         commit: self.public_input.role_commitment[i],
-        //     };
-        //     pedersen_circuit.generate_constraints(cs.clone())?;
-        // }
+        // 4. role_val -> 役職ID (role_id_lookup の対応)
+        let role_id_lookup = role_id_lookup::<Fr>(&self.public_input.grouping_parameter, length);
+        let calced_role_id = calced_role
+            .iter()
+            .map(|role_value| {
+                let mut role_id = <FpVar<Fr> as Zero>::zero();
+                for (idx, mapped_role_id) in role_id_lookup.iter().enumerate() {
+                    let is_eq = role_value.is_eq(&FpVar::Constant(Fr::from(idx as u32)))?;
+                    role_id += FpVar::conditionally_select(
+                        &is_eq,
+                        &FpVar::Constant(*mapped_role_id),
+                        &<FpVar<Fr> as Zero>::zero(),
+                    )?;
+                }
+                Ok(role_id)
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        // commitment
+        for (i, role_id) in calced_role_id.iter().enumerate() {
+            let commitment_var = alloc_pedersen_opening::<Fr>(
+                cs.clone(),
+                &pedersen_param_var,
+                &role_id.to_bytes()?,
+                &self.private_input[i].randomness,
+                &self.public_input.role_commitment[i],
+                commitment_mode,
+            )?;
+            instance_var.extend(commitment_var_coordinates(&commitment_var));
+        }
+
+        // player_commitment の後に role_commitment を続けて hash する
+        let commitments = instance_var
+            .chunks(2)
+            .map(|point| [point[0].clone(), point[1].clone()])
+            .collect::<Vec<_>>();
+        let state_chain_var = alloc_state_chain(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::RoleAssignment,
+            &commitments,
+            None,
+            None,
+            &[],
+            commitment_mode,
+        )?;
+        if instance_mode == InstanceMode::Hashed {
+            instance_var.extend(state_chain_var);
+            enforce_instance_hash(cs.clone(), &instance_var)?;
+        }
 
         println!(
             "[RoleAssignmentCircuit(Local)] instance vars: {}",
@@ -888,34 +2250,51 @@ impl ConstraintSynthesizer<MpcField<Fr>> for RoleAssignmentCircuit<MpcField<Fr>>
         self,
         cs: ark_relations::r1cs::ConstraintSystemRef<MpcField<Fr>>,
     ) -> Result<(), ark_relations::r1cs::SynthesisError> {
-        // TODO: Implement constraint generation logic here
-        // check player commitment
-        // assert_eq!(
-        //     self.public_input.num_players,
-        //     self.private_input.player_randomness.len()
-        // );
-        // assert_eq!(
-        //     self.public_input.num_players,
-        //     self.public_input.player_commitment.len()
-        // );
+        let num_players = self.public_input.num_players;
+        if self.private_input.len() != num_players
+            || self.public_input.role_commitment.len() != num_players
+            || self.public_input.player_commitment.len() != num_players
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
 
-        // TODO: check player commitment
-        // for i in 0..self.public_input.num_players {
-        //     let pedersen_circuit = PedersenComCircuit {
-        //         param: Some(self.pedersen_param.clone()),
-        //         input: self.player_randomness[i],
-        //         open: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::default(),
-        //         commit: self.player_commitment[i],
-        //     };
-        //     pedersen_circuit.generate_constraints(cs.clone())?;
-        // }
+        let pedersen_param_var =
+            <MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenParamVar::new_constant(
+                ark_relations::ns!(cs, "pedersen_param"),
+                &self.public_input.pedersen_param,
+            )?;
+        let instance_mode = self.public_input.instance_mode;
+        let commitment_mode = instance_alloc_mode(instance_mode);
+        let mut instance_var = Vec::with_capacity(4 * num_players + 4);
+
+        // check player commitment
+        for (input, commitment) in self
+            .private_input
+            .iter()
+            .zip(self.public_input.player_commitment.iter())
+        {
+            let player_randomness_var =
+                MpcFpVar::new_witness(ark_relations::ns!(cs, "player_randomness"), || {
+                    Ok(input.player_randomness)
+                })?;
+            let commitment_var = alloc_pedersen_opening::<MpcField<Fr>>(
+                cs.clone(),
+                &pedersen_param_var,
+                &player_randomness_var.to_bytes()?,
+                &input.player_commitment_randomness,
+                commitment,
+                commitment_mode,
+            )?;
+            instance_var.extend(commitment_var_coordinates_mpc(&commitment_var));
+        }
 
         // initialize
+        // tau はプロファイルごとに固定なので定数として埋め込む
         let tau_matrix_var = na::DMatrix::from_iterator(
             self.public_input.tau_matrix.nrows(),
             self.public_input.tau_matrix.ncols(),
             self.public_input.tau_matrix.iter().map(|b| {
-                MpcFpVar::new_input(cs.clone(), || Ok(b))
+                MpcFpVar::new_constant(cs.clone(), b)
                     .expect("tau matrix var is not allocated correctly")
             }),
         );
@@ -935,28 +2314,18 @@ impl ConstraintSynthesizer<MpcField<Fr>> for RoleAssignmentCircuit<MpcField<Fr>>
             })
             .collect::<Vec<_>>();
 
-        let inverse_shuffle_matrix_var = self
-            .private_input
+        // each shuffle matrix is a permutation matrix and sub matrix is a identity matrix
+        for matrix in shuffle_matrix_var.iter() {
+            enforce_permutation_matrix_mpc(matrix, num_players)?;
+        }
+
+        // 置換行列の逆行列は転置なので、同じ変数から作る
+        let inverse_shuffle_matrix_var = shuffle_matrix_var
             .iter()
             .rev()
-            .map(|input| {
-                na::DMatrix::from_iterator(
-                    input.shuffle_matrices.nrows(),
-                    input.shuffle_matrices.ncols(),
-                    input.shuffle_matrices.transpose().iter().map(|b| {
-                        MpcFpVar::new_witness(cs.clone(), || Ok(b))
-                            .expect("shuffle matrix var is not allocated correctly")
-                    }),
-                )
-            })
+            .map(|matrix| matrix.transpose())
             .collect::<Vec<_>>();
 
-        // each shuffle matrix is a permutation matrix and sub matrix is a identity matrix
-        // Warning: This should be enforced, but we skip it to reduce the constraint count.
-        // shuffle_matrix_var.iter().for_each(|matrix| {
-        //     enforce_permutation_matrix_mpc(matrix, self.public_input.num_players).unwrap()
-        // });
-
         // calculate
         // M = Product of shuffle_matrix
         let matrix_m_var = shuffle_matrix_var
@@ -974,9 +2343,9 @@ impl ConstraintSynthesizer<MpcField<Fr>> for RoleAssignmentCircuit<MpcField<Fr>>
         // rho = M^-1 * tau * M
         let rho_var = inverse_matrix_m_var * &tau_matrix_var * &matrix_m_var;
 
-        let mut rho_sequence_var = Vec::with_capacity(self.public_input.num_players);
+        let mut rho_sequence_var = Vec::with_capacity(num_players);
         let mut current_rho = rho_var.clone();
-        for _ in 0..self.public_input.num_players {
+        for _ in 0..num_players {
             rho_sequence_var.push(current_rho.clone());
             current_rho *= rho_var.clone(); // rho^(i+1) = rho^i * rho
         }
@@ -985,7 +2354,7 @@ impl ConstraintSynthesizer<MpcField<Fr>> for RoleAssignmentCircuit<MpcField<Fr>>
         let length = self.public_input.tau_matrix.nrows();
 
         // 1. gen one-hot vector
-        let unit_vecs = (0..self.public_input.num_players)
+        let unit_vecs = (0..num_players)
             .map(|i| test_one_hot_vector_mpc(length, i, cs.clone()))
             .collect::<Vec<_>>();
 
@@ -997,29 +2366,72 @@ impl ConstraintSynthesizer<MpcField<Fr>> for RoleAssignmentCircuit<MpcField<Fr>>
                     .iter()
                     .map(|rho| {
                         let res_index = rho * unit_vec_j.clone();
-                        // Warning: This should be enforced, but we skip it to reduce the constraint count.
-                        test_index_to_value_mpc(res_index, false).unwrap()
+                        test_index_to_value_mpc(res_index, true)
                     })
-                    .collect::<Vec<_>>()
+                    .collect::<Result<Vec<_>, _>>()
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, _>>()?;
 
-        // Warning: This should be enforced, but we skip it to reduce the constraint count.
-        let _calced_role = calced_vec
+        // 3. role_val = max(rho^1(i), ..., rho^n(i))
+        let calced_role = calced_vec
             .iter()
-            .map(|val| test_max_mpc(val, self.public_input.num_players, false).unwrap())
-            .collect::<Vec<_>>();
+            .map(|val| test_max_mpc(val, num_players, true))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        // // commitment
-        // for i in 0..self.num_players {
-        //     let pedersen_circuit = PedersenComCircuit {
-        //         param: Some(self.public_input.pedersen_param.clone()),
-        //         input: calced_role[i].value().unwrap_or_default(),
-        //         open: self.private_input[i].randomness.clone(),
-        //         commit: self.public_input.role_commitment[i],
-        //     };
-        //     pedersen_circuit.generate_constraints(cs.clone())?;
-        // }
+        // 4. role_val -> 役職ID (role_id_lookup の対応)
+        let role_id_lookup =
+            role_id_lookup::<MpcField<Fr>>(&self.public_input.grouping_parameter, length);
+        let calced_role_id = calced_role
+            .iter()
+            .map(|role_value| {
+                let mut role_id = <MpcFpVar<MpcField<Fr>> as Zero>::zero();
+                for (idx, mapped_role_id) in role_id_lookup.iter().enumerate() {
+                    let idx_var =
+                        MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(idx as u32))?;
+                    let mapped_role_id_var = MpcFpVar::new_constant(cs.clone(), mapped_role_id)?;
+                    let is_eq = (role_value - idx_var).is_zero()?;
+                    role_id += MpcFpVar::conditionally_select(
+                        &is_eq,
+                        &mapped_role_id_var,
+                        &<MpcFpVar<MpcField<Fr>> as Zero>::zero(),
+                    )?;
+                }
+                Ok(role_id)
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        // commitment
+        for (i, role_id) in calced_role_id.iter().enumerate() {
+            let commitment_var = alloc_pedersen_opening::<MpcField<Fr>>(
+                cs.clone(),
+                &pedersen_param_var,
+                &role_id.to_bytes()?,
+                &self.private_input[i].randomness,
+                &self.public_input.role_commitment[i],
+                commitment_mode,
+            )?;
+            instance_var.extend(commitment_var_coordinates_mpc(&commitment_var));
+        }
+
+        // player_commitment の後に role_commitment を続けて hash する
+        let commitments = instance_var
+            .chunks(2)
+            .map(|point| [point[0].clone(), point[1].clone()])
+            .collect::<Vec<_>>();
+        let state_chain_var = alloc_state_chain_mpc(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::RoleAssignment,
+            &commitments,
+            None,
+            None,
+            &[],
+            commitment_mode,
+        )?;
+        if instance_mode == InstanceMode::Hashed {
+            instance_var.extend(state_chain_var);
+            enforce_instance_hash_mpc(cs.clone(), &instance_var)?;
+        }
 
         println!(
             "[RoleAssignmentCircuit(MPC)] instance vars: {}",
@@ -1040,60 +2452,174 @@ impl ConstraintSynthesizer<MpcField<Fr>> for RoleAssignmentCircuit<MpcField<Fr>>
 
 impl WinningJudgementCircuit<Fr> {
     pub fn calculate_output(&self) -> Fr {
+        let win_condition = &self.public_input.win_condition;
+
         let werewolf_count = self
             .private_input
             .iter()
             .filter(|input| input.am_werewolf.is_one())
             .count();
 
-        let villagers_count = self
+        // 狂人・妖狐は人間として数える。死亡者の行は空なので生存者数から引く
+        // 行が生存者より多く人狼を主張しても panic させず、制約側で不充足にする
+        let alive_player_num = self.public_input.state_chain.alive_mask.count_ones() as usize;
+        let villagers_count = alive_player_num.saturating_sub(werewolf_count);
+
+        let fox_count = self
             .private_input
             .iter()
-            .filter(|input| input.am_werewolf.is_zero())
+            .filter(|input| input.am_fox.is_one())
             .count();
 
         // game_state
-        if werewolf_count == 0 {
-            Fr::from(2u32)
-        } else if werewolf_count >= villagers_count {
-            Fr::from(1u32)
+        let werewolf_threshold = villagers_count + win_condition.werewolf_win_on_majority as usize;
+        let outcome = if werewolf_count == 0 {
+            WinningJudgeOutcome::VillagerWin
+        } else if werewolf_count >= werewolf_threshold {
+            WinningJudgeOutcome::WerewolfWin
+        } else if win_condition.is_day_limit_reached(self.public_input.state_chain.day_count) {
+            WinningJudgeOutcome::VillagerWinByDayLimit
         } else {
-            Fr::from(3u32)
+            WinningJudgeOutcome::Continue
+        };
+
+        // 決着時に妖狐が生き残っていれば妖狐の勝利
+        if outcome.is_game_over() && fox_count > 0 {
+            Fr::from(WinningJudgeOutcome::FoxWin.code())
+        } else {
+            Fr::from(outcome.code())
         }
     }
+
+    // 勝利した陣営に属するプレイヤーの bitmask (bit i = プレイヤー i)。ゲーム続行中は 0。
+    pub fn calculate_winner_mask(&self) -> Fr {
+        let outcome = WinningJudgeOutcome::from_field(self.calculate_output());
+        let alive_mask = self.public_input.state_chain.alive_mask;
+
+        self.private_input
+            .iter()
+            .filter(|input| alive_mask.checked_shr(input.id as u32).unwrap_or(0) & 1 == 1)
+            .filter(|input| match outcome {
+                Some(WinningJudgeOutcome::WerewolfWin) => input.am_werewolf_team.is_one(),
+                Some(WinningJudgeOutcome::VillagerWin)
+                | Some(WinningJudgeOutcome::VillagerWinByDayLimit) => {
+                    input.am_werewolf_team.is_zero() && input.am_fox.is_zero()
+                }
+                Some(WinningJudgeOutcome::FoxWin) => input.am_fox.is_one(),
+                Some(WinningJudgeOutcome::Continue) | None => false,
+            })
+            .fold(Fr::zero(), |acc, input| {
+                acc + Fr::from(1u32.checked_shl(input.id as u32).unwrap_or(0))
+            })
+    }
 }
 
 impl WinningJudgementCircuit<MpcField<Fr>> {
     pub fn calculate_output(&self) -> MpcField<Fr> {
-        let alive_player_num = self.private_input.len();
+        let alive_player_num = self.public_input.state_chain.alive_mask.count_ones();
+        let win_condition = &self.public_input.win_condition;
+        let one = MpcField::<Fr>::one();
+        let state = |outcome: WinningJudgeOutcome| MpcField::<Fr>::from(outcome.code());
 
         let werewolf_count = self
             .private_input
             .iter()
             .fold(MpcField::<Fr>::zero(), |acc, input| acc + input.am_werewolf);
 
-        let villagers_count = MpcField::<Fr>::from(alive_player_num as u32) - werewolf_count;
+        let villagers_count = MpcField::<Fr>::from(alive_player_num) - werewolf_count;
 
-        let no_werewolf = werewolf_count.sync_is_zero_shared();
+        let fox_count = self
+            .private_input
+            .iter()
+            .fold(MpcField::<Fr>::zero(), |acc, input| acc + input.am_fox);
+
+        let no_werewolf = werewolf_count.sync_is_zero_shared().field();
+
+        // 人狼数 < 人間数 + majority の間は人数による決着がつかない
+        let majority = MpcField::<Fr>::from(win_condition.werewolf_win_on_majority as u32);
+        let continues = (werewolf_count + one)
+            .sync_is_smaller_than(&(villagers_count + majority))
+            .field();
+        // 日数は公開情報なので、上限に達したかどうかは平文で判定できる
+        let continue_state =
+            if win_condition.is_day_limit_reached(self.public_input.state_chain.day_count) {
+                state(WinningJudgeOutcome::VillagerWinByDayLimit)
+            } else {
+                state(WinningJudgeOutcome::Continue)
+            };
 
         // game_state
-        no_werewolf.field() * MpcField::<Fr>::from(2_u32)
-            + (!no_werewolf).field()
-                * ((werewolf_count + MpcField::<Fr>::one())
-                    .sync_is_smaller_than(&villagers_count)
-                    .field()
-                    * MpcField::<Fr>::from(3_u32)
-                    + (MpcField::<Fr>::one()
-                        - ((werewolf_count + MpcField::<Fr>::one())
-                            .sync_is_smaller_than(&villagers_count))
-                        .field())
-                        * MpcField::<Fr>::from(1_u32))
+        let base_state = no_werewolf * state(WinningJudgeOutcome::VillagerWin)
+            + (one - no_werewolf)
+                * (continues * continue_state
+                    + (one - continues) * state(WinningJudgeOutcome::WerewolfWin));
+
+        // 決着時に妖狐が生き残っていれば妖狐の勝利
+        let game_over = one
+            - (base_state - state(WinningJudgeOutcome::Continue))
+                .sync_is_zero_shared()
+                .field();
+        let has_fox = one - fox_count.sync_is_zero_shared().field();
+        base_state + game_over * has_fox * (state(WinningJudgeOutcome::FoxWin) - base_state)
+    }
+
+    // 勝利した陣営に属するプレイヤーの bitmask (bit i = プレイヤー i)。ゲーム続行中は 0。
+    pub fn calculate_winner_mask(&self) -> MpcField<Fr> {
+        let game_state = self.calculate_output();
+        let is_state = |outcome: WinningJudgeOutcome| {
+            (game_state - MpcField::<Fr>::from(outcome.code()))
+                .sync_is_zero_shared()
+                .field()
+        };
+
+        let werewolf_win = is_state(WinningJudgeOutcome::WerewolfWin);
+        let villager_win = is_state(WinningJudgeOutcome::VillagerWin)
+            + is_state(WinningJudgeOutcome::VillagerWinByDayLimit);
+        let fox_win = is_state(WinningJudgeOutcome::FoxWin);
+        let alive_mask = self.public_input.state_chain.alive_mask;
+
+        self.private_input
+            .iter()
+            .fold(MpcField::<Fr>::zero(), |acc, input| {
+                // 死亡者の行は空なので、村人陣営の判定だけ生存ビットで絞る
+                let alive =
+                    MpcField::<Fr>::from(alive_mask.checked_shr(input.id as u32).unwrap_or(0) & 1);
+                let is_winner = werewolf_win * input.am_werewolf_team
+                    + villager_win * (alive - input.am_werewolf_team - input.am_fox)
+                    + fox_win * input.am_fox;
+                let weight = MpcField::<Fr>::from(1u32.checked_shl(input.id as u32).unwrap_or(0));
+                acc + weight * is_winner
+            })
     }
 }
 
 impl DivinationCircuit<Fr> {
-    pub fn calculate_output(&self) -> Fr {
-        todo!()
+    pub fn calculate_output(&self) -> <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalCiphertext {
+        let player_num = self.public_input.player_num;
+        let sum_target = (0..player_num)
+            .map(|i| {
+                self.private_input
+                    .iter()
+                    .fold(Fr::zero(), |acc, input| acc + input.is_target[i])
+            })
+            .collect::<Vec<_>>();
+        let is_werewolf: Fr = self
+            .private_input
+            .iter()
+            .map(|input| input.is_werewolf * sum_target[input.id])
+            .sum();
+
+        let message = match is_werewolf.is_one() {
+            true => <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::prime_subgroup_generator(),
+            false => <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::default(),
+        };
+        <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme::encrypt(
+            &self.public_input.elgamal_param,
+            &self.public_input.pub_key,
+            &message,
+            &self.private_input[0].randomness,
+        )
+        .unwrap()
     }
 }
 
@@ -1149,37 +2675,34 @@ impl DivinationCircuit<MpcField<Fr>> {
 
 impl KeyPublicizeCircuit<Fr> {
     pub fn calculate_output(&self) -> (Fr, Fr) {
-        let pub_key_x = self
-            .private_input
-            .iter()
-            .map(|input| input.pub_key_or_dummy_x)
-            .fold(Fr::zero(), |acc, x| acc + x);
-
-        let pub_key_y = self
-            .private_input
+        self.private_input
             .iter()
-            .map(|input| input.pub_key_or_dummy_y)
-            .fold(Fr::zero(), |acc, y| acc + y);
-
-        (pub_key_x, pub_key_y)
+            .filter(|input| input.role_id == Fr::from(FORTUNE_TELLER_ROLE_ID))
+            .fold((Fr::zero(), Fr::zero()), |(x, y), input| {
+                (x + input.pub_key_or_dummy_x, y + input.pub_key_or_dummy_y)
+            })
     }
 }
 
 impl KeyPublicizeCircuit<MpcField<Fr>> {
     pub fn calculate_output(&self) -> (MpcField<Fr>, MpcField<Fr>) {
-        let pub_key_x = self
-            .private_input
+        self.private_input
             .iter()
-            .map(|input| input.pub_key_or_dummy_x)
-            .fold(MpcField::<Fr>::zero(), |acc, x| acc + x);
-
-        let pub_key_y = self
-            .private_input
-            .iter()
-            .map(|input| input.pub_key_or_dummy_y)
-            .fold(MpcField::<Fr>::zero(), |acc, y| acc + y);
-
-        (pub_key_x, pub_key_y)
+            .map(|input| {
+                // 役職IDは share のままなので、占い師フラグも share で求める
+                let is_fortune_teller = (input.role_id
+                    - MpcField::<Fr>::from(FORTUNE_TELLER_ROLE_ID))
+                .sync_is_zero_shared()
+                .field();
+                (
+                    input.pub_key_or_dummy_x * is_fortune_teller,
+                    input.pub_key_or_dummy_y * is_fortune_teller,
+                )
+            })
+            .fold(
+                (MpcField::<Fr>::zero(), MpcField::<Fr>::zero()),
+                |(x, y), (px, py)| (x + px, y + py),
+            )
     }
 }
 
@@ -1226,13 +2749,7 @@ impl RoleAssignmentCircuit<Fr> {
             current_rho *= rho.clone();
         }
 
-        let role_id_lookup = (0..matrix_size)
-            .map(|idx| match grouping_parameter.get_corresponding_role(idx) {
-                Role::Villager => Fr::from(0u32),
-                Role::FortuneTeller => Fr::from(1u32),
-                Role::Werewolf => Fr::from(2u32),
-            })
-            .collect::<Vec<_>>();
+        let role_id_lookup = role_id_lookup::<Fr>(grouping_parameter, matrix_size);
 
         let stair_vector = na::DVector::from(
             (0..matrix_size)
@@ -1260,7 +2777,7 @@ impl RoleAssignmentCircuit<Fr> {
                 }
             }
 
-            // role_val (公開インデックス) -> 役職ID(0/1/2)
+            // role_val (公開インデックス) -> 役職ID (role_id_lookup の対応)
             let mut role_id = Fr::zero();
             for (idx, mapped_role_id) in role_id_lookup.iter().enumerate() {
                 if (role_value - Fr::from(idx as u32)).is_zero() {
@@ -1274,6 +2791,22 @@ impl RoleAssignmentCircuit<Fr> {
         output_vec
     }
 
+    /// 各プレイヤーの役職IDへのコミットメント Commit(role_id, randomness)。
+    pub fn calculate_role_commitments(&self) -> Vec<<Fr as LocalOrMPC<Fr>>::PedersenCommitment> {
+        self.calculate_output()
+            .iter()
+            .zip(self.private_input.iter())
+            .map(|(role_id, input)| {
+                <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::commit(
+                    &self.public_input.pedersen_param,
+                    &role_id.into_repr().to_bytes_le(),
+                    &input.randomness,
+                )
+                .expect("Failed to commit to role id")
+            })
+            .collect()
+    }
+
     pub fn calculate_output_with_werewolf_mates_mask(
         &self,
     ) -> Vec<RoleAssignmentPlayerShares<Fr>> {
@@ -1286,7 +2819,17 @@ impl RoleAssignmentCircuit<Fr> {
         let werewolf_flags = role_shares
             .iter()
             .map(|role_share| {
-                if (*role_share - Fr::from(2u32)).is_zero() {
+                if (*role_share - Fr::from(WEREWOLF_ROLE_ID)).is_zero() {
+                    Fr::one()
+                } else {
+                    Fr::zero()
+                }
+            })
+            .collect::<Vec<_>>();
+        let possessed_flags = role_shares
+            .iter()
+            .map(|role_share| {
+                if (*role_share - Fr::from(POSSESSED_ROLE_ID)).is_zero() {
                     Fr::one()
                 } else {
                     Fr::zero()
@@ -1309,9 +2852,14 @@ impl RoleAssignmentCircuit<Fr> {
                 teammate_mask_share += weight * self_is_werewolf * werewolf_flags[j];
             }
 
+            // 人狼陣営 = 人狼 + 狂人
+            let role_flags_share =
+                werewolf_flags[i] + Fr::from(2u32) * (werewolf_flags[i] + possessed_flags[i]);
+
             outputs.push(RoleAssignmentPlayerShares {
                 role_share: role_shares[i],
                 werewolf_mates_mask_share: teammate_mask_share,
+                role_flags_share,
             });
         }
 
@@ -1362,13 +2910,7 @@ impl RoleAssignmentCircuit<MpcField<Fr>> {
             current_rho *= rho.clone();
         }
 
-        let role_id_lookup = (0..matrix_size)
-            .map(|idx| match grouping_parameter.get_corresponding_role(idx) {
-                Role::Villager => MpcField::<Fr>::from(0u32),
-                Role::FortuneTeller => MpcField::<Fr>::from(1u32),
-                Role::Werewolf => MpcField::<Fr>::from(2u32),
-            })
-            .collect::<Vec<_>>();
+        let role_id_lookup = role_id_lookup::<MpcField<Fr>>(grouping_parameter, matrix_size);
 
         let stair_vector = na::DVector::from(
             (0..matrix_size)
@@ -1397,7 +2939,7 @@ impl RoleAssignmentCircuit<MpcField<Fr>> {
                 role_value += (*candidate - role_value) * is_new_max;
             }
 
-            // role_val (公開インデックス) -> 役職ID(0/1/2) を share のまま変換
+            // role_val (公開インデックス) -> 役職ID (role_id_lookup の対応) を share のまま変換
             let mut role_id = MpcField::<Fr>::zero();
             for (idx, mapped_role_id) in role_id_lookup.iter().enumerate() {
                 let is_eq =
@@ -1410,6 +2952,42 @@ impl RoleAssignmentCircuit<MpcField<Fr>> {
         output_vec
     }
 
+    /// 各プレイヤーの役職IDへのコミットメント Commit(role_id, randomness)。
+    /// 役職IDは share のままなので、回路と同じ gadget を使い捨ての制約系で評価して値を取り出す。
+    /// 結果は share のままで、公開入力にするときに reveal する
+    pub fn calculate_role_commitments(
+        &self,
+    ) -> Vec<<MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenCommitment> {
+        let cs = ark_relations::r1cs::ConstraintSystem::<MpcField<Fr>>::new_ref();
+        let pedersen_param_var =
+            <MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenParamVar::new_constant(
+                ark_relations::ns!(cs, "pedersen_param"),
+                &self.public_input.pedersen_param,
+            )
+            .expect("Failed to allocate pedersen param");
+
+        self.calculate_output()
+            .into_iter()
+            .zip(self.private_input.iter())
+            .map(|(role_id, input)| {
+                let role_id_var =
+                    MpcFpVar::new_witness(ark_relations::ns!(cs, "role_id"), || Ok(role_id))?;
+                let randomness_var =
+                    <MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenRandomnessVar::new_witness(
+                        ark_relations::ns!(cs, "pedersen_randomness"),
+                        || Ok(&input.randomness),
+                    )?;
+                <MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenComSchemeVar::commit(
+                    &pedersen_param_var,
+                    &role_id_var.to_bytes()?,
+                    &randomness_var,
+                )?
+                .value()
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()
+            .expect("Failed to calculate role commitments")
+    }
+
     pub fn calculate_output_with_werewolf_mates_mask(
         &self,
     ) -> Vec<RoleAssignmentPlayerShares<MpcField<Fr>>> {
@@ -1422,7 +3000,15 @@ impl RoleAssignmentCircuit<MpcField<Fr>> {
         let werewolf_flags = role_shares
             .iter()
             .map(|role_share| {
-                (*role_share - MpcField::<Fr>::from(2u32))
+                (*role_share - MpcField::<Fr>::from(WEREWOLF_ROLE_ID))
+                    .sync_is_zero_shared()
+                    .field()
+            })
+            .collect::<Vec<_>>();
+        let possessed_flags = role_shares
+            .iter()
+            .map(|role_share| {
+                (*role_share - MpcField::<Fr>::from(POSSESSED_ROLE_ID))
                     .sync_is_zero_shared()
                     .field()
             })
@@ -1443,9 +3029,14 @@ impl RoleAssignmentCircuit<MpcField<Fr>> {
                 teammate_mask_share += weight * self_is_werewolf * werewolf_flags[j];
             }
 
+            // 人狼陣営 = 人狼 + 狂人
+            let role_flags_share = werewolf_flags[i]
+                + MpcField::<Fr>::from(2u32) * (werewolf_flags[i] + possessed_flags[i]);
+
             outputs.push(RoleAssignmentPlayerShares {
                 role_share: role_shares[i],
                 werewolf_mates_mask_share: teammate_mask_share,
+                role_flags_share,
             });
         }
 
@@ -1455,21 +3046,45 @@ impl RoleAssignmentCircuit<MpcField<Fr>> {
 
 impl ConstraintSynthesizer<Fr> for WinningJudgementCircuit<Fr> {
     fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> ark_relations::r1cs::Result<()> {
-        // TODO: check player commitment
-        // let player_num = self.player_randomness.len();
-        let alive_player_num = self.private_input.len();
-        // for i in 0..alive_player_num {
-        //     let pedersen_circuit = PedersenComCircuit {
-        //         param: Some(self.pedersen_param.clone()),
-        //         input: self.player_randomness[i],
-        //         open: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::default(),
-        //         commit: self.player_commitment[i],
-        //     };
-        //     pedersen_circuit.generate_constraints(cs.clone())?;
-        // }
+        let alive_player_num = self.public_input.state_chain.alive_mask.count_ones();
+        let player_num = self.public_input.player_num;
+        let row_ids = self
+            .private_input
+            .iter()
+            .map(|input| input.id)
+            .collect::<Vec<_>>();
+        check_alive_mask_rows(
+            &row_ids,
+            player_num,
+            self.public_input.state_chain.alive_mask,
+        )?;
+        if self.public_input.role_commitment.len() != player_num {
+            return Err(SynthesisError::Unsatisfiable);
+        }
 
         // initialize
-        let num_alive_var = FpVar::new_input(cs.clone(), || Ok(Fr::from(alive_player_num as u32)))?;
+        let num_alive_var = FpVar::new_input(cs.clone(), || Ok(Fr::from(alive_player_num)))?;
+
+        // 生存者数は生存者 bitmask の立っているビット数と一致する
+        let (alive_mask_var, alive_var) = alloc_alive_mask(
+            cs.clone(),
+            self.public_input.state_chain.alive_mask,
+            player_num,
+        )?;
+        alive_var
+            .iter()
+            .fold(<FpVar<Fr> as Zero>::zero(), |acc, x| acc + x)
+            .enforce_equal(&num_alive_var)?;
+
+        // 勝利条件 (公開パラメータ)
+        let win_condition = self.public_input.win_condition;
+        let majority_var = FpVar::new_input(cs.clone(), || {
+            Ok(Fr::from(win_condition.werewolf_win_on_majority as u32))
+        })?;
+        let day_limit_var = FpVar::new_input(cs.clone(), || Ok(Fr::from(win_condition.day_limit)))?;
+        let day_count_var = FpVar::new_input(cs.clone(), || {
+            Ok(Fr::from(self.public_input.state_chain.day_count))
+        })?;
 
         let am_werewolf_var = self
             .private_input
@@ -1477,7 +3092,20 @@ impl ConstraintSynthesizer<Fr> for WinningJudgementCircuit<Fr> {
             .map(|input| FpVar::new_witness(cs.clone(), || Ok(input.am_werewolf)))
             .collect::<Result<Vec<_>, _>>()?;
 
+        let am_werewolf_team_var = self
+            .private_input
+            .iter()
+            .map(|input| FpVar::new_witness(cs.clone(), || Ok(input.am_werewolf_team)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let am_fox_var = self
+            .private_input
+            .iter()
+            .map(|input| FpVar::new_witness(cs.clone(), || Ok(input.am_fox)))
+            .collect::<Result<Vec<_>, _>>()?;
+
         let game_state_var = FpVar::new_input(cs.clone(), || Ok(self.calculate_output()))?;
+        let winner_mask_var = FpVar::new_input(cs.clone(), || Ok(self.calculate_winner_mask()))?;
 
         // calculate
         let num_werewolf_var =
@@ -1488,32 +3116,169 @@ impl ConstraintSynthesizer<Fr> for WinningJudgementCircuit<Fr> {
                     acc
                 });
 
+        let num_fox_var = am_fox_var
+            .iter()
+            .fold(<FpVar<Fr> as Zero>::zero(), |mut acc, x| {
+                acc += x;
+                acc
+            });
+
         let num_citizen_var = num_alive_var - &num_werewolf_var;
 
-        let calced_game_state_var = FpVar::conditionally_select(
-            &FieldVar::is_zero(&num_werewolf_var)?,
-            &FpVar::constant(Fr::from(2)), // villager win
-            &FpVar::conditionally_select(
-                &num_werewolf_var.is_cmp(&num_citizen_var, std::cmp::Ordering::Less, false)?,
-                &FpVar::constant(Fr::from(3)), // game continues
-                &FpVar::constant(Fr::from(1)), // werewolf win
-            )?,
-        )?;
+        // majority は 0 or 1
+        let is_majority_zero = ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_zero(&majority_var)?;
+        let is_majority_one = ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_one(&majority_var)?;
+        is_majority_zero
+            .or(&is_majority_one)?
+            .enforce_equal(&Boolean::TRUE)?;
+
+        let state = |outcome: WinningJudgeOutcome| FpVar::Constant(Fr::from(outcome.code()));
+
+        // 人数で決着がつかない場合、日数上限 (0 は無制限) に達していれば村人陣営の勝利
+        let continue_state_var = FpVar::conditionally_select(
+            &FieldVar::is_zero(&day_limit_var)?,
+            &state(WinningJudgeOutcome::Continue),
+            &FpVar::conditionally_select(
+                &day_count_var.is_cmp(&day_limit_var, std::cmp::Ordering::Greater, true)?,
+                &state(WinningJudgeOutcome::VillagerWinByDayLimit),
+                &state(WinningJudgeOutcome::Continue),
+            )?,
+        )?;
+
+        // 人狼数 < 人間数 + majority の間はゲーム続行
+        let base_state_var = FpVar::conditionally_select(
+            &FieldVar::is_zero(&num_werewolf_var)?,
+            &state(WinningJudgeOutcome::VillagerWin),
+            &FpVar::conditionally_select(
+                &num_werewolf_var.is_cmp(
+                    &(&num_citizen_var + &majority_var),
+                    std::cmp::Ordering::Less,
+                    false,
+                )?,
+                &continue_state_var,
+                &state(WinningJudgeOutcome::WerewolfWin),
+            )?,
+        )?;
+
+        // 決着時に妖狐が生き残っていれば妖狐の勝利
+        let calced_game_state_var = FpVar::conditionally_select(
+            &FieldVar::is_zero(&num_fox_var)?,
+            &base_state_var,
+            &FpVar::conditionally_select(
+                &FieldVar::is_zero(&(&base_state_var - state(WinningJudgeOutcome::Continue)))?,
+                &base_state_var,
+                &state(WinningJudgeOutcome::FoxWin),
+            )?,
+        )?;
+
+        // enforce equal
+        game_state_var.enforce_equal(&calced_game_state_var)?;
+
+        // 陣営は 0 or 1 で、人狼は必ず人狼陣営 (狂人は am_werewolf = 0, am_werewolf_team = 1)
+        // 妖狐は 0 or 1 で、人狼陣営には属さない。死亡者の行はすべて 0
+        let one = <FpVar<Fr> as One>::one();
+        let zero = <FpVar<Fr> as Zero>::zero();
+        for (((am_werewolf, am_werewolf_team), am_fox), alive) in am_werewolf_var
+            .iter()
+            .zip(am_werewolf_team_var.iter())
+            .zip(am_fox_var.iter())
+            .zip(alive_var.iter())
+        {
+            let dead = &one - alive;
+            (am_werewolf * &dead).enforce_equal(&zero)?;
+            (am_werewolf_team * &dead).enforce_equal(&zero)?;
+            (am_fox * &dead).enforce_equal(&zero)?;
+
+            let is_zero = ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_zero(am_werewolf)?;
+            let is_one = ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_one(am_werewolf)?;
+            is_zero.or(&is_one)?.enforce_equal(&Boolean::TRUE)?;
+
+            let is_zero = ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_zero(am_werewolf_team)?;
+            let is_one = ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_one(am_werewolf_team)?;
+            is_zero.or(&is_one)?.enforce_equal(&Boolean::TRUE)?;
+            (am_werewolf * (&one - am_werewolf_team)).enforce_equal(&zero)?;
+
+            let is_zero = ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_zero(am_fox)?;
+            let is_one = ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_one(am_fox)?;
+            is_zero.or(&is_one)?.enforce_equal(&Boolean::TRUE)?;
+            (am_fox * am_werewolf_team).enforce_equal(&zero)?;
+        }
+
+        // 勝利した陣営のプレイヤーを bitmask にまとめる
+        let is_state = |outcome: WinningJudgeOutcome| {
+            ark_r1cs_std::prelude::FieldVar::<Fr, Fr>::is_zero(
+                &(&calced_game_state_var - state(outcome)),
+            )
+            .map(FpVar::from)
+        };
+        let werewolf_win = is_state(WinningJudgeOutcome::WerewolfWin)?;
+        let villager_win = is_state(WinningJudgeOutcome::VillagerWin)?
+            + is_state(WinningJudgeOutcome::VillagerWinByDayLimit)?;
+        let fox_win = is_state(WinningJudgeOutcome::FoxWin)?;
+        let mut calced_winner_mask_var = zero.clone();
+        for (((input, am_werewolf_team), am_fox), alive) in self
+            .private_input
+            .iter()
+            .zip(am_werewolf_team_var.iter())
+            .zip(am_fox_var.iter())
+            .zip(alive_var.iter())
+        {
+            let is_winner = &werewolf_win * am_werewolf_team
+                + &villager_win * (alive - am_werewolf_team - am_fox)
+                + &fox_win * am_fox;
+            let weight = FpVar::Constant(Fr::from(1u32.checked_shl(input.id as u32).unwrap_or(0)));
+            calced_winner_mask_var += weight * is_winner;
+        }
+        winner_mask_var.enforce_equal(&calced_winner_mask_var)?;
 
-        // // TODO: check commitment
-        // for am_werewolf_with_commit in self.am_werewolf.iter() {
-        //     let am_werewolf_com_circuit = PedersenComCircuit {
-        //         param: Some(self.pedersen_param.clone()),
-        //         input: am_werewolf_with_commit.input,
-        //         open: am_werewolf_with_commit.randomness.clone(),
-        //         commit: am_werewolf_with_commit.commitment.clone(),
-        //     };
+        // role_commitment は公開入力として確保し、その変数から state digest を計算する
+        let role_commitment_vars =
+            alloc_commitment_inputs::<Fr>(cs.clone(), &self.public_input.role_commitment)?;
 
-        //     am_werewolf_com_circuit.generate_constraints(cs.clone())?;
-        // }
+        // 生存者の陣営を role_commitment に結びつける。死亡者は入力を送らないので開示しない
+        // 妖狐にはまだ役職IDがないので、am_fox は 0 or 1 の確認だけになる
+        let pedersen_param_var = <Fr as LocalOrMPC<Fr>>::PedersenParamVar::new_constant(
+            ark_relations::ns!(cs, "pedersen_param"),
+            &self.public_input.pedersen_param,
+        )?;
+        let werewolf_role_id = FpVar::Constant(Fr::from(WEREWOLF_ROLE_ID));
+        let possessed_role_id = FpVar::Constant(Fr::from(POSSESSED_ROLE_ID));
+        for ((((input, am_werewolf), am_werewolf_team), alive), role_commitment_var) in self
+            .private_input
+            .iter()
+            .zip(am_werewolf_var.iter())
+            .zip(am_werewolf_team_var.iter())
+            .zip(alive_var.iter())
+            .zip(role_commitment_vars.iter())
+        {
+            let role_id_var =
+                FpVar::new_witness(ark_relations::ns!(cs, "role_id"), || Ok(input.role_id))?;
+            enforce_alive_pedersen_opening(
+                cs.clone(),
+                &pedersen_param_var,
+                &role_id_var.to_bytes()?,
+                &input.randomness,
+                role_commitment_var,
+                alive,
+            )?;
+            let is_werewolf_var = FpVar::from(role_id_var.is_eq(&werewolf_role_id)?);
+            let is_possessed_var = FpVar::from(role_id_var.is_eq(&possessed_role_id)?);
+            am_werewolf.enforce_equal(&(&is_werewolf_var * alive))?;
+            am_werewolf_team.enforce_equal(&((is_werewolf_var + is_possessed_var) * alive))?;
+        }
 
-        // enforce equal
-        game_state_var.enforce_equal(&calced_game_state_var)?;
+        enforce_state_chain(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::WinningJudgement,
+            &role_commitment_vars
+                .iter()
+                .map(commitment_var_coordinates)
+                .collect::<Vec<_>>(),
+            Some(alive_mask_var),
+            Some(day_count_var),
+            &[game_state_var, winner_mask_var],
+        )?;
 
         println!(
             "[WinningJudgementCircuit(Local)] instance vars: {}",
@@ -1536,22 +3301,51 @@ impl ConstraintSynthesizer<MpcField<Fr>> for WinningJudgementCircuit<MpcField<Fr
         self,
         cs: ConstraintSystemRef<MpcField<Fr>>,
     ) -> ark_relations::r1cs::Result<()> {
-        // TODO: check player commitment
-        // let player_num = self.player_randomness.len();
-        let alive_player_num = self.private_input.len();
-        // for i in 0..alive_player_num {
-        //     let pedersen_circuit = PedersenComCircuit {
-        //         param: Some(self.pedersen_param.clone()),
-        //         input: self.player_randomness[i],
-        //         open: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::default(),
-        //         commit: self.player_commitment[i],
-        //     };
-        //     pedersen_circuit.generate_constraints(cs.clone())?;
-        // }
+        let alive_player_num = self.public_input.state_chain.alive_mask.count_ones();
+        let player_num = self.public_input.player_num;
+        let row_ids = self
+            .private_input
+            .iter()
+            .map(|input| input.id)
+            .collect::<Vec<_>>();
+        check_alive_mask_rows(
+            &row_ids,
+            player_num,
+            self.public_input.state_chain.alive_mask,
+        )?;
+        if self.public_input.role_commitment.len() != player_num {
+            return Err(SynthesisError::Unsatisfiable);
+        }
 
         // initialize
-        let num_alive_var = MpcFpVar::new_input(cs.clone(), || {
-            Ok(MpcField::<Fr>::from(alive_player_num as u32))
+        let num_alive_var =
+            MpcFpVar::new_input(cs.clone(), || Ok(MpcField::<Fr>::from(alive_player_num)))?;
+
+        // 生存者数は生存者 bitmask の立っているビット数と一致する
+        let (alive_mask_var, alive_var) = alloc_alive_mask_mpc(
+            cs.clone(),
+            self.public_input.state_chain.alive_mask,
+            player_num,
+        )?;
+        alive_var
+            .iter()
+            .fold(<MpcFpVar<MpcField<Fr>> as Zero>::zero(), |acc, x| acc + x)
+            .enforce_equal(&num_alive_var)?;
+
+        // 勝利条件 (公開パラメータ)
+        let win_condition = self.public_input.win_condition;
+        let majority_var = MpcFpVar::new_input(cs.clone(), || {
+            Ok(MpcField::<Fr>::from(
+                win_condition.werewolf_win_on_majority as u32,
+            ))
+        })?;
+        let day_limit_var = MpcFpVar::new_input(cs.clone(), || {
+            Ok(MpcField::<Fr>::from(win_condition.day_limit))
+        })?;
+        let day_count_var = MpcFpVar::new_input(cs.clone(), || {
+            Ok(MpcField::<Fr>::from(
+                self.public_input.state_chain.day_count,
+            ))
         })?;
 
         let am_werewolf_var = self
@@ -1560,7 +3354,20 @@ impl ConstraintSynthesizer<MpcField<Fr>> for WinningJudgementCircuit<MpcField<Fr
             .map(|input| MpcFpVar::new_witness(cs.clone(), || Ok(input.am_werewolf)))
             .collect::<Result<Vec<_>, _>>()?;
 
+        let am_werewolf_team_var = self
+            .private_input
+            .iter()
+            .map(|input| MpcFpVar::new_witness(cs.clone(), || Ok(input.am_werewolf_team)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let am_fox_var = self
+            .private_input
+            .iter()
+            .map(|input| MpcFpVar::new_witness(cs.clone(), || Ok(input.am_fox)))
+            .collect::<Result<Vec<_>, _>>()?;
+
         let game_state_var = MpcFpVar::new_input(cs.clone(), || Ok(self.calculate_output()))?;
+        let winner_mask_var = MpcFpVar::new_input(cs.clone(), || Ok(self.calculate_winner_mask()))?;
         // let game_state_var =
         //     MpcFpVar::new_input(cs.clone(), || Ok(MpcField::<Fr>::from(0_u32)))?;
 
@@ -1573,33 +3380,189 @@ impl ConstraintSynthesizer<MpcField<Fr>> for WinningJudgementCircuit<MpcField<Fr
                     acc
                 });
 
+        let num_fox_var =
+            am_fox_var
+                .iter()
+                .fold(<MpcFpVar<MpcField<Fr>> as Zero>::zero(), |mut acc, x| {
+                    acc += x;
+                    acc
+                });
+
         let num_citizen_var = num_alive_var - &num_werewolf_var;
 
-        let calced_game_state_var = MpcFpVar::conditionally_select(
-            &MpcFieldVar::is_zero(&num_werewolf_var)?,
-            &MpcFpVar::constant(MpcField::<Fr>::from(2_u32)), // villager win
+        let one = <MpcFpVar<MpcField<Fr>> as One>::one();
+        let zero = <MpcFpVar<MpcField<Fr>> as Zero>::zero();
+
+        // majority は 0 or 1
+        majority_var
+            .is_zero()?
+            .or(&(&majority_var - &one).is_zero()?)?
+            .enforce_equal(&MpcBoolean::TRUE)?;
+
+        let state =
+            |outcome: WinningJudgeOutcome| MpcFpVar::constant(MpcField::<Fr>::from(outcome.code()));
+
+        // 人数で決着がつかない場合、日数上限 (0 は無制限) に達していれば村人陣営の勝利
+        let continue_state_var = MpcFpVar::conditionally_select(
+            &day_limit_var.is_zero()?,
+            &state(WinningJudgeOutcome::Continue),
             &MpcFpVar::conditionally_select(
-                &num_werewolf_var.is_cmp(&num_citizen_var, std::cmp::Ordering::Less, false)?,
-                &MpcFpVar::constant(MpcField::<Fr>::from(3_u32)), // game continues
-                &MpcFpVar::constant(MpcField::<Fr>::from(1_u32)), // werewolf win
+                &day_count_var.is_cmp(&day_limit_var, std::cmp::Ordering::Greater, true)?,
+                &state(WinningJudgeOutcome::VillagerWinByDayLimit),
+                &state(WinningJudgeOutcome::Continue),
             )?,
         )?;
 
-        // // TODO: check commitment
-        // for am_werewolf_with_commit in self.am_werewolf.iter() {
-        //     let am_werewolf_com_circuit = PedersenComCircuit {
-        //         param: Some(self.pedersen_param.clone()),
-        //         input: am_werewolf_with_commit.input,
-        //         open: am_werewolf_with_commit.randomness.clone(),
-        //         commit: am_werewolf_with_commit.commitment.clone(),
-        //     };
+        // 人狼数 < 人間数 + majority の間はゲーム続行
+        let base_state_var = MpcFpVar::conditionally_select(
+            &MpcFieldVar::is_zero(&num_werewolf_var)?,
+            &state(WinningJudgeOutcome::VillagerWin),
+            &MpcFpVar::conditionally_select(
+                &num_werewolf_var.is_cmp(
+                    &(&num_citizen_var + &majority_var),
+                    std::cmp::Ordering::Less,
+                    false,
+                )?,
+                &continue_state_var,
+                &state(WinningJudgeOutcome::WerewolfWin),
+            )?,
+        )?;
 
-        //     am_werewolf_com_circuit.generate_constraints(cs.clone())?;
-        // }
+        // 決着時に妖狐が生き残っていれば妖狐の勝利
+        let calced_game_state_var = MpcFpVar::conditionally_select(
+            &num_fox_var.is_zero()?,
+            &base_state_var,
+            &MpcFpVar::conditionally_select(
+                &(&base_state_var - state(WinningJudgeOutcome::Continue)).is_zero()?,
+                &base_state_var,
+                &state(WinningJudgeOutcome::FoxWin),
+            )?,
+        )?;
 
         // enforce equal
         game_state_var.enforce_equal(&calced_game_state_var)?;
 
+        // 陣営は 0 or 1 で、人狼は必ず人狼陣営 (狂人は am_werewolf = 0, am_werewolf_team = 1)
+        // 妖狐は 0 or 1 で、人狼陣営には属さない。死亡者の行はすべて 0
+        for (((am_werewolf, am_werewolf_team), am_fox), alive) in am_werewolf_var
+            .iter()
+            .zip(am_werewolf_team_var.iter())
+            .zip(am_fox_var.iter())
+            .zip(alive_var.iter())
+        {
+            let dead = &one - alive;
+            (am_werewolf * &dead).enforce_equal(&zero)?;
+            (am_werewolf_team * &dead).enforce_equal(&zero)?;
+            (am_fox * &dead).enforce_equal(&zero)?;
+
+            am_werewolf
+                .is_zero()?
+                .or(&(am_werewolf - &one).is_zero()?)?
+                .enforce_equal(&MpcBoolean::TRUE)?;
+            am_werewolf_team
+                .is_zero()?
+                .or(&(am_werewolf_team - &one).is_zero()?)?
+                .enforce_equal(&MpcBoolean::TRUE)?;
+            (am_werewolf * (&one - am_werewolf_team)).enforce_equal(&zero)?;
+
+            am_fox
+                .is_zero()?
+                .or(&(am_fox - &one).is_zero()?)?
+                .enforce_equal(&MpcBoolean::TRUE)?;
+            (am_fox * am_werewolf_team).enforce_equal(&zero)?;
+        }
+
+        // 勝利した陣営のプレイヤーを bitmask にまとめる
+        let is_state = |outcome: WinningJudgeOutcome| {
+            MpcFpVar::conditionally_select(
+                &(&calced_game_state_var - state(outcome)).is_zero()?,
+                &one,
+                &zero,
+            )
+        };
+        let werewolf_win = is_state(WinningJudgeOutcome::WerewolfWin)?;
+        let villager_win = is_state(WinningJudgeOutcome::VillagerWin)?
+            + is_state(WinningJudgeOutcome::VillagerWinByDayLimit)?;
+        let fox_win = is_state(WinningJudgeOutcome::FoxWin)?;
+        let mut calced_winner_mask_var = zero.clone();
+        for (((input, am_werewolf_team), am_fox), alive) in self
+            .private_input
+            .iter()
+            .zip(am_werewolf_team_var.iter())
+            .zip(am_fox_var.iter())
+            .zip(alive_var.iter())
+        {
+            let is_winner = &werewolf_win * am_werewolf_team
+                + &villager_win * (alive - am_werewolf_team - am_fox)
+                + &fox_win * am_fox;
+            let weight = MpcFpVar::constant(MpcField::<Fr>::from(
+                1u32.checked_shl(input.id as u32).unwrap_or(0),
+            ));
+            calced_winner_mask_var += weight * is_winner;
+        }
+        winner_mask_var.enforce_equal(&calced_winner_mask_var)?;
+
+        let role_commitment_vars = alloc_commitment_inputs::<MpcField<Fr>>(
+            cs.clone(),
+            &self.public_input.role_commitment,
+        )?;
+
+        // 生存者の陣営を role_commitment に結びつける。死亡者は入力を送らないので開示しない
+        // 妖狐にはまだ役職IDがないので、am_fox は 0 or 1 の確認だけになる
+        let pedersen_param_var =
+            <MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenParamVar::new_constant(
+                ark_relations::ns!(cs, "pedersen_param"),
+                &self.public_input.pedersen_param,
+            )?;
+        let werewolf_role_id =
+            MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(WEREWOLF_ROLE_ID))?;
+        let possessed_role_id =
+            MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(POSSESSED_ROLE_ID))?;
+        for ((((input, am_werewolf), am_werewolf_team), alive), role_commitment_var) in self
+            .private_input
+            .iter()
+            .zip(am_werewolf_var.iter())
+            .zip(am_werewolf_team_var.iter())
+            .zip(alive_var.iter())
+            .zip(role_commitment_vars.iter())
+        {
+            let role_id_var =
+                MpcFpVar::new_witness(ark_relations::ns!(cs, "role_id"), || Ok(input.role_id))?;
+            enforce_alive_pedersen_opening_mpc(
+                cs.clone(),
+                &pedersen_param_var,
+                &role_id_var.to_bytes()?,
+                &input.randomness,
+                role_commitment_var,
+                alive,
+            )?;
+            let is_werewolf_var = MpcFpVar::conditionally_select(
+                &(&role_id_var - &werewolf_role_id).is_zero()?,
+                &one,
+                &zero,
+            )?;
+            let is_possessed_var = MpcFpVar::conditionally_select(
+                &(&role_id_var - &possessed_role_id).is_zero()?,
+                &one,
+                &zero,
+            )?;
+            am_werewolf.enforce_equal(&(&is_werewolf_var * alive))?;
+            am_werewolf_team.enforce_equal(&((is_werewolf_var + is_possessed_var) * alive))?;
+        }
+
+        enforce_state_chain_mpc(
+            cs.clone(),
+            &self.public_input.state_chain,
+            StateChainTag::WinningJudgement,
+            &role_commitment_vars
+                .iter()
+                .map(commitment_var_coordinates_mpc)
+                .collect::<Vec<_>>(),
+            Some(alive_mask_var),
+            Some(day_count_var),
+            &[game_state_var, winner_mask_var],
+        )?;
+
         println!(
             "[WinningJudgementCircuit(MPC)] instance vars: {}",
             cs.num_instance_variables()
@@ -1617,6 +3580,674 @@ impl ConstraintSynthesizer<MpcField<Fr>> for WinningJudgementCircuit<MpcField<Fr
     }
 }
 
+/// Twisted Edwards coefficients (a, d) of Baby Jubjub, the curve of the ElGamal keys.
+fn baby_jubjub_coefficients<F: PrimeField>() -> (F, F) {
+    let convert = |coeff: Fr| F::from_le_bytes_mod_order(&coeff.into_repr().to_bytes_le());
+    (
+        convert(<ark_ed_on_bn254::EdwardsParameters as TEModelParameters>::COEFF_A),
+        convert(<ark_ed_on_bn254::EdwardsParameters as TEModelParameters>::COEFF_D),
+    )
+}
+
+/// Enforces that `commitment` opens to `value_bytes` with `randomness`.
+/// Off-chain commitments are made over `value.into_repr().to_bytes_le()`.
+fn enforce_pedersen_opening<F: PrimeField + LocalOrMPC<F>>(
+    cs: ConstraintSystemRef<F>,
+    param_var: &F::PedersenParamVar,
+    value_bytes: &[UInt8<F>],
+    randomness: &F::PedersenRandomness,
+    commitment_var: &F::PedersenCommitmentVar,
+) -> Result<(), SynthesisError> {
+    let randomness_var = F::PedersenRandomnessVar::new_witness(
+        ark_relations::ns!(cs, "pedersen_randomness"),
+        || Ok(randomness),
+    )?;
+
+    let calced_commitment_var =
+        F::PedersenComSchemeVar::commit(param_var, value_bytes, &randomness_var)?;
+    calced_commitment_var.enforce_equal(commitment_var)
+}
+
+/// [`enforce_pedersen_opening`] with the commitment allocated as `mode`. Returns the
+/// commitment so that a hashed instance can include its coordinates.
+fn alloc_pedersen_opening<F: PrimeField + LocalOrMPC<F>>(
+    cs: ConstraintSystemRef<F>,
+    param_var: &F::PedersenParamVar,
+    value_bytes: &[UInt8<F>],
+    randomness: &F::PedersenRandomness,
+    commitment: &F::PedersenCommitment,
+    mode: AllocationMode,
+) -> Result<F::PedersenCommitmentVar, SynthesisError> {
+    let commitment_var = F::PedersenCommitmentVar::new_variable(
+        ark_relations::ns!(cs, "pedersen_commitment"),
+        || Ok(commitment),
+        mode,
+    )?;
+    enforce_pedersen_opening(cs, param_var, value_bytes, randomness, &commitment_var)?;
+    Ok(commitment_var)
+}
+
+/// [`enforce_pedersen_opening`] that only binds when `alive` is 1. Dead players submit no
+/// input, so their padded rows cannot open the commitment.
+fn enforce_alive_pedersen_opening(
+    cs: ConstraintSystemRef<Fr>,
+    param_var: &<Fr as LocalOrMPC<Fr>>::PedersenParamVar,
+    value_bytes: &[UInt8<Fr>],
+    randomness: &<Fr as LocalOrMPC<Fr>>::PedersenRandomness,
+    commitment_var: &<Fr as LocalOrMPC<Fr>>::PedersenCommitmentVar,
+    alive: &FpVar<Fr>,
+) -> Result<(), SynthesisError> {
+    let randomness_var = <Fr as LocalOrMPC<Fr>>::PedersenRandomnessVar::new_witness(
+        ark_relations::ns!(cs, "pedersen_randomness"),
+        || Ok(randomness),
+    )?;
+
+    let calced_commitment_var = <Fr as LocalOrMPC<Fr>>::PedersenComSchemeVar::commit(
+        param_var,
+        value_bytes,
+        &randomness_var,
+    )?;
+    let zero = <FpVar<Fr> as Zero>::zero();
+    for (calced, expected) in commitment_var_coordinates(&calced_commitment_var)
+        .iter()
+        .zip(commitment_var_coordinates(commitment_var).iter())
+    {
+        ((calced - expected) * alive).enforce_equal(&zero)?;
+    }
+    Ok(())
+}
+
+fn enforce_alive_pedersen_opening_mpc(
+    cs: ConstraintSystemRef<MpcField<Fr>>,
+    param_var: &<MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenParamVar,
+    value_bytes: &[UInt8<MpcField<Fr>>],
+    randomness: &<MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenRandomness,
+    commitment_var: &<MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenCommitmentVar,
+    alive: &MpcFpVar<MpcField<Fr>>,
+) -> Result<(), SynthesisError> {
+    let randomness_var =
+        <MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenRandomnessVar::new_witness(
+            ark_relations::ns!(cs, "pedersen_randomness"),
+            || Ok(randomness),
+        )?;
+
+    let calced_commitment_var =
+        <MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenComSchemeVar::commit(
+            param_var,
+            value_bytes,
+            &randomness_var,
+        )?;
+    let zero = <MpcFpVar<MpcField<Fr>> as Zero>::zero();
+    for (calced, expected) in commitment_var_coordinates_mpc(&calced_commitment_var)
+        .iter()
+        .zip(commitment_var_coordinates_mpc(commitment_var).iter())
+    {
+        ((calced - expected) * alive).enforce_equal(&zero)?;
+    }
+    Ok(())
+}
+
+/// Allocates every commitment as a public input, in order. Openings and the state digest
+/// use these variables, so both are bound to what the verifier checks.
+fn alloc_commitment_inputs<F: PrimeField + LocalOrMPC<F>>(
+    cs: ConstraintSystemRef<F>,
+    commitments: &[F::PedersenCommitment],
+) -> Result<Vec<F::PedersenCommitmentVar>, SynthesisError> {
+    commitments
+        .iter()
+        .map(|commitment| {
+            F::PedersenCommitmentVar::new_input(
+                ark_relations::ns!(cs, "pedersen_commitment"),
+                || Ok(commitment),
+            )
+        })
+        .collect()
+}
+
+fn commitment_var_coordinates(
+    commitment_var: &<Fr as LocalOrMPC<Fr>>::PedersenCommitmentVar,
+) -> [FpVar<Fr>; 2] {
+    [commitment_var.x.clone(), commitment_var.y.clone()]
+}
+
+fn commitment_var_coordinates_mpc(
+    commitment_var: &<MpcField<Fr> as LocalOrMPC<MpcField<Fr>>>::PedersenCommitmentVar,
+) -> [MpcFpVar<MpcField<Fr>>; 2] {
+    [commitment_var.x.clone(), commitment_var.y.clone()]
+}
+
+/// `c1.x, c1.y, c2.x, c2.y`, the order in which the ciphertext is exposed as public input.
+fn ciphertext_var_coordinates(
+    ciphertext_var: &<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalCiphertextVar,
+) -> [FpVar<Fr>; 4] {
+    [
+        ciphertext_var.c1.x.clone(),
+        ciphertext_var.c1.y.clone(),
+        ciphertext_var.c2.x.clone(),
+        ciphertext_var.c2.y.clone(),
+    ]
+}
+
+fn ciphertext_var_coordinates_mpc(
+    ciphertext_var: &<MpcField<Fr> as ElGamalLocalOrMPC<MpcField<Fr>>>::ElGamalCiphertextVar,
+) -> [MpcFpVar<MpcField<Fr>>; 4] {
+    [
+        ciphertext_var.c1.x.clone(),
+        ciphertext_var.c1.y.clone(),
+        ciphertext_var.c2.x.clone(),
+        ciphertext_var.c2.y.clone(),
+    ]
+}
+
+/// Requires one row per player in id order (see [`check_alive_mask_rows`]), one role
+/// commitment per player, and target vectors of length `player_num`.
+fn check_attack_inputs<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>>(
+    private_input: &[AttackPrivateInput<F>],
+    public_input: &AttackPublicInput<F>,
+) -> Result<(), SynthesisError> {
+    let player_num = public_input.player_num;
+    let row_ids = private_input
+        .iter()
+        .map(|input| input.id)
+        .collect::<Vec<_>>();
+    check_alive_mask_rows(&row_ids, player_num, public_input.state_chain.alive_mask)?;
+    if public_input.role_commitment.len() != player_num
+        || private_input
+            .iter()
+            .any(|input| input.is_target_id.len() != player_num)
+    {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    Ok(())
+}
+
+/// Rejects submitter ids that are out of range or duplicated, and attack or guard
+/// target vectors whose length differs from `player_num`.
+fn check_night_resolution_inputs<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>>(
+    private_input: &[NightResolutionPrivateInput<F>],
+    public_input: &NightResolutionPublicInput<F>,
+) -> Result<(), SynthesisError> {
+    let player_num = public_input.player_num;
+    if private_input.is_empty() || public_input.role_commitment.len() != player_num {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+
+    let mut seen_ids = HashSet::new();
+    for input in private_input {
+        if input.id >= player_num
+            || !seen_ids.insert(input.id)
+            || input.is_attack_target_id.len() != player_num
+            || input.is_guard_target_id.len() != player_num
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+    }
+    Ok(())
+}
+
+/// Rejects submitter ids that are out of range or duplicated, and an executed id
+/// that is not among the submitters.
+fn check_medium_inputs<F: PrimeField + LocalOrMPC<F> + ElGamalLocalOrMPC<F>>(
+    private_input: &[MediumPrivateInput<F>],
+    public_input: &MediumPublicInput<F>,
+) -> Result<(), SynthesisError> {
+    let player_num = public_input.player_num;
+    if private_input.is_empty()
+        || public_input.executed_id >= player_num
+        || public_input.role_commitment.len() != player_num
+    {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+
+    let mut seen_ids = HashSet::new();
+    for input in private_input {
+        if input.id >= player_num || !seen_ids.insert(input.id) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+    }
+
+    // 処刑者の入力が無いと役職IDを開示できない
+    if !seen_ids.contains(&public_input.executed_id) {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    Ok(())
+}
+
+/// Requires one row per player in id order (dead players contribute zero rows) and an
+/// alive mask that only covers those players.
+fn check_alive_mask_rows(
+    row_ids: &[usize],
+    player_num: usize,
+    alive_mask: u32,
+) -> Result<(), SynthesisError> {
+    let mask_in_range = player_num <= u32::BITS as usize
+        && alive_mask.checked_shr(player_num as u32).unwrap_or(0) == 0;
+    if !mask_in_range
+        || row_ids.len() != player_num
+        || row_ids.iter().enumerate().any(|(row, id)| *id != row)
+    {
+        return Err(SynthesisError::Unsatisfiable);
+    }
+    Ok(())
+}
+
+/// Allocates the public alive mask and decomposes it into one 0/1 variable per player.
+/// Returns the packed mask together with the bits.
+fn alloc_alive_mask<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    alive_mask: u32,
+    player_num: usize,
+) -> Result<(FpVar<F>, Vec<FpVar<F>>), SynthesisError> {
+    let alive_mask_var = FpVar::new_input(cs.clone(), || Ok(F::from(alive_mask)))?;
+
+    let one = <FpVar<F> as One>::one();
+    let zero = <FpVar<F> as Zero>::zero();
+    let mut packed = zero.clone();
+    let mut alive_var = Vec::with_capacity(player_num);
+    for i in 0..player_num {
+        let bit = FpVar::new_witness(cs.clone(), || Ok(F::from((alive_mask >> i) & 1)))?;
+        (&bit * (&one - &bit)).enforce_equal(&zero)?;
+        packed += &bit * FpVar::Constant(F::from(1u32 << i));
+        alive_var.push(bit);
+    }
+    alive_mask_var.enforce_equal(&packed)?;
+
+    Ok((alive_mask_var, alive_var))
+}
+
+fn alloc_alive_mask_mpc(
+    cs: ConstraintSystemRef<MpcField<Fr>>,
+    alive_mask: u32,
+    player_num: usize,
+) -> Result<(MpcFpVar<MpcField<Fr>>, Vec<MpcFpVar<MpcField<Fr>>>), SynthesisError> {
+    let alive_mask_var = MpcFpVar::new_input(cs.clone(), || Ok(MpcField::<Fr>::from(alive_mask)))?;
+
+    let one = <MpcFpVar<MpcField<Fr>> as One>::one();
+    let zero = <MpcFpVar<MpcField<Fr>> as Zero>::zero();
+    let mut packed = zero.clone();
+    let mut alive_var = Vec::with_capacity(player_num);
+    for i in 0..player_num {
+        let bit = MpcFpVar::new_witness(cs.clone(), || {
+            Ok(MpcField::<Fr>::from((alive_mask >> i) & 1))
+        })?;
+        (&bit * (&one - &bit)).enforce_equal(&zero)?;
+        packed += &bit * MpcFpVar::constant(MpcField::<Fr>::from(1u32 << i));
+        alive_var.push(bit);
+    }
+    alive_mask_var.enforce_equal(&packed)?;
+
+    Ok((alive_mask_var, alive_var))
+}
+
+/// Native `[alive_mask, day_count, prev_state_digest, next_state_digest]`, the last four
+/// public inputs of every circuit. The next digest folds `tag`, the commitments, the alive
+/// mask, the day count and the revealed `results` into the previous one with Poseidon.
+pub fn state_chain_public_inputs(
+    state_chain: &StateChainParameter,
+    tag: StateChainTag,
+    commitments: &[(Fr, Fr)],
+    results: &[Fr],
+) -> [Fr; 4] {
+    let params = PoseidonParameters::<Fr>::new();
+    let alive_mask = Fr::from(state_chain.alive_mask);
+    let day_count = Fr::from(state_chain.day_count);
+    let prev = digest_from_bytes::<Fr>(&state_chain.prev_state_digest);
+    let mut elements = vec![
+        Fr::from(tag as u32),
+        commitments_digest(&params, commitments),
+        alive_mask,
+        day_count,
+    ];
+    elements.extend_from_slice(results);
+    [
+        alive_mask,
+        day_count,
+        prev,
+        state_digest(&params, prev, &elements),
+    ]
+}
+
+/// Public inputs the Groth16 verifier checks for `instance`: the instance itself, or its
+/// [`instance_hash`] in `InstanceMode::Hashed`.
+pub fn verifier_public_inputs(instance: Vec<Fr>, mode: InstanceMode) -> Vec<Fr> {
+    match mode {
+        InstanceMode::Full => instance,
+        InstanceMode::Hashed => vec![instance_hash(&PoseidonParameters::new(), &instance)],
+    }
+}
+
+/// Affine `(x, y)` of the public commitments hashed into the state digest.
+pub trait CommitmentPoints: PrimeField + LocalOrMPC<Self> {
+    fn commitment_points(commitments: &[Self::PedersenCommitment]) -> Vec<(Fr, Fr)>;
+}
+
+impl CommitmentPoints for Fr {
+    fn commitment_points(commitments: &[Self::PedersenCommitment]) -> Vec<(Fr, Fr)> {
+        commitments.iter().map(|c| (c.x, c.y)).collect()
+    }
+}
+
+impl CommitmentPoints for MpcField<Fr> {
+    // 公開値なので reveal しても秘密は漏れない
+    fn commitment_points(commitments: &[Self::PedersenCommitment]) -> Vec<(Fr, Fr)> {
+        commitments
+            .iter()
+            .map(|c| {
+                let c = c.sync_reveal();
+                (c.x, c.y)
+            })
+            .collect()
+    }
+}
+
+// Fr で生成した定数を F に持ち上げる (baby_jubjub_coefficients と同じ変換)
+fn poseidon_parameters<F: PrimeField>() -> PoseidonParameters<F> {
+    let params = PoseidonParameters::<Fr>::new();
+    let convert = |c: &Fr| F::from_le_bytes_mod_order(&c.into_repr().to_bytes_le());
+    PoseidonParameters {
+        round_constants: params.round_constants.iter().map(convert).collect(),
+        mds: params.mds.map(|row| row.map(|m| convert(&m))),
+    }
+}
+
+/// Allocates `alive_mask`, `day_count`, `prev_state_digest` and `next_state_digest` as the
+/// last four public inputs and enforces the chain link of [`state_chain_public_inputs`]. Must
+/// be called after every other public input has been allocated.
+///
+/// `commitments` are the `(x, y)` variables of the circuit's own commitment inputs; their
+/// digest is computed in the circuit. `alive_mask_var` / `day_count_var` are the circuit's
+/// own variables for those values, if it has them, and are tied to the public ones.
+fn enforce_state_chain<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    state_chain: &StateChainParameter,
+    tag: StateChainTag,
+    commitments: &[[FpVar<F>; 2]],
+    alive_mask_var: Option<FpVar<F>>,
+    day_count_var: Option<FpVar<F>>,
+    results: &[FpVar<F>],
+) -> Result<(), SynthesisError> {
+    alloc_state_chain(
+        cs,
+        state_chain,
+        tag,
+        commitments,
+        alive_mask_var,
+        day_count_var,
+        results,
+        AllocationMode::Input,
+    )
+    .map(|_| ())
+}
+
+/// [`enforce_state_chain`] with the four values allocated as `mode`. Returns
+/// `[alive_mask, day_count, prev, next]`.
+#[allow(clippy::too_many_arguments)]
+fn alloc_state_chain<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    state_chain: &StateChainParameter,
+    tag: StateChainTag,
+    commitments: &[[FpVar<F>; 2]],
+    alive_mask_var: Option<FpVar<F>>,
+    day_count_var: Option<FpVar<F>>,
+    results: &[FpVar<F>],
+    mode: AllocationMode,
+) -> Result<[FpVar<F>; 4], SynthesisError> {
+    let params = poseidon_parameters::<F>();
+
+    // commitments_digest と同じく 0 から (x, y) を順に畳み込む
+    let mut commitments_var = FpVar::Constant(F::zero());
+    for [x, y] in commitments {
+        commitments_var = poseidon_hash_var(&params, &commitments_var, x)?;
+        commitments_var = poseidon_hash_var(&params, &commitments_var, y)?;
+    }
+
+    let alive_mask_input =
+        FpVar::new_variable(cs.clone(), || Ok(F::from(state_chain.alive_mask)), mode)?;
+    if let Some(var) = alive_mask_var {
+        alive_mask_input.enforce_equal(&var)?;
+    }
+    let day_count_input =
+        FpVar::new_variable(cs.clone(), || Ok(F::from(state_chain.day_count)), mode)?;
+    if let Some(var) = day_count_var {
+        day_count_input.enforce_equal(&var)?;
+    }
+
+    let prev_var = FpVar::new_variable(
+        cs.clone(),
+        || Ok(F::from_be_bytes_mod_order(&state_chain.prev_state_digest)),
+        mode,
+    )?;
+    let mut digest = poseidon_hash_var(&params, &prev_var, &FpVar::Constant(F::from(tag as u32)))?;
+    for element in [&commitments_var, &alive_mask_input, &day_count_input]
+        .into_iter()
+        .chain(results.iter())
+    {
+        digest = poseidon_hash_var(&params, &digest, element)?;
+    }
+
+    let next_var = FpVar::new_variable(cs.clone(), || digest.value(), mode)?;
+    next_var.enforce_equal(&digest)?;
+    Ok([alive_mask_input, day_count_input, prev_var, next_var])
+}
+
+fn enforce_state_chain_mpc(
+    cs: ConstraintSystemRef<MpcField<Fr>>,
+    state_chain: &StateChainParameter,
+    tag: StateChainTag,
+    commitments: &[[MpcFpVar<MpcField<Fr>>; 2]],
+    alive_mask_var: Option<MpcFpVar<MpcField<Fr>>>,
+    day_count_var: Option<MpcFpVar<MpcField<Fr>>>,
+    results: &[MpcFpVar<MpcField<Fr>>],
+) -> Result<(), SynthesisError> {
+    alloc_state_chain_mpc(
+        cs,
+        state_chain,
+        tag,
+        commitments,
+        alive_mask_var,
+        day_count_var,
+        results,
+        AllocationMode::Input,
+    )
+    .map(|_| ())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn alloc_state_chain_mpc(
+    cs: ConstraintSystemRef<MpcField<Fr>>,
+    state_chain: &StateChainParameter,
+    tag: StateChainTag,
+    commitments: &[[MpcFpVar<MpcField<Fr>>; 2]],
+    alive_mask_var: Option<MpcFpVar<MpcField<Fr>>>,
+    day_count_var: Option<MpcFpVar<MpcField<Fr>>>,
+    results: &[MpcFpVar<MpcField<Fr>>],
+    mode: AllocationMode,
+) -> Result<[MpcFpVar<MpcField<Fr>>; 4], SynthesisError> {
+    let params = PoseidonParameters::<Fr>::new();
+
+    let mut commitments_var = MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::zero())?;
+    for [x, y] in commitments {
+        commitments_var = poseidon_hash_var_mpc(&params, &commitments_var, x)?;
+        commitments_var = poseidon_hash_var_mpc(&params, &commitments_var, y)?;
+    }
+
+    let alive_mask_input = MpcFpVar::new_variable(
+        cs.clone(),
+        || Ok(MpcField::<Fr>::from(state_chain.alive_mask)),
+        mode,
+    )?;
+    if let Some(var) = alive_mask_var {
+        alive_mask_input.enforce_equal(&var)?;
+    }
+    let day_count_input = MpcFpVar::new_variable(
+        cs.clone(),
+        || Ok(MpcField::<Fr>::from(state_chain.day_count)),
+        mode,
+    )?;
+    if let Some(var) = day_count_var {
+        day_count_input.enforce_equal(&var)?;
+    }
+
+    let prev_var = MpcFpVar::new_variable(
+        cs.clone(),
+        || {
+            Ok(MpcField::<Fr>::from_public(digest_from_bytes::<Fr>(
+                &state_chain.prev_state_digest,
+            )))
+        },
+        mode,
+    )?;
+    let tag_var = MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(tag as u32))?;
+    let mut digest = poseidon_hash_var_mpc(&params, &prev_var, &tag_var)?;
+    for element in [&commitments_var, &alive_mask_input, &day_count_input]
+        .into_iter()
+        .chain(results.iter())
+    {
+        digest = poseidon_hash_var_mpc(&params, &digest, element)?;
+    }
+
+    let next_var = MpcFpVar::new_variable(cs.clone(), || digest.value(), mode)?;
+    next_var.enforce_equal(&digest)?;
+    Ok([alive_mask_input, day_count_input, prev_var, next_var])
+}
+
+fn instance_alloc_mode(instance_mode: InstanceMode) -> AllocationMode {
+    match instance_mode {
+        InstanceMode::Full => AllocationMode::Input,
+        InstanceMode::Hashed => AllocationMode::Witness,
+    }
+}
+
+/// Allocates [`instance_hash`] of `instance` as the only public input. `instance` holds the
+/// variables that `InstanceMode::Full` would expose, in the same order.
+fn enforce_instance_hash<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    instance: &[FpVar<F>],
+) -> Result<(), SynthesisError> {
+    let params = poseidon_parameters::<F>();
+    let mut digest = FpVar::Constant(F::from(instance.len() as u64));
+    for element in instance {
+        digest = poseidon_hash_var(&params, &digest, element)?;
+    }
+
+    let hash_var = FpVar::new_input(cs, || digest.value())?;
+    hash_var.enforce_equal(&digest)
+}
+
+fn enforce_instance_hash_mpc(
+    cs: ConstraintSystemRef<MpcField<Fr>>,
+    instance: &[MpcFpVar<MpcField<Fr>>],
+) -> Result<(), SynthesisError> {
+    let params = PoseidonParameters::<Fr>::new();
+    let mut digest =
+        MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from(instance.len() as u64))?;
+    for element in instance {
+        digest = poseidon_hash_var_mpc(&params, &digest, element)?;
+    }
+
+    let hash_var = MpcFpVar::new_input(cs, || digest.value())?;
+    hash_var.enforce_equal(&digest)
+}
+
+// In-circuit version of `PoseidonParameters::hash`. Only the S-box multiplies variables, so
+// one hash costs 3 constraints per S-box (81 S-boxes).
+fn poseidon_hash_var<F: PrimeField>(
+    params: &PoseidonParameters<F>,
+    a: &FpVar<F>,
+    b: &FpVar<F>,
+) -> Result<FpVar<F>, SynthesisError> {
+    let sbox = |x: &FpVar<F>| -> FpVar<F> {
+        let x2 = x * x;
+        let x4 = &x2 * &x2;
+        &x4 * x
+    };
+
+    let mut state = [FpVar::Constant(F::zero()), a.clone(), b.clone()];
+    for round in 0..POSEIDON_FULL_ROUNDS + POSEIDON_PARTIAL_ROUNDS {
+        for (i, s) in state.iter_mut().enumerate() {
+            *s += FpVar::Constant(params.round_constants[round * POSEIDON_WIDTH + i]);
+        }
+        if PoseidonParameters::<F>::is_full_round(round) {
+            state = [sbox(&state[0]), sbox(&state[1]), sbox(&state[2])];
+        } else {
+            state[0] = sbox(&state[0]);
+        }
+        state = [0, 1, 2].map(|i| {
+            state
+                .iter()
+                .zip(params.mds[i].iter())
+                .fold(FpVar::Constant(F::zero()), |acc, (s, m)| {
+                    acc + s * FpVar::Constant(*m)
+                })
+        });
+    }
+    Ok(state[0].clone())
+}
+
+fn poseidon_hash_var_mpc(
+    params: &PoseidonParameters<Fr>,
+    a: &MpcFpVar<MpcField<Fr>>,
+    b: &MpcFpVar<MpcField<Fr>>,
+) -> Result<MpcFpVar<MpcField<Fr>>, SynthesisError> {
+    let cs = a.cs().or(b.cs());
+    let constant = |c: Fr| MpcFpVar::new_constant(cs.clone(), MpcField::<Fr>::from_public(c));
+    let sbox = |x: &MpcFpVar<MpcField<Fr>>| -> MpcFpVar<MpcField<Fr>> {
+        let x2 = x * x;
+        let x4 = &x2 * &x2;
+        &x4 * x
+    };
+    let zero = constant(Fr::zero())?;
+    let mds = params
+        .mds
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|m| constant(*m))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut state = [zero.clone(), a.clone(), b.clone()];
+    for round in 0..POSEIDON_FULL_ROUNDS + POSEIDON_PARTIAL_ROUNDS {
+        for (i, s) in state.iter_mut().enumerate() {
+            *s += constant(params.round_constants[round * POSEIDON_WIDTH + i])?;
+        }
+        if PoseidonParameters::<Fr>::is_full_round(round) {
+            state = [sbox(&state[0]), sbox(&state[1]), sbox(&state[2])];
+        } else {
+            state[0] = sbox(&state[0]);
+        }
+        state = [0, 1, 2].map(|i| {
+            state
+                .iter()
+                .zip(mds[i].iter())
+                .fold(zero.clone(), |acc, (s, m)| acc + s * m)
+        });
+    }
+    Ok(state[0].clone())
+}
+
+// 役職配布で commit し、各回路が role_commitment に開示する役職ID。村人は 0。
+const FORTUNE_TELLER_ROLE_ID: u32 = 1;
+const WEREWOLF_ROLE_ID: u32 = 2;
+const GUARD_ROLE_ID: u32 = 3;
+const MEDIUM_ROLE_ID: u32 = 4;
+const POSSESSED_ROLE_ID: u32 = 5;
+
+// index (0..size) -> 役職ID。0が村人、1が占い師、2が人狼、3が騎士、4が霊媒師、5が狂人。
+fn role_id_lookup<F: PrimeField>(grouping_parameter: &GroupingParameter, size: usize) -> Vec<F> {
+    (0..size)
+        .map(|idx| match grouping_parameter.get_corresponding_role(idx) {
+            Role::Villager => F::from(0u32),
+            Role::FortuneTeller => F::from(FORTUNE_TELLER_ROLE_ID),
+            Role::Werewolf => F::from(WEREWOLF_ROLE_ID),
+            Role::Medium => F::from(MEDIUM_ROLE_ID),
+            Role::Possessed => F::from(POSSESSED_ROLE_ID),
+            Role::Guard => F::from(GUARD_ROLE_ID),
+        })
+        .collect()
+}
+
 // return maximum value in the vector a, index runs from 0 to use_index_len
 fn test_max<F: PrimeField>(
     a: &[FpVar<F>],
@@ -1634,6 +4265,12 @@ fn test_max<F: PrimeField>(
         for i in 0..use_index_len {
             a[i].enforce_cmp(&max_var, core::cmp::Ordering::Less, true)?;
         }
+
+        // max is one of the elements: prod (a[i] - max) = 0
+        let product = a[..use_index_len]
+            .iter()
+            .fold(<FpVar<F> as One>::one(), |acc, x| acc * (x - &max_var));
+        product.enforce_equal(&<FpVar<F> as Zero>::zero())?;
     }
 
     Ok(max_var)
@@ -1654,6 +4291,12 @@ fn test_max_mpc<F: PrimeField + SquareRootField + BitDecomposition + EqualityZer
         for i in 0..use_index_len {
             a[i].enforce_cmp(&max_var, core::cmp::Ordering::Less, true)?;
         }
+
+        // max is one of the elements: prod (a[i] - max) = 0
+        let product = a[..use_index_len]
+            .iter()
+            .fold(<MpcFpVar<F> as One>::one(), |acc, x| acc * (x - &max_var));
+        product.enforce_equal(&<MpcFpVar<F> as Zero>::zero())?;
     }
 
     Ok(max_var)
@@ -1756,7 +4399,6 @@ where
     res
 }
 
-#[allow(dead_code)]
 fn enforce_permutation_matrix<F: PrimeField>(
     matrix: &na::DMatrix<FpVar<F>>,
     n: usize,
@@ -1806,7 +4448,6 @@ fn enforce_permutation_matrix<F: PrimeField>(
     Ok(())
 }
 
-#[allow(dead_code)]
 fn enforce_permutation_matrix_mpc<
     F: PrimeField + Reveal + ark_ff::SquareRootField + mpc_algebra::EqualityZero,
 >(
@@ -1,11 +1,15 @@
 use ark_bn254::{Bn254, Fr};
 use ark_crypto_primitives::CommitmentScheme;
+use ark_ff::Zero;
 use ark_groth16::{
     create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
 };
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
 use ark_std::test_rng;
+use mpc_algebra_wasm::{digest_to_bytes, StateChainParameter, StateChainTag};
 use mpc_circuits::{
-    AnonymousVotingCircuit, AnonymousVotingPrivateInput, AnonymousVotingPublicInput,
+    state_chain_public_inputs, AnonymousVotingCircuit, AnonymousVotingPrivateInput,
+    AnonymousVotingPublicInput,
 };
 use zk_mpc::circuits::LocalOrMPC;
 
@@ -16,19 +20,36 @@ fn one_hot(index: usize, len: usize) -> Vec<Fr> {
 }
 
 fn build_anonymous_voting_circuit(targets: &[usize], player_num: usize) -> AnonymousVotingCircuit<Fr> {
+    let votes = targets.iter().map(|target| Some(*target)).collect::<Vec<_>>();
+    build_anonymous_voting_circuit_with_dead(&votes, player_num)
+}
+
+// None のプレイヤーは死亡者として投票行を 0 にする
+fn build_anonymous_voting_circuit_with_dead(
+    votes: &[Option<usize>],
+    player_num: usize,
+) -> AnonymousVotingCircuit<Fr> {
     let mut rng = test_rng();
     let pedersen_param =
         <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::setup(&mut rng).unwrap();
 
-    let private_input = targets
+    let private_input = votes
         .iter()
         .enumerate()
         .map(|(id, target)| AnonymousVotingPrivateInput::<Fr> {
             id,
-            is_target_id: one_hot(*target, player_num),
+            is_target_id: match target {
+                Some(target) => one_hot(*target, player_num),
+                None => vec![Fr::zero(); player_num],
+            },
             player_randomness: Fr::from((id + 1) as u64),
         })
         .collect::<Vec<_>>();
+    let alive_mask = votes
+        .iter()
+        .enumerate()
+        .filter(|(_, target)| target.is_some())
+        .fold(0u32, |mask, (id, _)| mask | (1 << id));
 
     AnonymousVotingCircuit::<Fr> {
         private_input,
@@ -39,12 +60,34 @@ fn build_anonymous_voting_circuit(targets: &[usize], player_num: usize) -> Anony
                 player_num
             ],
             player_num,
+            state_chain: StateChainParameter {
+                alive_mask,
+                ..Default::default()
+            },
         },
     }
 }
 
 fn build_public_inputs(circuit: &AnonymousVotingCircuit<Fr>) -> Vec<Fr> {
-    vec![circuit.calculate_output()]
+    let most_voted_id = circuit.calculate_output();
+    let commitments = circuit
+        .public_input
+        .player_commitment
+        .iter()
+        .map(|c| (c.x, c.y))
+        .collect::<Vec<_>>();
+    let mut inputs = vec![
+        Fr::from(circuit.public_input.state_chain.alive_mask),
+        most_voted_id,
+    ];
+    inputs.extend(commitments.iter().flat_map(|(x, y)| [*x, *y]));
+    inputs.extend(state_chain_public_inputs(
+        &circuit.public_input.state_chain,
+        StateChainTag::AnonymousVoting,
+        &commitments,
+        &[most_voted_id],
+    ));
+    inputs
 }
 
 fn prove_and_verify_anonymous_voting(targets: &[usize], player_num: usize) -> bool {
@@ -72,3 +115,74 @@ fn anonymous_voting_groth16_local_n5_split_vote_prove_and_verify() {
     let ok = prove_and_verify_anonymous_voting(&[1, 1, 2, 2, 3], 5);
     assert!(ok);
 }
+
+#[test]
+fn anonymous_voting_groth16_local_skips_dead_players() {
+    // 5人中プレイヤー1, 3 が死亡: 生存者 [0, 2, 4] が 4, 4, 0 に投票
+    let circuit =
+        build_anonymous_voting_circuit_with_dead(&[Some(4), None, Some(4), None, Some(0)], 5);
+    assert_eq!(circuit.calculate_output(), Fr::from(4u64));
+    let public_inputs = build_public_inputs(&circuit);
+
+    let mut rng = test_rng();
+    let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), &mut rng).unwrap();
+    let proof = create_random_proof(circuit, &params, &mut rng).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+    assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+
+    // 死亡者を生存扱いにした alive_mask では検証に失敗する
+    let mut forged_inputs = public_inputs.clone();
+    forged_inputs[0] = Fr::from(0b11111u32);
+    assert!(!verify_proof(&pvk, &proof, &forged_inputs).unwrap());
+}
+
+#[test]
+fn anonymous_voting_groth16_local_chains_state_digest() {
+    // 直前の proof の digest を prev として受け取り、投票結果を含めた next を出力する
+    let mut circuit = build_anonymous_voting_circuit(&[1, 2, 3, 0], 4);
+    circuit.public_input.state_chain.prev_state_digest = digest_to_bytes(Fr::from(42u64));
+    let public_inputs = build_public_inputs(&circuit);
+    let prev_index = public_inputs.len() - 2;
+    assert_eq!(public_inputs[prev_index], Fr::from(42u64));
+
+    let mut rng = test_rng();
+    let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), &mut rng).unwrap();
+    let proof = create_random_proof(circuit, &params, &mut rng).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+    assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+
+    // 別の digest から続く証明としては検証に失敗する
+    let mut forged_inputs = public_inputs.clone();
+    forged_inputs[prev_index] = Fr::zero();
+    assert!(!verify_proof(&pvk, &proof, &forged_inputs).unwrap());
+
+    // 経過日数と player_commitment も公開入力として digest に固定される
+    let mut forged_inputs = public_inputs.clone();
+    forged_inputs[prev_index - 1] += Fr::from(1u64);
+    assert!(!verify_proof(&pvk, &proof, &forged_inputs).unwrap());
+    let mut forged_inputs = public_inputs.clone();
+    forged_inputs[2] += Fr::from(1u64);
+    assert!(!verify_proof(&pvk, &proof, &forged_inputs).unwrap());
+
+    // next digest も回路で計算された値に固定される
+    let mut forged_inputs = public_inputs.clone();
+    forged_inputs[prev_index + 1] += Fr::from(1u64);
+    assert!(!verify_proof(&pvk, &proof, &forged_inputs).unwrap());
+}
+
+#[test]
+fn anonymous_voting_rejects_votes_from_or_for_dead_players() {
+    // 死亡者1が投票している
+    let mut circuit =
+        build_anonymous_voting_circuit_with_dead(&[Some(2), None, Some(0), Some(2)], 4);
+    circuit.private_input[1].is_target_id = one_hot(2, 4);
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+
+    // 生存者0が死亡者1に投票している
+    let circuit = build_anonymous_voting_circuit_with_dead(&[Some(1), None, Some(0), Some(2)], 4);
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
@@ -0,0 +1,205 @@
+use ark_bn254::{Bn254, Fr};
+use ark_crypto_primitives::CommitmentScheme;
+use ark_ff::{BigInteger, One, PrimeField, UniformRand, Zero};
+use ark_groth16::{
+    create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_std::test_rng;
+use mpc_algebra_wasm::{StateChainParameter, StateChainTag};
+use mpc_circuits::{
+    state_chain_public_inputs, AttackCircuit, AttackPrivateInput, AttackPublicInput,
+};
+use zk_mpc::circuits::LocalOrMPC;
+
+const VILLAGER: u64 = 0;
+const FORTUNE_TELLER: u64 = 1;
+const WEREWOLF: u64 = 2;
+
+fn commit_role(
+    pedersen_param: &<Fr as LocalOrMPC<Fr>>::PedersenParam,
+    role_id: Fr,
+    randomness: &<Fr as LocalOrMPC<Fr>>::PedersenRandomness,
+) -> <Fr as LocalOrMPC<Fr>>::PedersenCommitment {
+    <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::commit(
+        pedersen_param,
+        &role_id.into_repr().to_bytes_le(),
+        randomness,
+    )
+    .unwrap()
+}
+
+// 全員生存の状態で build_attack_circuit_with_alive_mask を呼ぶ
+fn build_attack_circuit(roles: &[u64], attacks: &[(usize, usize)]) -> AttackCircuit<Fr> {
+    build_attack_circuit_with_alive_mask(roles, (1 << roles.len()) - 1, attacks)
+}
+
+// roles[id] の役職で commit し、attacks に含まれる (人狼 id, 襲撃先) の組で投票する。
+// 死亡者の行も id 順に並べる
+fn build_attack_circuit_with_alive_mask(
+    roles: &[u64],
+    alive_mask: u32,
+    attacks: &[(usize, usize)],
+) -> AttackCircuit<Fr> {
+    let mut rng = test_rng();
+    let player_num = roles.len();
+
+    let pedersen_param =
+        <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::setup(&mut rng).unwrap();
+
+    let mut private_input = Vec::with_capacity(player_num);
+    let mut role_commitment = Vec::with_capacity(player_num);
+    for (id, role) in roles.iter().enumerate() {
+        let role_id = Fr::from(*role);
+        let randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng);
+        role_commitment.push(commit_role(&pedersen_param, role_id, &randomness));
+
+        let mut is_target_id = vec![Fr::zero(); player_num];
+        if let Some((_, target)) = attacks.iter().find(|(attacker, _)| *attacker == id) {
+            is_target_id[*target] = Fr::one();
+        }
+
+        private_input.push(AttackPrivateInput::<Fr> {
+            id,
+            is_target_id,
+            role_id,
+            randomness,
+        });
+    }
+
+    AttackCircuit::<Fr> {
+        private_input,
+        public_input: AttackPublicInput::<Fr> {
+            pedersen_param,
+            player_num,
+            role_commitment,
+            state_chain: StateChainParameter {
+                alive_mask,
+                day_count: 1,
+                ..Default::default()
+            },
+        },
+    }
+}
+
+fn build_public_inputs(circuit: &AttackCircuit<Fr>) -> Vec<Fr> {
+    // 生存者マスクと結果の後に role_commitment が id 順に並ぶ
+    let result = circuit.calculate_output();
+    let commitments = circuit
+        .public_input
+        .role_commitment
+        .iter()
+        .map(|commitment| (commitment.x, commitment.y))
+        .collect::<Vec<_>>();
+    let mut inputs = vec![Fr::from(circuit.public_input.state_chain.alive_mask), result];
+    inputs.extend(commitments.iter().flat_map(|(x, y)| [*x, *y]));
+    inputs.extend(state_chain_public_inputs(
+        &circuit.public_input.state_chain,
+        StateChainTag::Attack,
+        &commitments,
+        &[result],
+    ));
+    inputs
+}
+
+fn is_satisfied(circuit: AttackCircuit<Fr>) -> bool {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    cs.is_satisfied().unwrap()
+}
+
+#[test]
+fn attack_groth16_local_prove_and_verify() {
+    // 5人: プレイヤー1が占い師、プレイヤー3, 4が人狼でどちらもプレイヤー0を襲撃
+    let roles = [VILLAGER, FORTUNE_TELLER, VILLAGER, WEREWOLF, WEREWOLF];
+    let circuit = build_attack_circuit(&roles, &[(3, 0), (4, 0)]);
+    assert_eq!(circuit.calculate_output(), Fr::from(0u64));
+
+    let public_inputs = build_public_inputs(&circuit);
+    assert_eq!(public_inputs.len(), 2 + 2 * 5 + 4);
+
+    let mut rng = test_rng();
+    let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), &mut rng).unwrap();
+    let proof = create_random_proof(circuit, &params, &mut rng).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+
+    // 別の襲撃先を主張する証明としては検証に失敗する
+    let mut forged_inputs = public_inputs.clone();
+    forged_inputs[1] = Fr::from(2u64);
+    assert!(!verify_proof(&pvk, &proof, &forged_inputs).unwrap());
+}
+
+#[test]
+fn attack_split_vote_picks_earliest_target() {
+    let roles = [VILLAGER, FORTUNE_TELLER, VILLAGER, WEREWOLF, WEREWOLF];
+    let circuit = build_attack_circuit(&roles, &[(3, 2), (4, 1)]);
+
+    assert_eq!(circuit.calculate_output(), Fr::from(1u64));
+    assert!(is_satisfied(circuit));
+}
+
+#[test]
+fn attack_rejects_vote_from_non_werewolf() {
+    let roles = [VILLAGER, FORTUNE_TELLER, VILLAGER, WEREWOLF, WEREWOLF];
+    let circuit = build_attack_circuit(&roles, &[(3, 0), (4, 0), (2, 1)]);
+
+    assert!(!is_satisfied(circuit));
+}
+
+#[test]
+fn attack_rejects_werewolf_without_target() {
+    let roles = [VILLAGER, FORTUNE_TELLER, VILLAGER, WEREWOLF, WEREWOLF];
+    let circuit = build_attack_circuit(&roles, &[(3, 0)]);
+
+    assert!(!is_satisfied(circuit));
+}
+
+#[test]
+fn attack_rejects_role_not_matching_commitment() {
+    // プレイヤー2は村人として commit しているが、人狼として投票しようとする
+    let roles = [VILLAGER, FORTUNE_TELLER, VILLAGER, WEREWOLF, WEREWOLF];
+    let mut circuit = build_attack_circuit(&roles, &[(3, 0), (4, 0)]);
+    circuit.private_input[2].role_id = Fr::from(WEREWOLF);
+    circuit.private_input[2].is_target_id[1] = Fr::one();
+
+    assert!(!is_satisfied(circuit));
+}
+
+#[test]
+fn attack_rejects_vote_from_dead_werewolf() {
+    // プレイヤー4は死亡した人狼で、生存する人狼プレイヤー3と一緒に投票しようとする
+    let roles = [VILLAGER, FORTUNE_TELLER, VILLAGER, WEREWOLF, WEREWOLF];
+    let circuit = build_attack_circuit_with_alive_mask(&roles, 0b01111, &[(3, 0), (4, 0)]);
+
+    assert!(!is_satisfied(circuit));
+}
+
+#[test]
+fn attack_rejects_dead_target() {
+    // プレイヤー0は既に死亡している
+    let roles = [VILLAGER, FORTUNE_TELLER, VILLAGER, WEREWOLF, WEREWOLF];
+    let circuit = build_attack_circuit_with_alive_mask(&roles, 0b11110, &[(3, 0), (4, 0)]);
+
+    assert!(!is_satisfied(circuit));
+}
+
+#[test]
+fn attack_allows_dead_rows_without_votes() {
+    // プレイヤー0 (村人) と人狼プレイヤー4が死亡し、残る人狼が占い師を襲撃する
+    let roles = [VILLAGER, FORTUNE_TELLER, VILLAGER, WEREWOLF, WEREWOLF];
+    let circuit = build_attack_circuit_with_alive_mask(&roles, 0b01110, &[(3, 1)]);
+
+    assert_eq!(circuit.calculate_output(), Fr::from(1u64));
+    assert!(is_satisfied(circuit));
+}
+
+#[test]
+fn attack_rejects_zero_votes() {
+    // 生存する人狼がいないと誰も投票せず、襲撃先がプレイヤー0 に決まってしまう
+    let roles = [VILLAGER, FORTUNE_TELLER, VILLAGER, WEREWOLF, WEREWOLF];
+    let circuit = build_attack_circuit_with_alive_mask(&roles, 0b00111, &[]);
+
+    assert!(!is_satisfied(circuit));
+}
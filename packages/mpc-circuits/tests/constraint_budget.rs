@@ -0,0 +1,459 @@
+// Constraint and public input budgets per CircuitProfile.
+//
+// Every on-chain profile, and every player count of the off-chain profiles (Attack,
+// NightResolution, Medium), is synthesized in setup mode (no witness generation) and
+// checked against
+// - a constraint budget, so that the Groth16 / MPC prover stays within reach
+// - the expected public input count, which the node, the deploy script and the
+//   generated Solidity verifiers rely on
+// - an upper bound on the public inputs, since every input adds a point to the
+//   generated verifier and large verifiers exceed the EVM contract size limit
+//
+// Run with:
+// cargo test --release --test constraint_budget -- --nocapture
+
+use std::collections::BTreeMap;
+
+use ark_bn254::Fr;
+use ark_crypto_primitives::encryption::AsymmetricEncryptionScheme;
+use ark_crypto_primitives::CommitmentScheme;
+use ark_ff::{One, UniformRand, Zero};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisMode};
+use ark_std::test_rng;
+use mpc_algebra_wasm::{
+    CircuitProfile, GroupingParameter, InstanceMode, Role as GroupingRole, StateChainParameter,
+    WinConditionParameter,
+};
+use mpc_circuits::{
+    AnonymousVotingCircuit, AnonymousVotingPrivateInput, AnonymousVotingPublicInput, AttackCircuit,
+    AttackPrivateInput, AttackPublicInput, DivinationCircuit, DivinationPrivateInput,
+    DivinationPublicInput, KeyPublicizeCircuit, KeyPublicizePrivateInput, KeyPublicizePublicInput,
+    MediumCircuit, MediumPrivateInput, MediumPublicInput, NightResolutionCircuit,
+    NightResolutionPrivateInput, NightResolutionPublicInput, RoleAssignmentCircuit,
+    RoleAssignmentPrivateInput, RoleAssignmentPublicInput, WinningJudgementCircuit,
+    WinningJudgementPrivateInput, WinningJudgementPublicInput,
+};
+use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
+
+// RoleAssignment は n9-w1 (18x18 の行列積) が最大
+const ROLE_ASSIGNMENT_MAX_CONSTRAINTS: usize = 1 << 20;
+const DEFAULT_MAX_CONSTRAINTS: usize = 1 << 18;
+// Standard レイアウトでは RoleAssignment n7-w1 (tau を公開入力にしていた頃の 196 個) で 24,576 bytes を超えた。
+// Compact レイアウトは gamma_abc 1 点あたり 64 bytes なので 256 個でも 17 KB 程度に収まる
+const MAX_EVM_PUBLIC_INPUTS: usize = 256;
+// 全回路の公開入力の末尾に付く alive_mask, day_count, prev / next state digest
+const STATE_CHAIN_PUBLIC_INPUT_LEN: usize = 4;
+
+fn budgeted_profiles() -> Vec<CircuitProfile> {
+    let mut profiles = Vec::new();
+    for player_count in 2..=9 {
+        for werewolf_count in 1..=3 {
+            profiles.push(CircuitProfile::RoleAssignment {
+                player_count,
+                werewolf_count,
+            });
+        }
+        profiles.push(CircuitProfile::Divination { player_count });
+        profiles.push(CircuitProfile::AnonymousVoting { player_count });
+        profiles.push(CircuitProfile::WinningJudge { player_count });
+        profiles.push(CircuitProfile::KeyPublicize { player_count });
+    }
+    profiles.retain(|profile| profile.is_supported_onchain_profile());
+    // Solidity verifier を持たない profile も同じ予算で測る
+    for player_count in 3..=9 {
+        profiles.push(CircuitProfile::Attack { player_count });
+        profiles.push(CircuitProfile::NightResolution { player_count });
+        profiles.push(CircuitProfile::Medium { player_count });
+    }
+    profiles
+}
+
+fn constraint_budget(profile: &CircuitProfile) -> usize {
+    match profile {
+        CircuitProfile::RoleAssignment { .. } => ROLE_ASSIGNMENT_MAX_CONSTRAINTS,
+        _ => DEFAULT_MAX_CONSTRAINTS,
+    }
+}
+
+fn expected_public_input_len(profile: &CircuitProfile) -> usize {
+    let n = profile.player_count();
+    STATE_CHAIN_PUBLIC_INPUT_LEN
+        + match profile {
+            // player_commitment (x, y) + role_commitment (x, y)
+            CircuitProfile::RoleAssignment { .. } => 4 * n,
+            // alive_mask + ElGamal param, pub_key, ciphertext
+            CircuitProfile::Divination { .. } => 9,
+            // alive_mask + most_voted_id + player_commitment (x, y)
+            CircuitProfile::AnonymousVoting { .. } => 2 + 2 * n,
+            // num_alive + alive_mask + majority, day_limit, day_count + game_state + winner_mask
+            // + role_commitment (x, y)
+            CircuitProfile::WinningJudge { .. } => 7 + 2 * n,
+            // pub_key (x, y) + role_commitment (x, y)
+            CircuitProfile::KeyPublicize { .. } => 2 + 2 * n,
+            // alive_mask + victim id + role_commitment (x, y)
+            CircuitProfile::Attack { .. } => 2 + 2 * n,
+            // dead id + role_commitment (x, y)
+            CircuitProfile::NightResolution { .. } => 1 + 2 * n,
+            // executed id + Divination layout + role_commitment (x, y)
+            CircuitProfile::Medium { .. } => 9 + 2 * n,
+        }
+}
+
+fn pedersen_param(rng: &mut impl ark_std::rand::RngCore) -> <Fr as LocalOrMPC<Fr>>::PedersenParam {
+    <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::setup(rng).unwrap()
+}
+
+fn build_role_assignment_circuit(
+    num_players: usize,
+    werewolf_count: usize,
+) -> RoleAssignmentCircuit<Fr> {
+    let mut rng = test_rng();
+    let mut map = BTreeMap::new();
+    map.insert(GroupingRole::FortuneTeller, (1, false));
+    map.insert(GroupingRole::Werewolf, (werewolf_count, werewolf_count > 1));
+    map.insert(
+        GroupingRole::Villager,
+        (num_players - 1 - werewolf_count, false),
+    );
+    let grouping_parameter = GroupingParameter::new(map);
+    let tau_matrix = grouping_parameter.generate_tau_matrix::<Fr>();
+    let matrix_size = tau_matrix.nrows();
+
+    let private_input = (0..num_players)
+        .map(|id| RoleAssignmentPrivateInput::<Fr> {
+            id,
+            shuffle_matrices: nalgebra::DMatrix::<Fr>::identity(matrix_size, matrix_size),
+            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng),
+            player_randomness: Fr::from((id + 1) as u64),
+            player_commitment_randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(
+                &mut rng,
+            ),
+        })
+        .collect::<Vec<_>>();
+
+    RoleAssignmentCircuit {
+        private_input,
+        public_input: RoleAssignmentPublicInput::<Fr> {
+            num_players,
+            max_group_size: grouping_parameter.get_max_group_size(),
+            pedersen_param: pedersen_param(&mut rng),
+            grouping_parameter,
+            tau_matrix,
+            role_commitment: vec![
+                <Fr as LocalOrMPC<Fr>>::PedersenCommitment::default();
+                num_players
+            ],
+            player_commitment: vec![
+                <Fr as LocalOrMPC<Fr>>::PedersenCommitment::default();
+                num_players
+            ],
+            state_chain: StateChainParameter::default(),
+            instance_mode: InstanceMode::Full,
+        },
+    }
+}
+
+fn build_divination_circuit(num_players: usize) -> DivinationCircuit<Fr> {
+    let mut rng = test_rng();
+    let pedersen_param = pedersen_param(&mut rng);
+    let elgamal_param =
+        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::setup(
+            &mut rng,
+        )
+        .unwrap();
+    let (pub_key, _secret_key) =
+        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::keygen(
+            &elgamal_param,
+            &mut rng,
+        )
+        .unwrap();
+    let randomness = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalRandomness::rand(&mut rng);
+
+    let private_input = (0..num_players)
+        .map(|id| {
+            let mut is_target = vec![Fr::zero(); num_players];
+            if id == 0 {
+                is_target[0] = Fr::one();
+            }
+            DivinationPrivateInput::<Fr> {
+                id,
+                is_werewolf: if id == 0 { Fr::one() } else { Fr::zero() },
+                is_target,
+                randomness: randomness.clone(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    DivinationCircuit {
+        private_input,
+        public_input: DivinationPublicInput::<Fr> {
+            pedersen_param,
+            elgamal_param,
+            pub_key,
+            player_num: num_players,
+            state_chain: StateChainParameter {
+                alive_mask: (1 << num_players) - 1,
+                ..Default::default()
+            },
+        },
+    }
+}
+
+fn build_anonymous_voting_circuit(num_players: usize) -> AnonymousVotingCircuit<Fr> {
+    let mut rng = test_rng();
+    let private_input = (0..num_players)
+        .map(|id| {
+            let mut is_target_id = vec![Fr::zero(); num_players];
+            is_target_id[1] = Fr::one();
+            AnonymousVotingPrivateInput::<Fr> {
+                id,
+                is_target_id,
+                player_randomness: Fr::from((id + 1) as u64),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    AnonymousVotingCircuit {
+        private_input,
+        public_input: AnonymousVotingPublicInput::<Fr> {
+            pedersen_param: pedersen_param(&mut rng),
+            player_commitment: vec![
+                <Fr as LocalOrMPC<Fr>>::PedersenCommitment::default();
+                num_players
+            ],
+            player_num: num_players,
+            state_chain: StateChainParameter {
+                alive_mask: (1 << num_players) - 1,
+                ..Default::default()
+            },
+        },
+    }
+}
+
+fn build_winning_judgement_circuit(num_players: usize) -> WinningJudgementCircuit<Fr> {
+    let mut rng = test_rng();
+    let private_input = (0..num_players)
+        .map(|id| WinningJudgementPrivateInput::<Fr> {
+            id,
+            am_werewolf: if id == 0 { Fr::one() } else { Fr::zero() },
+            am_werewolf_team: if id == 0 { Fr::one() } else { Fr::zero() },
+            am_fox: Fr::zero(),
+            role_id: if id == 0 { Fr::from(2u64) } else { Fr::zero() },
+            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng),
+        })
+        .collect::<Vec<_>>();
+
+    WinningJudgementCircuit {
+        private_input,
+        public_input: WinningJudgementPublicInput::<Fr> {
+            pedersen_param: pedersen_param(&mut rng),
+            player_num: num_players,
+            win_condition: WinConditionParameter::default(),
+            role_commitment: vec![
+                <Fr as LocalOrMPC<Fr>>::PedersenCommitment::default();
+                num_players
+            ],
+            state_chain: StateChainParameter {
+                alive_mask: (1 << num_players) - 1,
+                day_count: 1,
+                ..Default::default()
+            },
+        },
+    }
+}
+
+fn build_key_publicize_circuit(num_players: usize) -> KeyPublicizeCircuit<Fr> {
+    let mut rng = test_rng();
+    let private_input = (0..num_players)
+        .map(|id| KeyPublicizePrivateInput::<Fr> {
+            id,
+            pub_key_or_dummy_x: Fr::zero(),
+            pub_key_or_dummy_y: Fr::zero(),
+            role_id: if id == 0 { Fr::one() } else { Fr::zero() },
+            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng),
+        })
+        .collect::<Vec<_>>();
+
+    KeyPublicizeCircuit {
+        private_input,
+        public_input: KeyPublicizePublicInput::<Fr> {
+            pedersen_param: pedersen_param(&mut rng),
+            role_commitment: vec![
+                <Fr as LocalOrMPC<Fr>>::PedersenCommitment::default();
+                num_players
+            ],
+            state_chain: StateChainParameter::default(),
+        },
+    }
+}
+
+fn build_attack_circuit(num_players: usize) -> AttackCircuit<Fr> {
+    let mut rng = test_rng();
+    let private_input = (0..num_players)
+        .map(|id| AttackPrivateInput::<Fr> {
+            id,
+            is_target_id: vec![Fr::zero(); num_players],
+            role_id: if id == 0 { Fr::from(2u64) } else { Fr::zero() },
+            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng),
+        })
+        .collect::<Vec<_>>();
+
+    AttackCircuit {
+        private_input,
+        public_input: AttackPublicInput::<Fr> {
+            pedersen_param: pedersen_param(&mut rng),
+            player_num: num_players,
+            role_commitment: vec![
+                <Fr as LocalOrMPC<Fr>>::PedersenCommitment::default();
+                num_players
+            ],
+            state_chain: StateChainParameter {
+                alive_mask: (1 << num_players) - 1,
+                ..Default::default()
+            },
+        },
+    }
+}
+
+fn build_night_resolution_circuit(num_players: usize) -> NightResolutionCircuit<Fr> {
+    let mut rng = test_rng();
+    let private_input = (0..num_players)
+        .map(|id| NightResolutionPrivateInput::<Fr> {
+            id,
+            is_attack_target_id: vec![Fr::zero(); num_players],
+            is_guard_target_id: vec![Fr::zero(); num_players],
+            role_id: Fr::zero(),
+            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng),
+        })
+        .collect::<Vec<_>>();
+
+    NightResolutionCircuit {
+        private_input,
+        public_input: NightResolutionPublicInput::<Fr> {
+            pedersen_param: pedersen_param(&mut rng),
+            player_num: num_players,
+            role_commitment: vec![
+                <Fr as LocalOrMPC<Fr>>::PedersenCommitment::default();
+                num_players
+            ],
+            state_chain: StateChainParameter::default(),
+        },
+    }
+}
+
+fn build_medium_circuit(num_players: usize) -> MediumCircuit<Fr> {
+    let mut rng = test_rng();
+    let pedersen_param = pedersen_param(&mut rng);
+    let elgamal_param =
+        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::setup(
+            &mut rng,
+        )
+        .unwrap();
+    let (pub_key, _secret_key) =
+        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::keygen(
+            &elgamal_param,
+            &mut rng,
+        )
+        .unwrap();
+    let randomness = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalRandomness::rand(&mut rng);
+
+    let private_input = (0..num_players)
+        .map(|id| MediumPrivateInput::<Fr> {
+            id,
+            role_id: if id == 0 { Fr::from(2u64) } else { Fr::zero() },
+            role_randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng),
+            randomness: randomness.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    MediumCircuit {
+        private_input,
+        public_input: MediumPublicInput::<Fr> {
+            pedersen_param,
+            elgamal_param,
+            pub_key,
+            player_num: num_players,
+            executed_id: 0,
+            role_commitment: vec![
+                <Fr as LocalOrMPC<Fr>>::PedersenCommitment::default();
+                num_players
+            ],
+            state_chain: StateChainParameter::default(),
+        },
+    }
+}
+
+/// Returns (number of constraints, number of public inputs).
+fn synthesize_in_setup_mode<C: ConstraintSynthesizer<Fr>>(circuit: C) -> (usize, usize) {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    circuit.generate_constraints(cs.clone()).unwrap();
+    // 1 つ目の instance 変数は定数 1
+    (cs.num_constraints(), cs.num_instance_variables() - 1)
+}
+
+fn measure(profile: &CircuitProfile) -> (usize, usize) {
+    let n = profile.player_count();
+    match profile {
+        CircuitProfile::RoleAssignment { werewolf_count, .. } => {
+            synthesize_in_setup_mode(build_role_assignment_circuit(n, *werewolf_count))
+        }
+        CircuitProfile::Divination { .. } => synthesize_in_setup_mode(build_divination_circuit(n)),
+        CircuitProfile::AnonymousVoting { .. } => {
+            synthesize_in_setup_mode(build_anonymous_voting_circuit(n))
+        }
+        CircuitProfile::WinningJudge { .. } => {
+            synthesize_in_setup_mode(build_winning_judgement_circuit(n))
+        }
+        CircuitProfile::KeyPublicize { .. } => {
+            synthesize_in_setup_mode(build_key_publicize_circuit(n))
+        }
+        CircuitProfile::Attack { .. } => synthesize_in_setup_mode(build_attack_circuit(n)),
+        CircuitProfile::NightResolution { .. } => {
+            synthesize_in_setup_mode(build_night_resolution_circuit(n))
+        }
+        CircuitProfile::Medium { .. } => synthesize_in_setup_mode(build_medium_circuit(n)),
+    }
+}
+
+#[test]
+fn circuit_profiles_stay_within_constraint_and_public_input_budget() {
+    let mut violations = Vec::new();
+
+    for profile in budgeted_profiles() {
+        let (num_constraints, num_public_inputs) = measure(&profile);
+        println!(
+            "{:?}: constraints={}, public_inputs={}",
+            profile, num_constraints, num_public_inputs
+        );
+
+        if num_constraints > constraint_budget(&profile) {
+            violations.push(format!(
+                "{:?}: {} constraints exceed the budget of {}",
+                profile,
+                num_constraints,
+                constraint_budget(&profile)
+            ));
+        }
+        if num_public_inputs != expected_public_input_len(&profile) {
+            violations.push(format!(
+                "{:?}: expected {} public inputs, got {}",
+                profile,
+                expected_public_input_len(&profile),
+                num_public_inputs
+            ));
+        }
+        if num_public_inputs > MAX_EVM_PUBLIC_INPUTS {
+            violations.push(format!(
+                "{:?}: {} public inputs exceed the EVM verifier bound of {}",
+                profile, num_public_inputs, MAX_EVM_PUBLIC_INPUTS
+            ));
+        }
+    }
+
+    assert!(
+        violations.is_empty(),
+        "circuit budget violations:\n{}",
+        violations.join("\n")
+    );
+}
@@ -4,8 +4,12 @@ use ark_crypto_primitives::CommitmentScheme;
 use ark_ec::AffineCurve;
 use ark_ff::{One, UniformRand, Zero};
 use ark_groth16::{create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
 use ark_std::test_rng;
-use mpc_circuits::{DivinationCircuit, DivinationPrivateInput, DivinationPublicInput};
+use mpc_algebra_wasm::{StateChainParameter, StateChainTag};
+use mpc_circuits::{
+    state_chain_public_inputs, DivinationCircuit, DivinationPrivateInput, DivinationPublicInput,
+};
 use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
 
 fn one_hot(index: usize, len: usize) -> Vec<Fr> {
@@ -62,6 +66,10 @@ fn build_divination_circuit(
             elgamal_param,
             pub_key,
             player_num,
+            state_chain: StateChainParameter {
+                alive_mask: (1 << player_num) - 1,
+                ..Default::default()
+            },
         },
     }
 }
@@ -95,16 +103,22 @@ fn build_public_inputs(circuit: &DivinationCircuit<Fr>) -> Vec<Fr> {
         )
         .unwrap();
 
-    vec![
+    let ciphertext = [ciphertext.0.x, ciphertext.0.y, ciphertext.1.x, ciphertext.1.y];
+    let mut inputs = vec![
+        Fr::from(circuit.public_input.state_chain.alive_mask),
         circuit.public_input.elgamal_param.generator.x,
         circuit.public_input.elgamal_param.generator.y,
         circuit.public_input.pub_key.x,
         circuit.public_input.pub_key.y,
-        ciphertext.0.x,
-        ciphertext.0.y,
-        ciphertext.1.x,
-        ciphertext.1.y,
-    ]
+    ];
+    inputs.extend(ciphertext);
+    inputs.extend(state_chain_public_inputs(
+        &circuit.public_input.state_chain,
+        StateChainTag::Divination,
+        &[],
+        &ciphertext,
+    ));
+    inputs
 }
 
 #[test]
@@ -121,3 +135,21 @@ fn divination_groth16_local_prove_and_verify() {
     let ok = verify_proof(&pvk, &proof, &public_inputs).unwrap();
     assert!(ok);
 }
+
+#[test]
+fn divination_rejects_dead_seer_or_dead_target() {
+    // 占い先のプレイヤー1が死亡している
+    let mut circuit = build_divination_circuit(5, 3, 1, 1);
+    circuit.public_input.state_chain.alive_mask = 0b11101;
+    circuit.private_input[1].is_werewolf = Fr::zero();
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+
+    // 占い師3が死亡しているのに占い先を指定している
+    let mut circuit = build_divination_circuit(5, 3, 1, 1);
+    circuit.public_input.state_chain.alive_mask = 0b10111;
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
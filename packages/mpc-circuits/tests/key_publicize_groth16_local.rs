@@ -0,0 +1,149 @@
+use ark_bn254::{Bn254, Fr};
+use ark_crypto_primitives::encryption::AsymmetricEncryptionScheme;
+use ark_crypto_primitives::CommitmentScheme;
+use ark_ff::{BigInteger, One, PrimeField, UniformRand, Zero};
+use ark_groth16::{
+    create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_std::test_rng;
+use mpc_algebra_wasm::{StateChainParameter, StateChainTag};
+use mpc_circuits::{
+    state_chain_public_inputs, KeyPublicizeCircuit, KeyPublicizePrivateInput,
+    KeyPublicizePublicInput,
+};
+use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
+
+type ElGamalScheme = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme;
+
+fn commit_role(
+    pedersen_param: &<Fr as LocalOrMPC<Fr>>::PedersenParam,
+    role_id: Fr,
+    randomness: &<Fr as LocalOrMPC<Fr>>::PedersenRandomness,
+) -> <Fr as LocalOrMPC<Fr>>::PedersenCommitment {
+    <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::commit(
+        pedersen_param,
+        &role_id.into_repr().to_bytes_le(),
+        randomness,
+    )
+    .unwrap()
+}
+
+// fortune_tellers に含まれるプレイヤー (役職ID 1) は自分の公開鍵を、それ以外 (人狼 2 / 村人 0) は 0 を入力する
+fn build_key_publicize_circuit(
+    player_num: usize,
+    fortune_tellers: &[usize],
+) -> KeyPublicizeCircuit<Fr> {
+    let mut rng = test_rng();
+
+    let pedersen_param =
+        <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::setup(&mut rng).unwrap();
+    let elgamal_param = ElGamalScheme::setup(&mut rng).unwrap();
+
+    let mut private_input = Vec::with_capacity(player_num);
+    let mut role_commitment = Vec::with_capacity(player_num);
+    for id in 0..player_num {
+        let is_fortune_teller = fortune_tellers.contains(&id);
+        let (pub_key_or_dummy_x, pub_key_or_dummy_y, role_id) = if is_fortune_teller {
+            let (pub_key, _secret_key) = ElGamalScheme::keygen(&elgamal_param, &mut rng).unwrap();
+            (pub_key.x, pub_key.y, Fr::from(1u32))
+        } else if id == 1 {
+            (Fr::zero(), Fr::zero(), Fr::from(2u32))
+        } else {
+            (Fr::zero(), Fr::zero(), Fr::zero())
+        };
+        let randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng);
+        role_commitment.push(commit_role(&pedersen_param, role_id, &randomness));
+
+        private_input.push(KeyPublicizePrivateInput::<Fr> {
+            id,
+            pub_key_or_dummy_x,
+            pub_key_or_dummy_y,
+            role_id,
+            randomness,
+        });
+    }
+
+    KeyPublicizeCircuit::<Fr> {
+        private_input,
+        public_input: KeyPublicizePublicInput::<Fr> {
+            pedersen_param,
+            role_commitment,
+            state_chain: StateChainParameter::default(),
+        },
+    }
+}
+
+fn build_public_inputs(circuit: &KeyPublicizeCircuit<Fr>) -> Vec<Fr> {
+    let (pub_key_x, pub_key_y) = circuit.calculate_output();
+    let mut inputs = vec![pub_key_x, pub_key_y];
+    let mut commitments = Vec::new();
+    for commitment in circuit.public_input.role_commitment.iter() {
+        inputs.push(commitment.x);
+        inputs.push(commitment.y);
+        commitments.push((commitment.x, commitment.y));
+    }
+    inputs.extend(state_chain_public_inputs(
+        &circuit.public_input.state_chain,
+        StateChainTag::KeyPublicize,
+        &commitments,
+        &[pub_key_x, pub_key_y],
+    ));
+    inputs
+}
+
+fn is_satisfied(circuit: KeyPublicizeCircuit<Fr>) -> bool {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    cs.is_satisfied().unwrap()
+}
+
+#[test]
+fn key_publicize_groth16_local_prove_and_verify() {
+    // 5人: プレイヤー2が占い師
+    let circuit = build_key_publicize_circuit(5, &[2]);
+    let public_inputs = build_public_inputs(&circuit);
+    assert_eq!(public_inputs.len(), 2 + 2 * 5 + 4);
+
+    let mut rng = test_rng();
+    let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), &mut rng).unwrap();
+    let proof = create_random_proof(circuit, &params, &mut rng).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+
+    // 別の公開鍵を主張する証明としては検証に失敗する
+    let mut forged_inputs = public_inputs.clone();
+    forged_inputs[0] += Fr::one();
+    assert!(!verify_proof(&pvk, &proof, &forged_inputs).unwrap());
+}
+
+#[test]
+fn key_publicize_rejects_multiple_or_missing_fortune_tellers() {
+    assert!(is_satisfied(build_key_publicize_circuit(5, &[2])));
+    assert!(!is_satisfied(build_key_publicize_circuit(5, &[1, 2])));
+    assert!(!is_satisfied(build_key_publicize_circuit(5, &[])));
+}
+
+#[test]
+fn key_publicize_rejects_off_curve_key() {
+    let mut circuit = build_key_publicize_circuit(5, &[2]);
+    circuit.private_input[2].pub_key_or_dummy_x += Fr::one();
+
+    assert!(!is_satisfied(circuit));
+}
+
+#[test]
+fn key_publicize_rejects_role_id_not_matching_commitment() {
+    // プレイヤー0は占い師ではないと commit しているが、占い師として鍵を公開しようとする
+    let mut circuit = build_key_publicize_circuit(5, &[2]);
+    let fortune_teller = circuit.private_input[2].clone();
+    circuit.private_input[0].pub_key_or_dummy_x = fortune_teller.pub_key_or_dummy_x;
+    circuit.private_input[0].pub_key_or_dummy_y = fortune_teller.pub_key_or_dummy_y;
+    circuit.private_input[0].role_id = Fr::from(1u32);
+    circuit.private_input[2].pub_key_or_dummy_x = Fr::zero();
+    circuit.private_input[2].pub_key_or_dummy_y = Fr::zero();
+    circuit.private_input[2].role_id = Fr::zero();
+
+    assert!(!is_satisfied(circuit));
+}
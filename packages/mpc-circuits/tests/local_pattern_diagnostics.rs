@@ -2,6 +2,7 @@ use ark_bn254::Fr;
 use ark_crypto_primitives::CommitmentScheme;
 use ark_ff::{BigInteger, One, PrimeField, Zero};
 use ark_std::test_rng;
+use mpc_algebra_wasm::StateChainParameter;
 use mpc_circuits::{
     AnonymousVotingCircuit, AnonymousVotingPrivateInput, AnonymousVotingPublicInput,
 };
@@ -62,6 +63,10 @@ fn build_voting_circuit(targets: &[usize], candidate_count: usize) -> AnonymousV
                 candidate_count
             ],
             player_num: candidate_count,
+            state_chain: StateChainParameter {
+                alive_mask: (1 << candidate_count) - 1,
+                ..Default::default()
+            },
         },
     }
 }
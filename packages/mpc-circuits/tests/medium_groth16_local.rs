@@ -0,0 +1,194 @@
+use ark_bn254::{Bn254, Fr};
+use ark_crypto_primitives::encryption::AsymmetricEncryptionScheme;
+use ark_crypto_primitives::CommitmentScheme;
+use ark_ec::AffineCurve;
+use ark_ff::{BigInteger, PrimeField, UniformRand};
+use ark_groth16::{
+    create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_std::test_rng;
+use mpc_algebra_wasm::{StateChainParameter, StateChainTag};
+use mpc_circuits::{
+    state_chain_public_inputs, MediumCircuit, MediumPrivateInput, MediumPublicInput,
+};
+use zk_mpc::circuits::{ElGamalLocalOrMPC, LocalOrMPC};
+
+type ElGamalScheme = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme;
+
+const VILLAGER: u64 = 0;
+const WEREWOLF: u64 = 2;
+
+fn commit_role(
+    pedersen_param: &<Fr as LocalOrMPC<Fr>>::PedersenParam,
+    role_id: Fr,
+    randomness: &<Fr as LocalOrMPC<Fr>>::PedersenRandomness,
+) -> <Fr as LocalOrMPC<Fr>>::PedersenCommitment {
+    <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::commit(
+        pedersen_param,
+        &role_id.into_repr().to_bytes_le(),
+        randomness,
+    )
+    .unwrap()
+}
+
+// werewolf_id が人狼で、executed_id が処刑された状態の入力を作る
+fn build_medium_circuit(
+    player_num: usize,
+    werewolf_id: usize,
+    executed_id: usize,
+) -> (
+    MediumCircuit<Fr>,
+    <ElGamalScheme as AsymmetricEncryptionScheme>::SecretKey,
+) {
+    let mut rng = test_rng();
+
+    let pedersen_param =
+        <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::setup(&mut rng).unwrap();
+    let elgamal_param = ElGamalScheme::setup(&mut rng).unwrap();
+    let (pub_key, secret_key) = ElGamalScheme::keygen(&elgamal_param, &mut rng).unwrap();
+
+    let shared_randomness = <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalRandomness::rand(&mut rng);
+
+    let mut private_input = Vec::with_capacity(player_num);
+    let mut role_commitment = Vec::with_capacity(player_num);
+    for id in 0..player_num {
+        let role_id = Fr::from(if id == werewolf_id { WEREWOLF } else { VILLAGER });
+        let role_randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng);
+        role_commitment.push(commit_role(&pedersen_param, role_id, &role_randomness));
+
+        private_input.push(MediumPrivateInput::<Fr> {
+            id,
+            role_id,
+            role_randomness,
+            randomness: shared_randomness.clone(),
+        });
+    }
+
+    let circuit = MediumCircuit::<Fr> {
+        private_input,
+        public_input: MediumPublicInput::<Fr> {
+            pedersen_param,
+            elgamal_param,
+            pub_key,
+            player_num,
+            executed_id,
+            role_commitment,
+            state_chain: StateChainParameter::default(),
+        },
+    };
+    (circuit, secret_key)
+}
+
+fn build_public_inputs(circuit: &MediumCircuit<Fr>) -> Vec<Fr> {
+    let ciphertext = circuit.calculate_output();
+    let executed_id = Fr::from(circuit.public_input.executed_id as u64);
+    let ciphertext = [
+        ciphertext.0.x,
+        ciphertext.0.y,
+        ciphertext.1.x,
+        ciphertext.1.y,
+    ];
+
+    let mut inputs = vec![
+        executed_id,
+        circuit.public_input.elgamal_param.generator.x,
+        circuit.public_input.elgamal_param.generator.y,
+        circuit.public_input.pub_key.x,
+        circuit.public_input.pub_key.y,
+    ];
+    inputs.extend(ciphertext);
+
+    // 暗号文の後に role_commitment が id 順に並ぶ
+    let commitments = circuit
+        .public_input
+        .role_commitment
+        .iter()
+        .map(|commitment| (commitment.x, commitment.y))
+        .collect::<Vec<_>>();
+    inputs.extend(commitments.iter().flat_map(|(x, y)| [*x, *y]));
+    inputs.extend(state_chain_public_inputs(
+        &circuit.public_input.state_chain,
+        StateChainTag::Medium,
+        &commitments,
+        &[
+            executed_id,
+            ciphertext[0],
+            ciphertext[1],
+            ciphertext[2],
+            ciphertext[3],
+        ],
+    ));
+    inputs
+}
+
+fn decrypt_output(
+    circuit: &MediumCircuit<Fr>,
+    secret_key: &<ElGamalScheme as AsymmetricEncryptionScheme>::SecretKey,
+) -> <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext {
+    ElGamalScheme::decrypt(
+        &circuit.public_input.elgamal_param,
+        secret_key,
+        &circuit.calculate_output(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn medium_groth16_local_prove_and_verify() {
+    // 5人: 人狼のプレイヤー2が処刑された
+    let (circuit, secret_key) = build_medium_circuit(5, 2, 2);
+    assert_eq!(
+        decrypt_output(&circuit, &secret_key),
+        <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::prime_subgroup_generator()
+    );
+
+    let public_inputs = build_public_inputs(&circuit);
+    assert_eq!(public_inputs.len(), 9 + 2 * 5 + 4);
+
+    let mut rng = test_rng();
+    let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), &mut rng).unwrap();
+    let proof = create_random_proof(circuit, &params, &mut rng).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+
+    // 別のプレイヤーの処刑結果としては検証に失敗する
+    let mut forged_inputs = public_inputs.clone();
+    forged_inputs[0] = Fr::from(3u64);
+    assert!(!verify_proof(&pvk, &proof, &forged_inputs).unwrap());
+}
+
+#[test]
+fn medium_executed_villager_encrypts_zero() {
+    let (circuit, secret_key) = build_medium_circuit(5, 2, 0);
+    assert_eq!(
+        decrypt_output(&circuit, &secret_key),
+        <Fr as ElGamalLocalOrMPC<Fr>>::ElGamalPlaintext::default()
+    );
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn medium_rejects_executed_id_without_submission() {
+    // 処刑されたプレイヤー4の入力が提出されていない
+    let (mut circuit, _) = build_medium_circuit(5, 2, 4);
+    circuit.private_input.retain(|input| input.id != 4);
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    assert!(circuit.generate_constraints(cs).is_err());
+}
+
+#[test]
+fn medium_rejects_role_not_matching_commitment() {
+    // 処刑された村人が、commit した役職と違う人狼の役職IDを主張する
+    let (mut circuit, _) = build_medium_circuit(5, 2, 0);
+    circuit.private_input[0].role_id = Fr::from(WEREWOLF);
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
@@ -0,0 +1,174 @@
+use ark_bn254::{Bn254, Fr};
+use ark_crypto_primitives::CommitmentScheme;
+use ark_ff::{BigInteger, One, PrimeField, UniformRand, Zero};
+use ark_groth16::{
+    create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_std::test_rng;
+use mpc_algebra_wasm::{StateChainParameter, StateChainTag};
+use mpc_circuits::{
+    state_chain_public_inputs, NightResolutionCircuit, NightResolutionPrivateInput,
+    NightResolutionPublicInput,
+};
+use zk_mpc::circuits::LocalOrMPC;
+
+const VILLAGER: u64 = 0;
+const FORTUNE_TELLER: u64 = 1;
+const WEREWOLF: u64 = 2;
+const GUARD: u64 = 3;
+
+fn commit_role(
+    pedersen_param: &<Fr as LocalOrMPC<Fr>>::PedersenParam,
+    role_id: Fr,
+    randomness: &<Fr as LocalOrMPC<Fr>>::PedersenRandomness,
+) -> <Fr as LocalOrMPC<Fr>>::PedersenCommitment {
+    <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::commit(
+        pedersen_param,
+        &role_id.into_repr().to_bytes_le(),
+        randomness,
+    )
+    .unwrap()
+}
+
+// roles[id] の役職で commit し、attacks / guards に含まれる (提出者 id, 対象) の組で入力する
+fn build_night_resolution_circuit(
+    roles: &[u64],
+    attacks: &[(usize, usize)],
+    guards: &[(usize, usize)],
+) -> NightResolutionCircuit<Fr> {
+    let mut rng = test_rng();
+    let player_num = roles.len();
+
+    let pedersen_param =
+        <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::setup(&mut rng).unwrap();
+
+    let one_hot = |pairs: &[(usize, usize)], id: usize| {
+        let mut v = vec![Fr::zero(); player_num];
+        if let Some((_, target)) = pairs.iter().find(|(submitter, _)| *submitter == id) {
+            v[*target] = Fr::one();
+        }
+        v
+    };
+
+    let mut private_input = Vec::with_capacity(player_num);
+    let mut role_commitment = Vec::with_capacity(player_num);
+    for (id, role) in roles.iter().enumerate() {
+        let role_id = Fr::from(*role);
+        let randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng);
+        role_commitment.push(commit_role(&pedersen_param, role_id, &randomness));
+
+        private_input.push(NightResolutionPrivateInput::<Fr> {
+            id,
+            is_attack_target_id: one_hot(attacks, id),
+            is_guard_target_id: one_hot(guards, id),
+            role_id,
+            randomness,
+        });
+    }
+
+    NightResolutionCircuit::<Fr> {
+        private_input,
+        public_input: NightResolutionPublicInput::<Fr> {
+            pedersen_param,
+            player_num,
+            role_commitment,
+            state_chain: StateChainParameter::default(),
+        },
+    }
+}
+
+fn build_public_inputs(circuit: &NightResolutionCircuit<Fr>) -> Vec<Fr> {
+    // 結果の後に role_commitment が id 順に並ぶ
+    let result = circuit.calculate_output();
+    let commitments = circuit
+        .public_input
+        .role_commitment
+        .iter()
+        .map(|commitment| (commitment.x, commitment.y))
+        .collect::<Vec<_>>();
+    let mut inputs = vec![result];
+    inputs.extend(commitments.iter().flat_map(|(x, y)| [*x, *y]));
+    inputs.extend(state_chain_public_inputs(
+        &circuit.public_input.state_chain,
+        StateChainTag::NightResolution,
+        &commitments,
+        &[result],
+    ));
+    inputs
+}
+
+fn is_satisfied(circuit: NightResolutionCircuit<Fr>) -> bool {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    cs.is_satisfied().unwrap()
+}
+
+const ROLES: [u64; 5] = [VILLAGER, FORTUNE_TELLER, GUARD, WEREWOLF, WEREWOLF];
+
+#[test]
+fn night_resolution_groth16_local_prove_and_verify() {
+    // 人狼2人がプレイヤー0を襲撃し、騎士はプレイヤー1を護衛する
+    let circuit = build_night_resolution_circuit(&ROLES, &[(3, 0), (4, 0)], &[(2, 1)]);
+    assert_eq!(circuit.calculate_output(), Fr::from(0u64));
+
+    let public_inputs = build_public_inputs(&circuit);
+    assert_eq!(public_inputs.len(), 1 + 2 * 5 + 4);
+
+    let mut rng = test_rng();
+    let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), &mut rng).unwrap();
+    let proof = create_random_proof(circuit, &params, &mut rng).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+
+    // 「誰も死亡しなかった」と主張する証明としては検証に失敗する
+    let mut forged_inputs = public_inputs.clone();
+    forged_inputs[0] = Fr::from(5u64);
+    assert!(!verify_proof(&pvk, &proof, &forged_inputs).unwrap());
+}
+
+#[test]
+fn night_resolution_guarded_victim_survives() {
+    let circuit = build_night_resolution_circuit(&ROLES, &[(3, 0), (4, 0)], &[(2, 0)]);
+
+    // 誰も死亡しなかった場合は player_num
+    assert_eq!(circuit.calculate_output(), Fr::from(5u64));
+    assert!(is_satisfied(circuit));
+}
+
+#[test]
+fn night_resolution_without_werewolves_kills_nobody() {
+    let roles = [VILLAGER, FORTUNE_TELLER, GUARD, VILLAGER];
+    let circuit = build_night_resolution_circuit(&roles, &[], &[(2, 0)]);
+
+    assert_eq!(circuit.calculate_output(), Fr::from(4u64));
+    assert!(is_satisfied(circuit));
+}
+
+#[test]
+fn night_resolution_rejects_guard_from_non_guard() {
+    // 村人 (プレイヤー0) が騎士のふりをして襲撃先を護衛しようとする
+    let circuit = build_night_resolution_circuit(&ROLES, &[(3, 1), (4, 1)], &[(2, 0), (0, 1)]);
+
+    assert!(!is_satisfied(circuit));
+}
+
+#[test]
+fn night_resolution_rejects_guard_without_target() {
+    let circuit = build_night_resolution_circuit(&ROLES, &[(3, 0), (4, 0)], &[]);
+
+    assert!(!is_satisfied(circuit));
+}
+
+#[test]
+fn night_resolution_rejects_role_not_matching_commitment() {
+    // プレイヤー0は村人として commit しているが、騎士として護衛しようとする
+    let mut circuit = build_night_resolution_circuit(&ROLES, &[(3, 0), (4, 0)], &[(2, 1)]);
+    circuit.private_input[0].role_id = Fr::from(GUARD);
+    circuit.private_input[0].is_guard_target_id[0] = Fr::one();
+    circuit.private_input[2].role_id = Fr::from(VILLAGER);
+    circuit.private_input[2].is_guard_target_id[1] = Fr::zero();
+
+    assert!(!is_satisfied(circuit));
+}
@@ -2,14 +2,18 @@ use std::collections::BTreeMap;
 
 use ark_bn254::{Bn254, Fr};
 use ark_crypto_primitives::CommitmentScheme;
-use ark_ff::PrimeField;
+use ark_ff::{BigInteger, PrimeField, UniformRand};
 use ark_groth16::{create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof};
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
 use ark_std::test_rng;
 use mpc_algebra_wasm::{
-    calc_shuffle_matrix, generate_individual_shuffle_matrix, GroupingParameter, Role as GroupingRole,
+    calc_shuffle_matrix, generate_individual_shuffle_matrix, instance_hash, GroupingParameter,
+    InstanceMode, PoseidonParameters, Role as GroupingRole, StateChainParameter, StateChainTag,
+};
+use mpc_circuits::{
+    state_chain_public_inputs, verifier_public_inputs, RoleAssignmentCircuit,
+    RoleAssignmentPrivateInput, RoleAssignmentPublicInput,
 };
-use mpc_circuits::{RoleAssignmentCircuit, RoleAssignmentPrivateInput, RoleAssignmentPublicInput};
 use zk_mpc::circuits::LocalOrMPC;
 
 fn build_grouping_parameter(num_players: usize, werewolf_count: usize) -> GroupingParameter {
@@ -27,27 +31,50 @@ fn build_grouping_parameter(num_players: usize, werewolf_count: usize) -> Groupi
     GroupingParameter::new(map)
 }
 
+fn commit_fr(
+    pedersen_param: &<Fr as LocalOrMPC<Fr>>::PedersenParam,
+    value: Fr,
+    randomness: &<Fr as LocalOrMPC<Fr>>::PedersenRandomness,
+) -> <Fr as LocalOrMPC<Fr>>::PedersenCommitment {
+    <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::commit(
+        pedersen_param,
+        &value.into_repr().to_bytes_le(),
+        randomness,
+    )
+    .unwrap()
+}
+
 fn build_role_assignment_circuit(num_players: usize, werewolf_count: usize) -> RoleAssignmentCircuit<Fr> {
+    build_role_assignment_circuit_with_grouping(num_players, build_grouping_parameter(num_players, werewolf_count))
+}
+
+fn build_role_assignment_circuit_with_grouping(
+    num_players: usize,
+    grouping_parameter: GroupingParameter,
+) -> RoleAssignmentCircuit<Fr> {
     let mut rng = test_rng();
-    let grouping_parameter = build_grouping_parameter(num_players, werewolf_count);
     let tau_matrix = grouping_parameter.generate_tau_matrix::<Fr>();
     let num_groups = grouping_parameter.get_num_groups();
 
+    let pedersen_param =
+        <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::setup(&mut rng).unwrap();
+
     let private_input = (0..num_players)
         .map(|id| RoleAssignmentPrivateInput::<Fr> {
             id,
             shuffle_matrices: generate_individual_shuffle_matrix::<Fr, _>(num_players, num_groups, &mut rng),
-            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::default(),
+            randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng),
             player_randomness: Fr::from((id + 1) as u64),
+            player_commitment_randomness: <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng),
         })
         .collect::<Vec<_>>();
 
-    let pedersen_param =
-        <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::setup(&mut rng).unwrap();
-    let role_commitment = vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); num_players];
-    let player_commitment = vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); num_players];
+    let player_commitment = private_input
+        .iter()
+        .map(|input| commit_fr(&pedersen_param, input.player_randomness, &input.player_commitment_randomness))
+        .collect::<Vec<_>>();
 
-    RoleAssignmentCircuit::<Fr> {
+    let mut circuit = RoleAssignmentCircuit::<Fr> {
         private_input,
         public_input: RoleAssignmentPublicInput::<Fr> {
             num_players,
@@ -55,17 +82,52 @@ fn build_role_assignment_circuit(num_players: usize, werewolf_count: usize) -> R
             pedersen_param,
             grouping_parameter,
             tau_matrix,
-            role_commitment,
+            role_commitment: Vec::new(),
             player_commitment,
+            state_chain: StateChainParameter::default(),
+            instance_mode: InstanceMode::Full,
         },
-    }
+    };
+
+    // role_commitment[i] は計算された役職IDへのコミットメント
+    circuit.public_input.role_commitment = circuit
+        .calculate_output()
+        .iter()
+        .zip(circuit.private_input.iter())
+        .map(|(role_id, input)| commit_fr(&circuit.public_input.pedersen_param, *role_id, &input.randomness))
+        .collect();
+
+    circuit
 }
 
 fn build_public_inputs(circuit: &RoleAssignmentCircuit<Fr>) -> Vec<Fr> {
-    let tau = &circuit.public_input.tau_matrix;
-    // IMPORTANT: nalgebra matrix iterator is column-major. This must match
-    // FpVar::new_input allocation order in the circuit.
-    tau.iter().copied().collect()
+    // IMPORTANT: must match the input allocation order in the circuit:
+    // player_commitment[i] (x, y) for all players, then role_commitment[i] (x, y),
+    // followed by the alive mask, the day count and the previous and next state digest.
+    let commitments = circuit
+        .public_input
+        .player_commitment
+        .iter()
+        .chain(circuit.public_input.role_commitment.iter())
+        .map(|commitment| (commitment.x, commitment.y))
+        .collect::<Vec<_>>();
+    let mut inputs = commitments
+        .iter()
+        .flat_map(|(x, y)| [*x, *y])
+        .collect::<Vec<_>>();
+    inputs.extend(state_chain_public_inputs(
+        &circuit.public_input.state_chain,
+        StateChainTag::RoleAssignment,
+        &commitments,
+        &[],
+    ));
+    inputs
+}
+
+fn is_satisfied(circuit: RoleAssignmentCircuit<Fr>) -> bool {
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    cs.is_satisfied().unwrap()
 }
 
 fn fr_to_u32(value: Fr) -> u32 {
@@ -84,6 +146,9 @@ fn role_to_role_id(role: GroupingRole) -> u32 {
         GroupingRole::Villager => 0,
         GroupingRole::FortuneTeller => 1,
         GroupingRole::Werewolf => 2,
+        GroupingRole::Guard => 3,
+        GroupingRole::Medium => 4,
+        GroupingRole::Possessed => 5,
     }
 }
 
@@ -115,6 +180,7 @@ fn print_role_assignment_outputs(num_players: usize, werewolf_count: usize) {
 fn prove_and_verify_role_assignment_profile(num_players: usize, werewolf_count: usize) -> bool {
     let circuit = build_role_assignment_circuit(num_players, werewolf_count);
     let public_inputs = build_public_inputs(&circuit);
+    assert_eq!(public_inputs.len(), 4 * num_players + 4);
 
     let mut rng = test_rng();
     let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), &mut rng).unwrap();
@@ -140,6 +206,35 @@ fn role_assignment_groth16_local_profiles_prove_and_verify() {
     );
 }
 
+#[test]
+fn role_assignment_hashed_instance_prove_and_verify() {
+    let mut circuit = build_role_assignment_circuit(5, 1);
+    circuit.public_input.instance_mode = InstanceMode::Hashed;
+    let instance = build_public_inputs(&circuit);
+    let public_inputs = verifier_public_inputs(instance.clone(), InstanceMode::Hashed);
+    assert_eq!(
+        public_inputs,
+        vec![instance_hash(&PoseidonParameters::new(), &instance)]
+    );
+
+    // 公開入力は instance hash だけ (+ 定数 1)
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.clone().generate_constraints(cs.clone()).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+    assert_eq!(cs.num_instance_variables(), 2);
+
+    let mut rng = test_rng();
+    let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), &mut rng).unwrap();
+    let proof = create_random_proof(circuit, &params, &mut rng).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+    assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+
+    let mut tampered = instance;
+    tampered[0] += Fr::from(1u32);
+    let tampered_inputs = verifier_public_inputs(tampered, InstanceMode::Hashed);
+    assert!(!verify_proof(&pvk, &proof, &tampered_inputs).unwrap());
+}
+
 #[test]
 fn role_assignment_local_output_n5w2_n6w2() {
     print_role_assignment_outputs(5, 2);
@@ -172,6 +267,74 @@ fn role_assignment_local_constraint_satisfaction_diagnostics() {
     }
 }
 
+#[test]
+fn role_assignment_assigns_guard_role_id() {
+    let num_players = 6;
+    let mut map = BTreeMap::new();
+    map.insert(GroupingRole::FortuneTeller, (1, false));
+    map.insert(GroupingRole::Werewolf, (1, false));
+    map.insert(GroupingRole::Villager, (3, false));
+    map.insert(GroupingRole::Guard, (1, false));
+    let grouping_parameter = GroupingParameter::new(map);
+
+    let circuit = build_role_assignment_circuit_with_grouping(num_players, grouping_parameter.clone());
+    assert!(is_satisfied(circuit.clone()));
+
+    let shuffle_matrices = circuit
+        .private_input
+        .iter()
+        .map(|input| input.shuffle_matrices.clone())
+        .collect::<Vec<_>>();
+    let role_ids = circuit.calculate_output();
+    for player_id in 0..num_players {
+        let (role, _role_val, _fellow_ids) =
+            calc_shuffle_matrix(&grouping_parameter, &shuffle_matrices, player_id).unwrap();
+        assert_eq!(fr_to_u32(role_ids[player_id]), role_to_role_id(role));
+    }
+    assert_eq!(
+        role_ids
+            .iter()
+            .filter(|role_id| fr_to_u32(**role_id) == role_to_role_id(GroupingRole::Guard))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn role_assignment_rejects_shuffle_moving_group_slots() {
+    // n5-w1 は 5 グループ。グループ部分 (index >= n) の単位行列を入れ替えた置換行列は拒否される
+    let (num_players, werewolf_count) = (5, 1);
+    let mut circuit = build_role_assignment_circuit(num_players, werewolf_count);
+    assert!(is_satisfied(circuit.clone()));
+
+    let size = circuit.private_input[0].shuffle_matrices.nrows();
+    circuit.private_input[0].shuffle_matrices.swap_rows(num_players, size - 1);
+
+    assert!(!is_satisfied(circuit));
+}
+
+#[test]
+fn role_assignment_rejects_role_commitment_to_other_role() {
+    let mut circuit = build_role_assignment_circuit(5, 1);
+    let role_ids = circuit.calculate_output();
+    // プレイヤー0の役職コミットメントを別の役職IDへのコミットメントに差し替える
+    circuit.public_input.role_commitment[0] = commit_fr(
+        &circuit.public_input.pedersen_param,
+        role_ids[0] + Fr::from(1u32),
+        &circuit.private_input[0].randomness,
+    );
+
+    assert!(!is_satisfied(circuit));
+}
+
+#[test]
+fn role_assignment_rejects_player_randomness_not_matching_commitment() {
+    let mut circuit = build_role_assignment_circuit(5, 1);
+    circuit.private_input[3].player_randomness += Fr::from(1u32);
+
+    assert!(!is_satisfied(circuit));
+}
+
 #[test]
 fn role_assignment_local_calculate_output_matches_calc_shuffle_matrix_profiles() {
     let profiles = [(5, 2), (6, 2), (7, 2), (7, 3), (8, 3)];
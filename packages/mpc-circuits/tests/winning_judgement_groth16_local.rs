@@ -0,0 +1,355 @@
+use ark_bn254::{Bn254, Fr};
+use ark_crypto_primitives::CommitmentScheme;
+use ark_ff::{BigInteger, One, PrimeField, UniformRand, Zero};
+use ark_groth16::{
+    create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_std::test_rng;
+use mpc_algebra_wasm::{StateChainParameter, StateChainTag, WinConditionParameter};
+use mpc_circuits::{
+    state_chain_public_inputs, WinningJudgementCircuit, WinningJudgementPrivateInput,
+    WinningJudgementPublicInput,
+};
+use zk_mpc::circuits::LocalOrMPC;
+
+// 全テストで 5 人ゲームを想定し、列挙されないプレイヤーは死亡者として扱う
+const PLAYER_NUM: usize = 5;
+
+#[derive(Clone, Copy)]
+enum Alive {
+    Villager,
+    Werewolf,
+    Possessed,
+    Fox,
+}
+
+impl Alive {
+    // 妖狐にはまだ役職IDがないので村人と同じ 0 で commit する
+    fn role_id(self) -> Fr {
+        match self {
+            Alive::Villager | Alive::Fox => Fr::from(0u32),
+            Alive::Werewolf => Fr::from(2u32),
+            Alive::Possessed => Fr::from(5u32),
+        }
+    }
+}
+
+fn bit(flag: bool) -> Fr {
+    if flag {
+        Fr::one()
+    } else {
+        Fr::zero()
+    }
+}
+
+// 生存者 (id, 役職) から入力を作る
+fn build_winning_judgement_circuit(alive: &[(usize, Alive)]) -> WinningJudgementCircuit<Fr> {
+    build_winning_judgement_circuit_with_rules(alive, WinConditionParameter::default(), 1)
+}
+
+fn build_winning_judgement_circuit_with_rules(
+    alive: &[(usize, Alive)],
+    win_condition: WinConditionParameter,
+    day_count: u32,
+) -> WinningJudgementCircuit<Fr> {
+    let mut rng = test_rng();
+    let pedersen_param =
+        <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::setup(&mut rng).unwrap();
+
+    // 死亡者は村人として commit しておく。死亡者の行は開示されない
+    let mut private_input = Vec::with_capacity(PLAYER_NUM);
+    let mut role_commitment = Vec::with_capacity(PLAYER_NUM);
+    for id in 0..PLAYER_NUM {
+        let role = alive
+            .iter()
+            .find(|(alive_id, _)| *alive_id == id)
+            .map(|(_, role)| *role);
+        let role_id = role.map_or(Fr::from(0u32), Alive::role_id);
+        let randomness = <Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng);
+        role_commitment.push(
+            <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::commit(
+                &pedersen_param,
+                &role_id.into_repr().to_bytes_le(),
+                &randomness,
+            )
+            .unwrap(),
+        );
+
+        private_input.push(WinningJudgementPrivateInput::<Fr> {
+            id,
+            am_werewolf: bit(matches!(role, Some(Alive::Werewolf))),
+            am_werewolf_team: bit(matches!(role, Some(Alive::Werewolf | Alive::Possessed))),
+            am_fox: bit(matches!(role, Some(Alive::Fox))),
+            role_id,
+            randomness,
+        });
+    }
+    let alive_mask = alive.iter().fold(0u32, |mask, (id, _)| mask | (1 << id));
+
+    WinningJudgementCircuit {
+        private_input,
+        public_input: WinningJudgementPublicInput::<Fr> {
+            pedersen_param,
+            player_num: PLAYER_NUM,
+            win_condition,
+            role_commitment,
+            state_chain: StateChainParameter {
+                alive_mask,
+                day_count,
+                ..Default::default()
+            },
+        },
+    }
+}
+
+fn build_public_inputs(circuit: &WinningJudgementCircuit<Fr>) -> Vec<Fr> {
+    let win_condition = circuit.public_input.win_condition;
+    let state_chain = &circuit.public_input.state_chain;
+    let results = [circuit.calculate_output(), circuit.calculate_winner_mask()];
+    let commitments = circuit
+        .public_input
+        .role_commitment
+        .iter()
+        .map(|c| (c.x, c.y))
+        .collect::<Vec<_>>();
+    let mut inputs = vec![
+        Fr::from(state_chain.alive_mask.count_ones()),
+        Fr::from(state_chain.alive_mask),
+        Fr::from(win_condition.werewolf_win_on_majority as u32),
+        Fr::from(win_condition.day_limit),
+        Fr::from(state_chain.day_count),
+    ];
+    inputs.extend(results);
+    inputs.extend(commitments.iter().flat_map(|(x, y)| [*x, *y]));
+    inputs.extend(state_chain_public_inputs(
+        state_chain,
+        StateChainTag::WinningJudgement,
+        &commitments,
+        &results,
+    ));
+    inputs
+}
+
+#[test]
+fn winning_judgement_groth16_local_possessed_wins_with_werewolf() {
+    // 生存者: 人狼0, 狂人2, 村人4。狂人は人間として数えるので人狼1 < 人間2 で続行
+    let circuit = build_winning_judgement_circuit(&[
+        (0, Alive::Werewolf),
+        (2, Alive::Possessed),
+        (4, Alive::Villager),
+    ]);
+    assert_eq!(circuit.calculate_output(), Fr::from(3u32));
+    assert_eq!(circuit.calculate_winner_mask(), Fr::zero());
+
+    // 村人4が死亡すると人狼1 >= 人間1 で人狼陣営の勝利。狂人も勝者になる
+    let circuit = build_winning_judgement_circuit(&[(0, Alive::Werewolf), (2, Alive::Possessed)]);
+    assert_eq!(circuit.calculate_output(), Fr::from(1u32));
+    assert_eq!(circuit.calculate_winner_mask(), Fr::from(0b101u32));
+
+    let public_inputs = build_public_inputs(&circuit);
+
+    let mut rng = test_rng();
+    let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), &mut rng).unwrap();
+    let proof = create_random_proof(circuit, &params, &mut rng).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+
+    assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+
+    // 狂人を勝者から外した winner_mask は検証に失敗する
+    let mut forged_inputs = public_inputs.clone();
+    forged_inputs[6] = Fr::from(0b001u32);
+    assert!(!verify_proof(&pvk, &proof, &forged_inputs).unwrap());
+}
+
+#[test]
+fn winning_judgement_villager_win_excludes_possessed() {
+    // 人狼が全滅: 狂人1は生存していても敗者
+    let circuit = build_winning_judgement_circuit(&[
+        (1, Alive::Possessed),
+        (3, Alive::Villager),
+        (4, Alive::Villager),
+    ]);
+    assert_eq!(circuit.calculate_output(), Fr::from(2u32));
+    assert_eq!(circuit.calculate_winner_mask(), Fr::from(0b11000u32));
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn winning_judgement_rejects_werewolf_outside_werewolf_team() {
+    let mut circuit = build_winning_judgement_circuit(&[
+        (0, Alive::Werewolf),
+        (1, Alive::Villager),
+        (2, Alive::Villager),
+    ]);
+    circuit.private_input[0].am_werewolf_team = Fr::zero();
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn winning_judgement_majority_rule_requires_werewolf_outnumbering() {
+    let alive = [(0, Alive::Werewolf), (1, Alive::Villager)];
+
+    // 同数ルールでは人狼1 >= 人間1 で人狼の勝利
+    let circuit = build_winning_judgement_circuit(&alive);
+    assert_eq!(circuit.calculate_output(), Fr::from(1u32));
+
+    // 過半数ルールでは人狼1 > 人間1 を満たさないので続行
+    let majority = WinConditionParameter {
+        werewolf_win_on_majority: true,
+        day_limit: 0,
+    };
+    let circuit = build_winning_judgement_circuit_with_rules(&alive, majority, 3);
+    assert_eq!(circuit.calculate_output(), Fr::from(3u32));
+    assert_eq!(circuit.calculate_winner_mask(), Fr::zero());
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.clone().generate_constraints(cs.clone()).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+
+    // 公開入力の majority を同数ルールに書き換えた証明は検証に失敗する
+    let public_inputs = build_public_inputs(&circuit);
+    let mut rng = test_rng();
+    let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), &mut rng).unwrap();
+    let proof = create_random_proof(circuit, &params, &mut rng).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+    assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+
+    let mut forged_inputs = public_inputs.clone();
+    forged_inputs[2] = Fr::zero();
+    assert!(!verify_proof(&pvk, &proof, &forged_inputs).unwrap());
+}
+
+#[test]
+fn winning_judgement_day_limit_gives_villagers_the_win() {
+    let alive = [
+        (0, Alive::Werewolf),
+        (1, Alive::Possessed),
+        (2, Alive::Villager),
+        (3, Alive::Villager),
+        (4, Alive::Villager),
+    ];
+    let day_limit = WinConditionParameter {
+        werewolf_win_on_majority: false,
+        day_limit: 4,
+    };
+
+    // 上限前は続行
+    let circuit = build_winning_judgement_circuit_with_rules(&alive, day_limit, 3);
+    assert_eq!(circuit.calculate_output(), Fr::from(3u32));
+
+    // 上限に達すると村人陣営 (狂人を除く) の勝利
+    let circuit = build_winning_judgement_circuit_with_rules(&alive, day_limit, 4);
+    assert_eq!(circuit.calculate_output(), Fr::from(5u32));
+    assert_eq!(circuit.calculate_winner_mask(), Fr::from(0b11100u32));
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn winning_judgement_surviving_fox_steals_the_win() {
+    // 人狼全滅時に妖狐が生存していれば妖狐だけが勝者
+    let circuit = build_winning_judgement_circuit(&[
+        (1, Alive::Fox),
+        (2, Alive::Villager),
+        (3, Alive::Possessed),
+    ]);
+    assert_eq!(circuit.calculate_output(), Fr::from(4u32));
+    assert_eq!(circuit.calculate_winner_mask(), Fr::from(0b10u32));
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+
+    // 決着がつかない間は妖狐がいても続行
+    let circuit = build_winning_judgement_circuit(&[
+        (0, Alive::Werewolf),
+        (1, Alive::Fox),
+        (2, Alive::Villager),
+    ]);
+    assert_eq!(circuit.calculate_output(), Fr::from(3u32));
+}
+
+#[test]
+fn winning_judgement_rejects_fox_in_werewolf_team() {
+    let mut circuit = build_winning_judgement_circuit(&[
+        (0, Alive::Werewolf),
+        (1, Alive::Fox),
+        (2, Alive::Villager),
+    ]);
+    circuit.private_input[1].am_werewolf_team = Fr::one();
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn winning_judgement_rejects_roles_on_dead_rows() {
+    // 死亡者3の行に人狼フラグを立てて人狼の数を水増しする
+    let mut circuit = build_winning_judgement_circuit(&[
+        (0, Alive::Werewolf),
+        (1, Alive::Villager),
+        (2, Alive::Villager),
+    ]);
+    circuit.private_input[3].am_werewolf = Fr::one();
+    circuit.private_input[3].am_werewolf_team = Fr::one();
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn winning_judgement_rejects_non_boolean_werewolf_flag() {
+    // 人狼0の am_werewolf を 2 にして人狼の数を水増しする
+    let mut circuit = build_winning_judgement_circuit(&[
+        (0, Alive::Werewolf),
+        (1, Alive::Villager),
+        (2, Alive::Villager),
+    ]);
+    circuit.private_input[0].am_werewolf = Fr::from(2u32);
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn winning_judgement_rejects_flags_that_differ_from_role_commitment() {
+    // 村人1が人狼を名乗る。フラグ自体は 0/1 の組として正しいが role_commitment と一致しない
+    let mut circuit = build_winning_judgement_circuit(&[
+        (0, Alive::Werewolf),
+        (1, Alive::Villager),
+        (2, Alive::Villager),
+    ]);
+    circuit.private_input[1].am_werewolf = Fr::one();
+    circuit.private_input[1].am_werewolf_team = Fr::one();
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
+
+#[test]
+fn winning_judgement_rejects_more_werewolves_than_alive_players() {
+    // 生存者は人狼0だけだが、死亡者の行にも人狼フラグを立てる。計算は panic せず不充足になる
+    let mut circuit = build_winning_judgement_circuit(&[(0, Alive::Werewolf)]);
+    for input in circuit.private_input.iter_mut().skip(1).take(2) {
+        input.am_werewolf = Fr::one();
+        input.am_werewolf_team = Fr::one();
+    }
+    circuit.calculate_output();
+
+    let cs = ConstraintSystem::<Fr>::new_ref();
+    circuit.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
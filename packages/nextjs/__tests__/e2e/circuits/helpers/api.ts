@@ -612,15 +612,10 @@ export class CircuitTestClient {
   }
 
   /**
-   * 夜アクション（襲撃）を送信
-   * 本番環境では NightActionModal が行う処理
+   * Attackリクエストを送信
+   * 本番環境では useBackgroundNightAction フックが行う処理
    */
-  async submitNightAttack(
-    roomId: string,
-    attackerPlayerId: string,
-    targetPlayerId: string,
-    authToken?: string,
-  ): Promise<any> {
+  async submitAttack(roomId: string, attackInput: any, playerCount: number, authToken?: string): Promise<any> {
     const headers: Record<string, string> = {
       "Content-Type": "application/json",
     };
@@ -629,22 +624,27 @@ export class CircuitTestClient {
       headers["Authorization"] = `Bearer ${authToken}`;
     }
 
-    const response = await fetch(`${this.baseUrl}/api/game/${roomId}/actions/night-action`, {
+    // MPCEncryption.encryptAttack() の結果を使用
+    const encryptedAttack = await MPCEncryption.encryptAttack(attackInput);
+
+    const requestBody = {
+      proof_type: "Attack",
+      data: {
+        user_id: String(attackInput.privateInput.id),
+        prover_count: playerCount,
+        encrypted_data: encryptedAttack,
+      },
+    };
+
+    const response = await fetch(`${this.baseUrl}/api/game/${roomId}/proof`, {
       method: "POST",
       headers,
-      body: JSON.stringify({
-        player_id: attackerPlayerId,
-        action: {
-          Attack: {
-            target_id: targetPlayerId,
-          },
-        },
-      }),
+      body: JSON.stringify(requestBody),
     });
 
     if (!response.ok) {
       const errorText = await response.text();
-      throw new Error(`Night attack submission failed (${response.status}): ${errorText}`);
+      throw new Error(`Attack submission failed (${response.status}): ${errorText}`);
     }
 
     return await response.json();
@@ -12,6 +12,7 @@ import type {
   //   CryptoParams,
   //   ElGamalKeyPair,
   AnonymousVotingInput,
+  AttackInput,
   DivinationInput,
   ElGamalKeygenOutput,
   ElGamalParam,
@@ -66,7 +67,7 @@ export class CryptoHelper {
    * 回路タイプに応じた暗号化処理
    */
   static async encryptForCircuit(
-    circuitType: "RoleAssignment" | "KeyPublicize" | "Divination" | "AnonymousVoting" | "WinningJudgement",
+    circuitType: "RoleAssignment" | "KeyPublicize" | "Divination" | "Attack" | "AnonymousVoting" | "WinningJudgement",
     input: any,
   ): Promise<any> {
     switch (circuitType) {
@@ -76,6 +77,8 @@ export class CryptoHelper {
         return await MPCEncryption.encryptKeyPublicize(input as KeyPublicizeInput);
       case "Divination":
         return await MPCEncryption.encryptDivination(input as DivinationInput);
+      case "Attack":
+        return await MPCEncryption.encryptAttack(input as AttackInput);
       case "AnonymousVoting":
         return await MPCEncryption.encryptAnonymousVoting(input as AnonymousVotingInput);
       case "WinningJudgement":
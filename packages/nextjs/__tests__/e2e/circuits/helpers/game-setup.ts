@@ -334,16 +334,39 @@ export class GameSetupHelper {
   }
 
   /**
-   * 夜アクション（襲撃）を送信
+   * 生存プレイヤー全員のAttackリクエストを送信
+   * 本番環境では useBackgroundNightAction フックが占いと同時に実行
    */
-  static async submitNightAttack(roomId: string, attacker: TestPlayer, targetPlayerId: string): Promise<void> {
-    console.log(
-      `🌙 Submitting night attack (attacker: ${attacker.name}(${attacker.id}) -> target: ${targetPlayerId})...`,
-    );
+  static async submitAttackRequests(
+    roomId: string,
+    players: TestPlayer[],
+    gameInfo: any,
+    targetPlayerId: string,
+  ): Promise<void> {
+    console.log(`🌙 Submitting Attack requests for all alive players (target: ${targetPlayerId})...`);
 
+    const GameInputGenerator = await import("~~/services/gameInputGenerator");
     const apiClient = new CircuitTestClient(roomId);
-    await apiClient.submitNightAttack(roomId, attacker.id, targetPlayerId, attacker.token);
-    console.log(`✅ Night attack submitted\n`);
+    const batchIds = new Set<string>();
+    const alivePlayerIds = new Set(gameInfo.players.filter((p: any) => !p.is_dead).map((p: any) => p.id));
+    const alivePlayers = players.filter(player => alivePlayerIds.has(player.id));
+
+    for (const player of alivePlayers) {
+      console.log(`   🔄 Generating Attack input for ${player.name}...`);
+      // 人狼以外のプレイヤーの入力では targetPlayerId は無視される
+      const attackInput = await GameInputGenerator.generateAttackInput(roomId, player.name, gameInfo, targetPlayerId);
+
+      console.log(`   📤 Submitting Attack request for ${player.name}...`);
+      const response = await apiClient.submitAttack(roomId, attackInput, alivePlayers.length, player.token);
+      const batchId = this.extractBatchId(response);
+      if (batchId) {
+        batchIds.add(batchId);
+      }
+      console.log(`   ✅ ${player.name} Attack request sent`);
+    }
+
+    await this.waitForProofJobs(apiClient, roomId, batchIds, "Attack");
+    console.log(`✅ All Attack requests submitted\n`);
   }
 
   /**
@@ -140,21 +140,14 @@ async function runFullScenarioFlow(scenario: FullScenario): Promise<void> {
     expect(seerPlayerId).toBeDefined();
 
     const werewolfIdSet = new Set(werewolves.map(info => info.playerId));
-    const attackerPlayerId = werewolves[0]?.playerId ?? players[0].id;
-    const attacker = players.find(player => player.id === attackerPlayerId) ?? players[0];
     const targetPlayer =
       gameState.players.find(
         player => !player.is_dead && player.id !== seerPlayerId && !werewolfIdSet.has(player.id),
       ) ??
-      gameState.players.find(player => !player.is_dead && player.id !== attacker.id) ??
+      gameState.players.find(player => !player.is_dead && !werewolfIdSet.has(player.id)) ??
       gameState.players[0];
 
     nightAttackTargetId = targetPlayer.id;
-    await GameSetupHelper.submitNightAttack(roomId, attacker, nightAttackTargetId);
-
-    const stateAfterAttack = await global.apiClient.getGameState(roomId);
-    const attackedPlayerAfterAttack = stateAfterAttack.players.find(player => player.id === nightAttackTargetId);
-    expect(attackedPlayerAfterAttack?.is_dead).toBe(false);
   }
 
   gameState = await GameSetupHelper.ensureGamePhase(roomId, "DivinationProcessing");
@@ -174,6 +167,13 @@ async function runFullScenarioFlow(scenario: FullScenario): Promise<void> {
     ? gameState.players.map(() => nightAttackTargetId as string)
     : buildDivinationTargets(gameState, scenario.divinationStrategy);
   const isDummyFlags = players.map(player => (seerPlayerId ? player.id !== seerPlayerId : player.id !== players[0].id));
+  if (nightAttackTargetId) {
+    // 襲撃先は占い処理の完了時に適用されるため、占いより先に送信する
+    await GameSetupHelper.submitAttackRequests(roomId, players, gameState, nightAttackTargetId);
+    const stateAfterAttack = await global.apiClient.getGameState(roomId);
+    const attackedPlayerAfterAttack = stateAfterAttack.players.find(player => player.id === nightAttackTargetId);
+    expect(attackedPlayerAfterAttack?.is_dead).toBe(false);
+  }
   await GameSetupHelper.submitDivinationRequests(roomId, players, gameState, divinationTargets, isDummyFlags);
 
   if (nightAttackTargetId) {
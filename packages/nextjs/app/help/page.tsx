@@ -25,6 +25,11 @@ const roleCards: InfoCard[] = [
     description: "Coordinate with your team, blend in during discussion, and eliminate villagers at night.",
     icon: <Skull className="h-5 w-5" />,
   },
+  {
+    title: "Guard",
+    description: "During night, choose one player to protect. If the werewolves attack that player, nobody dies.",
+    icon: <Shield className="h-5 w-5" />,
+  },
 ];
 
 const phaseCards: InfoCard[] = [
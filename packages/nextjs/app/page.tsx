@@ -53,6 +53,7 @@ type RoleConfig = {
   Seer: number;
   Werewolf: number;
   Villager: number;
+  Guard: number;
 };
 
 type TimeConfig = {
@@ -69,6 +70,7 @@ const Home = () => {
   const [maxPlayers, setMaxPlayers] = useState(8);
   const [seerCount, setSeerCount] = useState(1);
   const [werewolfCount, setWerewolfCount] = useState(2);
+  const [guardCount, setGuardCount] = useState(0);
   const [dayPhase, setDayPhase] = useState(300);
   const [nightPhase, setNightPhase] = useState(120);
   const [votingPhase, setVotingPhase] = useState(90);
@@ -97,14 +99,15 @@ const Home = () => {
     setWerewolfCount(defaultWerewolf);
   }, [maxPlayers]);
 
-  const villagerCount = maxPlayers - seerCount - werewolfCount;
+  const villagerCount = maxPlayers - seerCount - werewolfCount - guardCount;
   const roleConfig: RoleConfig = useMemo(
     () => ({
       Seer: seerCount,
       Werewolf: werewolfCount,
       Villager: Math.max(0, villagerCount),
+      Guard: guardCount,
     }),
-    [seerCount, werewolfCount, villagerCount],
+    [seerCount, werewolfCount, villagerCount, guardCount],
   );
   const timeConfig: TimeConfig = useMemo(
     () => ({
@@ -124,6 +127,7 @@ const Home = () => {
     setMaxPlayers(8);
     setSeerCount(1);
     setWerewolfCount(2);
+    setGuardCount(0);
     setDayPhase(300);
     setNightPhase(120);
     setVotingPhase(90);
@@ -304,7 +308,7 @@ const Home = () => {
                   />
                 </div>
 
-                <div className="grid grid-cols-2 gap-3">
+                <div className="grid grid-cols-3 gap-3">
                   <div>
                     <label className="block text-sm font-medium text-indigo-900 mb-2">Seer</label>
                     <input
@@ -327,6 +331,17 @@ const Home = () => {
                       className="w-full px-3 py-2 border border-indigo-200 rounded-lg"
                     />
                   </div>
+                  <div>
+                    <label className="block text-sm font-medium text-indigo-900 mb-2">Guard</label>
+                    <input
+                      type="number"
+                      min={0}
+                      max={1}
+                      value={guardCount}
+                      onChange={e => setGuardCount(Math.max(0, Math.min(1, Number(e.target.value))))}
+                      className="w-full px-3 py-2 border border-indigo-200 rounded-lg"
+                    />
+                  </div>
                 </div>
 
                 <div className="grid grid-cols-3 gap-3">
@@ -382,6 +397,10 @@ const Home = () => {
                     <span>Werewolf</span>
                     <span className="font-semibold">{roleConfig.Werewolf}</span>
                   </div>
+                  <div className="flex justify-between">
+                    <span>Guard</span>
+                    <span className="font-semibold">{roleConfig.Guard}</span>
+                  </div>
                   <div className="flex justify-between">
                     <span>Villager</span>
                     <span className="font-semibold">{roleConfig.Villager}</span>
@@ -469,7 +469,12 @@ export default function RoomPage({ params }: { params: { id: string } }) {
 
                     {gameInfo.result !== "InProgress" && (
                       <span className="flex items-center gap-2 text-green-600 bg-green-50 px-3 py-1 rounded-full text-sm">
-                        Game Result: {gameInfo.result === "VillagerWin" ? "Villagers Win" : "Werewolves Win"}
+                        Game Result:{" "}
+                        {gameInfo.result === "VillagerWin"
+                          ? "Villagers Win"
+                          : gameInfo.result === "FoxWin"
+                            ? "Fox Wins"
+                            : "Werewolves Win"}
                       </span>
                     )}
 
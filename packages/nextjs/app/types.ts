@@ -23,4 +23,4 @@ export type Player = {
   role: Role;
 };
 
-export type Role = "Villager" | "Werewolf" | "Seer" | null;
+export type Role = "Villager" | "Werewolf" | "Seer" | "Medium" | "Possessed" | "Guard" | null;
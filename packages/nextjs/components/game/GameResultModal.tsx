@@ -5,7 +5,11 @@ export const GameResultModal = ({ result, onClose }: GameResultModalProps) => {
     <div className="fixed inset-0 bg-black/50 flex items-center justify-center z-50">
       <div className="bg-white rounded-lg p-8 max-w-lg w-full mx-4 text-center">
         <h2 className="text-3xl font-bold mb-4 text-indigo-900">
-          {result === "VillagerWin" ? "Villagers team Win!" : "Werewolf team Win!"}
+          {result === "VillagerWin"
+            ? "Villagers team Win!"
+            : result === "FoxWin"
+              ? "Fox Win!"
+              : "Werewolf team Win!"}
         </h2>
         <button
           onClick={onClose}
@@ -43,33 +43,16 @@ const NightActionModal: React.FC<NightActionModalProps> = ({
       }
       // 人狼の場合は襲撃処理を行う
       else if (role === "Werewolf") {
-        console.log("Executing werewolf attack:", selectedPlayer);
-
-        // サーバーに襲撃リクエストを送信
-        const response = await fetch(
-          `${process.env.NEXT_PUBLIC_API_URL || "http://localhost:8080/api"}/game/${roomId}/actions/night-action`,
-          {
-            method: "POST",
-            headers: {
-              "Content-Type": "application/json",
-            },
-            body: JSON.stringify({
-              player_id: myId,
-              action: {
-                Attack: {
-                  target_id: selectedPlayer,
-                },
-              },
-            }),
-          },
-        );
-
-        if (!response.ok) {
-          const errorText = await response.text();
-          throw new Error(`Failed to submit attack: ${errorText}`);
-        }
-
-        console.log("Werewolf attack submitted successfully");
+        // 襲撃先は平文で送信せず、遷移時に全員同時に Attack の証明リクエストとして送信する。
+        const dayCount = gameInfo.day_count ?? 0;
+        localStorage.setItem(`pending_attack_target_${roomId}_${dayCount}`, selectedPlayer);
+        console.log(`Attack target saved for synchronized submission: room=${roomId}, day=${dayCount}`);
+      }
+      // 騎士の場合は護衛先を保存し、遷移時に NightResolution の証明リクエストとして送信する
+      else if (role === "Guard") {
+        const dayCount = gameInfo.day_count ?? 0;
+        localStorage.setItem(`pending_guard_target_${roomId}_${dayCount}`, selectedPlayer);
+        console.log(`Guard target saved for synchronized submission: room=${roomId}, day=${dayCount}`);
       }
 
       // 親コンポーネントのonSubmit関数を呼び出す
@@ -99,7 +82,13 @@ const NightActionModal: React.FC<NightActionModalProps> = ({
     <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
       <div className="bg-white rounded-lg p-6 w-96 shadow-xl">
         <h2 className="text-xl font-bold mb-4 text-indigo-900">
-          {role === "Seer" ? "Select a target to divine" : role === "Werewolf" ? "Select a target to attack" : ""}
+          {role === "Seer"
+            ? "Select a target to divine"
+            : role === "Werewolf"
+              ? "Select a target to attack"
+              : role === "Guard"
+                ? "Select a target to guard"
+                : ""}
         </h2>
         {role === "Werewolf" && werewolfTeammateNames.length > 0 && (
           <p className="mb-3 text-sm text-red-700 bg-red-50 border border-red-100 rounded px-3 py-2">
@@ -8,6 +8,12 @@ import { getPrivateGameInfo } from "~~/utils/privateGameInfoUtils";
 const pendingDivinationTargetKey = (roomId: string, dayCount: number): string =>
   `pending_divination_target_${roomId}_${dayCount}`;
 
+const pendingAttackTargetKey = (roomId: string, dayCount: number): string =>
+  `pending_attack_target_${roomId}_${dayCount}`;
+
+const pendingGuardTargetKey = (roomId: string, dayCount: number): string =>
+  `pending_guard_target_${roomId}_${dayCount}`;
+
 const divinationTargetIdByDayKey = (roomId: string, dayCount: number): string =>
   `divination_target_${roomId}_${dayCount}`;
 
@@ -157,6 +163,83 @@ export const useBackgroundNightAction = () => {
         }
 
         console.log("Synchronized divination request sent successfully");
+
+        // 襲撃も占いと同様に生存者全員が同時に送信し、誰が人狼かを隠す
+        const isWerewolf = privateGameInfo?.playerRole === "Werewolf";
+        const werewolfTeammateIds = new Set(privateGameInfo?.werewolfTeammateIds ?? []);
+        const pendingAttackTargetId = localStorage.getItem(pendingAttackTargetKey(roomId, dayCount));
+        // 襲撃先を選ばなかった人狼は、自分と仲間以外の最初の生存プレイヤーを襲撃する
+        const attackTargetId = isWerewolf
+          ? pendingAttackTargetId ||
+            currentPlayers.find(p => !p.is_dead && p.id !== myId && !werewolfTeammateIds.has(p.id))?.id ||
+            null
+          : null;
+
+        // 騎士がいる村では襲撃と護衛を NightResolution でまとめて解決する
+        const hasGuard = (gameInfoWithDivinationKey.grouping_parameter?.Guard?.[0] ?? 0) > 0;
+        const isGuard = privateGameInfo?.playerRole === "Guard";
+        const pendingGuardTargetId = localStorage.getItem(pendingGuardTargetKey(roomId, dayCount));
+        // 護衛先を選ばなかった騎士は、自分以外の最初の生存プレイヤーを護衛する
+        const guardTargetId = isGuard
+          ? pendingGuardTargetId || currentPlayers.find(p => !p.is_dead && p.id !== myId)?.id || null
+          : null;
+
+        let attackProofType: "Attack" | "NightResolution";
+        let attackUserId: number;
+        let encryptedAttack: string;
+        if (hasGuard) {
+          const nightResolutionData = await GameInput.generateNightResolutionInput(
+            roomId,
+            username,
+            gameInfoWithDivinationKey,
+            attackTargetId,
+            guardTargetId,
+          );
+          attackProofType = "NightResolution";
+          attackUserId = nightResolutionData.privateInput.id;
+          encryptedAttack = await MPCEncryption.encryptNightResolution(nightResolutionData);
+        } else {
+          const attackData = await GameInput.generateAttackInput(
+            roomId,
+            username,
+            gameInfoWithDivinationKey,
+            attackTargetId,
+          );
+          attackProofType = "Attack";
+          attackUserId = attackData.privateInput.id;
+          encryptedAttack = await MPCEncryption.encryptAttack(attackData);
+        }
+
+        console.log(
+          `Sending synchronized ${attackProofType} request to server (alive players: ${alivePlayerCount})`,
+        );
+        const attackResponse = await fetch(
+          `${process.env.NEXT_PUBLIC_API_URL || "http://localhost:8080/api"}/game/${roomId}/proof`,
+          {
+            method: "POST",
+            headers: {
+              "Content-Type": "application/json",
+            },
+            body: JSON.stringify({
+              proof_type: attackProofType,
+              data: {
+                user_id: String(attackUserId),
+                prover_count: alivePlayerCount,
+                encrypted_data: encryptedAttack,
+              },
+            }),
+          },
+        );
+
+        if (!attackResponse.ok) {
+          const errorText = await attackResponse.text();
+          console.error("Server response:", errorText);
+          throw new Error(`Failed to send attack request: ${attackResponse.status} ${errorText}`);
+        }
+
+        localStorage.removeItem(pendingAttackTargetKey(roomId, dayCount));
+        localStorage.removeItem(pendingGuardTargetKey(roomId, dayCount));
+        console.log("Synchronized attack request sent successfully");
       } catch (error) {
         console.error("Background night action error:", error);
         throw error; // エラーを再スロー
@@ -1,4 +1,5 @@
 import { useCallback, useEffect, useRef, useState } from "react";
+import type { Role } from "~~/app/types";
 import { getFortuneTellerSecretKey, loadCryptoParams } from "~~/services/gameInputGenerator";
 import type { ChatMessage, PrivateGameInfo } from "~~/types/game";
 import { MPCEncryption } from "~~/utils/crypto/InputEncryption";
@@ -34,7 +35,7 @@ interface RoleAssignmentResult {
 }
 
 interface WinningJudgeResult {
-  game_result: "VillagerWin" | "WerewolfWin" | "InProgress";
+  game_result: "VillagerWin" | "WerewolfWin" | "FoxWin" | "InProgress";
   game_state_value: string;
   status: string;
 }
@@ -123,10 +124,16 @@ const normalizeFieldElement = (value: bigint): bigint => {
   return reduced >= 0n ? reduced : reduced + BN254_SCALAR_MODULUS;
 };
 
-const decodeRoleName = (roleId: bigint): "Villager" | "Seer" | "Werewolf" => {
-  const normalized = normalizeFieldElement(roleId) % 3n;
+type DecodedRoleName = NonNullable<Role>;
+
+// 役職ID: 0=村人, 1=占い師, 2=人狼, 3=騎士, 4=霊媒師, 5=狂人
+const decodeRoleName = (roleId: bigint): DecodedRoleName => {
+  const normalized = normalizeFieldElement(roleId);
   if (normalized === 1n) return "Seer";
   if (normalized === 2n) return "Werewolf";
+  if (normalized === 3n) return "Guard";
+  if (normalized === 4n) return "Medium";
+  if (normalized === 5n) return "Possessed";
   return "Villager";
 };
 
@@ -134,7 +141,7 @@ const decodeWerewolfTeammateIds = (
   maskValue: bigint,
   playerOrderIds: string[] | undefined,
   myPlayerId: string,
-  myRole: "Villager" | "Seer" | "Werewolf",
+  myRole: DecodedRoleName,
 ): string[] => {
   if (myRole !== "Werewolf" || !playerOrderIds || playerOrderIds.length === 0) {
     return [];
@@ -295,7 +302,7 @@ export const useComputationResults = (
       }
 
       const updatedInfo = updatePrivateGameInfo(roomId, playerId, {
-        playerRole: roleName as "Villager" | "Werewolf" | "Seer",
+        playerRole: roleName,
         werewolfTeammateIds,
       });
 
@@ -657,7 +664,11 @@ export const useComputationResults = (
             setWinningJudgeResult(result.resultData);
             if (result.resultData.game_result !== "InProgress") {
               const resultMessage =
-                result.resultData.game_result === "VillagerWin" ? "Villagers win!" : "Werewolves win!";
+                result.resultData.game_result === "VillagerWin"
+                  ? "Villagers win!"
+                  : result.resultData.game_result === "FoxWin"
+                    ? "The fox wins!"
+                    : "Werewolves win!";
               addMessage({
                 id: Date.now().toString(),
                 sender: "System",
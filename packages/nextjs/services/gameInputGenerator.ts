@@ -6,6 +6,9 @@ import {
   AnonymousVotingInput,
   AnonymousVotingPrivateInput,
   AnonymousVotingPublicInput,
+  AttackInput,
+  AttackPrivateInput,
+  AttackPublicInput,
   DivinationInput,
   DivinationPrivateInput,
   DivinationPublicInput,
@@ -13,6 +16,9 @@ import {
   KeyPublicizeInput,
   KeyPublicizePrivateInput,
   KeyPublicizePublicInput,
+  NightResolutionInput,
+  NightResolutionPrivateInput,
+  NightResolutionPublicInput,
   NodeKey,
   PedersenCommitment,
   PedersenCommitmentInput,
@@ -21,6 +27,7 @@ import {
   RoleAssignmentPrivateInput,
   RoleAssignmentPublicInput,
   SecretSharingScheme,
+  StateChainParameter,
   WinningJudgementInput,
   WinningJudgementPrivateInput,
   WinningJudgementPublicInput,
@@ -42,6 +49,16 @@ const FINITE_FIELD_ONE: Field[] = [
   null,
 ] as const;
 
+// BN254 ScalarField の位数
+const BN254_SCALAR_MODULUS = BigInt("21888242871839275222246405745257275088548364400416034343698204186575808495617");
+
+// 小さな整数を有限体の要素(Montgomery representaion)に変換する。役職 id などに使う
+function smallFieldElement(value: number): Field[] {
+  const montgomery = (BigInt(value) << 256n) % BN254_SCALAR_MODULUS;
+  const limbs = [0, 1, 2, 3].map(i => ((montgomery >> BigInt(64 * i)) & 0xffffffffffffffffn).toString());
+  return [JSONbigNative.parse(JSON.stringify(limbs)), null];
+}
+
 // ============================================================================
 // グローバルキャッシュ
 // ============================================================================
@@ -72,6 +89,11 @@ export async function loadCryptoParams(gameInfo?: GameInfo): Promise<any> {
         cryptoParamsCache.pedersenCommitment = gp.player_commitment?.[0] ?? cryptoParamsCache.pedersenCommitment;
         console.log("Updated cached playerCommitments from gameInfo");
       }
+      // Update role commitments once RoleAssignment has been proven
+      if (gp.role_commitment?.length) {
+        cryptoParamsCache.roleCommitments = gp.role_commitment;
+        console.log("Updated cached roleCommitments from gameInfo");
+      }
       // Update ElGamal public key if KeyPublicize has been executed
       if (gp.fortune_teller_public_key) {
         cryptoParamsCache.elgamalPublicKey = gp.fortune_teller_public_key;
@@ -93,6 +115,7 @@ export async function loadCryptoParams(gameInfo?: GameInfo): Promise<any> {
       elgamalParam: gp.elgamal_param,
       elgamalPublicKey: gp.fortune_teller_public_key,
       playerCommitments: gp.player_commitment,
+      roleCommitments: gp.role_commitment,
     };
 
     console.log("Crypto params loaded successfully from gameInfo");
@@ -216,15 +239,24 @@ export function clearTestElGamalPublicKey(roomId: string, playerId: string): voi
  * LocalStorageから読み込み、なければ新規生成
  */
 async function getRandomness(roomId: string, username: string): Promise<Field[]> {
-  const cacheKey = `${roomId}_${username}`;
+  return loadOrCreateRandomness(`${roomId}_${username}`, `randomness_${roomId}_${username}`);
+}
 
+/**
+ * 役職コミットメント用のランダムネスを取得（キャッシュあり）
+ * 役職配布でノードがこの値で役職IDにコミットし、以降の回路では自分の役職を開示するために使う
+ */
+async function getRoleRandomness(roomId: string, username: string): Promise<Field[]> {
+  return loadOrCreateRandomness(`role_${roomId}_${username}`, `role_randomness_${roomId}_${username}`);
+}
+
+async function loadOrCreateRandomness(cacheKey: string, storageKey: string): Promise<Field[]> {
   // メモリキャッシュをチェック
   if (randomnessCache.has(cacheKey)) {
     return randomnessCache.get(cacheKey)!;
   }
 
   // LocalStorageから読み込み
-  const storageKey = `randomness_${roomId}_${username}`;
   const stored = localStorage.getItem(storageKey);
 
   if (stored) {
@@ -261,8 +293,8 @@ async function getRandomness(roomId: string, username: string): Promise<Field[]>
  */
 export function clearRandomnessCache(roomId?: string, username?: string): void {
   if (roomId && username) {
-    const cacheKey = `${roomId}_${username}`;
-    randomnessCache.delete(cacheKey);
+    randomnessCache.delete(`${roomId}_${username}`);
+    randomnessCache.delete(`role_${roomId}_${username}`);
   } else {
     randomnessCache.clear();
   }
@@ -275,7 +307,7 @@ export function clearRandomnessFromStorage(roomId: string): void {
   // LocalStorageから該当ルームのランダムネスを削除
   const keys = Object.keys(localStorage);
   keys.forEach(key => {
-    if (key.startsWith(`randomness_${roomId}_`)) {
+    if (key.startsWith(`randomness_${roomId}_`) || key.startsWith(`role_randomness_${roomId}_`)) {
       localStorage.removeItem(key);
       console.log(`Cleared randomness from localStorage: ${key}`);
     }
@@ -335,6 +367,20 @@ function getMyPlayerIndex(gameInfo: GameInfo, username: string): number {
   return gameInfo.players.findIndex(p => p.name === username);
 }
 
+// 生存者の bitmask (bit i = players[i])。サーバーが管理する生死と一致していないとリクエストが拒否される
+function getAliveMask(gameInfo: GameInfo): number {
+  return gameInfo.players.reduce((mask, player, index) => (player.is_dead ? mask : mask | (1 << index)), 0);
+}
+
+// 直前の state digest と生存者・日数。サーバーが最後に受理した proof から続いていないとリクエストが拒否される
+function getStateChain(gameInfo: GameInfo): StateChainParameter {
+  return {
+    prev_state_digest: gameInfo.state_digest ?? Array(32).fill(0),
+    day_count: gameInfo.day_count ?? 1,
+    alive_mask: getAliveMask(gameInfo),
+  };
+}
+
 function getMyPlayerId(gameInfo: GameInfo, username: string): string | null {
   const player = gameInfo.players.find(p => p.name === username);
   return player ? player.id : null;
@@ -344,6 +390,43 @@ function isWerewolf(privateGameInfo: PrivateGameInfo | null): boolean {
   return privateGameInfo?.playerRole === "Werewolf";
 }
 
+// 役職ID: 0=村人, 1=占い師, 2=人狼, 3=騎士, 4=霊媒師, 5=狂人 (RoleAssignment 回路の役職IDと同じ)
+const ROLE_IDS: Record<string, number> = {
+  Villager: 0,
+  Seer: 1,
+  Werewolf: 2,
+  Guard: 3,
+  Medium: 4,
+  Possessed: 5,
+};
+
+// 役職コミットメントを開示するための自分の役職ID
+function getMyRoleId(privateGameInfo: PrivateGameInfo | null): Field[] {
+  const role = privateGameInfo?.playerRole;
+  if (!role || !(role in ROLE_IDS)) {
+    throw new Error("Own role is not known yet; role assignment must complete first");
+  }
+  return smallFieldElement(ROLE_IDS[role]);
+}
+
+// 役職配布の証明から取り出され、サーバーが公開している役職コミットメント (players の並び)
+function getRoleCommitments(cryptoParams: any, gameInfo: GameInfo): PedersenCommitment[] {
+  const roleCommitments: PedersenCommitment[] | undefined =
+    gameInfo.crypto_parameters?.role_commitment ?? cryptoParams.roleCommitments;
+  if (!roleCommitments || roleCommitments.length !== gameInfo.players.length) {
+    throw new Error("Role commitments are not published yet; role assignment must complete first");
+  }
+  return roleCommitments;
+}
+
+// NightResolution の id は生存者内の index なので、役職コミットメントも生存者の並びに揃える
+function getAliveRoleCommitments(cryptoParams: any, gameInfo: GameInfo): PedersenCommitment[] {
+  const allRoleCommitments = getRoleCommitments(cryptoParams, gameInfo);
+  return gameInfo.players
+    .map((player, index) => (player.is_dead ? null : allRoleCommitments[index]))
+    .filter((commitment): commitment is PedersenCommitment => commitment !== null);
+}
+
 function getNodeKeys(): NodeKey[] {
   return [
     { nodeId: "0", publicKey: process.env.NEXT_PUBLIC_MPC_NODE0_PUBLIC_KEY || "" },
@@ -590,23 +673,19 @@ export async function generateRoleAssignmentInput(
     maxGroupSize, // m
   );
   const playerRandomness = await getRandomness(roomId, username);
+  const roleRandomness = await getRoleRandomness(roomId, username);
 
   const privateInput: RoleAssignmentPrivateInput = {
     id: myIndex,
     shuffleMatrices: generatedShuffleMatrices,
-    randomness: FINITE_FIELD_ZERO,
+    randomness: roleRandomness,
     playerRandomness,
+    // submitCommitment と同じく playerRandomness をそのまま Pedersen のランダムネスに使う
+    playerCommitmentRandomness: playerRandomness,
   };
 
   const generatedTau = generateTauMatrixForWasm(groupingParameter, latestGameInfo.players.length);
 
-  const dummyRoleCommitment = [
-    { x: FINITE_FIELD_ZERO, y: FINITE_FIELD_ZERO, _params: null },
-    { x: FINITE_FIELD_ZERO, y: FINITE_FIELD_ZERO, _params: null },
-    { x: FINITE_FIELD_ZERO, y: FINITE_FIELD_ZERO, _params: null },
-    { x: FINITE_FIELD_ZERO, y: FINITE_FIELD_ZERO, _params: null },
-  ];
-
   console.log("cryptoParams before roleassignment input generation:", cryptoParams);
   console.log("playerCommitments available:", cryptoParams.playerCommitments);
   console.log("playerCommitments length:", cryptoParams.playerCommitments?.length);
@@ -645,8 +724,10 @@ export async function generateRoleAssignmentInput(
     pedersenParam: cryptoParams.pedersenParam,
     groupingParameter,
     tauMatrix: generatedTau,
-    roleCommitment: dummyRoleCommitment,
+    // 役職コミットメントは各プレイヤーの roleRandomness からノードが計算し、証明後にサーバーが公開する
+    roleCommitment: [],
     playerCommitment: playerCommitments,
+    stateChain: getStateChain(latestGameInfo),
   };
 
   // プレイヤーの公開鍵を取得または生成
@@ -684,8 +765,9 @@ export async function generateDivinationInput(
   const cryptoParams = await loadCryptoParams(gameInfo);
   const randomness = await getRandomness(roomId, username);
   const alivePlayers = gameInfo.players.filter(player => !player.is_dead);
-  const myIndex = alivePlayers.findIndex(player => player.name === username);
-  if (myIndex < 0) {
+  // 回路は死亡者を含む全プレイヤー分の行を持つので、id は players 内のインデックス
+  const myIndex = getMyPlayerIndex(gameInfo, username);
+  if (myIndex < 0 || gameInfo.players[myIndex].is_dead) {
     throw new Error("Current player is not alive or not found in game state for divination");
   }
 
@@ -702,13 +784,13 @@ export async function generateDivinationInput(
     isDummy === false
       ? {
           id: myIndex,
-          isTarget: alivePlayers.map((player: any) => (player.id === targetId ? FINITE_FIELD_ONE : FINITE_FIELD_ZERO)),
+          isTarget: gameInfo.players.map(player => (player.id === targetId ? FINITE_FIELD_ONE : FINITE_FIELD_ZERO)),
           isWerewolf: isWerewolfValue,
           randomness: randomness,
         }
       : {
           id: myIndex,
-          isTarget: alivePlayers.map(() => FINITE_FIELD_ZERO),
+          isTarget: gameInfo.players.map(() => FINITE_FIELD_ZERO),
           isWerewolf: isWerewolfValue,
           randomness: randomness,
         };
@@ -717,7 +799,8 @@ export async function generateDivinationInput(
     pedersenParam: cryptoParams.pedersenParam,
     elgamalParam: cryptoParams.elgamalParam || {},
     pubKey: cryptoParams.elgamalPublicKey || {},
-    playerNum: alivePlayers.length,
+    playerNum: gameInfo.players.length,
+    stateChain: getStateChain(gameInfo),
   };
 
   return {
@@ -740,8 +823,8 @@ export async function generateVotingInput(
   const cryptoParams = await loadCryptoParams(gameInfo);
   const randomness = await getRandomness(roomId, username);
   const alivePlayers = gameInfo.players.filter(player => !player.is_dead);
-  const myIndex = alivePlayers.findIndex(player => player.name === username);
-  if (myIndex < 0) {
+  const myIndex = getMyPlayerIndex(gameInfo, username);
+  if (myIndex < 0 || gameInfo.players[myIndex].is_dead) {
     throw new Error("Current player is not alive or not found in game state for voting");
   }
 
@@ -757,14 +840,15 @@ export async function generateVotingInput(
 
   const privateInput: AnonymousVotingPrivateInput = {
     id: myIndex,
-    isTargetId: alivePlayers.map((player: any) => (player.id === votedForId ? FINITE_FIELD_ONE : FINITE_FIELD_ZERO)),
+    isTargetId: gameInfo.players.map(player => (player.id === votedForId ? FINITE_FIELD_ONE : FINITE_FIELD_ZERO)),
     playerRandomness: randomness,
   };
 
   const publicInput: AnonymousVotingPublicInput = {
     pedersenParam: cryptoParams.pedersenParam,
-    playerCommitment: Array(alivePlayers.length).fill(cryptoParams.pedersenCommitment as PedersenCommitment),
-    playerNum: alivePlayers.length,
+    playerCommitment: Array(gameInfo.players.length).fill(cryptoParams.pedersenCommitment as PedersenCommitment),
+    playerNum: gameInfo.players.length,
+    stateChain: getStateChain(gameInfo),
   };
 
   return {
@@ -808,7 +892,6 @@ export async function generateKeyPublicizeInput(
 
   let publicKeyX: Field[];
   let publicKeyY: Field[];
-  let isFortuneTellerFlag: Field[];
 
   if (isFortuneTeller) {
     // 占い師の場合: ElGamal鍵ペアを生成または取得
@@ -860,7 +943,6 @@ export async function generateKeyPublicizeInput(
     // publicKeyはオブジェクト形式 { x: Field[], y: Field[] }
     publicKeyX = publicKey.x;
     publicKeyY = publicKey.y;
-    isFortuneTellerFlag = FINITE_FIELD_ONE;
 
     console.log("KeyPublicize: Using public key (Fortune Teller)");
     console.log("  X:", publicKey.x);
@@ -869,7 +951,6 @@ export async function generateKeyPublicizeInput(
     // 占い師以外の場合: ゼロ値を使用
     publicKeyX = FINITE_FIELD_ZERO;
     publicKeyY = FINITE_FIELD_ZERO;
-    isFortuneTellerFlag = FINITE_FIELD_ZERO;
 
     console.log("KeyPublicize: Using zero values (not Fortune Teller)");
   }
@@ -878,11 +959,125 @@ export async function generateKeyPublicizeInput(
     id: myIndex,
     pubKeyOrDummyX: publicKeyX as any,
     pubKeyOrDummyY: publicKeyY as any,
-    isFortuneTeller: isFortuneTellerFlag as any,
+    // 回路は roleId を roleCommitment[id] に開示し、占い師かどうかを roleId から求める
+    roleId: getMyRoleId(privateGameInfo),
+    randomness: await getRoleRandomness(roomId, username),
   };
 
   const publicInput: KeyPublicizePublicInput = {
     pedersenParam: cryptoParams.pedersenParam,
+    roleCommitment: getRoleCommitments(cryptoParams, gameInfo),
+    stateChain: getStateChain(gameInfo),
+  };
+
+  return {
+    privateInput,
+    publicInput,
+    nodeKeys: getNodeKeys(),
+    scheme: getScheme(),
+  };
+}
+
+/**
+ * 人狼の襲撃用の入力を生成
+ * 誰が人狼かを隠すため、生存者全員が送信する。人狼以外は襲撃先を全て 0 にする。
+ */
+export async function generateAttackInput(
+  roomId: string,
+  username: string,
+  gameInfo: GameInfo,
+  targetId: string | null,
+): Promise<AttackInput> {
+  const cryptoParams = await loadCryptoParams(gameInfo);
+  const alivePlayers = gameInfo.players.filter(player => !player.is_dead);
+  // 投票と同じく id は players の index。死亡者の行は MPC ノードが 0 で補う
+  const myIndex = getMyPlayerIndex(gameInfo, username);
+  if (myIndex < 0 || gameInfo.players[myIndex].is_dead) {
+    throw new Error("Current player is not alive or not found in game state for attack");
+  }
+
+  // PrivateGameInfoから自分の役職を取得
+  const playerId = getMyPlayerId(gameInfo, username);
+  const privateGameInfo = playerId ? getPrivateGameInfo(roomId, playerId) : null;
+  const amWerewolf = isWerewolf(privateGameInfo);
+
+  if (amWerewolf && (!targetId || !alivePlayers.some(player => player.id === targetId))) {
+    throw new Error("Attack target must be an alive player");
+  }
+
+  const privateInput: AttackPrivateInput = {
+    id: myIndex,
+    isTargetId: gameInfo.players.map(player =>
+      amWerewolf && player.id === targetId ? FINITE_FIELD_ONE : FINITE_FIELD_ZERO,
+    ),
+    // 回路は roleId を roleCommitment[id] に開示し、人狼かどうかを roleId から求める
+    roleId: getMyRoleId(privateGameInfo),
+    randomness: await getRoleRandomness(roomId, username),
+  };
+
+  const publicInput: AttackPublicInput = {
+    pedersenParam: cryptoParams.pedersenParam,
+    playerNum: gameInfo.players.length,
+    roleCommitment: getRoleCommitments(cryptoParams, gameInfo),
+    stateChain: getStateChain(gameInfo),
+  };
+
+  return {
+    privateInput,
+    publicInput,
+    nodeKeys: getNodeKeys(),
+    scheme: getScheme(),
+  };
+}
+
+/**
+ * 騎士がいる村の夜の入力を生成 (襲撃先と護衛先をまとめて解決する)
+ */
+export async function generateNightResolutionInput(
+  roomId: string,
+  username: string,
+  gameInfo: GameInfo,
+  attackTargetId: string | null,
+  guardTargetId: string | null,
+): Promise<NightResolutionInput> {
+  const cryptoParams = await loadCryptoParams(gameInfo);
+  const alivePlayers = gameInfo.players.filter(player => !player.is_dead);
+  const myIndex = alivePlayers.findIndex(player => player.name === username);
+  if (myIndex < 0) {
+    throw new Error("Current player is not alive or not found in game state for night resolution");
+  }
+
+  // PrivateGameInfoから自分の役職を取得
+  const playerId = getMyPlayerId(gameInfo, username);
+  const privateGameInfo = playerId ? getPrivateGameInfo(roomId, playerId) : null;
+  const amWerewolf = isWerewolf(privateGameInfo);
+  const amGuard = privateGameInfo?.playerRole === "Guard";
+
+  if (amWerewolf && (!attackTargetId || !alivePlayers.some(player => player.id === attackTargetId))) {
+    throw new Error("Attack target must be an alive player");
+  }
+  if (amGuard && (!guardTargetId || !alivePlayers.some(player => player.id === guardTargetId))) {
+    throw new Error("Guard target must be an alive player");
+  }
+
+  const privateInput: NightResolutionPrivateInput = {
+    id: myIndex,
+    isAttackTargetId: alivePlayers.map(player =>
+      amWerewolf && player.id === attackTargetId ? FINITE_FIELD_ONE : FINITE_FIELD_ZERO,
+    ),
+    isGuardTargetId: alivePlayers.map(player =>
+      amGuard && player.id === guardTargetId ? FINITE_FIELD_ONE : FINITE_FIELD_ZERO,
+    ),
+    // 回路は roleId を roleCommitment[id] に開示し、人狼・騎士かどうかを roleId から求める
+    roleId: getMyRoleId(privateGameInfo),
+    randomness: await getRoleRandomness(roomId, username),
+  };
+
+  const publicInput: NightResolutionPublicInput = {
+    pedersenParam: cryptoParams.pedersenParam,
+    playerNum: alivePlayers.length,
+    roleCommitment: getAliveRoleCommitments(cryptoParams, gameInfo),
+    stateChain: getStateChain(gameInfo),
   };
 
   return {
@@ -902,23 +1097,35 @@ export async function generateWinningJudgementInput(
   gameInfo: GameInfo,
 ): Promise<WinningJudgementInput> {
   const cryptoParams = await loadCryptoParams(gameInfo);
-  const randomness = await getRandomness(roomId, username);
   const myIndex = getMyPlayerIndex(gameInfo, username);
 
   // PrivateGameInfoから自分の役職を取得
   const playerId = getMyPlayerId(gameInfo, username);
   const privateGameInfo = playerId ? getPrivateGameInfo(roomId, playerId) : null;
   const amWerewolfValues = isWerewolf(privateGameInfo) ? FINITE_FIELD_ONE : FINITE_FIELD_ZERO;
+  // 狂人は人狼として数えないが、人狼陣営として勝敗が決まる
+  const amWerewolfTeamValues =
+    isWerewolf(privateGameInfo) || privateGameInfo?.playerRole === "Possessed" ? FINITE_FIELD_ONE : FINITE_FIELD_ZERO;
 
   const privateInput: WinningJudgementPrivateInput = {
     id: myIndex,
     amWerewolf: amWerewolfValues,
-    playerRandomness: randomness,
+    amWerewolfTeam: amWerewolfTeamValues,
+    // 妖狐はまだ配役されないので常に 0
+    amFox: FINITE_FIELD_ZERO,
+    // 回路は roleId を roleCommitment[id] に開示し、陣営フラグと一致するか確認する
+    roleId: getMyRoleId(privateGameInfo),
+    randomness: await getRoleRandomness(roomId, username),
   };
 
+  // 勝利条件と日数はサーバーの値と一致していないとリクエストが拒否される
   const publicInput: WinningJudgementPublicInput = {
     pedersenParam: cryptoParams.pedersenParam,
-    playerCommitment: Array(gameInfo.players.length).fill(cryptoParams.pedersenCommitment),
+    playerNum: gameInfo.players.length,
+    winCondition: gameInfo.win_condition ?? { werewolf_win_on_majority: false, day_limit: 0 },
+    // 勝敗判定の id は players の並びなので、役職コミットメントも全員分を渡す
+    roleCommitment: getRoleCommitments(cryptoParams, gameInfo),
+    stateChain: getStateChain(gameInfo),
   };
 
   return {
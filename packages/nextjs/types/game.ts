@@ -24,6 +24,7 @@ export interface RoomInfo {
 export interface CryptoParameters {
   pedersen_param: PedersenParam; // Pedersenコミットメントパラメータ
   player_commitment: PedersenCommitment[]; // プレイヤーのコミットメント配列
+  role_commitment?: PedersenCommitment[]; // 役職配布の証明から取り出した役職コミットメント配列
   fortune_teller_public_key: ElGamalPublicKey; // 占い師の公開鍵
   elgamal_param: ElGamalParam; // ElGamal暗号化パラメータ
 }
@@ -38,7 +39,13 @@ export interface GameInfo {
   players: Player[];
   playerRole: Role;
   hasActed: boolean;
-  result: "InProgress" | "VillagerWin" | "WerewolfWin";
+  result: "InProgress" | "VillagerWin" | "WerewolfWin" | "FoxWin";
+  win_condition?: {
+    werewolf_win_on_majority: boolean;
+    day_limit: number;
+  };
+  // 最後に受理された proof の state digest (32 byte big-endian)
+  state_digest?: number[];
   crypto_parameters?: CryptoParameters;
   chat_log?: {
     messages: Array<{
@@ -86,7 +93,7 @@ export interface WebSocketMessage {
 }
 
 export interface GameResultModalProps {
-  result: "VillagerWin" | "WerewolfWin" | "InProgress";
+  result: "VillagerWin" | "WerewolfWin" | "FoxWin" | "InProgress";
   onClose: () => void;
 }
 
@@ -94,6 +101,9 @@ export interface GroupingParameter {
   Villager: [number, boolean];
   FortuneTeller: [number, boolean];
   Werewolf: [number, boolean];
+  Medium?: [number, boolean];
+  Possessed?: [number, boolean];
+  Guard?: [number, boolean];
 }
 
 export interface RoomConfig {
@@ -3,11 +3,14 @@
 // Browser用のWASMインポート（default export）
 import wasmInit, {
   init as RustInit,
+  attack_split_and_encrypt,
   divination,
   elgamal_decrypt,
   elgamal_keygen,
   fr_rand,
   key_publicize,
+  medium_split_and_encrypt,
+  night_resolution_split_and_encrypt,
   pedersen_commitment,
   role_assignment,
   voting_split_and_encrypt,
@@ -16,6 +19,8 @@ import wasmInit, {
 import {
   AnonymousVotingInput,
   AnonymousVotingOutput,
+  AttackInput,
+  AttackOutput,
   DivinationInput,
   DivinationOutput,
   ElGamalDecryptInput,
@@ -24,6 +29,10 @@ import {
   ElGamalKeygenOutput,
   KeyPublicizeInput,
   KeyPublicizeOutput,
+  MediumInput,
+  MediumOutput,
+  NightResolutionInput,
+  NightResolutionOutput,
   PedersenCommitment,
   PedersenCommitmentInput,
   RoleAssignmentInput,
@@ -81,6 +90,45 @@ export class MPCEncryption {
     }
   }
 
+  /**
+   * 人狼の襲撃先の暗号化
+   */
+  public static async encryptAttack(input: AttackInput): Promise<AttackOutput> {
+    await this.initializeWasm();
+    try {
+      return attack_split_and_encrypt(input);
+    } catch (error) {
+      console.error("Attack encryption failed:", error);
+      throw new Error(`Failed to encrypt attack`);
+    }
+  }
+
+  /**
+   * 襲撃先と護衛先の暗号化
+   */
+  public static async encryptNightResolution(input: NightResolutionInput): Promise<NightResolutionOutput> {
+    await this.initializeWasm();
+    try {
+      return night_resolution_split_and_encrypt(input);
+    } catch (error) {
+      console.error("Night resolution encryption failed:", error);
+      throw new Error(`Failed to encrypt night resolution`);
+    }
+  }
+
+  /**
+   * 霊媒師の暗号化
+   */
+  public static async encryptMedium(input: MediumInput): Promise<MediumOutput> {
+    await this.initializeWasm();
+    try {
+      return medium_split_and_encrypt(input);
+    } catch (error) {
+      console.error("Medium encryption failed:", error);
+      throw new Error(`Failed to encrypt medium`);
+    }
+  }
+
   /**
    * 役職割り当ての暗号化
    */
@@ -26,6 +26,33 @@ export type KeyPublicizeInput = {
 
 export type KeyPublicizeOutput = string;
 
+export type AttackInput = {
+  privateInput: AttackPrivateInput;
+  publicInput: AttackPublicInput;
+  nodeKeys: NodeKey[];
+  scheme: SecretSharingScheme;
+};
+
+export type AttackOutput = string;
+
+export type NightResolutionInput = {
+  privateInput: NightResolutionPrivateInput;
+  publicInput: NightResolutionPublicInput;
+  nodeKeys: NodeKey[];
+  scheme: SecretSharingScheme;
+};
+
+export type NightResolutionOutput = string;
+
+export type MediumInput = {
+  privateInput: MediumPrivateInput;
+  publicInput: MediumPublicInput;
+  nodeKeys: NodeKey[];
+  scheme: SecretSharingScheme;
+};
+
+export type MediumOutput = string;
+
 export type RoleAssignmentInput = {
   privateInput: RoleAssignmentPrivateInput;
   publicInput: RoleAssignmentPublicInput;
@@ -103,6 +130,15 @@ export interface ElGamalPublicKey {
 
 export type ElGamalSecretKey = Field[];
 
+// 直前の proof の state digest (32 byte big-endian) と、このリクエスト時点の生存者・日数。
+// キーはサーバーの StateChainParameter と同じ snake_case
+export interface StateChainParameter {
+  prev_state_digest: number[];
+  day_count: number;
+  // 生存者の bitmask (bit i = プレイヤー i)
+  alive_mask: number;
+}
+
 export interface AnonymousVotingPrivateInput {
   id: number;
   //   isTargetId: string[];
@@ -113,24 +149,78 @@ export interface AnonymousVotingPublicInput {
   pedersenParam: PedersenParam;
   playerCommitment: PedersenCommitment[];
   playerNum: number;
+  stateChain: StateChainParameter;
 }
 
 export interface KeyPublicizePrivateInput {
   id: number;
   pubKeyOrDummyX: Field[] | null;
   pubKeyOrDummyY: Field[] | null;
-  isFortuneTeller: Field[] | null;
+  // roleCommitment[id] の開示値
+  roleId: Field[] | null;
+  randomness: any;
 }
 export interface KeyPublicizePublicInput {
   pedersenParam: PedersenParam;
+  roleCommitment: PedersenCommitment[];
+  stateChain: StateChainParameter;
+}
+
+export interface AttackPrivateInput {
+  id: number;
+  isTargetId: Field[][];
+  roleId: Field[] | null;
+  randomness: any;
+}
+export interface AttackPublicInput {
+  pedersenParam: PedersenParam;
+  playerNum: number;
+  roleCommitment: PedersenCommitment[];
+  stateChain: StateChainParameter;
+}
+
+export interface NightResolutionPrivateInput {
+  id: number;
+  isAttackTargetId: Field[][];
+  isGuardTargetId: Field[][];
+  roleId: Field[] | null;
+  randomness: any;
+}
+export interface NightResolutionPublicInput {
+  pedersenParam: PedersenParam;
+  playerNum: number;
+  roleCommitment: PedersenCommitment[];
+  stateChain: StateChainParameter;
+}
+
+export interface MediumPrivateInput {
+  id: number;
+  // roleCommitment[id] の開示値。人狼かどうかはここから求める
+  roleId: Field[] | null;
+  roleRandomness: any;
+  randomness: Field[];
+}
+export interface MediumPublicInput {
+  pedersenParam: PedersenParam;
+  elgamalParam: ElGamalParam;
+  // KeyPublicize で公開された霊媒師の公開鍵
+  pubKey: any;
+  playerNum: number;
+  // AnonymousVoting の出力 (処刑時点の生存者 index)
+  executedId: number;
+  roleCommitment: PedersenCommitment[];
+  stateChain: StateChainParameter;
 }
 
 // TODO: modify.
 export interface RoleAssignmentPrivateInput {
   id: number;
   shuffleMatrices: any;
+  // roleCommitment[id] のランダムネス
   randomness: any;
   playerRandomness: Field[];
+  // playerCommitment[id] の開示値
+  playerCommitmentRandomness: any;
 }
 export interface RoleAssignmentPublicInput {
   // parameter
@@ -141,14 +231,21 @@ export interface RoleAssignmentPublicInput {
 
   // instance
   tauMatrix: any;
+  // ノードが計算するので空配列を送る
   roleCommitment: PedersenCommitment[];
   playerCommitment: PedersenCommitment[];
+  stateChain: StateChainParameter;
+  // "hashed" はデプロイ済みの鍵が GROTH16_INSTANCE_MODE=hashed で作られている場合のみ
+  instanceMode?: "full" | "hashed";
 }
 
 type GroupingParameter = {
   Villager: [number, boolean];
   FortuneTeller: [number, boolean];
   Werewolf: [number, boolean];
+  Medium?: [number, boolean];
+  Possessed?: [number, boolean];
+  Guard?: [number, boolean];
 };
 
 // TODO: modify.
@@ -164,17 +261,30 @@ export interface DivinationPublicInput {
   elgamalParam: ElGamalParam;
   pubKey: any;
   playerNum: any;
+  stateChain: StateChainParameter;
   //   playerCommitment: PedersenCommitment[];
 }
 
 export interface WinningJudgementPrivateInput {
   id: number;
   amWerewolf: Field[];
-  playerRandomness: Field[];
+  amWerewolfTeam: Field[];
+  amFox: Field[];
+  // roleCommitment[id] の開示値
+  roleId: Field[] | null;
+  randomness: any;
+}
+// 勝利条件。キーはサーバーの WinConditionParameter と同じ snake_case
+export interface WinConditionParameter {
+  werewolf_win_on_majority: boolean;
+  day_limit: number;
 }
 export interface WinningJudgementPublicInput {
   pedersenParam: PedersenParam;
-  playerCommitment: PedersenCommitment[];
+  playerNum: number;
+  winCondition: WinConditionParameter;
+  roleCommitment: PedersenCommitment[];
+  stateChain: StateChainParameter;
 }
 
 export interface ElGamalDecryptInput {
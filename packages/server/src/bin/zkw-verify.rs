@@ -0,0 +1,149 @@
+//! Re-verifies an exported game bundle (`GET /api/game/:roomid/bundle`) without a chain.
+//!
+//! Usage: zkw-verify <bundle.json> [--data-dir <groth16 data dir>] [--batch <out.json>]
+//!
+//! With `--batch`, a bundle that verified is also batched into the calldata of
+//! `TranscriptVerifier.verifyTranscript` (`out.json`), and the matching contract is written
+//! next to it as `TranscriptVerifier.sol`.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::{anyhow, bail, Context, Result};
+use arkworks_solidity_verifier::batch::export_transcript_verifier;
+use server::models::proof_bundle::ProofBundle;
+use server::services::groth16_verifier::Groth16Verifier;
+use server::services::proof_bundle::{batch_verify_bundle, verify_bundle, ProofVerdict};
+use zk_mpc_node::groth16_data_dir;
+
+struct Args {
+    bundle: PathBuf,
+    data_dir: PathBuf,
+    batch: Option<PathBuf>,
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run() -> Result<bool> {
+    let Args {
+        bundle: bundle_path,
+        data_dir,
+        batch,
+    } = parse_args()?;
+    let body = std::fs::read_to_string(&bundle_path)
+        .with_context(|| format!("failed to read {}", bundle_path.display()))?;
+    let bundle: ProofBundle = serde_json::from_str(&body)
+        .with_context(|| format!("failed to parse {}", bundle_path.display()))?;
+    let verifier = Groth16Verifier::from_data_dir(&data_dir).map_err(|e| {
+        anyhow!(
+            "failed to load verifying keys from {}: {}",
+            data_dir.display(),
+            e
+        )
+    })?;
+    let report = verify_bundle(&bundle, &verifier)?;
+
+    println!(
+        "room {} (game {}), result {:?}, {} proofs",
+        bundle.room_id,
+        bundle.game_id,
+        bundle.result,
+        bundle.proofs.len()
+    );
+    for (i, check) in report.checks.iter().enumerate() {
+        let verdict = match &check.verdict {
+            ProofVerdict::Valid => "valid".to_string(),
+            ProofVerdict::Invalid(reason) => format!("INVALID ({})", reason),
+            ProofVerdict::Skipped(reason) => format!("skipped ({})", reason),
+        };
+        let chain = match &check.chain_error {
+            None => "ok".to_string(),
+            Some(reason) => format!("BROKEN ({})", reason),
+        };
+        println!(
+            "#{} day {} {:?} {:?} [{}]: proof {}, state chain {}",
+            i, check.day_count, check.phase, check.circuit_profile, check.batch_id, verdict, chain
+        );
+    }
+    match &report.final_digest_error {
+        None => println!("final state digest {} ok", bundle.final_state_digest),
+        Some(reason) => println!("final state digest BROKEN ({})", reason),
+    }
+
+    let verified = report
+        .checks
+        .iter()
+        .filter(|check| check.verdict == ProofVerdict::Valid)
+        .count();
+    println!(
+        "{}/{} proofs verified: {}",
+        verified,
+        report.checks.len(),
+        if report.is_ok() { "OK" } else { "FAILED" }
+    );
+    if !report.is_ok() {
+        return Ok(false);
+    }
+
+    if let Some(out) = batch {
+        let batched = batch_verify_bundle(&bundle, &verifier)?;
+        std::fs::write(&out, serde_json::to_string_pretty(&batched)?)
+            .with_context(|| format!("failed to write {}", out.display()))?;
+        let contract_out = out.with_file_name("TranscriptVerifier.sol");
+        std::fs::write(
+            &contract_out,
+            export_transcript_verifier(&batched.verifying_keys),
+        )
+        .with_context(|| format!("failed to write {}", contract_out.display()))?;
+        println!(
+            "batched {} proofs over {} verifying keys: wrote {} and {}",
+            batched.transcript.proofs.len(),
+            batched.circuit_profiles.len(),
+            out.display(),
+            contract_out.display()
+        );
+    }
+    Ok(true)
+}
+
+fn parse_args() -> Result<Args> {
+    let mut bundle = None;
+    let mut data_dir = None;
+    let mut batch = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--data-dir" => {
+                let dir = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--data-dir needs a value"))?;
+                data_dir = Some(PathBuf::from(dir));
+            }
+            "--batch" => {
+                let out = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--batch needs a value"))?;
+                batch = Some(PathBuf::from(out));
+            }
+            _ if bundle.is_none() => bundle = Some(PathBuf::from(&arg)),
+            _ => bail!("unexpected argument {}", arg),
+        }
+    }
+    let bundle = bundle.ok_or_else(|| {
+        anyhow!("usage: zkw-verify <bundle.json> [--data-dir <dir>] [--batch <out.json>]")
+    })?;
+    Ok(Args {
+        bundle,
+        data_dir: data_dir.unwrap_or_else(groth16_data_dir),
+        batch,
+    })
+}
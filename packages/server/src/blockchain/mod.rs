@@ -1,5 +1,6 @@
 use crate::models::game::GameResult;
 use crate::utils::config::Config;
+use mpc_algebra_wasm::ProofScheme;
 use std::sync::Arc;
 use tokio::process::Command;
 use tokio::sync::Mutex;
@@ -17,6 +18,8 @@ pub enum ProofType {
 }
 
 const ROLE_ASSIGNMENT_VERIFY_PROOF_GAS_LIMIT: u64 = 12_000_000;
+// Marlin verifier は transcript の ChaCha20 と多数の modexp で Groth16 より重い
+const MARLIN_VERIFY_PROOF_GAS_LIMIT: u64 = 25_000_000;
 const CAST_SEND_MAX_RETRIES: usize = 3;
 
 impl ProofType {
@@ -172,6 +175,12 @@ impl BlockchainClient {
         !matches!(&*self.backend, Backend::Disabled)
     }
 
+    /// Only the real backend runs the verifier contracts. Otherwise the server checks
+    /// the proofs itself with `Groth16Verifier`.
+    pub fn verifies_proofs_onchain(&self) -> bool {
+        matches!(&*self.backend, Backend::Real(_))
+    }
+
     pub async fn create_game(
         &self,
         game_id: [u8; 32],
@@ -333,6 +342,7 @@ impl BlockchainClient {
         proof_id: [u8; 32],
         game_id: [u8; 32],
         proof_type: ProofType,
+        scheme: ProofScheme,
         player_count: u8,
         werewolf_count: u8,
         proof_data: &[u8],
@@ -343,10 +353,11 @@ impl BlockchainClient {
             Backend::Simulated(_) => {
                 let verified = true;
                 tracing::info!(
-                    "[simulated-chain] verify_proof proof_id={} game_id={} proof_type={:?} player_count={} werewolf_count={} verified={} proof_bytes={} public_inputs_bytes={}",
+                    "[simulated-chain] verify_proof proof_id={} game_id={} proof_type={:?} scheme={:?} player_count={} werewolf_count={} verified={} proof_bytes={} public_inputs_bytes={}",
                     state_hash::bytes32_to_hex(&proof_id),
                     state_hash::bytes32_to_hex(&game_id),
                     proof_type,
+                    scheme,
                     player_count,
                     werewolf_count,
                     verified,
@@ -359,10 +370,12 @@ impl BlockchainClient {
                 let proof_hex = bytes_to_hex(proof_data);
                 let public_inputs_hex = bytes_to_hex(public_inputs);
                 let proof_type_u8 = proof_type.as_u8();
-                let verify_gas_limit = if matches!(proof_type, ProofType::RoleAssignment) {
-                    Some(ROLE_ASSIGNMENT_VERIFY_PROOF_GAS_LIMIT)
-                } else {
-                    None
+                let verify_gas_limit = match (scheme, proof_type) {
+                    (ProofScheme::Marlin, _) => Some(MARLIN_VERIFY_PROOF_GAS_LIMIT),
+                    (ProofScheme::Groth16, ProofType::RoleAssignment) => {
+                        Some(ROLE_ASSIGNMENT_VERIFY_PROOF_GAS_LIMIT)
+                    }
+                    (ProofScheme::Groth16, _) => None,
                 };
 
                 let call_args = vec![
@@ -390,10 +403,11 @@ impl BlockchainClient {
                 let preview_verified = parse_cast_bool(&call_output)?;
                 if !preview_verified {
                     tracing::warn!(
-                        "[real-chain] verify_proof static call returned false proof_id={} game_id={} proof_type={:?}",
+                        "[real-chain] verify_proof static call returned false proof_id={} game_id={} proof_type={:?} scheme={:?}",
                         state_hash::bytes32_to_hex(&proof_id),
                         state_hash::bytes32_to_hex(&game_id),
-                        proof_type
+                        proof_type,
+                        scheme
                     );
                     return Ok(Some(false));
                 }
@@ -417,10 +431,11 @@ impl BlockchainClient {
                 let tx = self.send_and_extract_tx_hash(backend, &send_args).await?;
 
                 tracing::info!(
-                    "[real-chain] verify_proof proof_id={} game_id={} proof_type={:?} player_count={} werewolf_count={} verified=true tx={}",
+                    "[real-chain] verify_proof proof_id={} game_id={} proof_type={:?} scheme={:?} player_count={} werewolf_count={} verified=true tx={}",
                     state_hash::bytes32_to_hex(&proof_id),
                     state_hash::bytes32_to_hex(&game_id),
                     proof_type,
+                    scheme,
                     player_count,
                     werewolf_count,
                     tx
@@ -693,6 +708,7 @@ fn game_result_to_u8(result: &GameResult) -> u8 {
         GameResult::InProgress => 0,
         GameResult::VillagerWin => 1,
         GameResult::WerewolfWin => 2,
+        GameResult::FoxWin => 3,
     }
 }
 
@@ -137,6 +137,7 @@ fn result_to_u8(result: &GameResult) -> u8 {
         GameResult::InProgress => 0,
         GameResult::VillagerWin => 1,
         GameResult::WerewolfWin => 2,
+        GameResult::FoxWin => 3,
     }
 }
 
@@ -115,8 +115,25 @@ async fn main() {
         .allow_methods([Method::GET, Method::POST])
         .allow_headers([http::header::CONTENT_TYPE, http::header::AUTHORIZATION]);
 
+    // オンチェーンで検証しないときは、サーバーが持つ Groth16 鍵で proof を検証する
+    let state = state::AppState::new();
+    if utils::config::CONFIG.allow_unverified_proofs {
+        eprintln!(
+            "Warning: ALLOW_UNVERIFIED_PROOFS=true のため、検証鍵のない proof を検証せずに受理します。開発環境以外では使用しないでください"
+        );
+    } else if !state.blockchain_client.verifies_proofs_onchain()
+        && state.groth16_verifier.is_empty()
+    {
+        eprintln!(
+            "Fatal: Groth16 の検証鍵を 1 つも読み込めませんでした ({})",
+            zk_mpc_node::groth16_data_dir().display()
+        );
+        eprintln!("GROTH16_DATA_DIR を確認するか、開発環境では ALLOW_UNVERIFIED_PROOFS=true を設定してください。");
+        std::process::exit(1);
+    }
+
     // ルーティングの設定
-    let app = app::create_app()
+    let app = app::create_app_with_state(state)
         .route("/greet", get(greet))
         .layer(cors) // CORSレイヤーを追加
         .layer(
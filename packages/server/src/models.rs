@@ -3,6 +3,7 @@ pub mod config;
 pub mod game;
 pub mod node;
 pub mod player;
+pub mod proof_bundle;
 pub mod role;
 pub mod room;
 mod rule;
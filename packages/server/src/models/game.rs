@@ -1,12 +1,14 @@
 use crate::{
     blockchain::{
-        state_hash::{compute_game_id, compute_proof_id},
+        state_hash::{bytes32_to_hex, compute_game_id, compute_proof_id},
         ProofType as ChainProofType,
     },
     models::chat::{ChatMessage, ChatMessageType},
+    utils::config::CONFIG,
 };
 
 use super::player::Player;
+use super::proof_bundle::{encode_hex, ProofRecord};
 use ark_bn254::Fr;
 use ark_crypto_primitives::{encryption::AsymmetricEncryptionScheme, CommitmentScheme};
 use ark_ff::{BigInteger, PrimeField};
@@ -48,6 +50,15 @@ pub struct Game {
     #[serde(default)]
     pub phase_timer_paused_total_seconds: u64,
     pub grouping_parameter: GroupingParameter,
+    // ルーム設定から引き継ぐ勝利条件。WinningJudgement の公開入力と一致している必要がある
+    #[serde(default)]
+    pub win_condition: WinConditionParameter,
+    // 勝利陣営のプレイヤーID。WinningJudgement の winner_mask から復元する
+    #[serde(default)]
+    pub winner_ids: Vec<String>,
+    // 最後に受理した proof の next state digest。次の proof はこの値を prev として公開入力に含める
+    #[serde(default)]
+    pub state_digest: [u8; 32],
 }
 
 // 計算結果を管理する構造体群
@@ -55,6 +66,8 @@ pub struct Game {
 pub struct ComputationResults {
     pub role_assignment: Option<ComputationEntry<RoleAssignmentComputationResult>>,
     pub divination: Vec<ComputationEntry<DivinationComputationResult>>,
+    #[serde(default)]
+    pub medium: Vec<ComputationEntry<MediumComputationResult>>,
 }
 
 impl Default for ComputationResults {
@@ -62,6 +75,7 @@ impl Default for ComputationResults {
         ComputationResults {
             role_assignment: None,
             divination: Vec::new(),
+            medium: Vec::new(),
         }
     }
 }
@@ -93,6 +107,12 @@ pub struct DivinationComputationResult {
         <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::Ciphertext,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediumComputationResult {
+    pub ciphertext:
+        <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::Ciphertext,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum GamePhase {
     Waiting,              // ゲーム開始前
@@ -109,6 +129,7 @@ pub enum GameResult {
     InProgress,
     VillagerWin,
     WerewolfWin,
+    FoxWin,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -158,6 +179,9 @@ pub enum ClientRequestType {
     WinningJudge(ProverInfo),
     RoleAssignment(ProverInfo),
     KeyPublicize(ProverInfo),
+    Attack(ProverInfo),
+    NightResolution(ProverInfo),
+    Medium(ProverInfo),
 }
 
 impl ClientRequestType {
@@ -167,7 +191,10 @@ impl ClientRequestType {
             | ClientRequestType::AnonymousVoting(info)
             | ClientRequestType::WinningJudge(info)
             | ClientRequestType::RoleAssignment(info)
-            | ClientRequestType::KeyPublicize(info) => info.prover_count,
+            | ClientRequestType::KeyPublicize(info)
+            | ClientRequestType::Attack(info)
+            | ClientRequestType::NightResolution(info)
+            | ClientRequestType::Medium(info) => info.prover_count,
         }
     }
 
@@ -177,7 +204,10 @@ impl ClientRequestType {
             | ClientRequestType::AnonymousVoting(info)
             | ClientRequestType::WinningJudge(info)
             | ClientRequestType::RoleAssignment(info)
-            | ClientRequestType::KeyPublicize(info) => info.user_id.as_str(),
+            | ClientRequestType::KeyPublicize(info)
+            | ClientRequestType::Attack(info)
+            | ClientRequestType::NightResolution(info)
+            | ClientRequestType::Medium(info) => info.user_id.as_str(),
         }
     }
 
@@ -187,7 +217,10 @@ impl ClientRequestType {
             | ClientRequestType::AnonymousVoting(info)
             | ClientRequestType::WinningJudge(info)
             | ClientRequestType::RoleAssignment(info)
-            | ClientRequestType::KeyPublicize(info) => info.public_key.as_deref(),
+            | ClientRequestType::KeyPublicize(info)
+            | ClientRequestType::Attack(info)
+            | ClientRequestType::NightResolution(info)
+            | ClientRequestType::Medium(info) => info.public_key.as_deref(),
         }
     }
 
@@ -205,6 +238,9 @@ impl ClientRequestType {
             ClientRequestType::WinningJudge(_) => ProofTypeKey::WinningJudge,
             ClientRequestType::RoleAssignment(_) => ProofTypeKey::RoleAssignment,
             ClientRequestType::KeyPublicize(_) => ProofTypeKey::KeyPublicize,
+            ClientRequestType::Attack(_) => ProofTypeKey::Attack,
+            ClientRequestType::NightResolution(_) => ProofTypeKey::NightResolution,
+            ClientRequestType::Medium(_) => ProofTypeKey::Medium,
         }
     }
 }
@@ -216,6 +252,9 @@ pub enum ProofTypeKey {
     AnonymousVoting,
     WinningJudge,
     KeyPublicize,
+    Attack,
+    NightResolution,
+    Medium,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -336,6 +375,49 @@ pub(crate) fn try_convert_to_identifier(
                     .collect::<Result<Vec<_>, _>>()?;
                 Ok(CircuitEncryptedInputIdentifier::KeyPublicize(items))
             }
+            Attack(_) if rest.iter().all(|r| matches!(r, Attack(_))) => {
+                let items = requests
+                    .into_iter()
+                    .map(|r| {
+                        if let Attack(d) = r {
+                            serde_json::from_str(&d.encrypted_data)
+                                .map_err(|e| format!("Failed to deserialize AttackOutput: {}", e))
+                        } else {
+                            Err("Unexpected request type in Attack batch".to_string())
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(CircuitEncryptedInputIdentifier::Attack(items))
+            }
+            NightResolution(_) if rest.iter().all(|r| matches!(r, NightResolution(_))) => {
+                let items = requests
+                    .into_iter()
+                    .map(|r| {
+                        if let NightResolution(d) = r {
+                            serde_json::from_str(&d.encrypted_data).map_err(|e| {
+                                format!("Failed to deserialize NightResolutionOutput: {}", e)
+                            })
+                        } else {
+                            Err("Unexpected request type in NightResolution batch".to_string())
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(CircuitEncryptedInputIdentifier::NightResolution(items))
+            }
+            Medium(_) if rest.iter().all(|r| matches!(r, Medium(_))) => {
+                let items = requests
+                    .into_iter()
+                    .map(|r| {
+                        if let Medium(d) = r {
+                            serde_json::from_str(&d.encrypted_data)
+                                .map_err(|e| format!("Failed to deserialize MediumOutput: {}", e))
+                        } else {
+                            Err("Unexpected request type in Medium batch".to_string())
+                        }
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(CircuitEncryptedInputIdentifier::Medium(items))
+            }
             _ => Err("ClientRequestType variants are mixed; cannot convert".to_string()),
         },
         None => Err("Empty request list".to_string()),
@@ -388,6 +470,9 @@ impl Game {
             phase_timer_paused_at: None,
             phase_timer_paused_total_seconds: 0,
             grouping_parameter,
+            win_condition: WinConditionParameter::default(),
+            winner_ids: Vec::new(),
+            state_digest: [0u8; 32],
         }
     }
 
@@ -431,6 +516,37 @@ impl Game {
         });
     }
 
+    pub fn has_medium_for_current_phase(&self) -> bool {
+        self.computation_results
+            .medium
+            .iter()
+            .any(|result| result.phase == self.phase && result.day_count == self.day_count)
+    }
+
+    // 霊媒結果を履歴に追加
+    pub fn save_medium_result(
+        &mut self,
+        id: String,
+        ciphertext: <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::Ciphertext,
+    ) {
+        self.computation_results.medium.push(ComputationEntry {
+            id,
+            computed_at: Utc::now(),
+            phase: self.phase.clone(),
+            day_count: self.day_count,
+            result: MediumComputationResult { ciphertext },
+        });
+    }
+
+    // 生存者の bitmask (bit i = players[i])。投票・占い・勝敗判定の公開入力と突き合わせる
+    pub fn alive_mask(&self) -> u32 {
+        self.players
+            .iter()
+            .enumerate()
+            .filter(|(_, player)| !player.is_dead)
+            .fold(0, |mask, (index, _)| mask | (1 << index))
+    }
+
     // より厳密な占い可能性チェック
     pub fn can_perform_divination(&self) -> bool {
         self.phase == GamePhase::Night && !self.has_divination_for_current_phase()
@@ -553,6 +669,40 @@ impl Game {
         Ok(())
     }
 
+    // 襲撃先 id は生存プレイヤー内のインデックス。
+    fn register_attack_by_alive_index(&mut self, alive_index: usize) -> Result<(), String> {
+        let target_id = self
+            .players
+            .iter()
+            .filter(|player| !player.is_dead)
+            .nth(alive_index)
+            .map(|player| player.id.clone())
+            .ok_or_else(|| format!("Invalid victim index: {}", alive_index))?;
+
+        self.apply_attack(&target_id)
+    }
+
+    // 襲撃先 id は players のインデックス。死亡者は襲撃先にできない。
+    fn register_attack_by_index(&mut self, index: usize) -> Result<(), String> {
+        let target_id = self
+            .players
+            .get(index)
+            .filter(|player| !player.is_dead)
+            .map(|player| player.id.clone())
+            .ok_or_else(|| format!("Invalid victim index: {}", index))?;
+
+        self.apply_attack(&target_id)
+    }
+
+    // 襲撃は占い処理の完了時にまとめて適用する。既に昼に進んでいれば即時に適用する。
+    fn apply_attack(&mut self, target_id: &str) -> Result<(), String> {
+        self.register_attack(target_id)?;
+        if self.phase != GamePhase::Night && self.phase != GamePhase::DivinationProcessing {
+            self.resolve_night_actions();
+        }
+        Ok(())
+    }
+
     // pub fn divine_player(&self, target_id: &str) -> Result<String, String> {
     //     let target = self
     //         .players
@@ -649,6 +799,7 @@ impl Game {
             GamePhase::Finished => match self.result {
                 GameResult::VillagerWin => "Villagers win!",
                 GameResult::WerewolfWin => "Werewolves win!",
+                GameResult::FoxWin => "The fox wins!",
                 GameResult::InProgress => "Game has ended.",
             },
             GamePhase::Waiting => "Waiting for game to start.",
@@ -806,7 +957,46 @@ impl Game {
                     self.batch_request.batch_id, output
                 );
 
-                if app_state.blockchain_client.is_enabled() {
+                if let Err(e) = check_manifest_hash(
+                    CONFIG.groth16_manifest_hash.as_deref(),
+                    output.manifest_hash.as_deref(),
+                ) {
+                    self.batch_request.status = BatchStatus::Failed;
+                    self.chat_log
+                        .add_system_message(format!("Proof rejected: {}", e));
+                    return;
+                }
+
+                if !app_state.blockchain_client.verifies_proofs_onchain() {
+                    match app_state
+                        .groth16_verifier
+                        .verify_output(&identifier, &output)
+                    {
+                        Ok(Some(true)) | Ok(None) => {}
+                        Ok(Some(false)) => {
+                            self.batch_request.status = BatchStatus::Failed;
+                            self.chat_log.add_system_message(
+                                "Proof rejected: Groth16 verification failed.".to_string(),
+                            );
+                            return;
+                        }
+                        Err(e) => {
+                            self.batch_request.status = BatchStatus::Failed;
+                            self.chat_log
+                                .add_system_message(format!("Proof rejected: {}", e));
+                            return;
+                        }
+                    }
+                }
+
+                // TODO: Attack / NightResolution / Medium の verifier をデプロイしたらオンチェーン検証の対象にする
+                let is_onchain_circuit = !matches!(
+                    identifier,
+                    CircuitEncryptedInputIdentifier::Attack(_)
+                        | CircuitEncryptedInputIdentifier::NightResolution(_)
+                        | CircuitEncryptedInputIdentifier::Medium(_)
+                );
+                if app_state.blockchain_client.is_enabled() && is_onchain_circuit {
                     let proof_id = compute_proof_id(&self.batch_request.batch_id);
                     let game_id = compute_game_id(&self.room_id);
                     let Some(circuit_profile) = identifier.circuit_profile() else {
@@ -841,6 +1031,13 @@ impl Game {
                         CircuitEncryptedInputIdentifier::KeyPublicize(_) => {
                             (ChainProofType::KeyPublicize, "KeyPublicize")
                         }
+                        CircuitEncryptedInputIdentifier::Attack(_)
+                        | CircuitEncryptedInputIdentifier::NightResolution(_) => {
+                            unreachable!("night action proofs are not verified on-chain yet")
+                        }
+                        CircuitEncryptedInputIdentifier::Medium(_) => {
+                            unreachable!("medium proofs are not verified on-chain yet")
+                        }
                     };
                     let player_count: u8 = match u8::try_from(circuit_profile.player_count()) {
                         Ok(v) => v,
@@ -894,6 +1091,7 @@ impl Game {
                             proof_id,
                             game_id,
                             proof_type,
+                            output.scheme,
                             player_count,
                             werewolf_count,
                             &proof_data,
@@ -926,6 +1124,48 @@ impl Game {
                     }
                 }
 
+                if let Some(public_inputs) = output.public_inputs.as_deref() {
+                    match next_state_digest(&self.state_digest, public_inputs) {
+                        Ok(next) => self.state_digest = next,
+                        Err(e) => {
+                            self.batch_request.status = BatchStatus::Failed;
+                            self.chat_log
+                                .add_system_message(format!("Proof rejected: {}", e));
+                            return;
+                        }
+                    }
+                }
+
+                // ゲーム終了後にオフチェーンで検証し直せるよう、受理した proof を残す
+                if let (Some(circuit_profile), Some(proof)) = (
+                    identifier.circuit_profile(),
+                    output.proof.as_deref().filter(|bytes| !bytes.is_empty()),
+                ) {
+                    let vk_hash = match output.scheme {
+                        ProofScheme::Groth16 => {
+                            app_state.groth16_verifier.vk_hash(&circuit_profile)
+                        }
+                        ProofScheme::Marlin => None,
+                    };
+                    // ゲーム状態の API では返さないよう、Game とは別に保存する
+                    let record = ProofRecord {
+                        batch_id: self.batch_request.batch_id.clone(),
+                        phase: self.phase.clone(),
+                        day_count: self.day_count,
+                        circuit_profile,
+                        instance_mode: identifier.instance_mode(),
+                        scheme: output.scheme,
+                        proof: encode_hex(proof),
+                        public_inputs: encode_hex(
+                            output.public_inputs.as_deref().unwrap_or_default(),
+                        ),
+                        vk_hash,
+                        manifest_hash: output.manifest_hash.clone(),
+                        state_digest: bytes32_to_hex(&self.state_digest),
+                    };
+                    app_state.record_proof(&self.room_id, record).await;
+                }
+
                 // プルーフ生成成功時の処理
                 // 例: WebSocketで結果をクライアントに通知
                 match identifier {
@@ -1040,25 +1280,17 @@ impl Game {
                         println!("Deserialized target_id: {:?}", target_id);
 
                         // 2. Fr型のtarget_idをusize型のインデックスとして解釈
-                        // Note: これはFr型の値がプレイヤーの配列のインデックスとして
-                        // 適切な範囲内であることを前提としています
+                        // 投票回路は死亡者を含む全プレイヤー分の行を持つので、
+                        // target_id はそのまま players のインデックスになる
                         let target_index = {
-                            let alive_player_indices = self
-                                .players
-                                .iter()
-                                .enumerate()
-                                .filter(|(_, player)| !player.is_dead)
-                                .map(|(index, _)| index)
-                                .collect::<Vec<_>>();
-
                             // Fr型からBigUintに変換し、usizeに変換
                             let bytes = target_id.into_repr().to_bytes_le();
-                            let alive_index = bytes[0] as usize; // 最初のバイトをインデックスとして使用
-                            if alive_index >= alive_player_indices.len() {
-                                println!("Invalid alive player index: {}", alive_index);
+                            let index = bytes[0] as usize; // 最初のバイトをインデックスとして使用
+                            if index >= self.players.len() || self.players[index].is_dead {
+                                println!("Invalid voting target index: {}", index);
                                 return;
                             }
-                            alive_player_indices[alive_index]
+                            index
                         };
 
                         println!("Target index for voting: {}", target_index);
@@ -1121,14 +1353,26 @@ impl Game {
                     CircuitEncryptedInputIdentifier::WinningJudge(_items) => {
                         // itemsを処理する
 
-                        let game_state: Fr = match output.value {
-                            Some(bytes) => match CanonicalDeserialize::deserialize(&*bytes) {
-                                Ok(state) => state,
-                                Err(e) => {
-                                    println!("Failed to deserialize game_state: {}", e);
-                                    return;
-                                }
-                            },
+                        // game_state の後ろに勝利陣営の winner_mask が続く
+                        let (game_state, winner_mask): (Fr, Fr) = match output.value {
+                            Some(bytes) => {
+                                let mut reader = &*bytes;
+                                let game_state = match Fr::deserialize(&mut reader) {
+                                    Ok(state) => state,
+                                    Err(e) => {
+                                        println!("Failed to deserialize game_state: {}", e);
+                                        return;
+                                    }
+                                };
+                                let winner_mask = match Fr::deserialize(&mut reader) {
+                                    Ok(mask) => mask,
+                                    Err(e) => {
+                                        println!("Failed to deserialize winner_mask: {}", e);
+                                        return;
+                                    }
+                                };
+                                (game_state, winner_mask)
+                            }
                             None => {
                                 println!("No output value found");
                                 return;
@@ -1137,20 +1381,35 @@ impl Game {
 
                         // 状態をゲームの結果に反映
 
-                        let result = if game_state == Fr::from(1u32) {
-                            GameResult::WerewolfWin
-                        } else if game_state == Fr::from(2u32) {
-                            GameResult::VillagerWin
-                        } else {
-                            self.chat_log
-                                .add_system_message("The game continues.".to_string());
-                            GameResult::InProgress
+                        let result = match WinningJudgeOutcome::from_field(game_state) {
+                            Some(WinningJudgeOutcome::WerewolfWin) => GameResult::WerewolfWin,
+                            Some(WinningJudgeOutcome::VillagerWin) => GameResult::VillagerWin,
+                            Some(WinningJudgeOutcome::VillagerWinByDayLimit) => {
+                                self.chat_log.add_system_message(format!(
+                                    "Day {} has been reached. The werewolves ran out of time.",
+                                    self.day_count
+                                ));
+                                GameResult::VillagerWin
+                            }
+                            Some(WinningJudgeOutcome::FoxWin) => GameResult::FoxWin,
+                            Some(WinningJudgeOutcome::Continue) => {
+                                self.chat_log
+                                    .add_system_message("The game continues.".to_string());
+                                GameResult::InProgress
+                            }
+                            None => {
+                                println!("Unknown game_state: {}", game_state);
+                                return;
+                            }
                         };
 
                         if result != GameResult::InProgress {
                             let winner_message = match result {
                                 GameResult::VillagerWin => "Villagers win!",
                                 GameResult::WerewolfWin => "Werewolves win!",
+                                GameResult::FoxWin => {
+                                    "The fox survived to the end and steals the victory!"
+                                }
                                 GameResult::InProgress => unreachable!(),
                             };
 
@@ -1161,6 +1420,16 @@ impl Game {
                                 ChatMessageType::System,
                             ));
 
+                            // bit i = players[i]。狂人は人狼陣営として含まれる
+                            let winner_mask = winner_mask.into_repr();
+                            self.winner_ids = self
+                                .players
+                                .iter()
+                                .enumerate()
+                                .filter(|(i, _)| winner_mask.get_bit(*i))
+                                .map(|(_, p)| p.id.clone())
+                                .collect();
+
                             self.change_phase(GamePhase::Finished);
                         }
 
@@ -1177,6 +1446,7 @@ impl Game {
                         let result_data = serde_json::json!({
                             "game_result": result,
                             "alive_players": alive_players,
+                            "winner_ids": self.winner_ids,
                             "game_state_value": game_state.into_repr().to_string(),
                             "status": "completed"
                         });
@@ -1223,6 +1493,32 @@ impl Game {
 
                         println!("Received {} encrypted role shares", encrypted_shares.len());
 
+                        // 後の回路で役職を開示できるよう、証明済みの役職コミットメントを公開する
+                        let role_commitment = match output.public_inputs.as_deref() {
+                            Some(public_inputs) => role_commitments_from_public_inputs(
+                                public_inputs,
+                                player_order.len(),
+                            ),
+                            None => Err("proof output has no public inputs".to_string()),
+                        };
+                        match (role_commitment, self.crypto_parameters.as_mut()) {
+                            (Ok(role_commitment), Some(crypto_params)) => {
+                                crypto_params.role_commitment = role_commitment;
+                            }
+                            (Ok(_), None) => {
+                                println!("WARNING: crypto_parameters is None, cannot store role commitments");
+                            }
+                            (Err(e), _) => {
+                                println!("RoleAssignment failed: {}", e);
+                                self.batch_request.status = BatchStatus::Failed;
+                                self.chat_log.add_system_message(
+                                    "Role assignment failed: role commitments were missing."
+                                        .to_string(),
+                                );
+                                return;
+                            }
+                        }
+
                         let mut required_shares_by_player =
                             std::collections::HashMap::<String, usize>::new();
                         for share in &encrypted_shares {
@@ -1380,6 +1676,134 @@ impl Game {
                             "Fortune teller public key has been generated via MPC.".to_string(),
                         );
                     }
+                    CircuitEncryptedInputIdentifier::Attack(_items) => {
+                        println!("Attack process is starting...");
+                        let victim_id: Fr = match output.value {
+                            Some(bytes) => match CanonicalDeserialize::deserialize(&*bytes) {
+                                Ok(id) => id,
+                                Err(e) => {
+                                    println!("Failed to deserialize victim_id: {}", e);
+                                    return;
+                                }
+                            },
+                            None => {
+                                println!("No output value found");
+                                return;
+                            }
+                        };
+
+                        let bytes = victim_id.into_repr().to_bytes_le();
+                        let victim_index = bytes[0] as usize;
+                        // AttackCircuit は死亡者の行も含むので、victim_index は players のインデックス
+                        if let Err(e) = self.register_attack_by_index(victim_index) {
+                            println!("Failed to register attack: {}", e);
+                            return;
+                        }
+
+                        println!(
+                            "Attack processed successfully for victim index: {}",
+                            victim_index
+                        );
+                        self.chat_log.add_system_message(
+                            "The werewolves have chosen their target.".to_string(),
+                        );
+                    }
+                    CircuitEncryptedInputIdentifier::NightResolution(items) => {
+                        println!("NightResolution process is starting...");
+                        let dead_id: Fr = match output.value {
+                            Some(bytes) => match CanonicalDeserialize::deserialize(&*bytes) {
+                                Ok(id) => id,
+                                Err(e) => {
+                                    println!("Failed to deserialize dead_id: {}", e);
+                                    return;
+                                }
+                            },
+                            None => {
+                                println!("No output value found");
+                                return;
+                            }
+                        };
+
+                        // 誰も死亡しなかった場合は player_num が出力される
+                        let bytes = dead_id.into_repr().to_bytes_le();
+                        let dead_index = bytes[0] as usize;
+                        let player_num = items
+                            .first()
+                            .map(|item| item.public_input.player_num)
+                            .unwrap_or_default();
+                        if dead_index == player_num {
+                            println!("NightResolution processed: nobody died");
+                        } else {
+                            if let Err(e) = self.register_attack_by_alive_index(dead_index) {
+                                println!("Failed to register attack: {}", e);
+                                return;
+                            }
+                            println!(
+                                "NightResolution processed successfully for dead index: {}",
+                                dead_index
+                            );
+                        }
+                        self.chat_log.add_system_message(
+                            "The night actions have been resolved.".to_string(),
+                        );
+                    }
+                    CircuitEncryptedInputIdentifier::Medium(_items) => {
+                        let medium_ciphertext = match output.value {
+                            Some(bytes) => match CanonicalDeserialize::deserialize(&*bytes) {
+                                Ok(result) => result,
+                                Err(e) => {
+                                    println!("Failed to deserialize medium result: {}", e);
+                                    self.chat_log.add_system_message(
+                                        "Failed to process medium result.".to_string(),
+                                    );
+                                    return;
+                                }
+                            },
+                            None => {
+                                println!("No output value found");
+                                self.chat_log
+                                    .add_system_message("Medium result not found.".to_string());
+                                return;
+                            }
+                        };
+
+                        self.save_medium_result(
+                            self.batch_request.batch_id.clone(),
+                            medium_ciphertext,
+                        );
+                        println!("Medium result processed successfully.");
+                        self.chat_log
+                            .add_system_message("Medium result has been generated.".to_string());
+
+                        // 全プレイヤーに送信し、霊媒師だけが復号できる
+                        let Some(latest_medium) = self.computation_results.medium.last() else {
+                            self.batch_request.status = BatchStatus::Failed;
+                            self.chat_log.add_system_message(
+                                "Medium result was not persisted correctly.".to_string(),
+                            );
+                            return;
+                        };
+                        let result_data = serde_json::json!({
+                            "ciphertext": serde_json::to_value(&latest_medium.result.ciphertext).unwrap_or_default(),
+                            "phase": latest_medium.phase,
+                            "day_count": latest_medium.day_count,
+                            "performed_at": latest_medium.computed_at,
+                            "status": "ready"
+                        });
+
+                        if let Err(e) = app_state
+                            .broadcast_computation_result(
+                                &self.room_id,
+                                "medium",
+                                result_data,
+                                None,
+                                &self.batch_request.batch_id,
+                            )
+                            .await
+                        {
+                            println!("Failed to broadcast medium result: {}", e);
+                        }
+                    }
                 }
 
                 self.batch_request.status = BatchStatus::Completed;
@@ -1398,6 +1822,63 @@ impl Game {
     }
 }
 
+// 公開入力 (uint256 ワードの ABI エンコード) の末尾 4 ワードは alive_mask, day_count,
+// prev / next state digest。prev が現在の digest と一致するときだけ next を返す
+pub(crate) fn next_state_digest(
+    current: &[u8; 32],
+    public_inputs: &[u8],
+) -> Result<[u8; 32], String> {
+    if public_inputs.len() < 64 || public_inputs.len() % 32 != 0 {
+        return Err("public inputs do not contain the state digest".to_string());
+    }
+    let (prev, next) = public_inputs[public_inputs.len() - 64..].split_at(32);
+    if prev != current {
+        return Err("proof does not continue from the latest state digest".to_string());
+    }
+    Ok(next.try_into().expect("digest word is 32 bytes"))
+}
+
+type PedersenCommitmentOutput =
+    <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::Output;
+
+// 役職配布の公開入力は player_commitment, role_commitment の順に (x, y) が並び、
+// その後ろに state digest などが続く
+pub(crate) fn role_commitments_from_public_inputs(
+    public_inputs: &[u8],
+    num_players: usize,
+) -> Result<Vec<PedersenCommitmentOutput>, String> {
+    if public_inputs.len() % 32 != 0 || public_inputs.len() < (4 * num_players + 4) * 32 {
+        return Err("public inputs do not contain the role commitments".to_string());
+    }
+    public_inputs
+        .chunks(32)
+        .map(Fr::from_be_bytes_mod_order)
+        .collect::<Vec<_>>()[2 * num_players..4 * num_players]
+        .chunks(2)
+        .map(|point| {
+            let commitment = PedersenCommitmentOutput::new(point[0], point[1]);
+            if commitment.is_on_curve() {
+                Ok(commitment)
+            } else {
+                Err("role commitment is not on the curve".to_string())
+            }
+        })
+        .collect()
+}
+
+// オンチェーン検証の前に、想定外の鍵で作られた証明を弾く
+fn check_manifest_hash(expected: Option<&str>, actual: Option<&str>) -> Result<(), String> {
+    match (expected, actual) {
+        (None, _) => Ok(()),
+        (Some(expected), Some(actual)) if expected.eq_ignore_ascii_case(actual) => Ok(()),
+        (Some(_), Some(actual)) => Err(format!(
+            "proof was generated with Groth16 manifest {}",
+            actual
+        )),
+        (Some(_), None) => Err("proof does not report a Groth16 manifest hash".to_string()),
+    }
+}
+
 impl std::fmt::Display for Game {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -1411,6 +1892,7 @@ impl std::fmt::Display for Game {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ark_std::UniformRand;
     use chrono::Duration;
     use std::collections::BTreeMap;
 
@@ -1457,6 +1939,70 @@ mod tests {
         )
     }
 
+    #[test]
+    fn alive_mask_tracks_dead_players() {
+        let mut game = make_test_game();
+        assert_eq!(game.alive_mask(), 0b1111);
+
+        game.players[1].is_dead = true;
+        game.players[3].is_dead = true;
+        assert_eq!(game.alive_mask(), 0b0101);
+    }
+
+    #[test]
+    fn next_state_digest_requires_latest_prev() {
+        let current = [1u8; 32];
+        let mut public_inputs = vec![9u8; 32];
+        public_inputs.extend_from_slice(&current);
+        public_inputs.extend_from_slice(&[2u8; 32]);
+        assert_eq!(next_state_digest(&current, &public_inputs), Ok([2u8; 32]));
+
+        assert!(next_state_digest(&[0u8; 32], &public_inputs).is_err());
+        assert!(next_state_digest(&current, &public_inputs[..32]).is_err());
+    }
+
+    #[test]
+    fn role_commitments_are_read_after_player_commitments() {
+        let mut rng = ark_std::test_rng();
+        let pedersen_param =
+            <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::setup(&mut rng)
+                .unwrap();
+        let commitments = (0..4u64)
+            .map(|i| {
+                <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::commit(
+                    &pedersen_param,
+                    &Fr::from(i).into_repr().to_bytes_le(),
+                    &<Fr as LocalOrMPC<Fr>>::PedersenRandomness::rand(&mut rng),
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // 2 人分: player_commitment 2 つ、role_commitment 2 つ、alive_mask、day_count、prev、next
+        let mut public_inputs = Vec::new();
+        for commitment in &commitments {
+            public_inputs.extend(commitment.x.into_repr().to_bytes_be());
+            public_inputs.extend(commitment.y.into_repr().to_bytes_be());
+        }
+        public_inputs.extend([0u8; 128]);
+
+        let role_commitment = role_commitments_from_public_inputs(&public_inputs, 2).unwrap();
+        assert_eq!(role_commitment, commitments[2..].to_vec());
+
+        assert!(role_commitments_from_public_inputs(&public_inputs[..64], 2).is_err());
+        let mut off_curve = public_inputs.clone();
+        off_curve[4 * 32 + 31] ^= 1;
+        assert!(role_commitments_from_public_inputs(&off_curve, 2).is_err());
+    }
+
+    #[test]
+    fn manifest_hash_must_match_expected() {
+        assert_eq!(check_manifest_hash(None, None), Ok(()));
+        assert_eq!(check_manifest_hash(Some("ab12"), Some("AB12")), Ok(()));
+        assert!(check_manifest_hash(Some("ab12"), Some("cd34")).is_err());
+        assert!(check_manifest_hash(Some("ab12"), None).is_err());
+    }
+
     #[test]
     fn pause_resume_accumulates_paused_duration() {
         let mut game = make_test_game();
@@ -1516,6 +2062,10 @@ pub struct CryptoParameters {
     pub pedersen_param: <<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::Parameters,
     pub player_commitment:
         Vec<<<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::Output>,
+    // 役職配布の公開入力から取り出した各プレイヤーの役職コミットメント
+    #[serde(default)]
+    pub role_commitment:
+        Vec<<<Fr as LocalOrMPC<Fr>>::PedersenComScheme as CommitmentScheme>::Output>,
     pub fortune_teller_public_key: Option<
         <<Fr as ElGamalLocalOrMPC<Fr>>::ElGamalScheme as AsymmetricEncryptionScheme>::PublicKey,
     >,
@@ -1528,6 +2078,7 @@ impl Clone for CryptoParameters {
         Self {
             pedersen_param: self.pedersen_param.clone(),
             player_commitment: self.player_commitment.clone(),
+            role_commitment: self.role_commitment.clone(),
             fortune_teller_public_key: self.fortune_teller_public_key,
             elgamal_param: self.elgamal_param.clone(),
         }
@@ -0,0 +1,64 @@
+use crate::blockchain::state_hash::{bytes32_to_hex, compute_game_id};
+use crate::models::game::{Game, GamePhase, GameResult};
+use mpc_algebra_wasm::{CircuitProfile, InstanceMode, ProofScheme};
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever the bundle layout changes.
+pub const PROOF_BUNDLE_VERSION: u32 = 1;
+
+/// A proof the server accepted, recorded in the order it was applied to the game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofRecord {
+    pub batch_id: String,
+    // proof を適用したときのフェーズと日数
+    pub phase: GamePhase,
+    pub day_count: u32,
+    pub circuit_profile: CircuitProfile,
+    pub instance_mode: InstanceMode,
+    pub scheme: ProofScheme,
+    /// `ProofOutput.proof` as 0x-hex (`verifyTx` calldata encoding).
+    pub proof: String,
+    /// `ProofOutput.public_inputs` as 0x-hex: the full instance as `uint256` words.
+    pub public_inputs: String,
+    /// Hash of the verifying key the server checked the proof with, if it had one.
+    #[serde(default)]
+    pub vk_hash: Option<String>,
+    #[serde(default)]
+    pub manifest_hash: Option<String>,
+    /// Game state digest after this proof. Unchanged for proofs without public inputs.
+    pub state_digest: String,
+}
+
+/// Every proof of a finished game with the state digest chain, so that players can
+/// re-verify the game off-chain (`zkw-verify`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProofBundle {
+    pub version: u32,
+    pub room_id: String,
+    pub game_id: String,
+    pub result: GameResult,
+    pub final_state_digest: String,
+    pub proofs: Vec<ProofRecord>,
+}
+
+impl ProofBundle {
+    /// `proofs` are the records the server kept for the game, in the order they were applied.
+    pub fn from_game(game: &Game, proofs: Vec<ProofRecord>) -> Self {
+        ProofBundle {
+            version: PROOF_BUNDLE_VERSION,
+            room_id: game.room_id.clone(),
+            game_id: bytes32_to_hex(&compute_game_id(&game.room_id)),
+            result: game.result.clone(),
+            final_state_digest: bytes32_to_hex(&game.state_digest),
+            proofs,
+        }
+    }
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+pub fn decode_hex(value: &str) -> Result<Vec<u8>, hex::FromHexError> {
+    hex::decode(value.strip_prefix("0x").unwrap_or(value))
+}
@@ -3,10 +3,12 @@ use std::fmt;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Role {
-    Villager, // 村人
-    Werewolf, // 人狼
-    Seer,     // 占い師
-    Guard,    // 騎士
+    Villager,  // 村人
+    Werewolf,  // 人狼
+    Seer,      // 占い師
+    Guard,     // 騎士
+    Medium,    // 霊媒師
+    Possessed, // 狂人
 }
 
 impl fmt::Display for Role {
@@ -16,6 +18,8 @@ impl fmt::Display for Role {
             Role::Werewolf => write!(f, "人狼"),
             Role::Seer => write!(f, "占い師"),
             Role::Guard => write!(f, "騎士"),
+            Role::Medium => write!(f, "霊媒師"),
+            Role::Possessed => write!(f, "狂人"),
         }
     }
 }
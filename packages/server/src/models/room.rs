@@ -1,6 +1,7 @@
 use super::chat::ChatLog;
 use super::player::Player;
 use chrono::{DateTime, Utc};
+use mpc_algebra_wasm::WinConditionParameter;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -11,11 +12,17 @@ pub struct RoleConfig {
     pub werewolf: usize,
     #[serde(rename = "Villager")]
     pub villager: usize,
+    #[serde(rename = "Medium", default)]
+    pub medium: usize,
+    #[serde(rename = "Possessed", default)]
+    pub possessed: usize,
+    #[serde(rename = "Guard", default)]
+    pub guard: usize,
 }
 
 impl RoleConfig {
     pub fn total_players(&self) -> usize {
-        self.seer + self.werewolf + self.villager
+        self.seer + self.werewolf + self.villager + self.medium + self.possessed + self.guard
     }
 }
 
@@ -31,6 +38,9 @@ pub struct RoomConfig {
     pub max_players: usize,
     pub role_config: RoleConfig,
     pub time_config: TimeConfig,
+    // 勝利条件 (過半数ルール・日数上限)。WinningJudgement の公開入力になる
+    #[serde(default)]
+    pub win_condition: WinConditionParameter,
 }
 
 impl Default for RoomConfig {
@@ -41,12 +51,16 @@ impl Default for RoomConfig {
                 seer: 1,
                 werewolf: 2,
                 villager: 6,
+                medium: 0,
+                possessed: 0,
+                guard: 0,
             },
             time_config: TimeConfig {
                 day_phase: 300,
                 night_phase: 120,
                 voting_phase: 90,
             },
+            win_condition: WinConditionParameter::default(),
         }
     }
 }
@@ -13,5 +13,6 @@ pub fn create_routes(state: AppState) -> Router {
         .nest("/api/game", game::routes(state.clone()))
         .nest("/api/users", user::routes(state.clone()))
         .nest("/api/nodes/keys", node::routes(state.clone()))
+        .route("/api/nodes/status", get(node::get_nodes_status))
         .route("/health", get(health::health_check))
 }
@@ -2,6 +2,7 @@ use crate::blockchain::state_hash::{compute_commitment_hash, compute_game_id, is
 use crate::models::game::{
     BatchRequest, ClientRequestType, ComputationResults, GamePhase, GameResult, NightActionRequest,
 };
+use crate::models::proof_bundle::ProofBundle;
 use crate::models::room::RoomStatus;
 use crate::services::game_service::initialize_crypto_parameters;
 use crate::services::zk_proof;
@@ -41,6 +42,8 @@ pub fn routes(state: AppState) -> Router {
                 .route("/end", post(end_game_handler))
                 // curl http://localhost:8080/api/game/{roomid}/state
                 .route("/state", get(get_game_state))
+                // 終了したゲームの proof 一式 (オフチェーン監査用)
+                .route("/bundle", get(get_proof_bundle))
                 // ゲームアクション
                 .nest(
                     "/actions",
@@ -94,6 +97,30 @@ pub async fn get_game_state(
     }
 }
 
+async fn get_proof_bundle(
+    State(state): State<AppState>,
+    Path(room_id): Path<String>,
+) -> impl IntoResponse {
+    let games = state.games.lock().await;
+    match games.get(&room_id) {
+        Some(game) if game.phase == GamePhase::Finished => {
+            let proofs = state.proof_records(&room_id).await;
+            (
+                StatusCode::OK,
+                Json(json!(ProofBundle::from_game(game, proofs))),
+            )
+        }
+        Some(_) => (
+            StatusCode::CONFLICT,
+            Json(json!({ "error": "Game has not finished yet" })),
+        ),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "Game not found" })),
+        ),
+    }
+}
+
 async fn end_game_handler(
     State(state): State<AppState>,
     Path(room_id): Path<String>,
@@ -477,6 +504,65 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_proof_records_are_only_served_from_bundle() {
+        use crate::models::proof_bundle::ProofRecord;
+        use http_body_util::BodyExt;
+        use mpc_algebra_wasm::{CircuitProfile, InstanceMode, ProofScheme};
+
+        setup_test_env();
+        let state = AppState::new();
+        let app = routes(state.clone());
+        let room_id = crate::services::room_service::create_room(state.clone(), None, None).await;
+
+        join_and_ready_players(&state, room_id, 4).await;
+        game_service::start_game(state.clone(), &room_id.to_string())
+            .await
+            .unwrap();
+        state
+            .record_proof(
+                &room_id.to_string(),
+                ProofRecord {
+                    batch_id: "batch-1".to_string(),
+                    phase: GamePhase::Night,
+                    day_count: 1,
+                    circuit_profile: CircuitProfile::Divination { player_count: 4 },
+                    instance_mode: InstanceMode::Full,
+                    scheme: ProofScheme::Groth16,
+                    proof: "0x01".to_string(),
+                    public_inputs: "0x".to_string(),
+                    vk_hash: None,
+                    manifest_hash: None,
+                    state_digest: "0x00".to_string(),
+                },
+            )
+            .await;
+        state
+            .games
+            .lock()
+            .await
+            .get_mut(&room_id.to_string())
+            .unwrap()
+            .phase = GamePhase::Finished;
+
+        let get_json = |uri: String| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let body = response.into_body().collect().await.unwrap().to_bytes();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+
+        let game_state = get_json(format!("/{}/state", room_id)).await;
+        assert!(!game_state.to_string().contains("batch-1"));
+
+        let bundle = get_json(format!("/{}/bundle", room_id)).await;
+        assert_eq!(bundle["proofs"][0]["batch_id"], "batch-1");
+    }
+
     #[tokio::test]
     async fn test_reset_game() {
         setup_test_env();
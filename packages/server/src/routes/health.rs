@@ -1,10 +1,29 @@
+use crate::services::node_status::fetch_cluster_status;
 use axum::Json;
 use serde_json::{json, Value};
 
 pub async fn health_check() -> Json<Value> {
+    let cluster = fetch_cluster_status().await;
+    let nodes: Vec<Value> = cluster
+        .nodes
+        .iter()
+        .map(|node| {
+            json!({
+                "url": node.url,
+                "reachable": node.reachable,
+                "ready": node.ready,
+            })
+        })
+        .collect();
+
     Json(json!({
-        "status": "ok",
+        "status": cluster.status,
         "timestamp": chrono::Utc::now().to_rfc3339(),
-        "service": "zk-werewolf-server"
+        "service": "zk-werewolf-server",
+        "mpc_nodes": {
+            "ready": cluster.ready_nodes,
+            "total": cluster.total_nodes,
+            "nodes": nodes,
+        }
     }))
 }
@@ -1,5 +1,6 @@
 use crate::{
     models::node::{ErrorResponse, NodeKey, RegisterKeyResponse},
+    services::node_status::{fetch_cluster_status, ClusterStatus},
     state::AppState,
 };
 use axum::{
@@ -67,6 +68,10 @@ pub async fn get_all_keys(State(state): State<AppState>) -> impl IntoResponse {
     (StatusCode::OK, Json(state.node_key_service.get_all_keys())).into_response()
 }
 
+pub async fn get_nodes_status() -> Json<ClusterStatus> {
+    Json(fetch_cluster_status().await)
+}
+
 pub fn routes(state: AppState) -> Router {
     Router::new()
         .route("/", post(register_key))
@@ -11,6 +11,7 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
+use mpc_algebra_wasm::WinConditionParameter;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize)]
@@ -22,6 +23,8 @@ pub struct CreateRoomRequest {
     pub role_config: Option<RoleConfig>,
     #[serde(default)]
     pub time_config: Option<TimeConfig>,
+    #[serde(default)]
+    pub win_condition: Option<WinConditionParameter>,
 }
 
 pub fn routes(state: AppState) -> Router {
@@ -60,6 +63,7 @@ pub async fn create_room(
     let room_config = if payload.max_players.is_some()
         || payload.role_config.is_some()
         || payload.time_config.is_some()
+        || payload.win_condition.is_some()
     {
         let max_players = payload.max_players.unwrap_or(9);
         Some(RoomConfig {
@@ -68,12 +72,16 @@ pub async fn create_room(
                 seer: 1,
                 werewolf: 2,
                 villager: max_players.saturating_sub(3),
+                medium: 0,
+                possessed: 0,
+                guard: 0,
             }),
             time_config: payload.time_config.unwrap_or(TimeConfig {
                 day_phase: 300,
                 night_phase: 120,
                 voting_phase: 90,
             }),
+            win_condition: payload.win_condition.unwrap_or_default(),
         })
     } else {
         None
@@ -192,6 +200,7 @@ mod tests {
             max_players: None,
             role_config: None,
             time_config: None,
+            win_condition: None,
         };
 
         let request = Request::builder()
@@ -1,6 +1,11 @@
 pub mod game_service;
+pub mod groth16_verifier;
 pub mod node_key;
+pub mod node_status;
+pub mod proof_bundle;
 pub mod proof_job_service;
 pub mod room_service;
+#[cfg(test)]
+pub(crate) mod test_circuits;
 pub mod user_service;
 pub mod zk_proof;
@@ -23,6 +23,16 @@ fn grouping_parameter_from_role_config(role_config: &RoleConfig) -> GroupingPara
         (role_config.werewolf, role_config.werewolf > 1),
     );
     map.insert(GroupingRole::Villager, (role_config.villager, false));
+    if role_config.medium > 0 {
+        map.insert(GroupingRole::Medium, (role_config.medium, false));
+    }
+    // 狂人は人狼仲間を知らないため、人狼グループとは別の単独役職として扱う
+    if role_config.possessed > 0 {
+        map.insert(GroupingRole::Possessed, (role_config.possessed, false));
+    }
+    if role_config.guard > 0 {
+        map.insert(GroupingRole::Guard, (role_config.guard, false));
+    }
     GroupingParameter::new(map)
 }
 
@@ -49,17 +59,20 @@ pub async fn start_game(state: AppState, room_id: &str) -> Result<String, String
         let _roles = assign_roles(room.players.len())?;
         let joined_players = room.players.len();
         let mut effective_role_config = room.room_config.role_config.clone();
-        if joined_players < effective_role_config.seer + effective_role_config.werewolf {
+        let special_role_count = effective_role_config.seer
+            + effective_role_config.werewolf
+            + effective_role_config.medium
+            + effective_role_config.possessed
+            + effective_role_config.guard;
+        if joined_players < special_role_count {
             return Err(format!(
-                "joined players ({}) are fewer than required special roles (seer + werewolf = {})",
-                joined_players,
-                effective_role_config.seer + effective_role_config.werewolf
+                "joined players ({}) are fewer than required special roles (seer + werewolf + medium + possessed + guard = {})",
+                joined_players, special_role_count
             ));
         }
 
         if effective_role_config.total_players() != joined_players {
-            effective_role_config.villager = joined_players
-                .saturating_sub(effective_role_config.seer + effective_role_config.werewolf);
+            effective_role_config.villager = joined_players.saturating_sub(special_role_count);
         }
 
         let grouping_parameter = grouping_parameter_from_role_config(&effective_role_config);
@@ -69,6 +82,7 @@ pub async fn start_game(state: AppState, room_id: &str) -> Result<String, String
             room.room_config.max_players,
             grouping_parameter,
         );
+        new_game.win_condition = room.room_config.win_condition;
 
         // 暗号パラメータの初期化
         initialize_crypto_parameters(&mut new_game);
@@ -332,11 +346,11 @@ fn extract_evm_player_addresses(game: &Game) -> Vec<String> {
 }
 
 fn extract_winner_addresses(game: &Game) -> Vec<String> {
-    // サーバー側では役職を保持していないため、結果判定後に生存者全員を暫定 winners として扱う。
-    game.players
+    // サーバー側では役職を保持していないため、WinningJudgement が公開した勝利陣営の生存者を winners とする。
+    game.winner_ids
         .iter()
-        .filter(|player| !player.is_dead && is_evm_address(&player.id))
-        .map(|player| player.id.clone())
+        .filter(|player_id| is_evm_address(player_id))
+        .cloned()
         .collect()
 }
 
@@ -353,23 +367,17 @@ pub async fn process_night_action(
         return Err("夜のアクションは夜にのみ実行できます".to_string());
     }
 
-    // プレイヤーの存在確認のみ実施（役職チェックは行わない）
+    // プレイヤーの存在確認
     let _player = game
         .players
         .iter()
         .find(|p| p.id == action_req.player_id)
         .ok_or("プレイヤーが見つかりません")?;
 
-    // 役職情報はクライアント側（MPC計算結果）で管理されるため、
-    // サーバーは役職チェックを行わず、アクションタイプに応じた処理のみ実施
     match &action_req.action {
-        NightAction::Attack { target_id } => {
-            // 人狼の襲撃処理
-            drop(games);
-            let mut games = state.games.lock().await;
-            let game = games.get_mut(room_id).ok_or("Game not found")?;
-            game.register_attack(target_id)?;
-            Ok("襲撃先を登録しました".to_string())
+        NightAction::Attack { .. } => {
+            // 平文の襲撃先は受け付けない。人狼かどうかと襲撃先は AttackCircuit の中で検証される。
+            Err("襲撃は Attack の証明リクエストとして送信してください".to_string())
         }
     }
 }
@@ -533,6 +541,7 @@ pub fn initialize_crypto_parameters(game: &mut Game) {
     game.crypto_parameters = Some(crate::models::game::CryptoParameters {
         pedersen_param,
         player_commitment,
+        role_commitment: Vec::new(),
         fortune_teller_public_key: None,
         elgamal_param,
     });
@@ -580,6 +589,9 @@ mod tests {
             seer: 1,
             werewolf: 1,
             villager: 2,
+            medium: 0,
+            possessed: 0,
+            guard: 0,
         };
         Game::new(
             "room-auto-advance-test".to_string(),
@@ -628,4 +640,20 @@ mod tests {
 
         assert!(!is_phase_due(&game, &time_config, now));
     }
+
+    #[test]
+    fn winner_addresses_come_from_winning_team_only() {
+        let mut game = make_game();
+        let werewolf = format!("0x{}", "a".repeat(40));
+        let possessed = format!("0x{}", "b".repeat(40));
+        let villager = format!("0x{}", "c".repeat(40));
+        game.players[0].id = werewolf.clone();
+        game.players[1].id = possessed.clone();
+        game.players[2].id = villager;
+
+        // 生存している村人は勝利陣営に含まれない
+        game.winner_ids = vec![werewolf.clone(), possessed.clone(), "p4".to_string()];
+
+        assert_eq!(extract_winner_addresses(&game), vec![werewolf, possessed]);
+    }
 }
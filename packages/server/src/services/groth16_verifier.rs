@@ -0,0 +1,441 @@
+use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_groth16::{prepare_verifying_key, verify_proof, PreparedVerifyingKey, Proof, VerifyingKey};
+use ark_serialize::CanonicalDeserialize;
+use mpc_algebra_wasm::{
+    CircuitEncryptedInputIdentifier, CircuitProfile, InstanceMode, ProofScheme,
+};
+use mpc_circuits::verifier_public_inputs;
+use std::collections::HashMap;
+use std::path::Path;
+use zk_mpc_node::{
+    check_proving_key_hash, groth16_data_dir, load_manifest, parse_profile_from_pk_filename,
+    pk_circuit_id, vk_hash, ProofOutput, ProvingKeyReader,
+};
+
+const WORD_LEN: usize = 32;
+// a (x, y), b (x.c0, x.c1, y.c0, y.c1), c (x, y)
+const PROOF_WORDS: usize = 8;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Groth16VerifyError {
+    #[error("missing proof bytes")]
+    MissingProof,
+    #[error("malformed proof: {0}")]
+    MalformedProof(String),
+    #[error("malformed public inputs: {0}")]
+    MalformedPublicInputs(String),
+    #[error("verifying key expects {expected} public inputs, got {actual}")]
+    PublicInputLength { expected: usize, actual: usize },
+    #[error("pairing check failed: {0}")]
+    Pairing(String),
+    #[error("no Groth16 verifying key is loaded for {0}")]
+    MissingVerifyingKey(String),
+}
+
+/// Checks proofs returned by the nodes with the same verifying keys as the on-chain verifiers.
+/// Used instead of the contracts when the blockchain backend is disabled or simulated.
+#[derive(Default)]
+pub struct Groth16Verifier {
+    by_profile: HashMap<CircuitProfile, PreparedVerifyingKey<Bn254>>,
+    // 開発用: 鍵のない circuit の proof を検証せずに受理する
+    allow_missing_keys: bool,
+}
+
+impl Groth16Verifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads the verifying key of every profile `.pk` in the nodes' Groth16 data dir.
+    /// Keys that fail to load are skipped, and proofs for their profiles are rejected.
+    pub fn load() -> Self {
+        let mut verifier = Self::new();
+        let data_dir = groth16_data_dir();
+        if let Err(e) = verifier.load_data_dir(&data_dir) {
+            tracing::warn!(
+                "Failed to load Groth16 verifying keys from {}: {}",
+                data_dir.display(),
+                e
+            );
+        }
+        tracing::info!(
+            "Groth16 verifier loaded {} verifying keys from {}.",
+            verifier.by_profile.len(),
+            data_dir.display()
+        );
+        verifier
+    }
+
+    /// Like [`Self::load`] for an explicit data dir, failing instead of logging when the
+    /// manifest or the directory cannot be read.
+    pub fn from_data_dir(data_dir: &Path) -> Result<Self, String> {
+        let mut verifier = Self::new();
+        verifier.load_data_dir(data_dir)?;
+        Ok(verifier)
+    }
+
+    fn load_data_dir(&mut self, data_dir: &Path) -> Result<(), String> {
+        if !data_dir.exists() {
+            return Ok(());
+        }
+        let manifest = load_manifest(data_dir).map_err(|e| e.to_string())?;
+
+        let mut paths = std::fs::read_dir(data_dir)
+            .map_err(|e| e.to_string())?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|s| s.to_str())
+                    .and_then(pk_circuit_id)
+                    .is_some()
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        for path in paths {
+            let Some(file_name) = path.file_name().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let Some(profile) = parse_profile_from_pk_filename(file_name) else {
+                continue;
+            };
+            // .pk と .pk.gz が両方あるときは名前順で先の .pk を使う
+            if self.by_profile.contains_key(&profile) {
+                continue;
+            }
+            let mut reader = match ProvingKeyReader::open(&path) {
+                Ok(reader) => reader,
+                Err(e) => {
+                    tracing::warn!("Skipping Groth16 key {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            // ProvingKey は先頭に vk を持つので、vk の分だけ読めばよい
+            let vk = match VerifyingKey::<Bn254>::deserialize_uncompressed(&mut reader) {
+                Ok(vk) => vk,
+                Err(e) => {
+                    tracing::warn!("Skipping Groth16 key {}: {:?}", path.display(), e);
+                    continue;
+                }
+            };
+            if let Some(manifest) = &manifest {
+                let circuit_id = pk_circuit_id(file_name).unwrap_or(file_name);
+                // manifest と照合するときだけ残りを読んでハッシュを取る
+                let checked = manifest
+                    .entry(circuit_id)
+                    .filter(|entry| entry.profile == profile)
+                    .map(|entry| {
+                        let pk_hash = reader.finish().map_err(|e| e.to_string())?;
+                        check_proving_key_hash(entry, &pk_hash, &vk).map_err(|e| e.to_string())
+                    });
+                match checked {
+                    Some(Ok(())) => {}
+                    Some(Err(e)) => {
+                        tracing::warn!("Skipping Groth16 key {}: {}", path.display(), e);
+                        continue;
+                    }
+                    None => {
+                        tracing::warn!(
+                            "Skipping Groth16 key {}: not listed in the manifest",
+                            path.display()
+                        );
+                        continue;
+                    }
+                }
+            }
+            self.insert(profile, &vk);
+        }
+        Ok(())
+    }
+
+    /// Accepts proofs of profiles without a loaded key unchecked instead of rejecting them.
+    /// Only for local development without Groth16 keys.
+    pub fn with_missing_keys_allowed(mut self, allow: bool) -> Self {
+        self.allow_missing_keys = allow;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_profile.is_empty()
+    }
+
+    pub fn insert(&mut self, profile: CircuitProfile, vk: &VerifyingKey<Bn254>) {
+        self.by_profile.insert(profile, prepare_verifying_key(vk));
+    }
+
+    pub fn verifying_key(&self, profile: &CircuitProfile) -> Option<&PreparedVerifyingKey<Bn254>> {
+        self.by_profile.get(profile)
+    }
+
+    /// Manifest hash of the loaded verifying key for `profile`.
+    pub fn vk_hash(&self, profile: &CircuitProfile) -> Option<String> {
+        self.verifying_key(profile).map(|pvk| vk_hash(&pvk.vk))
+    }
+
+    /// Same contract as `BlockchainClient::verify_proof`: `Ok(None)` when the proof is not a
+    /// Groth16 proof, otherwise whether it verifies. A Groth16 proof whose profile has no
+    /// loaded key is an error unless missing keys are allowed.
+    pub fn verify_output(
+        &self,
+        identifier: &CircuitEncryptedInputIdentifier,
+        output: &ProofOutput,
+    ) -> Result<Option<bool>, Groth16VerifyError> {
+        if output.scheme != ProofScheme::Groth16 {
+            return Ok(None);
+        }
+        let profile = identifier.circuit_profile();
+        let Some(pvk) = profile.and_then(|profile| self.by_profile.get(&profile)) else {
+            let profile = profile
+                .map(|profile| format!("{:?}", profile))
+                .unwrap_or_else(|| "an empty request".to_string());
+            if self.allow_missing_keys {
+                tracing::warn!(
+                    "Accepting a Groth16 proof for {} without verification (ALLOW_UNVERIFIED_PROOFS)",
+                    profile
+                );
+                return Ok(None);
+            }
+            return Err(Groth16VerifyError::MissingVerifyingKey(profile));
+        };
+        let proof = match output.proof.as_deref() {
+            Some(bytes) if !bytes.is_empty() => bytes,
+            _ => return Err(Groth16VerifyError::MissingProof),
+        };
+        // KeyPublicize などは公開入力なしで返ることがある
+        let public_inputs = output.public_inputs.as_deref().unwrap_or_default();
+        verify_encoded_proof(pvk, identifier.instance_mode(), proof, public_inputs).map(Some)
+    }
+}
+
+/// Verifies a proof in the calldata encoding the nodes produce for `verifyTx`.
+/// `public_inputs` is the full instance; in `InstanceMode::Hashed` it is hashed first,
+/// as the Solidity verifier does.
+pub fn verify_encoded_proof(
+    pvk: &PreparedVerifyingKey<Bn254>,
+    mode: InstanceMode,
+    proof: &[u8],
+    public_inputs: &[u8],
+) -> Result<bool, Groth16VerifyError> {
+    let proof = decode_groth16_proof(proof)?;
+    let instance = decode_uint256_inputs(public_inputs)?;
+    let inputs = verifier_public_inputs(instance, mode);
+    let expected = pvk.vk.gamma_abc_g1.len() - 1;
+    if inputs.len() != expected {
+        return Err(Groth16VerifyError::PublicInputLength {
+            expected,
+            actual: inputs.len(),
+        });
+    }
+    verify_proof(pvk, &proof, &inputs).map_err(|e| Groth16VerifyError::Pairing(e.to_string()))
+}
+
+/// Inverse of `zk_mpc_node::abi_encode_groth16_proof`. Points must be on the curve and in
+/// the prime-order subgroup; `(0, 0)` is the point at infinity, as for the precompiles.
+pub fn decode_groth16_proof(bytes: &[u8]) -> Result<Proof<Bn254>, Groth16VerifyError> {
+    if bytes.len() != PROOF_WORDS * WORD_LEN {
+        return Err(Groth16VerifyError::MalformedProof(format!(
+            "expected {} bytes, got {}",
+            PROOF_WORDS * WORD_LEN,
+            bytes.len()
+        )));
+    }
+    let mut words = Vec::with_capacity(PROOF_WORDS);
+    for word in bytes.chunks(WORD_LEN) {
+        let value = word_to_field::<Fq>(word).ok_or_else(|| {
+            Groth16VerifyError::MalformedProof("coordinate is not in the base field".to_string())
+        })?;
+        words.push(value);
+    }
+
+    let g1 = |x: Fq, y: Fq, label: &str| {
+        let point = if x.is_zero() && y.is_zero() {
+            G1Affine::zero()
+        } else {
+            G1Affine::new(x, y, false)
+        };
+        if point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve() {
+            Ok(point)
+        } else {
+            Err(Groth16VerifyError::MalformedProof(format!(
+                "{} is not a valid G1 point",
+                label
+            )))
+        }
+    };
+    let a = g1(words[0], words[1], "a")?;
+    let c = g1(words[6], words[7], "c")?;
+
+    let b_x = Fq2::new(words[2], words[3]);
+    let b_y = Fq2::new(words[4], words[5]);
+    let b = if b_x.is_zero() && b_y.is_zero() {
+        G2Affine::zero()
+    } else {
+        G2Affine::new(b_x, b_y, false)
+    };
+    if !b.is_on_curve() || !b.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Groth16VerifyError::MalformedProof(
+            "b is not a valid G2 point".to_string(),
+        ));
+    }
+
+    Ok(Proof { a, b, c })
+}
+
+/// Decodes `uint256[N]` calldata. Like the Solidity verifier, words outside the scalar
+/// field are rejected instead of being reduced.
+pub fn decode_uint256_inputs(bytes: &[u8]) -> Result<Vec<Fr>, Groth16VerifyError> {
+    if bytes.len() % WORD_LEN != 0 {
+        return Err(Groth16VerifyError::MalformedPublicInputs(format!(
+            "length {} is not a multiple of {}",
+            bytes.len(),
+            WORD_LEN
+        )));
+    }
+    bytes
+        .chunks(WORD_LEN)
+        .enumerate()
+        .map(|(i, word)| {
+            word_to_field::<Fr>(word).ok_or_else(|| {
+                Groth16VerifyError::MalformedPublicInputs(format!(
+                    "input {} is not in the scalar field",
+                    i
+                ))
+            })
+        })
+        .collect()
+}
+
+fn word_to_field<F: PrimeField>(word: &[u8]) -> Option<F> {
+    let bits = word
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1))
+        .collect::<Vec<_>>();
+    F::from_repr(F::BigInt::from_bits_be(&bits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::test_circuits::ProductCircuit;
+    use ark_groth16::{create_random_proof, generate_random_parameters};
+    use ark_std::test_rng;
+    use mpc_algebra_wasm::{digest_to_bytes, instance_hash, PoseidonParameters};
+    use zk_mpc_node::{abi_encode_groth16_proof, ProofOutputType};
+
+    fn encode_inputs(inputs: &[Fr]) -> Vec<u8> {
+        inputs.iter().flat_map(|x| digest_to_bytes(*x)).collect()
+    }
+
+    fn prove(circuit: ProductCircuit) -> (PreparedVerifyingKey<Bn254>, Vec<u8>) {
+        let rng = &mut test_rng();
+        let params = generate_random_parameters::<Bn254, _, _>(circuit.clone(), rng).unwrap();
+        let proof = create_random_proof(circuit, &params, rng).unwrap();
+        (
+            prepare_verifying_key(&params.vk),
+            abi_encode_groth16_proof(&proof),
+        )
+    }
+
+    #[test]
+    fn verifies_node_encoded_proof() {
+        let inputs = vec![Fr::from(21u32), Fr::from(4u32), Fr::from(9u32)];
+        let (pvk, proof) = prove(ProductCircuit {
+            a: Fr::from(3u32),
+            b: Fr::from(7u32),
+            inputs: inputs.clone(),
+        });
+
+        let encoded = encode_inputs(&inputs);
+        assert_eq!(
+            verify_encoded_proof(&pvk, InstanceMode::Full, &proof, &encoded),
+            Ok(true)
+        );
+
+        let mut tampered = encoded.clone();
+        tampered[31] ^= 1;
+        assert_eq!(
+            verify_encoded_proof(&pvk, InstanceMode::Full, &proof, &tampered),
+            Ok(false)
+        );
+        assert_eq!(
+            verify_encoded_proof(&pvk, InstanceMode::Full, &proof, &encoded[..64]),
+            Err(Groth16VerifyError::PublicInputLength {
+                expected: 3,
+                actual: 2
+            })
+        );
+    }
+
+    #[test]
+    fn verifies_hashed_instance_against_full_calldata() {
+        let instance = vec![Fr::from(5u32), Fr::from(8u32), Fr::from(13u32)];
+        let hash = instance_hash(&PoseidonParameters::new(), &instance);
+        let (pvk, proof) = prove(ProductCircuit {
+            a: hash,
+            b: Fr::from(1u32),
+            inputs: vec![hash],
+        });
+
+        let encoded = encode_inputs(&instance);
+        assert_eq!(
+            verify_encoded_proof(&pvk, InstanceMode::Hashed, &proof, &encoded),
+            Ok(true)
+        );
+        assert!(verify_encoded_proof(&pvk, InstanceMode::Full, &proof, &encoded).is_err());
+    }
+
+    #[test]
+    fn rejects_proofs_without_a_verifying_key() {
+        let identifier = CircuitEncryptedInputIdentifier::KeyPublicize(Vec::new());
+        let output = ProofOutput {
+            output_type: ProofOutputType::Public,
+            value: None,
+            proof: Some(vec![0u8; PROOF_WORDS * WORD_LEN]),
+            public_inputs: None,
+            shares: None,
+            manifest_hash: None,
+            scheme: ProofScheme::Groth16,
+        };
+
+        assert!(matches!(
+            Groth16Verifier::new().verify_output(&identifier, &output),
+            Err(Groth16VerifyError::MissingVerifyingKey(_))
+        ));
+        assert_eq!(
+            Groth16Verifier::new()
+                .with_missing_keys_allowed(true)
+                .verify_output(&identifier, &output),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_encodings() {
+        assert!(matches!(
+            decode_groth16_proof(&[0u8; 7 * 32]),
+            Err(Groth16VerifyError::MalformedProof(_))
+        ));
+        // (1, 1) は曲線上にない
+        let mut proof = vec![0u8; 8 * 32];
+        proof[31] = 1;
+        proof[63] = 1;
+        assert!(matches!(
+            decode_groth16_proof(&proof),
+            Err(Groth16VerifyError::MalformedProof(_))
+        ));
+
+        assert!(matches!(
+            decode_uint256_inputs(&[0u8; 33]),
+            Err(Groth16VerifyError::MalformedPublicInputs(_))
+        ));
+        assert!(matches!(
+            decode_uint256_inputs(&[0xffu8; 32]),
+            Err(Groth16VerifyError::MalformedPublicInputs(_))
+        ));
+        assert_eq!(
+            decode_uint256_inputs(&digest_to_bytes(Fr::from(42u32))),
+            Ok(vec![Fr::from(42u32)])
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use crate::utils::config::CONFIG;
+use futures::future::join_all;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use zk_mpc_node::NodeStatus;
+
+const NODE_STATUS_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeStatusReport {
+    pub url: String,
+    pub reachable: bool,
+    pub ready: bool,
+    pub status: Option<NodeStatus>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterStatus {
+    pub status: String, // ok/degraded
+    pub ready_nodes: usize,
+    pub total_nodes: usize,
+    pub nodes: Vec<NodeStatusReport>,
+}
+
+impl ClusterStatus {
+    pub fn from_reports(nodes: Vec<NodeStatusReport>) -> Self {
+        let ready_nodes = nodes.iter().filter(|node| node.ready).count();
+        let total_nodes = nodes.len();
+        let status = if total_nodes > 0 && ready_nodes == total_nodes {
+            "ok"
+        } else {
+            "degraded"
+        };
+
+        Self {
+            status: status.to_string(),
+            ready_nodes,
+            total_nodes,
+            nodes,
+        }
+    }
+}
+
+/// Queries `/status` on every zk-mpc-node concurrently.
+pub async fn fetch_cluster_status() -> ClusterStatus {
+    let client = match Client::builder().timeout(NODE_STATUS_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            let reports = CONFIG
+                .zk_mpc_node_urls()
+                .into_iter()
+                .map(|url| unreachable_report(url, e.to_string()))
+                .collect();
+            return ClusterStatus::from_reports(reports);
+        }
+    };
+
+    let requests = CONFIG
+        .zk_mpc_node_urls()
+        .into_iter()
+        .map(|url| fetch_node_status(&client, url));
+    ClusterStatus::from_reports(join_all(requests).await)
+}
+
+async fn fetch_node_status(client: &Client, url: String) -> NodeStatusReport {
+    let response = match client.get(format!("{}/status", url)).send().await {
+        Ok(response) => response,
+        Err(e) => return unreachable_report(url, e.to_string()),
+    };
+
+    match response.json::<NodeStatus>().await {
+        Ok(status) => NodeStatusReport {
+            url,
+            reachable: true,
+            ready: status.ready,
+            status: Some(status),
+            error: None,
+        },
+        Err(e) => NodeStatusReport {
+            url,
+            reachable: true,
+            ready: false,
+            status: None,
+            error: Some(format!("Failed to parse node status: {}", e)),
+        },
+    }
+}
+
+fn unreachable_report(url: String, error: String) -> NodeStatusReport {
+    NodeStatusReport {
+        url,
+        reachable: false,
+        ready: false,
+        status: None,
+        error: Some(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(ready: bool) -> NodeStatusReport {
+        NodeStatusReport {
+            url: "http://localhost:9000".to_string(),
+            reachable: ready,
+            ready,
+            status: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_cluster_is_ok_only_when_all_nodes_ready() {
+        let status = ClusterStatus::from_reports(vec![report(true), report(true), report(true)]);
+        assert_eq!(status.status, "ok");
+        assert_eq!(status.ready_nodes, 3);
+
+        let status = ClusterStatus::from_reports(vec![report(true), report(false), report(true)]);
+        assert_eq!(status.status, "degraded");
+        assert_eq!(status.ready_nodes, 2);
+
+        let status = ClusterStatus::from_reports(vec![]);
+        assert_eq!(status.status, "degraded");
+    }
+}
@@ -0,0 +1,403 @@
+use crate::blockchain::state_hash::bytes32_to_hex;
+use crate::models::game::{next_state_digest, GamePhase};
+use crate::models::proof_bundle::{decode_hex, ProofBundle, ProofRecord, PROOF_BUNDLE_VERSION};
+use crate::services::groth16_verifier::{
+    decode_groth16_proof, decode_uint256_inputs, verify_encoded_proof, Groth16Verifier,
+};
+use ark_bn254::Bn254;
+use ark_groth16::VerifyingKey;
+use arkworks_solidity_verifier::batch::{
+    batch_verify, BatchTranscript, BatchVerificationError, TranscriptEntry,
+};
+use mpc_algebra_wasm::{CircuitProfile, ProofScheme};
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum BundleError {
+    #[error("unsupported proof bundle version {0} (expected {PROOF_BUNDLE_VERSION})")]
+    UnsupportedVersion(u32),
+    #[error("proof #{0} cannot be batched: {1}")]
+    NotBatchable(usize, String),
+    #[error(transparent)]
+    BatchVerification(#[from] BatchVerificationError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofVerdict {
+    Valid,
+    Invalid(String),
+    /// The proof could not be checked, e.g. no verifying key is loaded for its profile.
+    Skipped(String),
+}
+
+/// Result of re-verifying one [`ProofRecord`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofCheck {
+    pub batch_id: String,
+    pub phase: GamePhase,
+    pub day_count: u32,
+    pub circuit_profile: CircuitProfile,
+    pub verdict: ProofVerdict,
+    /// Why the proof does not continue the state digest chain, if it does not.
+    pub chain_error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleReport {
+    pub checks: Vec<ProofCheck>,
+    /// Why the chain does not end at the bundle's final state digest, if it does not.
+    pub final_digest_error: Option<String>,
+}
+
+impl BundleReport {
+    /// Every proof verified and the state digests chain from the initial digest to the final one.
+    pub fn is_ok(&self) -> bool {
+        self.final_digest_error.is_none()
+            && self
+                .checks
+                .iter()
+                .all(|check| check.verdict == ProofVerdict::Valid && check.chain_error.is_none())
+    }
+}
+
+/// Checks every proof of `bundle` against the verifying keys in `verifier`, and that the
+/// state digests in the public inputs chain from the zero digest to the final digest.
+pub fn verify_bundle(
+    bundle: &ProofBundle,
+    verifier: &Groth16Verifier,
+) -> Result<BundleReport, BundleError> {
+    if bundle.version != PROOF_BUNDLE_VERSION {
+        return Err(BundleError::UnsupportedVersion(bundle.version));
+    }
+
+    let mut digest = [0u8; 32];
+    let mut checks = Vec::with_capacity(bundle.proofs.len());
+    for record in &bundle.proofs {
+        let (verdict, chain_error) = match decode_hex(&record.public_inputs) {
+            Ok(public_inputs) => (
+                check_proof(record, &public_inputs, verifier),
+                check_chain(&mut digest, &public_inputs, &record.state_digest),
+            ),
+            Err(e) => {
+                let error = format!("malformed public inputs: {}", e);
+                (ProofVerdict::Invalid(error.clone()), Some(error))
+            }
+        };
+        checks.push(ProofCheck {
+            batch_id: record.batch_id.clone(),
+            phase: record.phase.clone(),
+            day_count: record.day_count,
+            circuit_profile: record.circuit_profile,
+            verdict,
+            chain_error,
+        });
+    }
+
+    let final_digest_error = match decode_digest(&bundle.final_state_digest) {
+        Ok(expected) if expected == digest => None,
+        Ok(_) => Some(format!(
+            "chain ends at {}, bundle reports {}",
+            bytes32_to_hex(&digest),
+            bundle.final_state_digest
+        )),
+        Err(e) => Some(e),
+    };
+    Ok(BundleReport {
+        checks,
+        final_digest_error,
+    })
+}
+
+fn check_proof(
+    record: &ProofRecord,
+    public_inputs: &[u8],
+    verifier: &Groth16Verifier,
+) -> ProofVerdict {
+    if record.scheme != ProofScheme::Groth16 {
+        return ProofVerdict::Skipped(format!("{:?} proofs are not checked", record.scheme));
+    }
+    let Some(pvk) = verifier.verifying_key(&record.circuit_profile) else {
+        return ProofVerdict::Skipped("no verifying key for this profile".to_string());
+    };
+    if let (Some(recorded), Some(local)) = (
+        record.vk_hash.as_deref(),
+        verifier.vk_hash(&record.circuit_profile),
+    ) {
+        if !recorded.eq_ignore_ascii_case(&local) {
+            return ProofVerdict::Invalid(format!(
+                "proof was accepted with vk {}, local key is {}",
+                recorded, local
+            ));
+        }
+    }
+    let proof = match decode_hex(&record.proof) {
+        Ok(proof) => proof,
+        Err(e) => return ProofVerdict::Invalid(format!("malformed proof: {}", e)),
+    };
+    match verify_encoded_proof(pvk, record.instance_mode, &proof, public_inputs) {
+        Ok(true) => ProofVerdict::Valid,
+        Ok(false) => ProofVerdict::Invalid("pairing check failed".to_string()),
+        Err(e) => ProofVerdict::Invalid(e.to_string()),
+    }
+}
+
+/// `TranscriptVerifier.verifyTranscript` calldata for the Groth16 proofs of a bundle, see
+/// `arkworks_solidity_verifier::batch`. It is a batch check: the calldata still holds
+/// every proof and its instance.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleTranscript {
+    /// Profile of each `vkIndex`.
+    pub circuit_profiles: Vec<CircuitProfile>,
+    /// Keys of `circuit_profiles`, in the order `export_transcript_verifier` expects.
+    #[serde(skip)]
+    pub verifying_keys: Vec<VerifyingKey<Bn254>>,
+    pub transcript: BatchTranscript,
+}
+
+/// Batches every proof of `bundle` that has public inputs into a single transcript check.
+/// Fails when one of them is not a Groth16 proof or has no verifying key in `verifier`.
+pub fn batch_verify_bundle(
+    bundle: &ProofBundle,
+    verifier: &Groth16Verifier,
+) -> Result<BundleTranscript, BundleError> {
+    if bundle.version != PROOF_BUNDLE_VERSION {
+        return Err(BundleError::UnsupportedVersion(bundle.version));
+    }
+
+    let mut circuit_profiles = Vec::new();
+    let mut verifying_keys = Vec::new();
+    let mut entries = Vec::new();
+    for (index, record) in bundle.proofs.iter().enumerate() {
+        let not_batchable = |reason: String| BundleError::NotBatchable(index, reason);
+        let public_inputs = decode_hex(&record.public_inputs)
+            .map_err(|e| not_batchable(format!("malformed public inputs: {}", e)))?;
+        // 公開入力を持たない proof は state chain に入らない
+        if public_inputs.is_empty() {
+            continue;
+        }
+        if record.scheme != ProofScheme::Groth16 {
+            return Err(not_batchable(format!(
+                "{:?} proofs are not batched",
+                record.scheme
+            )));
+        }
+        let pvk = verifier
+            .verifying_key(&record.circuit_profile)
+            .ok_or_else(|| not_batchable("no verifying key for this profile".to_string()))?;
+        let proof = decode_hex(&record.proof)
+            .map_err(|e| not_batchable(format!("malformed proof: {}", e)))?;
+        let proof = decode_groth16_proof(&proof).map_err(|e| not_batchable(e.to_string()))?;
+        let public_inputs =
+            decode_uint256_inputs(&public_inputs).map_err(|e| not_batchable(e.to_string()))?;
+
+        let vk_index = match circuit_profiles
+            .iter()
+            .position(|profile| *profile == record.circuit_profile)
+        {
+            Some(vk_index) => vk_index,
+            None => {
+                circuit_profiles.push(record.circuit_profile);
+                verifying_keys.push(pvk.vk.clone());
+                circuit_profiles.len() - 1
+            }
+        };
+        entries.push(TranscriptEntry {
+            vk_index,
+            public_inputs,
+            proof,
+        });
+    }
+
+    let transcript = batch_verify(&verifying_keys, &entries)?;
+    Ok(BundleTranscript {
+        circuit_profiles,
+        verifying_keys,
+        transcript,
+    })
+}
+
+// 公開入力を持たない proof (KeyPublicize など) は digest を進めない
+fn check_chain(current: &mut [u8; 32], public_inputs: &[u8], recorded: &str) -> Option<String> {
+    let next = if public_inputs.is_empty() {
+        Ok(*current)
+    } else {
+        next_state_digest(current, public_inputs)
+    };
+    let recorded = decode_digest(recorded);
+    let error = match (&next, &recorded) {
+        (Ok(next), Ok(recorded)) if next == recorded => None,
+        (Ok(next), Ok(recorded)) => Some(format!(
+            "recorded state digest {} differs from {}",
+            bytes32_to_hex(recorded),
+            bytes32_to_hex(next)
+        )),
+        (Err(e), _) | (_, Err(e)) => Some(e.clone()),
+    };
+    // 1 つ崩れても後続の proof を個別に確認できるよう、記録された digest から続ける
+    if let Ok(digest) = recorded.or(next) {
+        *current = digest;
+    }
+    error
+}
+
+fn decode_digest(value: &str) -> Result<[u8; 32], String> {
+    decode_hex(value)
+        .map_err(|e| e.to_string())?
+        .try_into()
+        .map_err(|_| format!("state digest {} is not 32 bytes", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::game::GameResult;
+    use crate::models::proof_bundle::encode_hex;
+    use ark_bn254::{Bn254, Fr};
+    use crate::services::test_circuits::ProductCircuit;
+    use ark_groth16::{create_random_proof, generate_random_parameters};
+    use ark_std::test_rng;
+    use mpc_algebra_wasm::{digest_to_bytes, InstanceMode};
+    use zk_mpc_node::abi_encode_groth16_proof;
+
+    const PROFILE: CircuitProfile = CircuitProfile::Divination { player_count: 4 };
+
+    /// Exposes `[value, day, prev digest, next digest]` and checks `a * b == value`.
+    fn circuit(prev: u64, next: u64) -> ProductCircuit {
+        ProductCircuit {
+            a: Fr::from(3u32),
+            b: Fr::from(7u32),
+            inputs: vec![
+                Fr::from(21u32),
+                Fr::from(1u32),
+                Fr::from(prev),
+                Fr::from(next),
+            ],
+        }
+    }
+
+    fn record(
+        params: &ark_groth16::ProvingKey<Bn254>,
+        verifier: &Groth16Verifier,
+        prev: u64,
+        next: u64,
+    ) -> ProofRecord {
+        let circuit = circuit(prev, next);
+        let public_inputs = circuit
+            .inputs
+            .iter()
+            .flat_map(|x| digest_to_bytes(*x))
+            .collect::<Vec<_>>();
+        let proof = create_random_proof(circuit, params, &mut test_rng()).unwrap();
+        ProofRecord {
+            batch_id: format!("batch-{}", next),
+            phase: GamePhase::Night,
+            day_count: 1,
+            circuit_profile: PROFILE,
+            instance_mode: InstanceMode::Full,
+            scheme: ProofScheme::Groth16,
+            proof: encode_hex(&abi_encode_groth16_proof(&proof)),
+            public_inputs: encode_hex(&public_inputs),
+            vk_hash: verifier.vk_hash(&PROFILE),
+            manifest_hash: None,
+            state_digest: bytes32_to_hex(&digest_to_bytes(Fr::from(next))),
+        }
+    }
+
+    fn chained_bundle() -> (ProofBundle, Groth16Verifier) {
+        let params =
+            generate_random_parameters::<Bn254, _, _>(circuit(0, 1), &mut test_rng()).unwrap();
+        let mut verifier = Groth16Verifier::new();
+        verifier.insert(PROFILE, &params.vk);
+        let proofs = vec![
+            record(&params, &verifier, 0, 1),
+            record(&params, &verifier, 1, 2),
+        ];
+        let bundle = ProofBundle {
+            version: PROOF_BUNDLE_VERSION,
+            room_id: "room".to_string(),
+            game_id: bytes32_to_hex(&[0u8; 32]),
+            result: GameResult::VillagerWin,
+            final_state_digest: bytes32_to_hex(&digest_to_bytes(Fr::from(2u32))),
+            proofs,
+        };
+        (bundle, verifier)
+    }
+
+    #[test]
+    fn verifies_chained_bundle() {
+        let (bundle, verifier) = chained_bundle();
+        let report = verify_bundle(&bundle, &verifier).unwrap();
+        assert!(report.is_ok(), "{:?}", report);
+        assert_eq!(report.checks.len(), 2);
+
+        // JSON を経由しても同じ結果になる
+        let json = serde_json::to_string(&bundle).unwrap();
+        let decoded: ProofBundle = serde_json::from_str(&json).unwrap();
+        assert_eq!(verify_bundle(&decoded, &verifier).unwrap(), report);
+    }
+
+    #[test]
+    fn reports_broken_chain_and_tampered_proof() {
+        let (mut bundle, verifier) = chained_bundle();
+        bundle.proofs.swap(0, 1);
+        let report = verify_bundle(&bundle, &verifier).unwrap();
+        assert!(!report.is_ok());
+        assert!(report
+            .checks
+            .iter()
+            .all(|check| check.verdict == ProofVerdict::Valid));
+        assert!(report.checks[0].chain_error.is_some());
+        assert!(report.final_digest_error.is_some());
+
+        let (mut bundle, verifier) = chained_bundle();
+        let mut public_inputs = decode_hex(&bundle.proofs[1].public_inputs).unwrap();
+        public_inputs[31] ^= 1;
+        bundle.proofs[1].public_inputs = encode_hex(&public_inputs);
+        let report = verify_bundle(&bundle, &verifier).unwrap();
+        assert_eq!(report.checks[0].verdict, ProofVerdict::Valid);
+        assert!(matches!(report.checks[1].verdict, ProofVerdict::Invalid(_)));
+        assert!(report.checks[1].chain_error.is_none());
+    }
+
+    #[test]
+    fn batches_chained_bundle() {
+        let (bundle, verifier) = chained_bundle();
+        let batched = batch_verify_bundle(&bundle, &verifier).unwrap();
+        assert_eq!(batched.circuit_profiles, vec![PROFILE]);
+        assert_eq!(batched.verifying_keys.len(), 1);
+        assert_eq!(batched.transcript.vk_index, vec![0, 0]);
+
+        let (mut bundle, verifier) = chained_bundle();
+        bundle.proofs.swap(0, 1);
+        assert_eq!(
+            batch_verify_bundle(&bundle, &verifier).unwrap_err(),
+            BundleError::BatchVerification(BatchVerificationError::StateChainBroken { index: 1 })
+        );
+
+        let (bundle, _) = chained_bundle();
+        assert!(matches!(
+            batch_verify_bundle(&bundle, &Groth16Verifier::new()),
+            Err(BundleError::NotBatchable(0, _))
+        ));
+    }
+
+    #[test]
+    fn skips_proofs_without_a_local_key() {
+        let (bundle, _) = chained_bundle();
+        let report = verify_bundle(&bundle, &Groth16Verifier::new()).unwrap();
+        assert!(!report.is_ok());
+        assert!(report
+            .checks
+            .iter()
+            .all(|check| matches!(check.verdict, ProofVerdict::Skipped(_))));
+        assert!(report.final_digest_error.is_none());
+
+        let (mut bundle, verifier) = chained_bundle();
+        bundle.version += 1;
+        assert_eq!(
+            verify_bundle(&bundle, &verifier),
+            Err(BundleError::UnsupportedVersion(PROOF_BUNDLE_VERSION + 1))
+        );
+    }
+}
@@ -0,0 +1,25 @@
+//! Circuits shared by the Groth16 verification tests.
+
+use ark_bn254::Fr;
+use ark_relations::lc;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+
+/// Exposes `inputs` and checks `a * b == inputs[0]`.
+#[derive(Clone)]
+pub(crate) struct ProductCircuit {
+    pub a: Fr,
+    pub b: Fr,
+    pub inputs: Vec<Fr>,
+}
+
+impl ConstraintSynthesizer<Fr> for ProductCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let a = cs.new_witness_variable(|| Ok(self.a))?;
+        let b = cs.new_witness_variable(|| Ok(self.b))?;
+        let mut inputs = Vec::new();
+        for input in self.inputs {
+            inputs.push(cs.new_input_variable(|| Ok(input))?);
+        }
+        cs.enforce_constraint(lc!() + a, lc!() + b, lc!() + inputs[0])
+    }
+}
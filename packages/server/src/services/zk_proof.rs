@@ -1,19 +1,24 @@
 use crate::utils::config::CONFIG;
-use mpc_algebra_wasm::CircuitEncryptedInputIdentifier;
+use mpc_algebra_wasm::{
+    AnonymousVotingOutput, AttackOutput, CircuitEncryptedInputIdentifier, DivinationOutput,
+    KeyPublicizeOutput, MediumOutput, NightResolutionOutput, RoleAssignmentOutput,
+    StateChainParameter, WinningJudgementOutput,
+};
 use once_cell::sync::Lazy;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
+use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use tokio::sync::Mutex;
 use tokio::time::{sleep, Duration};
 use zk_mpc_node::{
     models::{ProofOutput, ProofOutputType, ProofRequest, ProofResponse},
-    ProofStatus,
+    ProofStatus, RequestSigner,
 };
 
 use crate::{
     models::game::{
-        try_convert_to_identifier, BatchEnqueueError, BatchRequest, ClientRequestType, GamePhase,
-        GameResult,
+        try_convert_to_identifier, BatchEnqueueError, BatchRequest, ClientRequestType, Game,
+        GamePhase, GameResult,
     },
     services::proof_job_service::ProofJob,
     state::AppState,
@@ -26,6 +31,40 @@ type BatchExecutionResult = Result<(CircuitEncryptedInputIdentifier, ProofOutput
 static PRECOMPUTED_BATCH_RESULTS: Lazy<Mutex<HashMap<String, BatchExecutionResult>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
 
+static PROOF_REQUEST_SIGNER: Lazy<Option<RequestSigner>> = Lazy::new(|| {
+    let key = CONFIG.proof_request_signing_key.as_ref()?;
+    match RequestSigner::from_base64_secret(key) {
+        Ok(signer) => Some(signer),
+        Err(e) => panic!("PROOF_REQUEST_SIGNING_KEY is invalid: {}", e),
+    }
+});
+
+/// Builds a POST of the proof request to a node, signed with `PROOF_REQUEST_SIGNING_KEY`.
+/// Every node gets its own nonce so that a request accepted by one node cannot be replayed to it.
+fn signed_proof_request(
+    client: &Client,
+    url: &str,
+    payload: &ProofRequest,
+) -> Result<RequestBuilder, String> {
+    let body = serde_json::to_vec(payload)
+        .map_err(|e| format!("Failed to serialize proof request: {}", e))?;
+    let mut request = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json");
+    match PROOF_REQUEST_SIGNER.as_ref() {
+        Some(signer) => {
+            for (name, value) in signer.sign(&body).pairs() {
+                request = request.header(name, value);
+            }
+        }
+        None => eprintln!(
+            "PROOF_REQUEST_SIGNING_KEY is not set; sending unsigned proof request {} to {}",
+            payload.proof_id, url
+        ),
+    }
+    Ok(request.body(body))
+}
+
 #[derive(Debug)]
 pub enum ProofHandlingError {
     Conflict(String),
@@ -54,9 +93,7 @@ pub async fn request_proof_with_output(
     let mut responses = Vec::new();
 
     for url in CONFIG.zk_mpc_node_urls() {
-        let response = client
-            .post(url)
-            .json(&payload)
+        let response = signed_proof_request(&client, &url, &payload)?
             .send()
             .await
             .map_err(|e| e.to_string())?;
@@ -166,6 +203,19 @@ fn merge_proof_outputs(statuses: Vec<ProofStatus>) -> Option<ProofOutput> {
             .and_then(|output| output.public_inputs.clone())
     });
 
+    // 全ノードが同じ manifest の鍵で証明したときだけ manifest hash を引き継ぐ
+    let manifest_hash = first_output.manifest_hash.clone().filter(|hash| {
+        statuses.iter().all(|status| {
+            status
+                .output
+                .as_ref()
+                .is_some_and(|output| output.manifest_hash.as_ref() == Some(hash))
+        })
+    });
+    if manifest_hash.is_none() && first_output.manifest_hash.is_some() {
+        println!("Nodes reported different Groth16 manifest hashes");
+    }
+
     // 各ノードからの暗号化シェアを集める
     for status in &statuses {
         if let Some(output) = &status.output {
@@ -189,6 +239,8 @@ fn merge_proof_outputs(statuses: Vec<ProofStatus>) -> Option<ProofOutput> {
         } else {
             Some(all_shares)
         },
+        manifest_hash,
+        scheme: first_output.scheme,
     })
 }
 
@@ -242,9 +294,7 @@ pub async fn execute_batch_request(batch_request: &BatchRequest) -> BatchExecuti
     let node_urls = CONFIG.zk_mpc_node_urls();
     let mut responses = Vec::new();
     for url in &node_urls {
-        let response = client
-            .post(url)
-            .json(&req_to_node)
+        let response = signed_proof_request(&client, url, &req_to_node)?
             .send()
             .await
             .map_err(|e| {
@@ -257,12 +307,25 @@ pub async fn execute_batch_request(batch_request: &BatchRequest) -> BatchExecuti
     }
 
     for (url, response) in node_urls.iter().zip(responses) {
-        response.json::<serde_json::Value>().await.map_err(|e| {
+        let status = response.status();
+        let body = response.json::<serde_json::Value>().await.map_err(|e| {
             format!(
                 "Failed to parse JSON response from {} for batch {}: {}",
                 url, batch_request.batch_id, e
             )
         })?;
+        // 再接続中のノードは 503 (cluster_degraded) を返す
+        if !status.is_success() {
+            return Err(format!(
+                "Node {} rejected batch {} ({}): {}",
+                url,
+                batch_request.batch_id,
+                status,
+                body.get("message")
+                    .and_then(|message| message.as_str())
+                    .unwrap_or("no error message")
+            ));
+        }
     }
 
     match check_status_with_retry(&batch_request.batch_id).await? {
@@ -305,6 +368,9 @@ pub async fn batch_proof_handling(
             ClientRequestType::AnonymousVoting(info) => info.user_id.clone(),
             ClientRequestType::WinningJudge(info) => info.user_id.clone(),
             ClientRequestType::KeyPublicize(info) => info.user_id.clone(),
+            ClientRequestType::Attack(info) => info.user_id.clone(),
+            ClientRequestType::NightResolution(info) => info.user_id.clone(),
+            ClientRequestType::Medium(info) => info.user_id.clone(),
         };
 
         // 計算結果の重複チェック
@@ -316,14 +382,23 @@ pub async fn batch_proof_handling(
                     ));
                 }
             }
-            ClientRequestType::Divination(_) => {
+            ClientRequestType::Divination(info) => {
                 if game.has_divination_for_current_phase() {
                     return Err(ProofHandlingError::Conflict(
                         "Divination has already been completed for current phase".to_string(),
                     ));
                 }
+
+                if let Ok(output) = serde_json::from_str::<DivinationOutput>(&info.encrypted_data) {
+                    check_alive_players(
+                        game,
+                        "divination",
+                        output.public_input.player_num,
+                        output.public_input.state_chain.alive_mask,
+                    )?;
+                }
             }
-            ClientRequestType::WinningJudge(_) => {
+            ClientRequestType::WinningJudge(info) => {
                 // GameResultが既に決定されている場合は重複
                 if game.result != GameResult::InProgress {
                     return Err(ProofHandlingError::Conflict(
@@ -331,20 +406,67 @@ pub async fn batch_proof_handling(
                             .to_string(),
                     ));
                 }
+
+                // 勝利条件は公開入力なので、ゲームの設定と一致しているものだけ受け付ける
+                // (経過日数は check_state_chain で確認する)
+                if let Ok(output) =
+                    serde_json::from_str::<WinningJudgementOutput>(&info.encrypted_data)
+                {
+                    if output.public_input.win_condition != game.win_condition {
+                        return Err(ProofHandlingError::Unprocessable(format!(
+                            "winning judgement public input does not match the game (win_condition: {:?})",
+                            game.win_condition
+                        )));
+                    }
+                    check_alive_players(
+                        game,
+                        "winning judgement",
+                        output.public_input.player_num,
+                        output.public_input.state_chain.alive_mask,
+                    )?;
+                }
             }
-            ClientRequestType::AnonymousVoting(_) => {
+            ClientRequestType::AnonymousVoting(info) => {
                 // vote_resultsが既に存在し、現在のphaseで投票が完了している場合は重複
                 if !game.vote_results.is_empty() {
                     return Err(ProofHandlingError::Conflict(
                         "Voting has already been completed for current phase".to_string(),
                     ));
                 }
+
+                if let Ok(output) =
+                    serde_json::from_str::<AnonymousVotingOutput>(&info.encrypted_data)
+                {
+                    check_alive_players(
+                        game,
+                        "anonymous voting",
+                        output.public_input.player_num,
+                        output.public_input.state_chain.alive_mask,
+                    )?;
+                }
             }
             ClientRequestType::KeyPublicize(_) => {
                 // キー公開は重複チェック対象外
             }
+            ClientRequestType::Attack(_) | ClientRequestType::NightResolution(_) => {
+                // 襲撃先が既に登録されている場合は重複
+                if !game.night_actions.attacks.is_empty() {
+                    return Err(ProofHandlingError::Conflict(
+                        "Attack has already been completed for current night".to_string(),
+                    ));
+                }
+            }
+            ClientRequestType::Medium(_) => {
+                if game.has_medium_for_current_phase() {
+                    return Err(ProofHandlingError::Conflict(
+                        "Medium has already been completed for current phase".to_string(),
+                    ));
+                }
+            }
         }
 
+        check_state_chain(game, request)?;
+
         // 一時的に proof request のシステムメッセージ送信を停止
         // game.chat_log
         //     .add_system_message(format!("{} has sent a proof request.", _user_id));
@@ -379,6 +501,108 @@ pub async fn batch_proof_handling(
     Ok(batch_id)
 }
 
+// 生存者 bitmask は公開入力として回路に縛られるので、サーバーが管理する生死と一致するものだけ受け付ける
+fn check_alive_players(
+    game: &Game,
+    circuit_name: &str,
+    player_num: usize,
+    alive_mask: u32,
+) -> Result<(), ProofHandlingError> {
+    if player_num != game.players.len() || alive_mask != game.alive_mask() {
+        return Err(ProofHandlingError::Unprocessable(format!(
+            "{} public input does not match the alive players (player_num: {}, alive_mask: {:#b})",
+            circuit_name,
+            game.players.len(),
+            game.alive_mask()
+        )));
+    }
+    Ok(())
+}
+
+// 全回路の proof は直前の state digest と生存者・日数を hash して次の digest を出力する。
+// サーバーが最後に受理した digest から続き、ゲームの状態と一致するものだけ受け付ける
+fn check_state_chain(game: &Game, request: &ClientRequestType) -> Result<(), ProofHandlingError> {
+    fn parse<T: DeserializeOwned>(
+        data: &str,
+        state_chain: impl FnOnce(T) -> StateChainParameter,
+    ) -> Option<StateChainParameter> {
+        serde_json::from_str::<T>(data).ok().map(state_chain)
+    }
+
+    let (circuit_name, state_chain) = match request {
+        ClientRequestType::RoleAssignment(info) => (
+            "role assignment",
+            parse(&info.encrypted_data, |o: RoleAssignmentOutput| {
+                o.public_input.state_chain
+            }),
+        ),
+        ClientRequestType::KeyPublicize(info) => (
+            "key publicize",
+            parse(&info.encrypted_data, |o: KeyPublicizeOutput| {
+                o.public_input.state_chain
+            }),
+        ),
+        ClientRequestType::Divination(info) => (
+            "divination",
+            parse(&info.encrypted_data, |o: DivinationOutput| {
+                o.public_input.state_chain
+            }),
+        ),
+        ClientRequestType::AnonymousVoting(info) => (
+            "anonymous voting",
+            parse(&info.encrypted_data, |o: AnonymousVotingOutput| {
+                o.public_input.state_chain
+            }),
+        ),
+        ClientRequestType::WinningJudge(info) => (
+            "winning judgement",
+            parse(&info.encrypted_data, |o: WinningJudgementOutput| {
+                o.public_input.state_chain
+            }),
+        ),
+        ClientRequestType::Attack(info) => (
+            "attack",
+            parse(&info.encrypted_data, |o: AttackOutput| {
+                o.public_input.state_chain
+            }),
+        ),
+        ClientRequestType::NightResolution(info) => (
+            "night resolution",
+            parse(&info.encrypted_data, |o: NightResolutionOutput| {
+                o.public_input.state_chain
+            }),
+        ),
+        ClientRequestType::Medium(info) => (
+            "medium",
+            parse(&info.encrypted_data, |o: MediumOutput| {
+                o.public_input.state_chain
+            }),
+        ),
+    };
+    let Some(state_chain) = state_chain else {
+        return Err(ProofHandlingError::Unprocessable(format!(
+            "{} public input could not be parsed",
+            circuit_name
+        )));
+    };
+
+    if state_chain.prev_state_digest != game.state_digest {
+        return Err(ProofHandlingError::Conflict(format!(
+            "{} public input does not continue from the latest state digest",
+            circuit_name
+        )));
+    }
+    if state_chain.day_count != game.day_count || state_chain.alive_mask != game.alive_mask() {
+        return Err(ProofHandlingError::Unprocessable(format!(
+            "{} public input does not match the game state (day_count: {}, alive_mask: {:#b})",
+            circuit_name,
+            game.day_count,
+            game.alive_mask()
+        )));
+    }
+    Ok(())
+}
+
 fn validate_phase_for_request(
     phase: &GamePhase,
     request: &ClientRequestType,
@@ -387,8 +611,15 @@ fn validate_phase_for_request(
         ClientRequestType::RoleAssignment(_) | ClientRequestType::KeyPublicize(_) => {
             matches!(phase, GamePhase::Night)
         }
-        ClientRequestType::Divination(_) => matches!(phase, GamePhase::DivinationProcessing),
+        // 襲撃は占いと同じく夜の終わりに全員が同時に送信する
+        ClientRequestType::Divination(_)
+        | ClientRequestType::Attack(_)
+        | ClientRequestType::NightResolution(_) => {
+            matches!(phase, GamePhase::DivinationProcessing)
+        }
         ClientRequestType::AnonymousVoting(_) => matches!(phase, GamePhase::Voting),
+        // 霊媒は処刑結果が確定した Result フェーズで行う
+        ClientRequestType::Medium(_) => matches!(phase, GamePhase::Result),
         ClientRequestType::WinningJudge(_) => {
             matches!(
                 phase,
@@ -7,7 +7,8 @@ use tokio::sync::{broadcast, Mutex};
 
 use crate::blockchain::BlockchainClient;
 use crate::models::config::DebugConfig;
-use crate::models::{game::Game, room::Room};
+use crate::models::{game::Game, proof_bundle::ProofRecord, room::Room};
+use crate::services::groth16_verifier::Groth16Verifier;
 use crate::services::node_key::NodeKeyService;
 use crate::services::proof_job_service::{ProofJobService, ProofJobStatus};
 use crate::services::user_service::UserService;
@@ -35,11 +36,14 @@ pub struct AppState {
     pub games: Arc<Mutex<HashMap<String, Game>>>,
     pub channel: Arc<Mutex<HashMap<String, broadcast::Sender<Message>>>>,
     room_event_store: Arc<Mutex<HashMap<String, RoomEventStore>>>,
+    // 受理した proof を部屋ごとに適用順で残す。/bundle だけが返す
+    proof_records: Arc<Mutex<HashMap<String, Vec<ProofRecord>>>>,
     pub user_service: UserService,
     pub debug_config: Arc<DebugConfig>,
     pub node_key_service: Arc<NodeKeyService>,
     pub proof_job_service: Arc<ProofJobService>,
     pub blockchain_client: Arc<BlockchainClient>,
+    pub groth16_verifier: Arc<Groth16Verifier>,
 }
 
 impl AppState {
@@ -49,11 +53,15 @@ impl AppState {
             games: Arc::new(Mutex::new(HashMap::new())),
             channel: Arc::new(Mutex::new(HashMap::new())),
             room_event_store: Arc::new(Mutex::new(HashMap::new())),
+            proof_records: Arc::new(Mutex::new(HashMap::new())),
             user_service: UserService::new(),
             debug_config: Arc::new(DebugConfig::default()),
             node_key_service: Arc::new(NodeKeyService::new()),
             proof_job_service: Arc::new(ProofJobService::new()),
             blockchain_client: Arc::new(BlockchainClient::new(&CONFIG)),
+            groth16_verifier: Arc::new(
+                Groth16Verifier::load().with_missing_keys_allowed(CONFIG.allow_unverified_proofs),
+            ),
         }
     }
 
@@ -77,6 +85,7 @@ impl AppState {
             let mut stores = self.room_event_store.lock().await;
             stores.remove(room_id).is_some()
         };
+        self.proof_records.lock().await.remove(room_id);
         (channel_removed, event_store_removed)
     }
 
@@ -92,6 +101,16 @@ impl AppState {
         stores.contains_key(room_id)
     }
 
+    pub async fn record_proof(&self, room_id: &str, record: ProofRecord) {
+        let mut records = self.proof_records.lock().await;
+        records.entry(room_id.to_string()).or_default().push(record);
+    }
+
+    pub async fn proof_records(&self, room_id: &str) -> Vec<ProofRecord> {
+        let records = self.proof_records.lock().await;
+        records.get(room_id).cloned().unwrap_or_default()
+    }
+
     async fn create_room_event(&self, room_id: &str, payload: Value) -> RoomEventEnvelope {
         let mut stores = self.room_event_store.lock().await;
         let store = stores.entry(room_id.to_string()).or_default();
@@ -11,6 +11,11 @@ pub struct Config {
     pub zk_mpc_node_0: String,
     pub zk_mpc_node_1: String,
     pub zk_mpc_node_2: String,
+    pub proof_request_signing_key: Option<String>,
+    /// Groth16 manifest hash every proof must be generated with. Unchecked when unset.
+    pub groth16_manifest_hash: Option<String>,
+    /// Development only: accept proofs whose Groth16 verifying key is not loaded, unchecked.
+    pub allow_unverified_proofs: bool,
     pub blockchain_enabled: bool,
     pub ethereum_rpc_url: String,
     pub ethereum_chain_id: u64,
@@ -29,6 +34,16 @@ impl Config {
             zk_mpc_node_0: env::var("ZK_MPC_NODE_0_HTTP").expect("ZK_MPC_NODE_0_HTTP must be set"),
             zk_mpc_node_1: env::var("ZK_MPC_NODE_1_HTTP").expect("ZK_MPC_NODE_1_HTTP must be set"),
             zk_mpc_node_2: env::var("ZK_MPC_NODE_2_HTTP").expect("ZK_MPC_NODE_2_HTTP must be set"),
+            proof_request_signing_key: env::var("PROOF_REQUEST_SIGNING_KEY")
+                .ok()
+                .filter(|key| !key.trim().is_empty()),
+            groth16_manifest_hash: env::var("GROTH16_MANIFEST_HASH")
+                .ok()
+                .map(|hash| hash.trim().to_ascii_lowercase())
+                .filter(|hash| !hash.is_empty()),
+            allow_unverified_proofs: env::var("ALLOW_UNVERIFIED_PROOFS")
+                .map(|v| v.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
             blockchain_enabled: env::var("BLOCKCHAIN_ENABLED")
                 .unwrap_or_else(|_| "false".to_string())
                 .to_ascii_lowercase()
@@ -5,7 +5,10 @@ use axum::{
 use server::app;
 use tower::ServiceExt;
 
+use server::models::{player::Player, proof_bundle::ProofBundle, room::Room};
 use server::routes::room::CreateRoomRequest;
+use server::services::game_service;
+use server::state::AppState;
 
 use server::utils::test_setup::setup_test_env;
 
@@ -87,6 +90,50 @@ async fn test_join_room() {
     assert_eq!(join_body_str, "\"Successfully joined room\"");
 }
 
+#[tokio::test]
+async fn test_proof_bundle_after_finished() {
+    setup_test_env();
+    let state = AppState::new();
+    let room_id = "bundle_room".to_string();
+    let mut room = Room::new(room_id.clone(), Some("Bundle Room".to_string()), None);
+    room.players = (1..=4)
+        .map(|i| Player {
+            id: i.to_string(),
+            name: format!("Player{}", i),
+            is_dead: false,
+            is_ready: true,
+        })
+        .collect();
+    state.rooms.lock().await.insert(room_id.clone(), room);
+    game_service::start_game(state.clone(), &room_id)
+        .await
+        .unwrap();
+    let app = app::create_app_with_state(state.clone());
+
+    let bundle_request = || {
+        Request::builder()
+            .uri(format!("/api/game/{}/bundle", room_id))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // 終了前は返さない
+    let response = app.clone().oneshot(bundle_request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    game_service::end_game(state.clone(), room_id.clone())
+        .await
+        .unwrap();
+    let response = app.oneshot(bundle_request()).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let bundle: ProofBundle = serde_json::from_slice(&body).unwrap();
+    assert_eq!(bundle.room_id, room_id);
+    assert!(bundle.proofs.is_empty());
+    assert_eq!(bundle.final_state_digest, format!("0x{}", "00".repeat(32)));
+}
+
 // #[tokio::test]
 // async fn test_voting_system() {
 //     setup_test_env();
@@ -7,6 +7,7 @@ use mpc_algebra::CommitmentScheme;
 use mpc_algebra_wasm::{
     types::AnonymousVotingInput, AnonymousVotingEncryption, AnonymousVotingOutput,
     CircuitEncryptedInputIdentifier, NodeKey, SecretSharingScheme, SplitAndEncrypt,
+    StateChainParameter,
 };
 // use mpc_circuits::inputs::anonymous_voting::{
 //     AnonymousVotingPrivateInput, AnonymousVotingPublicInput,
@@ -89,6 +90,10 @@ fn setup_voting() -> (CircuitEncryptedInputIdentifier, Vec<(SecretKey, PublicKey
         pedersen_param: pedersen_param.clone(),
         player_commitment: vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); USER_NUM],
         player_num: USER_NUM,
+        state_chain: StateChainParameter {
+            alive_mask: (1 << USER_NUM) - 1,
+            ..Default::default()
+        },
     };
 
     // let encrypted_inputs = private_inputs
@@ -191,6 +196,10 @@ fn setup_voting_dummy() -> ClientRequestType {
                 .unwrap(),
             player_commitment: vec![],
             player_num: USER_NUM,
+            state_chain: StateChainParameter {
+                alive_mask: (1 << USER_NUM) - 1,
+                ..Default::default()
+            },
         },
     };
     let prover_info = ProverInfo {
@@ -0,0 +1,289 @@
+use crate::proof::unix_now;
+use axum::http::HeaderMap;
+use base64::{decode, encode};
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signature, Signer};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use thiserror::Error;
+use tokio::sync::Mutex;
+
+pub const TIMESTAMP_HEADER: &str = "x-proof-request-timestamp";
+pub const NONCE_HEADER: &str = "x-proof-request-nonce";
+pub const SIGNATURE_HEADER: &str = "x-proof-request-signature";
+
+const SIGNING_DOMAIN: &str = "zk-mpc-node/proof-request/v1";
+const DEFAULT_MAX_AGE_SECS: u64 = 60;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum AuthError {
+    #[error("Invalid request signing key: {0}")]
+    InvalidKey(String),
+    #[error("Missing header: {0}")]
+    MissingHeader(&'static str),
+    #[error("Invalid header {0}: {1}")]
+    InvalidHeader(&'static str, String),
+    #[error("Request timestamp {timestamp} is outside the allowed window of {max_age_secs}s")]
+    Stale { timestamp: u64, max_age_secs: u64 },
+    #[error("Nonce has already been used")]
+    ReplayedNonce,
+    #[error("Signature verification failed")]
+    InvalidSignature,
+}
+
+/// Headers attached to a signed proof request.
+#[derive(Debug, Clone)]
+pub struct SignedHeaders {
+    pub timestamp: u64,
+    pub nonce: String,
+    pub signature: String, // Base64エンコードされた署名
+}
+
+impl SignedHeaders {
+    pub fn pairs(&self) -> [(&'static str, String); 3] {
+        [
+            (TIMESTAMP_HEADER, self.timestamp.to_string()),
+            (NONCE_HEADER, self.nonce.clone()),
+            (SIGNATURE_HEADER, self.signature.clone()),
+        ]
+    }
+}
+
+/// The signed message binds the timestamp, the nonce and the exact request body.
+fn signing_message(timestamp: u64, nonce: &str, body: &[u8]) -> Vec<u8> {
+    format!(
+        "{}\n{}\n{}\n{}",
+        SIGNING_DOMAIN,
+        timestamp,
+        nonce,
+        hex::encode(Sha256::digest(body))
+    )
+    .into_bytes()
+}
+
+/// Signs proof requests on the server side with an Ed25519 key.
+pub struct RequestSigner {
+    keypair: Keypair,
+}
+
+impl RequestSigner {
+    pub fn from_base64_secret(secret_key_base64: &str) -> Result<Self, AuthError> {
+        let bytes = decode(secret_key_base64.trim())
+            .map_err(|e| AuthError::InvalidKey(format!("invalid base64: {}", e)))?;
+        let secret =
+            SecretKey::from_bytes(&bytes).map_err(|e| AuthError::InvalidKey(e.to_string()))?;
+        let public = PublicKey::from(&secret);
+        Ok(Self {
+            keypair: Keypair { secret, public },
+        })
+    }
+
+    /// Generates a new signing key. Returns the signer and its Base64 encoded secret key.
+    pub fn generate() -> (Self, String) {
+        let keypair = Keypair::generate(&mut rand::rngs::OsRng);
+        let secret = encode(keypair.secret.to_bytes());
+        (Self { keypair }, secret)
+    }
+
+    pub fn public_key_base64(&self) -> String {
+        encode(self.keypair.public.to_bytes())
+    }
+
+    pub fn sign(&self, body: &[u8]) -> SignedHeaders {
+        self.sign_at(body, unix_now())
+    }
+
+    pub fn sign_at(&self, body: &[u8], timestamp: u64) -> SignedHeaders {
+        let nonce = uuid::Uuid::new_v4().simple().to_string();
+        let signature = self.keypair.sign(&signing_message(timestamp, &nonce, body));
+        SignedHeaders {
+            timestamp,
+            nonce,
+            signature: encode(signature.to_bytes()),
+        }
+    }
+}
+
+/// Verifies signed proof requests on the node side and rejects replayed nonces.
+pub struct RequestVerifier {
+    public_key: PublicKey,
+    max_age_secs: u64,
+    // nonce -> timestamp
+    seen_nonces: Mutex<HashMap<String, u64>>,
+}
+
+impl RequestVerifier {
+    pub fn new(public_key_base64: &str, max_age_secs: u64) -> Result<Self, AuthError> {
+        let bytes = decode(public_key_base64.trim())
+            .map_err(|e| AuthError::InvalidKey(format!("invalid base64: {}", e)))?;
+        let public_key =
+            PublicKey::from_bytes(&bytes).map_err(|e| AuthError::InvalidKey(e.to_string()))?;
+        Ok(Self {
+            public_key,
+            max_age_secs,
+            seen_nonces: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Reads `PROOF_REQUEST_VERIFY_KEY` and `PROOF_REQUEST_MAX_AGE_SECS`.
+    /// Returns `None` only when unsigned requests are explicitly allowed.
+    pub fn from_env() -> Result<Option<Self>, AuthError> {
+        let max_age_secs = std::env::var("PROOF_REQUEST_MAX_AGE_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_MAX_AGE_SECS);
+
+        match std::env::var("PROOF_REQUEST_VERIFY_KEY") {
+            Ok(key) if !key.trim().is_empty() => Self::new(&key, max_age_secs).map(Some),
+            _ if allow_unsigned_requests() => Ok(None),
+            _ => Err(AuthError::InvalidKey(
+                "PROOF_REQUEST_VERIFY_KEY must be set (or ALLOW_UNSIGNED_PROOF_REQUESTS=true for local development)"
+                    .to_string(),
+            )),
+        }
+    }
+
+    pub fn max_age_secs(&self) -> u64 {
+        self.max_age_secs
+    }
+
+    pub async fn verify(&self, headers: &HeaderMap, body: &[u8]) -> Result<(), AuthError> {
+        self.verify_at(headers, body, unix_now()).await
+    }
+
+    pub async fn verify_at(
+        &self,
+        headers: &HeaderMap,
+        body: &[u8],
+        now: u64,
+    ) -> Result<(), AuthError> {
+        let timestamp = header_value(headers, TIMESTAMP_HEADER)?
+            .parse::<u64>()
+            .map_err(|e| AuthError::InvalidHeader(TIMESTAMP_HEADER, e.to_string()))?;
+        let nonce = header_value(headers, NONCE_HEADER)?;
+        let signature = header_value(headers, SIGNATURE_HEADER)?;
+
+        if timestamp.abs_diff(now) > self.max_age_secs {
+            return Err(AuthError::Stale {
+                timestamp,
+                max_age_secs: self.max_age_secs,
+            });
+        }
+        if nonce.is_empty() || nonce.len() > 128 {
+            return Err(AuthError::InvalidHeader(
+                NONCE_HEADER,
+                "nonce must be 1-128 characters".to_string(),
+            ));
+        }
+
+        let signature_bytes = decode(signature)
+            .map_err(|e| AuthError::InvalidHeader(SIGNATURE_HEADER, e.to_string()))?;
+        let signature = Signature::try_from(signature_bytes.as_slice())
+            .map_err(|e| AuthError::InvalidHeader(SIGNATURE_HEADER, e.to_string()))?;
+        self.public_key
+            .verify_strict(&signing_message(timestamp, nonce, body), &signature)
+            .map_err(|_| AuthError::InvalidSignature)?;
+
+        // 署名検証に成功したリクエストのみ nonce を記録する
+        let mut seen_nonces = self.seen_nonces.lock().await;
+        let max_age_secs = self.max_age_secs;
+        seen_nonces.retain(|_, seen_at| seen_at.abs_diff(now) <= max_age_secs);
+        if seen_nonces.contains_key(nonce) {
+            return Err(AuthError::ReplayedNonce);
+        }
+        seen_nonces.insert(nonce.to_string(), timestamp);
+        Ok(())
+    }
+}
+
+pub fn allow_unsigned_requests() -> bool {
+    std::env::var("ALLOW_UNSIGNED_PROOF_REQUESTS")
+        .map(|v| v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+fn header_value<'a>(headers: &'a HeaderMap, name: &'static str) -> Result<&'a str, AuthError> {
+    headers
+        .get(name)
+        .ok_or(AuthError::MissingHeader(name))?
+        .to_str()
+        .map_err(|e| AuthError::InvalidHeader(name, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(signed: &SignedHeaders) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in signed.pairs() {
+            headers.insert(name, HeaderValue::from_str(&value).unwrap());
+        }
+        headers
+    }
+
+    fn verifier(signer: &RequestSigner) -> RequestVerifier {
+        RequestVerifier::new(&signer.public_key_base64(), 60).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_signed_request_is_accepted_once() {
+        let (signer, _) = RequestSigner::generate();
+        let verifier = verifier(&signer);
+        let body = br#"{"proof_id":"proof-1"}"#;
+        let signed = signer.sign_at(body, 1_000);
+
+        assert_eq!(
+            verifier.verify_at(&headers(&signed), body, 1_010).await,
+            Ok(())
+        );
+        assert_eq!(
+            verifier.verify_at(&headers(&signed), body, 1_020).await,
+            Err(AuthError::ReplayedNonce)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_tampered_body_and_foreign_key() {
+        let (signer, _) = RequestSigner::generate();
+        let (other, _) = RequestSigner::generate();
+        let verifier = verifier(&signer);
+        let signed = signer.sign_at(br#"{"proof_id":"proof-1"}"#, 1_000);
+
+        assert_eq!(
+            verifier
+                .verify_at(&headers(&signed), br#"{"proof_id":"proof-2"}"#, 1_000)
+                .await,
+            Err(AuthError::InvalidSignature)
+        );
+
+        let forged = other.sign_at(br#"{"proof_id":"proof-1"}"#, 1_000);
+        assert_eq!(
+            verifier
+                .verify_at(&headers(&forged), br#"{"proof_id":"proof-1"}"#, 1_000)
+                .await,
+            Err(AuthError::InvalidSignature)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejects_stale_and_unsigned_requests() {
+        let (signer, secret) = RequestSigner::generate();
+        let verifier = verifier(&signer);
+        let body = b"{}";
+
+        let signed = signer.sign_at(body, 1_000);
+        assert!(matches!(
+            verifier.verify_at(&headers(&signed), body, 1_061).await,
+            Err(AuthError::Stale { .. })
+        ));
+        assert_eq!(
+            verifier.verify_at(&HeaderMap::new(), body, 1_000).await,
+            Err(AuthError::MissingHeader(TIMESTAMP_HEADER))
+        );
+
+        // 秘密鍵から復元した署名者は同じ公開鍵を持つ
+        let restored = RequestSigner::from_base64_secret(&secret).unwrap();
+        assert_eq!(restored.public_key_base64(), signer.public_key_base64());
+    }
+}
@@ -0,0 +1,185 @@
+use crate::models::ProofRequest;
+use mpc_net::{MpcMultiNet as Net, MpcNet};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+const DIGEST_DOMAIN: &str = "zk-mpc-node/proof-request-consistency/v1";
+
+#[derive(Error, Debug)]
+pub enum RequestConsistencyError {
+    #[error("Failed to compute proof request digest: {0}")]
+    Digest(String),
+    #[error("Invalid proof request digest from node {0}")]
+    InvalidDigest(usize),
+    #[error(
+        "Proof request {proof_id} differs between nodes: local digest {local_digest}, mismatched nodes {mismatched_nodes:?}"
+    )]
+    Mismatch {
+        proof_id: String,
+        local_digest: String,
+        mismatched_nodes: Vec<usize>,
+    },
+}
+
+/// SHA-256 over the whole request: the proof_id, the circuit kind with its public
+/// inputs and every node's encrypted share, and the output type.
+///
+/// The server sends the same request, with the shares of all nodes, to every node, so
+/// the shares are covered too: a node must not prove with shares the others did not see.
+pub fn request_digest(request: &ProofRequest) -> Result<[u8; 32], RequestConsistencyError> {
+    let request = serde_json::to_value(request)
+        .map_err(|e| RequestConsistencyError::Digest(e.to_string()))?;
+
+    digest_json(&json!({
+        "domain": DIGEST_DOMAIN,
+        "request": request,
+    }))
+}
+
+fn digest_json(value: &Value) -> Result<[u8; 32], RequestConsistencyError> {
+    // serde_json::Map はキー順に並ぶため、同じ内容なら全ノードで同じバイト列になる
+    let bytes =
+        serde_json::to_vec(value).map_err(|e| RequestConsistencyError::Digest(e.to_string()))?;
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(&Sha256::digest(&bytes));
+    Ok(digest)
+}
+
+/// Returns the party ids whose digest differs from ours.
+pub fn mismatched_nodes(local: &[u8; 32], digests: &[Vec<u8>]) -> Vec<usize> {
+    digests
+        .iter()
+        .enumerate()
+        .filter(|(_, digest)| digest.as_slice() != local.as_slice())
+        .map(|(party_id, _)| party_id)
+        .collect()
+}
+
+/// Exchanges the request digest with every node over the MPC channel and fails
+/// if any node received a different request. Must be called by all nodes of the
+/// session before the first MPC operation.
+pub async fn ensure_request_consistency(
+    request: &ProofRequest,
+) -> Result<(), RequestConsistencyError> {
+    let local = request_digest(request)?;
+    let digests = Net::broadcast_bytes(&local).await;
+
+    if let Some(party_id) = digests
+        .iter()
+        .position(|digest| digest.len() != local.len())
+    {
+        return Err(RequestConsistencyError::InvalidDigest(party_id));
+    }
+
+    let mismatched_nodes = mismatched_nodes(&local, &digests);
+    if mismatched_nodes.is_empty() {
+        Ok(())
+    } else {
+        Err(RequestConsistencyError::Mismatch {
+            proof_id: request.proof_id.clone(),
+            local_digest: hex::encode(local),
+            mismatched_nodes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProofOutputType;
+    use ark_bn254::Fr;
+    use ark_std::test_rng;
+    use mpc_algebra::CommitmentScheme;
+    use mpc_algebra_wasm::{
+        CircuitEncryptedInputIdentifier, KeyPublicizeOutput, KeyPublicizePublicInput,
+        NodeEncryptedShare, StateChainParameter,
+    };
+    use zk_mpc::circuits::LocalOrMPC;
+
+    fn request(shares: &[&str], player_num: usize, output_type: ProofOutputType) -> ProofRequest {
+        let pedersen_param =
+            <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(&mut test_rng()).unwrap();
+        let output = KeyPublicizeOutput {
+            shares: shares
+                .iter()
+                .enumerate()
+                .map(|(node_id, share)| NodeEncryptedShare {
+                    node_id: node_id.to_string(),
+                    encrypted_share: share.to_string(),
+                    nonce: "nonce".to_string(),
+                    ephemeral_key: "ephemeral-key".to_string(),
+                })
+                .collect(),
+            public_input: KeyPublicizePublicInput {
+                pedersen_param,
+                role_commitment: vec![Default::default(); player_num],
+                state_chain: StateChainParameter::default(),
+            },
+        };
+
+        ProofRequest {
+            proof_id: "proof-1".to_string(),
+            circuit_type: CircuitEncryptedInputIdentifier::KeyPublicize(vec![output; player_num]),
+            output_type,
+        }
+    }
+
+    fn digest(request: &ProofRequest) -> [u8; 32] {
+        request_digest(request).unwrap()
+    }
+
+    #[test]
+    fn test_digest_is_deterministic() {
+        let shares = ["share-0", "share-1", "share-2"];
+        assert_eq!(
+            digest(&request(&shares, 4, ProofOutputType::Public)),
+            digest(&request(&shares, 4, ProofOutputType::Public))
+        );
+    }
+
+    #[test]
+    fn test_digest_covers_encrypted_shares() {
+        let base = digest(&request(
+            &["share-0", "share-1", "share-2"],
+            4,
+            ProofOutputType::Public,
+        ));
+        // サーバーが1ノード分のシェアだけ差し替えても検知する
+        assert_ne!(
+            base,
+            digest(&request(
+                &["share-0", "share-1", "other"],
+                4,
+                ProofOutputType::Public
+            ))
+        );
+    }
+
+    #[test]
+    fn test_digest_covers_public_inputs_and_output_type() {
+        let shares = ["share-0", "share-1", "share-2"];
+        let base = digest(&request(&shares, 4, ProofOutputType::Public));
+        assert_ne!(base, digest(&request(&shares, 5, ProofOutputType::Public)));
+        assert_ne!(
+            base,
+            digest(&request(
+                &shares,
+                4,
+                ProofOutputType::PrivateToPrivate("pubkey".to_string())
+            ))
+        );
+
+        let mut other_proof = request(&shares, 4, ProofOutputType::Public);
+        other_proof.proof_id = "proof-2".to_string();
+        assert_ne!(base, digest(&other_proof));
+    }
+
+    #[test]
+    fn test_mismatched_nodes() {
+        let local = [1u8; 32];
+        let digests = vec![local.to_vec(), [2u8; 32].to_vec(), local.to_vec()];
+        assert_eq!(mismatched_nodes(&local, &digests), vec![1]);
+        assert!(mismatched_nodes(&local, &[local.to_vec(), local.to_vec()]).is_empty());
+    }
+}
@@ -4,6 +4,7 @@ use crypto_box::{
     PublicKey, SalsaBox, SecretKey,
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 use thiserror::Error;
 use tokio::sync::RwLock;
@@ -47,6 +48,12 @@ impl UserPublicKey {
     }
 }
 
+pub fn public_key_fingerprint(public_key_base64: &str) -> Result<String, CryptoError> {
+    let public_key_bytes = decode(public_key_base64)
+        .map_err(|e| CryptoError::KeyGenerationError(format!("Invalid public key: {}", e)))?;
+    Ok(hex::encode(Sha256::digest(&public_key_bytes)))
+}
+
 pub struct KeyManager {
     keys: Arc<RwLock<Option<NodeKeys>>>,
 }
@@ -128,6 +135,12 @@ impl KeyManager {
             .ok_or(CryptoError::KeyNotInitialized)
     }
 
+    /// SHA-256 of the raw public key bytes, hex encoded.
+    pub async fn get_public_key_fingerprint(&self) -> Result<String, CryptoError> {
+        let public_key = self.get_public_key().await?;
+        public_key_fingerprint(&public_key)
+    }
+
     pub async fn get_secret_key(&self) -> Result<String, CryptoError> {
         self.keys
             .read()
@@ -234,6 +247,22 @@ mod tests {
         assert_eq!(keys.public_key, public_key);
     }
 
+    #[tokio::test]
+    async fn test_public_key_fingerprint() {
+        let key_manager = KeyManager::new();
+        let keys = key_manager
+            .generate_keypair(1, Some("data/test_node_keys_1.json".to_string()))
+            .await
+            .unwrap();
+        let fingerprint = key_manager.get_public_key_fingerprint().await.unwrap();
+
+        assert_eq!(fingerprint.len(), 64);
+        assert_eq!(
+            fingerprint,
+            public_key_fingerprint(&keys.public_key).unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_encrypt_decrypt_share() {
         let key_manager = KeyManager::new();
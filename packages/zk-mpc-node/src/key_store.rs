@@ -0,0 +1,164 @@
+use flate2::read::GzDecoder;
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+/// Uncompressed proving key, as written by the setup binaries.
+pub const PK_SUFFIX: &str = ".pk";
+/// Gzip of a `.pk` file (`gzip -k <circuit_id>.pk`). The manifest hash is that of the `.pk`.
+pub const PK_GZIP_SUFFIX: &str = ".pk.gz";
+
+/// Circuit id of a proving key file name, e.g. `divination_n5_v1` for `divination_n5_v1.pk.gz`.
+pub fn pk_circuit_id(file_name: &str) -> Option<&str> {
+    file_name
+        .strip_suffix(PK_GZIP_SUFFIX)
+        .or_else(|| file_name.strip_suffix(PK_SUFFIX))
+}
+
+/// Streams a proving key file and hashes the uncompressed bytes as they are read, so the
+/// key is checked against the manifest without holding the whole file in memory.
+pub struct ProvingKeyReader {
+    inner: Box<dyn Read + Send>,
+    hasher: Sha256,
+}
+
+impl ProvingKeyReader {
+    /// Opens a `.pk` or `.pk.gz` file.
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let file = BufReader::new(File::open(path)?);
+        let is_gzip = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .is_some_and(|name| name.ends_with(PK_GZIP_SUFFIX));
+        let inner: Box<dyn Read + Send> = if is_gzip {
+            Box::new(BufReader::new(GzDecoder::new(file)))
+        } else {
+            Box::new(file)
+        };
+        Ok(Self {
+            inner,
+            hasher: Sha256::new(),
+        })
+    }
+
+    /// Reads the rest of the key and returns the SHA-256 hex of the uncompressed bytes
+    /// (`Groth16ManifestEntry::pk_hash`).
+    pub fn finish(mut self) -> std::io::Result<String> {
+        std::io::copy(&mut self, &mut std::io::sink())?;
+        Ok(hex::encode(self.hasher.finalize()))
+    }
+}
+
+impl Read for ProvingKeyReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// Least recently used cache for deserialized keys. A handful of profiles is cached at a
+/// time, so a linear scan is enough.
+pub struct LruCache<K, V> {
+    capacity: usize,
+    // 先頭ほど最近使ったもの
+    entries: VecDeque<(K, V)>,
+}
+
+impl<K: PartialEq, V: Clone> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: VecDeque::new(),
+        }
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        let entry = self.entries.remove(index)?;
+        let value = entry.1.clone();
+        self.entries.push_front(entry);
+        Some(value)
+    }
+
+    /// Inserts `value` as the most recently used entry and returns the evicted key, if any.
+    pub fn insert(&mut self, key: K, value: V) -> Option<K> {
+        if let Some(index) = self.entries.iter().position(|(k, _)| *k == key) {
+            self.entries.remove(index);
+        }
+        self.entries.push_front((key, value));
+        if self.entries.len() > self.capacity {
+            self.entries.pop_back().map(|(k, _)| k)
+        } else {
+            None
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use mpc_algebra_wasm::sha256_hex;
+    use std::io::Write;
+
+    #[test]
+    fn lru_cache_evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        assert_eq!(cache.insert(1, "a"), None);
+        assert_eq!(cache.insert(2, "b"), None);
+        assert_eq!(cache.get(&1), Some("a"));
+        // 2 が一番古い
+        assert_eq!(cache.insert(3, "c"), Some(2));
+        assert_eq!(cache.get(&2), None);
+        assert_eq!(cache.get(&1), Some("a"));
+        assert_eq!(cache.get(&3), Some("c"));
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn reads_plain_and_gzip_keys_with_the_same_hash() {
+        let dir =
+            std::env::temp_dir().join(format!("zk-mpc-node-key-store-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let body = (0..100_000u32)
+            .flat_map(|i| i.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        let plain = dir.join("divination_n5_v1.pk");
+        std::fs::write(&plain, &body).unwrap();
+        let gzip = dir.join("divination_n5_v1.pk.gz");
+        let mut encoder = GzEncoder::new(File::create(&gzip).unwrap(), Compression::default());
+        encoder.write_all(&body).unwrap();
+        encoder.finish().unwrap();
+
+        for path in [&plain, &gzip] {
+            let mut reader = ProvingKeyReader::open(path).unwrap();
+            let mut head = [0u8; 16];
+            reader.read_exact(&mut head).unwrap();
+            assert_eq!(&head, &body[..16]);
+            assert_eq!(reader.finish().unwrap(), sha256_hex(&body));
+        }
+
+        assert_eq!(
+            pk_circuit_id("divination_n5_v1.pk.gz"),
+            Some("divination_n5_v1")
+        );
+        assert_eq!(
+            pk_circuit_id("divination_n5_v1.pk"),
+            Some("divination_n5_v1")
+        );
+        assert_eq!(pk_circuit_id("divination_n5_v1.marlin.ipk"), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,11 +1,23 @@
+pub mod auth;
+pub mod consistency;
 pub mod crypto;
+pub mod key_store;
+pub mod manifest;
 pub mod models;
 pub mod node;
+pub mod peers;
 pub mod proof;
 pub mod server;
+pub mod supervisor;
 
+pub use auth::*;
+pub use consistency::*;
 pub use crypto::*;
+pub use key_store::*;
+pub use manifest::*;
 pub use models::*;
 pub use node::*;
+pub use peers::*;
 pub use proof::*;
 pub use server::*;
+pub use supervisor::*;
@@ -3,7 +3,12 @@ use std::{env, net::SocketAddr, sync::Arc, time::Duration, time::Instant};
 use structopt::StructOpt;
 use tokio::time::sleep;
 use zk_mpc_node::{
-    models::Command, node::Node, proof::ProofManager, run_server, AppState, KeyManager,
+    models::Command,
+    node::Node,
+    peer_http_endpoints,
+    proof::{ProofManager, ProofStore},
+    run_server, AppState, KeyManager, LinkSupervisor, PeerMonitor, ReconnectPolicy, RequestSigner,
+    RequestVerifier,
 };
 
 #[tokio::main]
@@ -25,6 +30,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             println!("Public key: {}", keys.public_key);
             Ok(())
         }
+        Command::RequestKeyGen => {
+            let (signer, secret_key) = RequestSigner::generate();
+            println!("Set on the server:");
+            println!("PROOF_REQUEST_SIGNING_KEY={}", secret_key);
+            println!("Set on every zk-mpc-node:");
+            println!("PROOF_REQUEST_VERIFY_KEY={}", signer.public_key_base64());
+            Ok(())
+        }
         Command::Start { id } => {
             let boot_started = Instant::now();
             println!("[node:boot] start command received for node id={id}");
@@ -43,13 +56,58 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             ];
 
             println!("[node:boot] using MPC addresses: {:?}", addresses);
+            let peer_monitor = Arc::new(PeerMonitor::new(id, addresses.clone()));
+
+            // 署名付きの証明リクエストのみ受け付ける
+            let request_verifier = RequestVerifier::from_env()?.map(Arc::new);
+            match &request_verifier {
+                Some(verifier) => println!(
+                    "[node:boot] proof requests must be signed (max_age={}s)",
+                    verifier.max_age_secs()
+                ),
+                None => eprintln!(
+                    "[node:boot] WARNING: ALLOW_UNSIGNED_PROOF_REQUESTS=true, accepting unsigned proof requests"
+                ),
+            }
 
-            // Initialize ProofManager
-            let proof_manager = Arc::new(ProofManager::new());
+            // Initialize ProofManager backed by the on-disk proof store
+            let proof_store = ProofStore::from_env(id)?;
+            println!(
+                "[node:boot] using proof store at {} (ttl={:?})",
+                proof_store.dir().display(),
+                proof_store.ttl()
+            );
+            let proof_manager = Arc::new(ProofManager::with_store(proof_store));
+            let interrupted = proof_manager.recover_from_store().await?;
+            if interrupted > 0 {
+                println!(
+                    "[node:boot] marked {} in-flight proof(s) as failed after restart",
+                    interrupted
+                );
+            }
+
+            let gc_interval_secs = env::var("PROOF_STORE_GC_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(600);
+            let gc_proof_manager = proof_manager.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(Duration::from_secs(gc_interval_secs));
+                loop {
+                    ticker.tick().await;
+                    match gc_proof_manager.collect_garbage().await {
+                        Ok(0) => {}
+                        Ok(removed) => {
+                            println!("[node:proof-store] removed {} expired proof(s)", removed)
+                        }
+                        Err(e) => eprintln!("[node:proof-store] garbage collection failed: {}", e),
+                    }
+                }
+            });
 
             // Initialize the MPC network from environment addresses
             println!("[node:boot] creating MPC network connection for node {id}");
-            let mut net = MPCNetConnection::new(id, addresses).unwrap();
+            let mut net = MPCNetConnection::new(id, addresses.clone()).unwrap();
             println!("[node:boot] start listening for MPC peers...");
             net.listen().await.expect("Failed to listen");
             println!("[node:boot] MPC listener is ready");
@@ -68,13 +126,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             // Initialize the node
             let node_init_started = Instant::now();
             println!("[node:boot] start Node::new for node {id}...");
-            let mut node = Node::new(id, net, proof_manager.clone(), key_manager, server_url).await;
+            let node = Node::new(id, net, proof_manager.clone(), key_manager, server_url).await;
             println!(
                 "[node:boot] finished Node::new for node {id} in {} ms",
                 node_init_started.elapsed().as_millis()
             );
 
-            let net = Arc::get_mut(&mut node.net).expect(
+            let mut net_slot = node.net.write().await;
+            let net = net_slot.as_mut().and_then(Arc::get_mut).expect(
                 "node.net should be uniquely owned before wrapping Node in Arc; cannot run connect_to_all",
             );
             let mut connect_attempt = 0u32;
@@ -87,6 +146,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 );
                 match net.connect_to_all().await {
                     Ok(_) => {
+                        peer_monitor.mark_all_connected().await;
                         println!(
                             "[node:boot] connected to all peers (attempt {}/{}, elapsed={} ms)",
                             connect_attempt,
@@ -96,6 +156,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         break;
                     }
                     Err(e) if connect_attempt < connect_retry_max => {
+                        peer_monitor
+                            .mark_all_disconnected(&format!("{:?}", e))
+                            .await;
                         eprintln!(
                             "[node:boot] failed to connect to all peers (attempt {}/{}): {:?}. Retrying in {:?}...",
                             connect_attempt, connect_retry_max, e, connect_retry_interval
@@ -111,22 +174,41 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     }
                 }
             }
+            drop(net_slot);
             let node = Arc::new(node);
             println!(
                 "[node:boot] wrapped initialized node {id} into shared Arc after successful peer connection"
             );
 
+            let http_port_base = env::var("MPC_HTTP_PORT")
+                .unwrap_or_else(|_| "9000".to_string())
+                .parse::<u16>()
+                .unwrap_or(9000);
+
+            // Supervise MPC links and rebuild the mesh when a session reports a broken link
+            // or a link check finds a peer that is gone, restarted or rebuilding its links
+            let supervisor = Arc::new(LinkSupervisor::new(
+                id,
+                addresses.clone(),
+                peer_monitor.clone(),
+                ReconnectPolicy::from_env(),
+            ));
+            tokio::spawn(supervisor.clone().run(node.clone()));
+            let peer_endpoints = peer_http_endpoints(id, &addresses, http_port_base, |peer_id| {
+                env::var(format!("ZK_MPC_NODE_{}_HTTP", peer_id)).ok()
+            });
+            println!("[node:boot] checking MPC links via {:?}", peer_endpoints);
+            tokio::spawn(supervisor.clone().check_links(peer_endpoints));
+
             let state = AppState {
                 proof_manager: proof_manager.clone(),
                 node: node.clone(),
+                peer_monitor: peer_monitor.clone(),
+                supervisor: supervisor.clone(),
+                request_verifier,
             };
 
             // Create a listener for client connections
-            let http_port_base = env::var("MPC_HTTP_PORT")
-                .unwrap_or_else(|_| "9000".to_string())
-                .parse::<u16>()
-                .unwrap_or(9000);
-
             // Each node listens on a different port based on its ID
             // e.g., node 0 -> 9000, node 1 -> 9001, node 2 -> 9002
             let http_port = http_port_base + id as u16;
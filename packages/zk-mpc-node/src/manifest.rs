@@ -0,0 +1,251 @@
+use ark_groth16::VerifyingKey;
+use ark_serialize::CanonicalSerialize;
+use base64::decode;
+use ed25519_dalek::{PublicKey, Signature};
+use mpc_algebra_wasm::{
+    sha256_hex, Groth16Manifest, Groth16ManifestEntry, GROTH16_MANIFEST_FILE_NAME,
+    GROTH16_MANIFEST_VERSION,
+};
+use std::path::Path;
+use thiserror::Error;
+
+/// Base64 Ed25519 public key the manifest must be signed with. When set, the manifest is required.
+pub const MANIFEST_PUBLIC_KEY_ENV: &str = "GROTH16_MANIFEST_PUBLIC_KEY";
+/// Set to `true` to start without [`MANIFEST_PUBLIC_KEY_ENV`] in local development.
+pub const ALLOW_UNPINNED_MANIFEST_ENV: &str = "ALLOW_UNPINNED_GROTH16_MANIFEST";
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ManifestError {
+    #[error("Groth16 manifest {0} is required because {MANIFEST_PUBLIC_KEY_ENV} is set")]
+    Missing(String),
+    #[error("{MANIFEST_PUBLIC_KEY_ENV} must be set (or {ALLOW_UNPINNED_MANIFEST_ENV}=true for local development)")]
+    UnpinnedSigner,
+    #[error("Failed to read Groth16 manifest: {0}")]
+    Read(String),
+    #[error("Unsupported Groth16 manifest version {0}")]
+    UnsupportedVersion(u32),
+    #[error("Invalid Groth16 manifest key: {0}")]
+    InvalidKey(String),
+    #[error("Groth16 manifest is signed by an untrusted key")]
+    UntrustedSigner,
+    #[error("Groth16 manifest signature verification failed")]
+    InvalidSignature,
+    #[error("{0} is not listed in the Groth16 manifest")]
+    UnknownCircuit(String),
+    #[error("{circuit_id}: {field} mismatch (manifest {expected}, file {actual})")]
+    Mismatch {
+        circuit_id: String,
+        field: &'static str,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Reads `manifest.json` from the Groth16 data dir and checks its signature.
+/// The signer must be pinned with [`MANIFEST_PUBLIC_KEY_ENV`] unless unpinned manifests are
+/// explicitly allowed; then `None` is returned when the file is absent (dev setups).
+pub fn load_manifest(data_dir: &Path) -> Result<Option<Groth16Manifest>, ManifestError> {
+    let trusted_signer = std::env::var(MANIFEST_PUBLIC_KEY_ENV)
+        .ok()
+        .filter(|key| !key.trim().is_empty());
+    load_manifest_with_signer(
+        data_dir,
+        trusted_signer.as_deref(),
+        allow_unpinned_manifest(),
+    )
+}
+
+pub fn allow_unpinned_manifest() -> bool {
+    std::env::var(ALLOW_UNPINNED_MANIFEST_ENV)
+        .map(|v| v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+fn load_manifest_with_signer(
+    data_dir: &Path,
+    trusted_signer: Option<&str>,
+    allow_unpinned: bool,
+) -> Result<Option<Groth16Manifest>, ManifestError> {
+    if trusted_signer.is_none() && !allow_unpinned {
+        return Err(ManifestError::UnpinnedSigner);
+    }
+    let path = data_dir.join(GROTH16_MANIFEST_FILE_NAME);
+    if !path.exists() {
+        return match trusted_signer {
+            Some(_) => Err(ManifestError::Missing(path.display().to_string())),
+            None => Ok(None),
+        };
+    }
+
+    let body = std::fs::read_to_string(&path).map_err(|e| ManifestError::Read(e.to_string()))?;
+    let manifest: Groth16Manifest =
+        serde_json::from_str(&body).map_err(|e| ManifestError::Read(e.to_string()))?;
+    verify_manifest(&manifest, trusted_signer)?;
+    if trusted_signer.is_none() {
+        // 埋め込まれた署名者でしか確認していないので、差し替えられた manifest も通る
+        eprintln!(
+            "[node:init][groth16] WARNING: {MANIFEST_PUBLIC_KEY_ENV} is not set. The manifest is only checked against its own signer {}, so anyone who can write the data dir can replace the keys. Do not run like this outside local development",
+            manifest.signer_public_key
+        );
+    }
+    Ok(Some(manifest))
+}
+
+pub fn verify_manifest(
+    manifest: &Groth16Manifest,
+    trusted_signer: Option<&str>,
+) -> Result<(), ManifestError> {
+    if manifest.version != GROTH16_MANIFEST_VERSION {
+        return Err(ManifestError::UnsupportedVersion(manifest.version));
+    }
+    if let Some(trusted) = trusted_signer {
+        if trusted.trim() != manifest.signer_public_key {
+            return Err(ManifestError::UntrustedSigner);
+        }
+    }
+
+    let public_key_bytes = decode(&manifest.signer_public_key)
+        .map_err(|e| ManifestError::InvalidKey(format!("invalid base64: {}", e)))?;
+    let public_key = PublicKey::from_bytes(&public_key_bytes)
+        .map_err(|e| ManifestError::InvalidKey(e.to_string()))?;
+    let signature_bytes =
+        decode(&manifest.signature).map_err(|_| ManifestError::InvalidSignature)?;
+    let signature = Signature::try_from(signature_bytes.as_slice())
+        .map_err(|_| ManifestError::InvalidSignature)?;
+
+    public_key
+        .verify_strict(
+            &Groth16Manifest::signing_message(manifest.version, &manifest.entries),
+            &signature,
+        )
+        .map_err(|_| ManifestError::InvalidSignature)
+}
+
+/// Same hash as `arkworks_solidity_verifier::schemes::groth16::vk_hash`.
+pub fn vk_hash(vk: &VerifyingKey<ark_bn254::Bn254>) -> String {
+    let mut bytes = Vec::new();
+    vk.serialize_uncompressed(&mut bytes)
+        .expect("serializing into a Vec cannot fail");
+    sha256_hex(&bytes)
+}
+
+/// Checks a loaded `.pk` file against its manifest entry.
+pub fn check_proving_key(
+    entry: &Groth16ManifestEntry,
+    pk_bytes: &[u8],
+    vk: &VerifyingKey<ark_bn254::Bn254>,
+) -> Result<(), ManifestError> {
+    check_proving_key_hash(entry, &sha256_hex(pk_bytes), vk)
+}
+
+/// Same as [`check_proving_key`] with the SHA-256 hex of the `.pk` bytes computed by the caller,
+/// e.g. by [`ProvingKeyReader`](crate::ProvingKeyReader) while streaming the key.
+pub fn check_proving_key_hash(
+    entry: &Groth16ManifestEntry,
+    pk_hash: &str,
+    vk: &VerifyingKey<ark_bn254::Bn254>,
+) -> Result<(), ManifestError> {
+    let mismatch = |field: &'static str, expected: String, actual: String| {
+        if expected == actual {
+            Ok(())
+        } else {
+            Err(ManifestError::Mismatch {
+                circuit_id: entry.circuit_id.clone(),
+                field,
+                expected,
+                actual,
+            })
+        }
+    };
+
+    mismatch("pk hash", entry.pk_hash.clone(), pk_hash.to_string())?;
+    mismatch("vk hash", entry.vk_hash.clone(), vk_hash(vk))?;
+    mismatch(
+        "public input length",
+        entry.public_input_len.to_string(),
+        (vk.gamma_abc_g1.len() - 1).to_string(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Keypair, SecretKey, Signer};
+    use mpc_algebra_wasm::CircuitProfile;
+
+    fn signed_manifest(secret: [u8; 32]) -> Groth16Manifest {
+        let secret = SecretKey::from_bytes(&secret).unwrap();
+        let public = PublicKey::from(&secret);
+        let keypair = Keypair { secret, public };
+        let entries = vec![Groth16ManifestEntry {
+            circuit_id: "divination_n5_v1".to_string(),
+            profile: CircuitProfile::Divination { player_count: 5 },
+            vk_hash: sha256_hex(b"vk"),
+            pk_hash: sha256_hex(b"pk"),
+            public_input_len: 11,
+        }];
+        let signature = keypair.sign(&Groth16Manifest::signing_message(
+            GROTH16_MANIFEST_VERSION,
+            &entries,
+        ));
+        Groth16Manifest {
+            version: GROTH16_MANIFEST_VERSION,
+            entries,
+            signer_public_key: base64::encode(public.as_bytes()),
+            signature: base64::encode(signature.to_bytes()),
+        }
+    }
+
+    #[test]
+    fn verifies_signed_manifest() {
+        let manifest = signed_manifest([7u8; 32]);
+        assert_eq!(verify_manifest(&manifest, None), Ok(()));
+        assert_eq!(
+            verify_manifest(&manifest, Some(&manifest.signer_public_key)),
+            Ok(())
+        );
+
+        let other = signed_manifest([8u8; 32]);
+        assert_eq!(
+            verify_manifest(&manifest, Some(&other.signer_public_key)),
+            Err(ManifestError::UntrustedSigner)
+        );
+    }
+
+    #[test]
+    fn requires_a_pinned_signer_unless_allowed() {
+        let dir = std::env::temp_dir().join(format!("manifest-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let manifest = signed_manifest([7u8; 32]);
+        std::fs::write(
+            dir.join(GROTH16_MANIFEST_FILE_NAME),
+            serde_json::to_string(&manifest).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            load_manifest_with_signer(&dir, None, false),
+            Err(ManifestError::UnpinnedSigner)
+        );
+        assert_eq!(
+            load_manifest_with_signer(&dir, None, true),
+            Ok(Some(manifest.clone()))
+        );
+        assert_eq!(
+            load_manifest_with_signer(&dir, Some(&manifest.signer_public_key), false),
+            Ok(Some(manifest))
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_tampered_manifest() {
+        let mut manifest = signed_manifest([7u8; 32]);
+        manifest.entries[0].pk_hash = sha256_hex(b"other pk");
+        assert_eq!(
+            verify_manifest(&manifest, None),
+            Err(ManifestError::InvalidSignature)
+        );
+    }
+}
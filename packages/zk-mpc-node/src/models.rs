@@ -1,7 +1,9 @@
 pub mod api;
 pub mod cli;
 pub mod proof;
+pub mod status;
 
 pub use api::*;
 pub use cli::*;
 pub use proof::*;
+pub use status::*;
@@ -10,6 +10,9 @@ pub enum Command {
         #[structopt(long)]
         id: u32,
     },
+    /// generate the Ed25519 keypair the server uses to sign proof requests
+    #[structopt(name = "request-keygen")]
+    RequestKeyGen,
     /// start the node
     #[structopt(name = "start")]
     Start {
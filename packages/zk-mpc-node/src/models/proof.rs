@@ -1,4 +1,4 @@
-use mpc_algebra_wasm::CircuitEncryptedInputIdentifier;
+use mpc_algebra_wasm::{CircuitEncryptedInputIdentifier, ProofScheme};
 use serde::{Deserialize, Serialize};
 
 use crate::UserPublicKey;
@@ -42,6 +42,10 @@ pub struct ProofOutput {
     pub public_inputs: Option<Vec<u8>>, // オンチェーン検証向けの公開入力バイト列
     // pub shares: Option<Vec<Vec<u8>>>, // 暗号化されたシェア
     pub shares: Option<Vec<EncryptedShare>>, // 暗号化されたシェア
+    #[serde(default)]
+    pub manifest_hash: Option<String>, // 証明に使った鍵の manifest ハッシュ
+    #[serde(default)]
+    pub scheme: ProofScheme, // proof のバイト列を作った証明方式
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
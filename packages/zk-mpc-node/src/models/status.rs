@@ -0,0 +1,32 @@
+use mpc_algebra_wasm::CircuitProfile;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerStatus {
+    pub peer_id: u32,
+    pub address: String,
+    pub connected: bool, // MPCNetConnection 上のリンクが確立済みか
+    pub last_error: Option<String>,
+    pub updated_at: u64, // UNIX秒
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeStatus {
+    pub node_id: u32,
+    pub version: String,
+    pub ready: bool,
+    #[serde(default)]
+    pub degraded_reason: Option<String>, // MPCリンク再接続中の理由
+    pub key_initialized: bool,
+    pub public_key_fingerprint: Option<String>,
+    pub peers: Vec<PeerStatus>,
+    pub loaded_profiles: Vec<CircuitProfile>,
+    pub in_flight_proofs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeReadiness {
+    pub node_id: u32,
+    pub ready: bool,
+    pub reasons: Vec<String>, // ready=false の理由
+}
@@ -1,28 +1,55 @@
+use crate::consistency::ensure_request_consistency;
 use crate::crypto::KeyManager;
+use crate::key_store::{pk_circuit_id, LruCache, ProvingKeyReader};
+use crate::manifest::{
+    allow_unpinned_manifest, check_proving_key_hash, load_manifest, ManifestError,
+    ALLOW_UNPINNED_MANIFEST_ENV,
+};
 use crate::models::ProofRequest;
 use crate::proof::ProofManager;
 use crate::server::ApiClient;
 use crate::{EncryptedShare, ProofOutput, ProofOutputType, UserPublicKey};
 use ark_ff::{BigInteger, PrimeField};
-use ark_groth16::{generate_random_parameters, prepare_verifying_key, verify_proof, ProvingKey};
+use ark_groth16::{
+    generate_random_parameters, prepare_verifying_key, verify_proof, ProvingKey, VerifyingKey,
+};
+use ark_marlin::{IndexProverKey, Marlin};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly_commit::marlin_pc::MarlinKZG10;
 use ark_serialize::CanonicalDeserialize;
 use ark_std::test_rng;
+use arkworks_solidity_verifier::schemes::marlin::marlin_pc;
 use mpc_algebra::{AdditivePairingShare, MpcPairingEngine, Reveal};
-use mpc_algebra_wasm::{CircuitEncryptedInputIdentifier, CircuitProfile};
-use mpc_circuits::CircuitFactory;
+use mpc_algebra_wasm::{
+    CircuitEncryptedInputIdentifier, CircuitProfile, Groth16Manifest, Groth16ManifestEntry,
+    InstanceMode, ProofScheme,
+};
+use mpc_circuits::{verifier_public_inputs, BuiltinCircuit, CircuitFactory};
 use mpc_net::multi::MPCNetConnection;
 use serde::{Deserialize, Serialize};
+use sha3::Keccak256;
 use std::collections::HashMap;
 use std::iter::zip;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::RwLock;
 use zk_mpc::groth16::create_random_proof;
+use zk_mpc::marlin::MFr;
 
 type LocalProvingKey = ProvingKey<ark_bn254::Bn254>;
 type MPCProvingKey =
     ProvingKey<MpcPairingEngine<ark_bn254::Bn254, AdditivePairingShare<ark_bn254::Bn254>>>;
+type MpcEngine = MpcPairingEngine<ark_bn254::Bn254, AdditivePairingShare<ark_bn254::Bn254>>;
+type LocalMarlinPC = MarlinKZG10<ark_bn254::Bn254, DensePolynomial<ark_bn254::Fr>>;
+// Solidity verifier と同じ Keccak256 の transcript を使う
+type LocalMarlin = Marlin<ark_bn254::Fr, LocalMarlinPC, Keccak256>;
+type MpcMarlin = Marlin<MFr, MarlinKZG10<MpcEngine, DensePolynomial<MFr>>, Keccak256>;
+type LocalMarlinProvingKey = IndexProverKey<ark_bn254::Fr, LocalMarlinPC>;
+
+/// File suffix of the Marlin index prover keys written by `multi_profile_marlin_setup`.
+pub const MARLIN_INDEX_SUFFIX: &str = ".marlin.ipk";
 
 #[derive(Serialize)]
 struct RoleSharePayload<'a> {
@@ -31,6 +58,8 @@ struct RoleSharePayload<'a> {
     role_share_encoding: &'static str,
     werewolf_mates_mask_share: &'a str,
     werewolf_mates_mask_share_encoding: &'static str,
+    role_flags_share: &'a str,
+    role_flags_share_encoding: &'static str,
 }
 
 #[derive(Deserialize)]
@@ -38,93 +67,238 @@ struct RoleOutputV2 {
     role_share: String,
     #[serde(default)]
     werewolf_mates_mask_share: Option<String>,
+    #[serde(default)]
+    role_flags_share: Option<String>,
 }
 
-#[derive(Clone)]
+/// Profile key converted for MPC proving. `ProvingKey::from_public` runs once per load,
+/// not once per proof.
 struct Groth16Setup {
-    local_proving_key: Arc<LocalProvingKey>,
+    pvk: ark_groth16::PreparedVerifyingKey<ark_bn254::Bn254>,
+    mpc_proving_key: MPCProvingKey,
 }
 
 impl Groth16Setup {
-    fn from_pk_path(path: &PathBuf, label: &str) -> Result<Self, std::io::Error> {
-        let total_started = Instant::now();
+    fn new(local_proving_key: LocalProvingKey) -> Self {
+        Self {
+            pvk: prepare_verifying_key(&local_proving_key.vk),
+            mpc_proving_key: ProvingKey::from_public(local_proving_key),
+        }
+    }
+}
+
+/// A profile proving key found in the data dir. It is read when the profile is first proven.
+#[derive(Clone)]
+struct Groth16KeyFile {
+    path: PathBuf,
+    manifest_entry: Option<Groth16ManifestEntry>,
+}
+
+impl Groth16KeyFile {
+    fn load(&self, label: &str) -> Result<Groth16Setup, std::io::Error> {
+        let started = Instant::now();
         println!(
-            "[node:init][groth16] start loading {label} PK from {}",
-            path.display()
+            "[node:groth16] start loading {label} PK from {}",
+            self.path.display()
         );
 
-        let read_started = Instant::now();
-        let bytes = std::fs::read(path)?;
+        // .pk.gz も含めてストリームで読み、ファイル全体をメモリに載せない
+        let mut reader = ProvingKeyReader::open(&self.path)?;
+        let local_proving_key =
+            LocalProvingKey::deserialize_uncompressed(&mut reader).map_err(|e| {
+                std::io::Error::other(format!(
+                    "failed to deserialize {label} proving key {}: {:?}",
+                    self.path.display(),
+                    e
+                ))
+            })?;
+        let pk_hash = reader.finish()?;
+        if let Some(entry) = &self.manifest_entry {
+            check_proving_key_hash(entry, &pk_hash, &local_proving_key.vk)
+                .map_err(std::io::Error::other)?;
+        }
+        let deserialized_ms = started.elapsed().as_millis();
+
+        let setup = Groth16Setup::new(local_proving_key);
         println!(
-            "[node:init][groth16] read {} bytes for {label} PK from {} in {} ms",
-            bytes.len(),
-            path.display(),
-            read_started.elapsed().as_millis()
+            "[node:groth16] loaded {label} PK from {} in {} ms (deserialize {} ms)",
+            self.path.display(),
+            started.elapsed().as_millis(),
+            deserialized_ms
         );
+        Ok(setup)
+    }
+}
 
-        let deserialize_started = Instant::now();
-        let local_proving_key = LocalProvingKey::deserialize_uncompressed(bytes.as_slice())
+#[derive(Clone)]
+struct MarlinSetup {
+    local_index_pk: Arc<LocalMarlinProvingKey>,
+}
+
+impl MarlinSetup {
+    fn from_ipk_path(path: &PathBuf, label: &str) -> Result<Self, std::io::Error> {
+        let started = Instant::now();
+        let bytes = std::fs::read(path)?;
+        let local_index_pk = LocalMarlinProvingKey::deserialize_uncompressed(bytes.as_slice())
             .map_err(|e| {
                 std::io::Error::other(format!(
-                    "failed to deserialize {label} proving key {}: {:?}",
+                    "failed to deserialize {label} Marlin index {}: {:?}",
                     path.display(),
                     e
                 ))
             })?;
         println!(
-            "[node:init][groth16] finished deserializing {label} PK from {} in {} ms (total {} ms)",
+            "[node:init][marlin] loaded {label} index ({} bytes) from {} in {} ms",
+            bytes.len(),
             path.display(),
-            deserialize_started.elapsed().as_millis(),
-            total_started.elapsed().as_millis()
+            started.elapsed().as_millis()
         );
 
         Ok(Self {
-            local_proving_key: Arc::new(local_proving_key),
+            local_index_pk: Arc::new(local_index_pk),
         })
     }
-
-    fn prepared_verifying_key(&self) -> ark_groth16::PreparedVerifyingKey<ark_bn254::Bn254> {
-        prepare_verifying_key(&self.local_proving_key.vk)
-    }
-
-    fn mpc_proving_key(&self) -> MPCProvingKey {
-        ProvingKey::from_public((*self.local_proving_key).clone())
-    }
 }
 
-#[derive(Clone, Default)]
+/// Number of deserialized Groth16 proving keys kept in memory (`GROTH16_PK_CACHE_SIZE`).
+pub const DEFAULT_GROTH16_PK_CACHE_SIZE: usize = 4;
+
 struct CircuitGroth16Setups {
-    by_profile: HashMap<CircuitProfile, Groth16Setup>,
+    /// `ZK_PROOF_SCHEME`. Only the keys of this scheme are loaded.
+    scheme: ProofScheme,
+    /// Groth16 keys are found at boot and deserialized on first use.
+    key_files: HashMap<CircuitProfile, Groth16KeyFile>,
+    cache: Mutex<LruCache<CircuitProfile, Arc<Groth16Setup>>>,
+    marlin_by_profile: HashMap<CircuitProfile, MarlinSetup>,
+    /// Hash of the signed manifest the keys were checked against. `None` for unchecked dev keys.
+    manifest_hash: Option<String>,
 }
 
 impl CircuitGroth16Setups {
     fn load() -> Result<Self, std::io::Error> {
+        if proof_scheme_from_env()? == ProofScheme::Marlin {
+            return Self::load_marlin();
+        }
+
         let started = Instant::now();
-        println!("[node:init][groth16] loading all Groth16 setups...");
+        println!("[node:init][groth16] scanning Groth16 setups...");
+
+        let manifest = load_manifest(&groth16_data_dir()).map_err(std::io::Error::other)?;
+        match &manifest {
+            Some(manifest) => println!(
+                "[node:init][groth16] verified manifest: entries={}, hash={}",
+                manifest.entries.len(),
+                manifest.manifest_hash()
+            ),
+            None => println!(
+                "[node:init][groth16] no manifest found. proving keys are loaded without hash checks"
+            ),
+        }
 
-        let mut by_profile = HashMap::new();
-        load_profile_setups_from_data_dir(&mut by_profile)?;
+        let mut key_files = HashMap::new();
+        scan_profile_keys_in_data_dir(&mut key_files, manifest.as_ref())?;
+        let cache_size = groth16_pk_cache_size_from_env()?;
 
-        let setup = Self { by_profile };
+        let setup = Self {
+            scheme: ProofScheme::Groth16,
+            key_files,
+            cache: Mutex::new(LruCache::new(cache_size)),
+            marlin_by_profile: HashMap::new(),
+            manifest_hash: manifest.map(|manifest| manifest.manifest_hash()),
+        };
 
         println!(
-            "[node:init][groth16] completed loading Groth16 setups in {} ms (profile_setups={})",
+            "[node:init][groth16] completed scanning Groth16 setups in {} ms (profile_keys={}, cache_size={})",
             started.elapsed().as_millis(),
-            setup.by_profile.len(),
+            setup.key_files.len(),
+            cache_size,
         );
 
         Ok(setup)
     }
 
-    fn for_circuit(&self, circuit_type: &CircuitEncryptedInputIdentifier) -> Option<&Groth16Setup> {
+    /// Marlin の index は Groth16 manifest に載らないので hash の確認はしない
+    fn load_marlin() -> Result<Self, std::io::Error> {
+        let started = Instant::now();
+        println!("[node:init][marlin] loading all Marlin indexes...");
+
+        let mut marlin_by_profile = HashMap::new();
+        load_marlin_setups_from_data_dir(&mut marlin_by_profile)?;
+        println!(
+            "[node:init][marlin] completed loading Marlin indexes in {} ms (profile_setups={})",
+            started.elapsed().as_millis(),
+            marlin_by_profile.len(),
+        );
+
+        Ok(Self {
+            scheme: ProofScheme::Marlin,
+            key_files: HashMap::new(),
+            cache: Mutex::new(LruCache::new(1)),
+            marlin_by_profile,
+            manifest_hash: None,
+        })
+    }
+
+    fn profiles(&self) -> Vec<CircuitProfile> {
+        let mut profiles: Vec<CircuitProfile> = match self.scheme {
+            ProofScheme::Groth16 => self.key_files.keys().copied().collect(),
+            ProofScheme::Marlin => self.marlin_by_profile.keys().copied().collect(),
+        };
+        profiles.sort_by_key(|profile| circuit_profile_label(*profile));
+        profiles
+    }
+
+    /// Setup of the circuit's profile, loading its key on a cache miss. `Ok(None)` when the
+    /// data dir has no key for the profile. The key is read on a blocking thread so that a
+    /// cold load does not stall the runtime.
+    async fn for_circuit(
+        &self,
+        circuit_type: &CircuitEncryptedInputIdentifier,
+    ) -> Result<Option<Arc<Groth16Setup>>, std::io::Error> {
+        let Some(profile) = circuit_type.circuit_profile() else {
+            return Ok(None);
+        };
+        let Some(key_file) = self.key_files.get(&profile) else {
+            return Ok(None);
+        };
+        if let Some(setup) = self.lock_cache().get(&profile) {
+            return Ok(Some(setup));
+        }
+
+        // 読み込み中はロックを持たない。同じ profile を同時に読むと二重に読むが、結果は同じ
+        let key_file = key_file.clone();
+        let label = format!("{} profile", circuit_profile_label(profile));
+        let setup = tokio::task::spawn_blocking(move || key_file.load(&label))
+            .await
+            .map_err(std::io::Error::other)??;
+        let setup = Arc::new(setup);
+        if let Some(evicted) = self.lock_cache().insert(profile, setup.clone()) {
+            println!(
+                "[node:groth16] evicted {} PK from the cache",
+                circuit_profile_label(evicted)
+            );
+        }
+        Ok(Some(setup))
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, LruCache<CircuitProfile, Arc<Groth16Setup>>> {
+        // 読み込みに失敗してもキャッシュ自体は壊れないので、poison は無視する
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn marlin_for_circuit(
+        &self,
+        circuit_type: &CircuitEncryptedInputIdentifier,
+    ) -> Option<&MarlinSetup> {
         let profile = circuit_type.circuit_profile()?;
-        self.by_profile.get(&profile)
+        self.marlin_by_profile.get(&profile)
     }
 }
 
 pub struct Node<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> {
     pub id: u32,
-    pub net: Arc<MPCNetConnection<IO>>,
+    /// `None` while the supervisor is rebuilding the MPC mesh.
+    pub net: RwLock<Option<Arc<MPCNetConnection<IO>>>>,
     pub proof_manager: Arc<ProofManager>,
     pub key_manager: Arc<KeyManager>,
     pub api_client: Arc<ApiClient>,
@@ -198,7 +372,7 @@ impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> Node<IO> {
 
         let node = Self {
             id,
-            net: Arc::new(net),
+            net: RwLock::new(Some(Arc::new(net))),
             proof_manager,
             key_manager,
             api_client: api_client.clone(),
@@ -229,6 +403,24 @@ impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> Node<IO> {
         node
     }
 
+    pub async fn net(&self) -> Option<Arc<MPCNetConnection<IO>>> {
+        self.net.read().await.clone()
+    }
+
+    /// Swaps the MPC connection and returns the previous one.
+    pub async fn replace_net(
+        &self,
+        net: Option<MPCNetConnection<IO>>,
+    ) -> Option<Arc<MPCNetConnection<IO>>> {
+        std::mem::replace(&mut *self.net.write().await, net.map(Arc::new))
+    }
+
+    /// Circuit profiles with a proving key (Groth16 or Marlin index) in the data dir. Groth16
+    /// keys are listed before they are loaded.
+    pub fn loaded_profiles(&self) -> Vec<CircuitProfile> {
+        self.groth16_setups.profiles()
+    }
+
     // 公開鍵を登録するメソッドを追加
     pub async fn register_public_key(&self) -> Result<(), Box<dyn std::error::Error>> {
         let started = Instant::now();
@@ -271,6 +463,11 @@ impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> Node<IO> {
             }
         }
 
+        // 全ノードが同じリクエスト（公開入力・出力形式）を受け取っていることを確認してから証明を始める
+        ensure_request_consistency(&request)
+            .await
+            .map_err(|e| -> Box<dyn std::error::Error + Send> { Box::new(e) })?;
+
         // Setup circuit
         let local_circuit = CircuitFactory::create_local_circuit(&request.circuit_type);
 
@@ -286,51 +483,26 @@ impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> Node<IO> {
             &secret_key,
         );
 
-        let inputs = CircuitFactory::create_verify_inputs(&mpc_circuit);
+        let instance = CircuitFactory::create_verify_inputs(&mpc_circuit);
+        // Hashed のときは instance の hash 1 つだけで検証する
+        let inputs = verifier_public_inputs(instance.clone(), request.circuit_type.instance_mode());
 
-        let rng = &mut test_rng();
-        let (pvk, mpc_params): (_, MPCProvingKey) = if let Some(setup) =
-            self.groth16_setups.for_circuit(&request.circuit_type)
-        {
-            (setup.prepared_verifying_key(), setup.mpc_proving_key())
-        } else {
-            let params = generate_random_parameters::<ark_bn254::Bn254, _, _>(local_circuit, rng)
-                .map_err(|e| -> Box<dyn std::error::Error + Send> {
-                Box::new(std::io::Error::other(format!(
-                    "Failed to generate Groth16 parameters: {:?}",
-                    e
-                )))
-            })?;
-            let pvk = prepare_verifying_key(&params.vk);
-            (pvk, ProvingKey::from_public(params))
+        let scheme = self.groth16_setups.scheme;
+        let (is_valid, proof_bytes, manifest_hash) = match scheme {
+            ProofScheme::Groth16 => {
+                self.prove_groth16(&request, local_circuit, mpc_circuit.clone(), &inputs)
+                    .await?
+            }
+            ProofScheme::Marlin => {
+                self.prove_marlin(&request, mpc_circuit.clone(), &instance)
+                    .await?
+            }
         };
 
-        let mpc_proof = create_random_proof::<
-            MpcPairingEngine<ark_bn254::Bn254, AdditivePairingShare<ark_bn254::Bn254>>,
-            _,
-            _,
-        >(mpc_circuit.clone(), &mpc_params, rng)
-        .map_err(|e| -> Box<dyn std::error::Error + Send> {
-            Box::new(std::io::Error::other(format!(
-                "Failed to generate collaborative Groth16 proof: {:?}",
-                e
-            )))
-        })?;
-        let publicized_proof = mpc_proof.reveal().await;
-        let is_valid = verify_proof(&pvk, &publicized_proof, &inputs).map_err(
-            |e| -> Box<dyn std::error::Error + Send> {
-                Box::new(std::io::Error::other(format!(
-                    "Failed to verify Groth16 proof: {:?}",
-                    e
-                )))
-            },
-        )?;
-
         let outputs = if is_valid {
-            let proof_bytes = abi_encode_groth16_proof(&publicized_proof);
             let public_input_len = expected_public_input_len(&request.circuit_type);
             let public_input_bytes = Some(
-                abi_encode_fixed_uint256_inputs(&inputs, public_input_len).map_err(
+                abi_encode_fixed_uint256_inputs(&instance, public_input_len).map_err(
                     |e| -> Box<dyn std::error::Error + Send> {
                         Box::new(std::io::Error::other(format!(
                             "Failed to encode public inputs: {}",
@@ -348,6 +520,8 @@ impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> Node<IO> {
                     proof: Some(proof_bytes.clone()),
                     public_inputs: public_input_bytes.clone(),
                     shares: None,
+                    manifest_hash: manifest_hash.clone(),
+                    scheme,
                 },
                 ProofOutputType::PrivateToPublic(pubkeys) => {
                     // TODO: 出力をシェアに分割して暗号化
@@ -360,6 +534,8 @@ impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> Node<IO> {
                         proof: Some(proof_bytes.clone()),
                         public_inputs: public_input_bytes.clone(),
                         shares: Some(shares),
+                        manifest_hash: manifest_hash.clone(),
+                        scheme,
                     }
                 }
                 ProofOutputType::PrivateToPrivate(pubkey) => {
@@ -371,6 +547,8 @@ impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> Node<IO> {
                         proof: Some(proof_bytes),
                         public_inputs: public_input_bytes,
                         shares: None,
+                        manifest_hash,
+                        scheme,
                     }
                 }
             };
@@ -404,6 +582,134 @@ impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> Node<IO> {
         Ok(())
     }
 
+    /// Returns whether the revealed proof verified, its calldata encoding and the manifest hash.
+    async fn prove_groth16(
+        &self,
+        request: &ProofRequest,
+        local_circuit: BuiltinCircuit<ark_bn254::Fr>,
+        mpc_circuit: BuiltinCircuit<MFr>,
+        inputs: &[ark_bn254::Fr],
+    ) -> Result<(bool, Vec<u8>, Option<String>), Box<dyn std::error::Error + Send>> {
+        let rng = &mut test_rng();
+        let loaded = self
+            .groth16_setups
+            .for_circuit(&request.circuit_type)
+            .await
+            .map_err(|e| -> Box<dyn std::error::Error + Send> { Box::new(e) })?;
+        let (setup, manifest_hash) = match loaded {
+            Some(setup) => (setup, self.groth16_setups.manifest_hash.clone()),
+            None if !allow_unpinned_manifest() => {
+                return Err(Box::new(std::io::Error::other(format!(
+                    "no Groth16 proving key is loaded for {:?} (set {}=true to generate throwaway keys in local development)",
+                    request.circuit_type.circuit_profile(),
+                    ALLOW_UNPINNED_MANIFEST_ENV
+                ))));
+            }
+            None => {
+                let params =
+                    generate_random_parameters::<ark_bn254::Bn254, _, _>(local_circuit, rng)
+                        .map_err(|e| -> Box<dyn std::error::Error + Send> {
+                            Box::new(std::io::Error::other(format!(
+                                "Failed to generate Groth16 parameters: {:?}",
+                                e
+                            )))
+                        })?;
+                // その場で作った鍵は manifest に載っていない
+                (Arc::new(Groth16Setup::new(params)), None)
+            }
+        };
+        let pvk = &setup.pvk;
+
+        // 鍵と instance mode が食い違うと MPC の証明生成が無駄になるので先に弾く
+        if pvk.vk.gamma_abc_g1.len() != inputs.len() + 1 {
+            return Err(Box::new(std::io::Error::other(format!(
+                "Groth16 key expects {} public inputs, got {} ({:?} instance)",
+                pvk.vk.gamma_abc_g1.len() - 1,
+                inputs.len(),
+                request.circuit_type.instance_mode()
+            ))));
+        }
+
+        let mpc_proof = create_random_proof::<
+            MpcPairingEngine<ark_bn254::Bn254, AdditivePairingShare<ark_bn254::Bn254>>,
+            _,
+            _,
+        >(mpc_circuit, &setup.mpc_proving_key, rng)
+        .map_err(|e| -> Box<dyn std::error::Error + Send> {
+            Box::new(std::io::Error::other(format!(
+                "Failed to generate collaborative Groth16 proof: {:?}",
+                e
+            )))
+        })?;
+        let publicized_proof = mpc_proof.reveal().await;
+        let is_valid = verify_proof(pvk, &publicized_proof, inputs).map_err(
+            |e| -> Box<dyn std::error::Error + Send> {
+                Box::new(std::io::Error::other(format!(
+                    "Failed to verify Groth16 proof: {:?}",
+                    e
+                )))
+            },
+        )?;
+
+        Ok((
+            is_valid,
+            abi_encode_groth16_proof(&publicized_proof),
+            manifest_hash,
+        ))
+    }
+
+    /// Marlin の証明は full instance でのみ作る。`instance` は x-domain に合わせて 0 埋めされる
+    async fn prove_marlin(
+        &self,
+        request: &ProofRequest,
+        mpc_circuit: BuiltinCircuit<MFr>,
+        instance: &[ark_bn254::Fr],
+    ) -> Result<(bool, Vec<u8>, Option<String>), Box<dyn std::error::Error + Send>> {
+        if request.circuit_type.instance_mode() != InstanceMode::Full {
+            return Err(Box::new(std::io::Error::other(
+                "Marlin proofs only support the full instance mode",
+            )));
+        }
+        let setup = self
+            .groth16_setups
+            .marlin_for_circuit(&request.circuit_type)
+            .ok_or_else(|| -> Box<dyn std::error::Error + Send> {
+                Box::new(std::io::Error::other(
+                    "no Marlin index is loaded for the requested circuit profile",
+                ))
+            })?;
+
+        let rng = &mut test_rng();
+        let mpc_index_pk = IndexProverKey::from_public((*setup.local_index_pk).clone());
+        let mpc_proof = MpcMarlin::prove(&mpc_index_pk, mpc_circuit, rng).map_err(
+            |e| -> Box<dyn std::error::Error + Send> {
+                Box::new(std::io::Error::other(format!(
+                    "Failed to generate collaborative Marlin proof: {:?}",
+                    e
+                )))
+            },
+        )?;
+        let publicized_proof = mpc_proof.reveal().await;
+        let is_valid = LocalMarlin::verify(
+            &setup.local_index_pk.index_vk,
+            instance,
+            &publicized_proof,
+            rng,
+        )
+        .map_err(|e| -> Box<dyn std::error::Error + Send> {
+            Box::new(std::io::Error::other(format!(
+                "Failed to verify Marlin proof: {:?}",
+                e
+            )))
+        })?;
+
+        Ok((
+            is_valid,
+            marlin_pc::abi_encode_proof(&marlin_pc::Proof::from(publicized_proof)),
+            None,
+        ))
+    }
+
     async fn split_and_encrypt_output(
         &self,
         output: &[u8],
@@ -428,7 +734,7 @@ impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> Node<IO> {
             }
             for (role_output, pubkey) in zip(role_outputs.iter(), pubkeys.iter()) {
                 let share_payload = RoleSharePayload {
-                    schema_version: "role_assignment_share_v2",
+                    schema_version: "role_assignment_share_v3",
                     role_share: &role_output.role_share,
                     role_share_encoding: "bn254_fr_decimal_string",
                     werewolf_mates_mask_share: role_output
@@ -436,6 +742,8 @@ impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> Node<IO> {
                         .as_deref()
                         .unwrap_or("0"),
                     werewolf_mates_mask_share_encoding: "player_index_bitmask_lsb0",
+                    role_flags_share: role_output.role_flags_share.as_deref().unwrap_or("0"),
+                    role_flags_share_encoding: "werewolf_species_bit0_team_bit1",
                 };
                 let share_bytes = serde_json::to_vec(&share_payload)
                     .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
@@ -463,11 +771,13 @@ impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> Node<IO> {
             }
             for (role_share, pubkey) in zip(role_shares.iter(), pubkeys.iter()) {
                 let share_payload = RoleSharePayload {
-                    schema_version: "role_assignment_share_v2",
+                    schema_version: "role_assignment_share_v3",
                     role_share,
                     role_share_encoding: "bn254_fr_decimal_string",
                     werewolf_mates_mask_share: "0",
                     werewolf_mates_mask_share_encoding: "player_index_bitmask_lsb0",
+                    role_flags_share: "0",
+                    role_flags_share_encoding: "werewolf_species_bit0_team_bit1",
                 };
                 let share_bytes = serde_json::to_vec(&share_payload)
                     .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send>)?;
@@ -535,7 +845,8 @@ impl<IO: AsyncRead + AsyncWrite + Unpin + Send + 'static> Node<IO> {
     }
 }
 
-fn groth16_data_dir() -> PathBuf {
+/// `GROTH16_DATA_DIR`, or the bundled `data/groth16`. The server reads its verifying keys from here too.
+pub fn groth16_data_dir() -> PathBuf {
     if let Ok(value) = std::env::var("GROTH16_DATA_DIR") {
         let path = PathBuf::from(value);
         if path.exists() {
@@ -556,10 +867,13 @@ fn groth16_data_dir() -> PathBuf {
     cargo_dir
 }
 
-fn load_profile_setups_from_data_dir(
-    setups: &mut HashMap<CircuitProfile, Groth16Setup>,
+/// Finds the profile proving keys (`.pk` or `.pk.gz`). With a manifest, every key is
+/// streamed once and its hashes checked here, so a bad key fails the boot instead of the
+/// first proof; the key itself is deserialized when the profile is first proven.
+fn scan_profile_keys_in_data_dir(
+    key_files: &mut HashMap<CircuitProfile, Groth16KeyFile>,
+    manifest: Option<&Groth16Manifest>,
 ) -> Result<(), std::io::Error> {
-    let started = Instant::now();
     let data_dir = groth16_data_dir();
     println!(
         "[node:init][groth16] scanning data dir for profile PKs: {}",
@@ -577,10 +891,14 @@ fn load_profile_setups_from_data_dir(
     for entry in std::fs::read_dir(&data_dir)? {
         let entry = entry?;
         let path = entry.path();
-        if path.extension().and_then(|s| s.to_str()) != Some("pk") {
-            continue;
+        let is_pk = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .and_then(pk_circuit_id)
+            .is_some();
+        if is_pk {
+            pk_paths.push(path);
         }
-        pk_paths.push(path);
     }
     pk_paths.sort();
     println!(
@@ -602,34 +920,156 @@ fn load_profile_setups_from_data_dir(
             );
             continue;
         };
+        // 同じ鍵の .pk と .pk.gz が両方あるときは .pk を使う (名前順で .pk が先に来る)
+        if key_files.contains_key(&profile) {
+            println!(
+                "[node:init][groth16] skipping duplicate {} PK: {}",
+                circuit_profile_label(profile),
+                path.display()
+            );
+            continue;
+        }
+
+        // manifest があるときは、載っていない・profile が違う鍵は読み込まない
+        let manifest_entry = match manifest {
+            Some(manifest) => {
+                let circuit_id = pk_circuit_id(file_name).unwrap_or(file_name);
+                let entry = manifest.entry(circuit_id).ok_or_else(|| {
+                    std::io::Error::other(ManifestError::UnknownCircuit(circuit_id.to_string()))
+                })?;
+                if entry.profile != profile {
+                    return Err(std::io::Error::other(ManifestError::Mismatch {
+                        circuit_id: circuit_id.to_string(),
+                        field: "profile",
+                        expected: circuit_profile_label(entry.profile),
+                        actual: circuit_profile_label(profile),
+                    }));
+                }
+                check_key_file(&path, entry)?;
+                Some(entry.clone())
+            }
+            None => None,
+        };
 
-        let label = format!("{} profile", circuit_profile_label(profile));
-        println!(
-            "[node:init][groth16] loading profile PK {} from {}",
-            label,
-            path.display()
-        );
-        let setup = Groth16Setup::from_pk_path(&path, &label)?;
-        setups.insert(profile, setup);
         println!(
-            "Loaded {} Groth16 proving key from {}.",
+            "[node:init][groth16] found {} profile PK at {}",
             circuit_profile_label(profile),
             path.display()
         );
+        key_files.insert(
+            profile,
+            Groth16KeyFile {
+                path,
+                manifest_entry,
+            },
+        );
     }
 
+    Ok(())
+}
+
+/// Streams the key file once and checks its `.pk` hash and verifying key hash against `entry`.
+fn check_key_file(path: &Path, entry: &Groth16ManifestEntry) -> Result<(), std::io::Error> {
+    let started = Instant::now();
+    let mut reader = ProvingKeyReader::open(path)?;
+    // ProvingKey は先頭に vk を持つので、vk を読んだ後は hash を取るだけでよい
+    let vk =
+        VerifyingKey::<ark_bn254::Bn254>::deserialize_uncompressed(&mut reader).map_err(|e| {
+            std::io::Error::other(format!(
+                "failed to deserialize verifying key {}: {:?}",
+                path.display(),
+                e
+            ))
+        })?;
+    let pk_hash = reader.finish()?;
+    check_proving_key_hash(entry, &pk_hash, &vk).map_err(std::io::Error::other)?;
     println!(
-        "[node:init][groth16] completed profile PK scan/load in {} ms (loaded_profiles={})",
-        started.elapsed().as_millis(),
-        setups.len()
+        "[node:init][groth16] checked {} against the manifest in {} ms",
+        path.display(),
+        started.elapsed().as_millis()
     );
+    Ok(())
+}
+
+/// `GROTH16_PK_CACHE_SIZE`。既定は [`DEFAULT_GROTH16_PK_CACHE_SIZE`]
+fn groth16_pk_cache_size_from_env() -> Result<usize, std::io::Error> {
+    match std::env::var("GROTH16_PK_CACHE_SIZE") {
+        Err(_) => Ok(DEFAULT_GROTH16_PK_CACHE_SIZE),
+        Ok(value) => match value.parse::<usize>() {
+            Ok(size) if size > 0 => Ok(size),
+            _ => Err(std::io::Error::other(format!(
+                "GROTH16_PK_CACHE_SIZE must be a positive integer, got {value}"
+            ))),
+        },
+    }
+}
+
+/// `ZK_PROOF_SCHEME=groth16|marlin`。既定は groth16
+fn proof_scheme_from_env() -> Result<ProofScheme, std::io::Error> {
+    match std::env::var("ZK_PROOF_SCHEME").as_deref() {
+        Err(_) | Ok("groth16") => Ok(ProofScheme::Groth16),
+        Ok("marlin") => Ok(ProofScheme::Marlin),
+        Ok(other) => Err(std::io::Error::other(format!(
+            "unknown ZK_PROOF_SCHEME {other}"
+        ))),
+    }
+}
+
+fn load_marlin_setups_from_data_dir(
+    setups: &mut HashMap<CircuitProfile, MarlinSetup>,
+) -> Result<(), std::io::Error> {
+    let data_dir = groth16_data_dir();
+    if !data_dir.exists() {
+        println!(
+            "[node:init][marlin] data dir does not exist. skipping Marlin index scan: {}",
+            data_dir.display()
+        );
+        return Ok(());
+    }
+
+    let mut ipk_paths = Vec::new();
+    for entry in std::fs::read_dir(&data_dir)? {
+        let path = entry?.path();
+        let is_index = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .is_some_and(|name| name.ends_with(MARLIN_INDEX_SUFFIX));
+        if is_index {
+            ipk_paths.push(path);
+        }
+    }
+    ipk_paths.sort();
+
+    for path in ipk_paths {
+        let file_name = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("<invalid-utf8>");
+        let circuit_id = file_name
+            .strip_suffix(MARLIN_INDEX_SUFFIX)
+            .unwrap_or(file_name);
+        let Some(profile) = parse_profile_from_circuit_id(circuit_id) else {
+            println!(
+                "[node:init][marlin] skipping non-profile index filename: {}",
+                path.display()
+            );
+            continue;
+        };
+
+        let label = format!("{} profile", circuit_profile_label(profile));
+        let setup = MarlinSetup::from_ipk_path(&path, &label)?;
+        setups.insert(profile, setup);
+    }
 
     Ok(())
 }
 
-fn parse_profile_from_pk_filename(file_name: &str) -> Option<CircuitProfile> {
-    let stem = file_name.strip_suffix(".pk")?;
+pub fn parse_profile_from_pk_filename(file_name: &str) -> Option<CircuitProfile> {
+    parse_profile_from_circuit_id(pk_circuit_id(file_name)?)
+}
 
+/// Profile of a circuit id such as `divination_n5_v1`.
+pub fn parse_profile_from_circuit_id(stem: &str) -> Option<CircuitProfile> {
     if let Some(rest) = stem.strip_prefix("role_assignment_n") {
         let (players, tail) = rest.split_once("_w")?;
         let (werewolves, _) = tail.split_once("_v")?;
@@ -652,6 +1092,18 @@ fn parse_profile_from_pk_filename(file_name: &str) -> Option<CircuitProfile> {
             parse_single_count_profile(stem, "key_publicize")
                 .map(|player_count| CircuitProfile::KeyPublicize { player_count })
         })
+        .or_else(|| {
+            parse_single_count_profile(stem, "attack")
+                .map(|player_count| CircuitProfile::Attack { player_count })
+        })
+        .or_else(|| {
+            parse_single_count_profile(stem, "night_resolution")
+                .map(|player_count| CircuitProfile::NightResolution { player_count })
+        })
+        .or_else(|| {
+            parse_single_count_profile(stem, "medium")
+                .map(|player_count| CircuitProfile::Medium { player_count })
+        })
 }
 
 fn parse_single_count_profile(stem: &str, prefix: &str) -> Option<usize> {
@@ -676,10 +1128,16 @@ fn circuit_profile_label(profile: CircuitProfile) -> String {
             format!("winning_judgement_n{}", player_count)
         }
         CircuitProfile::KeyPublicize { player_count } => format!("key_publicize_n{}", player_count),
+        CircuitProfile::Attack { player_count } => format!("attack_n{}", player_count),
+        CircuitProfile::NightResolution { player_count } => {
+            format!("night_resolution_n{}", player_count)
+        }
+        CircuitProfile::Medium { player_count } => format!("medium_n{}", player_count),
     }
 }
 
-fn abi_encode_groth16_proof(proof: &ark_groth16::Proof<ark_bn254::Bn254>) -> Vec<u8> {
+/// `(a, b, c)` as 8 uint256 words in the order `verifyTx` takes them.
+pub fn abi_encode_groth16_proof(proof: &ark_groth16::Proof<ark_bn254::Bn254>) -> Vec<u8> {
     let mut out = Vec::with_capacity(8 * 32);
     out.extend_from_slice(&field_to_word(proof.a.x));
     out.extend_from_slice(&field_to_word(proof.a.y));
@@ -692,24 +1150,67 @@ fn abi_encode_groth16_proof(proof: &ark_groth16::Proof<ark_bn254::Bn254>) -> Vec
     out
 }
 
+// 全回路の公開入力の末尾には生存者 bitmask, 経過日数, 直前と次の state digest が続く
+const STATE_CHAIN_PUBLIC_INPUT_LEN: usize = 4;
+
+// calldata 上の長さ。Hashed instance でも instance 全体を送る
 fn expected_public_input_len(circuit_type: &CircuitEncryptedInputIdentifier) -> usize {
-    match circuit_type {
-        CircuitEncryptedInputIdentifier::RoleAssignment(items) => {
-            let Some(first) = items.first() else {
-                return 0;
-            };
-            let n = first.public_input.grouping_parameter.get_num_players();
-            let m = first.public_input.grouping_parameter.get_num_groups();
-            let matrix_size = n + m;
-            matrix_size * matrix_size
+    STATE_CHAIN_PUBLIC_INPUT_LEN
+        + match circuit_type {
+            // 各プレイヤーの player_commitment (x, y) + role_commitment (x, y)
+            CircuitEncryptedInputIdentifier::RoleAssignment(items) => {
+                let Some(first) = items.first() else {
+                    return 0;
+                };
+                4 * first.public_input.num_players
+            }
+            // 生存者 bitmask + ElGamal パラメータ・公開鍵・暗号文
+            CircuitEncryptedInputIdentifier::Divination(_) => 9,
+            // 生存者 bitmask + 最多得票者 id + 各プレイヤーの player_commitment (x, y)
+            CircuitEncryptedInputIdentifier::AnonymousVoting(items) => {
+                let Some(first) = items.first() else {
+                    return 0;
+                };
+                2 + 2 * first.public_input.player_num
+            }
+            // 生存者数, 生存者 bitmask, 勝利条件 (majority, day_limit), 経過日数, game_state, 勝利陣営の bitmask
+            // + 各プレイヤーの役職コミットメント (x, y)
+            CircuitEncryptedInputIdentifier::WinningJudge(items) => {
+                let Some(first) = items.first() else {
+                    return 0;
+                };
+                7 + 2 * first.public_input.player_num
+            }
+            // 公開鍵 (x, y) + 各プレイヤーの役職コミットメント (x, y)
+            CircuitEncryptedInputIdentifier::KeyPublicize(items) => 2 + 2 * items.len(),
+            // alive_mask + 襲撃先 id + 全プレイヤーの役職コミットメント (x, y)
+            CircuitEncryptedInputIdentifier::Attack(items) => {
+                let Some(first) = items.first() else {
+                    return 0;
+                };
+                2 + 2 * first.public_input.player_num
+            }
+            // 死亡者 id + 生存者全員の役職コミットメント (x, y)
+            CircuitEncryptedInputIdentifier::NightResolution(items) => {
+                let Some(first) = items.first() else {
+                    return 0;
+                };
+                1 + 2 * first.public_input.player_num
+            }
+            // 処刑者 id + Divination と同じ ElGamal パラメータ・公開鍵・暗号文
+            // + 生存者全員の役職コミットメント (x, y)
+            CircuitEncryptedInputIdentifier::Medium(items) => {
+                let Some(first) = items.first() else {
+                    return 0;
+                };
+                9 + 2 * first.public_input.player_num
+            }
         }
-        CircuitEncryptedInputIdentifier::Divination(_) => 8,
-        CircuitEncryptedInputIdentifier::AnonymousVoting(_) => 1,
-        CircuitEncryptedInputIdentifier::WinningJudge(_) => 2,
-        CircuitEncryptedInputIdentifier::KeyPublicize(_) => 0,
-    }
 }
 
+/// Encodes the instance as the `uint256[N]` calldata of `verifyTx`. In `InstanceMode::Hashed`
+/// the proof has a single public input, but the calldata still carries the full instance
+/// because the Solidity verifier recomputes the hash from it.
 fn abi_encode_fixed_uint256_inputs<F: PrimeField>(
     inputs: &[F],
     expected_len: usize,
@@ -0,0 +1,146 @@
+use crate::models::PeerStatus;
+use crate::proof::unix_now;
+use tokio::sync::RwLock;
+
+/// Tracks the link state of every MPC peer of this node.
+///
+/// `MPCNetConnection` does not expose per-link health, so the state is recorded
+/// from the outcome of its operations (`connect_to_all` at boot, MPC sessions,
+/// reconnects of the `LinkSupervisor`). The MPC listeners of the peers are never
+/// contacted outside of the MPC handshake.
+pub struct PeerMonitor {
+    node_id: u32,
+    peers: RwLock<Vec<PeerStatus>>,
+}
+
+impl PeerMonitor {
+    pub fn new(node_id: u32, addresses: Vec<String>) -> Self {
+        let now = unix_now();
+        let peers = addresses
+            .into_iter()
+            .enumerate()
+            .filter(|(peer_id, _)| *peer_id as u32 != node_id)
+            .map(|(peer_id, address)| PeerStatus {
+                peer_id: peer_id as u32,
+                address,
+                connected: false,
+                last_error: None,
+                updated_at: now,
+            })
+            .collect();
+
+        Self {
+            node_id,
+            peers: RwLock::new(peers),
+        }
+    }
+
+    pub fn node_id(&self) -> u32 {
+        self.node_id
+    }
+
+    pub async fn mark_all_connected(&self) {
+        let now = unix_now();
+        for peer in self.peers.write().await.iter_mut() {
+            peer.connected = true;
+            peer.last_error = None;
+            peer.updated_at = now;
+        }
+    }
+
+    pub async fn mark_all_disconnected(&self, reason: &str) {
+        let now = unix_now();
+        for peer in self.peers.write().await.iter_mut() {
+            peer.connected = false;
+            peer.last_error = Some(reason.to_string());
+            peer.updated_at = now;
+        }
+    }
+
+    pub async fn all_connected(&self) -> bool {
+        self.peers.read().await.iter().all(|peer| peer.connected)
+    }
+
+    pub async fn snapshot(&self) -> Vec<PeerStatus> {
+        self.peers.read().await.clone()
+    }
+
+    /// Peer status as seen through the current MPC connection: while the mesh is
+    /// being rebuilt (`degraded_reason`) or not established, no peer is connected.
+    pub async fn link_status(
+        &self,
+        net_established: bool,
+        degraded_reason: Option<&str>,
+    ) -> Vec<PeerStatus> {
+        let mut peers = self.snapshot().await;
+        let reason = match degraded_reason {
+            Some(reason) => reason,
+            None if net_established => return peers,
+            None => "MPC connection is not established",
+        };
+        for peer in peers.iter_mut().filter(|peer| peer.connected) {
+            peer.connected = false;
+            peer.last_error = Some(reason.to_string());
+        }
+        peers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addresses() -> Vec<String> {
+        vec![
+            "localhost:8000".to_string(),
+            "localhost:8001".to_string(),
+            "localhost:8002".to_string(),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_excludes_own_address() {
+        let monitor = PeerMonitor::new(1, addresses());
+        let peers = monitor.snapshot().await;
+
+        assert_eq!(peers.len(), 2);
+        assert_eq!(peers[0].peer_id, 0);
+        assert_eq!(peers[1].peer_id, 2);
+    }
+
+    #[tokio::test]
+    async fn test_connection_state_transitions() {
+        let monitor = PeerMonitor::new(0, addresses());
+        assert!(!monitor.all_connected().await);
+
+        monitor.mark_all_connected().await;
+        assert!(monitor.all_connected().await);
+
+        monitor.mark_all_disconnected("link closed").await;
+        assert!(!monitor.all_connected().await);
+        assert_eq!(
+            monitor.snapshot().await[0].last_error.as_deref(),
+            Some("link closed")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_link_status_follows_mpc_connection() {
+        let monitor = PeerMonitor::new(0, addresses());
+        monitor.mark_all_connected().await;
+        assert!(monitor
+            .link_status(true, None)
+            .await
+            .iter()
+            .all(|peer| peer.connected));
+
+        let peers = monitor.link_status(false, None).await;
+        assert!(peers.iter().all(|peer| !peer.connected));
+
+        let peers = monitor.link_status(true, Some("peer 2 closed")).await;
+        assert!(peers.iter().all(|peer| !peer.connected));
+        assert_eq!(peers[1].last_error.as_deref(), Some("peer 2 closed"));
+        // 記録済みの状態は変えない
+        assert!(monitor.all_connected().await);
+    }
+}
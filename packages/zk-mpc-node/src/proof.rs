@@ -1,13 +1,18 @@
 use crate::models::ProofRequest;
 use crate::models::ProofStatus;
 use crate::ProofOutput;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+pub mod store;
+
+pub use store::*;
+
 // TODO: Changeable to a more generic proof manager
 pub struct ProofManager {
-    proofs: Arc<RwLock<HashMap<String, ProofStatus>>>,
+    proofs: Arc<RwLock<HashMap<String, StoredProof>>>,
+    store: Option<ProofStore>,
 }
 
 impl Default for ProofManager {
@@ -20,28 +25,128 @@ impl ProofManager {
     pub fn new() -> Self {
         ProofManager {
             proofs: Arc::new(RwLock::new(HashMap::new())),
+            store: None,
+        }
+    }
+
+    pub fn with_store(store: ProofStore) -> Self {
+        ProofManager {
+            proofs: Arc::new(RwLock::new(HashMap::new())),
+            store: Some(store),
+        }
+    }
+
+    /// Loads persisted proofs into memory. Proofs that were still in flight when the
+    /// node stopped are marked `failed`, since their MPC session cannot be resumed.
+    /// Returns the number of proofs that were marked failed.
+    pub async fn recover_from_store(&self) -> Result<usize, std::io::Error> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+
+        let now = unix_now();
+        let mut interrupted = 0;
+        let mut proofs = self.proofs.write().await;
+        for mut record in store.load_all().await? {
+            if record.is_expired(store.ttl(), now) {
+                store.remove(&record.status.proof_id).await?;
+                continue;
+            }
+            if record.status.state == "pending" {
+                record.status.state = "failed".to_string();
+                record.status.message =
+                    Some("Node restarted before proof generation completed".to_string());
+                record.updated_at = now;
+                store.save(&record).await?;
+                interrupted += 1;
+            }
+            proofs.insert(record.status.proof_id.clone(), record);
         }
+
+        println!(
+            "[node:proof-store] recovered {} proof(s) from {} ({} marked failed)",
+            proofs.len(),
+            store.dir().display(),
+            interrupted
+        );
+
+        Ok(interrupted)
     }
-    pub async fn register_proof_request(&self, request: ProofRequest) {
+
+    /// Registers a new pending proof. Returns `false` if the `proof_id` is already known,
+    /// including proofs recovered from the store.
+    pub async fn register_proof_request(&self, request: ProofRequest) -> bool {
         let status = ProofStatus {
             state: "pending".to_string(),
             proof_id: request.proof_id.clone(),
             message: None,
             output: None,
         };
+        let record = StoredProof::new(status);
+        {
+            let mut proofs = self.proofs.write().await;
+            if proofs.contains_key(&request.proof_id) {
+                return false;
+            }
+            proofs.insert(request.proof_id.clone(), record.clone());
+        }
+        self.persist(&record).await;
+        true
+    }
+
+    pub async fn get_proof_status(&self, proof_id: &str) -> Option<ProofStatus> {
         self.proofs
-            .write()
+            .read()
             .await
-            .insert(request.proof_id.clone(), status);
+            .get(proof_id)
+            .map(|record| record.status.clone())
     }
 
-    pub async fn get_proof_status(&self, proof_id: &str) -> Option<ProofStatus> {
-        self.proofs.read().await.get(proof_id).cloned()
+    /// IDs of proofs that were accepted but have not completed or failed yet.
+    pub async fn in_flight_proof_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .proofs
+            .read()
+            .await
+            .values()
+            .filter(|record| record.status.state == "pending")
+            .map(|record| record.status.proof_id.clone())
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    pub async fn get_proof_output(&self, proof_id: &str) -> Option<ProofOutput> {
+        if let Some(record) = self.proofs.read().await.get(proof_id) {
+            return record.status.output.clone();
+        }
+
+        // メモリに無い場合はディスク上の記録を参照する
+        let store = self.store.as_ref()?;
+        match store.load(proof_id).await {
+            Ok(record) => record.and_then(|record| record.status.output),
+            Err(e) => {
+                eprintln!(
+                    "[node:proof-store] failed to load proof output for {}: {}",
+                    proof_id, e
+                );
+                None
+            }
+        }
     }
+
     pub async fn update_proof_status(&self, proof_id: &str, state: &str, message: Option<String>) {
-        if let Some(status) = self.proofs.write().await.get_mut(proof_id) {
-            status.state = state.to_string();
-            status.message = message;
+        let updated = {
+            let mut proofs = self.proofs.write().await;
+            proofs.get_mut(proof_id).map(|record| {
+                record.status.state = state.to_string();
+                record.status.message = message;
+                record.updated_at = unix_now();
+                record.clone()
+            })
+        };
+        if let Some(record) = &updated {
+            self.persist(record).await;
         }
 
         println!(
@@ -58,10 +163,18 @@ impl ProofManager {
         message: Option<String>,
         output: Option<ProofOutput>,
     ) {
-        if let Some(status) = self.proofs.write().await.get_mut(proof_id) {
-            status.state = state.to_string();
-            status.message = message;
-            status.output = output;
+        let updated = {
+            let mut proofs = self.proofs.write().await;
+            proofs.get_mut(proof_id).map(|record| {
+                record.status.state = state.to_string();
+                record.status.message = message;
+                record.status.output = output;
+                record.updated_at = unix_now();
+                record.clone()
+            })
+        };
+        if let Some(record) = &updated {
+            self.persist(record).await;
         }
 
         println!(
@@ -70,4 +183,169 @@ impl ProofManager {
             self.get_proof_status(proof_id).await
         );
     }
+
+    /// Marks the proof `failed` unless it already completed or failed.
+    pub async fn fail_if_pending(&self, proof_id: &str, reason: &str) {
+        let is_pending = matches!(
+            self.get_proof_status(proof_id).await,
+            Some(status) if status.state == "pending"
+        );
+        if is_pending {
+            self.update_proof_status(proof_id, "failed", Some(reason.to_string()))
+                .await;
+        }
+    }
+
+    /// Drops proofs older than the store TTL from memory and disk.
+    /// Returns the number of removed proofs.
+    pub async fn collect_garbage(&self) -> Result<usize, std::io::Error> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+
+        let now = unix_now();
+        let mut removed = HashSet::new();
+        self.proofs.write().await.retain(|proof_id, record| {
+            let expired = record.is_expired(store.ttl(), now);
+            if expired {
+                removed.insert(proof_id.clone());
+            }
+            !expired
+        });
+
+        removed.extend(store.collect_garbage().await?);
+        Ok(removed.len())
+    }
+
+    async fn persist(&self, record: &StoredProof) {
+        let Some(store) = &self.store else {
+            return;
+        };
+        if let Err(e) = store.save(record).await {
+            eprintln!(
+                "[node:proof-store] failed to persist proof {}: {}",
+                record.status.proof_id, e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProofOutputType;
+    use mpc_algebra_wasm::{CircuitEncryptedInputIdentifier, ProofScheme};
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn temp_store_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("zk-mpc-node-proof-store-{}", uuid::Uuid::new_v4()))
+    }
+
+    fn request(proof_id: &str) -> ProofRequest {
+        ProofRequest {
+            proof_id: proof_id.to_string(),
+            circuit_type: CircuitEncryptedInputIdentifier::AnonymousVoting(vec![]),
+            output_type: ProofOutputType::Public,
+        }
+    }
+
+    fn public_output(value: &[u8]) -> ProofOutput {
+        ProofOutput {
+            output_type: ProofOutputType::Public,
+            value: Some(value.to_vec()),
+            proof: None,
+            public_inputs: None,
+            shares: None,
+            manifest_hash: None,
+            scheme: ProofScheme::Groth16,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_completed_output_survives_restart() {
+        let dir = temp_store_dir();
+        let manager =
+            ProofManager::with_store(ProofStore::new(&dir, Duration::from_secs(60)).unwrap());
+        manager.register_proof_request(request("proof-1")).await;
+        manager
+            .update_proof_status_with_output(
+                "proof-1",
+                "completed",
+                None,
+                Some(public_output(b"42")),
+            )
+            .await;
+
+        let restarted =
+            ProofManager::with_store(ProofStore::new(&dir, Duration::from_secs(60)).unwrap());
+        assert_eq!(restarted.recover_from_store().await.unwrap(), 0);
+
+        let status = restarted.get_proof_status("proof-1").await.unwrap();
+        assert_eq!(status.state, "completed");
+        let output = restarted.get_proof_output("proof-1").await.unwrap();
+        assert_eq!(output.value, Some(b"42".to_vec()));
+
+        // 再起動後も同じ proof_id は再登録できない
+        assert!(!restarted.register_proof_request(request("proof-1")).await);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_proof_is_marked_failed_after_restart() {
+        let dir = temp_store_dir();
+        let manager =
+            ProofManager::with_store(ProofStore::new(&dir, Duration::from_secs(60)).unwrap());
+        manager.register_proof_request(request("proof-2")).await;
+
+        let restarted =
+            ProofManager::with_store(ProofStore::new(&dir, Duration::from_secs(60)).unwrap());
+        assert_eq!(restarted.recover_from_store().await.unwrap(), 1);
+
+        let status = restarted.get_proof_status("proof-2").await.unwrap();
+        assert_eq!(status.state, "failed");
+        assert!(status.message.unwrap().contains("restarted"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_collect_garbage_removes_expired_proofs() {
+        let dir = temp_store_dir();
+        let store = ProofStore::new(&dir, Duration::from_secs(60)).unwrap();
+        let mut record = StoredProof::new(ProofStatus {
+            state: "completed".to_string(),
+            proof_id: "proof-3".to_string(),
+            message: None,
+            output: None,
+        });
+        record.updated_at -= 120;
+        store.save(&record).await.unwrap();
+
+        let manager =
+            ProofManager::with_store(ProofStore::new(&dir, Duration::from_secs(60)).unwrap());
+        manager.register_proof_request(request("proof-4")).await;
+        assert_eq!(manager.collect_garbage().await.unwrap(), 1);
+
+        assert!(manager.get_proof_output("proof-3").await.is_none());
+        assert!(manager.get_proof_status("proof-4").await.is_some());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unsafe_proof_id() {
+        let dir = temp_store_dir();
+        let store = ProofStore::new(&dir, Duration::from_secs(60)).unwrap();
+        let record = StoredProof::new(ProofStatus {
+            state: "pending".to_string(),
+            proof_id: "../escape".to_string(),
+            message: None,
+            output: None,
+        });
+        assert!(store.save(&record).await.is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
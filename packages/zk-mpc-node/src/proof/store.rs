@@ -0,0 +1,150 @@
+use crate::models::ProofStatus;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DEFAULT_PROOF_STORE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// A proof status snapshot as it is written to disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredProof {
+    pub status: ProofStatus,
+    pub updated_at: u64, // UNIX秒
+}
+
+impl StoredProof {
+    pub fn new(status: ProofStatus) -> Self {
+        Self {
+            status,
+            updated_at: unix_now(),
+        }
+    }
+
+    pub fn is_expired(&self, ttl: Duration, now: u64) -> bool {
+        now.saturating_sub(self.updated_at) > ttl.as_secs()
+    }
+}
+
+/// Disk-backed store that keeps one JSON file per proof so that completed
+/// proofs and their output shares survive a node restart.
+pub struct ProofStore {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl ProofStore {
+    pub fn new(dir: impl Into<PathBuf>, ttl: Duration) -> Result<Self, std::io::Error> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir, ttl })
+    }
+
+    /// Opens `{DATA_DIR}/proofs/node_{id}`. The TTL is read from `PROOF_STORE_TTL_SECS`.
+    pub fn from_env(node_id: u32) -> Result<Self, std::io::Error> {
+        let data_dir = std::env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string());
+        let ttl_secs = std::env::var("PROOF_STORE_TTL_SECS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_PROOF_STORE_TTL_SECS);
+        let dir = PathBuf::from(data_dir)
+            .join("proofs")
+            .join(format!("node_{}", node_id));
+        Self::new(dir, Duration::from_secs(ttl_secs))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub async fn save(&self, record: &StoredProof) -> Result<(), std::io::Error> {
+        let path = self.path_for(&record.status.proof_id)?;
+        let bytes = serde_json::to_vec(record).map_err(std::io::Error::other)?;
+
+        // 書き込み途中のファイルを読まないよう、一時ファイルに書いてから rename する
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, bytes).await?;
+        tokio::fs::rename(&tmp_path, &path).await
+    }
+
+    pub async fn load(&self, proof_id: &str) -> Result<Option<StoredProof>, std::io::Error> {
+        let path = self.path_for(proof_id)?;
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map(Some)
+                .map_err(std::io::Error::other),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn load_all(&self) -> Result<Vec<StoredProof>, std::io::Error> {
+        let mut records = Vec::new();
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            let bytes = tokio::fs::read(&path).await?;
+            match serde_json::from_slice::<StoredProof>(&bytes) {
+                Ok(record) => records.push(record),
+                Err(e) => {
+                    eprintln!(
+                        "[node:proof-store] skipping unreadable proof record {}: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            }
+        }
+        Ok(records)
+    }
+
+    pub async fn remove(&self, proof_id: &str) -> Result<(), std::io::Error> {
+        let path = self.path_for(proof_id)?;
+        match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Deletes every record whose last update is older than the TTL and
+    /// returns the removed proof IDs.
+    pub async fn collect_garbage(&self) -> Result<Vec<String>, std::io::Error> {
+        let now = unix_now();
+        let mut removed = Vec::new();
+        for record in self.load_all().await? {
+            if record.is_expired(self.ttl, now) {
+                self.remove(&record.status.proof_id).await?;
+                removed.push(record.status.proof_id);
+            }
+        }
+        Ok(removed)
+    }
+
+    fn path_for(&self, proof_id: &str) -> Result<PathBuf, std::io::Error> {
+        // proof_id はリクエスト由来のためファイル名として安全な文字のみ許可する
+        let is_safe = !proof_id.is_empty()
+            && proof_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !is_safe {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("proof_id {:?} cannot be used as a file name", proof_id),
+            ));
+        }
+        Ok(self.dir.join(format!("{}.json", proof_id)))
+    }
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
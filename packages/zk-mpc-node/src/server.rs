@@ -1,10 +1,14 @@
+use crate::auth::RequestVerifier;
 use crate::models::ProofRequest;
 use crate::node::Node;
+use crate::peers::PeerMonitor;
 use crate::proof::ProofManager;
+use crate::supervisor::LinkSupervisor;
 use crate::ProofStatus;
 use anyhow::Context;
+use axum::body::Bytes;
 use axum::extract::{Path, State};
-use axum::http::{self, HeaderValue, Method};
+use axum::http::{self, HeaderMap};
 use axum::response::IntoResponse;
 use axum::routing::{get, post};
 use axum::{Json, Router};
@@ -15,11 +19,13 @@ use std::any::Any;
 use std::net::SocketAddr;
 use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::net::TcpStream;
 use tokio::spawn;
-use tower_http::cors::CorsLayer;
+use tokio::time::timeout;
 
 pub mod api_client;
+pub mod health;
 
 pub use api_client::*;
 
@@ -27,21 +33,22 @@ pub use api_client::*;
 pub struct AppState {
     pub proof_manager: Arc<ProofManager>,
     pub node: Arc<Node<TcpStream>>,
+    pub peer_monitor: Arc<PeerMonitor>,
+    pub supervisor: Arc<LinkSupervisor>,
+    /// `None` only when unsigned requests are explicitly allowed.
+    pub request_verifier: Option<Arc<RequestVerifier>>,
 }
 
 pub async fn run_server(addr: &SocketAddr, state: AppState) -> Result<(), anyhow::Error> {
-    let origins = ["http://localhost:3000".parse::<HeaderValue>().unwrap()];
-    let cors = CorsLayer::new()
-        .allow_origin(origins)
-        .allow_methods([Method::GET, Method::POST])
-        .allow_headers([http::header::CONTENT_TYPE, http::header::AUTHORIZATION]);
-
+    // ノードはサーバーからのみ呼び出されるため CORS は許可しない
     // build our application with a single route
     let app = Router::new()
         .route("/", post(handle_proof_request))
         .route("/proof/:proof_id", get(get_proof_status))
         .route("/proof/:proof_id/output", get(get_proof_output))
-        .layer(cors)
+        .route("/health", get(health::health))
+        .route("/ready", get(health::ready))
+        .route("/status", get(health::status))
         .with_state(state);
 
     let listener = tokio::net::TcpListener::bind(addr)
@@ -54,15 +61,93 @@ pub async fn run_server(addr: &SocketAddr, state: AppState) -> Result<(), anyhow
     Ok(())
 }
 
+fn reject(
+    code: http::StatusCode,
+    status: &str,
+    message: String,
+) -> (http::StatusCode, Json<serde_json::Value>) {
+    (
+        code,
+        Json(json!({
+            "status": status,
+            "message": message
+        })),
+    )
+}
+
 async fn handle_proof_request(
     State(state): State<AppState>,
-    Json(payload): Json<ProofRequest>,
+    headers: HeaderMap,
+    body: Bytes,
 ) -> impl IntoResponse {
-    // Handle the proof request
-    state
+    if let Some(verifier) = &state.request_verifier {
+        if let Err(e) = verifier.verify(&headers, &body).await {
+            eprintln!("Rejecting unauthenticated proof request: {}", e);
+            return reject(
+                http::StatusCode::UNAUTHORIZED,
+                "unauthorized",
+                e.to_string(),
+            );
+        }
+    }
+
+    let payload: ProofRequest = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(e) => {
+            return reject(
+                http::StatusCode::BAD_REQUEST,
+                "invalid_request",
+                format!("Invalid proof request: {}", e),
+            );
+        }
+    };
+
+    // 再接続中は新しいリクエストを受け付けずに即座に返す
+    let net = match state.supervisor.degraded_reason().await {
+        Some(reason) => Err(reason),
+        None => state
+            .node
+            .net()
+            .await
+            .ok_or_else(|| "MPC connection is not established".to_string()),
+    };
+    let net = match net {
+        Ok(net) => net,
+        Err(reason) => {
+            eprintln!(
+                "Rejecting proof request {}: cluster degraded ({})",
+                payload.proof_id, reason
+            );
+            return (
+                http::StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({
+                    "status": "cluster_degraded",
+                    "message": format!("cluster degraded: {}", reason),
+                    "proof_id": payload.proof_id
+                })),
+            );
+        }
+    };
+
+    // Handle the proof request. A proof_id is accepted only once.
+    if !state
         .proof_manager
         .register_proof_request(payload.clone())
-        .await;
+        .await
+    {
+        eprintln!(
+            "Rejecting proof request {}: proof_id already seen",
+            payload.proof_id
+        );
+        return (
+            http::StatusCode::CONFLICT,
+            Json(json!({
+                "status": "duplicate_proof_id",
+                "message": format!("Proof {} has already been requested", payload.proof_id),
+                "proof_id": payload.proof_id
+            })),
+        );
+    }
 
     println!(
         "Proof request registered: {} (profile: {:?})",
@@ -71,67 +156,93 @@ async fn handle_proof_request(
     );
 
     let payload_clone = payload.clone();
+    let proof_id = payload.proof_id.clone();
+    let session_state = state.clone();
 
     // Simulate the network request to generate the proof
-    spawn(async move {
-        Net::simulate(
-            state.node.net.clone(),
-            payload_clone.clone(),
-            move |_, request| {
-                let node_clone = state.node.clone();
-                let proof_manager = state.proof_manager.clone();
-                async move {
-                    println!(
-                        "Node {} is generating proof for request: {} (profile: {:?})",
-                        node_clone.id,
-                        request.proof_id,
-                        request.circuit_type.circuit_profile()
-                    );
-
-                    let proof_result =
-                        AssertUnwindSafe(node_clone.generate_proof(request.clone()))
-                            .catch_unwind()
-                            .await;
+    let session = spawn(timeout(
+        mpc_session_timeout(),
+        Net::simulate(net, payload_clone.clone(), move |_, request| {
+            let node_clone = state.node.clone();
+            let proof_manager = state.proof_manager.clone();
+            let supervisor = state.supervisor.clone();
+            async move {
+                println!(
+                    "Node {} is generating proof for request: {} (profile: {:?})",
+                    node_clone.id,
+                    request.proof_id,
+                    request.circuit_type.circuit_profile()
+                );
 
-                    match proof_result {
-                        Ok(Ok(_)) => {
-                            println!(
-                                "Proof generation completed successfully for {}",
-                                request.proof_id
-                            );
-                        }
-                        Ok(Err(e)) => {
-                            eprintln!(
-                                "Error during proof generation for {}: {:?}",
-                                request.proof_id, e
-                            );
-                            proof_manager
-                                .update_proof_status(
-                                    &request.proof_id,
-                                    "failed",
-                                    Some(format!("Error: {:?}", e)),
-                                )
-                                .await;
-                        }
-                        Err(panic_payload) => {
-                            let panic_message = panic_payload_to_string(panic_payload);
-                            eprintln!(
-                                "Panic during proof generation for {}: {}",
-                                request.proof_id, panic_message
-                            );
-                            proof_manager
-                                .update_proof_status(
-                                    &request.proof_id,
-                                    "failed",
-                                    Some(format!("Panic: {}", panic_message)),
-                                )
-                                .await;
+                let proof_result = AssertUnwindSafe(node_clone.generate_proof(request.clone()))
+                    .catch_unwind()
+                    .await;
+
+                match proof_result {
+                    Ok(Ok(_)) => {
+                        println!(
+                            "Proof generation completed successfully for {}",
+                            request.proof_id
+                        );
+                    }
+                    Ok(Err(e)) => {
+                        eprintln!(
+                            "Error during proof generation for {}: {}",
+                            request.proof_id, e
+                        );
+                        proof_manager
+                            .update_proof_status(
+                                &request.proof_id,
+                                "failed",
+                                Some(format!("Error: {}", e)),
+                            )
+                            .await;
+                    }
+                    Err(panic_payload) => {
+                        let panic_message = panic_payload_to_string(panic_payload);
+                        eprintln!(
+                            "Panic during proof generation for {}: {}",
+                            request.proof_id, panic_message
+                        );
+                        proof_manager
+                            .update_proof_status(
+                                &request.proof_id,
+                                "failed",
+                                Some(format!("Panic: {}", panic_message)),
+                            )
+                            .await;
+                        if is_link_failure(&panic_message) {
+                            supervisor.report_link_failure(&panic_message).await;
                         }
                     }
                 }
-            },
-        )
-        .await
+            }
+        }),
+    ));
+
+    // MPCセッション自体のパニック・タイムアウトはリンク断として扱う
+    spawn(async move {
+        let failure = match session.await {
+            Ok(Ok(_)) => None,
+            Ok(Err(_)) => Some(format!(
+                "MPC session timed out after {:?}",
+                mpc_session_timeout()
+            )),
+            Err(join_error) if join_error.is_panic() => Some(format!(
+                "MPC session panicked: {}",
+                panic_payload_to_string(join_error.into_panic())
+            )),
+            Err(join_error) => Some(format!("MPC session aborted: {}", join_error)),
+        };
+
+        if let Some(reason) = failure {
+            eprintln!("MPC session for {} failed: {}", proof_id, reason);
+            session_state
+                .proof_manager
+                .fail_if_pending(&proof_id, &reason)
+                .await;
+            session_state.supervisor.report_link_failure(&reason).await;
+        }
     });
 
     (
@@ -145,31 +256,27 @@ async fn handle_proof_request(
 }
 
 async fn get_proof_output(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Path(proof_id): Path<String>,
 ) -> impl IntoResponse {
-    // if let Some(output) = state.proof_manager.get_proof_output(&proof_id).await {
-    //     (
-    //         http::StatusCode::OK,
-    //         axum::Json(format!("{}", serde_json::to_string(&output).unwrap())),
-    //     )
-    // } else {
-    //     (
-    //         http::StatusCode::NOT_FOUND,
-    //         // axum::Json(json!({"error": "Proof output not found"})),
-    //         axum::Json(format!(
-    //             "{{\"error\": \"Proof output for {} not found\"}}",
-    //             proof_id
-    //         )),
-    //     )
-    // }
+    if let Some(output) = state.proof_manager.get_proof_output(&proof_id).await {
+        return (http::StatusCode::OK, axum::Json(output)).into_response();
+    }
+
+    let proof_state = state
+        .proof_manager
+        .get_proof_status(&proof_id)
+        .await
+        .map(|status| status.state);
     (
-        http::StatusCode::NOT_IMPLEMENTED,
+        http::StatusCode::NOT_FOUND,
         axum::Json(json!({
-            "error": "get_proof_output is not yet implemented",
-            "proof_id": proof_id
+            "error": format!("Proof output for {} not found", proof_id),
+            "proof_id": proof_id,
+            "state": proof_state,
         })),
     )
+        .into_response()
 }
 
 async fn get_proof_status(
@@ -198,3 +305,41 @@ fn panic_payload_to_string(payload: Box<dyn Any + Send>) -> String {
     }
     "unknown panic payload".to_string()
 }
+
+fn mpc_session_timeout() -> Duration {
+    let secs = std::env::var("MPC_SESSION_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(900);
+    Duration::from_secs(secs)
+}
+
+/// Heuristic for panics raised by the MPC channel rather than by the circuit itself.
+fn is_link_failure(panic_message: &str) -> bool {
+    let message = panic_message.to_ascii_lowercase();
+    [
+        "broken pipe",
+        "connection reset",
+        "connection refused",
+        "connection aborted",
+        "unexpected eof",
+        "early eof",
+        "not connected",
+    ]
+    .iter()
+    .any(|pattern| message.contains(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_link_failure() {
+        assert!(is_link_failure("called `Result::unwrap()` on an `Err` value: Os { code: 32, kind: BrokenPipe, message: \"Broken pipe\" }"));
+        assert!(is_link_failure(
+            "failed to fill whole buffer: unexpected EOF"
+        ));
+        assert!(!is_link_failure("assertion failed: inputs.len() == 3"));
+    }
+}
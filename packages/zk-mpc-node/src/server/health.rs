@@ -0,0 +1,93 @@
+use crate::models::{NodeReadiness, NodeStatus, PeerStatus};
+use crate::server::AppState;
+use axum::extract::State;
+use axum::http;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde_json::json;
+
+pub fn build_version() -> String {
+    match option_env!("GIT_COMMIT_SHA") {
+        Some(sha) => format!("{}+{}", env!("CARGO_PKG_VERSION"), sha),
+        None => env!("CARGO_PKG_VERSION").to_string(),
+    }
+}
+
+/// Liveness: the HTTP server is up.
+pub async fn health(State(state): State<AppState>) -> impl IntoResponse {
+    Json(json!({
+        "status": "ok",
+        "node_id": state.node.id,
+        "version": build_version(),
+        "boot_id": state.supervisor.boot_id(),
+        "link": state.supervisor.state().await,
+    }))
+}
+
+/// Readiness: the node can accept proof requests.
+pub async fn ready(State(state): State<AppState>) -> impl IntoResponse {
+    let readiness = readiness(&state).await;
+    let code = if readiness.ready {
+        http::StatusCode::OK
+    } else {
+        http::StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(readiness))
+}
+
+pub async fn status(State(state): State<AppState>) -> impl IntoResponse {
+    let public_key_fingerprint = state
+        .node
+        .key_manager
+        .get_public_key_fingerprint()
+        .await
+        .ok();
+    let readiness = readiness(&state).await;
+    let peers = peer_status(&state).await;
+
+    Json(NodeStatus {
+        node_id: state.node.id,
+        version: build_version(),
+        ready: readiness.ready,
+        degraded_reason: state.supervisor.degraded_reason().await,
+        key_initialized: public_key_fingerprint.is_some(),
+        public_key_fingerprint,
+        peers,
+        loaded_profiles: state.node.loaded_profiles(),
+        in_flight_proofs: state.proof_manager.in_flight_proof_ids().await,
+    })
+}
+
+async fn readiness(state: &AppState) -> NodeReadiness {
+    let mut reasons = Vec::new();
+    if state.node.key_manager.get_public_key().await.is_err() {
+        reasons.push("node key is not initialized".to_string());
+    }
+    if let Some(reason) = state.supervisor.degraded_reason().await {
+        reasons.push(format!("cluster degraded: {}", reason));
+    }
+    for peer in peer_status(state).await {
+        if !peer.connected {
+            reasons.push(format!(
+                "peer {} ({}) is not connected",
+                peer.peer_id, peer.address
+            ));
+        }
+    }
+
+    NodeReadiness {
+        node_id: state.node.id,
+        ready: reasons.is_empty(),
+        reasons,
+    }
+}
+
+// ピアの状態は MPC 接続と supervisor の状態から求め、ピアのMPCリスナーには接続しない
+async fn peer_status(state: &AppState) -> Vec<PeerStatus> {
+    let net_established = state.node.net().await.is_some();
+    let degraded_reason = state.supervisor.degraded_reason().await;
+    state
+        .peer_monitor
+        .link_status(net_established, degraded_reason.as_deref())
+        .await
+}
@@ -0,0 +1,477 @@
+use crate::node::Node;
+use crate::peers::PeerMonitor;
+use mpc_net::multi::MPCNetConnection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::{Notify, RwLock};
+use tokio::time::{sleep, timeout};
+
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Upper bound for a single `listen` + `connect_to_all` attempt.
+    pub attempt_timeout: Duration,
+    /// Interval of the link checks against the peers' `/health`. Zero disables them.
+    pub check_interval: Duration,
+}
+
+/// Consecutive failed link checks before a peer is treated as gone.
+const LINK_CHECK_MISS_LIMIT: u32 = 2;
+const LINK_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(1000),
+            max_backoff: Duration::from_millis(30000),
+            attempt_timeout: Duration::from_millis(30000),
+            check_interval: Duration::from_millis(5000),
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let millis = |name: &str, fallback: Duration| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(fallback)
+        };
+
+        Self {
+            initial_backoff: millis("MPC_RECONNECT_INITIAL_BACKOFF_MS", default.initial_backoff),
+            max_backoff: millis("MPC_RECONNECT_MAX_BACKOFF_MS", default.max_backoff),
+            attempt_timeout: millis("MPC_RECONNECT_ATTEMPT_TIMEOUT_MS", default.attempt_timeout),
+            check_interval: millis("MPC_LINK_CHECK_INTERVAL_MS", default.check_interval),
+        }
+    }
+
+    /// Backoff before the given (1-based) attempt, doubling up to `max_backoff`.
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        let factor = 1u32 << attempt.saturating_sub(1).min(16);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ClusterState {
+    Healthy,
+    Degraded { reason: String, attempt: u32 },
+}
+
+/// HTTP endpoint of a peer node, used for link checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerEndpoint {
+    pub peer_id: u32,
+    pub http_url: String,
+}
+
+/// `http_override(i)` (`ZK_MPC_NODE_{i}_HTTP`) if set, otherwise the host of the peer's
+/// MPC address with the HTTP port `http_port_base + i`.
+pub fn peer_http_endpoints(
+    node_id: u32,
+    addresses: &[String],
+    http_port_base: u16,
+    http_override: impl Fn(u32) -> Option<String>,
+) -> Vec<PeerEndpoint> {
+    addresses
+        .iter()
+        .enumerate()
+        .filter(|(peer_id, _)| *peer_id as u32 != node_id)
+        .map(|(peer_id, address)| {
+            let http_url = http_override(peer_id as u32).unwrap_or_else(|| {
+                let host = address
+                    .rsplit_once(':')
+                    .map_or(address.as_str(), |(h, _)| h);
+                format!("http://{}:{}", host, http_port_base + peer_id as u16)
+            });
+            PeerEndpoint {
+                peer_id: peer_id as u32,
+                http_url: http_url.trim_end_matches('/').to_string(),
+            }
+        })
+        .collect()
+}
+
+/// The part of a peer's `/health` response used for link checks.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinkHealth {
+    pub boot_id: String,
+    pub link: ClusterState,
+}
+
+/// Decides from consecutive link checks whether the MPC links to a peer are stale.
+#[derive(Debug, Default)]
+struct LinkWatch {
+    boot_ids: HashMap<u32, String>,
+    misses: HashMap<u32, u32>,
+}
+
+impl LinkWatch {
+    /// Returns the reason to rebuild the mesh, if the check calls for it.
+    fn observe(&mut self, peer_id: u32, health: Result<LinkHealth, String>) -> Option<String> {
+        let problem = match health {
+            Ok(health) => {
+                // 再起動したピアとのリンクは必ず切れている
+                if let Some(known) = self.boot_ids.insert(peer_id, health.boot_id.clone()) {
+                    if known != health.boot_id {
+                        self.misses.remove(&peer_id);
+                        return Some(format!("peer {} restarted", peer_id));
+                    }
+                }
+                match health.link {
+                    ClusterState::Healthy => None,
+                    ClusterState::Degraded { reason, .. } => Some(format!(
+                        "peer {} is rebuilding its MPC links ({})",
+                        peer_id, reason
+                    )),
+                }
+            }
+            Err(e) => Some(format!(
+                "peer {} does not answer link checks: {}",
+                peer_id, e
+            )),
+        };
+
+        let Some(reason) = problem else {
+            self.misses.remove(&peer_id);
+            return None;
+        };
+        let misses = self.misses.entry(peer_id).or_insert(0);
+        *misses += 1;
+        (*misses >= LINK_CHECK_MISS_LIMIT).then_some(reason)
+    }
+
+    fn reset(&mut self) {
+        self.boot_ids.clear();
+        self.misses.clear();
+    }
+}
+
+/// Supervises the MPC links of this node. When a session reports a broken link, or the
+/// periodic link check finds a peer that is gone, restarted or rebuilding its links, the
+/// node is marked degraded and the whole `MPCNetConnection` mesh is rebuilt with backoff.
+///
+/// Peers see the degraded state in this node's `/health` and rebuild as well, so every
+/// node re-listens and the mesh is re-established in full.
+pub struct LinkSupervisor {
+    node_id: u32,
+    boot_id: String,
+    addresses: Vec<String>,
+    peer_monitor: Arc<PeerMonitor>,
+    policy: ReconnectPolicy,
+    state: RwLock<ClusterState>,
+    wake: Notify,
+}
+
+impl LinkSupervisor {
+    pub fn new(
+        node_id: u32,
+        addresses: Vec<String>,
+        peer_monitor: Arc<PeerMonitor>,
+        policy: ReconnectPolicy,
+    ) -> Self {
+        Self {
+            node_id,
+            boot_id: uuid::Uuid::new_v4().to_string(),
+            addresses,
+            peer_monitor,
+            policy,
+            state: RwLock::new(ClusterState::Healthy),
+            wake: Notify::new(),
+        }
+    }
+
+    /// Random id of this process, so that peers notice a restart.
+    pub fn boot_id(&self) -> &str {
+        &self.boot_id
+    }
+
+    pub async fn state(&self) -> ClusterState {
+        self.state.read().await.clone()
+    }
+
+    /// Returns the reason while the cluster is degraded.
+    pub async fn degraded_reason(&self) -> Option<String> {
+        match &*self.state.read().await {
+            ClusterState::Healthy => None,
+            ClusterState::Degraded { reason, .. } => Some(reason.clone()),
+        }
+    }
+
+    /// Marks the cluster degraded and wakes the reconnect loop.
+    pub async fn report_link_failure(&self, reason: &str) {
+        {
+            let mut state = self.state.write().await;
+            if matches!(*state, ClusterState::Degraded { .. }) {
+                return;
+            }
+            eprintln!(
+                "[node:supervisor] node {} detected a broken MPC link: {}",
+                self.node_id, reason
+            );
+            *state = ClusterState::Degraded {
+                reason: reason.to_string(),
+                attempt: 0,
+            };
+        }
+        self.peer_monitor.mark_all_disconnected(reason).await;
+        self.wake.notify_one();
+    }
+
+    /// Reconnect loop. Runs for the lifetime of the node.
+    pub async fn run(self: Arc<Self>, node: Arc<Node<TcpStream>>) {
+        loop {
+            self.wake.notified().await;
+            if self.degraded_reason().await.is_none() {
+                continue;
+            }
+
+            // 古い接続を破棄してリスナーのポートを解放する
+            drop(node.replace_net(None).await);
+
+            let mut attempt = 0u32;
+            loop {
+                attempt += 1;
+                let backoff = self.policy.backoff_for(attempt);
+                if let ClusterState::Degraded {
+                    attempt: current, ..
+                } = &mut *self.state.write().await
+                {
+                    *current = attempt;
+                }
+                println!(
+                    "[node:supervisor] reconnecting node {} to MPC peers (attempt {}) in {:?}...",
+                    self.node_id, attempt, backoff
+                );
+                sleep(backoff).await;
+
+                match self.reconnect_once().await {
+                    Ok(net) => {
+                        node.replace_net(Some(net)).await;
+                        self.peer_monitor.mark_all_connected().await;
+                        *self.state.write().await = ClusterState::Healthy;
+                        println!(
+                            "[node:supervisor] node {} reconnected to all MPC peers after {} attempt(s)",
+                            self.node_id, attempt
+                        );
+                        break;
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "[node:supervisor] reconnect attempt {} failed for node {}: {}",
+                            attempt, self.node_id, e
+                        );
+                        self.peer_monitor.mark_all_disconnected(&e).await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Link check loop. Polls the peers' `/health` while the cluster is healthy and
+    /// reports a link failure when a peer is gone, restarted or rebuilding its links.
+    pub async fn check_links(self: Arc<Self>, peers: Vec<PeerEndpoint>) {
+        if self.policy.check_interval.is_zero() {
+            return;
+        }
+        let client = match reqwest::Client::builder()
+            .timeout(LINK_CHECK_TIMEOUT)
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                eprintln!(
+                    "[node:supervisor] link checks disabled, failed to create HTTP client: {}",
+                    e
+                );
+                return;
+            }
+        };
+
+        let mut watch = LinkWatch::default();
+        let mut ticker = tokio::time::interval(self.policy.check_interval);
+        loop {
+            ticker.tick().await;
+            // 再接続中は run() に任せ、再接続後にピアの状態を改めて確認する
+            if self.degraded_reason().await.is_some() {
+                watch.reset();
+                continue;
+            }
+            for peer in &peers {
+                let health = fetch_link_health(&client, &peer.http_url).await;
+                if let Some(reason) = watch.observe(peer.peer_id, health) {
+                    self.report_link_failure(&reason).await;
+                    break;
+                }
+            }
+        }
+    }
+
+    async fn reconnect_once(&self) -> Result<MPCNetConnection<TcpStream>, String> {
+        let mut net = MPCNetConnection::new(self.node_id, self.addresses.clone())
+            .map_err(|e| format!("failed to create MPC connection: {:?}", e))?;
+        net.listen()
+            .await
+            .map_err(|e| format!("failed to listen for MPC peers: {:?}", e))?;
+
+        match timeout(self.policy.attempt_timeout, net.connect_to_all()).await {
+            Ok(Ok(_)) => Ok(net),
+            Ok(Err(e)) => Err(format!("failed to connect to all peers: {:?}", e)),
+            Err(_) => Err(format!(
+                "timed out after {:?} while connecting to all peers",
+                self.policy.attempt_timeout
+            )),
+        }
+    }
+}
+
+async fn fetch_link_health(client: &reqwest::Client, url: &str) -> Result<LinkHealth, String> {
+    let response = client
+        .get(format!("{}/health", url))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("/health returned {}", response.status()));
+    }
+    response.json().await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn supervisor() -> LinkSupervisor {
+        let addresses = vec![
+            "localhost:8000".to_string(),
+            "localhost:8001".to_string(),
+            "localhost:8002".to_string(),
+        ];
+        let peer_monitor = Arc::new(PeerMonitor::new(0, addresses.clone()));
+        LinkSupervisor::new(0, addresses, peer_monitor, ReconnectPolicy::default())
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = ReconnectPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1000),
+            attempt_timeout: Duration::from_millis(1000),
+            check_interval: Duration::from_millis(1000),
+        };
+
+        assert_eq!(policy.backoff_for(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff_for(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff_for(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff_for(5), Duration::from_millis(1000));
+        assert_eq!(policy.backoff_for(100), Duration::from_millis(1000));
+    }
+
+    #[tokio::test]
+    async fn test_report_link_failure_marks_cluster_degraded() {
+        let supervisor = supervisor();
+        supervisor.peer_monitor.mark_all_connected().await;
+        assert_eq!(supervisor.state().await, ClusterState::Healthy);
+
+        supervisor.report_link_failure("peer 2 closed").await;
+        assert_eq!(
+            supervisor.degraded_reason().await.as_deref(),
+            Some("peer 2 closed")
+        );
+        assert!(!supervisor.peer_monitor.all_connected().await);
+
+        // 既に degraded の場合は最初の理由を保持する
+        supervisor.report_link_failure("peer 1 closed").await;
+        assert_eq!(
+            supervisor.degraded_reason().await.as_deref(),
+            Some("peer 2 closed")
+        );
+    }
+
+    fn health(boot_id: &str, link: ClusterState) -> Result<LinkHealth, String> {
+        Ok(LinkHealth {
+            boot_id: boot_id.to_string(),
+            link,
+        })
+    }
+
+    #[test]
+    fn test_link_watch_reports_gone_and_rebuilding_peers() {
+        let mut watch = LinkWatch::default();
+        assert_eq!(watch.observe(1, health("a", ClusterState::Healthy)), None);
+
+        // 一時的な失敗では再接続しない
+        assert_eq!(watch.observe(1, Err("timeout".to_string())), None);
+        assert_eq!(watch.observe(1, health("a", ClusterState::Healthy)), None);
+        assert_eq!(watch.observe(1, Err("timeout".to_string())), None);
+        assert_eq!(
+            watch.observe(1, Err("timeout".to_string())),
+            Some("peer 1 does not answer link checks: timeout".to_string())
+        );
+
+        let degraded = || ClusterState::Degraded {
+            reason: "peer 2 closed".to_string(),
+            attempt: 1,
+        };
+        let mut watch = LinkWatch::default();
+        assert_eq!(watch.observe(2, health("b", degraded())), None);
+        assert_eq!(
+            watch.observe(2, health("b", degraded())),
+            Some("peer 2 is rebuilding its MPC links (peer 2 closed)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_link_watch_reports_restarted_peer_immediately() {
+        let mut watch = LinkWatch::default();
+        assert_eq!(watch.observe(1, health("a", ClusterState::Healthy)), None);
+        assert_eq!(
+            watch.observe(1, health("b", ClusterState::Healthy)),
+            Some("peer 1 restarted".to_string())
+        );
+
+        // 再接続後は新しい boot_id を覚え直す
+        watch.reset();
+        assert_eq!(watch.observe(1, health("b", ClusterState::Healthy)), None);
+    }
+
+    #[test]
+    fn test_peer_http_endpoints_follow_mpc_hosts() {
+        let addresses = vec![
+            "zk-mpc-node-0:8000".to_string(),
+            "zk-mpc-node-1:8001".to_string(),
+            "zk-mpc-node-2:8002".to_string(),
+        ];
+        let endpoints = peer_http_endpoints(1, &addresses, 9000, |_| None);
+        assert_eq!(
+            endpoints,
+            vec![
+                PeerEndpoint {
+                    peer_id: 0,
+                    http_url: "http://zk-mpc-node-0:9000".to_string(),
+                },
+                PeerEndpoint {
+                    peer_id: 2,
+                    http_url: "http://zk-mpc-node-2:9002".to_string(),
+                },
+            ]
+        );
+
+        let endpoints = peer_http_endpoints(0, &addresses, 9000, |peer_id| {
+            (peer_id == 2).then(|| "https://node-2.example/".to_string())
+        });
+        assert_eq!(endpoints[0].http_url, "http://zk-mpc-node-1:9001");
+        assert_eq!(endpoints[1].http_url, "https://node-2.example");
+    }
+}
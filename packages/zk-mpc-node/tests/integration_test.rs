@@ -19,7 +19,7 @@ use std::time::Duration;
 use zk_mpc::circuits::LocalOrMPC;
 use zk_mpc_node::NodeKeys;
 use zk_mpc_node::ProofStatus;
-use zk_mpc_node::{ProofOutputType, ProofRequest};
+use zk_mpc_node::{ProofOutputType, ProofRequest, RequestSigner};
 
 use mpc_algebra_wasm::*;
 
@@ -95,6 +95,10 @@ fn setup_voting() -> (CircuitEncryptedInputIdentifier, Vec<(SecretKey, PublicKey
         pedersen_param: pedersen_param.clone(),
         player_commitment: vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); USER_NUM],
         player_num: USER_NUM,
+        state_chain: StateChainParameter {
+            alive_mask: (1 << USER_NUM) - 1,
+            ..Default::default()
+        },
     };
 
     // let encrypted_inputs = private_inputs
@@ -289,15 +293,22 @@ async fn test_mpc_node_proof_generation_voting() -> Result<(), Box<dyn std::erro
         let client = client.clone();
         let circuit = circuit_encrypted_input.clone();
         async move {
-            let response = client
+            let body = serde_json::to_vec(&ProofRequest {
+                proof_id: "test_proof_id".to_string(),
+                circuit_type: circuit.clone(),
+                output_type: ProofOutputType::Public,
+            })?;
+            let mut request = client
                 .post(format!("http://localhost:{}", port))
-                .json(&ProofRequest {
-                    proof_id: "test_proof_id".to_string(),
-                    circuit_type: circuit.clone(),
-                    output_type: ProofOutputType::Public,
-                })
-                .send()
-                .await?;
+                .header("content-type", "application/json");
+            // ノードが署名を要求する場合はサーバーと同じ鍵で署名する
+            if let Ok(secret_key) = std::env::var("PROOF_REQUEST_SIGNING_KEY") {
+                let signer = RequestSigner::from_base64_secret(&secret_key)?;
+                for (name, value) in signer.sign(&body).pairs() {
+                    request = request.header(name, value);
+                }
+            }
+            let response = request.body(body).send().await?;
 
             let response_body: serde_json::Value = response.json().await?;
             println!("Response from port {}: {:?}", port, response_body);
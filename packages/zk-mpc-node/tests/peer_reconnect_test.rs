@@ -0,0 +1,281 @@
+// Kill and restore one MPC peer and check that the remaining nodes
+// 1. notice the broken link from the link checks alone, without a failed MPC session
+// 2. reject new proof requests with "cluster degraded" while the link is broken
+// 3. re-listen together with the restarted peer and become ready again
+//
+// Run with:
+// cargo test --test peer_reconnect_test -- --ignored --nocapture
+
+use ark_bn254::Fr;
+use ark_std::test_rng;
+use ark_std::PubUniformRand;
+use mpc_algebra::CommitmentScheme;
+use mpc_algebra_wasm::*;
+use serde_json::json;
+use serial_test::serial;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use zk_mpc::circuits::LocalOrMPC;
+use zk_mpc_node::{NodeKeys, ProofOutputType, ProofRequest, RequestSigner};
+
+const NODE_NUM: usize = 3;
+const MPC_TCP_PORT_BASE: u16 = 18100;
+const HTTP_PORT_BASE: u16 = 19100;
+
+struct TestCluster {
+    data_dir: PathBuf,
+    groth16_dir: PathBuf,
+    server_url: String,
+    signer: RequestSigner,
+    nodes: Vec<Option<Child>>,
+}
+
+impl TestCluster {
+    fn new(server_url: String) -> Self {
+        let data_dir =
+            std::env::temp_dir().join(format!("zk-mpc-node-reconnect-{}", uuid::Uuid::new_v4()));
+        // 空のディレクトリを指定して proving key の読み込みを省略する
+        let groth16_dir = data_dir.join("groth16");
+        std::fs::create_dir_all(&groth16_dir).unwrap();
+
+        for id in 0..NODE_NUM {
+            let status = Command::new(env!("CARGO_BIN_EXE_zk-mpc-node"))
+                .args(["keygen", "--id", &id.to_string()])
+                .env("DATA_DIR", &data_dir)
+                .status()
+                .unwrap();
+            assert!(status.success(), "keygen failed for node {}", id);
+        }
+
+        Self {
+            data_dir,
+            groth16_dir,
+            server_url,
+            signer: RequestSigner::generate().0,
+            nodes: (0..NODE_NUM).map(|_| None).collect(),
+        }
+    }
+
+    fn start(&mut self, id: usize) {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_zk-mpc-node"));
+        command
+            .args(["start", "--id", &id.to_string()])
+            .env("DATA_DIR", &self.data_dir)
+            .env("GROTH16_DATA_DIR", &self.groth16_dir)
+            .env("SERVER_URL", &self.server_url)
+            .env("PROOF_REQUEST_VERIFY_KEY", self.signer.public_key_base64())
+            .env_remove("ALLOW_UNSIGNED_PROOF_REQUESTS")
+            .env("MPC_HTTP_PORT", HTTP_PORT_BASE.to_string())
+            .env("MPC_CONNECT_RETRY_INTERVAL_MS", "500")
+            .env("MPC_RECONNECT_INITIAL_BACKOFF_MS", "200")
+            .env("MPC_RECONNECT_MAX_BACKOFF_MS", "2000")
+            .env("MPC_RECONNECT_ATTEMPT_TIMEOUT_MS", "5000")
+            .env("MPC_LINK_CHECK_INTERVAL_MS", "500")
+            .env("MPC_SESSION_TIMEOUT_SECS", "30")
+            .env_remove("MPC_PRIVATE_KEY")
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit());
+        for peer in 0..NODE_NUM {
+            command.env(
+                format!("ZK_MPC_NODE_{}_TCP", peer),
+                format!("localhost:{}", MPC_TCP_PORT_BASE + peer as u16),
+            );
+            command.env_remove(format!("ZK_MPC_NODE_{}_HTTP", peer));
+        }
+        self.nodes[id] = Some(command.spawn().unwrap());
+    }
+
+    fn kill(&mut self, id: usize) {
+        if let Some(mut child) = self.nodes[id].take() {
+            child.kill().unwrap();
+            child.wait().unwrap();
+        }
+    }
+
+    fn node_keys(&self) -> Vec<NodeKey> {
+        (0..NODE_NUM)
+            .map(|id| {
+                let file = std::fs::File::open(
+                    Path::new(&self.data_dir).join(format!("node_keys_{}.json", id)),
+                )
+                .unwrap();
+                let keys: NodeKeys = serde_json::from_reader(file).unwrap();
+                NodeKey {
+                    node_id: id.to_string(),
+                    public_key: keys.public_key,
+                }
+            })
+            .collect()
+    }
+
+    fn post_proof_request(
+        &self,
+        client: &reqwest::Client,
+        id: usize,
+        request: &ProofRequest,
+    ) -> reqwest::RequestBuilder {
+        let body = serde_json::to_vec(request).unwrap();
+        let mut builder = client
+            .post(node_url(id))
+            .header("content-type", "application/json");
+        for (name, value) in self.signer.sign(&body).pairs() {
+            builder = builder.header(name, value);
+        }
+        builder.body(body)
+    }
+}
+
+impl Drop for TestCluster {
+    fn drop(&mut self) {
+        for id in 0..NODE_NUM {
+            self.kill(id);
+        }
+        let _ = std::fs::remove_dir_all(&self.data_dir);
+    }
+}
+
+fn node_url(id: usize) -> String {
+    format!("http://localhost:{}", HTTP_PORT_BASE + id as u16)
+}
+
+fn voting_request(proof_id: &str, node_keys: Vec<NodeKey>) -> ProofRequest {
+    let rng = &mut test_rng();
+    let pedersen_param = <Fr as LocalOrMPC<Fr>>::PedersenComScheme::setup(rng).unwrap();
+    let public_input = AnonymousVotingPublicInput {
+        pedersen_param,
+        player_commitment: vec![<Fr as LocalOrMPC<Fr>>::PedersenCommitment::default(); NODE_NUM],
+        player_num: NODE_NUM,
+        state_chain: StateChainParameter {
+            alive_mask: (1 << NODE_NUM) - 1,
+            ..Default::default()
+        },
+    };
+    let scheme = SecretSharingScheme {
+        total_shares: NODE_NUM,
+        modulus: 97,
+    };
+
+    let outputs = (0..NODE_NUM)
+        .map(|id| {
+            AnonymousVotingEncryption::create_encrypted_shares(&AnonymousVotingInput {
+                private_input: AnonymousVotingPrivateInput {
+                    id,
+                    is_target_id: vec![Fr::from(0), Fr::from(1), Fr::from(0)],
+                    player_randomness: Fr::pub_rand(rng),
+                },
+                public_input: public_input.clone(),
+                node_keys: node_keys.clone(),
+                scheme: scheme.clone(),
+            })
+            .unwrap()
+        })
+        .collect();
+
+    ProofRequest {
+        proof_id: proof_id.to_string(),
+        circuit_type: CircuitEncryptedInputIdentifier::AnonymousVoting(outputs),
+        output_type: ProofOutputType::Public,
+    }
+}
+
+async fn wait_until(
+    client: &reqwest::Client,
+    id: usize,
+    expect_ready: bool,
+    timeout: Duration,
+) -> serde_json::Value {
+    let started = Instant::now();
+    loop {
+        if let Ok(response) = client.get(format!("{}/ready", node_url(id))).send().await {
+            let is_ready = response.status().is_success();
+            let body: serde_json::Value = response.json().await.unwrap_or(json!(null));
+            if is_ready == expect_ready {
+                return body;
+            }
+        }
+        assert!(
+            started.elapsed() < timeout,
+            "node {} did not become ready={} within {:?}",
+            id,
+            expect_ready,
+            timeout
+        );
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+#[tokio::test]
+#[serial]
+#[ignore = "spawns three zk-mpc-node processes"]
+async fn test_nodes_reconnect_after_peer_restart() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/nodes/keys"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "success": true,
+            "node_id": 0,
+            "public_key": ""
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let client = reqwest::Client::new();
+    let mut cluster = TestCluster::new(mock_server.uri());
+    for id in 0..NODE_NUM {
+        cluster.start(id);
+    }
+    for id in 0..NODE_NUM {
+        wait_until(&client, id, true, Duration::from_secs(120)).await;
+    }
+
+    // 署名のないリクエストは MPC セッションを開始せずに拒否される
+    let response = client
+        .post(node_url(0))
+        .json(&voting_request("reconnect_unsigned", cluster.node_keys()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // ピア2を落とす。証明リクエストを送らなくてもリンクチェックで検知される
+    cluster.kill(2);
+    for id in 0..2 {
+        let readiness = wait_until(&client, id, false, Duration::from_secs(30)).await;
+        assert!(
+            readiness.to_string().contains("cluster degraded"),
+            "unexpected readiness of node {}: {}",
+            id,
+            readiness
+        );
+    }
+
+    // 再接続中は新しいリクエストを即座に拒否する
+    let response = cluster
+        .post_proof_request(
+            &client,
+            0,
+            &voting_request("reconnect_rejected", cluster.node_keys()),
+        )
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "cluster_degraded");
+
+    // ピア2を復帰させると全ノードが再び ready になる
+    cluster.start(2);
+    for id in 0..NODE_NUM {
+        wait_until(&client, id, true, Duration::from_secs(180)).await;
+    }
+
+    // ノード0を即座に再起動しても、残りのノードが再 listen してノード0の起動時の接続が完了する
+    cluster.kill(0);
+    cluster.start(0);
+    for id in 0..NODE_NUM {
+        wait_until(&client, id, true, Duration::from_secs(180)).await;
+    }
+}